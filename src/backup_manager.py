@@ -0,0 +1,34 @@
+from datetime import datetime
+from pathlib import Path
+from shutil import copy2
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# バックアップを置く場所（.cph配下）
+BACKUP_DIR = "backups"
+
+class BackupManager:
+    """
+    上書き・削除する前のファイルを .cph/backups/{日時}-{label}/ に、ワークスペースからの相対パスのまま残す。
+    1回の操作（openなど）のバックアップは1つのディレクトリにまとめ、何も残さなければディレクトリも作らない。
+    """
+    def __init__(self, label, upm=None, now=None):
+        self.upm = upm or UnifiedPathManager()
+        self.root = self.upm.project_path.root
+        stamp = (now or datetime.now()).strftime("%Y%m%d-%H%M%S-%f")
+        self.path = self.upm.cph_dir(BACKUP_DIR, f"{stamp}-{label}")
+        self.saved = []
+
+    def save(self, path):
+        """pathの今の内容を残し、残した先を返す。ファイルが無ければNone"""
+        path = Path(str(path)).resolve()
+        if not path.is_file():
+            return None
+        try:
+            rel = path.relative_to(self.root)
+        except ValueError:
+            rel = Path("_outside", path.name)
+        dst = self.path / rel
+        dst.parent.mkdir(parents=True, exist_ok=True)
+        copy2(path, dst)
+        self.saved.append((path, dst))
+        return dst
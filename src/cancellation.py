@@ -0,0 +1,82 @@
+import threading
+import time
+from contextlib import contextmanager
+from src.error_hints import CphError
+
+# 中断・時間切れのエラーの種類
+CANCELLED = "cancelled"
+OPERATION_TIMEOUT = "operation_timeout"
+
+class CancellationToken:
+    """
+    ネットワーク操作の中断と、操作全体の制限時間（timeout秒）。
+    Ctrl+Cなどでcancelされるか制限時間を過ぎると、次のcheckでCphErrorになる。
+    スレッドで動いている取得処理（run_blocking）も、リクエストの前後でcheckするので止まる。
+    """
+    def __init__(self, timeout=None, clock=time.monotonic):
+        self.timeout = float(timeout) if timeout else None
+        self.clock = clock
+        self.deadline = clock() + self.timeout if self.timeout else None
+        self.event = threading.Event()
+
+    def cancel(self):
+        self.event.set()
+
+    @property
+    def cancelled(self):
+        return self.event.is_set()
+
+    def remaining(self):
+        """制限時間までの秒数。制限が無ければNone"""
+        return None if self.deadline is None else max(self.deadline - self.clock(), 0.0)
+
+    def check(self):
+        if self.cancelled:
+            raise CphError("中断しました", kind=CANCELLED)
+        if self.deadline is not None and self.clock() >= self.deadline:
+            raise CphError(f"{self.timeout:g}秒以内に終わらなかったため中止しました", kind=OPERATION_TIMEOUT)
+
+    def request_timeout(self, timeout):
+        """1回のリクエストのタイムアウト。操作の残り時間より長くはしない"""
+        remaining = self.remaining()
+        return timeout if remaining is None else min(timeout, max(remaining, 0.001))
+
+def is_interruption(exc):
+    """中断・時間切れによるエラーか（途中までの結果を知らせる対象）"""
+    return getattr(exc, "kind", None) in (CANCELLED, OPERATION_TIMEOUT, "http_timeout")
+
+# config.jsonのhttp.operation_timeout_secondsの値（operationの既定）
+_operation_timeout = None
+_token = CancellationToken()
+_lock = threading.Lock()
+
+def configure(operation_timeout=None):
+    """操作全体の制限時間の既定を決める（mainで一度呼ぶ）"""
+    global _operation_timeout
+    value = float(operation_timeout) if operation_timeout else None
+    if value is not None and value <= 0:
+        raise ValueError(f"http.operation_timeout_secondsは正の数を指定してください: {operation_timeout}")
+    _operation_timeout = value
+
+def current():
+    """今の操作のトークン（operationの外ではプロセス全体のもの）"""
+    return _token
+
+@contextmanager
+def operation(timeout=None, token=None):
+    """
+    withの間を1つの操作にし、制限時間（省略時はconfigureした既定）を掛ける。外側が中断済みなら最初から中断。
+    tokenを渡せば、そのトークンを使う（別のスレッドから中断するため。run_coordinatorなど）
+    """
+    global _token
+    with _lock:
+        outer = _token
+        token = token or CancellationToken(timeout or _operation_timeout)
+        if outer.cancelled:
+            token.cancel()
+        _token = token
+    try:
+        yield token
+    finally:
+        with _lock:
+            _token = outer
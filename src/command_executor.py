@@ -3,7 +3,47 @@ from .commands.command_login import CommandLogin
 from .commands.command_open import CommandOpen
 from .commands.command_test import CommandTest
 from .commands.command_submit import CommandSubmit
+from .commands.command_profile import CommandProfile
+from .commands.command_run import CommandRun
+from .commands.command_doctor import CommandDoctor
+from .commands.command_prepare import CommandPrepare
+from .commands.command_init import CommandInit
+from .commands.command_print import CommandPrint
+from .commands.command_verify import CommandVerify
+from .commands.command_verify_env import CommandVerifyEnv
+from .commands.command_tag import CommandTag
+from .commands.command_search import CommandSearch
+from .commands.command_import import CommandImport
+from .commands.command_gc import CommandGc
+from .commands.command_stats import CommandStats
+from .commands.command_case import CommandCase
+from .commands.command_clean import CommandClean
+from .commands.command_status import CommandStatus
+from .commands.command_badge import CommandBadge
+from .commands.command_switch import CommandSwitch
+from .commands.command_result import CommandResult
+from .commands.command_history import CommandHistory
+from .commands.command_archive import CommandArchive
+from .commands.command_view import CommandView
+from .commands.command_share import CommandShare
+from .commands.command_explain import CommandExplain
+from .commands.command_report_bug import CommandReportBug
+from .commands.command_refresh import CommandRefresh
+from .commands.command_remote_run import CommandRemoteRun
+from .commands.command_estimate import CommandEstimate
+from .commands.command_replay import CommandReplay
+from .commands.command_watch import CommandWatch
+from .commands.command_statement import CommandStatement
+from .commands.command_test_dir import CommandTestDir
+from .commands.command_fix import CommandFix
+from .commands.command_repl import CommandRepl
+from .commands.toolchain import DEFAULT_SITE, pinned_images
+from src.config_json_manager import ConfigJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src import cancellation, command_log, experimental, http_client, network_limit
+from src.site_config import SiteConfig
 from .commands.opener import Opener
+from .commands.progress_reporter import create_progress_reporter
 from src.environment.test_environment import DockerTestExecutionEnvironment
 from src.environment.execution_manager_test_environment import ExecutionManagerTestEnvironment
 from execution_client.execution_manager import ExecutionManager
@@ -11,22 +51,74 @@ from execution_client.local.client import LocalAsyncClient
 from execution_client.container.client import ContainerClient
 
 class CommandExecutor:
-    def __init__(self, file_manager: ContestFileManager = None, opener: Opener = None, exec_mode: str = None):
+    def __init__(self, file_manager: ContestFileManager = None, opener: Opener = None, exec_mode: str = None, options: dict = None):
         self.file_manager = file_manager
         self.opener = opener or Opener()
         self.exec_mode = exec_mode or "docker"
-        self.login_handler = CommandLogin()
+        self.options = options or {}
+        self.progress = create_progress_reporter(self.options.get("progress"))
+        network_limit.configure(self.get_config().get_network().get("max_concurrent"))
+        http_config = self.get_config().get_http()
+        http_client.configure_timeout(http_config.get("timeout_seconds"))
+        cancellation.configure(http_config.get("operation_timeout_seconds"))
+        command_log.configure(debug=self.options.get("debug"))
+        # sites.yamlは通信するときに読む（誤りがあっても通信しないコマンドは動く）
+        http_client.configure_sites(lambda: SiteConfig.load_layered(UnifiedPathManager().sites_yamls()))
+        self.login_handler = CommandLogin(options=self.options)
         # 実行環境の切り替え
         if self.exec_mode == "local":
-            local_client = LocalAsyncClient()
+            local_client = LocalAsyncClient(cpu_affinity=self.options.get("pin-cpu"), nice=self.options.get("nice"))
             manager = ExecutionManager(local_client)
             test_env = ExecutionManagerTestEnvironment(self.file_manager, manager)
         else:
-            # デフォルトはdocker
-            test_env = DockerTestExecutionEnvironment(self.file_manager)
-        self.open_handler = CommandOpen(self.file_manager, self.opener, test_env)
-        self.test_handler = CommandTest(self.file_manager, test_env)
-        self.submit_handler = CommandSubmit(self.file_manager, test_env)
+            # デフォルトはdocker（config.jsonでツールチェインを固定していればそのイメージを使う）
+            test_env = DockerTestExecutionEnvironment(self.file_manager, image_overrides=self.get_pinned_images())
+        self.open_handler = CommandOpen(self.file_manager, self.opener, test_env, progress=self.progress, options=self.options)
+        self.test_handler = CommandTest(self.file_manager, test_env, progress=self.progress, opener=self.opener, options=self.options)
+        self.submit_handler = CommandSubmit(self.file_manager, test_env, progress=self.progress, options=self.options)
+        self.profile_handler = CommandProfile(self.file_manager, test_env, options=self.options)
+        self.run_handler = CommandRun(self.file_manager, test_env, options=self.options)
+        self.repl_handler = CommandRepl(self.file_manager, test_env, options=self.options)
+        self.doctor_handler = CommandDoctor(self.exec_mode, options=self.options)
+        self.prepare_handler = CommandPrepare(self.exec_mode, options=self.options)
+        self.init_handler = CommandInit(options=self.options)
+        self.print_handler = CommandPrint(options=self.options)
+        self.verify_handler = CommandVerify(self.file_manager, test_env, options=self.options)
+        self.verify_env_handler = CommandVerifyEnv(self.file_manager, test_env, options=self.options)
+        self.tag_handler = CommandTag(options=self.options)
+        self.search_handler = CommandSearch(options=self.options)
+        self.import_handler = CommandImport(options=self.options)
+        self.gc_handler = CommandGc(options=self.options)
+        self.stats_handler = CommandStats(options=self.options)
+        self.case_handler = CommandCase(options=self.options)
+        self.clean_handler = CommandClean(options=self.options)
+        self.status_handler = CommandStatus(options=self.options)
+        self.badge_handler = CommandBadge(options=self.options)
+        self.result_handler = CommandResult(options=self.options)
+        self.history_handler = CommandHistory(options=self.options)
+        self.archive_handler = CommandArchive(options=self.options)
+        self.switch_handler = CommandSwitch(self.file_manager, options=self.options, opener=self.opener)
+        self.view_handler = CommandView(self.exec_mode, options=self.options)
+        self.share_handler = CommandShare(options=self.options)
+        self.explain_handler = CommandExplain(options=self.options)
+        self.report_bug_handler = CommandReportBug(options=self.options)
+        self.refresh_handler = CommandRefresh(options=self.options)
+        self.remote_run_handler = CommandRemoteRun(options=self.options)
+        self.estimate_handler = CommandEstimate(self.file_manager, test_env, options=self.options)
+        self.replay_handler = CommandReplay(self.file_manager, test_env, options=self.options)
+        self.watch_handler = CommandWatch(self.test_handler, options=self.options)
+        self.statement_handler = CommandStatement(options=self.options, opener=self.opener)
+        self.test_dir_handler = CommandTestDir(options=self.options)
+        self.fix_handler = CommandFix(options=self.options, opener=self.opener)
+
+    def get_config(self):
+        return ConfigJsonManager(str(UnifiedPathManager().config_json()))
+
+    def get_pinned_images(self):
+        config = self.get_config()
+        # config.jsonのlanguagesで定義した言語は、imageに書いたイメージで実行する
+        custom = {name: spec["image"] for name, spec in config.get_languages().items() if spec.get("image")}
+        return {**custom, **pinned_images(config.get_toolchain(self.options.get("site", DEFAULT_SITE)))}
 
     async def execute(self, command, contest_name=None, problem_name=None, language_name=None):
         """コマンド名に応じて各メソッドを呼び出す"""
@@ -38,6 +130,70 @@ class CommandExecutor:
             return await self.submit_handler.submit(contest_name, problem_name, language_name)
         elif command == "test":
             return await self.test_handler.run_test(contest_name, problem_name, language_name)
+        elif command == "profile":
+            return await self.profile_handler.profile(contest_name, problem_name, language_name)
+        elif command == "run":
+            return await self.run_handler.run(contest_name, problem_name, language_name)
+        elif command == "repl":
+            return await self.repl_handler.repl(contest_name, problem_name, language_name)
+        elif command == "doctor":
+            return await self.doctor_handler.doctor(language_name)
+        elif command == "prepare":
+            return await self.prepare_handler.prepare(language_name)
+        elif command == "init":
+            return await self.init_handler.init()
+        elif command == "print":
+            return await self.print_handler.print_notebook()
+        elif command == "verify":
+            return await self.verify_handler.verify()
+        elif command == "verify-env":
+            return await self.verify_env_handler.verify_env(language_name)
+        elif command == "tag":
+            return await self.tag_handler.tag(contest_name, problem_name)
+        elif command == "search":
+            return await self.search_handler.search()
+        elif command == "import":
+            return await self.import_handler.import_history()
+        elif command == "gc":
+            return await self.gc_handler.gc()
+        elif command == "stats":
+            return await self.stats_handler.show()
+        elif command == "case":
+            return await self.case_handler.run()
+        elif command == "clean":
+            return await self.clean_handler.clean()
+        elif command == "status":
+            return await self.status_handler.show()
+        elif command == "badge":
+            return await self.badge_handler.badge()
+        elif command == "history":
+            return await self.history_handler.run(contest_name, problem_name)
+        elif command == "result":
+            return await self.result_handler.result(contest_name)
+        elif command == "archive":
+            return await self.archive_handler.run()
+        elif command == "switch":
+            return await self.switch_handler.switch(contest_name, problem_name, language_name)
+        elif command == "view":
+            return await self.view_handler.view(contest_name, problem_name, language_name)
+        elif command == "share":
+            return await self.share_handler.share(contest_name, problem_name, language_name)
+        elif command == "explain":
+            return await self.explain_handler.explain(contest_name, problem_name, language_name)
+        elif command == "fix":
+            return await self.fix_handler.fix()
+        elif command == "report-bug":
+            return await self.report_bug_handler.report_bug()
+        elif command == "refresh":
+            return await self.refresh_handler.refresh()
+        elif command == "remote-run":
+            return await self.remote_run_handler.remote_run(contest_name, problem_name, language_name)
+        elif command == "estimate":
+            return await self.estimate_handler.estimate(contest_name, problem_name, language_name)
+        elif command == "replay":
+            return await self.replay_handler.replay()
+        elif command == "statement":
+            return await self.statement_handler.statement()
         else:
             raise ValueError(f"未対応のコマンドです: {command}")
 
@@ -48,14 +204,127 @@ class CommandExecutor:
         return await self.submit_handler.submit(contest_name, problem_name, language_name)
 
     async def run_test(self, contest_name, problem_name, language_name):
+        if self.options.get("dir"):
+            # ワークスペースの外のディレクトリは、作業中の問題とは関係なく実行する
+            return await self.test_dir_handler.run()
+        if self.options.get("watch"):
+            experimental.require("daemon", "test --watch")
+            return await self.watch_handler.watch(contest_name, problem_name, language_name)
         return await self.test_handler.run_test(contest_name, problem_name, language_name)
 
+    async def profile(self, contest_name, problem_name, language_name):
+        return await self.profile_handler.profile(contest_name, problem_name, language_name)
+
+    async def run(self, contest_name, problem_name, language_name):
+        return await self.run_handler.run(contest_name, problem_name, language_name)
+
+    async def repl(self, contest_name, problem_name, language_name):
+        return await self.repl_handler.repl(contest_name, problem_name, language_name)
+
+    async def doctor(self, language_name=None):
+        return await self.doctor_handler.doctor(language_name)
+
+    async def prepare(self, language_name=None):
+        return await self.prepare_handler.prepare(language_name)
+
+    async def init(self):
+        return await self.init_handler.init()
+
+    async def print_notebook(self):
+        return await self.print_handler.print_notebook()
+
+    async def verify(self):
+        return await self.verify_handler.verify()
+
+    async def verify_env(self, language_name=None):
+        return await self.verify_env_handler.verify_env(language_name)
+
+    async def tag(self, contest_name, problem_name):
+        return await self.tag_handler.tag(contest_name, problem_name)
+
+    async def search(self):
+        return await self.search_handler.search()
+
+    async def import_history(self):
+        return await self.import_handler.import_history()
+
+    async def gc(self):
+        return await self.gc_handler.gc()
+
+    async def stats(self):
+        return await self.stats_handler.show()
+
+    async def case(self, args=None):
+        self.case_handler.args = list(args or [])
+        return await self.case_handler.run()
+
+    async def clean(self):
+        return await self.clean_handler.clean()
+
+    async def status(self):
+        return await self.status_handler.show()
+
+    async def badge(self):
+        return await self.badge_handler.badge()
+
+    async def history(self, args=None, contest_name=None, problem_name=None):
+        self.history_handler.args = list(args or [])
+        return await self.history_handler.run(contest_name, problem_name)
+
+    async def result(self, contest_name):
+        return await self.result_handler.result(contest_name)
+
+    async def archive(self, args=None):
+        self.archive_handler.args = list(args or [])
+        return await self.archive_handler.run()
+
+    async def switch(self, contest_name, problem_name, language_name, args=None):
+        self.switch_handler.args = list(args or [])
+        return await self.switch_handler.switch(contest_name, problem_name, language_name)
+
+    async def view(self, args=None, contest_name=None, problem_name=None, language_name=None):
+        self.view_handler.args = list(args or [])
+        return await self.view_handler.view(contest_name, problem_name, language_name)
+
+    async def share(self, contest_name, problem_name, language_name):
+        return await self.share_handler.share(contest_name, problem_name, language_name)
+
+    async def explain(self, contest_name, problem_name, language_name, args=None):
+        self.explain_handler.args = list(args or [])
+        return await self.explain_handler.explain(contest_name, problem_name, language_name)
+
+    async def fix(self, args=None):
+        self.fix_handler.args = list(args or [])
+        return await self.fix_handler.fix()
+
+    async def report_bug(self):
+        return await self.report_bug_handler.report_bug()
+
+    async def refresh(self):
+        return await self.refresh_handler.refresh()
+
+    async def remote_run(self, contest_name, problem_name, language_name):
+        return await self.remote_run_handler.remote_run(contest_name, problem_name, language_name)
+
+    async def estimate(self, contest_name, problem_name, language_name):
+        return await self.estimate_handler.estimate(contest_name, problem_name, language_name)
+
+    async def replay(self, args=None):
+        self.replay_handler.args = list(args or [])
+        return await self.replay_handler.replay()
+
+    async def statement(self):
+        return await self.statement_handler.statement()
+
 class MockOpener(Opener):
     def __init__(self):
         self.opened_paths = []
         self.opened_urls = []
+        self.opened_locations = []
     def open_editor(self, path: str, language: str = None):
         main_file = f"{path}/main.py"
         self.opened_paths.append(main_file)
+    def open_editor_at(self, path: str, line: int, column: int = 1, command: str = None):
+        self.opened_locations.append((path, line, column))
     def open_browser(self, url: str):
         self.opened_urls.append(url) 
\ No newline at end of file
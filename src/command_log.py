@@ -0,0 +1,94 @@
+import re
+import shlex
+import sys
+import threading
+from datetime import datetime
+from pathlib import Path
+
+# 記録するコマンド（コンテナやojの問題を手で再現・調査できるように）
+LOGGED_PROGRAMS = ("docker", "oj")
+# プロセス内で覚えておく件数の上限（古いものから捨てる）
+MAX_ENTRIES = 1000
+MASK = "***"
+# 値を伏せるオプション。-pはoj loginのパスワードのときだけ（docker runの-pはポート）
+SECRET_FLAGS = ("--password", "--token")
+LOGIN_PASSWORD_FLAGS = ("-p",)
+# NAME=VALUE（docker run -e・envなど）で値を伏せる変数名
+SECRET_NAME = re.compile(r"PASSWORD|PASSWD|TOKEN|SECRET", re.IGNORECASE)
+
+def should_log(argv):
+    return bool(argv) and Path(str(argv[0])).name in LOGGED_PROGRAMS
+
+def redact(argv):
+    """パスワード・トークンを伏せた引数を返す"""
+    redacted = []
+    login = False
+    mask_next = False
+    for arg in map(str, argv):
+        if mask_next:
+            redacted.append(MASK)
+            mask_next = False
+            continue
+        flag, eq, _ = arg.partition("=")
+        if flag in SECRET_FLAGS or (login and flag in LOGIN_PASSWORD_FLAGS):
+            mask_next = not eq
+            redacted.append(f"{flag}={MASK}" if eq else arg)
+        elif eq and SECRET_NAME.search(flag) and not flag.startswith("-"):
+            redacted.append(f"{flag}={MASK}")
+        else:
+            redacted.append(arg)
+        login = login or arg == "login"
+    return redacted
+
+class CommandLog:
+    """
+    実行したdocker / ojコマンドの記録。引数は伏せ字にしてから残す。
+    debugなら実行のたびに標準エラーにも出す（--debug）。testの記録は.cph/runs/{run_id}/commands.jsonに保存する。
+    """
+    def __init__(self, debug=False, stream=None):
+        self.debug = debug
+        self.stream = stream
+        self.entries = []
+        # 捨てたものも含めた通し番号（mark()で区切りを覚える）
+        self.count = 0
+        self.lock = threading.Lock()
+
+    def record(self, argv, started_at, duration, returncode, timed_out=False, cwd=None):
+        args = redact(argv)
+        entry = {
+            "argv": args,
+            "command": shlex.join(args),
+            "cwd": str(cwd) if cwd else None,
+            "started_at": started_at.isoformat(),
+            "duration": round(duration, 3),
+            "returncode": returncode,
+            "timed_out": timed_out,
+        }
+        with self.lock:
+            self.entries.append(entry)
+            del self.entries[:-MAX_ENTRIES]
+            self.count += 1
+        if self.debug:
+            status = "タイムアウト" if timed_out else f"終了コード {returncode}"
+            print(f"[debug] $ {entry['command']}（{status}、{duration:.2f}秒）", file=self.stream or sys.stderr)
+        return entry
+
+    def mark(self):
+        """今までに記録した件数。since(mark)でそれ以降の記録を取り出す"""
+        with self.lock:
+            return self.count
+
+    def since(self, mark):
+        with self.lock:
+            kept_from = self.count - len(self.entries)
+            return list(self.entries[max(mark - kept_from, 0):])
+
+# プロセス全体で共有する記録
+_log = CommandLog()
+
+def get_log():
+    return _log
+
+def configure(debug=False):
+    """--debugのとき、実行したコマンドを標準エラーにも出す"""
+    _log.debug = bool(debug)
@@ -16,6 +16,38 @@ COMMANDS = {
     "open": {"aliases": ["o"]},
     "test": {"aliases": ["t"]},
     "submit": {"aliases": ["s"]},
+    "profile": {"aliases": []},
+    "run": {"aliases": []},
+    "doctor": {"aliases": []},
+    "prepare": {"aliases": []},
+    "init": {"aliases": []},
+    "print": {"aliases": []},
+    "verify": {"aliases": []},
+    "verify-env": {"aliases": []},
+    "tag": {"aliases": []},
+    "search": {"aliases": []},
+    "import": {"aliases": []},
+    "gc": {"aliases": []},
+    "stats": {"aliases": []},
+    "case": {"aliases": []},
+    "clean": {"aliases": []},
+    "status": {"aliases": []},
+    "view": {"aliases": []},
+    "share": {"aliases": []},
+    "explain": {"aliases": []},
+    "report-bug": {"aliases": []},
+    "refresh": {"aliases": []},
+    "remote-run": {"aliases": []},
+    "estimate": {"aliases": []},
+    "replay": {"aliases": []},
+    "statement": {"aliases": []},
+    "fix": {"aliases": []},
+    "repl": {"aliases": []},
+    "badge": {"aliases": []},
+    "switch": {"aliases": []},
+    "result": {"aliases": []},
+    "history": {"aliases": []},
+    "archive": {"aliases": []},
 }
 PROBLEM_NAMES = ["a", "b", "c", "d", "e", "f", "g", "ex"]
 LANGUAGES = {
@@ -24,10 +56,78 @@ LANGUAGES = {
     "rust": {"aliases": ["rs", "rustc"]},
 }
 EXEC_MODES = ["docker", "local"]
+# オプション（--key value / --key=value / --flag）。valueがTrueのものは値を取る
+OPTIONS = {
+    "progress": {"value": True, "choices": ["none", "ndjson"]},
+    "memory": {},
+    "cpu": {},
+    "case": {"value": True},
+    "jobs": {"value": True},
+    "set": {"value": True},
+    "compare": {"value": True},
+    "output-limit": {"value": True},
+    "accurate-timing": {},
+    "pin-cpu": {"value": True},
+    "nice": {"value": True},
+    "tl": {"value": True},
+    "ml": {"value": True},
+    "input": {"value": True},
+    "input-clipboard": {},
+    "save-as": {"value": True},
+    "site": {"value": True},
+    "ub-check": {},
+    "team": {},
+    "members": {"value": True},
+    "files": {"value": True},
+    "output": {"value": True},
+    "lib": {"value": True},
+    "force": {},
+    "debug": {},
+    "dry-run": {},
+    "add": {"value": True},
+    "remove": {"value": True},
+    "difficulty": {"value": True},
+    "tag": {"value": True},
+    "unsolved": {},
+    "min-difficulty": {"value": True},
+    "max-difficulty": {"value": True},
+    "user": {"value": True},
+    "days": {"value": True},
+    "yes": {},
+    "refresh": {},
+    "delete": {},
+    "tool": {},
+    "results": {},
+    "csv": {"value": True},
+    "threshold": {"value": True},
+    "budget": {"value": True},
+    "images": {},
+    "flush": {},
+    "run": {},
+    "service": {"value": True},
+    "failing": {},
+    "anonymize": {},
+    "order": {"value": True, "choices": ["natural", "failed-first", "slowest-first"]},
+    "color": {"value": True, "choices": ["auto", "always", "never"]},
+    "debug-scrape": {},
+    "seed": {"value": True},
+    "check": {},
+    "workspace": {},
+    "poll": {"value": True},
+    "checklist": {"value": True},
+    "max-n": {"value": True},
+    "watch": {},
+    "constraints": {},
+    "dir": {"value": True},
+    "pin": {},
+}
+# 1文字の短いオプション → OPTIONSのキー
+SHORT_OPTIONS = {"-y": "yes"}
 
 import argparse
 from src.info_json_manager import InfoJsonManager
 from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.config_json_manager import ConfigJsonManager
 
 # --- CLIコマンドパース用関数 ---
 def parse_args():
@@ -48,12 +148,64 @@ class CommandParser:
 
     def __init__(self):
         self.parsed = self.default_parsed.copy()
+        self.options = {}
+        # どの要素にも当てはまらなかった位置引数（case export のようなサブコマンド）
+        self.extra_args = []
         self.upm = UnifiedPathManager()
 
+    def languages(self):
+        """組み込みの言語に、config.jsonのlanguagesで定義した言語（aliasesも）を加える"""
+        try:
+            custom = ConfigJsonManager(str(self.upm.config_json())).get_languages()
+        except (OSError, ValueError):
+            custom = {}
+        return {**{name: {"aliases": spec.get("aliases", [])} for name, spec in custom.items()}, **LANGUAGES}
+
+    def parse_options(self, args):
+        """
+        --で始まるオプション（と-yのような短いもの）を取り出し、残りの位置引数を返す。
+        OPTIONSで値を取ると定義されたものは次の引数（または=以降）を値とする。
+        """
+        self.options = {}
+        rest = []
+        i = 0
+        while i < len(args):
+            arg = args[i]
+            if arg in SHORT_OPTIONS:
+                self.options[SHORT_OPTIONS[arg]] = True
+                i += 1
+                continue
+            if not arg.startswith("--") or arg == "--":
+                rest.append(arg)
+                i += 1
+                continue
+            key, eq, value = arg[2:].partition("=")
+            spec = OPTIONS.get(key, {})
+            if spec.get("value"):
+                if not eq:
+                    if i + 1 >= len(args):
+                        raise ValueError(f"--{key} には値が必要です")
+                    value = args[i + 1]
+                    i += 1
+                choices = spec.get("choices")
+                if choices and value not in choices:
+                    raise ValueError(f"--{key} の値が不正です: {value}（{', '.join(choices)}）")
+                self.options[key] = value
+            else:
+                self.options[key] = value if eq else True
+            i += 1
+        return rest
+
+    def get_options(self):
+        return dict(self.options)
+
     def parse(self, args):
         # 引数を順不同でパースし、各要素を特定
         self.parsed = self.default_parsed.copy()
+        args = self.parse_options(args)
         used = set()
+        command_index = None
+        languages = self.languages()
         # 右から順に判定
         for i, arg in enumerate(reversed(args)):
             # exec_mode
@@ -66,8 +218,8 @@ class CommandParser:
                 self.parsed["problem_name"] = arg
                 used.add(len(args)-1-i)
                 continue
-            # contest_name（CONTEST_NAMESのどれかにstartswithで反応すればOK）
-            if self.parsed["contest_name"] is None:
+            # contest_name（CONTEST_NAMESのどれかにstartswithで反応すればOK。archiveのようなコマンド名は除く）
+            if self.parsed["contest_name"] is None and arg not in COMMANDS:
                 for cname in CONTEST_NAMES:
                     if arg.startswith(cname) or cname.startswith(arg):
                         self.parsed["contest_name"] = arg
@@ -76,17 +228,20 @@ class CommandParser:
                 if self.parsed["contest_name"] is not None:
                     continue
             # language_name
-            for lang, v in LANGUAGES.items():
+            for lang, v in languages.items():
                 if self.parsed["language_name"] is None and (arg == lang or arg in v["aliases"]):
                     self.parsed["language_name"] = lang
                     used.add(len(args)-1-i)
                     break
-            # command
+            # command（コマンド名が複数あれば最も左のもの。import history の history はサブコマンドとして残す）
             for cmd, v in COMMANDS.items():
-                if self.parsed["command"] is None and (arg == cmd or arg in v["aliases"]):
+                if arg == cmd or arg in v["aliases"]:
+                    used.discard(command_index)
                     self.parsed["command"] = cmd
-                    used.add(len(args)-1-i)
+                    command_index = len(args)-1-i
+                    used.add(command_index)
                     break
+        self.extra_args = [a for i, a in enumerate(args) if i not in used]
         # Noneでない要素のみ表示
         filtered = {k: v for k, v in self.parsed.items() if v is not None}
         # 未特定の要素があれば警告（出力しないように変更）
@@ -0,0 +1,25 @@
+class CommandWrapClient:
+    """
+    実行クライアント（ContainerClient / ExecutionManager）を包み、解答の実行コマンドを順にwrappersで包む
+    （wrappersは wrap(cmd) を持つもの。先頭のものが内側になる）。
+    持っていないメソッドは増やさない（ハンドラはexec_in_containerの有無でコンテナかどうかを判断するため）。
+    """
+    WRAPPED = ("exec_in_container", "run_and_measure")
+
+    def __init__(self, client, *wrappers):
+        self.client = client
+        self.wrappers = wrappers
+
+    def wrap(self, cmd):
+        for wrapper in self.wrappers:
+            cmd = wrapper.wrap(cmd)
+        return cmd
+
+    def __getattr__(self, name):
+        attr = getattr(self.client, name)
+        if name not in self.WRAPPED:
+            return attr
+
+        def run(target, cmd, *args, **kwargs):
+            return attr(target, self.wrap(cmd), *args, **kwargs)
+        return run
@@ -0,0 +1,157 @@
+import json
+from datetime import datetime
+from pathlib import Path
+from src.file_lock import file_lock
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 問題ごとのメタデータ（contest_stocks/{contest}/{problem}/problem.json）
+PROBLEM_META_FILE = "problem.json"
+# 問題の状態: AC / WA / submitted（結果未確認） / None（未提出）
+SOLVED_STATUS = "AC"
+
+def parse_tags(value):
+    """"dp,graph" → ["dp", "graph"]（前後の空白と空要素は除く）"""
+    if not value or value is True:
+        return []
+    return [t.strip() for t in str(value).split(",") if t.strip()]
+
+class ArchiveIndex:
+    """
+    解いた（取り組んだ）問題の一覧を .cph/archive.json に集約する。キーは "{contest}/{problem}"。
+    タグ・難易度・状態を持ち、同じ内容を各問題のproblem.jsonにも書き出す。
+    """
+    def __init__(self, path=None, upm=None):
+        self.upm = upm or UnifiedPathManager()
+        self.path = Path(str(path)) if path else self.upm.cph_dir("archive.json")
+
+    @staticmethod
+    def key(contest_name, problem_name):
+        return f"{contest_name}/{problem_name}"
+
+    def load(self):
+        if not self.path.exists():
+            return {}
+        try:
+            return json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            return {}
+
+    def get(self, contest_name, problem_name):
+        return self.load().get(self.key(contest_name, problem_name))
+
+    def problem_meta_path(self, contest_name, problem_name):
+        return self.upm.contest_stocks(contest_name, problem_name, PROBLEM_META_FILE)
+
+    def update(self, contest_name, problem_name, func):
+        """func(entry)でエントリを書き換えて保存する。並行して動くcphと競合しないようロックする"""
+        with file_lock(self.path):
+            data = self.load()
+            key = self.key(contest_name, problem_name)
+            entry = data.get(key) or {"contest": contest_name, "problem": problem_name, "tags": [], "difficulty": None, "status": None}
+            func(entry)
+            entry["updated_at"] = datetime.now().isoformat()
+            data[key] = entry
+            atomic_write_json(self.path, dict(sorted(data.items())))
+        atomic_write_json(self.problem_meta_path(contest_name, problem_name), entry)
+        return entry
+
+    def set_tags(self, contest_name, problem_name, add=(), remove=(), difficulty=None):
+        def apply(entry):
+            tags = [t for t in entry.get("tags", []) if t not in remove]
+            tags.extend(t for t in add if t not in tags)
+            entry["tags"] = tags
+            if difficulty is not None:
+                # 手で付けた値は、AtCoder Problemsから取り直した値で上書きしない
+                entry["difficulty"] = difficulty
+                entry["difficulty_source"] = "manual"
+        return self.update(contest_name, problem_name, apply)
+
+    def record_submission(self, contest_name, problem_name, language_name, status="submitted"):
+        def apply(entry):
+            # 一度ACした問題は、結果未確認の提出でACを消さない
+            if entry.get("status") != SOLVED_STATUS or status == SOLVED_STATUS:
+                entry["status"] = status
+            languages = entry.setdefault("languages", [])
+            if language_name not in languages:
+                languages.append(language_name)
+            entry["submitted_at"] = datetime.now().isoformat()
+        return self.update(contest_name, problem_name, apply)
+
+    def merge(self, problems, source=None):
+        """
+        取り込んだ問題をまとめて索引に反映する。ACは消さず、タグ・言語は和集合、難易度は手で付けた値を優先する。
+        problem.jsonは手元に保管済みの問題についてだけ更新する。追加された問題数を返す
+        """
+        with file_lock(self.path):
+            data = self.load()
+            added = 0
+            for p in problems:
+                key = self.key(p["contest"], p["problem"])
+                entry = data.get(key)
+                if entry is None:
+                    entry = {"contest": p["contest"], "problem": p["problem"], "tags": [], "difficulty": None, "status": None}
+                    added += 1
+                if entry.get("status") != SOLVED_STATUS:
+                    entry["status"] = p.get("status")
+                for field in ("tags", "languages"):
+                    values = entry.setdefault(field, [])
+                    values.extend(v for v in p.get(field, []) if v not in values)
+                if entry.get("difficulty") is None:
+                    entry["difficulty"] = p.get("difficulty")
+                    if p.get("difficulty") is not None and p.get("difficulty_source"):
+                        entry["difficulty_source"] = p["difficulty_source"]
+                if p.get("submitted_at") and p["submitted_at"] > (entry.get("submitted_at") or ""):
+                    entry["submitted_at"] = p["submitted_at"]
+                if source:
+                    entry["source"] = source
+                entry["updated_at"] = datetime.now().isoformat()
+                data[key] = entry
+            atomic_write_json(self.path, dict(sorted(data.items())))
+        for p in problems:
+            meta_path = self.problem_meta_path(p["contest"], p["problem"])
+            if meta_path.parent.exists():
+                atomic_write_json(meta_path, data[self.key(p["contest"], p["problem"])])
+        return added
+
+    def sync_statuses(self, statuses, belongs=lambda entry: True):
+        """
+        ジャッジで確定した結果で状態を置き換える。statusesは(contest, problem) → 最終結果。
+        載っていない問題（まだ集計されていない提出など）と、belongs(entry)がFalseの問題（別のサイトのもの）は変えない。
+        変わった問題の[(エントリ, 前の状態)]を返す
+        """
+        changed = []
+        with file_lock(self.path):
+            data = self.load()
+            for entry in data.values():
+                key = (entry["contest"], entry["problem"])
+                if not belongs(entry) or key not in statuses:
+                    continue
+                status = statuses[key]
+                if status == entry.get("status"):
+                    continue
+                changed.append((entry, entry.get("status")))
+                entry["status"] = status
+                entry["updated_at"] = datetime.now().isoformat()
+            atomic_write_json(self.path, dict(sorted(data.items())))
+        for entry, _ in changed:
+            meta_path = self.problem_meta_path(entry["contest"], entry["problem"])
+            if meta_path.parent.exists():
+                atomic_write_json(meta_path, entry)
+        return changed
+
+    def search(self, tags=(), unsolved=False, min_difficulty=None, max_difficulty=None):
+        """全タグを含み、条件に合うエントリを難易度順（未設定は最後）に返す"""
+        found = []
+        for entry in self.load().values():
+            if not set(tags) <= set(entry.get("tags", [])):
+                continue
+            if unsolved and entry.get("status") == SOLVED_STATUS:
+                continue
+            difficulty = entry.get("difficulty")
+            if min_difficulty is not None and (difficulty is None or difficulty < min_difficulty):
+                continue
+            if max_difficulty is not None and (difficulty is None or difficulty > max_difficulty):
+                continue
+            found.append(entry)
+        return sorted(found, key=lambda e: (e.get("difficulty") is None, e.get("difficulty") or 0, e["contest"], e["problem"]))
@@ -0,0 +1,62 @@
+import json
+import shutil
+from pathlib import Path
+from .result_cache import ResultCache
+from .toolchain import DEFAULT_SITE, get_pins
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.file_operator import atomic_write_json
+from src.file_lock import file_lock
+
+def toolchain_epoch(pins, language_name, site):
+    """キャッシュのエポック（"atcoder:1.70.0"）。ジャッジのバージョンを固定していない言語はNone"""
+    version = pins.get(language_name)
+    return f"{site}:{version}" if version else None
+
+def compile_cache_dirs(language_name):
+    # testのビルドと提出前のコンパイル確認が使うcargoのtarget（.temp/{言語}/target）
+    return [Path(".temp") / language_name / "target"]
+
+class CacheEpochs:
+    """
+    言語ごとに、ビルドとテスト結果のキャッシュを作ったときのジャッジのバージョン（エポック）を .cph/cache_epochs.json に残す。
+    サイトがコンパイラを更新してtoolchainの固定バージョンが変わったら、その言語のキャッシュを消して知らせる
+    （古いコンパイラでのビルド成果物やAC結果を、新しいバージョンでも通るものとして使わないため）。
+    """
+    def __init__(self, upm=None, path=None):
+        self.upm = upm or UnifiedPathManager()
+        self.path = Path(str(path or self.upm.cph_dir("cache_epochs.json")))
+
+    def load(self):
+        if not self.path.exists():
+            return {}
+        try:
+            return json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            return {}
+
+    def refresh(self, language_name, epoch, results=None):
+        """
+        エポックが前回から変わっていれば、その言語のキャッシュを消して新しいエポックを残し、Trueを返す。
+        初めて記録するときは、どのバージョンのキャッシュか分からないので消さない
+        """
+        with file_lock(self.path):
+            data = self.load()
+            previous = data.get(language_name)
+            if previous == epoch:
+                return False
+            data[language_name] = epoch
+            atomic_write_json(self.path, data)
+        if previous is None:
+            return False
+        for d in compile_cache_dirs(language_name):
+            shutil.rmtree(str(d), ignore_errors=True)
+        removed = (results or ResultCache()).drop_language(language_name)
+        print(f"[cache] {language_name} のジャッジのバージョンが変わりました（{previous} → {epoch or '固定なし'}）。"
+              f"ビルドのキャッシュとテスト結果{removed}件を消しました")
+        return True
+
+def refresh_epoch(config, language_name, site=DEFAULT_SITE, upm=None):
+    """config.jsonのtoolchainから今のエポックを求め、変わっていればその言語のキャッシュを消してから返す"""
+    epoch = toolchain_epoch(get_pins(config, site), language_name, site)
+    CacheEpochs(upm).refresh(language_name, epoch)
+    return epoch
@@ -0,0 +1,70 @@
+import re
+import shlex
+from src.error_hints import CphError
+from src.shell import Shell, CommandSpec
+
+# 参照解の実行時間の上限（秒）
+REFERENCE_TIMEOUT = 60
+# 置換先を省略（null）したときに、同じ長さで伏せる文字
+MASK_CHAR = "x"
+
+class CaseAnonymizer:
+    """
+    テストケースを共有・書き出しする前に、名前やトークンなどを伏せる変換。
+    config.jsonのtest.anonymize で設定する。
+    例: {"patterns": {"alice|bob": "user", "[0-9a-f]{32}": null}, "reference": "python3 ref.py"}
+    patternsは正規表現 → 置換先（nullなら同じ長さの"x"で伏せ、文字数の制約を崩さない）。
+    referenceは期待出力を作り直す参照解のコマンド（伏せた入力を標準入力に渡す）。
+    """
+    def __init__(self, patterns=None, reference=None, shell=None, cwd=None):
+        try:
+            self.patterns = [(re.compile(p), r) for p, r in (patterns or {}).items()]
+        except re.error as e:
+            raise ValueError(f"test.anonymize.patternsの正規表現が不正です: {e}")
+        self.reference = reference
+        self.shell = shell or Shell()
+        self.cwd = cwd
+
+    @classmethod
+    def from_config(cls, test_config, **kwargs):
+        config = (test_config or {}).get("anonymize") or {}
+        return cls(config.get("patterns"), config.get("reference"), **kwargs)
+
+    @property
+    def enabled(self):
+        return bool(self.patterns)
+
+    def require(self):
+        """--anonymizeが指定されたのに伏せるパターンが無ければエラー（伏せたつもりで共有しないように）"""
+        if not self.enabled:
+            raise ValueError("--anonymize にはconfig.jsonのtest.anonymize.patternsで伏せるパターンを設定してください")
+        return self
+
+    def scrub(self, text):
+        """パターンに一致した部分を置き換える。置き換えた後の文字列と件数を返す"""
+        total = 0
+        for pattern, replacement in self.patterns:
+            def replace(m):
+                return MASK_CHAR * len(m.group(0)) if replacement is None else replacement
+            text, count = pattern.subn(replace, text)
+            total += count
+        return text, total
+
+    def reference_output(self, input_text):
+        """参照解に伏せた入力を渡し、新しい期待出力を返す。参照解が無ければNone"""
+        if not self.reference:
+            return None
+        result = self.shell.run(CommandSpec.from_argv(shlex.split(self.reference), cwd=self.cwd, stdin=input_text, timeout=REFERENCE_TIMEOUT))
+        if not result.ok:
+            raise CphError(f"参照解の実行に失敗しました: {self.reference}\n{result.stderr}", kind="anonymize_reference_failed")
+        return result.stdout
+
+    def transform(self, input_text, expected_text=None):
+        """
+        1ケース分を変換する。(伏せた入力, 期待出力, 置き換えた件数) を返す。
+        入力が変わったときは期待出力を参照解で作り直し、参照解が無ければ期待出力はNone（元のままでは合わないため）。
+        """
+        scrubbed, count = self.scrub(input_text)
+        if count == 0:
+            return input_text, expected_text, 0
+        return scrubbed, self.reference_output(scrubbed), count
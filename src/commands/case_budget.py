@@ -0,0 +1,52 @@
+import math
+import threading
+import time
+
+# 予算を使い切って実行しなかった（途中で止めた）ケースの理由
+BUDGET_EXHAUSTED = "budget_exhausted"
+# 1ケースの持ち時間の下限（TLの倍率）。これだけあればTLEまでは判定できる
+MIN_SHARE_TL_RATIO = 2.0
+
+class WallTimeLimit:
+    """解答の実行コマンドをtimeoutで包み、持ち時間を過ぎたら止める（solution_wrappersの1つ）"""
+    def __init__(self, seconds):
+        self.seconds = seconds
+
+    def wrap(self, cmd):
+        return ["timeout", "--signal=KILL", f"{self.seconds:.3f}", *cmd]
+
+class CaseBudget:
+    """
+    test全体のケースの実行時間の予算（config.jsonのtest.total_budget_seconds）。
+    残りの予算を残りのケースに割り振り（並列数の分だけ同時に使える）、各ケースはその持ち時間で止める。
+    前のケースが早く終われば、余った分は後のケースに回る。
+    持ち時間はTLの2倍を下限にするが、残りの予算より長くはしない。残りが無くなったら、それ以降のケースは始めない。
+    """
+    def __init__(self, total_seconds, case_count, jobs=1, time_limit=None, clock=None):
+        self.total = float(total_seconds)
+        if self.total <= 0:
+            raise ValueError(f"test.total_budget_secondsには正の秒数を指定してください: {total_seconds}")
+        self.remaining_cases = case_count
+        self.jobs = max(int(jobs), 1)
+        self.floor = (time_limit or 0) * MIN_SHARE_TL_RATIO
+        self.clock = clock or time.perf_counter
+        self.deadline = self.clock() + self.total
+        self.lock = threading.Lock()
+
+    @classmethod
+    def from_config(cls, test_config, case_count, jobs=1, time_limit=None):
+        value = (test_config or {}).get("total_budget_seconds")
+        return cls(value, case_count, jobs, time_limit) if value else None
+
+    def remaining(self):
+        return max(self.deadline - self.clock(), 0.0)
+
+    def start_case(self):
+        """これから始めるケースの持ち時間（秒）。予算を使い切っていればNone（そのケースは実行しない）"""
+        with self.lock:
+            remaining = self.remaining()
+            rounds = math.ceil(self.remaining_cases / self.jobs) if self.remaining_cases > 0 else 1
+            self.remaining_cases -= 1
+            if remaining <= 0:
+                return None
+            return min(max(remaining / rounds, self.floor), remaining)
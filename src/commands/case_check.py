@@ -0,0 +1,107 @@
+import hashlib
+import os
+import shlex
+from .case_storage import COPY_CHUNK, alternative_expected_files, case_input, case_name, case_stem, decompress_stream, expected_stem, find_case_file, is_compressed
+from .test_layout import TestLayout
+from src.shell import Shell, CommandSpec
+
+# 入力の検証コマンド（test.validator）の実行時間の上限（秒）
+VALIDATOR_TIMEOUT = 30
+# 問題の重さ。errorはテストの結果が信用できないもの、warningは念のための指摘
+ERROR = "error"
+WARNING = "warning"
+
+class CaseIssue:
+    def __init__(self, level, case, message):
+        self.level = level
+        self.case = case
+        self.message = message
+
+    def __repr__(self):
+        return f"CaseIssue({self.level}, {self.case}, {self.message})"
+
+def digest(path):
+    """内容のハッシュ。圧縮したケースは展開しながら読む（メモリに載せない）"""
+    h = hashlib.sha256()
+    with (decompress_stream(path) if is_compressed(path) else open(path, "rb")) as f:
+        for chunk in iter(lambda: f.read(COPY_CHUNK), b""):
+            h.update(chunk)
+    return h.hexdigest()
+
+def is_blank(path):
+    """中身が空白・改行だけか。圧縮するのは大きなケースだけなので、圧縮したものは空ではない"""
+    if is_compressed(path):
+        return False
+    with open(path, "r", encoding="utf-8", errors="replace") as f:
+        return not f.read().strip()
+
+class CaseChecker:
+    """
+    テストディレクトリの入力と期待出力の組を確かめる。
+    - 入力に期待出力が無い / 期待出力に入力が無い（取り残された.out）
+    - 空のファイル、内容が同じ入力どうし、入力と同じ内容の期待出力
+    - config.jsonのtest.validatorがあれば、各入力を標準入力に渡して検証（終了コードが0以外なら制約違反）
+    .cphignoreとtest.ignoreで除外したケースは対象外。
+    """
+    def __init__(self, test_dir, config=None, shell=None, cwd=None):
+        self.test_dir = str(test_dir)
+        self.config = config or {}
+        self.validator = self.config.get("validator")
+        self.shell = shell or Shell()
+        self.cwd = cwd
+
+    def case_files(self):
+        if not os.path.isdir(self.test_dir):
+            return [], []
+        files = [os.path.join(self.test_dir, f) for f in sorted(os.listdir(self.test_dir))]
+        files = TestLayout(self.test_dir, self.config).discover([f for f in files if os.path.isfile(f)])
+        in_files = [f for f in files if case_name(f).endswith(".in")]
+        out_files = [f for f in files if case_name(f).endswith(".out")]
+        return in_files, out_files
+
+    def validate(self, in_file):
+        """検証コマンドのエラー（制約違反の理由）を返す。問題なければNone"""
+        with case_input(in_file) as stdin:
+            result = self.shell.run(CommandSpec.from_argv(shlex.split(self.validator), cwd=self.cwd, stdin=stdin, timeout=VALIDATOR_TIMEOUT))
+        if result.ok:
+            return None
+        if result.timed_out:
+            return f"検証コマンドが{VALIDATOR_TIMEOUT}秒で終わりませんでした"
+        reason = (result.stderr or result.stdout).strip().splitlines()
+        return f"制約を満たしていません（{reason[-1] if reason else f'code={result.returncode}'}）"
+
+    def check(self):
+        in_files, out_files = self.case_files()
+        issues = []
+        stems = {case_stem(f) for f in in_files}
+        seen = {}
+        for in_file in in_files:
+            name = case_name(in_file)
+            out_file = find_case_file(case_stem(in_file) + ".out") or next(iter(alternative_expected_files(in_file)), None)
+            if out_file is None:
+                issues.append(CaseIssue(ERROR, name, "期待出力（.out）がありません"))
+            if is_blank(in_file):
+                issues.append(CaseIssue(WARNING, name, "入力が空です"))
+            if out_file is not None and is_blank(out_file):
+                issues.append(CaseIssue(WARNING, case_name(out_file), "期待出力が空です"))
+            in_digest = digest(in_file)
+            if in_digest in seen:
+                issues.append(CaseIssue(WARNING, name, f"{seen[in_digest]} と同じ入力です"))
+            seen.setdefault(in_digest, name)
+            if out_file is not None and digest(out_file) == in_digest:
+                issues.append(CaseIssue(WARNING, name, "期待出力が入力と同じ内容です（コピーの取り違え？）"))
+            if self.validator:
+                reason = self.validate(in_file)
+                if reason:
+                    issues.append(CaseIssue(ERROR, name, reason))
+        for out_file in out_files:
+            # sample-3.a.out などのもう一つの期待出力は、sample-3.inの出力として数える（x.b.inがあればx.b.outはその期待出力）
+            if case_stem(out_file) not in stems and expected_stem(out_file) not in stems:
+                issues.append(CaseIssue(ERROR, case_name(out_file), "対応する入力（.in）がありません"))
+        return issues
+
+def report(issues, log_prefix="[check]"):
+    for issue in issues:
+        mark = "エラー" if issue.level == ERROR else "注意"
+        print(f"{log_prefix} {mark}: {issue.case}: {issue.message}")
+    return issues
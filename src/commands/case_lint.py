@@ -0,0 +1,88 @@
+import os
+from .case_storage import case_name, is_compressed
+from .test_layout import GROUP_PREFIX
+from src.file_operator import atomic_write
+
+# 見た目では区別しにくい空白（全角スペース・ノーブレークスペースなど）
+UNUSUAL_SPACES = {"\u3000": "全角スペース", "\u00a0": "ノーブレークスペース", "\u200b": "ゼロ幅スペース", "\ufeff": "BOM"}
+# 同じ種類の指摘は、この行数までは行番号を並べる
+MAX_LINES_SHOWN = 3
+
+def normalize(text):
+    """改行をLFにそろえ、空でなければ末尾に改行を付ける（自動で直してよい違い。末尾の空行は指摘だけにする）"""
+    text = text.replace("\r\n", "\n").replace("\r", "\n")
+    return text + "\n" if text and not text.endswith("\n") else text
+
+def _lines_note(numbers):
+    shown = ", ".join(str(n) for n in numbers[:MAX_LINES_SHOWN])
+    return f"{shown}行目" + (f"ほか{len(numbers) - MAX_LINES_SHOWN}行" if len(numbers) > MAX_LINES_SHOWN else "")
+
+def suspicious(text):
+    """
+    完全一致の比較で紛らわしい結果になりそうな箇所（自動では直さない）。
+    タブ・行末の空白・見えにくい空白・先頭や末尾の空行。
+    """
+    lines = text.split("\n")
+    if lines and lines[-1] == "":
+        lines.pop()
+    issues = []
+    tabs = [i for i, line in enumerate(lines, 1) if "\t" in line]
+    if tabs:
+        issues.append(f"{_lines_note(tabs)}にタブがあります")
+    trailing = [i for i, line in enumerate(lines, 1) if line != line.rstrip(" \t")]
+    if trailing:
+        issues.append(f"{_lines_note(trailing)}の末尾に空白があります")
+    for ch, label in UNUSUAL_SPACES.items():
+        found = [i for i, line in enumerate(lines, 1) if ch in line]
+        if found:
+            issues.append(f"{_lines_note(found)}に{label}があります")
+    if lines and not lines[0].strip():
+        issues.append("先頭が空行です")
+    if len(lines) > 1 and not lines[-1].strip():
+        issues.append("末尾に空行があります")
+    return issues
+
+def lint_expected_file(path, fix=True):
+    """
+    期待出力ファイルを直し（CRLF→LF、末尾の改行）、紛らわしい箇所を返す。
+    (直したかどうか, 指摘の一覧) を返す。圧縮されたケースは大きいため対象外
+    """
+    if is_compressed(path):
+        return False, []
+    with open(path, "r", encoding="utf-8", errors="replace", newline="") as f:
+        text = f.read()
+    normalized = normalize(text)
+    fixed = fix and normalized != text
+    if fixed:
+        atomic_write(path, normalized)
+    return fixed, suspicious(normalized)
+
+def _expected_entries(test_dir):
+    """test_dirの期待出力（小課題ごとのgroup_N/の中も）を、test_dirからの相対パスで返す"""
+    entries = []
+    for entry in sorted(os.listdir(test_dir)):
+        path = os.path.join(test_dir, entry)
+        if entry.startswith(GROUP_PREFIX) and os.path.isdir(path):
+            entries += [os.path.join(entry, name) for name in sorted(os.listdir(path))
+                        if os.path.isfile(os.path.join(path, name)) and case_name(name).endswith(".out")]
+        elif os.path.isfile(path) and case_name(path).endswith(".out"):
+            entries.append(entry)
+    return entries
+
+def lint_cases(test_dir, fix=True, log_prefix="[lint]"):
+    """
+    test_dirの期待出力（*.out）をまとめて検査する。ケースを保存・取り込みしたときに呼ぶ。
+    {ケース名: 指摘の一覧} を返し、直したものと指摘を表示する
+    """
+    if not os.path.isdir(str(test_dir)):
+        return {}
+    found = {}
+    for entry in _expected_entries(str(test_dir)):
+        path = os.path.join(str(test_dir), entry)
+        fixed, issues = lint_expected_file(path, fix)
+        if fixed:
+            print(f"{log_prefix} {entry}: 改行をLFにそろえ、末尾の改行を整えました")
+        if issues:
+            found[entry] = issues
+            print(f"{log_prefix} {entry}: {'、'.join(issues)}（完全一致の比較では結果が紛らわしくなることがあります）")
+    return found
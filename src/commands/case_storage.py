@@ -0,0 +1,159 @@
+import os
+import re
+import shutil
+import subprocess
+from contextlib import contextmanager
+from src.error_hints import CphError
+
+# 圧縮したテストケースの拡張子（large-1.in.zst / large-1.out.zst）
+ZSTD_SUFFIX = ".zst"
+# この大きさ（バイト）以上のケースを圧縮して保存する。config.jsonのtest.compress_thresholdで変更できる
+DEFAULT_COMPRESS_THRESHOLD = 8 * 1024 * 1024
+DEFAULT_COMPRESS_LEVEL = 3
+ZSTD = "zstd"
+# 子プロセスが入力を読み切らずに終了したときのzstdの終了コード（SIGPIPE）
+SIGPIPE_RETURNCODE = -13
+COPY_CHUNK = 1024 * 1024
+
+# もう一つの期待出力の名前（{ケース名}.{英小文字1字}.out）
+ALTERNATIVE_EXPECTED = re.compile(r"^(?P<stem>.+)\.(?P<tag>[a-z])\.out$")
+
+def is_compressed(path):
+    return str(path).endswith(ZSTD_SUFFIX)
+
+def plain_path(path):
+    """large-1.in.zst → large-1.in"""
+    path = str(path)
+    return path[:-len(ZSTD_SUFFIX)] if is_compressed(path) else path
+
+def case_name(path):
+    """ケースのファイル名。圧縮していても .zst を除いた名前（large-1.in）にそろえる"""
+    return os.path.basename(plain_path(path))
+
+def case_stem(path):
+    """large-1.in.zst / large-1.in → large-1（ディレクトリ付き）"""
+    path = plain_path(path)
+    for ext in (".in", ".out"):
+        if path.endswith(ext):
+            return path[:-len(ext)]
+    return path
+
+def expected_file(in_file):
+    """入力に対応する期待出力。.out と .out.zst のうち存在する方（どちらも無ければ .out）"""
+    out_file = case_stem(in_file) + ".out"
+    if not os.path.exists(out_file) and os.path.exists(out_file + ZSTD_SUFFIX):
+        return out_file + ZSTD_SUFFIX
+    return out_file
+
+def alternative_expected_files(in_file):
+    """
+    受理する出力が複数ある問題の、もう一つの期待出力（sample-3.a.out / sample-3.b.out …、.zstも可）をタグ順に返す。
+    .outとこれらのどれか1つに一致すればACにする（答えの候補が少ないときにチェッカーを書かずに済む）
+    """
+    directory, base = os.path.split(case_stem(in_file))
+    try:
+        names = sorted(os.listdir(directory or "."))
+    except OSError:
+        return []
+    found = {}
+    for name in names:
+        m = ALTERNATIVE_EXPECTED.match(case_name(name))
+        # x.b.out は x.b.in があればそのケース自身の期待出力で、x の候補ではない
+        if m and m.group("stem") == base and not find_case_file(case_stem(os.path.join(directory, name)) + ".in"):
+            # .outと.out.zstの両方があれば圧縮していない方を使う
+            found.setdefault(m.group("tag"), os.path.join(directory, name))
+    return [found[tag] for tag in sorted(found)]
+
+def expected_stem(path):
+    """期待出力に対応するケース名。sample-3.a.out → sample-3（ディレクトリ付き）"""
+    m = ALTERNATIVE_EXPECTED.match(os.path.basename(plain_path(path)))
+    if m:
+        return os.path.join(os.path.dirname(str(path)), m.group("stem"))
+    return case_stem(path)
+
+def find_case_file(path):
+    """pathが無く圧縮版があればそちらを返す（どちらも無ければNone）"""
+    path = plain_path(path)
+    for candidate in (path, path + ZSTD_SUFFIX):
+        if os.path.exists(candidate):
+            return candidate
+    return None
+
+def check_zstd():
+    if shutil.which(ZSTD) is None:
+        raise CphError("圧縮されたテストケース（.zst）を扱うにはzstdコマンドが必要です", kind="zstd_missing")
+
+def check_returncode(proc, path):
+    if proc.returncode not in (0, SIGPIPE_RETURNCODE):
+        stderr = proc.stderr.read().decode("utf-8", errors="replace").strip() if proc.stderr else ""
+        raise CphError(f"テストケースの展開に失敗しました: {path}（{stderr}）", kind="zstd_failed")
+
+@contextmanager
+def decompress_stream(path):
+    """
+    .zstを展開しながら読むバイナリのストリームを返す。展開したファイルは作らないので、
+    子プロセスのstdinにそのまま渡せば大きなケースもメモリに載せずに流せる。
+    """
+    check_zstd()
+    proc = subprocess.Popen([ZSTD, "-dcq", str(path)], stdout=subprocess.PIPE, stderr=subprocess.PIPE)
+    try:
+        yield proc.stdout
+    finally:
+        proc.stdout.close()
+        proc.wait()
+    check_returncode(proc, path)
+
+@contextmanager
+def case_input(path):
+    """
+    テストケースの入力を、実行時のstdinに渡す形で返す。
+    通常のファイルはこれまで通り文字列、.zstは展開しながら流すストリーム（stdin=にそのまま渡せる）。
+    """
+    if not is_compressed(path):
+        with open(path, "r", encoding="utf-8") as f:
+            yield f.read()
+        return
+    with decompress_stream(path) as stream:
+        yield stream
+
+def read_case(path):
+    """ケースの内容を文字列で読む（期待出力との比較用）。.zstは展開して読む"""
+    if not is_compressed(path):
+        with open(path, "r", encoding="utf-8") as f:
+            return f.read()
+    with decompress_stream(path) as stream:
+        return stream.read().decode("utf-8")
+
+def export_case(path, dest):
+    """ケースを展開してdestに書き出す（圧縮していなければそのままコピー）"""
+    os.makedirs(os.path.dirname(os.path.abspath(dest)), exist_ok=True)
+    if not is_compressed(path):
+        shutil.copyfile(path, dest)
+        return dest
+    tmp = f"{dest}.tmp"
+    with decompress_stream(path) as stream, open(tmp, "wb") as f:
+        shutil.copyfileobj(stream, f, COPY_CHUNK)
+    os.replace(tmp, dest)
+    return dest
+
+def compress_case(path, level=DEFAULT_COMPRESS_LEVEL):
+    """pathをpath.zstに圧縮し、元のファイルを削除する。圧縮後のパスを返す"""
+    check_zstd()
+    dest = str(path) + ZSTD_SUFFIX
+    result = subprocess.run([ZSTD, "-q", "-f", f"-{level}", "--rm", str(path), "-o", dest], capture_output=True, text=True)
+    if result.returncode != 0:
+        raise CphError(f"テストケースの圧縮に失敗しました: {path}（{result.stderr.strip()}）", kind="zstd_failed")
+    return dest
+
+def compress_if_large(path, threshold=DEFAULT_COMPRESS_THRESHOLD, level=DEFAULT_COMPRESS_LEVEL):
+    """thresholdバイト以上なら圧縮して圧縮後のパスを、それ以外はpathを返す。zstdが無ければ圧縮しない"""
+    if is_compressed(path) or threshold is None or os.path.getsize(path) < threshold:
+        return str(path)
+    if shutil.which(ZSTD) is None:
+        return str(path)
+    return compress_case(path, level)
+
+def compress_threshold(test_config):
+    """config.jsonのtest.compress_threshold（バイト、0で無効）"""
+    value = (test_config or {}).get("compress_threshold", DEFAULT_COMPRESS_THRESHOLD)
+    return int(value) if value else None
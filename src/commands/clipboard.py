@@ -0,0 +1,23 @@
+import shutil
+import subprocess
+from src.error_hints import CphError
+
+# クリップボードを読むコマンド（見つかったものを上から順に使う）
+CLIPBOARD_COMMANDS = [
+    ["pbpaste"],
+    ["wl-paste", "--no-newline"],
+    ["xclip", "-selection", "clipboard", "-o"],
+    ["xsel", "--clipboard", "--output"],
+    ["powershell.exe", "-NoProfile", "-Command", "Get-Clipboard"],
+]
+
+def read_clipboard():
+    """クリップボードの内容を文字列で返す。使えるコマンドが無ければRuntimeError"""
+    for cmd in CLIPBOARD_COMMANDS:
+        if shutil.which(cmd[0]) is None:
+            continue
+        result = subprocess.run(cmd, capture_output=True, text=True)
+        if result.returncode == 0:
+            return result.stdout.replace("\r\n", "\n")
+    names = ", ".join(cmd[0] for cmd in CLIPBOARD_COMMANDS)
+    raise CphError(f"クリップボードを読み取れません（{names} のいずれかをインストールしてください）", kind="clipboard_unavailable")
@@ -0,0 +1,99 @@
+import os
+import sys
+
+ANSI_CODES = {
+    "red": "\033[31m",
+    "green": "\033[32m",
+    "yellow": "\033[33m",
+    "blue": "\033[34m",
+    "cyan": "\033[36m",
+    "bright_red": "\033[1;91m",
+    "bright_green": "\033[1;92m",
+    "bright_yellow": "\033[1;93m",
+    "bright_cyan": "\033[1;96m",
+    "bold": "\033[1m",
+    "underline": "\033[4m",
+    "reverse": "\033[7m",
+    "dim": "\033[2m",
+    "reset": "\033[0m",
+}
+# 表示の役割 → 色。判定（AC/WA/RE/OLE）と警告、期待出力と出力の差分、unified diffの行
+THEMES = {
+    "default": {
+        "AC": "green", "WA": "red", "RE": "yellow", "OLE": "yellow", "warning": "yellow",
+        "diff_expected": "green", "diff_actual": "red", "diff_header": "cyan",
+    },
+    "high-contrast": {
+        "AC": "bright_green", "WA": "bright_red", "RE": "bright_yellow", "OLE": "bright_yellow", "warning": "bright_yellow",
+        "diff_expected": "bright_green", "diff_actual": "bright_red", "diff_header": "bright_cyan",
+    },
+    # 色を使わず、太字・反転・下線だけで区別する
+    "monochrome": {
+        "AC": "bold", "WA": "reverse", "RE": "underline", "OLE": "underline", "warning": "underline",
+        "diff_expected": "dim", "diff_actual": "bold", "diff_header": "underline",
+    },
+}
+DEFAULT_THEME = "default"
+COLOR_MODES = ["auto", "always", "never"]
+
+def color_enabled(mode="auto", stream=None, environ=None):
+    """
+    色を付けるかどうか。--color always / never が最優先で、autoのときは
+    NO_COLOR（空でなければ無効）→ CLICOLOR_FORCE（0以外なら有効）→ CLICOLOR=0（無効）→ 端末かどうか、の順に見る。
+    """
+    environ = os.environ if environ is None else environ
+    mode = mode or "auto"
+    if mode not in COLOR_MODES:
+        raise ValueError(f"--color は {' / '.join(COLOR_MODES)} のいずれかです: {mode}")
+    if mode != "auto":
+        return mode == "always"
+    if environ.get("NO_COLOR"):
+        return False
+    if environ.get("CLICOLOR_FORCE", "0") != "0":
+        return True
+    if environ.get("CLICOLOR") == "0":
+        return False
+    stream = stream or sys.stdout
+    return bool(getattr(stream, "isatty", lambda: False)())
+
+class Palette:
+    """
+    テーマに従って文字列に色を付ける。無効なときは何もしない。
+    表示する側は色の名前ではなく役割（"AC"、"diff_actual"など）で指定する。
+    """
+    def __init__(self, theme=DEFAULT_THEME, enabled=True):
+        if theme not in THEMES:
+            raise ValueError(f"色のテーマ {theme} はありません（{' / '.join(THEMES)}）")
+        self.theme = theme
+        self.enabled = enabled
+
+    def paint(self, text, role):
+        code = ANSI_CODES.get(THEMES[self.theme].get(role, role))
+        if not self.enabled or not code:
+            return text
+        return f"{code}{text}{ANSI_CODES['reset']}"
+
+    def diff_line(self, line):
+        """unified diffの1行を、追加・削除・見出しで塗り分ける"""
+        if line.startswith(("+++", "---", "@@")):
+            return self.paint(line, "diff_header")
+        if line.startswith("+"):
+            return self.paint(line, "diff_actual")
+        if line.startswith("-"):
+            return self.paint(line, "diff_expected")
+        return line
+
+_palette = None
+
+def configure(mode=None, theme=None, stream=None, environ=None):
+    """
+    プロセス全体で使うパレットを決める（mainで一度呼ぶ）。
+    modeは--colorの値、themeはconfig.jsonのcolor.theme。
+    """
+    global _palette
+    _palette = Palette(theme or DEFAULT_THEME, color_enabled(mode, stream, environ))
+    return _palette
+
+def current():
+    """configure前に使われたときは既定（テーマdefault、autoで判定）"""
+    return _palette or configure()
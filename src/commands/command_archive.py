@@ -0,0 +1,87 @@
+import re
+from src import events
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from .archive_index import ArchiveIndex, SOLVED_STATUS
+from .command_import import CODEFORCES_PREFIX, FETCHERS, aggregate
+from .command_result import configured_user
+
+# ジャッジ中の提出（結果が確定していない）。索引では提出済み（submitted）のままにする
+PENDING_VERDICTS = {"WJ", "WR", "TESTING"}
+SUBMITTED_STATUS = "submitted"
+# AtCoderの定期コンテストのID。それ以外（過去問集・企業コンテストなど）は取り込み元（source）か、取得した結果に載っているかで見分ける
+ATCODER_CONTEST = re.compile(r"(abc|arc|agc|ahc)\d+")
+
+def is_codeforces_contest(contest_name):
+    return re.fullmatch(rf"{CODEFORCES_PREFIX}\d+", str(contest_name)) is not None
+
+def site_of(entry):
+    """索引のエントリのサイト。importで取り込んだものはsource、それ以外はコンテストIDの形で決め、分からなければNone（AOJなど）"""
+    if entry.get("source") in FETCHERS:
+        return entry["source"]
+    if is_codeforces_contest(entry["contest"]):
+        return "codeforces"
+    if ATCODER_CONTEST.fullmatch(str(entry["contest"])):
+        return "atcoder"
+    return None
+
+def belongs_to(site, contests=()):
+    """索引のエントリがそのサイトの問題か。contests（そのサイトから取得した結果に載っているコンテスト）も含める"""
+    contests = set(contests)
+    return lambda entry: site_of(entry) == site or entry["contest"] in contests
+
+def final_statuses(problems):
+    """aggregateした問題ごとの結果 → {(contest, problem): 状態}。ジャッジ中のものは提出済み"""
+    return {(p["contest"], p["problem"]): SUBMITTED_STATUS if p["status"] in PENDING_VERDICTS else p["status"]
+            for p in problems}
+
+def format_status(status):
+    return status or "未提出"
+
+class CommandArchive:
+    """
+    アーカイブの索引（.cph/archive.json）を管理する。
+    sync-verdicts: 索引にある問題の提出結果をジャッジ（AtCoder Problems / Codeforcesの公開API）から取り直し、
+    AC / WA などの最終結果・未提出に更新する。サイトから直接提出したものも反映される。
+    ユーザー名は--userか、config.jsonのresult.user。
+    使い方: archive sync-verdicts [--site atcoder|codeforces] [--user NAME]
+    """
+    def __init__(self, options=None, upm=None, index=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.index = index or ArchiveIndex(upm=self.upm)
+        self.args = []
+
+    def user(self):
+        return configured_user(self.options, self.upm)
+
+    async def run(self):
+        sub = self.args[0] if self.args else None
+        if sub != "sync-verdicts":
+            raise ValueError("使い方: archive sync-verdicts [--site atcoder|codeforces] [--user NAME]")
+        return self.sync_verdicts()
+
+    def sync_verdicts(self):
+        site = self.options.get("site") or "atcoder"
+        fetch = FETCHERS.get(site)
+        if fetch is None:
+            raise ValueError(f"未対応のサイトです: {site}（{', '.join(FETCHERS)}）")
+        if not self.index.load():
+            print("[archive] 索引に問題がありません")
+            return []
+        user = self.user()
+        statuses = final_statuses(aggregate(fetch(user)))
+        belongs = belongs_to(site, {contest for contest, _ in statuses})
+        entries = [e for e in self.index.load().values() if belongs(e)]
+        if not entries:
+            print(f"[archive] 索引に {site} の問題がありません")
+            return []
+        changed = self.index.sync_statuses(statuses, belongs)
+        for entry, previous in changed:
+            print(f"[archive] {entry['contest']} {entry['problem']}: {format_status(previous)} → {format_status(entry['status'])}")
+            if entry["status"] == SOLVED_STATUS:
+                # ジャッジでACが確定したのはここで分かる（submitは送信までしか見ない）
+                events.publish(events.SUBMISSION_ACCEPTED, contest=entry["contest"], problem=entry["problem"],
+                               languages=",".join(entry.get("languages") or []), site=site, user=user)
+        solved = sum(1 for e in self.index.load().values() if belongs(e) and e.get("status") == SOLVED_STATUS)
+        print(f"[archive] {site} の {user}: {len(entries)}問の結果を確認しました（変更 {len(changed)}問、AC {solved}問）")
+        return changed
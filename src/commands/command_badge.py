@@ -0,0 +1,116 @@
+import os
+from datetime import datetime, timedelta
+from xml.sax.saxutils import escape
+from .archive_index import ArchiveIndex, SOLVED_STATUS
+from .run_artifacts import RunArtifacts
+from .text_width import display_width
+from src.file_operator import atomic_write, atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 「今週」の既定（直近N日）
+DEFAULT_BADGE_DAYS = 7
+BADGE_JSON = "cph.json"
+SOLVED_SVG = "solved.svg"
+VERDICT_SVG = "verdict.svg"
+# shields.ioの配色に合わせる
+BADGE_COLORS = {
+    "label": "#555",
+    "solved": "#007ec6",
+    "AC": "#4c1",
+    "WA": "#fe7d37",
+    "none": "#9f9f9f",
+    "failure": "#e05d44",
+}
+# 文字幅の目安（Verdana 11px の半角1文字あたり）と左右の余白
+CHAR_PX = 7
+PADDING_PX = 10
+
+def run_verdict(run):
+    """1回のテスト実行の結果。打ち切ったケース（SKIP）は除き、全てACならAC、そうでなければ最初に失敗したケースの判定"""
+    verdicts = [c.get("verdict") for c in run.get("cases", []) if c.get("verdict") != "SKIP"]
+    if not verdicts:
+        return None
+    return next((v for v in verdicts if v != "AC"), "AC") or "?"
+
+def solved_within(entries, since):
+    """sinceより後に提出したACの問題を、新しい順に返す"""
+    solved = [e for e in entries if e.get("status") == SOLVED_STATUS and (e.get("submitted_at") or "") >= since.isoformat()]
+    return sorted(solved, key=lambda e: e.get("submitted_at") or "", reverse=True)
+
+def verdict_color(verdict):
+    if verdict is None:
+        return BADGE_COLORS["none"]
+    return BADGE_COLORS.get(verdict, BADGE_COLORS["failure"])
+
+def render_svg(label, message, color):
+    """shields.io風の2色のバッジ。外部サービスを使わずにREADMEへ埋め込めるよう、SVGをそのまま書く"""
+    label_w = display_width(label) * CHAR_PX + PADDING_PX
+    message_w = display_width(message) * CHAR_PX + PADDING_PX
+    width = label_w + message_w
+    label, message = escape(label), escape(message)
+    return (
+        f'<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">'
+        f'<title>{label}: {message}</title>'
+        f'<rect width="{label_w}" height="20" fill="{BADGE_COLORS["label"]}"/>'
+        f'<rect x="{label_w}" width="{message_w}" height="20" fill="{color}"/>'
+        f'<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">'
+        f'<text x="{label_w / 2:g}" y="14">{label}</text>'
+        f'<text x="{label_w + message_w / 2:g}" y="14">{message}</text>'
+        f'</g></svg>\n'
+    )
+
+class CommandBadge:
+    """
+    直近N日にACした問題数と、最後のテスト結果をバッジ（JSON / SVG）にしてbadges/に書き出す。
+    練習用のリポジトリのREADMEに ![](badges/solved.svg) のように埋め込める（外部サービスは使わない）。
+    ACの問題はアーカイブの索引（import historyで取り込んだ提出）から、最後の結果は.cph/runsから数える。
+    使い方: badge [--days 7] [--output DIR]
+    """
+    def __init__(self, options=None, upm=None, index=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.index = index or ArchiveIndex(upm=self.upm)
+
+    def days(self):
+        value = self.options.get("days")
+        return DEFAULT_BADGE_DAYS if value is None or value is True else float(value)
+
+    def output_dir(self):
+        output = self.options.get("output")
+        if not output or output is True:
+            return str(self.upm.badges_dir())
+        return output if os.path.isabs(output) else os.path.join(str(self.upm.project_path.root), output)
+
+    def summary(self, now=None):
+        now = now or datetime.now()
+        solved = solved_within(self.index.load().values(), now - timedelta(days=self.days()))
+        runs = RunArtifacts.all_runs(self.upm.project_path.root)
+        last = runs[0] if runs else None
+        return {
+            "generated_at": now.isoformat(),
+            "days": self.days(),
+            "solved": len(solved),
+            "solved_problems": [f"{e['contest']}/{e['problem']}" for e in solved],
+            "last_verdict": None if last is None else {
+                "verdict": run_verdict(last),
+                "contest": last.get("contest_name"),
+                "problem": last.get("problem_name"),
+                "language": last.get("language_name"),
+                "run_at": last.get("created_at"),
+            },
+        }
+
+    async def badge(self):
+        summary = self.summary()
+        output = self.output_dir()
+        os.makedirs(output, exist_ok=True)
+        last = summary["last_verdict"] or {}
+        verdict = last.get("verdict")
+        problem = " ".join(str(v) for v in (last.get("contest"), last.get("problem")) if v)
+        message = "-" if verdict is None else (f"{verdict} ({problem})" if problem else verdict)
+        atomic_write_json(os.path.join(output, BADGE_JSON), summary)
+        atomic_write(os.path.join(output, SOLVED_SVG), render_svg(f"solved ({summary['days']:g}d)", str(summary["solved"]), BADGE_COLORS["solved"]))
+        atomic_write(os.path.join(output, VERDICT_SVG), render_svg("last verdict", message, verdict_color(verdict)))
+        print(f"[badge] 直近{summary['days']:g}日のAC: {summary['solved']}問 / 最後のテスト: {message}")
+        print(f"[badge] {output} に {BADGE_JSON} / {SOLVED_SVG} / {VERDICT_SVG} を書き出しました")
+        return summary
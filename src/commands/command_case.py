@@ -0,0 +1,227 @@
+import os
+from .case_anonymizer import CaseAnonymizer
+from .case_check import ERROR, CaseChecker, report
+from .case_lint import lint_cases
+from .case_storage import (
+    ZSTD_SUFFIX, case_name, case_stem, compress_case, compress_threshold, expected_file, export_case, find_case_file,
+    is_compressed, read_case,
+)
+from .command_gc import format_size
+from .archive_index import ArchiveIndex
+from .command_switch import task_url
+from .problem_lock import active_problem
+from .sample_rebind import ACCEPT, KEEP, KEEPABLE, SKIP, apply_rebind, custom_name, describe, lint_fresh_samples, plan_rebind, sample_checksums
+from .sample_scraper import SampleScraper
+from src import temp_space
+from src.config_json_manager import ConfigJsonManager
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# case exportの既定の書き出し先（.cph/cases/）
+EXPORT_DIR = "cases"
+ACTIONS = ("export", "compress", "lint", "check", "rebind")
+# case rebindの答え → 扱い
+REBIND_ANSWERS = {"a": ACCEPT, "k": KEEP, "s": SKIP}
+
+class CommandCase:
+    """
+    contest_current/testのテストケースを扱う。
+    使い方: case export [NAME ...] [--output DIR] [--anonymize]（.zstを展開して書き出す。既定は.cph/cases/）
+            case compress [--threshold BYTES]（大きなケースをzstdで圧縮する。既定はconfig.jsonのtest.compress_threshold）
+            case lint [--dry-run]（期待出力の改行をLFにそろえ末尾に改行を付ける。タブ・行末の空白などは指摘だけする）
+            case check（入力と期待出力の組・空や重複のケース・test.validatorによる入力の制約を確かめる）
+            case rebind [--yes] [--dry-run]（サンプルを取り直して手元のものと比べ、ケースごとに取り込むか決める）
+    """
+    def __init__(self, options=None, args=None, upm=None, ask=input):
+        self.options = options or {}
+        self.args = list(args or [])
+        self.upm = upm or UnifiedPathManager()
+        self.ask = ask
+
+    def test_dir(self):
+        return self.upm.contest_current("test")
+
+    def case_files(self):
+        test_dir = self.test_dir()
+        if not test_dir.exists():
+            return []
+        return sorted(str(p) for p in test_dir.iterdir() if p.is_file() and case_name(p).endswith((".in", ".out")))
+
+    def select(self, names):
+        """NAME（large-1 / large-1.in など）に該当するケースファイル。省略時は圧縮されたものすべて"""
+        if not names:
+            return [f for f in self.case_files() if is_compressed(f)]
+        selected = []
+        for name in names:
+            stem = name[:-len(ZSTD_SUFFIX)] if name.endswith(ZSTD_SUFFIX) else name
+            candidates = [stem] if stem.endswith((".in", ".out")) else [f"{stem}.in", f"{stem}.out"]
+            found = [f for f in (find_case_file(self.test_dir() / c) for c in candidates) if f]
+            if not found:
+                raise FileNotFoundError(f"テストケースが見つかりません: {name}")
+            selected += found
+        return selected
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def get_anonymizer(self):
+        return CaseAnonymizer.from_config(self.get_config().get_test(), cwd=str(self.upm.project_path.root)).require()
+
+    def export_anonymized(self, paths, dest_dir):
+        """
+        書き出しの変換パス。入力を伏せ、期待出力は参照解で作り直す。
+        入力とそろって選ばれた期待出力は入力と一緒に処理する。
+        """
+        anonymizer = self.get_anonymizer()
+        selected = set(paths)
+        exported = []
+        for path in paths:
+            name = case_name(path)
+            if name.endswith(".out") and find_case_file(case_stem(path) + ".in") in selected:
+                continue
+            if name.endswith(".out"):
+                contents = [(name, anonymizer.scrub(read_case(path))[0])]
+                count = None
+            else:
+                out_file = find_case_file(expected_file(path))
+                expected = read_case(out_file) if out_file else None
+                scrubbed, expected, count = anonymizer.transform(read_case(path), expected)
+                contents = [(name, scrubbed)]
+                if expected is not None:
+                    contents.append((case_name(out_file or expected_file(path)), expected))
+                elif out_file:
+                    print(f"[case] {name}: 参照解（test.anonymize.reference）が無いため期待出力は書き出しません")
+            for file_name, content in contents:
+                dest = os.path.join(dest_dir, file_name)
+                os.makedirs(dest_dir, exist_ok=True)
+                with open(dest, "w", encoding="utf-8") as f:
+                    f.write(content)
+                exported.append(dest)
+            note = f"（{count}箇所を伏せました）" if count else ""
+            print(f"[case] {name} → {os.path.join(dest_dir, name)}{note}")
+        return exported
+
+    def export(self, names):
+        output = self.options.get("output")
+        dest_dir = output if output and output is not True else str(self.upm.cph_dir(EXPORT_DIR))
+        if self.options.get("anonymize"):
+            paths = self.select(names) if names else [f for f in self.case_files() if case_name(f).endswith(".in")]
+            return self.export_anonymized(paths, dest_dir)
+        exported = []
+        for path in self.select(names):
+            dest = os.path.join(dest_dir, case_name(path))
+            export_case(path, dest)
+            exported.append(dest)
+            print(f"[case] {case_name(path)} → {dest}")
+        if not exported:
+            print("[case] 圧縮されたテストケースはありません")
+        return exported
+
+    def threshold(self):
+        value = self.options.get("threshold")
+        if value is not None and value is not True:
+            return int(value)
+        return compress_threshold(self.get_config().get_test())
+
+    def compress(self):
+        threshold = self.threshold()
+        if threshold is None:
+            print("[case] test.compress_thresholdが0のため圧縮しません")
+            return []
+        compressed = []
+        for path in self.case_files():
+            if is_compressed(path):
+                continue
+            size = os.path.getsize(path)
+            if size < threshold:
+                continue
+            dest = compress_case(path)
+            compressed.append(dest)
+            print(f"[case] 圧縮: {case_name(path)}（{format_size(size)} → {format_size(os.path.getsize(dest))}）")
+        if not compressed:
+            print(f"[case] {format_size(threshold)}以上のテストケースはありません")
+        return compressed
+
+    def lint(self):
+        found = lint_cases(self.test_dir(), fix=not self.options.get("dry-run"), log_prefix="[case]")
+        if not found:
+            print("[case] 期待出力に紛らわしい箇所はありません")
+        return found
+
+    def check(self):
+        checker = CaseChecker(self.test_dir(), self.get_config().get_test(), cwd=str(self.upm.project_path.root))
+        issues = report(checker.check(), log_prefix="[case]")
+        errors = [i for i in issues if i.level == ERROR]
+        if errors:
+            raise CphError(f"テストケースに{len(errors)}件の問題があります", kind="case_check_failed")
+        if not issues:
+            print("[case] テストケースに問題はありません")
+        return issues
+
+    def choose(self, change):
+        """取り直したサンプルとの違い1件をどう扱うか。--yesなら既定（手で直したものは残す）のまま"""
+        default = change.default_action
+        if self.options.get("yes"):
+            return default
+        if change.kind in KEEPABLE:
+            prompt = f"[a]問題ページのものにする / [k]手元のものを{custom_name(change.name)}として残す / [s]そのまま"
+        else:
+            prompt = "[a]取り込む / [s]そのまま"
+        answer = self.ask(f"{prompt} (既定: {default[0]}): ").strip().lower()[:1]
+        action = REBIND_ANSWERS.get(answer, default)
+        # 残す内容が無いものをkと答えたら、取り込むだけにする
+        return ACCEPT if action == KEEP and change.kind not in KEEPABLE else action
+
+    def rebind(self):
+        """
+        問題ページからサンプルを取り直し、手元のサンプルとの違い（番号の付け替え・内容の変更・追加・削除）を
+        1件ずつ表示して扱いを決める。手で追加したケース（sample-で始まらないもの）には触らない。
+        前回取得したときのチェックサム（problem.jsonのsample_checksums）と違うサンプルは手で直したものとして、既定で残す
+        """
+        contest_name, problem_name = active_problem(self.upm)
+        if contest_name is None or problem_name is None:
+            raise ValueError("作業中の問題がありません。open で問題を開いてから実行してください")
+        test_dir = self.test_dir()
+        index = ArchiveIndex(upm=self.upm)
+        recorded = (index.get(contest_name, problem_name) or {}).get("sample_checksums")
+        with temp_space.current().scoped("rebind-") as fresh_dir:
+            SampleScraper(self.upm).download(task_url(contest_name, problem_name), fresh_dir)
+            lint_fresh_samples(fresh_dir, self.get_config().get_test())
+            fresh = sample_checksums(fresh_dir)
+            changes = plan_rebind(sample_checksums(test_dir), fresh, recorded)
+            if not changes:
+                print("[case] 手元のサンプルは問題ページと同じです")
+            decisions = []
+            for change in changes:
+                lines = describe(change, test_dir, fresh_dir)
+                print(f"[case] {lines[0]}{'（手元で変更されています）' if change.edited else ''}")
+                for line in lines[1:]:
+                    print(line)
+                if not self.options.get("dry-run"):
+                    decisions.append((change, self.choose(change)))
+            if self.options.get("dry-run"):
+                if changes:
+                    print("[case] --dry-runのため変更しませんでした")
+                return changes
+            touched = apply_rebind(test_dir, fresh_dir, decisions)
+        kept = [custom_name(c.name) for c, action in decisions if action == KEEP and c.kind in KEEPABLE]
+        # 次に比べるときの基準は、今回取り直したサンプル（そのままにしたものは手元で変更されたものになる）
+        index.update(contest_name, problem_name, lambda entry: entry.update(sample_checksums=fresh))
+        if changes:
+            note = f"、{', '.join(kept)}として残しました" if kept else ""
+            print(f"[case] {len(touched)}/{len(changes)}件を問題ページのサンプルにそろえました{note}")
+        return decisions
+
+    async def run(self):
+        action, names = (self.args[0], self.args[1:]) if self.args else (None, [])
+        if action not in ACTIONS:
+            raise ValueError(f"caseの操作を指定してください: {' / '.join(ACTIONS)}")
+        if action == "export":
+            return self.export(names)
+        if action == "lint":
+            return self.lint()
+        if action == "check":
+            return self.check()
+        if action == "rebind":
+            return self.rebind()
+        return self.compress()
@@ -0,0 +1,46 @@
+from datetime import datetime
+from src.execution_client.container.image_manager import ContainerImageManager
+
+DEFAULT_IMAGE_DAYS = 30
+
+class CommandClean:
+    """
+    cphが作ったものを片付ける。
+    使い方: clean --images [--days 30] [--dry-run]
+    --images: cphがビルド・取得したdockerイメージのうち、N日以上使っていないものを削除する
+    （使用中のコンテナがあるイメージは削除されない）。
+    """
+    def __init__(self, options=None, image_manager=None):
+        self.options = options or {}
+        self.image_manager = image_manager
+
+    def days(self):
+        value = self.options.get("days")
+        return DEFAULT_IMAGE_DAYS if value is None or value is True else float(value)
+
+    def clean_images(self):
+        manager = self.image_manager or ContainerImageManager()
+        dry_run = bool(self.options.get("dry-run"))
+        unused = manager.unused_images(self.days())
+        if not unused:
+            print(f"[clean] {self.days():g}日以上使っていないイメージはありません")
+            return []
+        removed = []
+        for name, last_used in unused:
+            when = datetime.fromtimestamp(last_used).strftime("%Y-%m-%d")
+            if dry_run:
+                print(f"[clean] 削除対象: {name}（最終使用 {when}）")
+                continue
+            if manager.remove_image(name):
+                removed.append(name)
+                print(f"[clean] 削除: {name}（最終使用 {when}）")
+            else:
+                print(f"[clean] 削除できませんでした（使用中のコンテナがある可能性）: {name}")
+        if dry_run:
+            print("[clean] --dry-runのため削除していません")
+        return removed
+
+    async def clean(self):
+        if not self.options.get("images"):
+            raise ValueError("片付ける対象を指定してください: clean --images")
+        return self.clean_images()
@@ -0,0 +1,42 @@
+from .toolchain import DEFAULT_SITE, get_pins, image_for, installed_version, version_matches
+from src.config_json_manager import ConfigJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+class CommandDoctor:
+    """
+    ローカル（またはコンテナ）のツールチェインが、ジャッジに合わせて固定したバージョンと一致するか確認する。
+    使い方: doctor [language] [docker|local]
+    """
+    def __init__(self, exec_mode="docker", options=None):
+        self.exec_mode = exec_mode
+        self.options = options or {}
+        self.upm = UnifiedPathManager()
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def check_language(self, language_name, expected):
+        if self.exec_mode == "local":
+            cwd = str(self.upm.contest_current(language_name)) if language_name == "rust" else None
+            actual = installed_version(language_name, cwd=cwd)
+        else:
+            actual = installed_version(language_name, image=image_for(language_name, expected))
+        return {"language": language_name, "expected": expected, "actual": actual, "ok": version_matches(expected, actual)}
+
+    async def doctor(self, language_name=None):
+        site = self.options.get("site", DEFAULT_SITE)
+        pins = get_pins(self.get_config(), site)
+        languages = [language_name] if language_name else list(pins)
+        results = []
+        print(f"[doctor] {site} のツールチェインと照合します（{self.exec_mode}）")
+        for lang in languages:
+            if lang not in pins:
+                print(f"[警告] {lang} のバージョンが固定されていません")
+                continue
+            r = self.check_language(lang, pins[lang])
+            results.append(r)
+            mark = "OK" if r["ok"] else "NG"
+            print(f"  [{mark}] {lang}: 期待 {r['expected']} / 実際 {r['actual'] or '未検出'}")
+        if any(not r["ok"] for r in results):
+            print("[doctor] 不一致があります。prepare で固定バージョンを導入できます")
+        return results
@@ -0,0 +1,159 @@
+import math
+import shlex
+import time
+from .archive_index import ArchiveIndex
+from .command_run import CommandRun, limit_command
+from .command_test import DEFAULT_TIME_LIMIT, decide_seed
+from .constraints import evaluate_bound, fetch_constraints, parse_max_n
+from .sample_scraper import SampleScraper
+from src.error_hints import CphError
+from src.shell import CommandSpec, Shell
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 入力の大きさ（estimate.sizesが無いとき）
+DEFAULT_SIZES = [1000, 10000, 100000]
+# 制約を読む変数（estimate.variableで変える）
+DEFAULT_VARIABLE = "N"
+# 入力の生成コマンドの実行時間の上限（秒）
+GENERATOR_TIMEOUT = 60
+# 起動の時間を測るときの入力の大きさ（各実行時間からこれを差し引く）
+BASELINE_SIZE = 1
+# 起動の時間を差し引いて、これより短いものは誤差に埋もれるので伸びの見積もりに使わない（秒）
+MIN_MEASURABLE_TIME = 0.02
+# 1回の実行にかける時間の上限（TLの何倍か）。超えたらそれより大きい入力は試さない
+RUN_TIMEOUT_RATIO = 3
+
+def fit_growth(points):
+    """
+    (大きさ, 秒)の組から、時間 ≈ c * n^k の k と c を最小二乗（両対数）で求める。
+    短すぎる実行時間は除き、大きさが2種類に満たなければNone
+    """
+    usable = [(n, t) for n, t in points if n > 0 and t >= MIN_MEASURABLE_TIME]
+    if len({n for n, _ in usable}) < 2:
+        return None
+    xs = [math.log(n) for n, _ in usable]
+    ys = [math.log(t) for _, t in usable]
+    mx, my = sum(xs) / len(xs), sum(ys) / len(ys)
+    exponent = sum((x - mx) * (y - my) for x, y in zip(xs, ys)) / sum((x - mx) ** 2 for x in xs)
+    return {"exponent": exponent, "coefficient": math.exp(my - exponent * mx)}
+
+def predict_time(fit, n):
+    return fit["coefficient"] * n ** fit["exponent"]
+
+def growth_label(exponent):
+    """伸びの指数のおおよその計算量"""
+    if exponent < 0.5:
+        return "ほぼ一定"
+    for k, label in ((1, "O(N)〜O(N log N)"), (2, "O(N^2)"), (3, "O(N^3)")):
+        if abs(exponent - k) < 0.35:
+            return label
+    return f"O(N^{exponent:.1f})"
+
+class CommandEstimate:
+    """
+    生成した大きさの違う入力で解答の実行時間を測り、その伸びから制約の上限でのTLEを見積もる。
+    入力はconfig.jsonのestimate.generator（{n}・{seed}を置き換えてcontest_currentで実行し、標準出力を入力にする）で作る。
+    制約の上限は --max-n か、problem.jsonのmax_n（無ければproblem.jsonのconstraints・問題文の制約から読んで残す）。
+    大きさ1の入力で測った起動の時間を差し引いてから、両対数の傾きで伸び（N^k）を求める。
+    使い方: estimate [--max-n 200000] [--seed 1]
+    """
+    def __init__(self, file_manager, test_env, options=None, upm=None, shell=None, scraper=None):
+        self.options = options or {}
+        self.runner = CommandRun(file_manager, test_env, options=self.options)
+        self.env = test_env
+        self.upm = upm or UnifiedPathManager()
+        self.shell = shell or Shell()
+        self.scraper = scraper or SampleScraper(self.upm)
+
+    def get_config(self):
+        return self.runner.command_test.get_config()
+
+    def option_max_n(self):
+        value = self.options.get("max-n")
+        if value is None or value is True:
+            return None
+        try:
+            return evaluate_bound(str(value).replace("e", "*10^") if "e" in str(value) else str(value))
+        except ValueError:
+            raise ValueError(f"--max-n には整数を指定してください（2e5 / 2*10^5 も可）: {value}")
+
+    def max_n(self, contest_name, problem_name, variable):
+        """制約の上限。--max-n → problem.jsonのmax_n → 問題文の制約の順に探し、見つけたものはproblem.jsonに残す"""
+        index = ArchiveIndex(upm=self.upm)
+        value = self.option_max_n()
+        if value is None:
+            entry = index.get(contest_name, problem_name) or {}
+            if entry.get("max_n") is not None:
+                return entry["max_n"]
+            # openで残した制約から読む（無ければ問題ページから読んで残す）
+            constraints = entry.get("constraints")
+            if constraints is None:
+                constraints = fetch_constraints(contest_name, problem_name, self.upm, self.scraper) or []
+            value = parse_max_n("\n".join(constraints), variable)
+            if value is None:
+                raise ValueError(f"問題文の制約から{variable}の上限を読めませんでした。--max-n で指定してください")
+            print(f"[estimate] 問題文の制約から {variable} ≦ {value} を読みました")
+        index.update(contest_name, problem_name, lambda e: e.update(max_n=value))
+        return value
+
+    def generate(self, generator, n, seed):
+        argv = shlex.split(generator.format(n=n, seed=seed))
+        result = self.shell.run(CommandSpec.from_argv(argv, cwd=str(self.upm.contest_current()), timeout=GENERATOR_TIMEOUT))
+        if not result.ok:
+            reason = "時間切れ" if result.timed_out else ((result.stderr or "").strip().splitlines() or [f"code={result.returncode}"])[-1]
+            raise CphError(f"入力の生成に失敗しました（n={n}）: {reason}", kind="generator_failed")
+        return result.stdout
+
+    def measure(self, prepared, input_data, timeout):
+        """1回実行して(成功したか, 秒, 標準エラー出力)を返す"""
+        handler, container, cont_source_path = prepared
+        cmd = limit_command(handler.run_command(cont_source_path), timeout)
+        in_file = self.runner.write_temp_input(input_data)
+        start = time.perf_counter()
+        ok, _, stderr = self.env.exec_command(container, cmd, self.env.to_container_path(in_file))
+        return ok, time.perf_counter() - start, stderr
+
+    async def estimate(self, contest_name, problem_name, language_name):
+        config = self.get_config().get_estimate()
+        generator = config.get("generator")
+        if not generator:
+            raise ValueError("config.jsonのestimate.generatorに入力の生成コマンドを書いてください（例: python3 gen.py {n} {seed}）")
+        variable = config.get("variable", DEFAULT_VARIABLE)
+        max_n = self.max_n(contest_name, problem_name, variable)
+        time_limit = self.get_config().get_time_limit() or DEFAULT_TIME_LIMIT
+        sizes = sorted({int(n) for n in config.get("sizes") or DEFAULT_SIZES if int(n) <= max_n})
+        if not sizes:
+            raise ValueError(f"estimate.sizesに{variable}の上限（{max_n}）以下の大きさがありません")
+        seed = decide_seed(self.options)
+        prepared = self.runner.prepare(contest_name, problem_name, language_name)
+        timeout = time_limit * RUN_TIMEOUT_RATIO
+
+        def run(n):
+            ok, elapsed, stderr = self.measure(prepared, self.generate(generator, n, seed), timeout)
+            if not ok and elapsed < timeout:
+                print(stderr or "", end="")
+                raise CphError(f"{variable}={n}の入力で解答が異常終了しました（--seed {seed} で再現できます）", kind="estimate_crashed")
+            return elapsed
+        # 起動（インタプリタ・コンテナへのexec）にかかる時間は入力の大きさによらないので、伸びからは除く
+        baseline = run(BASELINE_SIZE)
+        print(f"[estimate] 起動の時間: {baseline:.3f}秒（以下の伸びの見積もりでは差し引きます）")
+        points = []
+        for n in sizes:
+            elapsed = run(n)
+            print(f"[estimate] {variable}={n}: {elapsed:.3f}秒")
+            points.append((n, max(elapsed - baseline, 0.0)))
+            if elapsed >= time_limit:
+                print(f"[estimate] 警告: {variable}={n}で既にTL {time_limit:g}秒を超えています（上限は{variable}={max_n}）")
+                return {"max_n": max_n, "points": points, "fit": None, "predicted": None, "tle": True}
+        fit = fit_growth(points)
+        if fit is None:
+            print(f"[estimate] 実行時間が短すぎて伸びを見積もれません（estimate.sizesにもっと大きい{variable}を足してください）")
+            return {"max_n": max_n, "points": points, "fit": None, "predicted": None, "tle": False}
+        predicted = predict_time(fit, max_n) + baseline
+        print(f"[estimate] 実測の伸び: {variable}^{fit['exponent']:.2f}（{growth_label(fit['exponent'])}）")
+        tle = predicted > time_limit
+        if tle:
+            print(f"[estimate] 警告: {variable}={max_n}では約{predicted:.2f}秒と見込まれ、TL {time_limit:g}秒を超えそうです")
+        else:
+            print(f"[estimate] {variable}={max_n}では約{predicted:.2f}秒の見込みです（TL {time_limit:g}秒の{predicted / time_limit:.0%}）")
+        return {"max_n": max_n, "points": points, "fit": fit, "predicted": predicted, "tle": tle}
@@ -0,0 +1,170 @@
+from .case_storage import alternative_expected_files, expected_file, find_case_file
+from .command_test import DEFAULT_TIME_LIMIT, TL_WARN_RATIO, CommandTest
+from .comparator import TokensComparator, YesNoComparator
+from .run_artifacts import NON_FAILURE_VERDICTS, RunArtifacts
+from .test_layout import group_of
+from src.config_json_manager import ConfigJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 違う行の前後に出す行数
+CONTEXT_LINES = 2
+
+def first_difference(expected_items, actual_items):
+    """最初に違う位置（0始まり）。片方が先に終わった場合も含む。同じならNone"""
+    for i in range(max(len(expected_items), len(actual_items))):
+        e = expected_items[i] if i < len(expected_items) else None
+        a = actual_items[i] if i < len(actual_items) else None
+        if e != a:
+            return i
+    return None
+
+def formatting_difference(expected, actual):
+    """見た目だけの違いの種類（whitespace / yes_no / case）。中身が違えばNone"""
+    if expected == actual:
+        return None
+    tokens = TokensComparator()
+    if tokens.match(expected, actual):
+        return "whitespace"
+    if YesNoComparator(tokens).match(expected, actual):
+        return "yes_no"
+    if tokens.match(expected.lower(), actual.lower()):
+        return "case"
+    return None
+
+def explain_difference(expected, actual):
+    """
+    期待出力と実際の出力の違いのまとめ。
+    {"line": 最初に違う行(1始まり), "token": 最初に違うトークン(1始まり), "expected_token", "actual_token",
+     "expected_lines", "actual_lines", "expected_tokens", "actual_tokens", "formatting": 見た目だけの違い, "context": [(行番号, 期待, 実際)]}
+    """
+    exp_lines, out_lines = expected.rstrip("\n").splitlines(), actual.rstrip("\n").splitlines()
+    exp_tokens, out_tokens = expected.split(), actual.split()
+    line = first_difference(exp_lines, out_lines)
+    token = first_difference(exp_tokens, out_tokens)
+    context = []
+    if line is not None:
+        for i in range(max(line - CONTEXT_LINES, 0), min(line + CONTEXT_LINES + 1, max(len(exp_lines), len(out_lines)))):
+            context.append((i + 1, exp_lines[i] if i < len(exp_lines) else None, out_lines[i] if i < len(out_lines) else None))
+    return {
+        "line": None if line is None else line + 1,
+        "token": None if token is None else token + 1,
+        "expected_token": exp_tokens[token] if token is not None and token < len(exp_tokens) else None,
+        "actual_token": out_tokens[token] if token is not None and token < len(out_tokens) else None,
+        "expected_lines": len(exp_lines),
+        "actual_lines": len(out_lines),
+        "expected_tokens": len(exp_tokens),
+        "actual_tokens": len(out_tokens),
+        "formatting": formatting_difference(expected, actual),
+        "context": context,
+    }
+
+FORMATTING_NOTES = {
+    "whitespace": "空白・改行の違いだけです（--compare tokens ならAC）",
+    "yes_no": "Yes / YES / yes の表記の違いだけです（--compare exact+yesno ならAC）",
+    "case": "大文字・小文字の違いだけです",
+}
+
+class CommandExplain:
+    """
+    直近のtestで失敗したケースについて、なぜ失敗したかの見当を短くまとめる。
+    最初に違う行（前後の行つき）とトークン、出力の長さの違い、空白・大文字小文字だけの違いか、TLに対する実行時間を出す。
+    使い方: explain [CASE]（省略時は直近のtestで最初に失敗したケース）
+    """
+    def __init__(self, options=None, args=None, upm=None):
+        self.options = options or {}
+        self.args = list(args or [])
+        self.upm = upm or UnifiedPathManager()
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def latest_run(self, contest_name, problem_name, language_name):
+        history = RunArtifacts.history(contest_name, problem_name, language_name, self.upm.project_path.root)
+        if not history:
+            raise ValueError(f"{contest_name} {problem_name}（{language_name}）のtestの記録がありません。先にtestを実行してください")
+        return history[0]
+
+    def find_case(self, run, name=None):
+        cases = run.get("cases", [])
+        if name is None:
+            case = next((c for c in cases if c.get("verdict") not in NON_FAILURE_VERDICTS), None)
+            if case is None:
+                raise ValueError("直近のtestで失敗したケースはありません")
+            return case
+        stem = RunArtifacts.case_stem(name)
+        case = next((c for c in cases if RunArtifacts.case_stem(c["name"]) == stem), None)
+        if case is None:
+            raise ValueError(f"直近のtestに {name} はありません（{', '.join(c['name'] for c in cases)}）")
+        return case
+
+    def read_run_output(self, run, case, suffix):
+        path = self.upm.runs_dir(run["run_id"], f"{RunArtifacts.case_stem(case['name'])}.{suffix}")
+        return path.read_text(encoding="utf-8") if path.exists() else ""
+
+    def case_in_file(self, case):
+        """記録したケースのcontest_current/testでの入力（小課題のケースはgroup_N/の中）"""
+        group = group_of(case.get("in_file", ""))
+        return str(self.upm.contest_current("test", *([group] if group else []), f"{RunArtifacts.case_stem(case['name'])}.in"))
+
+    def read_expected(self, case):
+        """(期待出力, もう一つの期待出力の一覧)。testと同じ読み方をする。期待出力が無ければ(None, [])"""
+        in_file = self.case_in_file(case)
+        if find_case_file(expected_file(in_file)) is None and not alternative_expected_files(in_file):
+            return None, []
+        return CommandTest(None, None).read_expected_outputs(in_file)
+
+    @staticmethod
+    def closest_difference(candidates, actual):
+        """期待出力が複数あるときは一致したものを、どれとも違えば1つ目との違いを返す"""
+        analyses = [explain_difference(expected, actual) for expected in candidates]
+        return next((a for a in analyses if a["line"] is None and a["token"] is None), analyses[0])
+
+    def describe(self, case, analysis, stderr):
+        verdict = case.get("verdict")
+        lines = [f"[explain] {case['name']}: {verdict}"]
+        if case.get("runtime_error"):
+            lines.append(f"  原因の見当: {case['runtime_error']['note']}")
+        elif verdict == "RE" and stderr.strip():
+            lines.append(f"  標準エラー出力: {stderr.strip().splitlines()[-1]}")
+        if analysis is None:
+            lines.append("  期待出力が無いため、出力は比べられません")
+        elif analysis["line"] is None and analysis["token"] is None:
+            lines.append("  出力は期待出力と同じです")
+        else:
+            if analysis["formatting"]:
+                lines.append(f"  {FORMATTING_NOTES[analysis['formatting']]}")
+            if analysis["line"] is not None:
+                lines.append(f"  最初に違う行: {analysis['line']}行目")
+                for number, e, a in analysis["context"]:
+                    mark = ">" if number == analysis["line"] else " "
+                    lines.append(f"   {mark}{number:>4} 期待: {'（なし）' if e is None else e}")
+                    lines.append(f"   {mark}{'':>4} 出力: {'（なし）' if a is None else a}")
+            if analysis["token"] is not None:
+                lines.append(f"  最初に違うトークン: {analysis['token']}番目"
+                             f"（期待 {analysis['expected_token'] or '（なし）'} / 出力 {analysis['actual_token'] or '（なし）'}）")
+            if (analysis["expected_lines"], analysis["expected_tokens"]) != (analysis["actual_lines"], analysis["actual_tokens"]):
+                lines.append(f"  長さ: 期待 {analysis['expected_lines']}行・{analysis['expected_tokens']}トークン / "
+                             f"出力 {analysis['actual_lines']}行・{analysis['actual_tokens']}トークン")
+        lines.append(self.describe_time(case))
+        return lines
+
+    def describe_time(self, case):
+        time_limit = self.get_config().get_time_limit() or DEFAULT_TIME_LIMIT
+        elapsed = case.get("time")
+        if elapsed is None:
+            return "  実行時間: 記録なし"
+        ratio = elapsed / time_limit
+        # testのTL警告と同じ割合（config.jsonのtest.tl_warn_ratio）で、TLに近いとみなす
+        warn_ratio = self.get_config().get_test().get("tl_warn_ratio", TL_WARN_RATIO)
+        note = "（TLに近いので、ジャッジではTLEの可能性があります）" if warn_ratio and ratio >= float(warn_ratio) else ""
+        return f"  実行時間: {elapsed:.3f}秒（TL {time_limit:g}秒の{ratio:.0%}）{note}"
+
+    async def explain(self, contest_name, problem_name, language_name):
+        run = self.latest_run(contest_name, problem_name, language_name)
+        case = self.find_case(run, self.args[0] if self.args else None)
+        expected, alternatives = self.read_expected(case)
+        actual = self.read_run_output(run, case, "stdout")
+        analysis = None if expected is None else self.closest_difference([expected] + alternatives, actual)
+        for line in self.describe(case, analysis, self.read_run_output(run, case, "stderr")):
+            print(line)
+        return analysis
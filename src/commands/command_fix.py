@@ -0,0 +1,73 @@
+from pathlib import PurePosixPath
+from .opener import Opener
+from src.config_json_manager import ConfigJsonManager
+from src.environment.diagnostics import DiagnosticStore
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+class CommandFix:
+    """
+    直近のtestのビルドエラー・実行時エラーの位置をエディタで開く。
+    使い方: fix [N]（N番目の診断を開く。既定は1番目）
+    エディタはconfig.jsonのeditor.command（{file} {line} {column}）、無ければ$EDITOR、それも無ければVSCode。
+    editor.auto_jumpをtrueにすると、testが失敗したときに自動で開く。
+    """
+    def __init__(self, options=None, opener=None, args=None, upm=None):
+        self.options = options or {}
+        self.opener = opener or Opener()
+        self.args = list(args or [])
+        self.upm = upm or UnifiedPathManager()
+        self.store = DiagnosticStore(self.upm)
+
+    def get_editor_config(self):
+        return ConfigJsonManager(str(self.upm.config_json())).get_editor()
+
+    def resolve_file(self, path, language_name):
+        """
+        診断のパス（.temp/main.py、コンテナ内の/workspace/...、cargoのsrc/main.rsなど）を
+        contest_current/{language}の実ファイルに対応付ける。後ろの部分が一致する最も長いものを選ぶ
+        """
+        base = self.upm.contest_current(language_name)
+        parts = [p for p in PurePosixPath(str(path).replace("\\", "/")).parts if p not in ("/", "..")]
+        for i in range(len(parts)):
+            candidate = base.joinpath(*parts[i:])
+            if candidate.is_file():
+                return candidate
+        return None
+
+    def locations(self):
+        data = self.store.load()
+        if not data:
+            return None, []
+        found = []
+        for d in data.get("diagnostics", []):
+            path = self.resolve_file(d["file"], data.get("language_name"))
+            if path is not None:
+                found.append({**d, "path": path})
+        return data, found
+
+    def index(self):
+        if not self.args:
+            return 0
+        try:
+            return int(self.args[0]) - 1
+        except ValueError:
+            raise ValueError(f"開く診断の番号を指定してください: {self.args[0]}")
+
+    def jump(self, index=0):
+        data, found = self.locations()
+        if not found:
+            print("[fix] 直近のtestにファイルと行の分かるエラーはありません")
+            return None
+        if not 0 <= index < len(found):
+            raise ValueError(f"診断は{len(found)}件です（1〜{len(found)}で指定してください）")
+        for i, d in enumerate(found):
+            mark = "→" if i == index else " "
+            print(f"[fix] {mark} {i + 1}. {d['path']}:{d['line']}:{d['column']} {d['level']}: {d['message']}")
+        target = found[index]
+        where = f"（{data['case']}）" if data.get("case") else ""
+        print(f"[fix] {data.get('stage')}のエラー{where}の位置を開きます: {target['path']}:{target['line']}")
+        self.opener.open_editor_at(str(target["path"]), target["line"], target["column"], self.get_editor_config().get("command"))
+        return target
+
+    async def fix(self):
+        return self.jump(self.index())
@@ -0,0 +1,122 @@
+import os
+import shutil
+import tarfile
+import time
+from .archive_index import ArchiveIndex, SOLVED_STATUS
+from src.info_json_manager import InfoJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+DEFAULT_GC_DAYS = 30
+# 圧縮して退避した問題の置き場（.cph/gc/{contest}_{problem}.tar.gz）
+GC_ARCHIVE_DIR = "gc"
+# 問題ディレクトリと同じ階層にある、問題ではないディレクトリ（他の問題のテストケースの退避先）
+NON_PROBLEM_DIRS = {"test"}
+
+def dir_size(path):
+    total = 0
+    for root, _, names in os.walk(str(path)):
+        for name in names:
+            p = os.path.join(root, name)
+            if not os.path.islink(p):
+                total += os.path.getsize(p)
+    return total
+
+def last_modified(path):
+    latest = os.path.getmtime(str(path))
+    for root, _, names in os.walk(str(path)):
+        for name in names:
+            p = os.path.join(root, name)
+            if not os.path.islink(p):
+                latest = max(latest, os.path.getmtime(p))
+    return latest
+
+def format_size(size):
+    for unit in ("B", "KB", "MB"):
+        if size < 1024:
+            return f"{size:.0f}{unit}" if unit == "B" else f"{size:.1f}{unit}"
+        size /= 1024
+    return f"{size:.1f}GB"
+
+class CommandGc:
+    """
+    N日以上触っておらず、ACもしていない保管済みの問題を見つけて、圧縮して退避するか削除する。
+    使い方: gc [--days 30] [--yes] [--delete]
+    --yesなしでは問題ごとに確認する。--yesだけなら圧縮して退避（--deleteで削除）。
+    """
+    def __init__(self, options=None, upm=None, index=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.index = index or ArchiveIndex(upm=self.upm)
+
+    def days(self):
+        value = self.options.get("days")
+        return DEFAULT_GC_DAYS if value is None or value is True else float(value)
+
+    def current_problem(self):
+        info = InfoJsonManager(self.upm.info_json()).data
+        return info.get("contest_name"), info.get("problem_name")
+
+    def find_candidates(self, now=None):
+        now = now or time.time()
+        threshold = now - self.days() * 24 * 60 * 60
+        current = self.current_problem()
+        archive = self.index.load()
+        candidates = []
+        for contest, problem, path in self.upm.stock_problem_dirs():
+            if problem in NON_PROBLEM_DIRS or (contest, problem) == current:
+                continue
+            entry = archive.get(self.index.key(contest, problem)) or {}
+            if entry.get("status") == SOLVED_STATUS:
+                continue
+            modified = last_modified(path)
+            if modified > threshold:
+                continue
+            candidates.append({"contest": contest, "problem": problem, "path": path, "size": dir_size(path), "days": int((now - modified) // 86400)})
+        return candidates
+
+    def archive_problem(self, candidate):
+        dest_dir = self.upm.cph_dir(GC_ARCHIVE_DIR)
+        dest_dir.mkdir(parents=True, exist_ok=True)
+        dest = dest_dir / f"{candidate['contest']}_{candidate['problem']}.tar.gz"
+        with tarfile.open(dest, "w:gz") as tf:
+            # ビルド成果物は退避しない（容量の大半を占め、再ビルドできる）
+            tf.add(str(candidate["path"]), arcname=f"{candidate['contest']}/{candidate['problem']}",
+                   filter=lambda info: None if "/target/" in f"/{info.name}/" else info)
+        shutil.rmtree(candidate["path"])
+        return dest
+
+    def remove_empty_parent(self, path):
+        parent = path.parent
+        if parent != self.upm.stocks_root() and parent.exists() and not any(parent.iterdir()):
+            parent.rmdir()
+
+    def ask(self, candidate):
+        ans = input(f"{candidate['contest']} {candidate['problem']}（{candidate['days']}日前, {format_size(candidate['size'])}）: [a]圧縮して退避 / [d]削除 / [s]スキップ (a/d/S): ")
+        return {"a": "archive", "d": "delete"}.get(ans.strip().lower()[:1], "skip")
+
+    async def gc(self):
+        candidates = self.find_candidates()
+        if not candidates:
+            print(f"[gc] {self.days():g}日以上触っていない未ACの問題はありません")
+            return []
+        reclaimed = 0
+        handled = []
+        default_action = "delete" if self.options.get("delete") else "archive"
+        for c in candidates:
+            action = default_action if self.options.get("yes") else self.ask(c)
+            if action == "skip":
+                continue
+            before = c["size"]
+            if action == "delete":
+                shutil.rmtree(c["path"])
+                reclaimed += before
+                print(f"[gc] 削除: {c['contest']} {c['problem']}")
+            else:
+                dest = self.archive_problem(c)
+                reclaimed += before - os.path.getsize(dest)
+                print(f"[gc] 退避: {c['contest']} {c['problem']} → {dest}")
+            self.remove_empty_parent(c["path"])
+            c["action"] = action
+            handled.append(c)
+        print(f"[gc] {len(handled)}/{len(candidates)}問を整理し、{format_size(max(reclaimed, 0))}を解放しました")
+        return handled
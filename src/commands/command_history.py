@@ -0,0 +1,70 @@
+import csv
+import io
+import sys
+from src.file_operator import atomic_write
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from .run_artifacts import RunArtifacts
+
+# CSVの列（1行 = 1回の実行の1ケース）
+CSV_COLUMNS = ["run_id", "created_at", "contest", "problem", "language", "case", "verdict", "time", "cpu_time", "memory_kb"]
+# Excelで開いても文字化けしないようにBOMを付ける
+CSV_ENCODING = "utf-8-sig"
+
+def history_rows(runs, contest_name=None, problem_name=None):
+    """run.jsonの内容を、古い順にケースごとの行にする。contest_name / problem_nameを指定するとその問題だけ"""
+    rows = []
+    for run in sorted(runs, key=lambda r: r.get("run_id", "")):
+        if contest_name and run.get("contest_name") != contest_name:
+            continue
+        if problem_name and run.get("problem_name") != problem_name:
+            continue
+        for case in run.get("cases", []):
+            rows.append({
+                "run_id": run.get("run_id"),
+                "created_at": run.get("created_at"),
+                "contest": run.get("contest_name"),
+                "problem": run.get("problem_name"),
+                "language": run.get("language_name"),
+                "case": case.get("name"),
+                "verdict": case.get("verdict"),
+                "time": case.get("time"),
+                "cpu_time": case.get("cpu_time"),
+                "memory_kb": case.get("memory_kb"),
+            })
+    return rows
+
+def write_csv(rows, stream):
+    writer = csv.DictWriter(stream, fieldnames=CSV_COLUMNS, lineterminator="\n")
+    writer.writeheader()
+    for row in rows:
+        writer.writerow({k: "" if row.get(k) is None else row[k] for k in CSV_COLUMNS})
+
+class CommandHistory:
+    """
+    .cph/runs/に残っているテスト実行の履歴を書き出す（練習の記録を表計算ソフトで分析するため）。
+    使い方: history export --csv runs.csv [contest] [problem]（--csv - で標準出力）
+    """
+    def __init__(self, options=None, upm=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.args = []
+
+    async def run(self, contest_name=None, problem_name=None):
+        sub = self.args[0] if self.args else None
+        if sub != "export":
+            raise ValueError("使い方: history export --csv runs.csv [contest] [problem]")
+        return self.export(contest_name, problem_name)
+
+    def export(self, contest_name=None, problem_name=None):
+        path = self.options.get("csv")
+        if not path or path is True:
+            raise ValueError("--csv で書き出すファイルを指定してください（標準出力は --csv -）")
+        rows = history_rows(RunArtifacts.all_runs(self.upm.project_path.root), contest_name, problem_name)
+        if path == "-":
+            write_csv(rows, sys.stdout)
+            return rows
+        buffer = io.StringIO()
+        write_csv(rows, buffer)
+        atomic_write(path, buffer.getvalue(), encoding=CSV_ENCODING)
+        print(f"[history] {len(rows)}行を書き出しました: {path}")
+        return rows
@@ -0,0 +1,117 @@
+from datetime import datetime
+from src import http_client
+from .archive_index import ArchiveIndex, SOLVED_STATUS
+
+# 提出履歴を取得する公開API
+ATCODER_PROBLEMS_SUBMISSIONS = "https://kenkoooo.com/atcoder/atcoder-api/v3/user/submissions"
+ATCODER_PROBLEMS_MODELS = "https://kenkoooo.com/atcoder/resources/problem-models.json"
+ATCODER_PAGE_SIZE = 500  # AtCoder Problemsが1回に返す最大件数
+CODEFORCES_USER_STATUS = "https://codeforces.com/api/user.status"
+# Codeforcesのコンテストはcf{contestId}として索引に入れる（AtCoderの名前と区別するため）
+CODEFORCES_PREFIX = "cf"
+
+def split_atcoder_problem(contest_id, problem_id):
+    """("abc300", "abc300_a") → "a"。コンテストと問題IDの接頭辞が違う場合（過去問の流用など）は末尾を使う"""
+    if problem_id.startswith(contest_id + "_"):
+        return problem_id[len(contest_id) + 1:]
+    return problem_id.rsplit("_", 1)[-1]
+
+def fetch_atcoder(user):
+    """AtCoder Problemsから全提出を取得し、共通形式 [{contest, problem, status, language, time, difficulty}] にする"""
+    submissions = []
+    from_second = 0
+    while True:
+        page = http_client.get_json(ATCODER_PROBLEMS_SUBMISSIONS, {"user": user, "from_second": from_second})
+        submissions.extend(page)
+        if len(page) < ATCODER_PAGE_SIZE:
+            break
+        from_second = max(s["epoch_second"] for s in page) + 1
+    try:
+        models = http_client.get_json(ATCODER_PROBLEMS_MODELS)
+    except Exception:
+        # 難易度は付加情報なので、取れなくても履歴の取り込みは続ける
+        models = {}
+    # problem_difficultyはこのモジュールの定数を使うので、ここで読み込む
+    from .problem_difficulty import FETCHED_SOURCE, clip_difficulty
+    records = []
+    for s in submissions:
+        difficulty = (models.get(s["problem_id"]) or {}).get("difficulty")
+        records.append({
+            "contest": s["contest_id"],
+            "problem": split_atcoder_problem(s["contest_id"], s["problem_id"]),
+            "status": s.get("result"),
+            "language": s.get("language"),
+            "time": s.get("epoch_second"),
+            # openで取得したときと同じく、400未満の推定値は丸め込み、取得した値として残す（手で付けた値と区別する）
+            "difficulty": round(clip_difficulty(difficulty)) if difficulty is not None else None,
+            "difficulty_source": FETCHED_SOURCE if difficulty is not None else None,
+            "tags": [],
+        })
+    return records
+
+def fetch_codeforces(user):
+    data = http_client.get_json(CODEFORCES_USER_STATUS, {"handle": user})
+    if data.get("status") != "OK":
+        raise ValueError(f"Codeforces APIがエラーを返しました: {data.get('comment')}")
+    records = []
+    for s in data.get("result", []):
+        problem = s.get("problem", {})
+        if "contestId" not in problem:
+            continue
+        verdict = s.get("verdict")
+        records.append({
+            "contest": f"{CODEFORCES_PREFIX}{problem['contestId']}",
+            "problem": str(problem.get("index", "")).lower(),
+            "status": SOLVED_STATUS if verdict == "OK" else verdict,
+            "language": s.get("programmingLanguage"),
+            "time": s.get("creationTimeSeconds"),
+            "difficulty": problem.get("rating"),
+            "tags": list(problem.get("tags", [])),
+        })
+    return records
+
+FETCHERS = {
+    "atcoder": fetch_atcoder,
+    "codeforces": fetch_codeforces,
+}
+
+def aggregate(records):
+    """提出ごとの記録を問題ごとにまとめる。一度でもACならAC、そうでなければ最後の提出の結果"""
+    problems = {}
+    for r in sorted(records, key=lambda r: r.get("time") or 0):
+        key = (r["contest"], r["problem"])
+        p = problems.setdefault(key, {"contest": r["contest"], "problem": r["problem"], "status": None, "languages": [], "tags": [], "difficulty": None})
+        if p["status"] != SOLVED_STATUS:
+            p["status"] = r.get("status")
+        if r.get("language") and r["language"] not in p["languages"]:
+            p["languages"].append(r["language"])
+        p["tags"].extend(t for t in r.get("tags", []) if t not in p["tags"])
+        if r.get("difficulty") is not None:
+            p["difficulty"] = r["difficulty"]
+            p["difficulty_source"] = r.get("difficulty_source")
+        if r.get("time"):
+            p["submitted_at"] = datetime.fromtimestamp(r["time"]).isoformat()
+    return list(problems.values())
+
+class CommandImport:
+    """
+    AtCoder Problems / Codeforcesの公開APIから自分の提出履歴を取り込み、アーカイブの索引（.cph/archive.json）を作る。
+    使い方: import history --site atcoder|codeforces --user NAME
+    """
+    def __init__(self, options=None, index=None):
+        self.options = options or {}
+        self.index = index or ArchiveIndex()
+
+    async def import_history(self):
+        site = self.options.get("site") or "atcoder"
+        user = self.options.get("user")
+        if not user or user is True:
+            raise ValueError("--user でユーザー名を指定してください")
+        fetch = FETCHERS.get(site)
+        if fetch is None:
+            raise ValueError(f"未対応のサイトです: {site}（{', '.join(FETCHERS)}）")
+        problems = aggregate(fetch(user))
+        added = self.index.merge(problems, source=site)
+        solved = sum(1 for p in problems if p["status"] == SOLVED_STATUS)
+        print(f"[import] {site} の {user}: {len(problems)}問（AC {solved}問、新規 {added}問）を取り込みました")
+        return problems
@@ -0,0 +1,75 @@
+import os
+from .workspace_files import WorkspaceFiles
+from src.config_json_manager import ConfigJsonManager
+from src.workspace_manifest import WorkspaceManifestStore
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+DEFAULT_TEAM_MEMBERS = ["member1", "member2", "member3"]
+TEAM_README = """# チーム用ワークスペース
+
+- members/{名前}/ : メンバーごとの作業ディレクトリ
+- lib/            : 共有ライブラリ（print でノートブックにまとめる対象）
+- notebook/       : 印刷用ノートブック（notebook.pdf / notebook.ps）の出力先
+
+ノートブックの作成: python3 src/main.py print
+"""
+
+class CommandInit:
+    """
+    ワークスペースの初期化。
+    使い方: init（使う言語に合わせた.gitignoreと.editorconfigを作る。何度実行してもよい）
+          init --team [--members alice,bob,carol]（加えて、チーム戦・ICPC向けのレイアウトを作る）
+    """
+    def __init__(self, options=None):
+        self.options = options or {}
+        self.upm = UnifiedPathManager()
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def members(self):
+        members = self.options.get("members")
+        if not members or members is True:
+            return list(DEFAULT_TEAM_MEMBERS)
+        return [m.strip() for m in str(members).split(",") if m.strip()]
+
+    def init_team(self):
+        members = self.members()
+        for name in members:
+            os.makedirs(str(self.upm.team_dir("members", name)), exist_ok=True)
+        os.makedirs(str(self.upm.team_dir("lib")), exist_ok=True)
+        os.makedirs(str(self.upm.team_dir("notebook")), exist_ok=True)
+        readme = self.upm.team_dir("README.md")
+        if not readme.exists():
+            readme.write_text(TEAM_README, encoding="utf-8")
+        root = self.upm.project_path.root
+        team = {
+            "members": members,
+            "lib": os.path.relpath(str(self.upm.team_dir("lib")), str(root)),
+            "notebook": os.path.relpath(str(self.upm.team_dir("notebook", "notebook.pdf")), str(root)),
+        }
+        self.get_config().set_team(team)
+        print(f"[init] チーム用ワークスペースを作成しました: {self.upm.team_dir()}（メンバー: {', '.join(members)}）")
+        return team
+
+    def languages(self):
+        """contest_templateにテンプレートがある言語と、config.jsonのlanguagesで足した言語"""
+        template = self.upm.contest_template()
+        names = sorted(p.name for p in template.iterdir() if p.is_dir()) if template.is_dir() else []
+        return names + [name for name in self.get_config().get_languages() if name not in names]
+
+    def init_workspace_files(self):
+        written = WorkspaceFiles(self.upm.project_path.root, self.languages(), self.get_config().get_workspace()).generate()
+        if written:
+            print(f"[init] {' / '.join(written)} を更新しました（cphの範囲の外に書いた行はそのままです）")
+        else:
+            print("[init] .gitignore / .editorconfig は最新です")
+        return written
+
+    async def init(self):
+        # 構成の版を記録しておき、後の版のcphが古い構成を移行できるようにする
+        WorkspaceManifestStore(self.upm).create()
+        written = self.init_workspace_files()
+        if self.options.get("team"):
+            return self.init_team()
+        return written
@@ -1,13 +1,138 @@
+import http.cookiejar
+import os
+import re
+import time
+import urllib.parse
+from datetime import datetime
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.site_config import OJ_COOKIE_JAR, SITE_HOSTS, SITES_FILE, SiteConfig
+
+# ojがログインのセッションを残すクッキー（LWP形式）。ojと同じく$XDG_DATA_HOME（無ければ~/.local/share）の下に置かれる
+OJ_COOKIE_JAR_PATH = os.path.join("online-judge-tools", "cookie.jar")
+# dockerではホストのcookie.jarをコンテナのここにマウントする
+CONTAINER_OJ_COOKIE_JAR = "/root/.local/share/online-judge-tools/cookie.jar"
+# ログインしていると付くクッキー
+SESSION_COOKIES = {
+    "atcoder": ["REVEL_SESSION"],
+    "codeforces": ["X-User-Sha1", "X-User"],
+    "yukicoder": ["REVEL_SESSION"],
+}
+# AtCoderのREVEL_SESSIONにはユーザー名が入っている（%00UserScreenName%3Aalice%00）
+ACCOUNT_PATTERN = re.compile(r"UserScreenName:([^\x00]+)")
+# login --checkの終了コード（シェルのプロンプトなどから使う）
+EXIT_VALID = 0
+EXIT_EXPIRED = 2
+EXIT_MISSING = 3
+
+VALID = "valid"
+EXPIRED = "expired"
+MISSING = "missing"
+# cookie.jarが壊れていて読めない
+INVALID = "invalid"
+
+def oj_cookie_jars(environ=None):
+    """ojのcookie.jarを探す場所（$XDG_DATA_HOMEを設定していればそちらを先に）"""
+    environ = os.environ if environ is None else environ
+    data_home = environ.get("XDG_DATA_HOME")
+    paths = [os.path.join(data_home, OJ_COOKIE_JAR_PATH)] if data_home and os.path.isabs(data_home) else []
+    return paths + [OJ_COOKIE_JAR]
+
+def find_cookie_jar(paths=None):
+    """最初に見つかったcookie.jarのパス。無ければNone"""
+    return next((path for path in paths or oj_cookie_jars() if os.path.isfile(path)), None)
+
+def load_cookie_jar(paths=None):
+    """最初に見つかったcookie.jarを読む（期限切れのものも残す）。無ければNone、壊れていればhttp.cookiejar.LoadError"""
+    path = find_cookie_jar(paths)
+    if path is None:
+        return None
+    jar = http.cookiejar.LWPCookieJar(path)
+    jar.load(ignore_discard=True, ignore_expires=True)
+    return jar
+
+def site_cookies(jar, site):
+    hosts = SITE_HOSTS.get(site, [])
+    names = SESSION_COOKIES.get(site, [])
+    return [c for c in (jar or []) if c.name in names and any(c.domain.lstrip(".") == h or c.domain.lstrip(".").endswith("." + h) for h in hosts)]
+
+def account_of(cookie):
+    m = ACCOUNT_PATTERN.search(urllib.parse.unquote(cookie.value or ""))
+    return m.group(1) if m else None
+
+def session_status(jar, site, site_config=None, now=None, broken_jar=None):
+    """
+    サイトのセッションの状態。{"site", "state"（valid / expired / missing / invalid）, "expires"（不明ならNone）, "account", "source"}
+    ojのcookie.jarに無くても、sites.yamlにクッキーを書いていればログイン済みとみなす（期限は分からない）。
+    cookie.jarが壊れていれば（broken_jar）、sites.yamlのクッキーが無いサイトはinvalid
+    """
+    now = time.time() if now is None else now
+    cookies = site_cookies(jar, site)
+    status = {"site": site, "state": MISSING, "expires": None, "account": None, "source": None}
+    if cookies:
+        # 一番長く有効なもの（期限の無いセッションクッキーはブラウザを閉じるまで有効なので最優先）
+        cookie = max(cookies, key=lambda c: float("inf") if c.expires is None else c.expires)
+        expired = cookie.expires is not None and cookie.expires <= now
+        status.update(state=EXPIRED if expired else VALID, expires=cookie.expires, account=account_of(cookie), source="cookie.jar")
+        return status
+    if site_config is not None and site in site_config.sites and (site_config.sites[site].get("cookies")):
+        status.update(state=VALID, source=SITES_FILE)
+    elif broken_jar:
+        status.update(state=INVALID, source=broken_jar)
+    return status
+
+def format_status(status):
+    expires = datetime.fromtimestamp(status["expires"]).strftime("%Y-%m-%d %H:%M") if status["expires"] else None
+    if status["state"] == MISSING:
+        return f"{status['site']}: 未ログイン"
+    if status["state"] == EXPIRED:
+        return f"{status['site']}: 期限切れ（{expires}）。login でログインし直してください"
+    if status["state"] == INVALID:
+        return f"{status['site']}: cookie.jarを読めません（{status['source']}）。login でログインし直してください"
+    details = [status["account"] or "アカウント名は不明", f"有効期限 {expires}" if expires else "有効期限は不明"]
+    if status["source"] == SITES_FILE:
+        details.append(f"{SITES_FILE}のクッキー")
+    return f"{status['site']}: ログイン済み（{'、'.join(details)}）"
+
+def exit_code(statuses):
+    """期限切れ（読めないcookie.jarを含む）のサイトがあれば2、無くてどれかが有効なら0、どこにもログインしていなければ3"""
+    states = {s["state"] for s in statuses}
+    if EXPIRED in states or INVALID in states:
+        return EXIT_EXPIRED
+    return EXIT_VALID if VALID in states else EXIT_MISSING
+
 class CommandLogin:
-    def __init__(self):
-        pass
+    """
+    login --check: ojのcookie.jar（とsites.yaml）から、サイトごとのログイン状態・有効期限・アカウント名を表示する。
+    対話もネットワークへのアクセスもしない。--site NAMEでそのサイトだけを見る。終了コードはexit_codeを参照
+    """
+    def __init__(self, options=None, upm=None, jar_paths=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.jar_paths = jar_paths
 
     async def login(self):
+        if self.options.get("check"):
+            return self.check()
         # Dockerモードでloginする場合、cookie.jarをマウントする
         requirements = [
-            {"type": "ojtools", "count": 1, "volumes": {
-                "/home/cphelper/.local/share/online-judge-tools/cookie.jar": "/root/.local/share/online-judge-tools/cookie.jar"
-            }}
+            {"type": "ojtools", "count": 1, "volumes": {oj_cookie_jars()[0]: CONTAINER_OJ_COOKIE_JAR}}
         ]
         # ここでrequirementsを使ってコンテナ起動・oj login実行処理を追加する必要あり
-        raise NotImplementedError("login機能は新設計で未実装です (requirementsにcookie.jarマウント例を追加済み)") 
\ No newline at end of file
+        raise NotImplementedError("login機能は新設計で未実装です (requirementsにcookie.jarマウント例を追加済み)")
+
+    def check(self, now=None):
+        site = self.options.get("site")
+        sites = [site] if site and site is not True else list(SESSION_COOKIES)
+        unknown = [s for s in sites if s not in SESSION_COOKIES]
+        if unknown:
+            raise ValueError(f"未対応のサイトです: {', '.join(unknown)}（{', '.join(SESSION_COOKIES)}）")
+        jar = broken_jar = None
+        try:
+            jar = load_cookie_jar(self.jar_paths)
+        except http.cookiejar.LoadError:
+            broken_jar = find_cookie_jar(self.jar_paths)
+        site_config = SiteConfig.load_layered(self.upm.sites_yamls())
+        statuses = [session_status(jar, s, site_config, now, broken_jar) for s in sites]
+        for status in statuses:
+            print(f"[login] {format_status(status)}")
+        return exit_code(statuses)
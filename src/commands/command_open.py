@@ -1,3 +1,7 @@
+import asyncio
+import os
+import shutil
+from pathlib import Path
 from src.info_json_manager import InfoJsonManager
 from src.execution_client.container.pool import ContainerPool
 from src.execution_client.container.client import ContainerClient
@@ -5,30 +9,155 @@ from src.execution_client.container.image_manager import ContainerImageManager
 from src.path_manager.unified_path_manager import UnifiedPathManager
 from src.path_manager.file_operator import FileOperator
 from src.config_json_manager import ConfigJsonManager
+from src.file_operator import run_blocking
 from src.environment.test_environment import DockerTestExecutionEnvironment
+from src.error_hints import CphError
+from src.cancellation import is_interruption
+from .progress_reporter import ProgressReporter
+from .command_test import auto_jobs
+from .sample_scraper import SAMPLE_PREFIX, SampleScraper
+from .sample_rebind import sample_checksums
+from .constraints import fetch_constraints, show_constraints
+from .problem_difficulty import describe_difficulty, fetch_difficulty
+from .case_lint import lint_cases
+from .case_storage import case_name
+from .archive_index import ArchiveIndex
+from .test_layout import GROUP_PREFIX, group_dir_name
+from .existing_solution import detect_language, replaced_files
+from .problem_lock import active_problem
+from .command_switch import task_url
+from src.backup_manager import BackupManager
+from src import cancellation, events, temp_space
+
+# make_currentが返す変更の種類 → 表示
+CHANGE_LABELS = {
+    "stash": "contest_stocksに残しました",
+    "restore": "contest_stocksから取り出しました",
+    "template": "テンプレートから作りました",
+    "backup": "置き換える前の内容を残しました",
+}
 
 class CommandOpen:
-    def __init__(self, file_manager, opener, test_env):
+    def __init__(self, file_manager, opener, test_env, progress=None, options=None):
         self.file_manager = file_manager
         self.opener = opener
         self.test_env = test_env
+        self.progress = progress or ProgressReporter()
+        self.options = options or {}
         self.upm = UnifiedPathManager()
+        self.ask = input
+        # 問題ページを取得するもの（page_scraper）。openのたびに作り直し、前回のページを使わない
+        self.scraper = None
+
+    def choose_language(self, contest_name, problem_name):
+        """
+        言語の指定が無いとき、contest_stocksにある解答の拡張子から言語を決める（既定のテンプレートで始めないため）。
+        判定できなければsystem_info.jsonの言語を使う
+        """
+        default = InfoJsonManager(str(self.upm.info_json())).data.get("language_name")
+        language_name, found = detect_language(self.upm, contest_name, problem_name, default)
+        if language_name != default:
+            print(f"[open] {contest_name} {problem_name} には {language_name} の解答があるので、言語を {language_name} にします")
+        elif len(found) > 1 and default not in found:
+            print(f"[open] {contest_name} {problem_name} には {', '.join(found)} の解答があります。言語を指定すると切り替えられます")
+        if not language_name:
+            raise ValueError("言語を指定してください")
+        return language_name
+
+    def confirm_overwrite(self, contest_name, problem_name, language_name):
+        """
+        system_info.jsonに作業中の問題が無いと、contest_currentのコードがどの問題のものか分からないので、
+        保管済みの解答に置き換える前に確認する（置き換えるものは.cph/backups/に残す。--yesで確認しない）。
+        続けてよければTrue
+        """
+        if None not in active_problem(self.upm):
+            return True
+        replaced = replaced_files(self.upm, contest_name, problem_name, language_name)
+        if not replaced:
+            return True
+        print(f"[WARN] contest_currentの次のファイルはどの問題のものか分からないため、.cph/backups/に残してから {contest_name} {problem_name} の保管済みの解答に置き換えます:")
+        for path in replaced:
+            print(f"  {path}")
+        if self.options.get("yes"):
+            return True
+        try:
+            return self.ask("置き換えますか？ (y/N): ").strip().lower().startswith("y")
+        except EOFError:
+            # stdinが端末でない（パイプやCI）ときは答えられないので、置き換えない
+            print("\n[WARN] 確認できないため置き換えません（確認せずに置き換えるには --yes）")
+            return False
+
+    def display_path(self, path):
+        try:
+            return Path(str(path)).relative_to(self.upm.project_path.root)
+        except (TypeError, ValueError):
+            return path
+
+    def report_changes(self, changes):
+        """prepare_problem_filesで変わったファイルを1つずつ表示する"""
+        if not isinstance(changes, list):
+            return
+        if not changes:
+            print("[open] contest_currentはそのままです（変更したファイルはありません）")
+        for kind, path in changes:
+            print(f"[open] {CHANGE_LABELS.get(kind, kind)}: {self.display_path(path)}")
+
+    @staticmethod
+    def sample_files(test_dir):
+        """取得したサンプル。小課題ごとのサンプル（group_N/の中）も含む"""
+        test_dir = Path(str(test_dir))
+        if not test_dir.is_dir():
+            return []
+        files = [p for p in test_dir.iterdir() if p.is_file() and p.name.startswith(SAMPLE_PREFIX)]
+        for group_dir in test_dir.glob(f"{GROUP_PREFIX}*"):
+            files.extend(p for p in group_dir.iterdir() if p.is_file() and p.name.startswith(SAMPLE_PREFIX))
+        return sorted(files)
+
+    def should_download(self, test_dir):
+        """
+        サンプルを取得済みなら取り直さない（--refreshのときだけ、取得済みのサンプルを.cph/backups/に残してから取り直す）。
+        手で追加したケースは--refreshでも消さない
+        """
+        samples = self.sample_files(test_dir)
+        if not any(case_name(p).endswith(".in") for p in samples):
+            return True
+        if not self.options.get("refresh"):
+            print("[open] サンプルは取得済みなので取り直しません（取り直すには --refresh）")
+            return False
+        backup = BackupManager("refresh", upm=self.upm)
+        for path in samples:
+            backup.save(path)
+            path.unlink()
+            if path.parent.name.startswith(GROUP_PREFIX) and not any(path.parent.iterdir()):
+                path.parent.rmdir()
+        print(f"[open] 取得済みのサンプル{len(samples)}ファイルを{self.display_path(backup.path)}に残して取り直します")
+        return True
 
     async def open(self, contest_name, problem_name, language_name):
         """
         問題ファイルを準備し、VSCodeとCursorでディレクトリを開く
         必要なコンテナを事前に起動し、system_info.jsonで管理する
+        language_nameがNoneなら、保管済みの解答から言語を決める
         """
         import os
         import subprocess
+        if language_name is None:
+            language_name = self.choose_language(contest_name, problem_name)
+        if self.file_manager and not self.confirm_overwrite(contest_name, problem_name, language_name):
+            print("[open] 中止しました")
+            return None
         file_operator = self.file_manager.file_operator if self.file_manager and hasattr(self.file_manager, 'file_operator') else None
+        # 中断したときに、どこまで終わったかを知らせる
+        completed = []
         # 1. 問題ファイル準備（system_info.jsonもここで更新される）
         if self.file_manager:
-            self.file_manager.prepare_problem_files(contest_name, problem_name, language_name)
+            changes = await run_blocking(self.file_manager.prepare_problem_files, contest_name, problem_name, language_name)
+            self.report_changes(changes)
             problem_dir, test_dir = self.file_manager.get_problem_files(contest_name, problem_name, language_name)
+            completed.append("問題ファイルの準備")
         
         # 2. 問題ページをブラウザで開く
-        url = f"https://atcoder.jp/contests/{contest_name}/tasks/{contest_name}_{problem_name}"
+        url = task_url(contest_name, problem_name)
         if self.opener:
             self.opener.open_browser(url)
             # entry_file（config.json）を参照して開く
@@ -52,14 +181,118 @@ class CommandOpen:
         
         # 4. 必要なコンテナ・環境を調整
         requirements = [
-            {"type": "test", "language": language_name, "count": test_case_count},
+            {"type": "test", "language": language_name, "count": auto_jobs(test_case_count)},
             {"type": "ojtools", "count": 1, "volumes": {
                 "/home/cphelper/.local/share/online-judge-tools/cookie.jar": "/root/.local/share/online-judge-tools/cookie.jar"
             }}
         ]
         containers = self.test_env.adjust_containers(requirements, contest_name, problem_name, language_name)
+        completed.append("コンテナの準備")
         # 5. system_info.jsonの更新はadjust_containersで一括実施済み
         info_path = self.upm.info_json()
         manager = InfoJsonManager(info_path)
-        # 6. テストケースダウンロード（oj download）
-        self.test_env.download_testcases(url, self.upm.contest_current("test"))
\ No newline at end of file
+        # 6. テストケースダウンロード（oj download）。取得済みなら--refreshのときだけ取り直す
+        if self.should_download(self.upm.contest_current("test")):
+            self.scraper = None
+            self.progress.emit("download_started", url=url, contest_name=contest_name, problem_name=problem_name)
+            # ダウンロードと展開は別スレッドで行い、進捗表示などのイベントループを止めない。
+            # 取得全体にhttp.operation_timeout_secondsを掛け、Ctrl+Cではスレッド側の通信も止める
+            try:
+                with cancellation.operation():
+                    samples = await run_blocking(self.download_keeping_own_cases, url, self.upm.contest_current("test"))
+            except (asyncio.CancelledError, KeyboardInterrupt, CphError) as e:
+                if isinstance(e, CphError) and not is_interruption(e):
+                    raise
+                self.report_interrupted(contest_name, problem_name, completed, "サンプルの取得")
+                raise
+            self.record_sample_groups(contest_name, problem_name, samples)
+            if show_constraints(ConfigJsonManager(self.upm.config_json()).get_test()):
+                # testの冒頭で見せる制約を、問題ページから読んでproblem.jsonに残す（取れなくても開くのは続ける）
+                fetch_constraints(contest_name, problem_name, self.upm, self.page_scraper(), quiet=True)
+            self.progress.emit("download_finished", url=url, contest_name=contest_name, problem_name=problem_name)
+            # 取り込んだ期待出力の改行をそろえ、比較で紛らわしくなりそうな箇所を知らせる
+            if ConfigJsonManager(self.upm.config_json()).get_test().get("lint", True):
+                lint_cases(self.upm.contest_current("test"))
+            # チェックサムはlintで直した後の内容で残す（case rebindで取り直したものも同じように直してから比べる）
+            self.record_sample_checksums(contest_name, problem_name)
+        difficulty = fetch_difficulty(contest_name, problem_name, ConfigJsonManager(self.upm.config_json()).get_difficulty(), self.upm)
+        if difficulty is not None:
+            print(f"[open] 難易度: {describe_difficulty(difficulty)}")
+        events.publish(events.PROBLEM_OPENED, contest=contest_name, problem=problem_name, language=language_name,
+                       url=url, dir=self.upm.contest_current(language_name), via="open")
+
+    def report_interrupted(self, contest_name, problem_name, completed, step):
+        """中断・時間切れのときに、終わった手順と中断した手順を表示する"""
+        done = "、".join(completed) or "なし"
+        self.progress.emit("open_interrupted", contest_name=contest_name, problem_name=problem_name, completed=completed, step=step)
+        print(f"[open] {step}の途中で中断しました（完了: {done}）。取り直すには: open {contest_name} {problem_name} --refresh")
+
+    def download_keeping_own_cases(self, url, test_dir):
+        """oj downloadはtestディレクトリを作り直すので、手で追加したケースを退避してから取得し、元に戻す"""
+        test_dir = Path(str(test_dir))
+        own = sorted(p for p in test_dir.iterdir() if not p.name.startswith((SAMPLE_PREFIX, GROUP_PREFIX))) if test_dir.is_dir() else []
+        if not own:
+            return self.download_samples(url, test_dir)
+        with temp_space.current().scoped("open-") as kept:
+            for path in own:
+                shutil.move(str(path), os.path.join(kept, path.name))
+            try:
+                return self.download_samples(url, test_dir)
+            finally:
+                test_dir.mkdir(parents=True, exist_ok=True)
+                for path in kept.iterdir():
+                    shutil.move(str(path), str(test_dir / path.name))
+
+    def regroup_samples(self, url, test_dir):
+        """
+        oj downloadは小課題を区別しないので、問題ページの見出しに合わせてgroup_N/へ分ける。
+        ページが取れなくてもojのサンプルのまま進める（中断・時間切れは伝える）
+        """
+        try:
+            return self.page_scraper().regroup(url, test_dir)
+        except CphError as e:
+            if is_interruption(e):
+                raise
+            return []
+
+    def record_sample_groups(self, contest_name, problem_name, samples):
+        """小課題ごとのサンプルがあれば、そのグループ（--setで選べる名前）をproblem.jsonに残す"""
+        groups = [group_dir_name(g) for g in sorted({s.group for s in samples if getattr(s, "group", None)}, key=int)]
+        if groups:
+            ArchiveIndex(upm=self.upm).update(contest_name, problem_name, lambda entry: entry.update(sample_groups=groups))
+        return groups
+
+    def record_sample_checksums(self, contest_name, problem_name):
+        """取得したサンプルのチェックサムをproblem.jsonに残す（case rebindで手で直したサンプルを見分ける）"""
+        checksums = sample_checksums(self.upm.contest_current("test"))
+        if checksums:
+            ArchiveIndex(upm=self.upm).update(contest_name, problem_name, lambda entry: entry.update(sample_checksums=checksums))
+        return checksums
+
+    def page_scraper(self):
+        """この回のopenで問題ページを取得するもの。サンプルを取り出したページは、制約を読むときに取得し直さない"""
+        if self.scraper is None:
+            self.scraper = SampleScraper(self.upm, debug=bool(self.options.get("debug-scrape")))
+        return self.scraper
+
+    def download_samples(self, url, test_dir):
+        """
+        oj downloadでテストケースを取得し、失敗したら問題ページから直接サンプルを取り出す。
+        --debug-scrapeのときはojを使わず、取得したHTMLと取り出し方の結果を残す
+        """
+        if self.options.get("debug-scrape"):
+            return self.page_scraper().download(url, test_dir)
+        try:
+            self.test_env.download_testcases(url, test_dir)
+            return self.regroup_samples(url, test_dir)
+        except CphError as e:
+            print(f"[open] oj downloadに失敗したため、問題ページからサンプルを取り出します（{e}）")
+            try:
+                return self.page_scraper().download(url, test_dir)
+            except CphError as scrape_error:
+                # サンプルの数が合わないときはそのまま伝える（一部だけのテストで進めないため）
+                if scrape_error.kind == "sample_count_mismatch":
+                    raise
+                # 取り出しにも失敗したときは、元のoj downloadのエラーを伝える
+                print(f"[open] 問題ページからも取り出せませんでした（{scrape_error}）")
+                raise e
@@ -0,0 +1,77 @@
+import os
+import subprocess
+from .toolchain import DEFAULT_SITE, get_pins, image_for
+from src.config_json_manager import ConfigJsonManager
+from src.execution_client.container.image_manager import ContainerImageManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+RUST_TOOLCHAIN_FILE = "rust-toolchain.toml"
+
+def format_pins(pins):
+    return ", ".join(f"{lang}={version}" for lang, version in pins.items())
+
+class CommandPrepare:
+    """
+    ジャッジに合わせて固定したツールチェインを導入する。
+    docker: 固定バージョンのイメージ（rust:1.70.0など）を取得し、以降のテストで使う
+    local: rustはrustupでツールチェインを入れてrust-toolchain.tomlで固定する（Pythonは案内のみ）
+    使い方: prepare [language] [docker|local] [--pin]
+    """
+    def __init__(self, exec_mode="docker", options=None, image_manager=None):
+        self.exec_mode = exec_mode
+        self.options = options or {}
+        self.image_manager = image_manager or ContainerImageManager()
+        self.upm = UnifiedPathManager()
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def pin(self, config, site):
+        # config.jsonのtoolchainはtestのDockerイメージも切り替えるので、書き込むのは--pinのときだけ（それ以外は案内のみ）
+        pins = get_pins(config, site)
+        missing = {lang: v for lang, v in pins.items() if config.get_toolchain(site).get(lang) != v}
+        if not missing:
+            return pins
+        if self.options.get("pin"):
+            config.set_toolchain(site, pins)
+            print(f"[prepare] config.jsonのtoolchain.{site}に固定しました: {format_pins(missing)}")
+        else:
+            print(f"[prepare] ジャッジの既定バージョン: {format_pins(missing)}"
+                  f"（prepare --pin でconfig.jsonのtoolchain.{site}に固定すると、testもこのバージョンのイメージで実行します）")
+        return pins
+
+    def prepare_docker(self, language_name, version):
+        image = image_for(language_name, version)
+        print(f"[prepare] {language_name}: {image} を取得します")
+        return self.image_manager.pull_image(image)
+
+    def prepare_local(self, language_name, version):
+        if language_name != "rust":
+            print(f"[prepare] {language_name}: {version} は自動で導入できません（pyenv install {version.split('-v')[0]} などで導入してください）")
+            return False
+        rust_dir = self.upm.contest_current("rust")
+        os.makedirs(str(rust_dir), exist_ok=True)
+        with open(os.path.join(str(rust_dir), RUST_TOOLCHAIN_FILE), "w", encoding="utf-8") as f:
+            f.write(f'[toolchain]\nchannel = "{version}"\n')
+        print(f"[prepare] rust: rustup toolchain install {version}")
+        try:
+            result = subprocess.run(["rustup", "toolchain", "install", version, "--profile", "minimal"])
+        except FileNotFoundError:
+            print("[警告] rustupが見つかりません")
+            return False
+        return result.returncode == 0
+
+    async def prepare(self, language_name=None):
+        site = self.options.get("site", DEFAULT_SITE)
+        pins = self.pin(self.get_config(), site)
+        languages = [language_name] if language_name else list(pins)
+        results = {}
+        for lang in languages:
+            if lang not in pins:
+                print(f"[警告] {lang} のバージョンが固定されていません")
+                continue
+            if self.exec_mode == "local":
+                results[lang] = self.prepare_local(lang, pins[lang])
+            else:
+                results[lang] = self.prepare_docker(lang, pins[lang])
+        return results
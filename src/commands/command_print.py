@@ -0,0 +1,122 @@
+import os
+import shutil
+import subprocess
+from datetime import datetime
+from src.config_json_manager import ConfigJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# A4縦・Courier 8ptでの組版設定
+PAGE_WIDTH = 595
+PAGE_HEIGHT = 842
+MARGIN = 36
+FONT_SIZE = 8
+LINE_HEIGHT = 9.5
+LINE_WIDTH = 100  # 1行に収める文字数（超えたら折り返す）
+LINES_PER_PAGE = int((PAGE_HEIGHT - MARGIN * 2 - LINE_HEIGHT * 2) // LINE_HEIGHT)
+TAB_WIDTH = 4
+# ノートブックに含めるファイルの拡張子
+LIB_EXTENSIONS = (".py", ".rs", ".cpp", ".hpp", ".h", ".c", ".txt", ".md", ".tex")
+
+def ps_escape(text):
+    # Courierは非ASCIIを表示できないので?に置き換える
+    text = "".join(c if 32 <= ord(c) < 127 else "?" for c in text)
+    return text.replace("\\", "\\\\").replace("(", "\\(").replace(")", "\\)")
+
+def wrap_lines(text, width=LINE_WIDTH):
+    lines = []
+    for line in text.expandtabs(TAB_WIDTH).splitlines() or [""]:
+        while len(line) > width:
+            lines.append(line[:width])
+            line = "  " + line[width:]
+        lines.append(line)
+    return lines
+
+def paginate(files):
+    """[(見出し, 本文)] → ページごとの(見出し, 行リスト)。ファイルごとに改ページする"""
+    pages = []
+    for title, text in files:
+        lines = wrap_lines(text)
+        for i in range(0, len(lines), LINES_PER_PAGE):
+            pages.append((title, lines[i:i + LINES_PER_PAGE]))
+    return pages
+
+def render_postscript(files, title="notebook"):
+    pages = paginate(files)
+    out = [
+        "%!PS-Adobe-3.0",
+        f"%%Title: ({ps_escape(title)})",
+        f"%%Pages: {len(pages)}",
+        f"%%BoundingBox: 0 0 {PAGE_WIDTH} {PAGE_HEIGHT}",
+        "%%EndComments",
+        f"/Courier findfont {FONT_SIZE} scalefont setfont",
+    ]
+    top = PAGE_HEIGHT - MARGIN
+    for n, (header, lines) in enumerate(pages, 1):
+        out.append(f"%%Page: {n} {n}")
+        out.append(f"{MARGIN} {top} moveto ({ps_escape(header)}) show")
+        out.append(f"{PAGE_WIDTH - MARGIN - 60} {top} moveto ({n} / {len(pages)}) show")
+        y = top - LINE_HEIGHT * 2
+        for line in lines:
+            out.append(f"{MARGIN} {y:.1f} moveto ({ps_escape(line)}) show")
+            y -= LINE_HEIGHT
+        out.append("showpage")
+    out.append("%%EOF")
+    return "\n".join(out) + "\n"
+
+class CommandPrint:
+    """
+    共有ライブラリのファイルを1つの印刷用ノートブック（PostScript、ps2pdfがあればPDF）にまとめる。
+    使い方: print [--files a.py,b.rs] [--output team/notebook/notebook.pdf]
+    """
+    def __init__(self, options=None):
+        self.options = options or {}
+        self.upm = UnifiedPathManager()
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def root(self):
+        return str(self.upm.project_path.root)
+
+    def collect_files(self, lib_dir):
+        selected = self.options.get("files")
+        if selected and selected is not True:
+            paths = [p.strip() for p in str(selected).split(",") if p.strip()]
+            return [p if os.path.isabs(p) or os.path.exists(p) else os.path.join(lib_dir, p) for p in paths]
+        files = []
+        for root, dirs, names in os.walk(lib_dir):
+            dirs[:] = sorted(d for d in dirs if not d.startswith("."))
+            files.extend(os.path.join(root, n) for n in sorted(names) if n.endswith(LIB_EXTENSIONS))
+        return files
+
+    def to_pdf(self, ps_path, pdf_path):
+        if shutil.which("ps2pdf") is None:
+            return False
+        return subprocess.run(["ps2pdf", ps_path, pdf_path]).returncode == 0
+
+    async def print_notebook(self):
+        team = self.get_config().get_team()
+        lib_dir = os.path.join(self.root(), team.get("lib", os.path.relpath(str(self.upm.team_dir("lib")), self.root())))
+        output = self.options.get("output") or team.get("notebook") or os.path.relpath(str(self.upm.team_dir("notebook", "notebook.pdf")), self.root())
+        output = output if os.path.isabs(output) else os.path.join(self.root(), output)
+        files = self.collect_files(lib_dir)
+        if not files:
+            raise ValueError(f"ノートブックに含めるファイルがありません: {lib_dir}")
+        contents = []
+        for path in files:
+            with open(path, "r", encoding="utf-8", errors="replace") as f:
+                contents.append((os.path.relpath(path, lib_dir), f.read()))
+        base, ext = os.path.splitext(output)
+        ps_path = base + ".ps"
+        os.makedirs(os.path.dirname(ps_path), exist_ok=True)
+        title = f"notebook {datetime.now().strftime('%Y-%m-%d')}"
+        with open(ps_path, "w", encoding="ascii") as f:
+            f.write(render_postscript(contents, title))
+        result = ps_path
+        if ext.lower() == ".pdf":
+            if self.to_pdf(ps_path, output):
+                result = output
+            else:
+                print("[警告] ps2pdfが見つからないためPostScriptのみ出力しました")
+        print(f"[print] {len(files)}ファイル / {len(paginate(contents))}ページ: {result}")
+        return result
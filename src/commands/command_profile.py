@@ -0,0 +1,204 @@
+import os
+import re
+import shlex
+from .command_test import CommandTest
+from .run_artifacts import RunArtifacts
+from src.environment.test_language_handler import get_handler
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# メモリプロファイラ定義
+# command: 実行コマンドの前に付けるラッパー（{profile}は出力ファイルのパス）
+# suffix: 成果物ファイルの拡張子
+# report: ピーク値を得るために実行後に走らせるコマンド（任意）
+MEMORY_PROFILERS = {
+    "massif": {
+        "command": "valgrind --tool=massif --massif-out-file={profile}",
+        "suffix": "massif.out",
+    },
+    "heaptrack": {
+        "command": "heaptrack -o {profile}",
+        "suffix": "heaptrack",
+        "report": "heaptrack_print {profile}.zst",
+    },
+}
+DEFAULT_MEMORY_PROFILER = {
+    "python": "massif",
+    "pypy": "massif",
+    "rust": "heaptrack",
+}
+
+# CPUプロファイラ定義（{svg}にフレームグラフ、{data}に中間データを出力する）
+CPU_PROFILERS = {
+    "flamegraph": {
+        "command": "flamegraph -o {svg} --",
+    },
+    "py-spy": {
+        "command": "py-spy record -o {svg} --",
+    },
+    "perf": {
+        "command": "perf record -F 999 -g -o {data} --",
+        "report": "sh -c 'perf script -i {data} | stackcollapse-perf.pl | flamegraph.pl > {svg}'",
+    },
+}
+DEFAULT_CPU_PROFILER = {
+    "python": "py-spy",
+    "pypy": "perf",
+    "rust": "flamegraph",
+}
+
+def parse_massif_peak(text):
+    """massifの出力から、スナップショット中の最大ヒープ使用量（バイト）を求める"""
+    peak = None
+    heap = extra = 0
+    for line in text.splitlines():
+        m = re.match(r"mem_heap_B=(\d+)", line)
+        if m:
+            heap = int(m.group(1))
+            continue
+        m = re.match(r"mem_heap_extra_B=(\d+)", line)
+        if m:
+            extra = int(m.group(1))
+            total = heap + extra
+            peak = total if peak is None else max(peak, total)
+    return peak
+
+def parse_heaptrack_peak(text):
+    """heaptrack_printの出力から "peak heap memory consumption: 12.34M" を読み取る"""
+    m = re.search(r"peak heap memory consumption:\s*([\d.]+)\s*([KMGT]?)", text)
+    if not m:
+        return None
+    units = {"": 1, "K": 1000, "M": 1000 ** 2, "G": 1000 ** 3, "T": 1000 ** 4}
+    return int(float(m.group(1)) * units[m.group(2)])
+
+def format_bytes(n):
+    for unit in ["B", "KiB", "MiB", "GiB"]:
+        if n < 1024 or unit == "GiB":
+            return f"{n:.1f} {unit}" if unit != "B" else f"{n} B"
+        n /= 1024
+
+class CommandProfile:
+    """
+    指定したテストケースをプロファイラ配下で1回だけ実行し、結果を.cph/runs/{run_id}/に保存する。
+    使い方: profile --memory --case 2 / profile --cpu --case 2
+    """
+    def __init__(self, file_manager, test_env, options=None):
+        self.file_manager = file_manager
+        self.env = test_env
+        self.options = options or {}
+        self.command_test = CommandTest(file_manager, test_env)
+        self.upm = UnifiedPathManager()
+
+    @staticmethod
+    def select_case(in_files, case):
+        """caseは1始まりの番号、またはケース名（sample-2 / sample-2.in）"""
+        if case is None:
+            raise ValueError("--case でケースを指定してください")
+        case = str(case)
+        if case.isdigit():
+            index = int(case)
+            if not 1 <= index <= len(in_files):
+                raise ValueError(f"ケース番号が範囲外です: {case}（1〜{len(in_files)}）")
+            return in_files[index - 1]
+        for f in in_files:
+            name = os.path.basename(str(f))
+            if name == case or os.path.splitext(name)[0] == case:
+                return f
+        raise ValueError(f"ケースが見つかりません: {case}")
+
+    def get_profiler(self, mode, language_name):
+        """config.jsonのprofile.{mode}.{language}（名前またはコマンド定義）から使うプロファイラを決める"""
+        profilers, defaults, fallback = {
+            "memory": (MEMORY_PROFILERS, DEFAULT_MEMORY_PROFILER, "massif"),
+            "cpu": (CPU_PROFILERS, DEFAULT_CPU_PROFILER, "perf"),
+        }[mode]
+        config = self.command_test.get_config().get_profile().get(mode, {})
+        spec = config.get(language_name, defaults.get(language_name, fallback))
+        if isinstance(spec, dict):
+            return spec
+        if spec not in profilers:
+            raise ValueError(f"未対応のプロファイラです: {spec}")
+        return profilers[spec]
+
+    def prepare(self, contest_name, problem_name, language_name):
+        """ソースとテストケースを用意し、コンテナを1つ起動してビルドする"""
+        file_operator = self.file_manager.file_operator if self.file_manager and hasattr(self.file_manager, 'file_operator') else None
+        temp_source_path, temp_test_dir = self.command_test.prepare_test_environment(contest_name, problem_name, language_name)
+        in_files, _ = self.command_test.collect_test_cases(temp_test_dir, file_operator)
+        in_file = self.select_case(in_files, self.options.get("case"))
+        self.env.adjust_containers(self.command_test.build_requirements(language_name, 1), contest_name, problem_name, language_name)
+        containers = self.command_test.get_test_containers_from_info()
+        container = containers[0] if containers else f"cph_profile_{language_name}"
+        handler = get_handler(language_name)
+        cont_source_path = self.env.to_container_path(os.path.abspath(temp_source_path))
+        # Docker実行ならContainerClient、ローカル実行ならExecutionManagerでビルドする
+        build_client = getattr(self.env, "ctl", None) or getattr(self.env, "manager", None)
+        ok, _, stderr = handler.build(build_client, container, cont_source_path)
+        if not ok:
+            raise CphError(f"ビルド失敗\n{stderr}", kind="build_failed")
+        return handler, container, cont_source_path, in_file
+
+    def wrap_command(self, template, run_cmd, **values):
+        wrapper = [token.format(**{k: str(v) for k, v in values.items()}) for token in shlex.split(template)]
+        return wrapper + run_cmd
+
+    def run_profiler(self, spec, handler, container, cont_source_path, in_file, paths):
+        """
+        プロファイラでラップして実行する。pathsは{プレースホルダ名: ホスト側パス}で、
+        コンテナ内パスに変換してからコマンドに埋め込む。
+        """
+        values = {k: self.env.to_container_path(str(v)) for k, v in paths.items()}
+        cmd = self.wrap_command(spec["command"], handler.run_command(cont_source_path), **values)
+        cont_in_file = self.env.to_container_path(os.path.abspath(str(in_file)))
+        ok, stdout, stderr = self.env.exec_command(container, cmd, cont_in_file)
+        report = ""
+        if spec.get("report"):
+            report_cmd = self.wrap_command(spec["report"], [], **values)
+            _, report, _ = self.env.exec_command(container, report_cmd)
+        return ok, stdout, stderr, report
+
+    async def profile(self, contest_name, problem_name, language_name):
+        if self.options.get("memory"):
+            return await self.profile_memory(contest_name, problem_name, language_name)
+        if self.options.get("cpu"):
+            return await self.profile_cpu(contest_name, problem_name, language_name)
+        raise ValueError("--memory または --cpu を指定してください")
+
+    async def profile_cpu(self, contest_name, problem_name, language_name):
+        spec = self.get_profiler("cpu", language_name)
+        handler, container, cont_source_path, in_file = self.prepare(contest_name, problem_name, language_name)
+        artifacts = RunArtifacts()
+        artifacts.ensure_dir()
+        svg_path = artifacts.case_path(in_file, "flamegraph.svg")
+        paths = {"svg": svg_path, "data": artifacts.case_path(in_file, "perf.data")}
+        ok, stdout, stderr, _ = self.run_profiler(spec, handler, container, cont_source_path, in_file, paths)
+        if not ok:
+            print(f"[警告] プロファイル実行が異常終了しました\n{stderr.strip() if stderr else ''}")
+        print(f"[profile] ケース: {os.path.basename(str(in_file))}")
+        if svg_path.exists():
+            print(f"[profile] フレームグラフ: {svg_path}")
+        else:
+            print("[警告] フレームグラフが生成されませんでした（perf / flamegraph / py-spy がインストールされているか確認してください）")
+        return {"ok": ok, "svg_path": svg_path if svg_path.exists() else None}
+
+    async def profile_memory(self, contest_name, problem_name, language_name):
+        spec = self.get_profiler("memory", language_name)
+        handler, container, cont_source_path, in_file = self.prepare(contest_name, problem_name, language_name)
+        artifacts = RunArtifacts()
+        artifacts.ensure_dir()
+        profile_path = artifacts.case_path(in_file, spec["suffix"])
+        ok, stdout, stderr, report = self.run_profiler(spec, handler, container, cont_source_path, in_file, {"profile": profile_path})
+        if not ok:
+            print(f"[警告] プロファイル実行が異常終了しました\n{stderr.strip() if stderr else ''}")
+        peak = None
+        if profile_path.exists():
+            peak = parse_massif_peak(profile_path.read_text(encoding="utf-8", errors="replace"))
+        if peak is None and report:
+            peak = parse_heaptrack_peak(report)
+        print(f"[profile] ケース: {os.path.basename(str(in_file))}")
+        print(f"[profile] プロファイル: {profile_path}")
+        if peak is not None:
+            print(f"[profile] ピークヒープ使用量: {format_bytes(peak)}")
+        else:
+            print("[警告] ピークヒープ使用量を取得できませんでした")
+        return {"ok": ok, "profile_path": profile_path, "peak_bytes": peak}
@@ -0,0 +1,159 @@
+import hashlib
+import time
+from html.parser import HTMLParser
+from .archive_index import ArchiveIndex
+from .command_switch import task_url
+from .constraints import parse_constraints
+from .command_test import parse_duration
+from .problem_lock import active_problem
+from .result_cache import ResultCache
+from .sample_rebind import ACCEPT, ADDED, apply_rebind, describe, lint_fresh_samples, plan_rebind, sample_checksums
+from .sample_scraper import SampleScraper
+from src import temp_space
+from src.config_json_manager import ConfigJsonManager
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 問題文として比べる要素のid（AtCoder）。無ければページ全体の本文
+STATEMENT_ID = "task-statement"
+SKIP_TEXT_TAGS = {"script", "style"}
+VOID_TAGS = {"br", "hr", "img", "input", "meta", "link", "area", "base", "col", "embed", "source", "track", "wbr"}
+
+class StatementText(HTMLParser):
+    """問題文の文字だけを取り出す（scriptや、ページごとに変わるトークンなどを比べないように）"""
+    def __init__(self):
+        super().__init__(convert_charrefs=True)
+        self.stack = []
+        self.parts = []
+        self.statement_parts = []
+        self.statement_depth = None
+
+    def handle_starttag(self, tag, attrs):
+        if tag in VOID_TAGS:
+            return
+        self.stack.append(tag)
+        if self.statement_depth is None and dict(attrs).get("id") == STATEMENT_ID:
+            self.statement_depth = len(self.stack)
+
+    def handle_endtag(self, tag):
+        if tag in VOID_TAGS or tag not in self.stack:
+            return
+        while self.stack and self.stack.pop() != tag:
+            pass
+        if self.statement_depth is not None and len(self.stack) < self.statement_depth:
+            self.statement_depth = -1
+
+    def handle_data(self, data):
+        if any(t in SKIP_TEXT_TAGS for t in self.stack):
+            return
+        self.parts.append(data)
+        if self.statement_depth is not None and self.statement_depth > 0:
+            self.statement_parts.append(data)
+
+def statement_text(html):
+    """問題文（制約・入出力の説明・サンプル）の文字。空白は1つにまとめる"""
+    parser = StatementText()
+    parser.feed(html)
+    return " ".join("".join(parser.statement_parts or parser.parts).split())
+
+def statement_digest(html):
+    """問題文の文字のハッシュ。空白の違いは無視する"""
+    return hashlib.sha256(statement_text(html).encode("utf-8")).hexdigest()
+
+class CommandRefresh:
+    """
+    コンテスト中に問題ページを取り直し、増えたサンプルを新しいケースとして取り込む。
+    手元のケース（取得済みのサンプル・自分で足したもの）は書き換えないので、過去のtestの結果の記録はそのまま使える。
+    番号が変わった・内容が変わった・無くなったサンプルは知らせるだけにする（取り込むには case rebind）。
+    問題文（制約の訂正など）が前回から変わっていれば、それも知らせる。
+    使い方: refresh [--poll 60s]（--pollでCtrl+Cまで一定の間隔で確かめ続ける）
+    """
+    def __init__(self, options=None, upm=None, scraper=None, sleep=time.sleep):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.scraper = scraper or SampleScraper(self.upm)
+        self.sleep = sleep
+        # 知らせ済みの違い（--pollで同じことを繰り返し出さないように）
+        self.reported = set()
+
+    def poll_interval(self):
+        value = self.options.get("poll")
+        if value is None or value is True:
+            return None
+        try:
+            return parse_duration(value)
+        except ValueError:
+            raise ValueError(f"--poll には正の時間を指定してください: {value}（例: 60s / 2m）")
+
+    def refresh_once(self, contest_name, problem_name):
+        """1回確かめ、(取り込んだサンプルの名前の一覧, 知らせることがあったか)を返す。前に知らせた違いは繰り返さない"""
+        url = task_url(contest_name, problem_name)
+        test_dir = self.upm.contest_current("test")
+        index = ArchiveIndex(upm=self.upm)
+        entry = index.get(contest_name, problem_name) or {}
+        html = self.scraper.fetch(url)
+        digest = statement_digest(html)
+        statement_changed = entry.get("statement_digest") not in (None, digest)
+        with temp_space.current().scoped("refresh-") as fresh_dir:
+            self.scraper.download(url, fresh_dir, html=html)
+            lint_fresh_samples(fresh_dir, ConfigJsonManager(str(self.upm.config_json())).get_test())
+            fresh = sample_checksums(fresh_dir)
+            stored = sample_checksums(test_dir)
+            changes = plan_rebind(stored, fresh, entry.get("sample_checksums"))
+            # 増えたもののうち、手元に同じ名前のケースが無いものだけを取り込む（番号の付け替えで空いた名前は上書きしない）
+            added = [c for c in changes if c.kind == ADDED and c.to not in stored]
+            others = [c for c in changes if c not in added and (c.kind, c.name, c.to) not in self.reported]
+            for change in added:
+                print(f"[refresh] {describe(change, test_dir, fresh_dir)[0]}")
+            for change in others:
+                print(f"[refresh] {describe(change, test_dir, fresh_dir)[0]}（手元のケースは変えていません）")
+                self.reported.add((change.kind, change.name, change.to))
+            apply_rebind(test_dir, fresh_dir, [(c, ACCEPT) for c in added])
+        if others:
+            print("[refresh] 変わったサンプルを取り込むには case rebind を実行してください")
+        if statement_changed:
+            print(f"[refresh] 問題文が前回から変わっています（制約の訂正などを確認してください）: {url}")
+        names = [c.to for c in added]
+
+        constraints = parse_constraints(html)
+
+        def record(e):
+            e["statement_digest"] = digest
+            # 制約の訂正もtestの冒頭の表示に反映する
+            e["constraints"] = constraints
+            # 次に case rebind で比べるとき、取り込んだものは取得したままのサンプルとして扱う
+            e["sample_checksums"] = {**(e.get("sample_checksums") or {}), **{n: fresh[n] for n in names}}
+        index.update(contest_name, problem_name, record)
+        if names:
+            # ケースが増えたので、前回のフル実行の結果（提出前チェック）は使わない
+            ResultCache(self.upm.cph_dir("results.json")).drop_problem(contest_name, problem_name)
+            print(f"[refresh] サンプルを{len(names)}件取り込みました: {', '.join(names)}（testで確かめてください）")
+        return names, bool(others) or statement_changed
+
+    async def refresh(self, rounds=None):
+        contest_name, problem_name = active_problem(self.upm)
+        if contest_name is None or problem_name is None:
+            raise ValueError("作業中の問題がありません。open で問題を開いてから実行してください")
+        interval = self.poll_interval()
+        if interval is None:
+            added, changed = self.refresh_once(contest_name, problem_name)
+            if not added and not changed:
+                print("[refresh] 問題ページに新しいサンプル・変更はありません")
+            return added
+        print(f"[refresh] {interval:g}秒ごとに問題ページを確かめます（Ctrl+Cで終了）")
+        added, done = [], 0
+        while rounds is None or done < rounds:
+            if done:
+                self.sleep(interval)
+            added += self.poll_once(contest_name, problem_name)
+            done += 1
+        return added
+
+    def poll_once(self, contest_name, problem_name):
+        """--poll（test --watch --pollも）の1回分。通信の失敗などでは止めず、次の回に確かめ直す"""
+        try:
+            names, _ = self.refresh_once(contest_name, problem_name)
+        except CphError as e:
+            print(f"[refresh] 問題ページを確かめられませんでした（次の回にもう一度確かめます）: {e}")
+            return []
+        return names
@@ -0,0 +1,76 @@
+import os
+import sys
+from .case_storage import expected_file, find_case_file, read_case
+from .command_test import CommandTest
+from .remote_runner import create_remote_runner
+from .submission_bundle import SubmissionBundler
+from .test_result_formatter import judge_verdict
+from .toolchain import DEFAULT_SITE
+from src.config_json_manager import ConfigJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+class CommandRemoteRun:
+    """
+    解答と入力をサイトのジャッジ環境（AtCoderのコードテスト）で実行し、出力・時間・メモリを表示する（提出はしない）。
+    手元とジャッジで結果が違うときに、ジャッジと同じ環境での動きを確かめるためのもの。
+    送るソースはsubmitと同じ加工（config.jsonのsubmit）をしたもの。入力がテストケース（NAME.in）なら、testと同じ比較方法で期待出力とも比べる。
+    使い方: remote-run --input FILE（- で標準入力） [--site atcoder]
+    """
+    def __init__(self, options=None, upm=None, runner=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.runner = runner
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def get_runner(self):
+        return self.runner or create_remote_runner(self.options.get("site") or DEFAULT_SITE)
+
+    def read_input(self):
+        source = self.options.get("input")
+        if source is None or source is True:
+            raise ValueError("remote-run には --input FILE（- で標準入力）で入力を指定してください")
+        if source == "-":
+            return sys.stdin.read(), None
+        path = find_case_file(source) or source
+        if not os.path.exists(str(path)):
+            raise FileNotFoundError(f"入力ファイルが見つかりません: {source}")
+        return read_case(path), path
+
+    def read_source(self, contest_name, problem_name, language_name):
+        path = self.upm.contest_current(language_name, self.upm.source_file(language_name))
+        if not path.exists():
+            raise FileNotFoundError(f"解答ファイルがありません: {path}")
+        source = path.read_text(encoding="utf-8")
+        bundler = SubmissionBundler(self.get_config().get_submit())
+        if bundler.is_active(language_name):
+            source = bundler.bundle(source, language_name, contest=contest_name, problem=problem_name)
+        return source
+
+    def read_expected(self, input_path):
+        out_file = find_case_file(expected_file(input_path)) if input_path else None
+        return read_case(out_file) if out_file else None
+
+    async def remote_run(self, contest_name, problem_name, language_name):
+        language_id = self.get_config().get_language_id().get(language_name)
+        if not language_id:
+            raise ValueError(f"config.jsonのlanguage_idに{language_name}がありません")
+        input_data, input_path = self.read_input()
+        source = self.read_source(contest_name, problem_name, language_name)
+        runner = self.get_runner()
+        print(f"[remote-run] {runner.name} のコードテストで実行します（{contest_name} {problem_name}、{language_name}）")
+        result = runner.run(contest_name, language_id, source, input_data)
+        time_ms, memory_kb = result.get("time_ms"), result.get("memory_kb")
+        print(f"[remote-run] 終了コード {result['exit_code']} / {time_ms if time_ms is not None else '?'} ms / "
+              f"{memory_kb if memory_kb is not None else '?'} KB")
+        print(result["stdout"], end="" if result["stdout"].endswith("\n") or not result["stdout"] else "\n")
+        if result["stderr"].strip():
+            print(f"[remote-run] 標準エラー出力:\n{result['stderr'].rstrip()}")
+        expected = self.read_expected(input_path)
+        if expected is not None:
+            # testと同じ比較方法（--compare / config.jsonのcompare / tag --compare）で判定する
+            judged = {"result": (result["exit_code"] or 0, result["stdout"], result["stderr"]), "expected": expected, "in_file": input_path}
+            verdict = result["verdict"] = judge_verdict(judged, CommandTest(None, None, options=self.options).get_comparator())
+            print(f"[remote-run] 期待出力と比べた結果: {verdict}")
+        return result
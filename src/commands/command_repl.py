@@ -0,0 +1,100 @@
+import os
+import shlex
+from .command_test import CommandTest
+from src.error_hints import CphError
+from src.file_operator import atomic_write
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 言語ごとの対話環境（pythonは起動用スクリプトを読み込んでから対話に入る）
+REPL_COMMANDS = {
+    "python": ["python3", "-i", "{startup}"],
+    "pypy": ["pypy3", "-i", "{startup}"],
+    "rust": ["evcxr"],
+}
+# コマンドが見つからないときのシェルの終了コード
+COMMAND_NOT_FOUND = 127
+# 起動時に読み込むモジュール（コンテスト中によく使うもの）
+PRELUDE = """import sys, math, itertools, functools, bisect, heapq
+from math import *
+from itertools import *
+from functools import *
+from collections import *
+"""
+
+def startup_script(lib_dir=None, template=None):
+    """
+    pythonの対話環境の起動用スクリプト。チームのライブラリをimportできるようにし、
+    テンプレートの定義（main()などは__main__として実行しない）を読み込む。
+    """
+    lines = [PRELUDE]
+    if lib_dir:
+        lines.append(f"sys.path.insert(0, {str(lib_dir)!r})\n")
+    if template:
+        lines.append(
+            "import runpy as _runpy\n"
+            "try:\n"
+            f"    globals().update({{k: v for k, v in _runpy.run_path({str(template)!r}, run_name='cph_repl').items() if not k.startswith('__')}})\n"
+            "except Exception as _e:\n"
+            "    print('[repl] テンプレートを読み込めませんでした: ' + repr(_e))\n"
+        )
+    lines.append("print('[repl] math / itertools / collections などを読み込み済みです（終了はCtrl-D）')\n")
+    return "".join(lines)
+
+class CommandRepl:
+    """
+    言語のコンテナ（localなら手元）で対話環境を開く。ワークスペースはマウントされたまま使える。
+    使い方: repl [language]
+    pythonはテンプレートとチームのライブラリ（config.jsonのteam.lib）を読み込んだ状態で起動する。
+    rustはevcxrを起動する（イメージにevcxrが必要）。コマンドはconfig.jsonのrepl.{language}で変えられる。
+    """
+    def __init__(self, file_manager, test_env, options=None, upm=None):
+        self.file_manager = file_manager
+        self.env = test_env
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.command_test = CommandTest(file_manager, test_env)
+
+    def root(self):
+        return str(self.upm.project_path.root)
+
+    def lib_dir(self):
+        lib = self.command_test.get_config().get_team().get("lib") or os.path.relpath(str(self.upm.team_dir("lib")), self.root())
+        path = os.path.join(self.root(), lib)
+        return path if os.path.isdir(path) else None
+
+    def template(self, language_name):
+        path = self.upm.contest_template(language_name, self.upm.source_file(language_name))
+        return str(path) if path.exists() else None
+
+    def to_container_path(self, path):
+        return str(self.env.to_container_path(os.path.abspath(path))) if path else None
+
+    def write_startup(self, language_name):
+        path = os.path.join(self.root(), ".temp", "repl", "startup.py")
+        script = startup_script(self.to_container_path(self.lib_dir()), self.to_container_path(self.template(language_name)))
+        atomic_write(path, script)
+        return path
+
+    def build_command(self, language_name):
+        configured = self.command_test.get_config().get_repl().get(language_name)
+        template = shlex.split(configured) if configured else REPL_COMMANDS.get(language_name)
+        if template is None:
+            raise ValueError(f"{language_name} の対話環境は未対応です（config.jsonのrepl.{language_name}で指定できます）")
+        startup = self.to_container_path(self.write_startup(language_name)) if any("{startup}" in t for t in template) else None
+        return [t.format(startup=startup) for t in template]
+
+    def prepare_container(self, contest_name, problem_name, language_name):
+        self.env.adjust_containers(self.command_test.build_requirements(language_name, 1), contest_name, problem_name, language_name)
+        containers = self.command_test.get_test_containers_from_info()
+        return containers[0] if containers else f"cph_repl_{language_name}"
+
+    async def repl(self, contest_name, problem_name, language_name):
+        cmd = self.build_command(language_name)
+        container = self.prepare_container(contest_name, problem_name, language_name)
+        try:
+            returncode = self.env.attach_command(container, cmd)
+        except FileNotFoundError:
+            returncode = COMMAND_NOT_FOUND
+        if returncode == COMMAND_NOT_FOUND:
+            raise CphError(f"対話環境のコマンドが見つかりません: {cmd[0]}", kind="repl_missing")
+        return returncode
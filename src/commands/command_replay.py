@@ -0,0 +1,144 @@
+import difflib
+import os
+import shutil
+import time
+from . import color_theme
+from .case_check import digest
+from .case_storage import expected_file, find_case_file, read_case
+from .command_run import CommandRun, limit_command
+from .command_test import DEFAULT_TIME_LIMIT, SEED_ENV, CommandTest
+from .problem_lock import active_problem
+from .run_artifacts import NON_FAILURE_VERDICTS, RunArtifacts
+from .test_result_formatter import judge_verdict
+from src import temp_space
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 差分として表示する行数の上限
+MAX_DIFF_LINES = 40
+
+def output_diff(before, after, fromfile, tofile):
+    """unified diffの行（最大MAX_DIFF_LINES行）。同じなら空"""
+    lines = [line.rstrip("\n") for line in difflib.unified_diff(before.splitlines(keepends=True), after.splitlines(keepends=True), fromfile=fromfile, tofile=tofile)]
+    if len(lines) > MAX_DIFF_LINES:
+        lines = lines[:MAX_DIFF_LINES] + [f"...（ほか{len(lines) - MAX_DIFF_LINES}行）"]
+    return lines
+
+class CommandReplay:
+    """
+    過去のtestの実行（.cph/runs/{run_id}）で渡したのと同じ入力・シード・環境変数で、今の解答を1ケースだけ実行し直す。
+    その実行で記録した出力と期待出力のそれぞれと比べ、いつから・どう変わったかを追えるようにする。
+    使い方: replay RUN_ID [--case N]（RUN_IDは先頭の一部でもよい。--caseは番号かケース名、省略時は最初に失敗したケース）
+    """
+    def __init__(self, file_manager, test_env, options=None, args=None, upm=None):
+        self.options = options or {}
+        self.args = list(args or [])
+        self.env = test_env
+        self.runner = CommandRun(file_manager, test_env, options=self.options)
+        # 比較方法・出力の上限・TLは、testと同じく今の設定（--compare / tag / config.json）で決める
+        self.command_test = CommandTest(None, None, options=self.options)
+        self.upm = upm or UnifiedPathManager()
+
+    def find_run(self, run_id):
+        """run_id（先頭の一部でもよい）の実行。latestなら直近のもの"""
+        runs = RunArtifacts.all_runs(self.upm.project_path.root)
+        if not runs:
+            raise ValueError("testの記録がありません。先にtestを実行してください")
+        if run_id == "latest":
+            return runs[0]
+        matched = [r for r in runs if str(r.get("run_id", "")).startswith(run_id)]
+        if len(matched) != 1:
+            found = "見つかりません" if not matched else f"{len(matched)}件に当てはまります"
+            raise ValueError(f"実行 {run_id} は{found}（history export や .cph/runs/ で確認できます）")
+        return matched[0]
+
+    def find_case(self, run):
+        cases = run.get("cases", [])
+        value = self.options.get("case")
+        if value is None or value is True:
+            case = next((c for c in cases if c.get("verdict") not in NON_FAILURE_VERDICTS), cases[0] if cases else None)
+        elif str(value).isdigit():
+            case = cases[int(value) - 1] if 0 < int(value) <= len(cases) else None
+        else:
+            stem = RunArtifacts.case_stem(value)
+            case = next((c for c in cases if RunArtifacts.case_stem(c["name"]) == stem), None)
+        if case is None:
+            raise ValueError(f"実行 {run['run_id']} に --case {value} はありません（{', '.join(c['name'] for c in cases)}）")
+        return case
+
+    def input_file(self, artifacts, case):
+        """その実行で渡した入力。写しが無ければ、内容が同じ（ハッシュが一致する）今のケースを使う"""
+        stored = artifacts.stored_input(case)
+        if stored is not None and stored.exists():
+            return str(stored)
+        current = find_case_file(str(self.upm.contest_current("test", f"{RunArtifacts.case_stem(case['name'])}.in")))
+        if current and case.get("input_digest") and digest(current) == case["input_digest"]:
+            return current
+        raise ValueError(f"{case['name']} の入力が残っていません（記録後に変わったか、入力の写しを残す前の実行です）")
+
+    def read_expected(self, case):
+        in_file = str(self.upm.contest_current("test", f"{RunArtifacts.case_stem(case['name'])}.in"))
+        out_file = find_case_file(expected_file(in_file))
+        return read_case(out_file) if out_file else None
+
+    def solution_env(self, run):
+        """記録した環境変数（古い記録ではシードだけ）"""
+        if run.get("solution_env"):
+            return dict(run["solution_env"])
+        return {SEED_ENV: str(run["seed"])} if run.get("seed") is not None else {}
+
+    def execute(self, run, in_file, time_limit):
+        """今の解答を実行して(終了コード, 標準出力, 標準エラー出力, 秒)を返す"""
+        contest_name, problem_name, language_name = run["contest_name"], run["problem_name"], run["language_name"]
+        self.env.solution_env = self.solution_env(run)
+        self.env.output_limit = self.command_test.get_output_limit()
+        handler, container, cont_source_path = self.runner.prepare(contest_name, problem_name, language_name)
+        # 圧縮した写しもそのまま渡せるよう、コンテナから見える一時ディレクトリに置く
+        local_in = str(temp_space.for_env(self.env).file("replay", os.path.basename(in_file)))
+        shutil.copyfile(in_file, local_in)
+        cmd = limit_command(handler.run_command(cont_source_path), time_limit * 2)
+        start = time.perf_counter()
+        ok, stdout, stderr = self.env.exec_command(container, cmd, self.env.to_container_path(local_in))
+        return (0 if ok else 1), stdout or "", stderr or "", time.perf_counter() - start
+
+    async def replay(self):
+        if not self.args:
+            raise ValueError("使い方: replay RUN_ID [--case N]（RUN_IDはlatestで直近の実行）")
+        run = self.find_run(self.args[0])
+        contest_name, problem_name = active_problem(self.upm)
+        if (contest_name, problem_name) != (run.get("contest_name"), run.get("problem_name")):
+            raise ValueError(f"実行 {run['run_id']} は {run.get('contest_name')} {run.get('problem_name')} のものです。"
+                             f"作業中の問題（{contest_name} {problem_name}）の実行を指定してください")
+        artifacts = RunArtifacts(run["run_id"], self.upm.project_path.root)
+        case = self.find_case(run)
+        in_file = self.input_file(artifacts, case)
+        time_limit = self.command_test.get_config().get_time_limit() or DEFAULT_TIME_LIMIT
+        code, stdout, stderr, elapsed = self.execute(run, in_file, time_limit)
+        expected = self.read_expected(case)
+        if elapsed > time_limit:
+            verdict = "TLE"
+        elif expected is None:
+            verdict = "RE" if code != 0 else None
+        else:
+            judged = {"result": (code, stdout, stderr), "expected": expected, "in_file": in_file,
+                      "output_limit_exceeded": self.env.output_limit.exceeded(stdout) and self.env.output_limit.limit}
+            verdict = judge_verdict(judged, self.command_test.get_comparator())
+        before = case.get("time")
+        print(f"[replay] {run['run_id']} {case['name']}: 記録 {case.get('verdict') or '-'}"
+              f"{f' {before:.3f}秒' if before is not None else ''} → 今回 {verdict or '-'} {elapsed:.3f}秒")
+        if self.env.solution_env:
+            print(f"[replay] 環境変数: {' '.join(f'{k}={v}' for k, v in self.env.solution_env.items())}")
+        palette = color_theme.current()
+        stored_path = artifacts.case_path(case["name"], "stdout")
+        stored = stored_path.read_text(encoding="utf-8") if stored_path.exists() else None
+        for label, other in (("記録した出力", stored), ("期待出力", expected)):
+            if other is None:
+                print(f"[replay] {label}がありません")
+                continue
+            diff = output_diff(other, stdout, label, "今回の出力")
+            print(f"[replay] {label}と同じです" if not diff else f"[replay] {label}との差分:")
+            for line in diff:
+                print(palette.diff_line(line))
+        if stderr.strip():
+            print(f"[replay] 標準エラー出力: {stderr.strip().splitlines()[-1]}")
+        return {"run_id": run["run_id"], "case": case["name"], "verdict": verdict, "time": elapsed,
+                "same_as_recorded": stored == stdout, "stdout": stdout}
@@ -0,0 +1,187 @@
+import io
+import json
+import os
+import platform
+import re
+import subprocess
+import sys
+import tarfile
+from datetime import datetime
+from pathlib import Path
+from .run_artifacts import RunArtifacts
+from .toolchain import DEFAULT_SITE, get_pins
+from src.command_log import MASK, SECRET_NAME
+from src.config_json_manager import ConfigJsonManager
+from src.failure_record import load_last_failure
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.version import CPH_VERSION
+
+# 値を伏せる設定のキー（command_logの変数名に加えて、クッキーや認証ヘッダ・WebhookのURL）
+SECRET_KEY = re.compile(SECRET_NAME.pattern + r"|COOKIE|AUTH|WEBHOOK", re.IGNORECASE)
+# バージョンを添えるツール
+TOOL_VERSION_COMMANDS = {
+    "docker": ["docker", "--version"],
+    "oj": ["oj", "--version"],
+    "git": ["git", "--version"],
+}
+VERSION_TIMEOUT = 10
+# --workspaceで同梱しないディレクトリと、1ファイルの大きさの上限
+WORKSPACE_SKIP_DIRS = {".temp", "target", "__pycache__", ".git"}
+MAX_WORKSPACE_FILE_BYTES = 1024 * 1024
+REPORT_PREFIX = "cph-bug-report"
+
+def sanitize(value, home=None):
+    """設定の中の秘密の値を伏せ、ホームディレクトリのパスを~にする（dict / listの中まで）"""
+    home = home if home is not None else str(Path.home())
+    if isinstance(value, dict):
+        return {k: MASK if SECRET_KEY.search(str(k)) and v else sanitize(v, home) for k, v in value.items()}
+    if isinstance(value, list):
+        return [sanitize(v, home) for v in value]
+    if isinstance(value, str) and home and home != os.sep:
+        return value.replace(home, "~")
+    return value
+
+def tool_version(cmd):
+    try:
+        result = subprocess.run(cmd, capture_output=True, text=True, timeout=VERSION_TIMEOUT)
+    except (OSError, subprocess.TimeoutExpired):
+        return None
+    output = (result.stdout or result.stderr).strip()
+    return output.splitlines()[0] if result.returncode == 0 and output else None
+
+class CommandReportBug:
+    """
+    不具合の報告に添付する、再現用の情報を1つのtar.gzにまとめる。
+    - summary.md: 環境・直近の失敗・同梱したファイルの一覧
+    - config.json: 秘密の値（パスワード・トークン・クッキーなど）を伏せた設定
+    - versions.json: cph・Python・OS・docker / oj / gitと、固定したジャッジのツールチェイン
+    - last_failure.json: 直近に失敗したコマンドの引数・エラー・トレースバック・実行したdocker / ojコマンド
+    - run/: 直近のtestの記録（run.json・commands.json）
+    - workspace/: --workspaceのとき、作業中の問題（contest_current、ビルド成果物を除く）
+    sites.yaml（ログインのクッキー）は含めない。
+    使い方: report-bug [--workspace] [--output PATH]
+    """
+    def __init__(self, options=None, upm=None, version_of=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.version_of = version_of or tool_version
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def read_config(self):
+        path = self.upm.config_json()
+        if not path.exists():
+            return {}
+        try:
+            return json.loads(path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            return {"error": "config.jsonを読めませんでした"}
+
+    def versions(self):
+        try:
+            pins = get_pins(self.get_config(), self.options.get("site", DEFAULT_SITE))
+        except Exception:
+            pins = {}
+        return {
+            "cph": CPH_VERSION,
+            "python": sys.version.split()[0],
+            "platform": platform.platform(),
+            "tools": {name: self.version_of(cmd) for name, cmd in TOOL_VERSION_COMMANDS.items()},
+            "toolchain": pins,
+        }
+
+    def latest_run_files(self):
+        """直近のtestの記録（問題を問わず最新のもの）"""
+        runs = RunArtifacts.all_runs(self.upm.project_path.root)
+        if not runs:
+            return {}
+        run_dir = self.upm.runs_dir(runs[0]["run_id"])
+        files = {}
+        for name in ("run.json", "commands.json"):
+            path = run_dir / name
+            if path.exists():
+                files[f"run/{name}"] = path.read_text(encoding="utf-8")
+        return files
+
+    def workspace_files(self):
+        root = self.upm.contest_current()
+        files, skipped = {}, []
+        for current, dirs, names in os.walk(str(root)):
+            dirs[:] = sorted(d for d in dirs if d not in WORKSPACE_SKIP_DIRS)
+            for name in sorted(names):
+                path = Path(current) / name
+                rel = path.relative_to(root).as_posix()
+                if path.stat().st_size > MAX_WORKSPACE_FILE_BYTES:
+                    skipped.append(rel)
+                    continue
+                files[f"workspace/{rel}"] = path.read_bytes()
+        return files, skipped
+
+    def summary(self, versions, failure, names, skipped):
+        lines = [
+            "# cph 不具合報告",
+            "",
+            f"- 作成日時: {datetime.now().isoformat(timespec='seconds')}",
+            f"- cph: {versions['cph']}",
+            f"- Python: {versions['python']}",
+            f"- OS: {versions['platform']}",
+        ]
+        lines += [f"- {name}: {v or '未検出'}" for name, v in versions["tools"].items()]
+        lines += ["", "## 直近の失敗", ""]
+        if failure:
+            lines += [f"- コマンド: `{' '.join(failure.get('argv') or [])}`",
+                      f"- エラー: {failure.get('error_type')}: {failure.get('error')}",
+                      f"- 日時: {failure.get('created_at')}"]
+        else:
+            lines.append("記録はありません")
+        lines += ["", "## 同梱したファイル", ""] + [f"- {name}" for name in names]
+        if skipped:
+            lines += ["", f"大きいため含めなかったファイル（{MAX_WORKSPACE_FILE_BYTES // 1024}KB超）: {', '.join(skipped)}"]
+        lines += ["", "config.jsonの秘密の値は伏せ、sites.yaml（ログインのクッキー）は含めていません。"]
+        return "\n".join(lines) + "\n"
+
+    def collect(self):
+        """アーカイブに入れるファイル名 → 内容"""
+        home = str(Path.home())
+        versions = self.versions()
+        failure = load_last_failure(self.upm)
+        files = {
+            "config.json": json.dumps(sanitize(self.read_config(), home), ensure_ascii=False, indent=2),
+            "versions.json": json.dumps(versions, ensure_ascii=False, indent=2),
+        }
+        if failure:
+            files["last_failure.json"] = json.dumps(sanitize(failure, home), ensure_ascii=False, indent=2)
+        files.update(self.latest_run_files())
+        skipped = []
+        if self.options.get("workspace"):
+            workspace, skipped = self.workspace_files()
+            files.update(workspace)
+        files["summary.md"] = self.summary(versions, failure, ["summary.md"] + list(files), skipped)
+        return files
+
+    def output_path(self):
+        output = self.options.get("output")
+        if output and output is not True:
+            return Path(str(output))
+        return Path(f"{REPORT_PREFIX}-{datetime.now().strftime('%Y%m%d-%H%M%S')}.tar.gz")
+
+    def write(self, files, path):
+        path.parent.mkdir(parents=True, exist_ok=True)
+        with tarfile.open(str(path), "w:gz") as tar:
+            for name, content in files.items():
+                data = content if isinstance(content, bytes) else content.encode("utf-8")
+                info = tarfile.TarInfo(f"{REPORT_PREFIX}/{name}")
+                info.size = len(data)
+                info.mtime = int(datetime.now().timestamp())
+                tar.addfile(info, io.BytesIO(data))
+        return path
+
+    async def report_bug(self):
+        files = self.collect()
+        path = self.write(files, self.output_path())
+        print(f"[report-bug] {len(files)}ファイルを {path} にまとめました（秘密の値は伏せ、sites.yamlは含めていません）")
+        if not self.options.get("workspace"):
+            print("[report-bug] 作業中の問題も添えるときは --workspace を付けてください")
+        print("[report-bug] 添付する前に summary.md と中身を確認してください")
+        return path
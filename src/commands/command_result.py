@@ -0,0 +1,110 @@
+import json
+from datetime import datetime
+from pathlib import Path
+from src import http_client
+from src.config_json_manager import ConfigJsonManager
+from src.file_lock import file_lock
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# コンテスト成績の履歴（ログイン不要で公開されている）
+ATCODER_HISTORY = "https://atcoder.jp/users/{user}/history/json"
+# コンテストごとの成績（contest_stocks/{contest}/result.json）
+RESULT_FILE = "result.json"
+
+def contest_of(entry):
+    """履歴のContestScreenName（abc350.contest.atcoder.jp）からコンテスト名（abc350）を取り出す"""
+    return str(entry.get("ContestScreenName", "")).split(".", 1)[0]
+
+def to_result(entry, user):
+    """履歴の1件を保存用の形にする。deltaはRatedのときだけ"""
+    rated = bool(entry.get("IsRated"))
+    old_rating, new_rating = entry.get("OldRating"), entry.get("NewRating")
+    return {
+        "contest": contest_of(entry),
+        "title": entry.get("ContestName"),
+        "user": user,
+        "place": entry.get("Place"),
+        "performance": entry.get("Performance"),
+        "rated": rated,
+        "old_rating": old_rating,
+        "new_rating": new_rating,
+        "delta": new_rating - old_rating if rated and None not in (old_rating, new_rating) else None,
+        "end_time": entry.get("EndTime"),
+        "fetched_at": datetime.now().isoformat(),
+    }
+
+def format_delta(delta):
+    return "-" if delta is None else f"{delta:+d}"
+
+def format_result(result):
+    head = f"{result['contest']}（{result['title']}）" if result.get("title") else result["contest"]
+    line = f"{head}: {result['place']}位 パフォーマンス {result['performance']}"
+    if not result.get("rated"):
+        return f"{line}（Unrated）"
+    return f"{line} レート {result['old_rating']} → {result['new_rating']}（{format_delta(result['delta'])}）"
+
+class ResultStore:
+    """
+    コンテストの成績を .cph/results.json に集約する。キーはコンテスト名。
+    同じ内容をcontest_stocks/{contest}/result.jsonにも書き出し、保管した問題と一緒に残す。
+    """
+    def __init__(self, path=None, upm=None):
+        self.upm = upm or UnifiedPathManager()
+        self.path = Path(str(path)) if path else self.upm.cph_dir("results.json")
+
+    def load(self):
+        if not self.path.exists():
+            return {}
+        try:
+            return json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            return {}
+
+    def save(self, result):
+        with file_lock(self.path):
+            data = self.load()
+            data[result["contest"]] = result
+            atomic_write_json(self.path, dict(sorted(data.items())))
+        atomic_write_json(self.upm.contest_stocks(result["contest"], None, None, RESULT_FILE), result)
+        return result
+
+    def all(self):
+        """終了日時の順"""
+        return sorted(self.load().values(), key=lambda r: (r.get("end_time") or "", r["contest"]))
+
+def configured_user(options, upm):
+    """--userか、config.jsonのresult.user（公開の成績・提出履歴を取るユーザー名）"""
+    user = options.get("user")
+    if not user or user is True:
+        user = ConfigJsonManager(str(upm.config_json())).get_result().get("user")
+    if not user:
+        raise ValueError("--user でユーザー名を指定するか、config.jsonに \"result\": {\"user\": \"NAME\"} を書いてください")
+    return user
+
+class CommandResult:
+    """
+    終了したコンテストの自分の成績（順位・パフォーマンス・レートの増減）をAtCoderの公開されている成績履歴から取得して残す。
+    ユーザー名は--userか、config.jsonのresult.user。残した成績は stats --results で一覧できる。
+    使い方: result abc350 [--user NAME]
+    """
+    def __init__(self, options=None, upm=None, store=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.store = store or ResultStore(upm=self.upm)
+
+    def user(self):
+        return configured_user(self.options, self.upm)
+
+    async def result(self, contest_name):
+        if not contest_name:
+            raise ValueError("コンテスト名を指定してください（例: result abc350）")
+        user = self.user()
+        history = http_client.get_json(ATCODER_HISTORY.format(user=user))
+        entry = next((e for e in history if contest_of(e) == contest_name), None)
+        if entry is None:
+            print(f"[result] {user} の {contest_name} の成績はまだ公開されていません（参加していない場合も表示されません）")
+            return None
+        result = self.store.save(to_result(entry, user))
+        print(f"[result] {format_result(result)}")
+        return result
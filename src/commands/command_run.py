@@ -0,0 +1,114 @@
+import os
+import sys
+import time
+from .command_test import CommandTest
+from .clipboard import read_clipboard
+from .case_storage import compress_if_large, compress_threshold, find_case_file
+from src.environment.test_language_handler import get_handler
+from src.error_hints import CphError
+from src.file_operator import atomic_write
+from src import temp_space
+
+TLE_EXIT_CODES = (124, 137)  # timeout(1)がタイムアウト時に返す終了コード（--signal=KILL時は137）
+
+def limit_command(run_cmd, time_limit=None, memory_limit_mb=None):
+    """
+    実行コマンドにTL（timeout）とML（ulimit -v）をかける。
+    どちらも指定がなければそのまま返す。
+    """
+    cmd = list(run_cmd)
+    if time_limit:
+        cmd = ["timeout", "--signal=KILL", str(time_limit)] + cmd
+    if memory_limit_mb:
+        cmd = ["sh", "-c", f'ulimit -v {int(float(memory_limit_mb) * 1024)}; exec "$@"', "sh"] + cmd
+    return cmd
+
+class CommandRun:
+    """
+    テストケースを使わず、ビルドした解答を端末の標準入出力につないで実行する。
+    使い方: run [--tl 2] [--ml 1024]（入力を手で打ち込んで動作を試す）
+          run --input-clipboard / --input -（クリップボードやパイプから入力を渡す）
+          run --input - --save-as NAME（渡した入力をテストケースNAME.inとして保存する）
+    """
+    def __init__(self, file_manager, test_env, options=None):
+        self.file_manager = file_manager
+        self.env = test_env
+        self.options = options or {}
+        self.command_test = CommandTest(file_manager, test_env)
+
+    def prepare(self, contest_name, problem_name, language_name):
+        """ソースを用意し、コンテナを1つ起動してビルドする"""
+        temp_source_path = self.env.prepare_source_code(contest_name, problem_name, language_name)
+        self.env.adjust_containers(self.command_test.build_requirements(language_name, 1), contest_name, problem_name, language_name)
+        containers = self.command_test.get_test_containers_from_info()
+        container = containers[0] if containers else f"cph_run_{language_name}"
+        handler = get_handler(language_name)
+        cont_source_path = self.env.to_container_path(os.path.abspath(temp_source_path))
+        # Docker実行ならContainerClient、ローカル実行ならExecutionManagerでビルドする
+        build_client = getattr(self.env, "ctl", None) or getattr(self.env, "manager", None)
+        ok, _, stderr = handler.build(build_client, container, cont_source_path)
+        if not ok:
+            raise CphError(f"ビルド失敗\n{stderr}", kind="build_failed")
+        return handler, container, cont_source_path
+
+    def read_input(self):
+        """--input-clipboard / --input - で渡された入力を返す。指定がなければNone（端末につなぐ）"""
+        if self.options.get("input-clipboard"):
+            return read_clipboard()
+        source = self.options.get("input")
+        if source is None:
+            return None
+        if source == "-":
+            return sys.stdin.read()
+        with open(source, "r", encoding="utf-8") as f:
+            return f.read()
+
+    def save_case(self, name, input_data):
+        """入力をcontest_current/test/{name}.inとして保存する。既存のケースは上書きしない"""
+        name = name[:-len(".in")] if name.endswith(".in") else name
+        test_dir = self.command_test.upm.contest_current("test")
+        path = str(self.command_test.upm.validator().safe_join(test_dir, f"{name}.in"))
+        existing = find_case_file(path)
+        if existing:
+            raise FileExistsError(f"テストケースが既に存在します: {existing}")
+        atomic_write(path, input_data)
+        # 大きな入力はzstdで圧縮して保存する（testでは展開しながら流す）
+        path = compress_if_large(path, compress_threshold(self.command_test.get_config().get_test()))
+        print(f"[run] テストケースとして保存しました: {path}（期待出力は {name}.out に記入してください）")
+        return path
+
+    def write_temp_input(self, input_data):
+        # この実行だけの一時ディレクトリに入力を置く（dockerならコンテナからも見える.temp/sessions配下）
+        path = str(temp_space.for_env(self.env).file("run", "input.in"))
+        with open(path, "w", encoding="utf-8") as f:
+            f.write(input_data)
+        return path
+
+    async def run(self, contest_name, problem_name, language_name):
+        input_data = self.read_input()
+        save_as = self.options.get("save-as")
+        if save_as and input_data is None:
+            raise ValueError("--save-as は --input - または --input-clipboard と一緒に指定してください")
+        handler, container, cont_source_path = self.prepare(contest_name, problem_name, language_name)
+        time_limit = self.options.get("tl")
+        cmd = limit_command(handler.run_command(cont_source_path), time_limit, self.options.get("ml"))
+        if input_data is None:
+            print("[run] 入力を待っています（終了はCtrl-D）", flush=True)
+            returncode = self.env.attach_command(container, cmd)
+        else:
+            in_file = self.write_temp_input(input_data)
+            start = time.perf_counter()
+            ok, stdout, stderr = self.env.exec_command(container, cmd, self.env.to_container_path(in_file))
+            # exec_commandは終了コードを返さないので、TLを使い切ったかどうかでTLEとみなす
+            timed_out = time_limit and time.perf_counter() - start >= float(time_limit)
+            returncode = 0 if ok else (TLE_EXIT_CODES[0] if timed_out else 1)
+            print(stdout or "", end="")
+            if stderr:
+                print(stderr, end="", file=sys.stderr)
+        if time_limit and returncode in TLE_EXIT_CODES:
+            print(f"[run] TLE: {time_limit}秒を超えました")
+        elif returncode != 0:
+            print(f"[run] 終了コード: {returncode}")
+        if save_as:
+            self.save_case(save_as, input_data)
+        return returncode
@@ -0,0 +1,40 @@
+from .archive_index import ArchiveIndex, parse_tags
+
+def format_entry(entry):
+    difficulty = entry.get("difficulty")
+    status = entry.get("status") or "未提出"
+    tags = ",".join(entry.get("tags", []))
+    return f"{entry['contest']:<10} {entry['problem']:<4} {difficulty if difficulty is not None else '-':>5}  {status:<9} {tags}"
+
+class CommandSearch:
+    """
+    アーカイブの索引から練習する問題を探す。
+    使い方: search [--tag dp,graph] [--unsolved] [--min-difficulty 800] [--max-difficulty 1600]
+    """
+    def __init__(self, options=None, index=None):
+        self.options = options or {}
+        self.index = index or ArchiveIndex()
+
+    def int_option(self, key):
+        value = self.options.get(key)
+        if value is None or value is True:
+            return None
+        try:
+            return int(value)
+        except ValueError:
+            raise ValueError(f"--{key} には整数を指定してください: {value}")
+
+    async def search(self):
+        found = self.index.search(
+            tags=parse_tags(self.options.get("tag")),
+            unsolved=bool(self.options.get("unsolved")),
+            min_difficulty=self.int_option("min-difficulty"),
+            max_difficulty=self.int_option("max-difficulty"),
+        )
+        if not found:
+            print("[search] 条件に合う問題はありません")
+            return found
+        for entry in found:
+            print(format_entry(entry))
+        print(f"[search] {len(found)}問")
+        return found
@@ -0,0 +1,110 @@
+from .case_anonymizer import CaseAnonymizer
+from .case_storage import case_name, decompress_stream, expected_file, find_case_file, is_compressed
+from .paste_service import DEFAULT_PASTE_SERVICE, create_paste_service
+from .run_artifacts import NON_FAILURE_VERDICTS, RunArtifacts
+from src.config_json_manager import ConfigJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# これより大きいテストケースは貼らない（貼り付け先の上限と読みやすさのため）
+MAX_CASE_BYTES = 64 * 1024
+
+class CommandShare:
+    """
+    作業中の解答を貼り付けサービスに上げ、URLを表示する（チームメイトに相談するとき用）。
+    使い方: share [--service gist|0x0] [--failing] [--case NAME] [--anonymize]
+    --failingで直近のtestで失敗したケース、--caseで指定したケースの入力・期待出力・実際の出力も一緒に貼る。
+    --anonymizeでケースをtest.anonymizeの設定で伏せてから貼る（case export --anonymizeと同じ変換）。
+    貼り付け先の既定はconfig.jsonのshare.service（無ければ0x0）。
+    """
+    def __init__(self, options=None, upm=None, service=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.service = service
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def get_service(self):
+        if self.service is not None:
+            return self.service
+        share_config = self.get_config().get_share()
+        name = self.options.get("service") or share_config.get("service") or DEFAULT_PASTE_SERVICE
+        return create_paste_service(name, share_config)
+
+    def source_files(self, language_name):
+        source = self.upm.contest_current(language_name, self.upm.source_file(language_name))
+        if not source.exists():
+            raise FileNotFoundError(f"解答ファイルがありません: {source}")
+        return {source.name: source.read_text(encoding="utf-8")}
+
+    def latest_failure(self, contest_name, problem_name, language_name):
+        """直近のtestで最初に失敗したケース名と、その実行のrun_id"""
+        history = RunArtifacts.history(contest_name, problem_name, language_name)
+        if not history:
+            return None, None
+        for case in history[0].get("cases", []):
+            if case.get("verdict") not in NON_FAILURE_VERDICTS:
+                return case["name"], history[0].get("run_id")
+        return None, history[0].get("run_id")
+
+    def read_limited(self, path):
+        """MAX_CASE_BYTESまで読む。.zstは展開後の大きさで判定するので、展開しきらずに打ち切る"""
+        if is_compressed(path):
+            with decompress_stream(path) as stream:
+                data = stream.read(MAX_CASE_BYTES + 1)
+        else:
+            with open(path, "rb") as f:
+                data = f.read(MAX_CASE_BYTES + 1)
+        if len(data) > MAX_CASE_BYTES:
+            return f"（{MAX_CASE_BYTES // 1024}KBを超えるため省略）\n"
+        return data.decode("utf-8")
+
+    def case_files(self, name, run_id=None):
+        """ケースの入力・期待出力と、あれば直近の実行での出力"""
+        stem = name[:-len(".in")] if name.endswith(".in") else name
+        in_file = find_case_file(self.upm.contest_current("test", f"{stem}.in"))
+        if in_file is None:
+            raise FileNotFoundError(f"テストケースが見つかりません: {name}")
+        files = {case_name(in_file): self.read_limited(in_file)}
+        out_file = find_case_file(expected_file(in_file))
+        if out_file:
+            files[case_name(out_file)] = self.read_limited(out_file)
+        stdout = self.upm.runs_dir(run_id, f"{stem}.stdout") if run_id else None
+        if stdout and stdout.exists():
+            files[f"{stem}.actual"] = self.read_limited(stdout)
+        if self.options.get("anonymize"):
+            files = self.anonymize(files, case_name(in_file), case_name(out_file or expected_file(in_file)))
+        return files
+
+    def anonymize(self, files, in_name, out_name):
+        anonymizer = CaseAnonymizer.from_config(self.get_config().get_test(), cwd=str(self.upm.project_path.root)).require()
+        scrubbed, expected, count = anonymizer.transform(files[in_name], files.get(out_name))
+        result = {name: anonymizer.scrub(content)[0] for name, content in files.items() if name not in (in_name, out_name)}
+        result[in_name] = scrubbed
+        if expected is not None:
+            result[out_name] = expected
+        elif out_name in files:
+            print(f"[share] {in_name}: 参照解（test.anonymize.reference）が無いため期待出力は貼りません")
+        if count:
+            print(f"[share] {in_name}: {count}箇所を伏せました")
+        return result
+
+    def collect(self, contest_name, problem_name, language_name):
+        files = self.source_files(language_name)
+        failing, run_id = self.latest_failure(contest_name, problem_name, language_name)
+        case = self.options.get("case")
+        if case and case is not True:
+            files.update(self.case_files(case, run_id))
+        if self.options.get("failing"):
+            if failing is None:
+                print("[share] 直近のtestで失敗したケースはありません（解答だけを貼ります）")
+            else:
+                files.update(self.case_files(failing, run_id))
+        return files
+
+    async def share(self, contest_name, problem_name, language_name):
+        files = self.collect(contest_name, problem_name, language_name)
+        service = self.get_service()
+        url = service.upload(files, description=f"{contest_name} {problem_name} ({language_name})")
+        print(f"[share] {service.name} に {len(files)}ファイルを貼りました: {url}")
+        return url
@@ -0,0 +1,42 @@
+from .archive_index import ArchiveIndex
+from .command_switch import task_url
+from .constraints import fetch_constraints
+from .opener import Opener
+from .problem_lock import active_problem
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+class CommandStatement:
+    """
+    作業中の問題の問題文。
+    使い方: statement（ブラウザで開く） / statement --constraints [--refresh]（制約の一覧。--refreshで問題ページから読み直す）
+    """
+    def __init__(self, options=None, upm=None, opener=None, scraper=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.opener = opener or Opener()
+        self.scraper = scraper
+
+    def constraints(self, contest_name, problem_name):
+        """problem.jsonに残した制約。無いか--refreshなら問題ページから読む"""
+        entry = ArchiveIndex(upm=self.upm).get(contest_name, problem_name) or {}
+        if entry.get("constraints") is not None and not self.options.get("refresh"):
+            return entry["constraints"]
+        return fetch_constraints(contest_name, problem_name, self.upm, self.scraper)
+
+    async def statement(self):
+        contest_name, problem_name = active_problem(self.upm)
+        if contest_name is None or problem_name is None:
+            raise ValueError("作業中の問題がありません。open で問題を開いてから実行してください")
+        if not self.options.get("constraints"):
+            url = task_url(contest_name, problem_name)
+            print(f"[statement] {url}")
+            self.opener.open_browser(url)
+            return url
+        constraints = self.constraints(contest_name, problem_name)
+        if constraints is None:
+            return None
+        if not constraints:
+            print(f"[statement] {contest_name} {problem_name} の問題文に制約の節が見つかりませんでした")
+        for c in constraints:
+            print(f"  {c}")
+        return constraints
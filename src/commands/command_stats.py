@@ -0,0 +1,55 @@
+from src.usage_stats import UsageStats, STATS_ENV
+from .command_result import ResultStore, format_delta
+
+def format_seconds(value):
+    return "-" if value is None else f"{value:.3f}s"
+
+def format_rate(value):
+    return "-" if value is None else f"{value * 100:.1f}%"
+
+def format_value(value):
+    return "-" if value is None else str(value)
+
+class CommandStats:
+    """
+    ローカルに集計したcph自体の利用状況（高速化の優先度を決めるための参考）や、resultで残したコンテストの成績を表示する。
+    使い方: stats --tool / stats --results
+    """
+    def __init__(self, options=None, stats=None, results=None):
+        self.options = options or {}
+        self.stats = stats or UsageStats()
+        self.results = results or ResultStore()
+
+    def show_results(self):
+        results = self.results.all()
+        print(f"[stats] コンテストの成績（{self.results.path}）")
+        if not results:
+            print("  （記録なし。result abc350 のように取得できます）")
+        for r in results:
+            rating = f"{format_value(r.get('new_rating'))}（{format_delta(r.get('delta'))}）" if r.get("rated") else "Unrated"
+            # 履歴に順位やパフォーマンスが無い（None）こともある
+            print(f"  {r['contest']:<10} {format_value(r.get('place')):>6}位  パフォーマンス {format_value(r.get('performance')):>5}  レート {rating}")
+        return results
+
+    async def show(self):
+        if self.options.get("results"):
+            return self.show_results()
+        if not self.options.get("tool"):
+            raise ValueError("表示する統計を指定してください: stats --tool / stats --results")
+        if not self.stats.enabled:
+            print(f"[stats] 利用統計は無効です。config.jsonに \"stats\": {{\"enabled\": true}} を書くか {STATS_ENV}=1 で有効にできます（記録はローカルのみ）")
+        summary = self.stats.summary()
+        print(f"[stats] {summary['since']} からの集計（{self.stats.path}）")
+        print("コマンド:")
+        if not summary["commands"]:
+            print("  （記録なし）")
+        for name, e in summary["commands"].items():
+            print(f"  {name:<10} {e['count']:>5}回  平均 {format_seconds(e['average'])}  失敗 {e['failed']}回")
+        tests = summary["tests"]
+        print(f"テスト: {tests['runs']}回 / {tests['cases']}ケース  1ケース平均 {format_seconds(tests['average_case'])}")
+        print("キャッシュ:")
+        if not summary["caches"]:
+            print("  （記録なし）")
+        for name, e in summary["caches"].items():
+            print(f"  {name:<10} ヒット率 {format_rate(e['rate'])}（{e['hit']}/{e['hit'] + e['miss']}）")
+        return summary
@@ -0,0 +1,35 @@
+from .archive_index import ArchiveIndex
+from .problem_difficulty import describe_difficulty
+from .submission_queue import SubmissionQueue
+from src.info_json_manager import InfoJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+class CommandStatus:
+    """
+    作業中の問題と、保留中の提出を表示する。
+    使い方: status
+    """
+    def __init__(self, options=None, upm=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+
+    async def show(self):
+        info = InfoJsonManager(self.upm.info_json()).data
+        current = [info.get(k) for k in ("contest_name", "problem_name", "language_name")]
+        if any(current):
+            print(f"[status] 作業中: {' '.join(str(v) for v in current if v)}")
+            entry = ArchiveIndex(upm=self.upm).get(current[0], current[1]) if current[0] and current[1] else None
+            difficulty = (entry or {}).get("difficulty")
+            if difficulty is not None:
+                print(f"[status] 難易度: {describe_difficulty(difficulty)}")
+        else:
+            print("[status] 作業中の問題はありません")
+        items = SubmissionQueue(self.upm).items()
+        if not items:
+            print("[status] 保留中の提出: なし")
+        else:
+            print(f"[status] 保留中の提出: {len(items)}件（submit --flush で送信）")
+            for item in items:
+                error = f" / 最後のエラー: {item['last_error']}" if item.get("last_error") else ""
+                print(f"  {item['contest_name']} {item['problem_name']} {item['language_name']}  {item['queued_at']}  試行{item.get('attempts', 0)}回{error}")
+        return {"current": info, "queue": items}
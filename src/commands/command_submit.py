@@ -1,32 +1,90 @@
+import difflib
 import os
+import shutil
 HOST_PROJECT_ROOT = os.path.abspath(".")
 CONTAINER_WORKSPACE = "/workspace"
 TEMP_DIR = "/workspace/.temp"
-from .command_test import CommandTest
+from .command_test import CommandTest, auto_jobs, is_sample_case
+from .result_cache import ResultCache, source_hash
+from .cache_epoch import refresh_epoch
+from .archive_index import ArchiveIndex
+from . import color_theme
 from .common import get_project_root_volumes
+from .progress_reporter import ProgressReporter
+from .submission_bundle import SubmissionBundler
+from src import temp_space
+from .submission_queue import SubmissionQueue, is_network_error
+from .submit_checklist import SubmitChecklist, checklist_items, record_checklist
+from .toolchain import DEFAULT_SITE, compile_command
+from src import events, http_client
+from src.config_json_manager import ConfigJsonManager
 from src.info_json_manager import InfoJsonManager
+from src.error_hints import CphError
+from src.usage_stats import UsageStats
 from src.execution_client.container.client import ContainerClient
 from src.execution_client.container.image_manager import ContainerImageManager
 from src.path_manager.unified_path_manager import UnifiedPathManager
 from src.path_manager.file_operator import FileOperator
 
-SUBMIT_FILES = {
-    "python": "main.py",
-    "pypy": "main.py",
-    "rust": "src/main.rs",
-}
-
 class CommandSubmit:
-    def __init__(self, file_manager, test_env):
+    def __init__(self, file_manager, test_env, progress=None, options=None):
         self.file_manager = file_manager
-        self.command_test = CommandTest(file_manager, test_env)
+        self.options = options or {}
+        self.progress = progress or ProgressReporter()
+        self.command_test = CommandTest(file_manager, test_env, progress=self.progress)
         self.upm = UnifiedPathManager()
         self.test_env = test_env
 
-    def confirm_submit_with_wa(self):
-        ans = input("AC以外のケースがあります。提出してよいですか？ (y/N): ")
+    def confirm_submit_with_wa(self, summary=None):
+        prefix = f"{summary}\n" if summary else ""
+        ans = input(f"{prefix}AC以外のケースがあります。提出してよいですか？ (y/N): ")
         return ans.lower() in ("y", "yes")
 
+    def cache_epoch(self, language_name):
+        config = ConfigJsonManager(str(self.upm.config_json()))
+        return refresh_epoch(config, language_name, self.options.get("site", DEFAULT_SITE), self.upm)
+
+    async def preflight_samples(self, contest_name, problem_name, language_name):
+        """
+        提出前チェック。前回のフル実行からソースもジャッジのツールチェインも変わっていなければその結果を使い、
+        変わっていればサンプルケースだけを再実行する（AC以外のケースのみ表示）。
+        """
+        cache = ResultCache()
+        current_hash = source_hash(self.upm.contest_current(language_name))
+        entry = cache.get(cache.key(contest_name, problem_name, language_name))
+        hit = cache.is_fresh(entry, current_hash, self.cache_epoch(language_name))
+        UsageStats().record_cache("results", hit)
+        if hit:
+            cases = entry.get("cases", [])
+            passed = sum(1 for c in cases if c.get("verdict") == "AC")
+            return entry.get("all_ac", False), f"前回のテスト結果: {passed}/{len(cases)} AC（ソース変更なし）"
+        results = await self.command_test.run_test_return_results(contest_name, problem_name, language_name, case_filter=is_sample_case)
+        failed = [r for r in results if not self.command_test.is_all_ac([r])]
+        self.command_test.print_test_results(failed)
+        all_ac = bool(results) and not failed
+        return all_ac, f"サンプル再実行: {len(results) - len(failed)}/{len(results)} AC（前回のテストからソースかジャッジのツールチェインが変わっています）"
+
+    def run_checklist(self, contest_name, problem_name, language_name):
+        """
+        提出前のチェックリスト（config.jsonのsubmit.checklistと、tag --checklistの問題ごとの項目）をy/nで確かめる。
+        -y / --yesなら聞かずに通す。回答は問題ごとの記録に残し、すべてyなら（項目が無ければ）Trueを返す
+        """
+        index = ArchiveIndex()
+        entry = index.get(contest_name, problem_name) or {}
+        config = ConfigJsonManager(str(self.upm.config_json()))
+        items = checklist_items(config.get_submit(), entry.get("checklist"))
+        if not items:
+            return True
+        if self.options.get("yes"):
+            record_checklist(index, contest_name, problem_name, language_name, [{"item": i, "answer": None} for i in items], skipped=True)
+            print(f"[提出前チェック] チェックリスト{len(items)}項目を省きました（--yes）")
+            return True
+        answers = SubmitChecklist(items).run()
+        record = record_checklist(index, contest_name, problem_name, language_name, answers)
+        if not record["passed"]:
+            print(f"[提出前チェック] チェックリストの「{answers[-1]['item']}」を確かめてから提出してください")
+        return record["passed"]
+
     def validate_info_file(self, info_path, contest_name, problem_name, file_operator=None):
         manager = InfoJsonManager(info_path)
         info = manager.data
@@ -69,19 +127,133 @@ class CommandSubmit:
         manager = InfoJsonManager(info_path)
         for c in manager.get_containers(type="ojtools"):
             return c["name"]
-        raise RuntimeError("ojtools用コンテナがsystem_info.jsonにありません")
+        raise CphError("ojtools用コンテナがsystem_info.jsonにありません", kind="ojtools_container_missing")
 
     async def run_submit_command(self, args, volumes, workdir):
         # test_env経由で提出処理を実行
         return self.test_env.submit_via_ojtools(args, volumes, workdir)
 
+    def resolve_submit_file(self, language_name, file_operator=None):
+        submit_file = self.upm.source_file(language_name)
+        temp_file_path = f".temp/{submit_file}"
+        if file_operator:
+            temp_file_exists = file_operator.exists(temp_file_path)
+        else:
+            temp_file_exists = os.path.exists(temp_file_path)
+        if temp_file_exists:
+            return temp_file_path
+        return self.upm.contest_current(language_name, submit_file)
+
+    def get_bundler(self):
+        return SubmissionBundler(ConfigJsonManager(str(self.upm.config_json())).get_submit())
+
+    def bundle_dir(self):
+        """加工した提出用ファイルの置き場。実行ごとの一時ディレクトリ（dockerならコンテナからも見える.temp/sessions配下）"""
+        return str(temp_space.for_env(self.test_env).dir("submit"))
+
+    def prepare_compile_project(self, bundled_path, language_name):
+        """rustは提出ファイルをsrc/main.rsに置いた確認用のcargoプロジェクトを作る（依存はcontest_currentと同じ）"""
+        check_dir = os.path.join(os.path.dirname(str(bundled_path)), f"check-{language_name}")
+        os.makedirs(os.path.join(check_dir, "src"), exist_ok=True)
+        for name in ("Cargo.toml", "Cargo.lock"):
+            src = self.upm.contest_current(language_name, name)
+            if os.path.exists(str(src)):
+                shutil.copy(str(src), os.path.join(check_dir, name))
+        shutil.copy(str(bundled_path), os.path.join(check_dir, "src", "main.rs"))
+        return check_dir
+
+    def compile_check(self, bundled_path, language_name, site=DEFAULT_SITE):
+        """
+        加工後の提出用ファイルをテスト用コンテナ（ローカル実行ならローカル）のツールチェーンで
+        ジャッジ相当のコマンドでコンパイルする。失敗したら提出しない。
+        """
+        check_dir = self.prepare_compile_project(bundled_path, language_name) if language_name == "rust" else os.path.dirname(str(bundled_path))
+        cmd = compile_command(
+            language_name, site,
+            file=self.test_env.to_container_path(os.path.abspath(str(bundled_path))),
+            dir=self.test_env.to_container_path(os.path.abspath(check_dir)),
+            target=self.test_env.to_container_path(os.path.abspath(os.path.join(".temp", language_name, "target"))),
+        )
+        if cmd is None:
+            return True
+        containers = InfoJsonManager(self.upm.info_json()).get_containers(type="test", language=language_name)
+        container = containers[0]["name"] if containers else None
+        ok, _, stderr = self.test_env.exec_command(container, cmd)
+        if not ok:
+            print(stderr)
+            raise CphError("提出用ファイルのコンパイルに失敗しました（ヘッダー・除去の設定を確認してください）", kind="build_failed")
+        print("[提出前チェック] 提出用ファイルのコンパイルを確認しました")
+        return True
+
+    def preview_submission(self, contest_name, problem_name, language_name):
+        """--dry-run: 提出用ファイルへの加工内容を差分で表示し、提出はしない"""
+        file_path = self.resolve_submit_file(language_name)
+        with open(str(file_path), "r", encoding="utf-8") as f:
+            source = f.read()
+        bundled = self.get_bundler().bundle(source, language_name, contest=contest_name, problem=problem_name)
+        diff = list(difflib.unified_diff(source.splitlines(keepends=True), bundled.splitlines(keepends=True), fromfile=str(file_path), tofile="submission"))
+        palette = color_theme.current()
+        print("".join(palette.diff_line(line.rstrip("\n")) + "\n" for line in diff) if diff else "[dry-run] 提出用ファイルへの加工はありません")
+        print("[dry-run] 提出は行いませんでした")
+        return bundled
+
+    def defer_submission(self, file_path, contest_name, problem_name, language_name, language_id, reason):
+        """ネットワークに接続できないときは提出を保留し、submit --flushで後から送れるようにする"""
+        item = SubmissionQueue(self.upm).enqueue(file_path, contest_name, problem_name, language_name, language_id, reason=reason)
+        self.progress.emit("submit_queued", id=item["id"], contest_name=contest_name, problem_name=problem_name)
+        print(f"[queue] {reason}。提出を保留しました: {item['id']}")
+        print("[queue] 接続が戻ったら submit --flush で送信してください（status で保留中の提出を確認できます）")
+        return item
+
+    async def flush_queue(self):
+        """保留した提出を古い順に送る。接続できなければそこで止め、残りは保留したままにする"""
+        queue = SubmissionQueue(self.upm)
+        items = queue.items()
+        if not items:
+            print("[queue] 保留中の提出はありません")
+            return []
+        sent = []
+        for i, item in enumerate(items):
+            contest_name, problem_name, language_name = item["contest_name"], item["problem_name"], item["language_name"]
+            cont_file_path = self.test_env.to_container_path(os.path.abspath(str(queue.file_path(item))))
+            args, url = self.build_submit_command(contest_name, problem_name, language_name, cont_file_path, item.get("language_id"))
+            if not http_client.is_reachable(url):
+                print(f"[queue] まだ接続できません。{len(items) - i}件を保留したままにします")
+                break
+            result = await self.run_submit_command(args, get_project_root_volumes(), "/workspace")
+            ok = bool(result[0]) if isinstance(result, tuple) and result else False
+            if ok:
+                queue.remove(item)
+                ArchiveIndex().record_submission(contest_name, problem_name, language_name)
+                events.publish(events.SUBMISSION_SENT, contest=contest_name, problem=problem_name, language=language_name, url=url)
+                sent.append(item)
+                print(f"[queue] 送信しました: {contest_name} {problem_name} {language_name}（{item['queued_at']}に保留）")
+                continue
+            output = " ".join(str(x) for x in result[1:]) if isinstance(result, tuple) else ""
+            queue.record_failure(item, output.strip()[-200:] or "submit failed")
+            print(f"[queue] 送信に失敗しました: {contest_name} {problem_name} {language_name}")
+            if is_network_error(output):
+                print(f"[queue] 接続が切れたため、{len(items) - i}件を保留したままにします")
+                break
+        return sent
+
     async def submit(self, contest_name, problem_name, language_name):
-        results = await self.command_test.run_test_return_results(contest_name, problem_name, language_name)
-        self.command_test.print_test_results(results)
-        if not self.command_test.is_all_ac(results):
-            if not self.confirm_submit_with_wa():
+        if self.options.get("flush"):
+            return await self.flush_queue()
+        if self.options.get("dry-run"):
+            return self.preview_submission(contest_name, problem_name, language_name)
+        pending = SubmissionQueue(self.upm).items()
+        if pending:
+            print(f"[queue] 保留中の提出が{len(pending)}件あります（submit --flush で送信）")
+        all_ac, summary = await self.preflight_samples(contest_name, problem_name, language_name)
+        print(f"[提出前チェック] {summary}")
+        if not all_ac:
+            if not self.confirm_submit_with_wa(summary):
                 print("提出を中止しました。")
                 return
+        if not self.run_checklist(contest_name, problem_name, language_name):
+            print("提出を中止しました。")
+            return
         file_operator = self.file_manager.file_operator if self.file_manager and hasattr(self.file_manager, 'file_operator') else None
         import os
         info_path = self.upm.info_json()
@@ -92,24 +264,25 @@ class CommandSubmit:
         language_id = self.get_language_id_from_config(config_path, language_name, file_operator)
         volumes = get_project_root_volumes()
         workdir = "/workspace"
-        submit_file = SUBMIT_FILES.get(language_name, "main.py")
-        temp_file_path = f".temp/{submit_file}"
-        if file_operator:
-            temp_file_exists = file_operator.exists(temp_file_path)
-        else:
-            temp_file_exists = os.path.exists(temp_file_path)
-        if temp_file_exists:
-            file_path = temp_file_path
-        else:
-            file_path = self.upm.contest_current(language_name, submit_file)
+        file_path = self.resolve_submit_file(language_name, file_operator)
+        # ヘッダー・フッターや除去の設定があれば、作業中のファイルとは別に提出用ファイルを作る
+        bundler = self.get_bundler()
+        bundled_path = bundler.write(file_path, language_name, dest_dir=self.bundle_dir(), contest=contest_name, problem=problem_name)
+        if bundled_path:
+            # 加工で壊れていないか、実際に送るファイルをコンパイルして確かめる
+            if bundler.config.get("compile_check", True):
+                self.compile_check(bundled_path, language_name)
+            file_path = bundled_path
         # ファイルパスをコンテナ内パスに変換
         cont_file_path = self.test_env.to_container_path(file_path)
         args, url = self.build_submit_command(contest_name, problem_name, language_name, cont_file_path, language_id)
+        if not http_client.is_reachable(url):
+            return self.defer_submission(file_path, contest_name, problem_name, language_name, language_id, "ネットワークに接続できません")
         temp_source_path, temp_test_dir = self.command_test.prepare_test_environment(contest_name, problem_name, language_name)
         temp_in_files, _ = self.command_test.collect_test_cases(temp_test_dir, file_operator)
         test_case_count = len(temp_in_files)
         requirements = [
-            {"type": "test", "language": language_name, "count": test_case_count, "volumes": {
+            {"type": "test", "language": language_name, "count": auto_jobs(test_case_count), "volumes": {
                 HOST_PROJECT_ROOT: CONTAINER_WORKSPACE
             }},
             {"type": "ojtools", "count": 1, "volumes": {
@@ -118,4 +291,14 @@ class CommandSubmit:
                 "/home/cphelper/.local/share/online-judge-tools/cookie.jar": "/root/.local/share/online-judge-tools/cookie.jar"
             }}
         ]
-        return await self.run_submit_command(args, volumes, workdir) 
\ No newline at end of file
+        self.progress.emit("submit_started", url=url, language_name=language_name)
+        result = await self.run_submit_command(args, volumes, workdir)
+        ok = bool(result[0]) if isinstance(result, tuple) and result else None
+        self.progress.emit("submit_finished", url=url, ok=ok)
+        if not ok and isinstance(result, tuple) and is_network_error(" ".join(str(x) for x in result[1:])):
+            return self.defer_submission(file_path, contest_name, problem_name, language_name, language_id, "提出中に接続が切れました")
+        if ok:
+            # 結果はジャッジ側で確定するので、ここでは提出済みとして索引に残す
+            ArchiveIndex().record_submission(contest_name, problem_name, language_name)
+            events.publish(events.SUBMISSION_SENT, contest=contest_name, problem=problem_name, language=language_name, url=url)
+        return result 
\ No newline at end of file
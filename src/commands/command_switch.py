@@ -0,0 +1,138 @@
+from .archive_index import ArchiveIndex
+from .case_storage import case_name
+from .command_archive import is_codeforces_contest
+from .command_import import CODEFORCES_PREFIX
+from .hooks import HookRunner
+from .problem_lock import active_problem
+from .sample_scraper import page_title
+from src import events, http_client
+from src.command_parser import PROBLEM_NAMES
+from src.config_json_manager import ConfigJsonManager
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+NEXT = "next"
+PREV = "prev"
+
+# サイトごとの問題ページのURL（Codeforcesのコンテストはcf{contestId}、問題は小文字で持っている）
+TASK_URLS = {
+    "atcoder": lambda contest_name, problem_name: f"https://atcoder.jp/contests/{contest_name}/tasks/{contest_name}_{problem_name}",
+    "codeforces": lambda contest_name, problem_name: f"https://codeforces.com/contest/{contest_name[len(CODEFORCES_PREFIX):]}/problem/{problem_name.upper()}",
+}
+
+def contest_site(contest_name):
+    """コンテスト名の形から決めたサイト（cf1900 → codeforces、それ以外はatcoder）"""
+    return "codeforces" if is_codeforces_contest(contest_name) else "atcoder"
+
+def task_url(contest_name, problem_name):
+    return TASK_URLS[contest_site(contest_name)](contest_name, problem_name)
+
+def split_problem_id(value):
+    """"abc300_c" → ("abc300", "c")。問題IDの形でなければNone"""
+    contest_name, sep, problem_name = str(value or "").rpartition("_")
+    if sep and contest_name and problem_name in PROBLEM_NAMES:
+        return contest_name, problem_name
+    return None
+
+def step_problem(problem_name, step):
+    """a→b（step=1）、b→a（step=-1）。端を越えるとValueError"""
+    if problem_name not in PROBLEM_NAMES:
+        raise ValueError(f"{problem_name} の前後の問題がわかりません（{' / '.join(PROBLEM_NAMES)}）")
+    i = PROBLEM_NAMES.index(problem_name) + step
+    if not 0 <= i < len(PROBLEM_NAMES):
+        raise ValueError(f"{problem_name} の{'次' if step > 0 else '前'}の問題はありません")
+    return PROBLEM_NAMES[i]
+
+class CommandSwitch:
+    """
+    contest_currentの問題を切り替える（サンプルのダウンロードやコンテナの準備はしない。初めての問題はopenを使う）。
+    今の問題のコードとサンプルをcontest_stocksに残してから、指定した問題をcontest_stocks（無ければテンプレート）から取り出す。
+    切り替えた後に問題名とサンプル数を表示し、config.jsonのswitchに従ってエディタ・ブラウザを開く。
+    使い方: switch [contest] problem [language] / switch next / switch prev / switch abc300_c
+    """
+    def __init__(self, file_manager, options=None, upm=None, opener=None):
+        self.file_manager = file_manager
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.opener = opener
+        self.args = []
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def resolve(self, contest_name, problem_name):
+        """引数（next / prev / 問題ID）から切り替え先の(contest_name, problem_name)を求める"""
+        active_contest, active_name = active_problem(self.upm)
+        target = self.args[0] if self.args else None
+        if target in (NEXT, PREV):
+            if not active_name:
+                raise ValueError("作業中の問題がありません。switch [contest] problem で指定してください")
+            return active_contest, step_problem(active_name, 1 if target == NEXT else -1)
+        for value in (target, contest_name):
+            problem_id = split_problem_id(value)
+            if problem_id:
+                return problem_id
+        if target in PROBLEM_NAMES:
+            return contest_name or active_contest, target
+        if target:
+            raise ValueError(f"切り替え先がわかりません: {target}（next / prev / 問題ID（例: abc300_c））")
+        return contest_name or active_contest, problem_name
+
+    def problem_title(self, contest_name, problem_name):
+        """問題名。archive.jsonに無ければ問題ページの<title>から取って残す。取れなければNone"""
+        index = ArchiveIndex(upm=self.upm)
+        title = (index.get(contest_name, problem_name) or {}).get("title")
+        if title:
+            return title
+        try:
+            title = page_title(http_client.get(task_url(contest_name, problem_name), timeout=http_client.REACHABLE_TIMEOUT).text())
+        except (CphError, ValueError):
+            return None
+        if title:
+            index.update(contest_name, problem_name, lambda entry: entry.update(title=title))
+        return title
+
+    def sample_count(self):
+        test_dir = self.upm.contest_current("test")
+        if not test_dir.exists():
+            return 0
+        # 小課題ごとのサンプル（group_N/の中）も数える
+        return sum(1 for p in test_dir.rglob("*") if p.is_file() and case_name(p).endswith(".in"))
+
+    def report(self, contest_name, problem_name):
+        title = self.problem_title(contest_name, problem_name)
+        count = self.sample_count()
+        print(f"[switch] {contest_name} {problem_name}: {title}" if title else f"[switch] {contest_name} {problem_name}")
+        if count:
+            print(f"[switch] サンプル {count}件")
+        else:
+            print(f"[switch] サンプルがありません（取得するには: open {contest_name} {problem_name}）")
+
+    def open_after_switch(self, contest_name, problem_name, language_name):
+        """config.jsonのswitch.editor / switch.browserで開き、hooks.switchがあれば実行する"""
+        config = self.get_config()
+        settings = config.get_switch()
+        url = task_url(contest_name, problem_name)
+        lang_dir = self.upm.contest_current(language_name)
+        if self.opener and settings.get("browser"):
+            self.opener.open_browser(url)
+        if self.opener and settings.get("editor"):
+            entry_file = config.get_entry_file(language_name)
+            self.opener.open_editor(str(lang_dir / entry_file) if entry_file else str(lang_dir), language_name)
+        HookRunner(config.get_hooks()).run("switch", contest=contest_name, problem=problem_name,
+                                           language=language_name, dir=lang_dir, url=url)
+
+    async def switch(self, contest_name, problem_name, language_name):
+        """切り替えた問題の(contest_name, problem_name)を返す"""
+        active_contest, active_name = active_problem(self.upm)
+        contest_name, problem_name = self.resolve(contest_name, problem_name)
+        if (active_contest, active_name) == (contest_name, problem_name):
+            print(f"[switch] すでに {contest_name} {problem_name} です")
+            return contest_name, problem_name
+        self.file_manager.make_current(contest_name, problem_name, language_name, label="switch")
+        print(f"[switch] {active_contest} {active_name} → {contest_name} {problem_name}")
+        self.report(contest_name, problem_name)
+        self.open_after_switch(contest_name, problem_name, language_name)
+        events.publish(events.PROBLEM_OPENED, contest=contest_name, problem=problem_name, language=language_name,
+                       url=task_url(contest_name, problem_name), dir=self.upm.contest_current(language_name), via="switch")
+        return contest_name, problem_name
@@ -0,0 +1,81 @@
+from .archive_index import ArchiveIndex, parse_tags
+from .comparator import parse_compare_option
+from .submit_checklist import CHECKLIST_SEPARATOR, parse_checklist
+from src.output_limit import parse_output_limit
+
+# tag --compare / --output-limit default で問題ごとの設定を消す（config.jsonの設定に戻す）
+DEFAULT_COMPARE = "default"
+
+class CommandTag:
+    """
+    問題にタグと難易度を付ける（.cph/archive.json と problem.json に保存）。
+    使い方: tag [--add dp,graph] [--remove greedy] [--difficulty 1200] [--compare yesno] [--output-limit 256MB] [--checklist "A;B"]
+    --compareはこの問題のtestでの比較方法（config.jsonのcompareに重ねる。defaultで消す）。
+    --output-limitはこの問題のtestでの1ケースの出力の上限（config.jsonのtest.output_limitより優先。0で上限なし、defaultで消す）。
+    --checklistはこの問題で提出前に確かめる項目（;区切り。config.jsonのsubmit.checklistのあとに聞く。defaultで消す）。
+    何も指定しなければ現在のタグを表示する。
+    """
+    def __init__(self, options=None, index=None):
+        self.options = options or {}
+        self.index = index or ArchiveIndex()
+
+    def difficulty(self):
+        value = self.options.get("difficulty")
+        if value is None or value is True:
+            return None
+        try:
+            return int(value)
+        except ValueError:
+            raise ValueError(f"--difficulty には整数を指定してください: {value}")
+
+    def compare(self):
+        """--compareの値 → problem.jsonに残す比較設定（defaultならNone）"""
+        value = self.options.get("compare")
+        return None if value == DEFAULT_COMPARE else parse_compare_option(value)
+
+    def output_limit(self):
+        """--output-limitの値 → problem.jsonに残すバイト数（defaultならNone）"""
+        value = self.options.get("output-limit")
+        if value == DEFAULT_COMPARE:
+            return None
+        try:
+            return parse_output_limit(value)
+        except ValueError as e:
+            raise ValueError(f"--output-limit: {e}")
+
+    def checklist(self):
+        """--checklistの値 → problem.jsonに残す項目の一覧（defaultならNone）"""
+        value = self.options.get("checklist")
+        return None if value == DEFAULT_COMPARE else parse_checklist(value) or None
+
+    async def tag(self, contest_name, problem_name):
+        add = parse_tags(self.options.get("add"))
+        remove = parse_tags(self.options.get("remove"))
+        difficulty = self.difficulty()
+        set_compare = self.options.get("compare") not in (None, True)
+        compare = self.compare() if set_compare else None
+        set_output_limit = self.options.get("output-limit") not in (None, True)
+        output_limit = self.output_limit() if set_output_limit else None
+        set_checklist = self.options.get("checklist") not in (None, True)
+        checklist = self.checklist() if set_checklist else None
+        if add or remove or difficulty is not None:
+            entry = self.index.set_tags(contest_name, problem_name, add=add, remove=remove, difficulty=difficulty)
+        else:
+            entry = self.index.get(contest_name, problem_name) or {"tags": [], "difficulty": None}
+        if set_compare:
+            entry = self.index.update(contest_name, problem_name, lambda e: e.update(compare=compare))
+        if set_output_limit:
+            entry = self.index.update(contest_name, problem_name, lambda e: e.update(output_limit=output_limit))
+        if set_checklist:
+            entry = self.index.update(contest_name, problem_name, lambda e: e.update(checklist=checklist))
+        tags = ", ".join(entry.get("tags", [])) or "なし"
+        difficulty = entry.get("difficulty")
+        line = f"[tag] {contest_name} {problem_name}: タグ {tags} / 難易度 {difficulty if difficulty is not None else '未設定'}"
+        if entry.get("compare"):
+            line += f" / 比較 {entry['compare']}"
+        if entry.get("output_limit") is not None:
+            line += f" / 出力の上限 {entry['output_limit'] or 'なし'}{'バイト' if entry['output_limit'] else ''}"
+        if entry.get("checklist"):
+            line += f" / チェックリスト {CHECKLIST_SEPARATOR.join(entry['checklist'])}"
+        print(line)
+        return entry
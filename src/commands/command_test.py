@@ -1,23 +1,225 @@
 import os
+import random
+import time
+from concurrent.futures import ThreadPoolExecutor
 from src.path_manager.unified_path_manager import UnifiedPathManager
 from src.path_manager.file_operator import FileOperator
 # === 定数定義 ===
 HOST_PROJECT_ROOT = os.path.abspath(".")
 CONTAINER_WORKSPACE = "/workspace"
 TEMP_DIR = os.path.abspath(".temp")
+DEFAULT_TIME_LIMIT = 2.0  # 秒（config.jsonのtime_limitで上書き）
+TIMING_CANDIDATE_RATIO = 0.5  # 実行時間がTLのこの割合以上のケースを--accurate-timingで計測し直す
+TIMING_PIN_CORE = 0  # 計測し直すときに固定するCPUコア
+TL_WARN_RATIO = 0.7  # 実行時間がTLのこの割合以上のケースを警告する（config.jsonのtest.tl_warn_ratioで変更、0で無効）
+SLOWDOWN_RATIO = 1.2  # 最も遅いケースが前回のこの倍率を超えたら知らせる
+SKIPPED_VERDICT = "SKIP"  # --budget / test.total_budget_secondsを使い切って実行しなかったケース
+SEED_ENV = "CPH_SEED"  # 解答に渡す乱数のシード（run.jsonに残し、test --seedで同じ値を渡し直せる）
+MAX_SEED = 2 ** 31 - 1
+DURATION_UNITS = {"s": 1, "m": 60, "h": 3600}
+# ケースの実行順。既定は前回失敗したケースから（履歴が無ければ元の順序）
+CASE_ORDERS = ("natural", "failed-first", "slowest-first")
+DEFAULT_CASE_ORDER = "failed-first"
 
-from .test_result_formatter import ResultFormatter
-from src.environment.test_language_handler import HANDLERS
+def decide_seed(options):
+    """--seedがあればその値、無ければ実行ごとに選ぶ"""
+    value = (options or {}).get("seed")
+    if value is None or value is True:
+        return random.randint(1, MAX_SEED)
+    try:
+        return int(value)
+    except ValueError:
+        raise ValueError(f"--seed には整数を指定してください: {value}")
+
+def is_sample_case(in_file):
+    """oj downloadで取得したサンプル（sample-1.in など）かどうか"""
+    return os.path.basename(str(in_file)).startswith("sample")
+
+def parse_duration(value):
+    """--budgetの値（90s / 1.5m / 90）を秒に変換する"""
+    text = str(value).strip().lower()
+    unit = DURATION_UNITS.get(text[-1:])
+    try:
+        seconds = float(text[:-1] if unit else text) * (unit or 1)
+    except ValueError:
+        raise ValueError(f"--budget の値が不正です: {value}（例: 90s / 1.5m）")
+    if seconds <= 0:
+        raise ValueError(f"--budget には正の時間を指定してください: {value}")
+    return seconds
+
+def is_skipped(result):
+    return isinstance(result, dict) and result.get("verdict") == SKIPPED_VERDICT
+
+def slowest_time(cases):
+    """ケースの中で最も長い実行時間とそのケース名（打ち切ったケースは除く）"""
+    timed = [(c["time"], c["name"]) for c in cases if c.get("time") is not None and c.get("verdict") != SKIPPED_VERDICT]
+    return max(timed) if timed else None
+
+def auto_jobs(case_count, cpu_count=None):
+    """テストの並列数 = min(論理CPU数, ケース数)。ケースが無くても1は確保する"""
+    cpu_count = cpu_count or os.cpu_count() or 1
+    return max(1, min(cpu_count, case_count))
+
+from .test_result_formatter import ResultFormatter, judge_verdict
+from .progress_reporter import ProgressReporter
+from .runtime_errors import RuntimeErrorClassifier
+from .comparator import create_comparator, merge_compare_specs, parse_compare_option
+from .hooks import HookRunner
+from .opener import Opener
+from .command_fix import CommandFix
+from .formula_cases import expand_formula_cases
+from .case_check import CaseChecker, report
+from .problem_lock import ProblemLock, active_problem
+from .archive_index import ArchiveIndex
+from .run_artifacts import RunArtifacts
+from .case_budget import BUDGET_EXHAUSTED, CaseBudget
+from .constraints import format_constraints, show_constraints
+from .result_cache import ResultCache, source_hash
+from .cache_epoch import refresh_epoch
+from .toolchain import DEFAULT_SITE
+from .test_layout import TestLayout, ALL_SET, GROUP_PREFIX
+from .case_storage import ZSTD_SUFFIX, alternative_expected_files, case_name, case_stem, expected_file, find_case_file, is_compressed, read_case
+from src.config_json_manager import ConfigJsonManager
+from src.environment.diagnostics import DiagnosticStore
+from src.file_operator import run_blocking
+from src import cancellation, command_log, cpu_time, events
+from src.output_limit import OLE, OutputLimit
+from src.input_passing import shares_input_file
+from src.environment.test_language_handler import HANDLERS, get_handler
 from src.info_json_manager import InfoJsonManager
 from src.execution_client.container.client import ContainerClient
 from src.environment.test_environment import DockerTestExecutionEnvironment
 from src.execution_client.container.image_manager import ContainerImageManager
 
 class CommandTest:
-    def __init__(self, file_manager, test_env):
+    def __init__(self, file_manager, test_env, progress=None, opener=None, options=None, problem_settings=True):
         self.file_manager = file_manager
         self.env = test_env
+        self.options = options or {}
+        # contest_currentの問題の設定（problem.json）を使うか。test --dirのように別の問題を実行するときはFalse
+        self.problem_settings = problem_settings
         self.upm = UnifiedPathManager()
+        self.progress = progress or ProgressReporter()
+        self.opener = opener or Opener()
+        self.comparator = None
+        self.seed = None
+        self.epoch = None
+        self.runtime_errors = None
+        # このtestで実行したdocker / ojコマンドを取り出すための区切り
+        self.command_mark = command_log.get_log().mark()
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def get_comparator(self):
+        # --compareがあればそれを、なければconfig.jsonのcompare設定に問題ごとの設定（tag --compare）を重ねて決定（初回のみ読み込む）
+        if self.comparator is None:
+            option = self.options.get("compare")
+            spec = parse_compare_option(option) if option else merge_compare_specs(self.get_config().get_compare(), self.problem_compare())
+            self.comparator = create_comparator(spec)
+        return self.comparator
+
+    def problem_setting(self, key):
+        """contest_currentの問題のproblem.jsonにある設定（tag --compare / --output-limit）。無ければ（使わないときも）None"""
+        if not self.problem_settings:
+            return None
+        contest_name, problem_name = active_problem(self.upm)
+        if contest_name is None or problem_name is None:
+            return None
+        entry = ArchiveIndex(upm=self.upm).get(contest_name, problem_name) or {}
+        return entry.get(key)
+
+    def print_constraints(self):
+        """制約の読み落としでWAにならないよう、problem.jsonに残した制約をtestの冒頭に出す"""
+        if not show_constraints(self.get_config().get_test()):
+            return
+        for line in format_constraints(self.problem_setting("constraints")):
+            print(line)
+
+    def problem_compare(self):
+        return self.problem_setting("compare")
+
+    def get_output_limit(self):
+        """解答の標準出力の上限。問題ごとの設定（tag --output-limit）があればconfig.jsonのtest.output_limitより優先する"""
+        return OutputLimit.from_config(self.get_config().get_test(), self.problem_setting("output_limit"))
+
+    def get_runtime_error_classifier(self):
+        # REの説明の表（config.jsonのtest.runtime_errorsを組み込みの表の前に足す。初回のみ読み込む）
+        if self.runtime_errors is None:
+            self.runtime_errors = RuntimeErrorClassifier.from_config(self.get_config().get_test())
+        return self.runtime_errors
+
+    def verdict_of(self, result):
+        if "verdict" in result:
+            return result["verdict"]
+        return judge_verdict(result, self.get_comparator())
+
+    def decide_jobs(self, case_count):
+        """
+        --jobsが指定されていればそれを上限に、なければCPU数とケース数から並列数を決める。
+        入力をtest.cwdの同じファイルに書き出すときは、ケースどうしで上書きしないよう1つずつ
+        """
+        if shares_input_file(self.get_config().get_test()):
+            return 1
+        jobs = self.options.get("jobs")
+        if jobs:
+            return max(1, min(int(jobs), max(case_count, 1)))
+        return auto_jobs(case_count)
+
+    def get_budget(self):
+        value = self.options.get("budget")
+        return None if value is None or value is True else parse_duration(value)
+
+    def prioritize_cases(self, in_files, failures):
+        """
+        --budget用の実行順。サンプル → 直近に失敗したケース（新しい失敗ほど先）→ その他。
+        同じ優先度の中では元の順序を保つ。
+        """
+        order = {f: i for i, f in enumerate(in_files)}
+        def key(f):
+            name = case_name(f)
+            return (not is_sample_case(name), failures.get(name, float("inf")), order[f])
+        return sorted(in_files, key=key)
+
+    def get_case_order(self):
+        """--order、config.jsonのtest.order、既定の順に決める。--budgetだけのときはNone（予算用の順序を使う）"""
+        order = self.options.get("order") or self.get_config().get_test().get("order")
+        if order is None and self.get_budget() is not None:
+            return None
+        order = order or DEFAULT_CASE_ORDER
+        if order not in CASE_ORDERS:
+            raise ValueError(f"ケースの実行順が不正です: {order}（{', '.join(CASE_ORDERS)}）")
+        return order
+
+    def order_cases(self, in_files, contest_name, problem_name, language_name):
+        """実行履歴（.cph/runs）をもとにケースを並べ替える。同じ優先度の中では元の順序を保つ"""
+        order = self.get_case_order()
+        if order == "natural":
+            return list(in_files)
+        if order is None:
+            return self.prioritize_cases(in_files, RunArtifacts.recent_failures(contest_name, problem_name, language_name))
+        index = {f: i for i, f in enumerate(in_files)}
+        if order == "failed-first":
+            failures = RunArtifacts.recent_failures(contest_name, problem_name, language_name)
+            return sorted(in_files, key=lambda f: (failures.get(case_name(f), float("inf")), index[f]))
+        times = RunArtifacts.recent_times(contest_name, problem_name, language_name)
+        return sorted(in_files, key=lambda f: (-times.get(case_name(f), 0.0), index[f]))
+
+    def skipped_result(self, in_file, reason=None):
+        return {
+            "result": (None, "", ""),
+            "expected": "",
+            "time": 0.0,
+            "name": case_name(in_file),
+            "in_file": in_file,
+            "container": None,
+            "attempt": 0,
+            "verdict": SKIPPED_VERDICT,
+            "skip_reason": reason,
+        }
+
+    def get_time_limit(self):
+        return self.get_config().get_time_limit() or DEFAULT_TIME_LIMIT
 
     def prepare_test_environment(self, contest_name, problem_name, language_name):
         # DockerTestExecutionEnvironmentに移譲
@@ -25,27 +227,95 @@ class CommandTest:
         temp_test_dir = self.env.prepare_test_cases(contest_name, problem_name)
         return temp_source_path, temp_test_dir
 
+    async def aprepare_test_environment(self, contest_name, problem_name, language_name):
+        """prepare_test_environmentの非同期版。ソースは別スレッドで、テストケースは並行してコピーする"""
+        temp_source_path = await run_blocking(self.env.prepare_source_code, contest_name, problem_name, language_name)
+        if hasattr(self.env, "aprepare_test_cases"):
+            temp_test_dir = await self.env.aprepare_test_cases(contest_name, problem_name)
+        else:
+            temp_test_dir = await run_blocking(self.env.prepare_test_cases, contest_name, problem_name)
+        return temp_source_path, temp_test_dir
+
     def collect_test_cases(self, temp_test_dir, file_operator=None):
         import glob
         import os
+        # 大きなケースはzstdで圧縮（*.in.zst）して置かれていることがある。小課題ごとのサンプルはgroup_N/の中
+        patterns = [f"{temp_test_dir}/*.in*", f"{temp_test_dir}/{GROUP_PREFIX}*/*.in*"]
         if file_operator:
-            in_files = sorted(file_operator.glob(f"{temp_test_dir}/*.in"))
+            found = [f for p in patterns for f in file_operator.glob(p)]
         else:
-            in_files = sorted(glob.glob(f"{temp_test_dir}/*.in"))
-        out_files = [str(f).replace('.in', '.out') for f in in_files]
+            found = [f for p in patterns for f in glob.glob(p)]
+        in_files = sorted({f for f in found if str(f).endswith((".in", ".in" + ZSTD_SUFFIX))})
+        out_files = [expected_file(f) for f in in_files]
         return in_files, out_files
 
+    def build_requirements(self, language_name, test_container_count):
+        return [
+            {"type": "test", "language": language_name, "count": test_container_count, "volumes": {
+                HOST_PROJECT_ROOT: CONTAINER_WORKSPACE,
+                TEMP_DIR: "/workspace/.temp"
+            }},
+            {"type": "ojtools", "count": 1, "volumes": {
+                HOST_PROJECT_ROOT: CONTAINER_WORKSPACE,
+                TEMP_DIR: "/workspace/.temp",
+                "/home/cphelper/.local/share/online-judge-tools/cookie.jar": "/root/.local/share/online-judge-tools/cookie.jar"
+            }}
+        ]
+
     def get_test_containers_from_info(self):
         info_path = self.upm.info_json()
         manager = InfoJsonManager(info_path)
         return [c["name"] for c in manager.get_containers(type="test")]
 
+    def uses_containers(self):
+        return getattr(self.env, "uses_containers", True)
+
+    def get_test_workers(self, language_name, case_count):
+        """解答を実行する先。コンテナ実行ならsystem_info.jsonのコンテナ、ローカル実行なら並列数ぶんの名前"""
+        if self.uses_containers():
+            return self.get_test_containers_from_info()
+        return [f"local-{language_name}-{i + 1}" for i in range(self.decide_jobs(case_count))]
+
     def to_container_path(self, host_path):
         return self.env.to_container_path(host_path)
 
+    def build_client(self, ctl):
+        # コンテナ実行ならContainerClient、ローカル実行ならExecutionManagerでビルドする
+        return ctl if self.uses_containers() else getattr(self.env, "manager", ctl)
+
     def build_in_container(self, ctl, handler, container, source_path):
         return handler.build(ctl, container, source_path)
 
+    def compile_warnings(self, handler, stdout, stderr):
+        # compile_warningsを持たないハンドラはビルドの警告を出さないものとして扱う
+        extract = getattr(handler, "compile_warnings", None)
+        return extract(stdout, stderr) if extract else []
+
+    def diagnostics_of(self, handler, kind, *output):
+        # 診断を出さないハンドラは位置の分かるエラーが無いものとして扱う
+        extract = getattr(handler, kind, None)
+        return extract(*output) if extract else []
+
+    def record_diagnostics(self, language_name, stage, diagnostics, case=None):
+        """fixで開けるよう、直近のエラーの位置を残す（無ければ前回のものを消す）"""
+        try:
+            return DiagnosticStore(self.upm).save(language_name, stage, diagnostics, case=case)
+        except OSError:
+            return None
+
+    def record_runtime_diagnostics(self, results, handler, language_name):
+        failed = next((r for r in results if not is_skipped(r) and r["result"][0] != 0), None)
+        if failed is None:
+            return self.record_diagnostics(language_name, "runtime", [])
+        diagnostics = self.diagnostics_of(handler, "runtime_diagnostics", failed["result"][2])
+        return self.record_diagnostics(language_name, "runtime", diagnostics, case=failed["name"])
+
+    def jump_to_error(self):
+        # editor.auto_jumpが有効なら、testの失敗後にエラーの位置をエディタで開く
+        if self.get_config().get_editor().get("auto_jump"):
+            return CommandFix(self.options, opener=self.opener, upm=self.upm).jump()
+        return None
+
     def select_container_for_case(self, test_containers, i):
         return test_containers[i] if i < len(test_containers) else test_containers[-1]
 
@@ -70,101 +340,367 @@ class CommandTest:
             "result": (0 if ok else 1, stdout, stderr),
             "expected": expected,
             "time": 0.0,
-            "name": os.path.basename(in_file),
+            "name": case_name(in_file),
             "in_file": in_file,
             "container": container,
             "attempt": attempt,
         }
 
-    async def run_test_cases(self, temp_source_path, temp_in_files, language_name):
+    async def run_test_cases(self, temp_source_path, temp_in_files, language_name, deadline=None):
         import os
-        test_containers = self.get_test_containers_from_info()
+        test_containers = self.get_test_workers(language_name, len(temp_in_files))
         ctl = ContainerClient()
-        handler = HANDLERS[language_name]
+        handler = get_handler(language_name)
         # --- ビルド工程 ---
         abs_temp_source_path = os.path.abspath(temp_source_path)
         cont_temp_source_path = self.to_container_path(abs_temp_source_path)
-        ok, stdout, stderr = self.build_in_container(ctl, handler, test_containers[0], cont_temp_source_path)
+        self.progress.emit("build_started", language_name=language_name)
+        ok, stdout, stderr = self.build_in_container(self.build_client(ctl), handler, test_containers[0], cont_temp_source_path)
+        warnings = self.compile_warnings(handler, stdout, stderr) if ok else []
+        self.progress.emit("build_finished", language_name=language_name, ok=bool(ok), warnings=len(warnings))
         if not ok:
+            self.record_diagnostics(language_name, "build", self.diagnostics_of(handler, "compile_diagnostics", stdout, stderr))
             print(f"[エラー] ビルド失敗\n{stderr}")
             return []
-        # --- テスト実行 ---
-        results = []
-        for i, in_file in enumerate(temp_in_files):
-            container = self.select_container_for_case(test_containers, i)
+        if warnings:
+            print(f"[build] コンパイラの警告 {len(warnings)}件（判定はACのまま、結果に「警告あり」と表示します）")
+            for w in warnings:
+                print(f"  warning: {w}")
+        # --- テスト実行（並列数はケース数とCPU数から自動決定） ---
+        total = len(temp_in_files)
+        jobs = self.decide_jobs(total)
+        # ケースの実行時間の予算（ビルドは含めない）。残りの予算を残りのケースに割り振る
+        case_budget = CaseBudget.from_config(self.get_config().get_test(), total, jobs, self.get_time_limit())
+        def run_case(args):
+            i, in_file = args
+            # watchで新しい実行に置き換えられたら、まだ始めていないケースは実行しない
+            cancellation.current().check()
+            if deadline is not None and time.perf_counter() >= deadline:
+                # 予算を使い切ったら残りのケースは実行しない（実行中のケースは最後まで待つ）
+                self.progress.emit("case_skipped", name=case_name(in_file), index=i + 1, total=total)
+                return self.skipped_result(in_file)
+            limit = {}
+            if case_budget is not None:
+                share = case_budget.start_case()
+                if share is None:
+                    self.progress.emit("case_skipped", name=case_name(in_file), index=i + 1, total=total)
+                    return self.skipped_result(in_file, BUDGET_EXHAUSTED)
+                limit["wall_limit"] = share
+            container = self.select_container_for_case(test_containers, i % len(test_containers))
+            return self.run_single_case(ctl, container, in_file, language_name, cont_temp_source_path, i, total, **limit)
+        with ThreadPoolExecutor(max_workers=jobs) as executor:
+            results = list(executor.map(run_case, enumerate(temp_in_files)))
+        for r in results:
+            # 警告はビルド単位だが、表やJSONで各ケースから参照できるようにする
+            r["warnings"] = warnings
+        self.record_runtime_diagnostics(results, handler, language_name)
+        if self.options.get("accurate-timing"):
+            self.remeasure_timing_candidates(results, handler, test_containers[0], cont_temp_source_path)
+        if self.options.get("ub-check"):
+            self.detect_ub(results, language_name, self.build_client(ctl), handler, test_containers[0], cont_temp_source_path)
+        return results
+
+    def detect_ub(self, results, language_name, ctl, handler, container, cont_temp_source_path):
+        """
+        --ub-check: 失敗したケースをdebugビルド（オーバーフロー検査あり）でも実行し、
+        releaseビルドと結果が異なるケースを未定義動作・オーバーフローの疑いとして報告する。
+        ctlは通常のビルドと同じもの（build_client）を渡す
+        """
+        import os
+        failing = [r for r in results if r.get("verdict") not in ("AC", SKIPPED_VERDICT)]
+        if not failing:
+            return []
+        if "debug" not in handler.BUILD_PRESETS:
+            print(f"[ub-check] {language_name} はdebugビルドに対応していないためスキップします")
+            return []
+        ok, _, stderr = handler.build(ctl, container, cont_temp_source_path, preset="debug")
+        if not ok:
+            print(f"[ub-check] debugビルドに失敗しました\n{stderr}")
+            return []
+        suspects = []
+        for r in failing:
+            cont_in_file = self.to_container_path(os.path.abspath(str(r["in_file"])))
+            ok, stdout, stderr = self.env.exec_command(container, handler.run_command(cont_temp_source_path, preset="debug"), cont_in_file)
+            release_ok = r["result"][0] == 0
+            release_stdout = r["result"][1] or ""
+            if ok == release_ok and (stdout or "").strip() == release_stdout.strip():
+                continue
+            r["ub_suspect"] = {"debug_ok": ok, "debug_stdout": stdout, "debug_stderr": stderr}
+            suspects.append(r)
+            print(f"[ub-check] {r['name']}: debugとreleaseで結果が異なります（未定義動作・オーバーフローの可能性）")
+            if stderr and stderr.strip():
+                print(stderr.strip().splitlines()[-1])
+        if not suspects:
+            print("[ub-check] debug/releaseで結果が異なるケースはありませんでした")
+        return suspects
+
+    def read_expected(self, in_file):
+        import os
+        out_file = expected_file(in_file)
+        if is_compressed(out_file):
+            return read_case(out_file)
+        file_operator = self.file_manager.file_operator if self.file_manager else None
+        if file_operator:
+            if file_operator.exists(out_file):
+                with file_operator.open(out_file, "r", encoding="utf-8") as f:
+                    return f.read()
+        else:
+            if os.path.exists(out_file):
+                with open(out_file, "r", encoding="utf-8") as f:
+                    return f.read()
+        return ""
+
+    def read_expected_outputs(self, in_file):
+        """
+        (期待出力, もう一つの期待出力の一覧)。sample-3.a.out などがあれば、.outとどれか1つに一致すればAC。
+        .outが無くもう一つの期待出力だけのときは、その1つ目を期待出力として表示に使う
+        """
+        alternatives = [read_case(f) for f in alternative_expected_files(in_file)]
+        if alternatives and find_case_file(case_stem(in_file) + ".out") is None:
+            return alternatives[0], alternatives[1:]
+        return self.read_expected(in_file), alternatives
+
+    def run_single_case(self, ctl, container, in_file, language_name, cont_temp_source_path, i, total, wall_limit=None):
+        import os
+        self.progress.emit("case_started", name=case_name(in_file), index=i + 1, total=total)
+        if self.uses_containers():
             image = ContainerImageManager().ensure_image("ojtools") if container.startswith("cph_ojtools") else language_name
             self.ensure_container_running(ctl, container, image)
-            abs_in_file = os.path.abspath(in_file)
-            cont_in_file = self.to_container_path(abs_in_file)
-            ok, stdout, stderr, attempt = self.env.run_test_case(language_name, container, cont_in_file, cont_temp_source_path, retry=3)
-            out_file = str(in_file).replace('.in', '.out')
-            expected = ""
-            file_operator = self.file_manager.file_operator if self.file_manager else None
-            if file_operator:
-                if file_operator.exists(out_file):
-                    with file_operator.open(out_file, "r", encoding="utf-8") as f:
-                        expected = f.read()
-            else:
-                if os.path.exists(out_file):
-                    with open(out_file, "r", encoding="utf-8") as f:
-                        expected = f.read()
-            result = self.collect_test_result(ok, stdout, stderr, expected, in_file, container, attempt)
-            results.append(result)
-        return results
+        abs_in_file = os.path.abspath(in_file)
+        cont_in_file = self.to_container_path(abs_in_file)
+        cpu_time.take()
+        cpu_time.take_memory()
+        start = time.perf_counter()
+        limit = {"wall_limit": wall_limit} if wall_limit else {}
+        ok, stdout, stderr, attempt = self.env.run_test_case(language_name, container, cont_in_file, cont_temp_source_path, retry=3, **limit)
+        elapsed = time.perf_counter() - start
+        if wall_limit and elapsed >= wall_limit and elapsed < self.get_time_limit():
+            # TLより前に予算の持ち時間で止めたケースは、判定できないので実行しなかったものとして扱う
+            self.progress.emit("case_skipped", name=case_name(in_file), index=i + 1, total=total)
+            return {**self.skipped_result(in_file, BUDGET_EXHAUSTED), "time": elapsed}
+        expected, alternatives = self.read_expected_outputs(in_file)
+        output_limit = getattr(self.env, "output_limit", None)
+        exceeded = output_limit is not None and output_limit.exceeded(stdout)
+        if exceeded:
+            # 切った出力を期待出力と比べても意味がないので、OLEとして上限までを残す
+            stdout = output_limit.truncate(stdout)
+        result = self.collect_test_result(ok, stdout, stderr, expected, in_file, container, attempt)
+        if exceeded:
+            result["output_limit_exceeded"] = output_limit.limit
+        if alternatives:
+            result["alternatives"] = alternatives
+        result["time"] = elapsed
+        # ジャッジが測るのはCPU時間なので、測れた環境では壁時計の時間と並べて残す
+        result["cpu_time"] = cpu_time.take()
+        result["memory_kb"] = cpu_time.take_memory()
+        result["verdict"] = self.verdict_of(result)
+        if result["verdict"] == "RE":
+            runtime_error = self.get_runtime_error_classifier().classify(stderr, language_name)
+            if runtime_error:
+                result["runtime_error"] = runtime_error
+        self.progress.emit("case_finished", name=result["name"], index=i + 1, total=total, verdict=result["verdict"], time=result["time"], cpu_time=result["cpu_time"])
+        return result
+
+    def timing_candidates(self, results, time_limit):
+        """並列実行の計測時間がTLに近い（TIMING_CANDIDATE_RATIO以上）ケースを選ぶ。RE・OLEは対象外"""
+        return [r for r in results if r.get("verdict") not in ("RE", OLE) and r.get("time", 0.0) >= time_limit * TIMING_CANDIDATE_RATIO]
+
+    def remeasure_timing_candidates(self, results, handler, container, cont_temp_source_path):
+        """
+        --accurate-timing: TLに近いケースだけを1ケースずつ、コアを固定して計測し直す。
+        並列実行による時間のぶれでTLE判定が変わらないようにするため。
+        """
+        import os
+        candidates = self.timing_candidates(results, self.get_time_limit())
+        for r in candidates:
+            cont_in_file = self.to_container_path(os.path.abspath(str(r["in_file"])))
+            run_cmd = handler.run_command(cont_temp_source_path)
+            pinned = ["taskset", "-c", str(TIMING_PIN_CORE)] + run_cmd
+            start = time.perf_counter()
+            ok, stdout, stderr = self.env.exec_command(container, pinned, cont_in_file)
+            if not ok and stderr and "taskset" in stderr:
+                # tasksetが使えない環境ではコア固定なしで直列実行する
+                start = time.perf_counter()
+                ok, stdout, stderr = self.env.exec_command(container, run_cmd, cont_in_file)
+            r["time"] = time.perf_counter() - start
+            r["timing"] = "serial"
+            print(f"[accurate-timing] {r['name']}: {r['time']:.3f}s（直列・コア{TIMING_PIN_CORE}固定で再計測）")
+        return candidates
+
+    def get_tl_warn_ratio(self):
+        value = self.get_config().get_test().get("tl_warn_ratio", TL_WARN_RATIO)
+        return float(value) if value else None
+
+    @staticmethod
+    def cpu_note(result):
+        """TL警告に添える、CPU時間と「計算が重いのか待っているのか」の区別"""
+        cpu = result.get("cpu_time")
+        if cpu is None:
+            return ""
+        if cpu_time.is_idle_bound(result["time"], cpu):
+            return f" CPU {cpu:.3f}s: 待ち（I/O・sleep）が大半で、CPU時間で測るジャッジではTLEにならない可能性があります"
+        return f" CPU {cpu:.3f}s: 計算が重いケースです"
+
+    def report_time_headroom(self, results, contest_name, problem_name, language_name):
+        """
+        TLのtl_warn_ratio以上かかったケースを警告し、最も遅いケースを過去の実行と比べて表示する。
+        ジャッジでTLEになる前に、実行時間がTLに近づいていることに気づけるようにするため。
+        """
+        time_limit = self.get_time_limit()
+        ratio = self.get_tl_warn_ratio()
+        warned = []
+        for r in results:
+            if ratio and not is_skipped(r) and r.get("time", 0.0) >= time_limit * ratio:
+                warned.append(r)
+                print(f"[TL警告] {r['name']}: {r['time']:.3f}s（TL {time_limit:g}s の{r['time'] / time_limit:.0%}）{self.cpu_note(r)}")
+        current = slowest_time(results)
+        if current is None:
+            return warned
+        line = f"[slowest] {current[1]}: {current[0]:.3f}s（TLの{current[0] / time_limit:.0%}）"
+        history = [t for t in (slowest_time(run.get("cases", [])) for run in RunArtifacts.history(contest_name, problem_name, language_name)) if t]
+        if history:
+            previous, worst = history[0], max(history)
+            line += f" / 前回 {previous[0]:.3f}s（{previous[1]}） / 過去最遅 {worst[0]:.3f}s（{worst[1]}）"
+            if current[0] > previous[0] * SLOWDOWN_RATIO:
+                line += " ※前回より遅くなっています"
+        print(line)
+        return warned
 
     def print_test_results(self, results):
         for r in results:
+            if is_skipped(r):
+                continue
             print(ResultFormatter(r).format())
             print("")
+        skipped = [r["name"] for r in results if is_skipped(r) and r.get("skip_reason") != BUDGET_EXHAUSTED]
+        if skipped:
+            print(f"[budget] 予算（{self.options.get('budget')}）を使い切ったため {len(skipped)}/{len(results)} ケースを実行していません: {', '.join(skipped)}")
+        exhausted = [r["name"] for r in results if is_skipped(r) and r.get("skip_reason") == BUDGET_EXHAUSTED]
+        if exhausted:
+            seconds = self.get_config().get_test().get("total_budget_seconds")
+            print(f"[budget] test.total_budget_seconds（{seconds}秒）を使い切ったため {len(exhausted)}/{len(results)} ケースは "
+                  f"Skipped(BudgetExhausted) です: {', '.join(exhausted)}")
+            print("[budget] 全ケースを実行するには予算を増やすか、--set で実行するケースを絞ってください")
 
     async def run_test(self, contest_name, problem_name, language_name):
-        import pathlib
-        file_operator = self.file_manager.file_operator if self.file_manager else None
-        temp_source_path, temp_test_dir = self.prepare_test_environment(contest_name, problem_name, language_name)
-        temp_in_files, _ = self.collect_test_cases(temp_test_dir, file_operator)
-        # --- 必要なコンテナ数を調整し、system_info.jsonを最新化 ---
-        test_case_count = len(temp_in_files)
-        requirements = [
-            {"type": "test", "language": language_name, "count": test_case_count, "volumes": {
-                HOST_PROJECT_ROOT: CONTAINER_WORKSPACE,
-                TEMP_DIR: "/workspace/.temp"
-            }},
-            {"type": "ojtools", "count": 1, "volumes": {
-                HOST_PROJECT_ROOT: CONTAINER_WORKSPACE,
-                TEMP_DIR: "/workspace/.temp",
-                "/home/cphelper/.local/share/online-judge-tools/cookie.jar": "/root/.local/share/online-judge-tools/cookie.jar"
-            }}
-        ]
-        containers = self.env.adjust_containers(requirements, contest_name, problem_name, language_name)
-        # --- テスト実行 ---
-        results = await self.run_test_cases(temp_source_path, temp_in_files, language_name)
+        # contest_currentと違う問題を指定されたら、取り違えを知らせる
+        if not ProblemLock.from_config(self.get_config().get_test(), self.options, self.upm).check(contest_name, problem_name):
+            print("[test] 中止しました")
+            return []
+        self.print_constraints()
+        results = await self.run_test_return_results(contest_name, problem_name, language_name)
         self.print_test_results(results)
+        if not results or not self.is_all_ac(results):
+            if self.seed is not None:
+                print(f"[test] {SEED_ENV}={self.seed}（同じシードで再実行: test --seed {self.seed}）")
+            self.jump_to_error()
+        # 今回の実行を記録する前に、過去の実行と比べる
+        self.report_time_headroom(results, contest_name, problem_name, language_name)
+        artifacts = self.record_run(results, contest_name, problem_name, language_name)
+        # 利用統計の記録やフックなどの反応は、イベントを購読する側（src/event_subscribers.py）で行う
+        ran = [r for r in results if not is_skipped(r)]
+        events.publish(events.TEST_RUN_COMPLETED, contest=contest_name, problem=problem_name, language=language_name,
+                       results=ran, passed=sum(1 for r in ran if self.verdict_of(r) == "AC"), total=len(ran),
+                       all_ac=bool(results) and self.is_all_ac(results), run_id=artifacts.run_id)
+        if self.options.get("set") in (None, ALL_SET) and not any(is_skipped(r) for r in results):
+            # 一部のセットだけの結果や予算で打ち切った結果はフル実行の結果として残さない
+            self.record_result_cache(results, contest_name, problem_name, language_name)
+        self.run_visualize_hooks(results, artifacts)
+        return results
+
+    def check_cases(self, test_dir):
+        """test.checkが有効なら、実行の前にケースの組・重複・入力の制約を確かめて知らせる（実行は止めない）"""
+        checker = CaseChecker(test_dir, self.get_config().get_test(), cwd=str(self.upm.project_path.root))
+        return report(checker.check(), log_prefix="[test]")
+
+    def record_result_cache(self, results, contest_name, problem_name, language_name):
+        # フル実行の結果をソースのハッシュと一緒に残し、提出前チェックで再利用する
+        hash_value = source_hash(self.upm.contest_current(language_name))
+        if hash_value is None:
+            return None
+        cache = ResultCache()
+        return cache.put(cache.key(contest_name, problem_name, language_name), hash_value, results, epoch=self.epoch)
+
+    def cache_epoch(self, language_name):
+        """ジャッジのツールチェイン（config.jsonのtoolchain）が変わっていれば、この言語のキャッシュを消してから今のエポックを返す"""
+        self.epoch = refresh_epoch(self.get_config(), language_name, self.options.get("site", DEFAULT_SITE), self.upm)
+        return self.epoch
+
+    def record_run(self, results, contest_name, problem_name, language_name):
+        # 実行結果を.cph/runs/{run_id}/に保存し、フックなどから参照できるようにする
+        artifacts = RunArtifacts()
+        artifacts.save_results(results, contest_name=contest_name, problem_name=problem_name, language_name=language_name, seed=self.seed,
+                               solution_env=dict(self.env.solution_env) if self.env is not None else {})
+        artifacts.save_commands(command_log.get_log().since(self.command_mark))
+        return artifacts
 
-    async def run_test_return_results(self, contest_name, problem_name, language_name):
+    def run_visualize_hooks(self, results, artifacts):
+        """
+        失敗したケースごとにvisualizeフックを実行し、生成された画像/HTMLを開く。
+        成果物は実行結果と同じ.cph/runs/{run_id}/に保存される。
+        """
+        hooks = HookRunner(self.get_config().get_hooks())
+        hook = hooks.get("visualize")
+        if hook is None:
+            return []
+        produced = []
+        for r in results:
+            if self.verdict_of(r) in ("AC", SKIPPED_VERDICT):
+                continue
+            in_file = os.path.abspath(str(r.get("in_file", "")))
+            values = {
+                "case": RunArtifacts.case_stem(r["name"]),
+                "input": in_file,
+                "output": artifacts.case_path(r["name"], "stdout"),
+                "expected": expected_file(in_file) if case_name(in_file).endswith(".in") else "",
+                "artifact_dir": artifacts.path,
+            }
+            artifact = artifacts.path / hooks.render(hook.get("artifact", "{case}.visualize.html"), **values)
+            values["artifact"] = artifact
+            hooks.run("visualize", **values)
+            if artifact.exists():
+                produced.append(artifact)
+                self.opener.open_browser(artifact.resolve().as_uri())
+        return produced
+
+    async def run_test_return_results(self, contest_name, problem_name, language_name, case_filter=None):
         import pathlib
+        budget = self.get_budget()
+        self.seed = decide_seed(self.options)
+        self.command_mark = command_log.get_log().mark()
+        # ビルドの前に、コンパイラが変わって使えなくなったキャッシュを消しておく
+        self.cache_epoch(language_name)
+        if self.env is not None:
+            self.env.solution_env = {SEED_ENV: str(self.seed)}
+            self.env.output_limit = self.get_output_limit()
+        # 予算は準備とビルドも含めたtest全体の時間で数える
+        deadline = time.perf_counter() + budget if budget is not None else None
         file_operator = self.file_manager.file_operator if self.file_manager else None
-        temp_source_path, temp_test_dir = self.prepare_test_environment(contest_name, problem_name, language_name)
+        # ソースとテストケースのコピーはイベントループを止めずに行う（ケースが多いと長く止めるため）
+        temp_source_path, temp_test_dir = await self.aprepare_test_environment(contest_name, problem_name, language_name)
+        # cases.yamlの式で書いた期待出力は、一時ディレクトリで.in/.outにしてから他のケースと同じに扱う
+        expand_formula_cases(temp_test_dir)
+        if self.get_config().get_test().get("check"):
+            self.check_cases(temp_test_dir)
         temp_in_files, _ = self.collect_test_cases(temp_test_dir, file_operator)
+        set_name = self.options.get("set")
+        temp_in_files = TestLayout(temp_test_dir, self.get_config().get_test()).select(temp_in_files, set_name)
+        if set_name and not temp_in_files:
+            raise ValueError(f"テストセット {set_name} に該当するケースがありません")
+        if case_filter is not None:
+            # 該当ケースが無ければ全ケースを実行する
+            temp_in_files = [f for f in temp_in_files if case_filter(f)] or temp_in_files
+        temp_in_files = self.order_cases(temp_in_files, contest_name, problem_name, language_name)
+        cancellation.current().check()
         # --- 必要なコンテナ数を調整し、system_info.jsonを最新化 ---
         test_case_count = len(temp_in_files)
-        requirements = [
-            {"type": "test", "language": language_name, "count": test_case_count, "volumes": {
-                HOST_PROJECT_ROOT: CONTAINER_WORKSPACE,
-                TEMP_DIR: "/workspace/.temp"
-            }},
-            {"type": "ojtools", "count": 1, "volumes": {
-                HOST_PROJECT_ROOT: CONTAINER_WORKSPACE,
-                TEMP_DIR: "/workspace/.temp",
-                "/home/cphelper/.local/share/online-judge-tools/cookie.jar": "/root/.local/share/online-judge-tools/cookie.jar"
-            }}
-        ]
+        requirements = self.build_requirements(language_name, self.decide_jobs(test_case_count))
         containers = self.env.adjust_containers(requirements, contest_name, problem_name, language_name)
-        results = await self.run_test_cases(temp_source_path, temp_in_files, language_name)
+        self.progress.emit("test_started", contest_name=contest_name, problem_name=problem_name, language_name=language_name, total=test_case_count)
+        results = await self.run_test_cases(temp_source_path, temp_in_files, language_name, deadline=deadline)
+        self.progress.emit("test_finished", total=len(results), all_ac=self.is_all_ac(results) if results else False,
+                           warnings=len(results[0].get("warnings", [])) if results else 0)
         return results
 
     def is_all_ac(self, results):
-        for r in results:
-            returncode, stdout, _ = r["result"]
-            if returncode != 0 or stdout.strip() != r["expected"].strip():
-                return False
-        return True 
\ No newline at end of file
+        return all(self.verdict_of(r) == "AC" for r in results) 
\ No newline at end of file
@@ -0,0 +1,144 @@
+import os
+import shutil
+from pathlib import Path
+from .case_storage import ZSTD_SUFFIX, expected_file, find_case_file, plain_path
+from .command_test import CommandTest
+from .test_layout import TestLayout
+from src import temp_space
+from src.config_json_manager import ConfigJsonManager
+from src.environment.execution_manager_test_environment import ExecutionManagerTestEnvironment
+from src.environment.test_language_handler import HANDLERS
+from src.execution_client.execution_manager import ExecutionManager
+from src.execution_client.local.client import LocalAsyncClient
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 拡張子 → 言語（組み込みの言語のうち、解答ファイル1つで実行できるもの）
+SOURCE_LANGUAGES = {".py": "python"}
+# 解答ファイルの候補が複数あるときに優先する名前（拡張子を除く）
+PREFERRED_SOURCE = "main"
+# AtCoderのテストケースのzip（in/ と out/ に同じ名前で入力と出力がある）
+INPUT_DIR, OUTPUT_DIR = "in", "out"
+
+def source_languages(config=None):
+    """拡張子 → 言語。config.jsonのlanguagesでsourceを指定した言語も含める"""
+    languages = dict(SOURCE_LANGUAGES)
+    for name, spec in (config or {}).items():
+        ext = os.path.splitext(spec.get("source", ""))[1]
+        if ext and name not in HANDLERS:
+            languages[ext] = name
+    return languages
+
+def detect_source(directory, languages):
+    """ディレクトリ直下の解答ファイルと言語。候補が複数なら main.* を選び、決められなければValueError"""
+    candidates = sorted(p for p in Path(directory).iterdir() if p.is_file() and p.suffix in languages and not p.name.startswith("."))
+    if len(candidates) > 1:
+        candidates = [p for p in candidates if p.stem == PREFERRED_SOURCE] or candidates
+    if not candidates:
+        raise ValueError(f"{directory} に解答ファイルがありません（対応する拡張子: {', '.join(sorted(languages))}）")
+    if len(candidates) > 1:
+        raise ValueError(f"解答ファイルを決められません: {', '.join(p.name for p in candidates)}（--dir にファイルを指定してください）")
+    return candidates[0], languages[candidates[0].suffix]
+
+def walk_files(directory):
+    for root, dirs, files in os.walk(str(directory)):
+        dirs[:] = sorted(d for d in dirs if not d.startswith("."))
+        for name in sorted(files):
+            if not name.startswith("."):
+                yield Path(root) / name
+
+def detect_cases(directory, exclude=()):
+    """
+    ディレクトリの中のテストケースを(ケース名, 入力, 期待出力またはNone)の一覧にする。見分けるのは次の並び:
+    name.in / name.out（cphと同じ）、in/name と out/name（AtCoderのテストケースのzip）、input*/output*（名前のinputをoutputにしたもの）。
+    excludeのファイル（解答）はケースとみなさない
+    """
+    cases = {}
+    directory = Path(directory)
+    exclude = {Path(p).resolve() for p in exclude}
+    for path in walk_files(directory):
+        if path.resolve() in exclude:
+            continue
+        relative = path.relative_to(directory)
+        plain = plain_path(str(path))
+        if plain.endswith(".in"):
+            name = str(relative)[:-len(".in" + (ZSTD_SUFFIX if str(path).endswith(ZSTD_SUFFIX) else ""))]
+            out_file = find_case_file(expected_file(plain))
+        elif relative.parts[0] == INPUT_DIR and len(relative.parts) > 1:
+            name = str(Path(*relative.parts[1:]))
+            out_file = find_case_file(str(directory / OUTPUT_DIR / Path(*relative.parts[1:])))
+        elif "input" in path.name:
+            name = str(relative.with_name(path.name.replace("input", "", 1).strip("_-.") or path.name))
+            out_file = find_case_file(str(path.with_name(path.name.replace("input", "output", 1))))
+        else:
+            continue
+        cases.setdefault(name.replace(os.sep, "-"), (str(path), out_file))
+    return [(name, in_file, out_file) for name, (in_file, out_file) in sorted(cases.items())]
+
+class CommandTestDir:
+    """
+    test --dir PATH: ワークスペースの外（zipから展開した問題など）のディレクトリで、解答をテストケースに対して実行する。
+    解答ファイル（main.pyなど。PATHにファイルを指定してもよい）とテストケースの並びは中身から見分け、ローカルで実行する。
+    contest_currentやproblem.jsonは使わず、実行の記録（.cph/runs）や結果のキャッシュも残さない。
+    """
+    def __init__(self, options=None, upm=None, manager=None):
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.manager = manager
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def locate(self, target):
+        """--dirの値から(ディレクトリ, 解答ファイル, 言語)を決める"""
+        path = Path(str(target)).expanduser()
+        languages = source_languages(self.get_config().get_languages())
+        if path.is_file():
+            if path.suffix not in languages:
+                raise ValueError(f"{path.name} の言語が分かりません（対応する拡張子: {', '.join(sorted(languages))}）")
+            return path.parent, path, languages[path.suffix]
+        if not path.is_dir():
+            raise ValueError(f"ディレクトリがありません: {path}")
+        source, language_name = detect_source(path, languages)
+        return path, source, language_name
+
+    def stage(self, work_dir, source, cases):
+        """解答とケースを一時ディレクトリにcphの並び（name.in / name.out）で写す。元のディレクトリは変えない"""
+        source_path = work_dir / "src" / source.name
+        source_path.parent.mkdir(parents=True)
+        shutil.copyfile(source, source_path)
+        test_dir = work_dir / "test"
+        test_dir.mkdir()
+        in_files = []
+        for name, in_file, out_file in cases:
+            in_suffix = ".in" + (ZSTD_SUFFIX if in_file.endswith(ZSTD_SUFFIX) else "")
+            shutil.copyfile(in_file, test_dir / f"{name}{in_suffix}")
+            if out_file:
+                out_suffix = ".out" + (ZSTD_SUFFIX if out_file.endswith(ZSTD_SUFFIX) else "")
+                shutil.copyfile(out_file, test_dir / f"{name}{out_suffix}")
+            in_files.append(str(test_dir / f"{name}{in_suffix}"))
+        return str(source_path), test_dir, in_files
+
+    async def run(self, target=None):
+        target = target if target not in (None, True) else self.options.get("dir")
+        if target in (None, True):
+            raise ValueError("使い方: test --dir PATH（解答とテストケースのあるディレクトリ、または解答ファイル）")
+        directory, source, language_name = self.locate(target)
+        cases = detect_cases(directory, exclude=[source])
+        if not cases:
+            raise ValueError(f"{directory} にテストケースがありません（name.in / name.out、in/ と out/、input*/output* の並びを探します）")
+        missing = [name for name, _, out_file in cases if out_file is None]
+        print(f"[test] {source} ({language_name}) を {len(cases)} ケースで実行します"
+              + (f"（期待出力の無いケース: {', '.join(missing)}）" if missing else ""))
+        env = ExecutionManagerTestEnvironment(None, self.manager or ExecutionManager(LocalAsyncClient()))
+        # 比較方法と出力の上限は--compareとconfig.jsonだけで決める（作業中の問題のtag --compareなどは使わない）
+        command_test = CommandTest(None, env, options=self.options, problem_settings=False)
+        env.solution_env = {}
+        env.output_limit = command_test.get_output_limit()
+        with temp_space.current().scoped("test-dir-") as work_dir:
+            source_path, test_dir, in_files = self.stage(work_dir, source, cases)
+            in_files = TestLayout(test_dir, self.get_config().get_test()).select(in_files, self.options.get("set"))
+            results = await command_test.run_test_cases(source_path, in_files, language_name)
+            command_test.print_test_results(results)
+        passed = sum(1 for r in results if command_test.verdict_of(r) == "AC")
+        print(f"[test] {passed}/{len(results)} AC")
+        return results
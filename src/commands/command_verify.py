@@ -0,0 +1,158 @@
+import glob
+import hashlib
+import json
+import os
+import re
+import subprocess
+from datetime import datetime
+from .comparator import create_comparator
+from src.config_json_manager import ConfigJsonManager
+from src.file_operator import atomic_write, atomic_write_json
+from src.file_lock import file_lock
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.path_manager.user_paths import migrate
+from src.usage_stats import UsageStats
+
+# online-judge-verify-helperと同じ書式の検証指定（例: # verification-helper: PROBLEM https://judge.yosupo.jp/problem/unionfind）
+PROBLEM_PATTERN = re.compile(r"verification-helper:\s*PROBLEM\s+(\S+)")
+# 検証用ファイルの実行方法（拡張子→コマンド）
+VERIFY_RUNNERS = {
+    ".py": ["python3", "{file}"],
+}
+VERIFY_TIMEOUT = 10
+TIMESTAMP_FORMAT = "%Y-%m-%d %H:%M:%S %z"
+BADGES = {
+    "AC": "https://img.shields.io/badge/verify-AC-brightgreen",
+    "WA": "https://img.shields.io/badge/verify-WA-red",
+    "SKIP": "https://img.shields.io/badge/verify-not%20supported-lightgrey",
+}
+
+def find_problem_url(text):
+    m = PROBLEM_PATTERN.search(text)
+    return m.group(1) if m else None
+
+class CommandVerify:
+    """
+    ライブラリの検証用ファイル（*.test.py）をジャッジのテストケースで実行し、
+    online-judge-verify-helper互換の .verify-helper/timestamps.remote.json とバッジ付きのまとめを出力する。
+    使い方: verify [--lib DIR] [--force]
+    """
+    def __init__(self, file_manager, test_env, options=None):
+        self.file_manager = file_manager
+        self.env = test_env
+        self.options = options or {}
+        self.upm = UnifiedPathManager()
+
+    def root(self):
+        return str(self.upm.project_path.root)
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def lib_dir(self):
+        lib = self.options.get("lib") or self.get_config().get_team().get("lib") or os.path.relpath(str(self.upm.team_dir("lib")), self.root())
+        return lib if os.path.isabs(lib) else os.path.join(self.root(), lib)
+
+    def helper_dir(self, *paths):
+        return os.path.join(self.root(), ".verify-helper", *paths)
+
+    def load_timestamps(self):
+        path = self.helper_dir("timestamps.remote.json")
+        if not os.path.exists(path):
+            return {}
+        with open(path, "r", encoding="utf-8") as f:
+            return json.load(f)
+
+    def save_timestamps(self, timestamps):
+        path = self.helper_dir("timestamps.remote.json")
+        with file_lock(path):
+            atomic_write_json(path, dict(sorted(timestamps.items())))
+
+    def collect_tests(self, lib_dir):
+        return sorted(glob.glob(os.path.join(lib_dir, "**", "*.test.*"), recursive=True))
+
+    def is_verified(self, path, timestamps):
+        # 最後に検証してからファイルが変更されていなければ再検証しない
+        stamp = timestamps.get(os.path.relpath(path, self.root()))
+        if not stamp:
+            return False
+        verified_at = datetime.strptime(stamp, TIMESTAMP_FORMAT).timestamp()
+        # タイムスタンプは秒単位なので、更新時刻も秒に切り捨てて比べる
+        return int(os.path.getmtime(path)) <= verified_at
+
+    def ensure_cases(self, url):
+        # 問題のURLごとに取得したケースは、どのプロジェクトのライブラリ検証でも使い回す
+        key = hashlib.sha1(url.encode("utf-8")).hexdigest()[:12]
+        case_dir = str(migrate(self.upm.cph_dir("verify", key), self.upm.cache_dir("verify", key)))
+        if not glob.glob(os.path.join(case_dir, "*.in")):
+            self.env.download_testcases(url, case_dir)
+        return case_dir
+
+    def run_case(self, cmd, in_file):
+        with open(in_file, "r", encoding="utf-8") as f:
+            input_data = f.read()
+        try:
+            result = subprocess.run(cmd, input=input_data, capture_output=True, text=True, timeout=VERIFY_TIMEOUT)
+        except subprocess.TimeoutExpired:
+            return False, ""
+        return result.returncode == 0, result.stdout
+
+    def verify_file(self, path, comparator):
+        runner = VERIFY_RUNNERS.get(os.path.splitext(path)[1])
+        if runner is None:
+            return "SKIP"
+        with open(path, "r", encoding="utf-8") as f:
+            url = find_problem_url(f.read())
+        if url is None:
+            return "SKIP"
+        case_dir = self.ensure_cases(url)
+        cmd = [token.format(file=path) for token in runner]
+        for in_file in sorted(glob.glob(os.path.join(case_dir, "*.in"))):
+            out_file = in_file[:-len(".in")] + ".out"
+            expected = ""
+            if os.path.exists(out_file):
+                with open(out_file, "r", encoding="utf-8") as f:
+                    expected = f.read()
+            ok, stdout = self.run_case(cmd, in_file)
+            if not ok or not comparator.match(expected, stdout, input_file=in_file):
+                print(f"  [WA] {os.path.basename(in_file)}")
+                return "WA"
+        return "AC"
+
+    def write_summary(self, statuses):
+        lines = ["# Verification Summary", ""]
+        for rel, status in sorted(statuses.items()):
+            lines.append(f"- ![{status}]({BADGES[status]}) `{rel}`")
+        path = self.helper_dir("markdown", "summary.md")
+        atomic_write(path, "\n".join(lines) + "\n")
+        return path
+
+    async def verify(self):
+        lib_dir = self.lib_dir()
+        tests = self.collect_tests(lib_dir)
+        if not tests:
+            print(f"[verify] 検証用ファイル（*.test.*）が見つかりません: {lib_dir}")
+            return {}
+        comparator = create_comparator(self.get_config().get_compare())
+        timestamps = self.load_timestamps()
+        statuses = {}
+        stats = UsageStats()
+        for path in tests:
+            rel = os.path.relpath(path, self.root())
+            if not self.options.get("force") and self.is_verified(path, timestamps):
+                stats.record_cache("verify", True)
+                statuses[rel] = "AC"
+                continue
+            stats.record_cache("verify", False)
+            print(f"[verify] {rel}")
+            status = self.verify_file(path, comparator)
+            statuses[rel] = status
+            if status == "AC":
+                timestamps[rel] = datetime.now().astimezone().strftime(TIMESTAMP_FORMAT)
+            else:
+                timestamps.pop(rel, None)
+        self.save_timestamps(timestamps)
+        summary = self.write_summary(statuses)
+        passed = sum(1 for s in statuses.values() if s == "AC")
+        print(f"[verify] {passed}/{len(statuses)} AC（まとめ: {summary}）")
+        return statuses
@@ -0,0 +1,247 @@
+import os
+import shutil
+import time
+from pathlib import Path
+from .color_theme import current as current_palette
+from .command_run import limit_command
+from .command_test import CommandTest
+from .output_mode import FAIL, PASS, is_accessible
+from .text_width import display_width, pad
+from src.config_json_manager import ConfigJsonManager
+from src.environment.test_language_handler import HANDLERS, CustomCommandHandler
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src import temp_space
+
+# 確かめる項目（表の列の順）
+BUILD = "build"
+IO = "io"
+TL = "tl"
+EXIT_CODE = "exit_code"
+CHECKS = [BUILD, IO, TL, EXIT_CODE]
+CHECK_LABELS = {BUILD: "ビルド", IO: "入出力", TL: "TL", EXIT_CODE: "終了コード"}
+# TLの確認で掛ける制限（秒）。無限ループの代わりのプログラムは、制限が効かなくてもESCAPE_SECONDSで自分で抜ける
+SMOKE_TL = 1
+ESCAPE_SECONDS = 10
+EXIT_STATUS = 3
+
+# 1行目で動きを変える hello world
+#   echo WORD → "hello WORD" / loop → "loop"を出して回り続ける（ESCAPE_SECONDS経つと"escaped"） / exit N → "exit"を出して終了コードN
+PYTHON_SOURCE = f'''import sys
+import time
+
+args = sys.stdin.readline().split()
+if args[0] == "echo":
+    print("hello", args[1])
+elif args[0] == "loop":
+    print("loop", flush=True)
+    start = time.time()
+    while time.time() - start < {ESCAPE_SECONDS}:
+        pass
+    print("escaped")
+elif args[0] == "exit":
+    print("exit", flush=True)
+    sys.exit(int(args[1]))
+'''
+RUST_SOURCE = f'''use std::io::{{self, BufRead, Write}};
+use std::time::{{Duration, Instant}};
+
+fn main() {{
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line).unwrap();
+    let args: Vec<&str> = line.split_whitespace().collect();
+    match args[0] {{
+        "echo" => println!("hello {{}}", args[1]),
+        "loop" => {{
+            println!("loop");
+            io::stdout().flush().unwrap();
+            let start = Instant::now();
+            while start.elapsed() < Duration::from_secs({ESCAPE_SECONDS}) {{}}
+            println!("escaped");
+        }}
+        "exit" => {{
+            println!("exit");
+            io::stdout().flush().unwrap();
+            std::process::exit(args[1].parse().unwrap());
+        }}
+        _ => {{}}
+    }}
+}}
+'''
+# RustTestHandlerはtarget/release/rustを実行するので、パッケージ名はrust
+CARGO_TOML = '''[package]
+name = "rust"
+version = "0.1.0"
+edition = "2021"
+'''
+
+class CommandVerifyEnv:
+    """
+    言語ごとに小さな hello world を今の実行環境（docker / local）でビルド・実行し、
+    ビルド・標準入出力の受け渡し・TL（timeout）の強制・終了コードの取得が使えるかを表にする。
+    コンテスト前に、壊れた言語の設定（イメージ・config.jsonのlanguages）に気づくためのもの。
+    config.jsonのlanguagesで足した言語は、smoke_sourceに同じ動きをするソースを書いておくと確かめられる。
+    例: {"languages": {"nim": {"source": "main.nim", "run": "{binary}", "smoke_source": "team/smoke/hello.nim"}}}
+    使い方: verify-env [language]
+    """
+    def __init__(self, file_manager, test_env, options=None):
+        self.file_manager = file_manager
+        self.env = test_env
+        self.options = options or {}
+        self.upm = UnifiedPathManager()
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json()))
+
+    def uses_containers(self):
+        return getattr(self.env, "uses_containers", True)
+
+    def languages(self, language_name=None):
+        """組み込みの言語とconfig.jsonのlanguagesで足した言語（指定があればその言語だけ）"""
+        custom = [name for name in self.get_config().get_languages() if name not in HANDLERS]
+        names = list(HANDLERS) + custom
+        return [language_name] if language_name else names
+
+    def handler_for(self, language_name):
+        if language_name in HANDLERS:
+            return HANDLERS[language_name]
+        spec = self.get_config().get_languages().get(language_name)
+        if spec is None:
+            raise ValueError(f"未対応の言語です: {language_name}")
+        return CustomCommandHandler(language_name, spec)
+
+    def write_source(self, language_name, root):
+        """rootに hello world を置き、ビルド・実行に渡すパス（rustはcargoのディレクトリ）を返す"""
+        root = Path(str(root))
+        if language_name == "rust":
+            (root / "src").mkdir(parents=True, exist_ok=True)
+            (root / "Cargo.toml").write_text(CARGO_TOML, encoding="utf-8")
+            (root / "src" / "main.rs").write_text(RUST_SOURCE, encoding="utf-8")
+            return root
+        if language_name in ("python", "pypy"):
+            path = root / "main.py"
+            path.write_text(PYTHON_SOURCE, encoding="utf-8")
+            return path
+        spec = self.get_config().get_languages().get(language_name) or {}
+        smoke = spec.get("smoke_source")
+        if not smoke:
+            return None
+        path = root / os.path.basename(spec.get("source") or smoke)
+        shutil.copyfile(os.path.join(str(self.upm.project_path.root), smoke), str(path))
+        return path
+
+    def prepare_workers(self, languages):
+        """dockerなら言語ごとにテスト用コンテナを1つずつ用意する。言語 → 実行先の名前"""
+        if not self.uses_containers():
+            return {lang: f"verify-env-{lang}" for lang in languages}
+        # ojtoolsのコンテナも要るものとして渡す（adjustは要らないコンテナを消すため）
+        command_test = CommandTest(self.file_manager, self.env)
+        requirements = [command_test.build_requirements(lang, 1)[0] for lang in languages]
+        containers = self.env.adjust_containers(requirements + command_test.build_requirements(languages[0], 1)[1:])
+        return {c["language"]: c["name"] for c in containers if c.get("type") == "test"}
+
+    def run_case(self, worker, cmd, root, name, line):
+        """1行の入力で実行し、(成功したか, 標準出力, 標準エラー, 秒数)を返す"""
+        in_file = Path(str(root)) / f"{name}.in"
+        in_file.write_text(line + "\n", encoding="utf-8")
+        start = time.perf_counter()
+        ok, stdout, stderr = self.env.exec_command(worker, cmd, self.env.to_container_path(str(in_file)))
+        return ok, stdout or "", stderr or "", time.perf_counter() - start
+
+    def check_language(self, language_name, worker, root):
+        """{項目: (成功したか, 説明)}。ビルドできなければ残りは確かめない（None）"""
+        results = {check: None for check in CHECKS}
+        try:
+            handler = self.handler_for(language_name)
+            source = self.write_source(language_name, root)
+        except (OSError, ValueError) as e:
+            results[BUILD] = (False, str(e))
+            return results
+        if source is None:
+            results[BUILD] = (False, "config.jsonのlanguagesにsmoke_source（確かめる hello world）がありません")
+            return results
+        cont_source = self.env.to_container_path(os.path.abspath(str(source)))
+        build_client = getattr(self.env, "ctl", None) or getattr(self.env, "manager", None)
+        try:
+            ok, _, stderr = handler.build(build_client, worker, cont_source)
+        except Exception as e:
+            ok, stderr = False, str(e)
+        results[BUILD] = (bool(ok), "" if ok else first_line(stderr))
+        if not ok:
+            return results
+        run_cmd = handler.run_command(cont_source)
+        results[IO] = self.check_io(worker, run_cmd, root)
+        results[TL] = self.check_tl(worker, run_cmd, root)
+        results[EXIT_CODE] = self.check_exit_code(worker, run_cmd, root)
+        return results
+
+    def check_io(self, worker, run_cmd, root):
+        ok, stdout, stderr, _ = self.run_case(worker, run_cmd, root, "io", "echo cph")
+        if ok and stdout.strip() == "hello cph":
+            return True, ""
+        return False, first_line(stderr) or f"出力が違います: {stdout.strip()[:40]!r}"
+
+    def check_tl(self, worker, run_cmd, root):
+        ok, stdout, stderr, elapsed = self.run_case(worker, limit_command(run_cmd, SMOKE_TL), root, "tl", "loop")
+        if "loop" not in stdout:
+            return False, first_line(stderr) or "実行できませんでした"
+        if ok or "escaped" in stdout:
+            return False, f"{SMOKE_TL}秒で止まりませんでした（{elapsed:.1f}秒）"
+        return True, f"{elapsed:.1f}秒で停止"
+
+    def check_exit_code(self, worker, run_cmd, root):
+        ok, stdout, stderr, _ = self.run_case(worker, run_cmd, root, "exit", f"exit {EXIT_STATUS}")
+        if "exit" not in stdout:
+            return False, first_line(stderr) or "実行できませんでした"
+        if ok:
+            return False, f"終了コード{EXIT_STATUS}を失敗として受け取れませんでした"
+        return True, ""
+
+    def format_matrix(self, rows):
+        """言語ごとの結果を表（アクセシブル表示では1行ずつの文）にする"""
+        palette = current_palette()
+        if is_accessible():
+            return [f"{lang}: " + "、".join(f"{CHECK_LABELS[c]} {mark_word(r[c])}" for c in CHECKS) for lang, r in rows.items()]
+        width = max([display_width("言語")] + [display_width(lang) for lang in rows]) + 2
+        widths = {c: max(display_width(CHECK_LABELS[c]), 2) + 2 for c in CHECKS}
+        lines = [(pad("言語", width) + "".join(pad(CHECK_LABELS[c], widths[c]) for c in CHECKS)).rstrip()]
+        for lang, r in rows.items():
+            cells = []
+            for c in CHECKS:
+                mark = mark_text(r[c])
+                painted = palette.paint(mark, "AC" if r[c] and r[c][0] else "WA") if r[c] else mark
+                cells.append(painted + " " * (widths[c] - display_width(mark)))
+            lines.append((pad(lang, width) + "".join(cells)).rstrip())
+        return lines
+
+    async def verify_env(self, language_name=None):
+        """すべて使えれば0、どれかが使えなければ1を返す（終了コードにする）"""
+        languages = self.languages(language_name)
+        mode = "docker" if self.uses_containers() else "local"
+        print(f"[verify-env] {mode} で各言語の hello world をビルド・実行します: {', '.join(languages)}")
+        workers = self.prepare_workers(languages)
+        rows = {}
+        with temp_space.for_env(self.env).scoped("verify-env-") as tmp:
+            for lang in languages:
+                root = tmp / lang
+                root.mkdir()
+                rows[lang] = self.check_language(lang, workers.get(lang, f"verify-env-{lang}"), root)
+        for line in self.format_matrix(rows):
+            print(line)
+        failures = [(lang, c, r[c][1]) for lang, r in rows.items() for c in CHECKS if r[c] and not r[c][0]]
+        for lang, check, detail in failures:
+            print(f"  {lang} {CHECK_LABELS[check]}: {detail}")
+        if failures:
+            print("[verify-env] 使えない言語があります。イメージ（prepare）やconfig.jsonのlanguagesを確認してください")
+            return 1
+        print("[verify-env] すべての言語が使えます")
+        return 0
+
+def first_line(text):
+    return next((line.strip() for line in (text or "").splitlines() if line.strip()), "")
+
+def mark_text(result):
+    # 確かめなかった項目（ビルド失敗のあと）は -
+    return "-" if result is None else ("OK" if result[0] else "NG")
+
+def mark_word(result):
+    return "未確認" if result is None else (PASS if result[0] else FAIL)
@@ -0,0 +1,170 @@
+import json
+import os
+import shutil
+import tarfile
+import zipfile
+from contextlib import contextmanager
+from pathlib import Path
+from src import temp_space
+from src.command_parser import LANGUAGES
+from src.path_manager.path_validator import PathValidator
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 問題の並び（{contest}/{problem}/{language}）を探すときに中へ入らないディレクトリ
+SKIP_DIRS = {"contest_current", "contest_template", "contest_env", ".cph", ".git", "target", "__pycache__"}
+TEST_DIR = "test"
+
+def is_archive(path):
+    return zipfile.is_zipfile(str(path)) or tarfile.is_tarfile(str(path))
+
+def list_files(directory):
+    """directory配下のファイル（相対パス、隠しファイルとビルド成果物を除く）"""
+    files = []
+    for current, dirs, names in os.walk(str(directory)):
+        dirs[:] = sorted(d for d in dirs if d not in SKIP_DIRS and not d.startswith("."))
+        files += [os.path.relpath(os.path.join(current, n), str(directory)) for n in sorted(names) if not n.startswith(".")]
+    return files
+
+def find_problems(root):
+    """
+    root配下から {contest}/{problem}/{language}/ の並びを探す。
+    contest_stocksごとのアーカイブ、gcの退避アーカイブ（{contest}/{problem}/...）のどちらも読める。
+    戻り値: {(contest, problem): {"path": 問題ディレクトリ, "languages": [...], "cases": [...]}}
+    """
+    problems = {}
+    for current, dirs, _ in os.walk(str(root)):
+        dirs[:] = sorted(d for d in dirs if d not in SKIP_DIRS)
+        languages = [d for d in dirs if d in LANGUAGES]
+        if not languages:
+            continue
+        path = Path(current)
+        test_dir = path / TEST_DIR
+        cases = sorted(f for f in list_files(test_dir) if f.endswith((".in", ".in.zst"))) if test_dir.is_dir() else []
+        problems[(path.parent.name, path.name)] = {"path": path, "languages": languages, "cases": cases}
+        # 解答とテストケースの中に問題は無い
+        dirs[:] = [d for d in dirs if d not in languages and d != TEST_DIR]
+    return problems
+
+class CommandView:
+    """
+    他の人のアーカイブ（zip / tar.gz、または展開済みのディレクトリ）を読み取り専用で開く。
+    アーカイブは一時ディレクトリに展開し、自分のcontest_current・contest_stocks・.cphには書き込まない。
+    使い方: view ARCHIVE（問題の一覧）
+            view ARCHIVE CONTEST PROBLEM [LANGUAGE]（解答を表示）
+            view ARCHIVE CONTEST PROBLEM [LANGUAGE] --run（一時的なワークスペースでその人のテストケースを実行）
+    """
+    def __init__(self, exec_mode=None, options=None, args=None, upm=None):
+        self.exec_mode = exec_mode
+        self.options = options or {}
+        self.args = list(args or [])
+        self.upm = upm or UnifiedPathManager()
+
+    @contextmanager
+    def open_archive(self, archive):
+        path = Path(str(archive))
+        if path.is_dir():
+            yield path
+            return
+        if not path.exists():
+            raise FileNotFoundError(f"アーカイブが見つかりません: {archive}")
+        if not is_archive(path):
+            raise ValueError(f"zip / tar形式のアーカイブではありません: {archive}")
+        with temp_space.current().scoped("view-") as tmp:
+            PathValidator(str(tmp)).extract_archive(path, str(tmp))
+            yield tmp
+
+    def select(self, problems, contest_name, problem_name):
+        key = (contest_name, problem_name)
+        if key not in problems:
+            available = ", ".join(f"{c} {p}" for c, p in sorted(problems)) or "なし"
+            raise FileNotFoundError(f"アーカイブに {contest_name} {problem_name} がありません（収録: {available}）")
+        return problems[key]
+
+    def print_problems(self, problems, contest_name=None):
+        shown = {k: v for k, v in sorted(problems.items()) if contest_name in (None, k[0])}
+        if not shown:
+            print("[view] アーカイブに問題が見つかりません")
+        for (contest, problem), p in shown.items():
+            print(f"[view] {contest} {problem}: {', '.join(p['languages'])}（テストケース {len(p['cases'])}件）")
+        return shown
+
+    def print_solution(self, problem, languages):
+        for language_name in languages:
+            language_dir = problem["path"] / language_name
+            for name in list_files(language_dir):
+                print(f"===== {language_name}/{name} =====")
+                try:
+                    print((language_dir / name).read_text(encoding="utf-8").rstrip("\n"))
+                except UnicodeDecodeError:
+                    print("（バイナリファイルのため表示しません）")
+
+    def build_workspace(self, root, problem, contest_name, problem_name, language_name):
+        """
+        テストを実行するための使い捨てのワークスペースをrootに作る。
+        解答とテストケースはアーカイブから、config.jsonとcontest_envは自分のものを写す。
+        """
+        current = root / "contest_current"
+        shutil.copytree(str(problem["path"] / language_name), str(current / language_name))
+        test_dir = problem["path"] / TEST_DIR
+        if test_dir.is_dir():
+            shutil.copytree(str(test_dir), str(current / TEST_DIR))
+        else:
+            (current / TEST_DIR).mkdir(parents=True)
+        if self.upm.config_json().exists():
+            shutil.copy(str(self.upm.config_json()), str(current / "config.json"))
+        env_dir = self.upm.contest_env("")
+        if env_dir.is_dir():
+            shutil.copytree(str(env_dir), str(root / "contest_env"))
+        info = {"contest_name": contest_name, "problem_name": problem_name, "language_name": language_name}
+        (current / "system_info.json").write_text(json.dumps(info, ensure_ascii=False), encoding="utf-8")
+
+    async def run_tests(self, problem, contest_name, problem_name, language_name):
+        # 循環importを避けるためここで読み込む
+        from src.command_executor import CommandExecutor
+        from src.contest_file_manager import ContestFileManager
+        from src.file_operator import LocalFileOperator
+        if not problem["cases"]:
+            print(f"[view] {contest_name} {problem_name} にはテストケースがありません")
+            return []
+        previous_cwd = os.getcwd()
+        with temp_space.current().scoped("view-ws-") as tmp:
+            self.build_workspace(tmp, problem, contest_name, problem_name, language_name)
+            os.chdir(tmp)
+            try:
+                executor = CommandExecutor(
+                    file_manager=ContestFileManager(LocalFileOperator()),
+                    exec_mode=self.exec_mode,
+                    options=self.options,
+                )
+                handler = executor.test_handler
+                # 結果は表示するだけで、実行の記録やキャッシュは残さない
+                results = await handler.run_test_return_results(contest_name, problem_name, language_name)
+                handler.print_test_results(results)
+            finally:
+                os.chdir(previous_cwd)
+        return results
+
+    def resolve_archive(self, contest_name):
+        """ARCHIVEを取り出す。abc300.zipのようにコンテスト名と解釈された場合も、パスが存在すればARCHIVEとする"""
+        if self.args:
+            return self.args[0], contest_name
+        if contest_name and os.path.exists(contest_name):
+            return contest_name, None
+        raise ValueError("開くアーカイブを指定してください: view ARCHIVE [CONTEST PROBLEM [LANGUAGE]] [--run]")
+
+    async def view(self, contest_name=None, problem_name=None, language_name=None):
+        archive, contest_name = self.resolve_archive(contest_name)
+        with self.open_archive(archive) as root:
+            problems = find_problems(root)
+            if problem_name is None:
+                return self.print_problems(problems, contest_name)
+            problem = self.select(problems, contest_name, problem_name)
+            if language_name is not None and language_name not in problem["languages"]:
+                raise FileNotFoundError(f"{contest_name} {problem_name} に {language_name} の解答はありません（{', '.join(problem['languages'])}）")
+            languages = [language_name] if language_name else problem["languages"]
+            if not self.options.get("run"):
+                self.print_solution(problem, languages)
+                return problem
+            if len(languages) > 1:
+                raise ValueError(f"テストする言語を指定してください: {', '.join(languages)}")
+            return await self.run_tests(problem, contest_name, problem_name, languages[0])
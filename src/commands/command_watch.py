@@ -0,0 +1,150 @@
+import asyncio
+import os
+from datetime import datetime
+from .command_refresh import CommandRefresh
+from src import file_lock, temp_space
+from src.config_json_manager import ConfigJsonManager
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.run_coordinator import RunCoordinator
+from src.shutdown import GracefulShutdown
+
+# 変更を調べる間隔（秒）。config.jsonのwatch.interval_secondsで変えられる
+DEFAULT_INTERVAL = 0.5
+# SIGTERMで終わるときに、監視していた問題と実行の履歴を残すファイル（.cph/の下）
+STATE_FILE = "watch.json"
+
+class SourceWatcher:
+    """監視するファイル・ディレクトリの更新時刻を覚えておき、前回から変わったファイルを返す"""
+    def __init__(self, paths):
+        self.paths = [str(p) for p in paths]
+        self.snapshot = self.scan()
+
+    def scan(self):
+        found = {}
+        for path in self.paths:
+            if os.path.isfile(path):
+                found[path] = os.stat(path).st_mtime_ns
+                continue
+            for root, dirs, files in os.walk(path):
+                dirs[:] = [d for d in dirs if not d.startswith(".")]
+                for name in files:
+                    file = os.path.join(root, name)
+                    try:
+                        found[file] = os.stat(file).st_mtime_ns
+                    except OSError:
+                        # 走査中に消えたファイル
+                        continue
+        return found
+
+    def changes(self):
+        """前回から追加・変更・削除されたファイル（並び順は名前順）"""
+        current = self.scan()
+        changed = sorted(p for p in set(current) | set(self.snapshot) if current.get(p) != self.snapshot.get(p))
+        self.snapshot = current
+        return changed
+
+class CommandWatch:
+    """
+    test --watch: 解答とテストケースを監視し、変わるたびにtestを実行し直す（Ctrl+Cで終了）。
+    実験的な機能（daemon）なので、config.jsonのexperimentalで有効にしたときだけ使える（コマンドからの入口で確かめる）。
+    実行中に変更があったときの扱いはconfig.jsonのwatch.strategy（cancel / queue / skip、既定はcancel）で決める。
+    例: {"watch": {"strategy": "queue", "interval_seconds": 1}}
+    --poll 60s を付けると、その間隔で問題ページを確かめ（refreshと同じ）、増えたサンプルを取り込んで実行し直す。
+    SIGTERMを受けたら、実行中のtestが終わるのを待ち、履歴を残してコンテナを止め、ロックを放してから終わる（shutdown.grace_seconds）。
+    """
+    def __init__(self, test_handler, options=None, upm=None, sleep=None, shutdown=None, refresh_handler=None):
+        self.test_handler = test_handler
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.sleep = sleep or asyncio.sleep
+        self.shutdown = shutdown
+        self.refresh_handler = refresh_handler or CommandRefresh(self.options, upm=self.upm)
+
+    def get_config(self):
+        return ConfigJsonManager(str(self.upm.config_json())).get_watch()
+
+    def get_shutdown(self):
+        return self.shutdown or GracefulShutdown.from_config(ConfigJsonManager(str(self.upm.config_json())).get_shutdown())
+
+    def container_pool(self):
+        """testが使うコンテナのプール（ローカルで実行する環境には無い）"""
+        return getattr(getattr(self.test_handler, "env", None), "pool", None)
+
+    def save_state(self, coordinator, contest_name, problem_name, language_name):
+        path = self.upm.cph_dir(STATE_FILE)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        atomic_write_json(path, {
+            "contest_name": contest_name, "problem_name": problem_name, "language_name": language_name,
+            "strategy": coordinator.strategy, "stopped_at": datetime.now().isoformat(timespec="seconds"),
+            "history": [{"reason": reason, "outcome": outcome} for reason, outcome in coordinator.history],
+        })
+
+    def add_shutdown_steps(self, shutdown, coordinator, contest_name, problem_name, language_name):
+        shutdown.add("実行中のtestの完了待ち", coordinator.drain, check=lambda: ["test"] if coordinator.busy else [])
+        shutdown.add("履歴の保存", lambda: self.save_state(coordinator, contest_name, problem_name, language_name), force=True)
+        pool = self.container_pool()
+        if pool is not None:
+            shutdown.add("コンテナの停止", pool.stop_all, force=True, check=pool.running_containers)
+        shutdown.add("ロックの解放", file_lock.release_all, force=True, check=file_lock.held_locks)
+        shutdown.add("一時ディレクトリの削除", temp_space.cleanup_all, force=True)
+        return shutdown
+
+    def watched_paths(self, language_name):
+        return [self.upm.contest_current(language_name), self.upm.contest_current("test")]
+
+    def run_once(self, contest_name, problem_name, language_name):
+        # ワーカースレッドで動くので、イベントループはtestごとに作る
+        return asyncio.run(self.test_handler.run_test(contest_name, problem_name, language_name))
+
+    async def watch(self, contest_name, problem_name, language_name, max_polls=None):
+        config = self.get_config()
+        interval = float(config.get("interval_seconds", DEFAULT_INTERVAL))
+        coordinator = RunCoordinator(lambda reason: self.run_once(contest_name, problem_name, language_name), config.get("strategy"))
+        watcher = SourceWatcher(self.watched_paths(language_name))
+        # 取り込んだサンプルはtestディレクトリに入るので、監視で気づいて実行し直す
+        refresh_interval = self.refresh_handler.poll_interval()
+        refresh = (lambda: self.refresh_handler.poll_once(contest_name, problem_name)) if refresh_interval is not None else None
+        print(f"[watch] {contest_name} {problem_name} ({language_name}) を監視します（実行中に変更があれば: {coordinator.strategy}、Ctrl+Cで終了）")
+        if refresh is not None:
+            print(f"[watch] {refresh_interval:g}秒ごとに問題ページを確かめ、増えたサンプルを取り込みます")
+        shutdown = self.get_shutdown()
+        coordinator.trigger("start")
+        with shutdown:
+            try:
+                await self.poll(coordinator, watcher, interval, shutdown, max_polls, refresh, refresh_interval)
+                if shutdown.requested.is_set():
+                    self.add_shutdown_steps(shutdown, coordinator, contest_name, problem_name, language_name).run()
+                    print("[watch] 終了しました")
+                else:
+                    coordinator.wait_idle()
+            finally:
+                # Ctrl+Cなどで抜けるときは、実行中のtestを中断して終わるのを待つ（コンテナや一時ディレクトリを残さない）
+                coordinator.close()
+        return coordinator.history
+
+    async def poll(self, coordinator, watcher, interval, shutdown, max_polls=None, refresh=None, refresh_interval=None):
+        polls = 0
+        # 前回問題ページを確かめてからの時間（秒）
+        waited = 0.0
+        while max_polls is None or polls < max_polls:
+            await self.sleep(interval)
+            polls += 1
+            waited += interval
+            if shutdown.requested.is_set():
+                return
+            if refresh is not None and waited >= refresh_interval:
+                waited = 0.0
+                # 問題ページの取得で変更の監視を止めないよう、別のスレッドで確かめる
+                await asyncio.get_running_loop().run_in_executor(None, refresh)
+            changed = watcher.changes()
+            if not changed:
+                continue
+            names = ", ".join(os.path.basename(p) for p in changed[:3]) + (f" ほか{len(changed) - 3}件" if len(changed) > 3 else "")
+            outcome = coordinator.trigger(names)
+            if outcome == "skipped":
+                print(f"[watch] 実行中のため、変更（{names}）では実行しません")
+            elif outcome in ("queued", "cancelled"):
+                print(f"[watch] 変更（{names}）: 実行中のtestが終わったら実行し直します")
+            else:
+                print(f"[watch] 変更（{names}）: testを実行します")
@@ -0,0 +1,217 @@
+import math
+import os
+import re
+import subprocess
+from abc import ABC, abstractmethod
+from src import temp_space
+from src.error_hints import CphError
+from .case_storage import is_compressed, export_case
+
+class Comparator(ABC):
+    """期待出力と実際の出力を比較する戦略。input_fileはチェッカーなど入力を参照する比較で使う"""
+    @abstractmethod
+    def match(self, expected: str, actual: str, input_file=None) -> bool:
+        pass
+
+class ExactComparator(Comparator):
+    """前後の空白を除いて完全一致するか（従来の比較方法）"""
+    def match(self, expected, actual, input_file=None):
+        return actual.strip() == expected.strip()
+
+class TokensComparator(Comparator):
+    """空白・改行の違いを無視して、空白区切りのトークン列が一致するか"""
+    def match(self, expected, actual, input_file=None):
+        return expected.split() == actual.split()
+
+class FloatComparator(Comparator):
+    """トークンごとに比較し、数値は絶対誤差または相対誤差がeps以下なら一致とみなす"""
+    def __init__(self, eps=1e-6):
+        self.eps = float(eps)
+
+    def _token_match(self, e, a):
+        if e == a:
+            return True
+        try:
+            x, y = float(e), float(a)
+        except ValueError:
+            return False
+        if math.isnan(x) or math.isnan(y):
+            return False
+        return abs(x - y) <= self.eps or abs(x - y) <= self.eps * abs(x)
+
+    def match(self, expected, actual, input_file=None):
+        e, a = expected.split(), actual.split()
+        return len(e) == len(a) and all(self._token_match(x, y) for x, y in zip(e, a))
+
+class CheckerComparator(Comparator):
+    """
+    外部のチェッカーで判定する（testlib形式: checker input output answer、終了コード0でAC）。
+    入力ファイルが無い場合は空のファイルを渡す。
+    """
+    def __init__(self, path, timeout=10):
+        self.path = path
+        self.timeout = timeout
+
+    def match(self, expected, actual, input_file=None):
+        with temp_space.current().scoped("checker-") as d:
+            out_file = os.path.join(d, "output.txt")
+            ans_file = os.path.join(d, "answer.txt")
+            with open(out_file, "w", encoding="utf-8") as f:
+                f.write(actual)
+            with open(ans_file, "w", encoding="utf-8") as f:
+                f.write(expected)
+            if input_file is None or not os.path.exists(str(input_file)):
+                input_file = os.path.join(d, "input.txt")
+                open(input_file, "w").close()
+            elif is_compressed(input_file):
+                # チェッカーには展開したファイルを渡す
+                input_file = export_case(input_file, os.path.join(d, "input.txt"))
+            try:
+                result = subprocess.run([self.path, str(input_file), out_file, ans_file], capture_output=True, timeout=self.timeout)
+            except subprocess.TimeoutExpired:
+                return False
+            except OSError as e:
+                raise CphError(f"チェッカーを実行できません: {self.path}（{e.strerror or e}）", kind="checker_failed") from e
+            return result.returncode == 0
+
+class UnorderedLinesComparator(Comparator):
+    """
+    行の順序を問わずに比較する。
+    per_block=Trueの場合は空行で区切られたブロックごとに行をソートし、ブロックの順序は維持する。
+    """
+    def __init__(self, per_block=False):
+        self.per_block = per_block
+
+    @staticmethod
+    def _sorted_lines(text):
+        return sorted(line.rstrip() for line in text.strip().splitlines())
+
+    def _normalize(self, text):
+        if not self.per_block:
+            return [self._sorted_lines(text)]
+        blocks = []
+        current = []
+        for line in text.strip().splitlines():
+            if line.strip() == "":
+                if current:
+                    blocks.append(current)
+                current = []
+            else:
+                current.append(line)
+        if current:
+            blocks.append(current)
+        return [self._sorted_lines("\n".join(b)) for b in blocks]
+
+    def match(self, expected, actual, input_file=None):
+        return self._normalize(expected) == self._normalize(actual)
+
+# 1語のYes / No（大文字小文字は問わない）
+YES_NO_TOKEN = re.compile(r"(?<!\S)(yes|no)(?!\S)", re.IGNORECASE)
+
+class YesNoComparator(Comparator):
+    """
+    別の比較方法を包み、Yes / YES / yes と No / NO / no をそれぞれ同じものとして比べる（多くのジャッジの扱いに合わせる）。
+    語ごとに表記をそろえてから元の比較に渡すので、tokens / float / unorderedなどと組み合わせられる
+    """
+    def __init__(self, inner=None):
+        self.inner = inner or ExactComparator()
+
+    @staticmethod
+    def normalize(text):
+        return YES_NO_TOKEN.sub(lambda m: m.group(1).capitalize(), text)
+
+    def match(self, expected, actual, input_file=None):
+        return self.inner.match(self.normalize(expected), self.normalize(actual), input_file)
+
+def match_any(comparator, candidates, actual, input_file=None):
+    """受理する出力が複数あるとき（sample-3.a.out など）、どれか1つに一致すれば一致とする"""
+    return any(comparator.match(expected, actual, input_file) for expected in candidates)
+
+COMPARATORS = {
+    "exact": ExactComparator,
+    "tokens": TokensComparator,
+    "float": FloatComparator,
+    "unordered": UnorderedLinesComparator,
+    "unordered-lines": UnorderedLinesComparator,
+    "checker": CheckerComparator,
+}
+# 他の比較方法を包むもの（設定のキー → クラス）。--compareでは MODE+yesno のように付ける
+DECORATORS = {
+    "yes_no": YesNoComparator,
+}
+CLI_MODIFIERS = {
+    "yesno": "yes_no",
+}
+# --compare MODE:ARG の引数を渡すパラメータ名
+CLI_ARGUMENTS = {
+    "float": "eps",
+    "checker": "path",
+}
+
+def parse_eps(arg):
+    try:
+        return float(arg)
+    except ValueError:
+        raise ValueError(f"floatの許容誤差は数値で指定してください: {arg!r}（例: --compare float:1e-6）") from None
+
+def parse_compare_option(value):
+    """
+    --compare の値を比較設定に変換する。
+    例: "tokens" / "float:1e-6" / "unordered" / "checker:./checker" / "tokens+yesno" / "yesno"（exactにyesnoを付けたもの）
+    """
+    value = str(value)
+    modifiers = {}
+    for name, key in CLI_MODIFIERS.items():
+        if value == name or value.endswith("+" + name):
+            modifiers[key] = True
+            value = value[:-len(name)].rstrip("+")
+    if not value:
+        return modifiers
+    mode, sep, arg = value.partition(":")
+    if mode not in COMPARATORS:
+        raise ValueError(f"未対応の比較モードです: {mode}（{', '.join(COMPARATORS)}。+{'/+'.join(CLI_MODIFIERS)}を付けられます）")
+    spec = {"mode": mode}
+    if sep:
+        if mode not in CLI_ARGUMENTS:
+            raise ValueError(f"比較モード {mode} は引数を取りません: {value}")
+        spec[CLI_ARGUMENTS[mode]] = parse_eps(arg) if mode == "float" else arg
+    elif mode == "checker":
+        raise ValueError("checkerにはパスを指定してください（例: --compare checker:./checker）")
+    return {**spec, **modifiers}
+
+def _spec_dict(spec):
+    return {"mode": spec} if isinstance(spec, str) else dict(spec or {})
+
+def merge_compare_specs(base, override):
+    """
+    config.jsonの比較設定に問題ごとの設定を重ねる（{"yes_no": true}だけなら元のモードのまま）。
+    モードを変えるときは元の設定を置き換え、yes_noなどの付け足しだけを引き継ぐ（floatのepsをtokensに渡さない）
+    """
+    if not override:
+        return base
+    base, override = _spec_dict(base), _spec_dict(override)
+    if "mode" not in override:
+        return {**base, **override}
+    modifiers = {key: value for key, value in base.items() if key in DECORATORS}
+    return {**modifiers, **override}
+
+def create_comparator(spec=None) -> Comparator:
+    """
+    config.jsonのcompare設定からComparatorを生成する。
+    spec: None / "unordered-lines" / {"mode": "unordered-lines", "per_block": true} / {"mode": "float", "eps": 1e-6}
+    / {"mode": "tokens", "yes_no": true}
+    """
+    if spec is None:
+        return ExactComparator()
+    if isinstance(spec, str):
+        spec = {"mode": spec}
+    spec = dict(spec)
+    mode = spec.pop("mode", "exact")
+    decorators = [cls for key, cls in DECORATORS.items() if spec.pop(key, False)]
+    comparator_class = COMPARATORS.get(mode)
+    if comparator_class is None:
+        raise ValueError(f"未対応の比較モードです: {mode}")
+    comparator = comparator_class(**spec)
+    for decorator in decorators:
+        comparator = decorator(comparator)
+    return comparator
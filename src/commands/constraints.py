@@ -0,0 +1,129 @@
+import re
+from html.parser import HTMLParser
+from .archive_index import ArchiveIndex
+from .command_switch import task_url
+from .sample_scraper import SampleScraper
+from src.error_hints import CphError
+
+# 制約の見出し（AtCoderは日本語・英語の両方が載っているので、先に見つかった方を使う）
+CONSTRAINT_HEADINGS = {"制約", "Constraints"}
+# 1行にまとめて表示する長さの上限（超えたら1つずつ行を分ける）
+MAX_INLINE_WIDTH = 100
+# LaTeXの記号 → 表示用の記号
+SYMBOLS = [
+    (re.compile(r"\\leq{0,2}(?![a-zA-Z])"), "≤"),
+    (re.compile(r"\\geq{0,2}(?![a-zA-Z])"), "≥"),
+    (re.compile(r"\\lt(?![a-zA-Z])"), "<"),
+    (re.compile(r"\\gt(?![a-zA-Z])"), ">"),
+    (re.compile(r"\\neq?(?![a-zA-Z])"), "≠"),
+    (re.compile(r"\\times(?![a-zA-Z])"), "×"),
+    (re.compile(r"\\cdot(?![a-zA-Z])"), "·"),
+    (re.compile(r"\\[lc]?dots(?![a-zA-Z])"), "…"),
+    (re.compile(r"\\(?:mathrm|text|rm|mathit)(?![a-zA-Z])"), ""),
+    (re.compile(r"\\[,;! ]|~"), " "),
+    (re.compile(r"[${}]"), ""),
+]
+
+# 上限を読むときの表記（compact_constraintで整えた後）。≤ / ≦ → <=、× / · → *、200,000 → 200000
+BOUND_REPLACEMENTS = [
+    (re.compile(r"≦|≤"), "<="),
+    (re.compile(r"×|·"), "*"),
+    (re.compile(r"(?<=\d)[, ](?=\d{3}\b)"), ""),
+]
+NUMBER = r"\d+(?:\s*\^\s*\d+)?"
+BOUND = rf"{NUMBER}(?:\s*\*\s*{NUMBER})*"
+
+def compact_constraint(text):
+    """LaTeXの記号を表示用にする。例: $1 \\leq N \\leq 2 \\times 10^5$ → 1 ≤ N ≤ 2 × 10^5"""
+    for pattern, replacement in SYMBOLS:
+        text = pattern.sub(replacement, text)
+    return " ".join(text.split())
+
+class ConstraintSection(HTMLParser):
+    """問題文の「制約」の見出しに続く箇条書きの項目を取り出す"""
+    def __init__(self):
+        super().__init__(convert_charrefs=True)
+        self.heading = None
+        self.capturing = False
+        self.item = None
+        self.items = []
+
+    def handle_starttag(self, tag, attrs):
+        if tag == "h3":
+            # 次の見出しで制約の節は終わり
+            self.capturing = False
+            self.heading = [] if not self.items else None
+        elif tag == "li" and self.capturing:
+            self.item = []
+
+    def handle_endtag(self, tag):
+        if tag == "h3" and self.heading is not None:
+            self.capturing = "".join(self.heading).strip() in CONSTRAINT_HEADINGS
+            self.heading = None
+        elif tag == "li" and self.item is not None:
+            text = compact_constraint("".join(self.item))
+            if text:
+                self.items.append(text)
+            self.item = None
+
+    def handle_data(self, data):
+        if self.heading is not None:
+            self.heading.append(data)
+        elif self.item is not None:
+            self.item.append(data)
+
+def parse_constraints(html):
+    """問題ページのHTMLから制約の一覧（表示用に整えたもの）。見つからなければ空"""
+    parser = ConstraintSection()
+    parser.feed(html)
+    return parser.items
+
+def evaluate_bound(text):
+    """"2 * 10^5" → 200000"""
+    value = 1
+    for term in text.split("*"):
+        base, _, exponent = term.replace(" ", "").partition("^")
+        value *= int(base) ** int(exponent or 1)
+    return value
+
+def parse_max_n(text, variable="N"):
+    """
+    制約（"1 \\le N \\le 2 \\times 10^5" / "1≦N,M≦10^5" / problem.jsonのconstraintsの項目など）から変数の上限を読む。
+    同じ変数の制約が複数あれば大きい方。見つからなければNone
+    """
+    text = compact_constraint(text)
+    for pattern, replacement in BOUND_REPLACEMENTS:
+        text = pattern.sub(replacement, text)
+    pattern = re.compile(rf"(?<![A-Za-z_\d]){re.escape(variable)}(?:\s*,\s*[A-Za-z_]\w*)*\s*<=\s*({BOUND})")
+    bounds = [evaluate_bound(m.group(1)) for m in pattern.finditer(text)]
+    return max(bounds) if bounds else None
+
+def format_constraints(constraints):
+    """testの冒頭などに出す短い表示の行"""
+    if not constraints:
+        return []
+    inline = " / ".join(constraints)
+    if len(inline) <= MAX_INLINE_WIDTH:
+        return [f"[制約] {inline}"]
+    return ["[制約]"] + [f"  {c}" for c in constraints]
+
+def record_constraints(index, contest_name, problem_name, html):
+    """HTMLから読んだ制約をproblem.jsonのconstraintsに残し、その一覧を返す"""
+    constraints = parse_constraints(html)
+    index.update(contest_name, problem_name, lambda entry: entry.update(constraints=constraints))
+    return constraints
+
+def show_constraints(test_config):
+    """制約を取得・表示するか（config.jsonのtest.show_constraints、既定は表示する）"""
+    return (test_config or {}).get("show_constraints", True)
+
+def fetch_constraints(contest_name, problem_name, upm=None, scraper=None, quiet=False):
+    """問題ページを取得して制約をproblem.jsonに残す。取得できなければNone（制約の表示は補助なので、呼び出し元は止めない）"""
+    scraper = scraper or SampleScraper(upm)
+    try:
+        html = scraper.page(task_url(contest_name, problem_name))
+    except CphError as e:
+        if not quiet:
+            print(f"[制約] 問題ページを取得できませんでした: {e}")
+        return None
+    return record_constraints(ArchiveIndex(upm=upm), contest_name, problem_name, html)
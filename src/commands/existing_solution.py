@@ -0,0 +1,53 @@
+from pathlib import Path
+from src.moveignore_manager import MoveIgnoreManager
+
+def is_language(upm, name):
+    """言語ディレクトリ名か（解答ファイルの定義かテンプレートがある）"""
+    return name in upm.project_path.resolver().source_files or Path(str(upm.contest_template(name))).is_dir()
+
+def solution_languages(upm, contest_name, problem_name):
+    """
+    contest_stocksのこの問題に解答ファイルがある言語。言語の解答ファイル（rustならsrc/main.rs）と
+    同じ拡張子のファイルがあるかで判定する
+    """
+    problem_dir = Path(str(upm.contest_stocks(contest_name, problem_name)))
+    if not problem_dir.is_dir():
+        return []
+    found = []
+    for lang_dir in sorted(problem_dir.iterdir()):
+        if not lang_dir.is_dir() or lang_dir.name == "test" or not is_language(upm, lang_dir.name):
+            continue
+        suffix = Path(upm.source_file(lang_dir.name)).suffix
+        if any(p.is_file() and p.suffix == suffix for p in lang_dir.rglob("*")):
+            found.append(lang_dir.name)
+    return found
+
+def detect_language(upm, contest_name, problem_name, default=None):
+    """
+    言語の指定が無いときに使う言語。既定の言語の解答が無く、ほかの1言語だけに解答があればその言語にする。
+    (言語, 判定に使った言語の一覧)を返す
+    """
+    found = solution_languages(upm, contest_name, problem_name)
+    if len(found) == 1 and default not in found:
+        return found[0], found
+    return default, found
+
+def replaced_files(upm, contest_name, problem_name, language_name):
+    """
+    contest_current/{language}をこの問題のcontest_stocksの解答に置き換えるときに、失われる内容のあるファイル
+    （保管済みの解答に無いか、内容が違うもの）。moveignoreのものは置き換えないので対象外
+    """
+    src_dir = Path(str(upm.contest_current(language_name)))
+    stock_dir = Path(str(upm.contest_stocks(contest_name, problem_name, language_name)))
+    if not src_dir.is_dir() or not stock_dir.is_dir():
+        return []
+    patterns = MoveIgnoreManager(str(upm.config_json())).moveignore
+    replaced = []
+    for src in sorted(src_dir.rglob("*")):
+        rel = src.relative_to(src_dir)
+        if not src.is_file() or MoveIgnoreManager.is_ignored_with_patterns(rel.parts[0], patterns):
+            continue
+        stocked = stock_dir / rel
+        if not stocked.is_file() or stocked.read_bytes() != src.read_bytes():
+            replaced.append(src)
+    return replaced
@@ -0,0 +1,155 @@
+import ast
+import math
+import operator
+import os
+import re
+from fractions import Fraction
+from src.error_hints import CphError
+
+# テストディレクトリに置く、式で期待出力を書くケースの定義
+CASES_FILE = "cases.yaml"
+# {n} のような入力変数の参照
+VARIABLE_REF = re.compile(r"\{([A-Za-z_]\w*)\}")
+
+BINARY_OPERATORS = {
+    ast.Add: operator.add,
+    ast.Sub: operator.sub,
+    ast.Mult: operator.mul,
+    ast.Div: operator.truediv,
+    ast.FloorDiv: operator.floordiv,
+    ast.Mod: operator.mod,
+    ast.Pow: operator.pow,
+}
+UNARY_OPERATORS = {ast.UAdd: operator.pos, ast.USub: operator.neg}
+
+def _integer_function(func):
+    return lambda *args: func(*(int(a) for a in args))
+
+# 式で使える関数
+FUNCTIONS = {
+    "abs": abs,
+    "min": min,
+    "max": max,
+    "gcd": _integer_function(math.gcd),
+    "lcm": _integer_function(math.lcm),
+    "comb": _integer_function(math.comb),
+    "perm": _integer_function(math.perm),
+    "factorial": _integer_function(math.factorial),
+    "isqrt": _integer_function(math.isqrt),
+    "floor": math.floor,
+    "ceil": math.ceil,
+}
+
+def to_number(token):
+    """入力のトークンを数にする（整数・小数は誤差の出ないFractionで扱う）"""
+    try:
+        return Fraction(token)
+    except (ValueError, ZeroDivisionError):
+        raise ValueError(f"数として読めない入力です: {token}")
+
+def evaluate(expression, variables):
+    """
+    四則演算・累乗・剰余と一部の関数（gcd, comb, min, ...）だけを評価する小さな式エンジン。
+    {n} は入力変数。途中の計算は分数で行うので n*(n+1)/2 も整数になる。
+    """
+    source = VARIABLE_REF.sub(lambda m: m.group(1), str(expression))
+    try:
+        tree = ast.parse(source, mode="eval")
+    except SyntaxError as e:
+        raise ValueError(f"式を読めません: {expression}（{e.msg}）")
+
+    def visit(node):
+        if isinstance(node, ast.Expression):
+            return visit(node.body)
+        if isinstance(node, ast.Constant) and isinstance(node.value, (int, float)) and not isinstance(node.value, bool):
+            return Fraction(str(node.value))
+        if isinstance(node, ast.Name):
+            if node.id not in variables:
+                raise ValueError(f"式 {expression} の変数 {node.id} が入力にありません（{', '.join(variables) or 'なし'}）")
+            return variables[node.id]
+        if isinstance(node, ast.BinOp) and type(node.op) in BINARY_OPERATORS:
+            left, right = visit(node.left), visit(node.right)
+            if isinstance(node.op, ast.Pow) and right.denominator != 1:
+                raise ValueError(f"累乗の指数は整数にしてください: {expression}")
+            try:
+                return Fraction(BINARY_OPERATORS[type(node.op)](left, right))
+            except ZeroDivisionError:
+                raise ValueError(f"式 {expression} で0除算が起きました")
+        if isinstance(node, ast.UnaryOp) and type(node.op) in UNARY_OPERATORS:
+            return UNARY_OPERATORS[type(node.op)](visit(node.operand))
+        if isinstance(node, ast.Call) and isinstance(node.func, ast.Name) and node.func.id in FUNCTIONS and not node.keywords:
+            return Fraction(FUNCTIONS[node.func.id](*(visit(a) for a in node.args)))
+        raise ValueError(f"式 {expression} に使えない要素があります: {ast.dump(node)[:40]}")
+
+    return visit(tree)
+
+def format_value(value, precision=10):
+    """整数ならそのまま、そうでなければ小数precision桁（末尾の0は落とす）"""
+    if value.denominator == 1:
+        return str(value.numerator)
+    return f"{float(value):.{precision}f}".rstrip("0").rstrip(".")
+
+def bind_variables(names, input_text):
+    """入力の空白区切りのトークンを、先頭から順にnamesの変数に割り当てる"""
+    tokens = input_text.split()
+    if len(tokens) < len(names):
+        raise ValueError(f"入力のトークンが足りません（変数 {', '.join(names)} に対して {len(tokens)}個）")
+    return {name: to_number(token) for name, token in zip(names, tokens)}
+
+def load_cases_file(path):
+    try:
+        import yaml
+    except ImportError:
+        raise CphError(f"{CASES_FILE}を読むにはPyYAMLが必要です", kind="yaml_missing")
+    with open(path, "r", encoding="utf-8") as f:
+        data = yaml.safe_load(f) or {}
+    if not isinstance(data, dict) or not isinstance(data.get("cases", []), list):
+        raise ValueError(f"{path}: casesにケースの一覧を書いてください")
+    return data
+
+def expected_output(case, names, input_text, precision):
+    expected = case.get("expected")
+    if expected is None:
+        raise ValueError(f"{CASES_FILE}のケース {case.get('name')} に expected がありません")
+    lines = expected if isinstance(expected, list) else [expected]
+    variables = {**bind_variables(names, input_text), **{k: to_number(str(v)) for k, v in (case.get("vars") or {}).items()}}
+    return "".join(f"{format_value(evaluate(line, variables), precision)}\n" for line in lines)
+
+def expand_formula_cases(test_dir):
+    """
+    test_dir/cases.yaml のケースを .in / .out に書き出す（testの一時ディレクトリで使う）。
+    例:
+        variables: [n]         # 入力の空白区切りのトークンの名前（先頭から順に）
+        precision: 10          # 整数でない結果の小数点以下の桁数
+        cases:
+          - name: sum-3
+            input: "3"
+            expected: "{n}*({n}+1)/2"
+          - name: sample-1     # inputを省くと既存の sample-1.in を使う
+            expected: ["{n}+1", "{n}*2"]   # 複数行の出力
+    書き出したケースの .in のパスを返す。
+    """
+    path = os.path.join(str(test_dir), CASES_FILE)
+    if not os.path.isfile(path):
+        return []
+    data = load_cases_file(path)
+    names = list(data.get("variables") or [])
+    precision = int(data.get("precision", 10))
+    written = []
+    for i, case in enumerate(data.get("cases") or []):
+        name = str(case.get("name") or f"formula-{i + 1}")
+        in_file = os.path.join(str(test_dir), f"{name}.in")
+        if "input" in case:
+            input_text = str(case["input"])
+            input_text = input_text if input_text.endswith("\n") else input_text + "\n"
+            with open(in_file, "w", encoding="utf-8") as f:
+                f.write(input_text)
+        elif os.path.isfile(in_file):
+            with open(in_file, "r", encoding="utf-8") as f:
+                input_text = f.read()
+        else:
+            raise ValueError(f"{CASES_FILE}のケース {name} に input が無く、{name}.in もありません")
+        with open(os.path.join(str(test_dir), f"{name}.out"), "w", encoding="utf-8") as f:
+            f.write(expected_output(case, names, input_text, precision))
+        written.append(in_file)
+    return written
@@ -0,0 +1,49 @@
+import shlex
+import subprocess
+
+# フック実行のタイムアウト（秒）
+HOOK_TIMEOUT = 60
+
+class HookRunner:
+    """
+    config.jsonのhooksに定義されたユーザーコマンドを実行する。
+    例: {"hooks": {"visualize": {"command": "python3 vis.py {input} {output} {artifact}", "artifact": "{case}.html"}}}
+    commandは文字列のみの省略形でもよい。{name}形式のプレースホルダは実行時の値で置換される。
+    """
+    def __init__(self, hooks_config=None):
+        self.hooks = hooks_config or {}
+
+    def get(self, name):
+        hook = self.hooks.get(name)
+        if hook is None:
+            return None
+        if isinstance(hook, str):
+            hook = {"command": hook}
+        return hook
+
+    def has(self, name):
+        return self.get(name) is not None
+
+    @staticmethod
+    def render(template, **values):
+        return template.format(**{k: str(v) for k, v in values.items()})
+
+    def build_command(self, name, **values):
+        hook = self.get(name)
+        if hook is None:
+            return None
+        # 先に分割してから置換し、空白を含むパスでも1引数として渡す
+        return [self.render(token, **values) for token in shlex.split(hook["command"])]
+
+    def run(self, name, cwd=None, **values):
+        cmd = self.build_command(name, **values)
+        if cmd is None:
+            return None
+        try:
+            result = subprocess.run(cmd, capture_output=True, text=True, cwd=cwd, timeout=HOOK_TIMEOUT)
+        except (OSError, subprocess.TimeoutExpired) as e:
+            print(f"[警告] フック {name} の実行に失敗しました: {e}")
+            return None
+        if result.returncode != 0:
+            print(f"[警告] フック {name} が異常終了しました (code={result.returncode})\n{result.stderr.strip()}")
+        return result
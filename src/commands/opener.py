@@ -1,4 +1,20 @@
 import os
+import shlex
+
+# エディタの指定が無いときのコマンド（VSCodeの「ファイル:行:列」を開く形式）
+DEFAULT_EDITOR_COMMAND = "code -g {file}:{line}:{column}"
+
+def editor_command(template=None, environ=None):
+    """行を指定して開くコマンドの雛形。config.jsonのeditor.command → $EDITOR（vim / nano / emacs の +行 形式）→ VSCode"""
+    if template:
+        return template
+    editor = (environ if environ is not None else os.environ).get("EDITOR")
+    if editor:
+        return f"{editor} +{{line}} {{file}}"
+    return DEFAULT_EDITOR_COMMAND
+
+def build_editor_argv(template, path, line, column=1):
+    return [token.format(file=path, line=line, column=column) for token in shlex.split(template)]
 
 class Opener:
     def open_editor(self, path: str, language: str = None):
@@ -13,6 +29,14 @@ class Opener:
             subprocess.call(["cursor", path], env=os.environ.copy())
         except Exception as e:
             print(f"[警告] Cursor起動失敗: {e}")
+    def open_editor_at(self, path: str, line: int, column: int = 1, command: str = None):
+        """エラーの位置（行・列）を指定してエディタで開く"""
+        argv = build_editor_argv(editor_command(command), path, line, column)
+        try:
+            import subprocess
+            subprocess.call(argv, env=os.environ.copy())
+        except Exception as e:
+            print(f"[警告] エディタを起動できませんでした（{' '.join(argv)}）: {e}")
     def open_browser(self, url: str):
         try:
             import webbrowser
@@ -24,7 +48,10 @@ class MockOpener(Opener):
     def __init__(self):
         self.opened_paths = []
         self.opened_urls = []
+        self.opened_locations = []
     def open_editor(self, path: str, language: str = None):
         self.opened_paths.append(path)
+    def open_editor_at(self, path: str, line: int, column: int = 1, command: str = None):
+        self.opened_locations.append((path, line, column))
     def open_browser(self, url: str):
         self.opened_urls.append(url) 
\ No newline at end of file
@@ -0,0 +1,31 @@
+import os
+
+# 判定を色ではなく言葉で伝える（スクリーンリーダーで読み上げても分かるように）
+PASS = "PASS"
+FAIL = "FAIL"
+
+def accessible_enabled(setting=None, environ=None):
+    """
+    アクセシブル表示（色・罫線・横並びの表を使わず、1行ずつ言葉で出す）にするかどうか。
+    config.jsonのoutput.accessible（true / false）が最優先で、未設定ならTERM=dumbのときに有効。
+    """
+    if setting is not None:
+        return bool(setting)
+    environ = os.environ if environ is None else environ
+    return environ.get("TERM") == "dumb"
+
+def verdict_label(verdict):
+    """"AC" → "PASS (AC)"、"WA" → "FAIL (WA)" """
+    return f"{PASS if verdict == 'AC' else FAIL} ({verdict})"
+
+_accessible = None
+
+def configure(setting=None, environ=None):
+    """プロセス全体の表示モードを決める（mainで一度呼ぶ）。settingはconfig.jsonのoutput.accessible"""
+    global _accessible
+    _accessible = accessible_enabled(setting, environ)
+    return _accessible
+
+def is_accessible():
+    """configure前に使われたときは環境変数（TERM）だけで判定する"""
+    return configure() if _accessible is None else _accessible
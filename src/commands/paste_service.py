@@ -0,0 +1,87 @@
+import json
+import os
+import uuid
+from abc import ABC, abstractmethod
+from src import http_client
+from src.error_hints import CphError
+
+GIST_API = "https://api.github.com/gists"
+ZERO_X_ZERO_URL = "https://0x0.st"
+# gistのトークンを読む環境変数（config.jsonのshare.token_envで変えられる）
+DEFAULT_TOKEN_ENV = "GITHUB_TOKEN"
+
+def join_files(files):
+    """1ファイルしか送れないサービス向けに、ファイル名の見出しを付けて1つにまとめる"""
+    if len(files) == 1:
+        return next(iter(files.values()))
+    return "\n".join(f"===== {name} =====\n{content.rstrip()}\n" for name, content in files.items())
+
+class PasteService(ABC):
+    """解答などのテキストを貼り付けて、共有用のURLを返すサービス"""
+    name = None
+
+    @abstractmethod
+    def upload(self, files, description=""):
+        """files: {ファイル名: 内容}。共有用のURLを返す"""
+
+class GistService(PasteService):
+    """GitHubのsecret gistに貼る（URLを知っている人だけが見られる）。トークンはgist権限が必要"""
+    name = "gist"
+
+    def __init__(self, token=None, api=GIST_API, token_env=DEFAULT_TOKEN_ENV):
+        self.token = token
+        self.api = api
+        self.token_env = token_env
+
+    def upload(self, files, description=""):
+        if not self.token:
+            raise CphError(f"gistに貼るにはGitHubのトークンが必要です（環境変数 {self.token_env}）", kind="share_token_missing")
+        body = json.dumps({
+            "description": description,
+            "public": False,
+            "files": {name: {"content": content} for name, content in files.items()},
+        }).encode("utf-8")
+        headers = {
+            "Authorization": f"token {self.token}",
+            "Accept": "application/vnd.github+json",
+            "Content-Type": "application/json",
+        }
+        response = http_client.post(self.api, body, headers=headers)
+        try:
+            return json.loads(response.text())["html_url"]
+        except (ValueError, KeyError) as e:
+            raise CphError(f"gistの作成結果を読めませんでした（{e}）", kind="http_failed")
+
+class ZeroXZeroService(PasteService):
+    """0x0.stに貼る（登録不要。URLを知っていれば誰でも見られる）"""
+    name = "0x0"
+
+    def __init__(self, url=ZERO_X_ZERO_URL):
+        self.url = url
+
+    def upload(self, files, description=""):
+        boundary = uuid.uuid4().hex
+        content = join_files(files).encode("utf-8")
+        body = (
+            f"--{boundary}\r\n"
+            f'Content-Disposition: form-data; name="file"; filename="solution.txt"\r\n'
+            f"Content-Type: text/plain; charset=utf-8\r\n\r\n"
+        ).encode("utf-8") + content + f"\r\n--{boundary}--\r\n".encode("utf-8")
+        response = http_client.post(self.url, body, headers={"Content-Type": f"multipart/form-data; boundary={boundary}"})
+        return response.text().strip()
+
+PASTE_SERVICES = {
+    GistService.name: GistService,
+    ZeroXZeroService.name: ZeroXZeroService,
+}
+DEFAULT_PASTE_SERVICE = ZeroXZeroService.name
+
+def create_paste_service(name, share_config=None):
+    """名前とconfig.jsonのshare設定からPasteServiceを作る"""
+    share_config = share_config or {}
+    if name not in PASTE_SERVICES:
+        raise ValueError(f"未対応の貼り付け先です: {name}（{', '.join(PASTE_SERVICES)}）")
+    if name == GistService.name:
+        token_env = share_config.get("token_env", DEFAULT_TOKEN_ENV)
+        return GistService(token=os.environ.get(token_env), token_env=token_env)
+    return ZeroXZeroService(url=share_config.get("url", ZERO_X_ZERO_URL))
@@ -0,0 +1,90 @@
+import json
+import math
+import time
+from pathlib import Path
+from .archive_index import ArchiveIndex
+from .command_import import ATCODER_PROBLEMS_MODELS, CODEFORCES_PREFIX
+from src import http_client
+from src.error_hints import CphError
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# AtCoder Problemsの推定難易度を取り直すまでの時間（時間）。config.jsonのdifficulty.max_age_hoursで変えられる
+DEFAULT_MAX_AGE_HOURS = 24
+CACHE_FILE = "problem-models.json"
+# 推定難易度を取得したときのdifficulty_source（これ以外の値は手で付けたもの）
+FETCHED_SOURCE = "atcoder_problems"
+# AtCoderの色の区切り（難易度がこの値未満ならその色）
+DIFFICULTY_COLORS = [(400, "灰"), (800, "茶"), (1200, "緑"), (1600, "水"), (2000, "青"), (2400, "黄"), (2800, "橙")]
+
+def difficulty_color(difficulty):
+    for bound, color in DIFFICULTY_COLORS:
+        if difficulty < bound:
+            return color
+    return "赤"
+
+def describe_difficulty(difficulty):
+    """表示用の難易度。例: 1234 → 1234（水）、無ければ未設定"""
+    return "未設定" if difficulty is None else f"{difficulty}（{difficulty_color(difficulty)}）"
+
+class DifficultyModels:
+    """
+    AtCoder Problemsの推定難易度（problem-models.json、全問題で数MB）を、ユーザーのキャッシュ（~/.cache/cph）に置いて使い回す。
+    取り直す時間を過ぎていても、取得に失敗したら古いものを使う。
+    """
+    def __init__(self, path=None, max_age_hours=None, clock=time.time):
+        self.path = Path(str(path)) if path else UnifiedPathManager().cache_dir(CACHE_FILE)
+        self.max_age = float(max_age_hours if max_age_hours is not None else DEFAULT_MAX_AGE_HOURS) * 3600
+        self.clock = clock
+
+    def load(self):
+        try:
+            return json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            return None
+
+    def models(self):
+        cached = self.load()
+        if cached and self.clock() - cached.get("fetched_at", 0) < self.max_age:
+            return cached.get("models", {})
+        try:
+            models = http_client.get_json(ATCODER_PROBLEMS_MODELS)
+        except CphError as e:
+            if cached:
+                return cached.get("models", {})
+            raise CphError(f"AtCoder Problemsから難易度を取得できませんでした（{e}）", kind="difficulty_unavailable")
+        self.path.parent.mkdir(parents=True, exist_ok=True)
+        atomic_write_json(self.path, {"fetched_at": self.clock(), "models": models})
+        return models
+
+    def difficulty(self, contest_name, problem_name):
+        """推定難易度（整数）。AtCoder Problemsに推定が無い問題（新しすぎる・例外的な問題）はNone"""
+        value = (self.models().get(f"{contest_name}_{problem_name}") or {}).get("difficulty")
+        return round(clip_difficulty(value)) if value is not None else None
+
+def clip_difficulty(value):
+    """AtCoder Problemsの表示と同じく、400未満の推定値を0より大きい値に丸め込む（例: -512.3 → 40.9）"""
+    return value if value >= 400 else 400 / math.exp(1 - value / 400)
+
+def fetch_difficulty(contest_name, problem_name, config, upm=None, models=None):
+    """
+    config.jsonのdifficulty.fetchが有効なら、AtCoderの問題の推定難易度を取得してproblem.jsonに残し、その値を返す。
+    tag --difficultyで手で付けた値があればそちらを優先し、取得はしない（以前に取得した値は取り直す）。取得できなくても止めない（None）
+    """
+    if (config or {}).get("fetch") is not True or contest_name.startswith(CODEFORCES_PREFIX):
+        return None
+    upm = upm or UnifiedPathManager()
+    index = ArchiveIndex(upm=upm)
+    entry = index.get(contest_name, problem_name) or {}
+    # 以前に取得した値は、推定が更新されているかもしれないので取り直す
+    if entry.get("difficulty") is not None and entry.get("difficulty_source") != FETCHED_SOURCE:
+        return entry["difficulty"]
+    models = models or DifficultyModels(upm.cache_dir(CACHE_FILE), config.get("max_age_hours"))
+    try:
+        difficulty = models.difficulty(contest_name, problem_name)
+    except CphError as e:
+        print(f"[警告] {e}")
+        return entry.get("difficulty")
+    if difficulty is not None and difficulty != entry.get("difficulty"):
+        index.update(contest_name, problem_name, lambda e: e.update(difficulty=difficulty, difficulty_source=FETCHED_SOURCE))
+    return difficulty
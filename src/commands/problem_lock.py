@@ -0,0 +1,57 @@
+from src.error_hints import CphError
+from src.info_json_manager import InfoJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# config.jsonのtest.problem_lock。contest_currentの問題と違う問題をtestしようとしたときの扱い
+WARN = "warn"
+PROMPT = "prompt"
+ERROR = "error"
+OFF = "off"
+LOCK_MODES = (WARN, PROMPT, ERROR, OFF)
+DEFAULT_LOCK_MODE = WARN
+
+def active_problem(upm=None):
+    """contest_currentにある問題（system_info.jsonのcontest_name, problem_name）。無ければ(None, None)"""
+    upm = upm or UnifiedPathManager()
+    try:
+        info = InfoJsonManager(str(upm.info_json())).data
+    except (OSError, ValueError):
+        return None, None
+    return info.get("contest_name"), info.get("problem_name")
+
+class ProblemLock:
+    """
+    contest_currentにあるのはsystem_info.jsonの問題のコードとサンプルなので、別の問題を指定してtestすると
+    Aのコードを Bの結果として記録してしまう。指定がcontest_currentの問題と違うときに、test.problem_lockに従って
+    warn: 警告して続ける（既定） / prompt: 続けるか確認する（--yesで確認しない） / error: 止める / off: 何もしない
+    """
+    def __init__(self, mode=DEFAULT_LOCK_MODE, options=None, upm=None, ask=input):
+        if mode not in LOCK_MODES:
+            raise ValueError(f"test.problem_lockが不正です: {mode}（{' / '.join(LOCK_MODES)}）")
+        self.mode = mode
+        self.options = options or {}
+        self.upm = upm or UnifiedPathManager()
+        self.ask = ask
+
+    @classmethod
+    def from_config(cls, test_config, options=None, upm=None, ask=input):
+        return cls((test_config or {}).get("problem_lock", DEFAULT_LOCK_MODE), options, upm, ask)
+
+    def check(self, contest_name, problem_name):
+        """続けてよければTrue。errorなら止める"""
+        active = active_problem(self.upm)
+        if self.mode == OFF or None in active or active == (contest_name, problem_name):
+            return True
+        message = (f"contest_currentにあるのは {active[0]} {active[1]} のコードとサンプルですが、{contest_name} {problem_name} としてtestしようとしています"
+                   f"（切り替えるには: switch {contest_name} {problem_name}）")
+        if self.mode == ERROR:
+            raise CphError(message, kind="problem_mismatch")
+        print(f"[WARN] {message}")
+        if self.mode == PROMPT and not self.options.get("yes"):
+            try:
+                return self.ask("このまま実行しますか？ (y/N): ").strip().lower().startswith("y")
+            except EOFError:
+                # stdinが端末でない（パイプやCI）ときは答えられないので、続けない
+                print("\n[WARN] 確認できないため実行しません（確認せずに続けるには --yes）")
+                return False
+        return True
@@ -0,0 +1,38 @@
+import json
+import sys
+import time
+
+class ProgressReporter:
+    """
+    長時間かかる処理の進捗イベントを通知する。
+    デフォルトは何も出力しない（人間向けの表示はstdoutのprintで行う）。
+    """
+    def emit(self, event, **fields):
+        pass
+
+class NdjsonProgressReporter(ProgressReporter):
+    """
+    1イベントにつき1行のJSON（NDJSON）をstderrへ書き出す。
+    stdoutの通常出力とは分離されるため、エディタ拡張やステータスバーから購読できる。
+    """
+    def __init__(self, stream=None):
+        self.stream = stream if stream is not None else sys.stderr
+
+    def emit(self, event, **fields):
+        record = {"event": event, "timestamp": time.time()}
+        record.update(fields)
+        self.stream.write(json.dumps(record, ensure_ascii=False) + "\n")
+        self.stream.flush()
+
+PROGRESS_REPORTERS = {
+    "none": ProgressReporter,
+    "ndjson": NdjsonProgressReporter,
+}
+
+def create_progress_reporter(mode=None):
+    if mode is None:
+        return ProgressReporter()
+    reporter_class = PROGRESS_REPORTERS.get(mode)
+    if reporter_class is None:
+        raise ValueError(f"未対応の進捗出力形式です: {mode}")
+    return reporter_class()
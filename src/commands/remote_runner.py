@@ -0,0 +1,106 @@
+import json
+import re
+import time
+import urllib.parse
+from abc import ABC, abstractmethod
+from src import cancellation, http_client
+from src.error_hints import CphError
+from .command_login import load_cookie_jar, site_cookies
+
+ATCODER_CUSTOM_TEST = "https://atcoder.jp/contests/{contest}/custom_test"
+# ページに埋め込まれたCSRFトークン（var csrfToken = "..."）
+CSRF_TOKEN = re.compile(r'csrfToken\s*=\s*"([^"]+)"')
+CUSTOM_TEST_FORM = 'name="sourceCode"'
+# コードテストの結果のStatus（3で実行済み）
+CUSTOM_TEST_DONE = 3
+# 結果を待つ間隔（秒、サイトがIntervalを返せばそれを使う）と、待つ時間の上限（秒）
+POLL_INTERVAL = 1.0
+MAX_WAIT = 60
+
+class RemoteRunner(ABC):
+    """サイトのジャッジ環境で、解答と入力を実行してもらう（提出はしない）"""
+    name = None
+
+    @abstractmethod
+    def run(self, contest_name, language_id, source, input_data):
+        """{"exit_code", "stdout", "stderr", "time_ms", "memory_kb"} を返す"""
+
+class AtCoderCustomTest(RemoteRunner):
+    """
+    AtCoderのコードテスト（/contests/{contest}/custom_test）で実行する。
+    ログインのセッションはojのcookie.jar（login）か、sites.yamlのatcoderのクッキーを使う。
+    """
+    name = "atcoder"
+
+    def __init__(self, sleep=time.sleep, cookie_jar=None):
+        self.sleep = sleep
+        self.cookie_jar = cookie_jar
+
+    def base_url(self, contest_name):
+        return ATCODER_CUSTOM_TEST.format(contest=contest_name)
+
+    def headers(self):
+        """ojのcookie.jarにセッションがあればCookieヘッダーにする（無ければsites.yamlのクッキーだけが送られる）"""
+        jar = self.cookie_jar if self.cookie_jar is not None else load_cookie_jar()
+        cookies = site_cookies(jar, self.name)
+        return {"Cookie": "; ".join(f"{c.name}={c.value}" for c in cookies)} if cookies else {}
+
+    def csrf_token(self, contest_name, headers):
+        html = http_client.send("GET", self.base_url(contest_name), headers=headers).text()
+        m = CSRF_TOKEN.search(html)
+        # ログインしていないとログインページに転送される（コードテストのフォームが無い）
+        if not m or CUSTOM_TEST_FORM not in html:
+            raise CphError(f"{contest_name}のコードテストを開けませんでした。login でログインしてください", kind="remote_run_login")
+        return m.group(1)
+
+    def submit(self, contest_name, language_id, source, input_data, headers):
+        body = urllib.parse.urlencode({
+            "data.LanguageId": language_id,
+            "sourceCode": source,
+            "input": input_data,
+            "csrf_token": self.csrf_token(contest_name, headers),
+        }).encode("utf-8")
+        http_client.post(f"{self.base_url(contest_name)}/submit/json", body,
+                         headers={**headers, "Content-Type": "application/x-www-form-urlencoded"})
+
+    def poll(self, contest_name, headers):
+        waited = 0.0
+        while True:
+            response = http_client.send("GET", f"{self.base_url(contest_name)}/json?reload=true", headers=headers)
+            try:
+                data = json.loads(response.text())
+            except ValueError as e:
+                raise CphError(f"コードテストの結果を読めませんでした（{e}）", kind="http_failed")
+            result = data.get("Result") or {}
+            if result.get("Status") == CUSTOM_TEST_DONE:
+                return data
+            if waited >= MAX_WAIT:
+                raise CphError(f"コードテストの結果が{MAX_WAIT}秒以内に返りませんでした", kind="remote_run_timeout")
+            interval = (data.get("Interval") or POLL_INTERVAL * 1000) / 1000
+            cancellation.current().check()
+            self.sleep(interval)
+            waited += interval
+
+    def run(self, contest_name, language_id, source, input_data):
+        headers = self.headers()
+        self.submit(contest_name, language_id, source, input_data, headers)
+        data = self.poll(contest_name, headers)
+        result = data["Result"]
+        return {
+            "exit_code": result.get("ExitCode"),
+            "stdout": data.get("Stdout") or "",
+            "stderr": data.get("Stderr") or "",
+            "time_ms": result.get("TimeConsumption"),
+            "memory_kb": result.get("MemoryConsumption"),
+        }
+
+# サイト → そのジャッジで実行できるもの（コードテストの無いサイトは未対応）
+REMOTE_RUNNERS = {
+    AtCoderCustomTest.name: AtCoderCustomTest,
+}
+
+def create_remote_runner(site, **kwargs):
+    runner = REMOTE_RUNNERS.get(site)
+    if runner is None:
+        raise CphError(f"{site} のジャッジでの実行（コードテスト）には対応していません（対応: {', '.join(REMOTE_RUNNERS)}）", kind="remote_run_unsupported")
+    return runner(**kwargs)
@@ -0,0 +1,97 @@
+import hashlib
+import json
+import os
+from datetime import datetime
+from pathlib import Path
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.file_operator import atomic_write_json
+from src.file_lock import file_lock
+
+# ハッシュ計算から除外するディレクトリ（ビルド成果物など）
+HASH_IGNORE_DIRS = {"target", "__pycache__"}
+
+def source_hash(path):
+    """
+    ファイルまたはディレクトリ配下の内容からsha256を求める。
+    ディレクトリは相対パス順に走査し、存在しなければNoneを返す。
+    """
+    path = Path(str(path))
+    if not path.exists():
+        return None
+    h = hashlib.sha256()
+    if path.is_file():
+        files = [path]
+    else:
+        files = []
+        for root, dirs, names in os.walk(path):
+            dirs[:] = sorted(d for d in dirs if d not in HASH_IGNORE_DIRS and not d.startswith("."))
+            files.extend(Path(root) / n for n in names)
+        files.sort()
+    for f in files:
+        h.update(str(f.relative_to(path) if f != path else f.name).encode("utf-8"))
+        h.update(b"\0")
+        h.update(f.read_bytes())
+    return h.hexdigest()
+
+class ResultCache:
+    """
+    フルテストの結果をソースのハッシュと一緒に .cph/results.json に保存する。
+    キーは "{contest}/{problem}/{language}"。提出前チェックでソース変更の有無を判定するのに使う。
+    epochはそのときのジャッジのツールチェイン（cache_epoch.toolchain_epoch）で、変わった結果は使わない。
+    """
+    def __init__(self, path=None):
+        self.path = Path(str(path)) if path else UnifiedPathManager().cph_dir("results.json")
+
+    @staticmethod
+    def key(contest_name, problem_name, language_name):
+        return f"{contest_name}/{problem_name}/{language_name}"
+
+    def load(self):
+        if not self.path.exists():
+            return {}
+        try:
+            return json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            return {}
+
+    def get(self, key):
+        return self.load().get(key)
+
+    @staticmethod
+    def is_fresh(entry, hash_value, epoch=None):
+        """ソースもジャッジのツールチェインも前回のフル実行から変わっていないか"""
+        return hash_value is not None and bool(entry) and entry.get("source_hash") == hash_value and entry.get("epoch") == epoch
+
+    def put(self, key, hash_value, results, epoch=None):
+        entry = {
+            "source_hash": hash_value,
+            "epoch": epoch,
+            "updated_at": datetime.now().isoformat(),
+            "all_ac": bool(results) and all(r.get("verdict") == "AC" for r in results),
+            "cases": [{"name": r.get("name"), "verdict": r.get("verdict"), "time": r.get("time")} for r in results],
+        }
+        # 並行して動く別のcphの書き込みを消さないよう、読み直しから書き込みまでをロックする
+        with file_lock(self.path):
+            data = self.load()
+            data[key] = entry
+            atomic_write_json(self.path, data)
+        return entry
+
+    def drop_problem(self, contest_name, problem_name):
+        """その問題の結果を言語を問わず消し、消した件数を返す（ケースが増えて前回のフル実行が古くなったとき）"""
+        prefix = f"{contest_name}/{problem_name}/"
+        with file_lock(self.path):
+            data = self.load()
+            kept = {k: v for k, v in data.items() if not k.startswith(prefix)}
+            if len(kept) != len(data):
+                atomic_write_json(self.path, kept)
+        return len(data) - len(kept)
+
+    def drop_language(self, language_name):
+        """その言語の結果をすべて消し、消した件数を返す"""
+        with file_lock(self.path):
+            data = self.load()
+            kept = {k: v for k, v in data.items() if not k.endswith(f"/{language_name}")}
+            if len(kept) != len(data):
+                atomic_write_json(self.path, kept)
+        return len(data) - len(kept)
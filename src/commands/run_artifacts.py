@@ -0,0 +1,165 @@
+import json
+import os
+import shutil
+from datetime import datetime
+from pathlib import Path
+from .case_check import digest
+from .case_storage import ZSTD_SUFFIX, is_compressed
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.file_operator import atomic_write_json
+
+# 保持する実行履歴の最大数（古いものから削除）
+MAX_RUNS = 20
+# 失敗として扱わない判定（SKIPは--budgetで実行しなかったケース）
+NON_FAILURE_VERDICTS = (None, "AC", "SKIP")
+# replay用に入力の写しを残す大きさの上限（ファイルの大きさ、圧縮したケースは圧縮したまま）。超えたらハッシュだけ残す
+MAX_STORED_INPUT_BYTES = 8 * 1024 * 1024
+# 入力の写しの置き場（.cph/run_inputs/{ハッシュ}）。同じ入力は実行をまたいで1つだけ持つ
+INPUTS_DIR = "run_inputs"
+
+class RunArtifacts:
+    """
+    1回のテスト実行の成果物を .cph/runs/{run_id}/ に保存する。
+    - {case}.stdout / {case}.stderr: 各ケースの実際の出力
+    - 各ケースに渡した入力は、写しを .cph/run_inputs/{ハッシュ}（.zst）に残す（replayで同じ入力を流し直す。
+      ハッシュで名前を付けるので、同じ入力は何回実行しても1つだけ。大きいものはハッシュだけ）
+    - run.json: 実行のメタ情報と各ケースの結果
+    - commands.json: 実行中に使ったdocker / ojコマンド（引数・時間・終了コード、パスワードは伏せ字）
+    プロファイルや可視化など、実行に付随するファイルも同じディレクトリに置く。
+    """
+    def __init__(self, run_id=None, project_root=None):
+        self.upm = UnifiedPathManager(project_root)
+        self.run_id = run_id or datetime.now().strftime("%Y%m%d-%H%M%S-%f")
+        self.path = self.upm.runs_dir(self.run_id)
+        self.inputs_dir = self.upm.cph_dir(INPUTS_DIR)
+
+    @staticmethod
+    def case_stem(case_name):
+        # sample-1.in → sample-1
+        return os.path.splitext(os.path.basename(str(case_name)))[0]
+
+    @staticmethod
+    def all_runs(project_root=None):
+        """保存されている実行（run.jsonの内容）を問題を問わず新しい順に返す。読めないものは飛ばす"""
+        runs_root = UnifiedPathManager(project_root).runs_dir()
+        if not runs_root.exists():
+            return []
+        runs = []
+        for run in sorted((p for p in runs_root.iterdir() if p.is_dir()), reverse=True):
+            try:
+                runs.append(json.loads((run / "run.json").read_text(encoding="utf-8")))
+            except (OSError, ValueError):
+                continue
+        return runs
+
+    @staticmethod
+    def history(contest_name, problem_name, language_name, project_root=None):
+        """同じ問題・言語の過去の実行（run.jsonの内容）を新しい順に返す"""
+        return [data for data in RunArtifacts.all_runs(project_root)
+                if (data.get("contest_name"), data.get("problem_name"), data.get("language_name")) == (contest_name, problem_name, language_name)]
+
+    @staticmethod
+    def recent_failures(contest_name, problem_name, language_name, project_root=None):
+        """過去の実行で失敗したケース名 → 何回前の実行で失敗したか（0が直近）"""
+        failures = {}
+        for age, data in enumerate(RunArtifacts.history(contest_name, problem_name, language_name, project_root)):
+            for case in data.get("cases", []):
+                if case.get("verdict") not in NON_FAILURE_VERDICTS:
+                    failures.setdefault(case["name"], age)
+        return failures
+
+    @staticmethod
+    def recent_times(contest_name, problem_name, language_name, project_root=None):
+        """ケース名 → 直近に実行したときの実行時間（打ち切ったケースは除く）"""
+        times = {}
+        for data in RunArtifacts.history(contest_name, problem_name, language_name, project_root):
+            for case in data.get("cases", []):
+                if case.get("verdict") != "SKIP" and case.get("time") is not None:
+                    times.setdefault(case["name"], case["time"])
+        return times
+
+    def case_path(self, case_name, suffix):
+        return self.path / f"{self.case_stem(case_name)}.{suffix}"
+
+    def store_input(self, case_name, in_file):
+        """入力の写しを残し、(ファイル名, ハッシュ)を返す。入力が無ければ(None, None)、大きすぎれば写しはNone"""
+        if not in_file or not os.path.isfile(str(in_file)):
+            return None, None
+        in_file = str(in_file)
+        input_digest = digest(in_file)
+        if os.path.getsize(in_file) > MAX_STORED_INPUT_BYTES:
+            return None, input_digest
+        stored = input_digest + (ZSTD_SUFFIX if is_compressed(in_file) else "")
+        dest = self.inputs_dir / stored
+        if not dest.exists():
+            self.inputs_dir.mkdir(parents=True, exist_ok=True)
+            tmp = dest.with_name(f"{stored}.{os.getpid()}.tmp")
+            shutil.copyfile(in_file, str(tmp))
+            os.replace(str(tmp), str(dest))
+        return stored, input_digest
+
+    def stored_input(self, case):
+        """run.jsonのケースの入力の写し。残していなければNone（以前の記録は実行のディレクトリにある）"""
+        if not case.get("stored_input"):
+            return None
+        legacy = self.path / case["stored_input"]
+        return legacy if legacy.exists() else self.inputs_dir / case["stored_input"]
+
+    def ensure_dir(self):
+        self.path.mkdir(parents=True, exist_ok=True)
+        return self.path
+
+    def save_results(self, results, **meta):
+        self.ensure_dir()
+        cases = []
+        for r in results:
+            _, stdout, stderr = r["result"]
+            self.case_path(r["name"], "stdout").write_text(stdout or "", encoding="utf-8")
+            self.case_path(r["name"], "stderr").write_text(stderr or "", encoding="utf-8")
+            stored_input, input_digest = self.store_input(r["name"], r.get("in_file"))
+            cases.append({
+                "name": r["name"],
+                "in_file": str(r.get("in_file", "")),
+                "verdict": r.get("verdict"),
+                "time": r.get("time"),
+                "cpu_time": r.get("cpu_time"),
+                "memory_kb": r.get("memory_kb"),
+                "warnings": len(r.get("warnings") or []),
+                "runtime_error": r.get("runtime_error"),
+                "stored_input": stored_input,
+                "input_digest": input_digest,
+            })
+        # コンパイラの警告はビルド単位なので、実行全体にも1回だけ残す
+        warnings = next((r["warnings"] for r in results if r.get("warnings")), [])
+        data = {"run_id": self.run_id, "created_at": datetime.now().isoformat(), **meta, "compile_warnings": warnings, "cases": cases}
+        atomic_write_json(self.path / "run.json", data)
+        self.prune()
+        return self.path
+
+    def save_commands(self, entries):
+        """command_logの記録を残す（手でコンテナの問題を再現するため）"""
+        self.ensure_dir()
+        atomic_write_json(self.path / "commands.json", entries)
+        return self.path / "commands.json"
+
+    def prune(self, keep=MAX_RUNS):
+        runs_root = self.upm.runs_dir()
+        if not runs_root.exists():
+            return
+        runs = sorted(p for p in runs_root.iterdir() if p.is_dir())
+        removed = False
+        for old in runs[:-keep] if keep > 0 else runs:
+            if old != self.path:
+                shutil.rmtree(old, ignore_errors=True)
+                removed = True
+        if removed:
+            self.prune_inputs()
+
+    def prune_inputs(self):
+        """残っている実行のどれからも使われなくなった入力の写しを消す"""
+        if not self.inputs_dir.exists():
+            return
+        used = {case.get("stored_input") for run in self.all_runs(self.upm.project_path.root) for case in run.get("cases", [])}
+        for path in self.inputs_dir.iterdir():
+            if path.is_file() and path.name not in used and not path.name.endswith(".tmp"):
+                path.unlink()
@@ -0,0 +1,45 @@
+import re
+
+# 実行時エラー（RE）の標準エラー出力によく出る文言 → 分かりやすい説明。上から順に最初に合ったものを使う
+# languagesを付けたものはその言語のときだけ見る（無ければどの言語でも）
+DEFAULT_RUNTIME_ERRORS = [
+    {"pattern": r"^MemoryError", "kind": "MLE", "note": "メモリ不足（MemoryError）。ジャッジではMLEになる可能性があります", "languages": ["python", "pypy"]},
+    {"pattern": r"^RecursionError", "kind": "recursion", "note": "再帰が深すぎます（RecursionError）。sys.setrecursionlimitかループへの書き換えを検討してください", "languages": ["python", "pypy"]},
+    {"pattern": r"^ZeroDivisionError", "kind": "zero_division", "note": "0で割りました（ZeroDivisionError）", "languages": ["python", "pypy"]},
+    {"pattern": r"attempt to \w+ with overflow", "kind": "overflow", "note": "算術オーバーフロー。型の幅（i32 → i64など）を確認してください", "languages": ["rust"]},
+    {"pattern": r"index out of bounds", "kind": "index", "note": "配列の範囲外を参照しました", "languages": ["rust"]},
+    {"pattern": r"has overflowed its stack", "kind": "recursion", "note": "スタックオーバーフロー。再帰が深すぎる可能性があります", "languages": ["rust"]},
+    {"pattern": r"memory allocation of \d+ bytes failed", "kind": "MLE", "note": "メモリの確保に失敗しました。ジャッジではMLEになる可能性があります", "languages": ["rust"]},
+    {"pattern": r"java\.lang\.StackOverflowError", "kind": "recursion", "note": "スタックオーバーフロー（StackOverflowError）。再帰が深すぎる可能性があります"},
+    {"pattern": r"java\.lang\.OutOfMemoryError", "kind": "MLE", "note": "メモリ不足（OutOfMemoryError）。ジャッジではMLEになる可能性があります"},
+]
+
+class RuntimeErrorClassifier:
+    """
+    REになったケースの標準エラー出力を表に照らし、原因の見当（種類と説明）を付ける。
+    config.jsonのtest.runtime_errorsに同じ形の表を書くと、組み込みの表より先に見る。
+    例: {"test": {"runtime_errors": [{"pattern": "Killed", "kind": "MLE", "note": "OOM killerに止められました"}]}}
+    """
+    def __init__(self, entries=None):
+        self.entries = [self.compile(e) for e in (entries if entries is not None else DEFAULT_RUNTIME_ERRORS)]
+
+    @classmethod
+    def from_config(cls, test_config):
+        return cls(list((test_config or {}).get("runtime_errors") or []) + DEFAULT_RUNTIME_ERRORS)
+
+    @staticmethod
+    def compile(entry):
+        try:
+            pattern = re.compile(entry["pattern"], re.MULTILINE)
+        except (KeyError, re.error) as e:
+            raise ValueError(f"test.runtime_errorsのpatternが正しくありません: {entry}（{e}）")
+        return pattern, entry.get("kind", "runtime_error"), entry.get("note", ""), entry.get("languages")
+
+    def classify(self, stderr, language_name=None):
+        """{"kind": ..., "note": ...}。どれにも合わなければNone"""
+        for pattern, kind, note, languages in self.entries:
+            if languages and language_name not in languages:
+                continue
+            if pattern.search(stderr or ""):
+                return {"kind": kind, "note": note}
+        return None
@@ -0,0 +1,160 @@
+import difflib
+import hashlib
+import os
+import shutil
+from pathlib import Path
+from .case_check import digest
+from .case_lint import lint_expected_file
+from .case_storage import case_name, case_stem, expected_file, find_case_file, read_case
+from .sample_scraper import SAMPLE_PREFIX
+from .test_layout import GROUP_PREFIX
+
+# 手元のサンプルを残すときの名前（custom-sample-1.in）。sample-で始まらないので、open --refreshでも消えない
+CUSTOM_PREFIX = "custom-"
+# 取り直したサンプルとの違い
+RENAMED = "renamed"
+CHANGED = "changed"
+ADDED = "added"
+REMOVED = "removed"
+# 違いごとの扱い: 取り直したものにする / 手元のものをcustom-として残してから取り直したものにする / 何もしない
+ACCEPT = "accept"
+KEEP = "keep"
+SKIP = "skip"
+# 手元に残す内容があるのは、内容が変わったものと無くなったものだけ
+KEEPABLE = {CHANGED, REMOVED}
+
+def sample_inputs(test_dir):
+    """取得したサンプルの入力 → {"sample-1": path, "group_1/sample-1-1": path}"""
+    test_dir = Path(str(test_dir))
+    if not test_dir.is_dir():
+        return {}
+    dirs = [test_dir] + sorted(d for d in test_dir.glob(f"{GROUP_PREFIX}*") if d.is_dir())
+    inputs = {}
+    for d in dirs:
+        for p in d.iterdir():
+            if p.is_file() and p.name.startswith(SAMPLE_PREFIX) and case_name(p).endswith(".in"):
+                inputs[Path(case_stem(os.path.relpath(p, test_dir))).as_posix()] = str(p)
+    return dict(sorted(inputs.items()))
+
+def case_checksum(in_file):
+    """入力と期待出力の組のハッシュ（期待出力が無ければ入力だけ）"""
+    out_file = find_case_file(expected_file(in_file))
+    return hashlib.sha256(f"{digest(in_file)}:{digest(out_file) if out_file else ''}".encode()).hexdigest()
+
+def sample_checksums(test_dir):
+    """{"sample-1": チェックサム}。openで取得したときにproblem.jsonへ残し、手で直したかどうかの判定に使う"""
+    return {name: case_checksum(path) for name, path in sample_inputs(test_dir).items()}
+
+def lint_fresh_samples(fresh_dir, test_config):
+    """
+    取り直したサンプルの期待出力を、openで取り込んだときと同じように直す（test.lint）。
+    改行をそろえただけの手元のサンプルを、内容が変わったものと見なさないため
+    """
+    if not (test_config or {}).get("lint", True):
+        return
+    for in_file in sample_inputs(fresh_dir).values():
+        out_file = find_case_file(expected_file(in_file))
+        if out_file:
+            lint_expected_file(out_file)
+
+class Change:
+    """取り直したサンプルとの違い1件。nameは手元の名前、toは取り直した方の名前（renamedのときだけ違う）"""
+    def __init__(self, kind, name, to=None, edited=False):
+        self.kind = kind
+        self.name = name
+        self.to = to or name
+        self.edited = edited
+
+    @property
+    def default_action(self):
+        # 手で直したサンプルは消さずに残す
+        return KEEP if self.edited else ACCEPT
+
+    def __repr__(self):
+        return f"Change({self.kind}, {self.name}, {self.to})"
+
+def plan_rebind(stored, fresh, recorded=None):
+    """
+    手元のサンプルと取り直したサンプル（どちらも{名前: チェックサム}）を突き合わせる。
+    同じ名前・同じ内容のものは対象外。内容が同じで名前だけ違うものは番号が変わった（renamed）とみなす。
+    recorded（前回取得したときのチェックサム）と手元のものが違えば、手で直したもの（edited）
+    """
+    recorded = recorded or {}
+    stored_left = {n: c for n, c in stored.items() if fresh.get(n) != c}
+    fresh_left = {n: c for n, c in fresh.items() if stored.get(n) != c}
+    def edited(name):
+        return name in recorded and recorded[name] != stored.get(name)
+    changes = []
+    for name, checksum in list(stored_left.items()):
+        to = next((n for n, c in fresh_left.items() if c == checksum), None)
+        if to is not None:
+            changes.append(Change(RENAMED, name, to))
+            del stored_left[name], fresh_left[to]
+    for name in [n for n in stored_left if n in fresh_left]:
+        changes.append(Change(CHANGED, name, edited=edited(name)))
+        del stored_left[name], fresh_left[name]
+    changes += [Change(ADDED, name) for name in fresh_left]
+    changes += [Change(REMOVED, name, edited=edited(name)) for name in stored_left]
+    return changes
+
+def describe(change, stored_dir, fresh_dir):
+    """表示用の説明と差分の行"""
+    if change.kind == RENAMED:
+        return [f"{change.name} → {change.to}（内容は同じで番号が変わりました）"]
+    if change.kind == ADDED:
+        return [f"{change.to}: 追加されました"] + _preview(fresh_dir, change.to)
+    if change.kind == REMOVED:
+        return [f"{change.name}: 問題ページから無くなりました"] + _preview(stored_dir, change.name)
+    lines = [f"{change.name}: 内容が変わりました"]
+    for ext in (".in", ".out"):
+        lines += _diff(stored_dir, fresh_dir, change.name + ext)
+    return lines
+
+def _read(test_dir, rel):
+    path = find_case_file(os.path.join(str(test_dir), rel))
+    return read_case(path) if path else ""
+
+def _preview(test_dir, name):
+    return [f"  {ext}: {_read(test_dir, name + ext).strip()[:80]!r}" for ext in (".in", ".out")]
+
+def _diff(stored_dir, fresh_dir, rel):
+    before, after = _read(stored_dir, rel), _read(fresh_dir, rel)
+    if before == after:
+        return []
+    return [line.rstrip("\n") for line in difflib.unified_diff(
+        before.splitlines(keepends=True), after.splitlines(keepends=True), fromfile=f"手元/{rel}", tofile=f"問題ページ/{rel}")]
+
+def custom_name(name):
+    """group_1/sample-1-2 → custom-sample-1-2（testディレクトリの直下に置く）"""
+    return CUSTOM_PREFIX + Path(name).name
+
+def apply_rebind(test_dir, fresh_dir, decisions):
+    """
+    decisions: [(Change, action)]。先に手元のものを残してから消し、最後に取り直したものを置く
+    （番号が入れ替わっただけの組でも上書きし合わないように）。書き換えたケース名の一覧を返す
+    """
+    test_dir = Path(str(test_dir))
+    def files(base, name):
+        return [f for f in (find_case_file(os.path.join(str(base), name + ext)) for ext in (".in", ".out")) if f]
+    for change, action in decisions:
+        if action == KEEP and change.kind in KEEPABLE:
+            for f in files(test_dir, change.name):
+                shutil.copy2(f, test_dir / (custom_name(change.name) + f[len(os.path.join(str(test_dir), change.name)):]))
+    for change, action in decisions:
+        if action != SKIP and change.kind != ADDED:
+            for f in files(test_dir, change.name):
+                os.remove(f)
+    touched = []
+    for change, action in decisions:
+        if action == SKIP or change.kind == REMOVED:
+            continue
+        for f in files(fresh_dir, change.to):
+            dest = test_dir / os.path.relpath(f, str(fresh_dir))
+            dest.parent.mkdir(parents=True, exist_ok=True)
+            shutil.copy2(f, dest)
+        touched.append(change.to)
+    # 空になった小課題のディレクトリは消す
+    for group_dir in test_dir.glob(f"{GROUP_PREFIX}*"):
+        if group_dir.is_dir() and not any(group_dir.iterdir()):
+            group_dir.rmdir()
+    return touched
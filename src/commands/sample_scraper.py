@@ -0,0 +1,293 @@
+import html as html_lib
+import os
+import re
+from html.parser import HTMLParser
+from src import http_client
+from src.error_hints import CphError
+from src.file_operator import atomic_write
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from .test_layout import group_dir_name
+
+# 入力例 1 / Sample Input 1 / Example Input 2 などの見出し
+INPUT_HEADING = re.compile(r"(入力例|sample\s*input|example\s*input)\s*(\d*)", re.IGNORECASE)
+# 「入出力例」（サンプルの節の見出し）は出力例ではない
+OUTPUT_HEADING = re.compile(r"((?<!入)出力例|sample\s*output|example\s*output)\s*(\d*)", re.IGNORECASE)
+# 小課題ごと・グループごとにサンプルを分けている見出し（小課題 1 / Subtask 2 / Sample Group 1）
+GROUP_HEADING = re.compile(r"(小課題|サブタスク|subtask|sample\s*group|サンプルグループ)\s*(\d+)", re.IGNORECASE)
+HEADING_TAGS = {"h1", "h2", "h3", "h4", "h5", "h6"}
+# 中身の無い要素（終了タグが来ない）
+VOID_TAGS = {"br", "hr", "img", "input", "meta", "link", "area", "base", "col", "embed", "source", "track", "wbr"}
+# --debug-scrapeで取得したHTMLを残す場所（.cph配下）。tests/scrape_corpusに足すと回帰テストになる（src.test_support.scrape_corpus）
+DUMP_DIR = "scrape"
+# oj download・問題ページから取り出したサンプルのファイル名（sample-1.in など）。open --refreshで取り直すのはこれだけ
+SAMPLE_PREFIX = "sample-"
+
+class Sample(tuple):
+    """
+    サンプルの(入力, 出力)の組。groupは小課題・グループの番号（"1"など）で、分かれていなければNone。
+    ただの組として比べたり、input_text, output_text = sample と展開したりできる
+    """
+    def __new__(cls, input_text, output_text, group=None):
+        sample = super().__new__(cls, (input_text, output_text))
+        sample.group = group
+        return sample
+
+class Block:
+    """見出しか<pre>のひとまとまり。祖先のクラスで「どこにあるか」を判断する"""
+    def __init__(self, kind, tag, ancestor_classes):
+        self.kind = kind
+        self.tag = tag
+        self.ancestor_classes = ancestor_classes
+        self.parts = []
+
+    @property
+    def text(self):
+        return "".join(self.parts)
+
+    def within(self, cls):
+        return cls in self.ancestor_classes
+
+class BlockParser(HTMLParser):
+    """HTMLを見出し・<pre>・Codeforcesの div.title の並びにする（壊れたマークアップでも落ちない）"""
+    def __init__(self):
+        super().__init__(convert_charrefs=True)
+        self.stack = []
+        self.blocks = []
+        self.current = None
+        self.depth = None
+
+    def classes(self):
+        return {c for _, cls in self.stack for c in cls}
+
+    def handle_starttag(self, tag, attrs):
+        cls = set((dict(attrs).get("class") or "").split())
+        if self.current is not None:
+            if tag == "br":
+                self.current.parts.append("\n")
+        elif tag == "pre" or tag in HEADING_TAGS or (tag == "div" and "title" in cls):
+            self.current = Block("pre" if tag == "pre" else "heading", tag, self.classes() | cls)
+            self.depth = len(self.stack)
+        if tag not in VOID_TAGS:
+            self.stack.append((tag, cls))
+
+    def handle_endtag(self, tag):
+        if tag in VOID_TAGS or not any(t == tag for t, _ in self.stack):
+            return
+        while self.stack:
+            t, _ = self.stack.pop()
+            if self.current is not None and len(self.stack) == self.depth:
+                self.blocks.append(self.current)
+                self.current = None
+            elif self.current is not None and t == "div" and self.current.kind == "pre":
+                # Codeforcesは<pre>の中で1行ずつ<div>に分けている
+                self.current.parts.append("\n")
+            if t == tag:
+                break
+
+    def handle_data(self, data):
+        if self.current is not None:
+            self.current.parts.append(data)
+
+def parse_blocks(html):
+    parser = BlockParser()
+    parser.feed(html)
+    parser.close()
+    return parser.blocks
+
+def normalize(text):
+    lines = text.replace("\r\n", "\n").replace("\r", "\n").split("\n")
+    while lines and not lines[0].strip():
+        lines.pop(0)
+    return "\n".join(line.rstrip() for line in lines).rstrip("\n") + "\n"
+
+def _heading_groups(blocks):
+    """
+    見出しごとに(見出し, 入力例・出力例のマッチ, グループ番号)を返す。グループは「小課題 N」などの見出しから
+    次の関係ない見出し（入出力例 / 制約など）までで、入力例の見出し自体に書いてあればそれを使う
+    """
+    group = None
+    for block in blocks:
+        if block.kind != "heading":
+            yield block, None, group
+            continue
+        text = " ".join(block.text.split())
+        g = GROUP_HEADING.search(text)
+        m = INPUT_HEADING.search(text) or OUTPUT_HEADING.search(text)
+        if m:
+            yield block, m, g.group(2) if g else group
+            continue
+        group = g.group(2) if g else None
+        yield block, None, group
+
+def _heading_pairs(blocks):
+    """
+    見出し（入力例 N / 出力例 N）の直後の<pre>を集め、(グループ, 番号)で組にする。同じ番号は最初のものを使う。
+    小課題ごとに番号が1から振り直されていても、グループが違えば別のサンプルにする
+    """
+    inputs, outputs = {}, {}
+    pending = None
+    for block, m, group in _heading_groups(blocks):
+        if block.kind == "heading":
+            pending = None
+            if m:
+                target = inputs if m.re is INPUT_HEADING else outputs
+                pending = (target, (group, m.group(2) or str(sum(1 for g, _ in target if g == group) + 1)))
+        elif pending is not None:
+            target, key = pending
+            target.setdefault(key, normalize(block.text))
+            pending = None
+    return [Sample(inputs[k], outputs[k], group=k[0]) for k in inputs if k in outputs]
+
+def by_atcoder_lang_ja(blocks):
+    """AtCoder: 日本語の問題文（span.lang-ja）の 入力例 N / 出力例 N"""
+    return _heading_pairs([b for b in blocks if b.within("lang-ja")])
+
+def by_heading(blocks):
+    """サイトを問わず、入力例・Sample Inputなどの見出しの直後の<pre>"""
+    return _heading_pairs(blocks)
+
+def by_codeforces_sample_test(blocks):
+    """Codeforces: div.sample-test の div.input / div.output の<pre>"""
+    samples = [b for b in blocks if b.kind == "pre" and b.within("sample-test")]
+    inputs = [normalize(b.text) for b in samples if b.within("input")]
+    outputs = [normalize(b.text) for b in samples if b.within("output")]
+    return list(zip(inputs, outputs)) if len(inputs) == len(outputs) else []
+
+def by_pre_pairs(blocks):
+    """最後の手段: 問題文の<pre>を前から2つずつ組にする（入力形式などの<pre>が混ざると誤るので数が偶数のときだけ）"""
+    pres = [normalize(b.text) for b in blocks if b.kind == "pre" and not b.within("prettyprint")]
+    return list(zip(pres[0::2], pres[1::2])) if pres and len(pres) % 2 == 0 else []
+
+# 上から順に試し、最初にサンプルが取れたものを使う
+STRATEGIES = [
+    ("atcoder-lang-ja", by_atcoder_lang_ja),
+    ("codeforces-sample-test", by_codeforces_sample_test),
+    ("heading", by_heading),
+    ("pre-pairs", by_pre_pairs),
+]
+
+def count_sample_headings(blocks):
+    """
+    ページにある「入力例 N」の見出しの数（番号の重複は1つ）。AtCoderのように日本語と英語の両方があるときは日本語の方を数える。
+    見出しの無いページ（Codeforcesなど）は0
+    """
+    headings = [b for b in blocks if b.kind == "heading"]
+    if any(b.within("lang-ja") for b in headings):
+        headings = [b for b in headings if b.within("lang-ja")]
+    numbers = set()
+    for _, m, group in _heading_groups(headings):
+        if m and m.re is INPUT_HEADING:
+            numbers.add((group, m.group(2) or str(sum(1 for g, _ in numbers if g == group) + 1)))
+    return len(numbers)
+
+def scrape_samples(html):
+    """
+    問題ページのHTMLからサンプルの(入力, 出力)の組を取り出す。
+    (組の一覧, 使った方法の名前かNone, 方法ごとに取れた数) を返す
+    """
+    blocks = parse_blocks(html)
+    report = []
+    matched, samples = None, []
+    for name, strategy in STRATEGIES:
+        found = strategy(blocks)
+        report.append((name, len(found)))
+        if found and matched is None:
+            matched, samples = name, found
+    return samples, matched, report
+
+def page_title(html):
+    """<title>の中身（AtCoderなら "A - N-choice question"）。無ければNone"""
+    m = re.search(r"<title[^>]*>(.*?)</title>", html, re.IGNORECASE | re.DOTALL)
+    return (" ".join(html_lib.unescape(m.group(1)).split()) or None) if m else None
+
+def sample_paths(test_dir, sample, index):
+    """
+    サンプルの.in / .outの書き出し先。グループのあるものはtest/group_N/sample-N-{index}（ケース名が他と重ならないように）
+    """
+    group = getattr(sample, "group", None)
+    if group is None:
+        return [os.path.join(str(test_dir), f"{SAMPLE_PREFIX}{index}{ext}") for ext in (".in", ".out")]
+    return [os.path.join(str(test_dir), group_dir_name(group), f"{SAMPLE_PREFIX}{group}-{index}{ext}") for ext in (".in", ".out")]
+
+class SampleScraper:
+    """
+    oj downloadが使えないときに、問題ページを取得してサンプルを取り出す。
+    debugにすると、取得したHTMLを.cph/scrape/に残し、どの方法で取れたかを表示する（マークアップが変わったときの報告用）。
+    """
+    def __init__(self, upm=None, debug=False):
+        self.upm = upm or UnifiedPathManager()
+        self.debug = debug
+        # 取得したページ（URL → HTML）
+        self.pages = {}
+
+    def fetch(self, url):
+        html = http_client.get(url).text()
+        self.pages[url] = html
+        return html
+
+    def page(self, url):
+        """取得済みならそのHTML（openでサンプルを取り出したページから制約も読む）。無ければ取得する"""
+        return self.pages[url] if url in self.pages else self.fetch(url)
+
+    def dump(self, url, html):
+        name = re.sub(r"[^\w.-]+", "_", url.split("://", 1)[-1]).strip("_") + ".html"
+        path = self.upm.cph_dir(DUMP_DIR, name)
+        atomic_write(path, html)
+        return path
+
+    def download(self, url, test_dir, html=None):
+        """htmlを渡せば取得し直さずにそれから取り出す（refreshで問題文と同じ取得を使うとき）"""
+        html = self.fetch(url) if html is None else html
+        samples, matched, report = scrape_samples(html)
+        dump_path = self.dump(url, html) if self.debug else None
+        if self.debug:
+            print(f"[scrape] {url} のHTMLを保存しました: {dump_path}")
+            for name, count in report:
+                mark = "→" if name == matched else " "
+                print(f"[scrape] {mark} {name}: {count}組")
+        if not samples:
+            where = f"（HTML: {dump_path}）" if dump_path else ""
+            raise CphError(f"問題ページからサンプルを取り出せませんでした: {url}{where}", kind="scrape_failed")
+        # 一部だけ取れて黙って進むのが一番困るので、見出しの数と合わなければHTMLを残して止める
+        expected = count_sample_headings(parse_blocks(html))
+        if expected and expected != len(samples):
+            dump_path = dump_path or self.dump(url, html)
+            got = f"{len(samples)}組しか取り出せませんでした" if len(samples) < expected else f"{len(samples)}組取り出しました"
+            raise CphError(f"問題ページの入力例は{expected}個ですが、{got}（{matched}）: {url}（HTML: {dump_path}）", kind="sample_count_mismatch")
+        os.makedirs(str(test_dir), exist_ok=True)
+        counts = {}
+        for sample in samples:
+            # 番号はグループごとに1から
+            group = getattr(sample, "group", None)
+            counts[group] = counts.get(group, 0) + 1
+            for path, text in zip(sample_paths(test_dir, sample, counts[group]), sample):
+                atomic_write(path, text)
+        groups = sorted({s.group for s in samples if getattr(s, "group", None)}, key=int)
+        where = f"、小課題ごとに{', '.join(group_dir_name(g) for g in groups)}へ" if groups else ""
+        print(f"[scrape] サンプルを{len(samples)}組取得しました（{matched}{where}）")
+        return samples
+
+    def regroup(self, url, test_dir):
+        """
+        oj downloadで通し番号のまま書き出したサンプル（test/sample-N）を、問題ページの小課題ごとにgroup_N/へ分ける。
+        ページから取り出した組と数・入力が合うときだけ動かし、分けたサンプルの一覧を返す（分けなければ[]）
+        """
+        samples, _, _ = scrape_samples(self.page(url))
+        if not any(getattr(s, "group", None) for s in samples):
+            return []
+        downloaded = [[os.path.join(str(test_dir), f"{SAMPLE_PREFIX}{i}{ext}") for ext in (".in", ".out")] for i in range(1, len(samples) + 1)]
+        if os.path.exists(os.path.join(str(test_dir), f"{SAMPLE_PREFIX}{len(samples) + 1}.in")):
+            return []
+        for (in_path, out_path), sample in zip(downloaded, samples):
+            if not (os.path.isfile(in_path) and os.path.isfile(out_path)):
+                return []
+            with open(in_path, "r", encoding="utf-8", errors="replace") as f:
+                if normalize(f.read()) != sample[0]:
+                    return []
+        counts = {}
+        for paths, sample in zip(downloaded, samples):
+            counts[sample.group] = counts.get(sample.group, 0) + 1
+            for src, dst in zip(paths, sample_paths(test_dir, sample, counts[sample.group])):
+                os.makedirs(os.path.dirname(dst), exist_ok=True)
+                os.replace(src, dst)
+        return samples
@@ -0,0 +1,153 @@
+import getpass
+import os
+import re
+from datetime import datetime
+from src.file_operator import atomic_write
+from src.notifier import SafeValues
+
+# 言語ごとの行コメント
+COMMENT_PREFIXES = {
+    "python": "#",
+    "pypy": "#",
+    "rust": "//",
+}
+DEFAULT_COMMENT_PREFIX = "#"
+# "strip": true のときに提出前に取り除く行。patternsは1行単位、blocksは開始行〜終了行（両端を含む）。
+# blocksの3つ目（#elseなど）があれば、その行から終了行の手前までは提出用の分岐として残す
+DEFAULT_STRIP = {
+    "python": {"patterns": [r"^\s*print\(.*file=sys\.stderr.*\)\s*$"]},
+    "pypy": {"patterns": [r"^\s*print\(.*file=sys\.stderr.*\)\s*$"]},
+    "rust": {"patterns": [r"^\s*(eprintln!|eprint!|dbg!)\(.*\);?\s*$"]},
+    "default": {"blocks": [[r"^\s*#\s*ifdef\s+LOCAL\b", r"^\s*#\s*endif\b", r"^\s*#\s*else\b"]]},
+}
+# どの言語でも使えるデバッグ区間（{c}は言語のコメント）: {c} DEBUG 〜 {c} END DEBUG
+DEBUG_BLOCK = (r"^\s*{c}\s*DEBUG\s*$", r"^\s*{c}\s*END DEBUG\s*$")
+# header/footerで使える変数
+TEMPLATE_VARIABLES = ("author", "time", "contest", "problem", "language")
+# 加工した提出用ファイルの既定の置き場（コンテナからも/workspace/.temp/submitで見える）。submitは実行ごとの一時ディレクトリを使う
+BUNDLE_DIR = os.path.join(".temp", "submit")
+
+def pick(value, language_name):
+    """文字列ならそのまま、{言語: 文字列}なら言語ごと（無ければ"default"）の値を返す"""
+    if isinstance(value, dict):
+        return value.get(language_name, value.get("default"))
+    return value
+
+def comment_lines(text, prefix):
+    return "\n".join(f"{prefix} {line}".rstrip() for line in text.splitlines())
+
+class UnclosedBlock(ValueError):
+    """取り除く区間の終了行が無い（消しすぎないよう、何も取り除かない）"""
+
+def strip_lines(source, patterns=(), blocks=()):
+    """
+    patternsに一致する行と、blocksの開始行〜終了行を取り除く。3つ目の行（#else）の後ろは残す。
+    閉じていないブロックがあればUnclosedBlock
+    """
+    line_res = [re.compile(p) for p in patterns]
+    block_res = [(re.compile(b[0]), re.compile(b[1]), re.compile(b[2]) if len(b) > 2 else None) for b in blocks]
+    out = []
+    block = None
+    keeping = False
+    for number, line in enumerate(source.splitlines(keepends=True), 1):
+        if block is not None:
+            _, end, other = block
+            if end.search(line):
+                block, keeping = None, False
+                continue
+            if not keeping:
+                keeping = other is not None and bool(other.search(line))
+                continue
+        else:
+            block = next(((number, end, other) for start, end, other in block_res if start.search(line)), None)
+            if block is not None:
+                continue
+        if any(r.search(line) for r in line_res):
+            continue
+        out.append(line)
+    if block is not None:
+        raise UnclosedBlock(f"{block[0]}行目から始まる区間が閉じていません")
+    return "".join(out)
+
+class SubmissionBundler:
+    """
+    提出するソースを加工した提出用ファイルを作る（ヘッダー・フッターの付与、デバッグ出力の除去）。
+    作業中のファイルは変更しない。
+    config.jsonのsubmitで設定する。例:
+    {"submit": {"author": "alice", "header": "author: {author} / {time}", "footer": {"rust": "submitted by cph"}}}
+    header/footerは言語のコメントとして挿入する。"comment": false ならそのまま挿入する（#![allow(...)] など）。
+    使える変数: {author} {time} {contest} {problem} {language}（TEMPLATE_VARIABLES。それ以外の{name}はそのまま残す）
+    "strip": true で eprintln!/dbg!・#ifdef LOCAL（#elseの分岐は残す）・DEBUG区間を取り除く。
+    {"strip": {"patterns": ["^\\s*debug_"], "blocks": [["^# BEGIN LOCAL", "^# END LOCAL"]]}} で独自の規則も指定できる。
+    加工した場合、提出前にジャッジ相当のコマンドでコンパイルを確認する（"compile_check": false で無効）。
+    """
+    def __init__(self, config=None):
+        self.config = config or {}
+
+    def comment_prefix(self, language_name):
+        return COMMENT_PREFIXES.get(language_name, DEFAULT_COMMENT_PREFIX)
+
+    def render(self, key, language_name, **values):
+        template = pick(self.config.get(key), language_name)
+        if not template:
+            return None
+        values = {
+            "author": self.config.get("author") or getpass.getuser(),
+            "time": datetime.now().strftime("%Y-%m-%d %H:%M:%S"),
+            "language": language_name,
+            **values,
+        }
+        # 知らない{name}はそのまま残す。{の閉じ忘れなど書式そのものの誤りは、どの設定かを示して止める
+        try:
+            text = template.format_map(SafeValues(values))
+        except (ValueError, IndexError, AttributeError) as e:
+            names = " ".join(f"{{{name}}}" for name in TEMPLATE_VARIABLES)
+            raise ValueError(f"submit.{key}の書式が不正です: {template!r}（{e}）。使える変数: {names}。{{や}}そのものは{{{{ }}}}と書きます")
+        if self.config.get("comment", True):
+            text = comment_lines(text, self.comment_prefix(language_name))
+        return text
+
+    def strip_rules(self, language_name):
+        strip = self.config.get("strip")
+        if not strip:
+            return [], []
+        c = re.escape(self.comment_prefix(language_name))
+        patterns = []
+        blocks = [[DEBUG_BLOCK[0].format(c=c), DEBUG_BLOCK[1].format(c=c)]]
+        rules = [DEFAULT_STRIP.get(language_name, {}), DEFAULT_STRIP["default"]]
+        if isinstance(strip, dict):
+            rules.append(pick(strip, language_name) if language_name in strip else strip)
+        for rule in rules:
+            patterns.extend(rule.get("patterns", []))
+            blocks.extend(rule.get("blocks", []))
+        return patterns, blocks
+
+    def is_active(self, language_name):
+        return bool(pick(self.config.get("header"), language_name) or pick(self.config.get("footer"), language_name) or self.config.get("strip"))
+
+    def bundle(self, source, language_name, **values):
+        patterns, blocks = self.strip_rules(language_name)
+        if patterns or blocks:
+            try:
+                source = strip_lines(source, patterns, blocks)
+            except UnclosedBlock as e:
+                print(f"[警告] {e}。デバッグ用の行は取り除かずに提出します")
+        header = self.render("header", language_name, **values)
+        footer = self.render("footer", language_name, **values)
+        parts = []
+        if header:
+            parts.append(header + "\n")
+        parts.append(source if source.endswith("\n") or not source else source + "\n")
+        if footer:
+            parts.append(footer + "\n")
+        return "".join(parts)
+
+    def write(self, source_path, language_name, dest_dir=BUNDLE_DIR, **values):
+        """加工が必要ならdest_dirに提出用ファイルを書き出してそのパスを返す。不要ならNone"""
+        if not self.is_active(language_name):
+            return None
+        with open(str(source_path), "r", encoding="utf-8") as f:
+            source = f.read()
+        path = os.path.join(str(dest_dir), os.path.basename(str(source_path)))
+        atomic_write(path, self.bundle(source, language_name, **values))
+        return path
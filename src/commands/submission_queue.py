@@ -0,0 +1,81 @@
+import json
+import os
+import shutil
+from datetime import datetime
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 保留した提出の置き場（.cph/queue/{id}/meta.json と提出するファイル）
+QUEUE_DIR = "queue"
+META_FILE = "meta.json"
+# oj submitの失敗がネットワークによるものかを判断する文言
+NETWORK_ERROR_MARKERS = (
+    "Failed to establish a new connection",
+    "Temporary failure in name resolution",
+    "Name or service not known",
+    "Network is unreachable",
+    "Max retries exceeded",
+    "Connection refused",
+    "Connection reset",
+    "Read timed out",
+)
+
+def is_network_error(output):
+    return any(marker in (output or "") for marker in NETWORK_ERROR_MARKERS)
+
+class SubmissionQueue:
+    """
+    ネットワークに接続できないときの提出を保留する。提出するファイル（加工後のもの）と、
+    提出に必要な情報を .cph/queue/{id}/ に保存し、submit --flush で古い順に送る。
+    """
+    def __init__(self, upm=None):
+        self.upm = upm or UnifiedPathManager()
+
+    def root(self):
+        return self.upm.cph_dir(QUEUE_DIR)
+
+    def enqueue(self, file_path, contest_name, problem_name, language_name, language_id=None, reason=None):
+        now = datetime.now()
+        item_id = f"{now.strftime('%Y%m%d-%H%M%S-%f')}-{contest_name}_{problem_name}"
+        item_dir = self.root() / item_id
+        item_dir.mkdir(parents=True, exist_ok=True)
+        file_name = os.path.basename(str(file_path))
+        # 保留中に作業中のファイルを書き換えても、保留した時点の内容を送る
+        shutil.copy(str(file_path), str(item_dir / file_name))
+        meta = {
+            "id": item_id,
+            "contest_name": contest_name,
+            "problem_name": problem_name,
+            "language_name": language_name,
+            "language_id": language_id,
+            "file": file_name,
+            "queued_at": now.isoformat(),
+            "attempts": 0,
+            "last_error": reason,
+        }
+        atomic_write_json(item_dir / META_FILE, meta)
+        return meta
+
+    def items(self):
+        """保留中の提出（古い順）"""
+        root = self.root()
+        if not root.exists():
+            return []
+        items = []
+        for item_dir in sorted(p for p in root.iterdir() if p.is_dir()):
+            try:
+                items.append(json.loads((item_dir / META_FILE).read_text(encoding="utf-8")))
+            except (OSError, ValueError):
+                continue
+        return items
+
+    def file_path(self, item):
+        return self.root() / item["id"] / item["file"]
+
+    def record_failure(self, item, error):
+        item = {**item, "attempts": item.get("attempts", 0) + 1, "last_error": error}
+        atomic_write_json(self.root() / item["id"] / META_FILE, item)
+        return item
+
+    def remove(self, item):
+        shutil.rmtree(self.root() / item["id"], ignore_errors=True)
@@ -0,0 +1,65 @@
+from datetime import datetime
+
+# tag --checklist で問題ごとの項目を区切る文字（項目の文に,を含められるように）
+CHECKLIST_SEPARATOR = ";"
+# problem.jsonに残す回答の記録の件数の上限（古いものから捨てる）
+MAX_CHECKLIST_HISTORY = 50
+
+def parse_checklist(value):
+    """"オーバーフローは？;制約を読み直した？" → ["オーバーフローは？", "制約を読み直した？"]（空要素は除く）"""
+    if not value or value is True:
+        return []
+    return [item.strip() for item in str(value).split(CHECKLIST_SEPARATOR) if item.strip()]
+
+def checklist_items(submit_config, problem_items=None):
+    """
+    提出前に確かめる項目。config.jsonのsubmit.checklist（全問題）のあとに、問題ごとの項目（tag --checklist）を続ける。
+    例: {"submit": {"checklist": ["オーバーフローは確認した？", "制約を読み直した？"]}}
+    """
+    items = submit_config.get("checklist") or []
+    if isinstance(items, str):
+        items = [items]
+    merged = []
+    for item in list(items) + list(problem_items or []):
+        if item and item not in merged:
+            merged.append(item)
+    return merged
+
+class SubmitChecklist:
+    """提出前のチェックリストを1項目ずつy/nで確かめる。nが1つでもあれば提出しない"""
+    def __init__(self, items, ask=None):
+        self.items = list(items)
+        self.ask = ask or input
+
+    def run(self):
+        """[{"item", "answer": True/False}] を返す。nと答えたところで止める（残りは聞かない）"""
+        answers = []
+        for i, item in enumerate(self.items, 1):
+            try:
+                ans = self.ask(f"[チェックリスト {i}/{len(self.items)}] {item} (y/N): ")
+            except EOFError:
+                # stdinが端末でない（パイプやCI）ときは答えられないので、nとして扱う
+                print("\n[WARN] 確認できないため提出しません（チェックリストを省くには --yes）")
+                ans = ""
+            ok = ans.strip().lower() in ("y", "yes")
+            answers.append({"item": item, "answer": ok})
+            if not ok:
+                break
+        return answers
+
+def record_checklist(index, contest_name, problem_name, language_name, answers, skipped=False):
+    """チェックリストの回答を問題ごとの記録（problem.jsonのchecklist_history）に残す"""
+    record = {
+        "at": datetime.now().isoformat(),
+        "language": language_name,
+        "skipped": skipped,
+        "answers": answers,
+        "passed": skipped or all(a["answer"] for a in answers),
+    }
+
+    def apply(entry):
+        history = entry.setdefault("checklist_history", [])
+        history.append(record)
+        del history[:-MAX_CHECKLIST_HISTORY]
+    index.update(contest_name, problem_name, apply)
+    return record
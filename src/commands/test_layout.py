@@ -0,0 +1,71 @@
+import fnmatch
+import os
+from .case_storage import case_name
+
+# テストディレクトリに置く除外パターンのファイル（1行1パターン、#以降はコメント）
+CPHIGNORE_FILE = ".cphignore"
+# 名前付きのテストセット（ファイル名に対するglob）。config.jsonのtest.setsで追加・上書きできる
+DEFAULT_TEST_SETS = {
+    "samples": ["sample*"],
+    "stress": ["stress*", "random*", "large*"],
+    "all": ["*"],
+}
+ALL_SET = "all"
+# 小課題ごとのサンプルを置くディレクトリ（test/group_1/）。--set group_1 でそのグループだけを実行する
+GROUP_PREFIX = "group_"
+
+def group_dir_name(group):
+    return f"{GROUP_PREFIX}{group}"
+
+def group_of(in_file):
+    """test/group_1/sample-1-1.in → "group_1"。グループのディレクトリに無いケースはNone"""
+    parent = os.path.basename(os.path.dirname(str(in_file)))
+    return parent if parent.startswith(GROUP_PREFIX) else None
+
+def read_ignore_file(path):
+    try:
+        with open(path, "r", encoding="utf-8") as f:
+            lines = f.read().splitlines()
+    except OSError:
+        return []
+    patterns = []
+    for line in lines:
+        line = line.split("#", 1)[0].strip()
+        if line:
+            patterns.append(line)
+    return patterns
+
+def matches_any(in_file, patterns):
+    name = case_name(in_file)
+    stem = name[:-len(".in")] if name.endswith(".in") else name
+    return any(fnmatch.fnmatch(name, p) or fnmatch.fnmatch(stem, p) for p in patterns)
+
+class TestLayout:
+    """
+    テストケースの発見規則。.cphignoreとconfig.jsonのtest.ignoreで除外し、
+    --set で名前付きのテストセットに絞り込む。test/group_N/があれば、group_Nもテストセットとして選べる。
+    例: {"test": {"ignore": ["notes*"], "sets": {"edge": ["edge-*", "sample-1"]}}}
+    """
+    def __init__(self, test_dir, config=None):
+        config = config or {}
+        self.ignore = list(config.get("ignore", [])) + read_ignore_file(os.path.join(str(test_dir), CPHIGNORE_FILE))
+        self.sets = dict(DEFAULT_TEST_SETS)
+        self.sets.update(config.get("sets", {}))
+        test_dir = str(test_dir)
+        self.groups = sorted(d for d in (os.listdir(test_dir) if os.path.isdir(test_dir) else [])
+                             if d.startswith(GROUP_PREFIX) and os.path.isdir(os.path.join(test_dir, d)))
+
+    def discover(self, in_files):
+        return [f for f in in_files if not matches_any(f, self.ignore)]
+
+    def select(self, in_files, set_name=None):
+        """除外を適用したうえでテストセットに含まれるケースを返す。未知のセット名はValueError"""
+        in_files = self.discover(in_files)
+        if not set_name or set_name is True:
+            return in_files
+        if set_name not in self.sets and set_name in self.groups:
+            return [f for f in in_files if group_of(f) == set_name]
+        if set_name not in self.sets:
+            raise ValueError(f"テストセット {set_name} は定義されていません（{', '.join(sorted(self.sets) + self.groups)}）")
+        patterns = self.sets[set_name]
+        return [f for f in in_files if matches_any(f, [patterns] if isinstance(patterns, str) else patterns)]
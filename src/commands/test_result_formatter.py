@@ -1,55 +1,145 @@
 import os
+from itertools import zip_longest
+from .comparator import ExactComparator, match_any
+from .case_storage import is_compressed
+from . import color_theme
+from . import output_mode
+from src.cpu_time import is_idle_bound
+from src.output_limit import OLE
+from .text_width import display_width, pad, terminal_width, truncate, wrap
+
+def judge_verdict(result, comparator=None):
+    """テスト結果dictからAC/WA/RE/OLEを判定する。comparator省略時は完全一致で比較する"""
+    returncode, stdout, _ = result["result"]
+    if result.get("output_limit_exceeded"):
+        return OLE
+    if returncode != 0:
+        return "RE"
+    comparator = comparator or ExactComparator()
+    candidates = [result["expected"]] + list(result.get("alternatives") or [])
+    if match_any(comparator, candidates, stdout, input_file=result.get("in_file")):
+        return "AC"
+    return "WA"
+
+# 表の列の最小幅（'Expected' / 'Output' の長さ）
+MIN_EXPECTED_WIDTH = 8
+MIN_OUTPUT_WIDTH = 6
+# ケース名を切り詰めても残す幅
+MIN_NAME_WIDTH = 12
 
 class ResultFormatter:
-    def __init__(self, result):
+    """
+    1ケース分の結果の表示。widthは端末の幅（省略時は検出する）で、
+    狭い端末ではケース名を…で切り詰め、期待出力と出力の表は列の中で折り返す。
+    accessibleのときは罫線と横並びの表を使わず、見出しを付けた行で出す（スクリーンリーダー向け）。
+    """
+    def __init__(self, result, width=None, palette=None, accessible=None):
         self.result = result
+        self.width = width or terminal_width()
+        # 色の有無・テーマはmainで決めたもの（--color / NO_COLOR / config.jsonのcolor.theme）
+        self.palette = palette or color_theme.current()
+        self.accessible = output_mode.is_accessible() if accessible is None else accessible
 
     @staticmethod
     def color_text(text, color):
-        colors = {
-            "red": "\033[31m",
-            "green": "\033[32m",
-            "yellow": "\033[33m",
-            "reset": "\033[0m"
-        }
-        return f"{colors.get(color, '')}{text}{colors['reset']}"
+        """色の名前で常に色を付ける（設定に関わらず使いたいとき用）"""
+        codes = color_theme.ANSI_CODES
+        return f"{codes.get(color, '')}{text}{codes['reset']}"
 
     def format(self):
+        if self.accessible:
+            return self.format_accessible()
         parts = [
             self._format_header(),
             "-" * 17,
             self._format_input(),
             "-" * 17,
             self._format_table(),
+            self._format_alternatives(),
             self._format_input_error_bar(),
             self._format_error(),
         ]
         return "\n".join([p for p in parts if p])
 
+    def format_accessible(self):
+        """
+        1行ずつ読み上げても分かる形。判定はPASS / FAILの言葉で、期待出力と出力は見出しの下に並べ、
+        違う行は色ではなく行番号で示す。ケース名は切り詰めない
+        """
+        r = self.result
+        stdout, stderr = r["result"][1], r["result"][2]
+        lines = [f"{r['name']}: {self._format_verdict()} {r['time']:.3f}秒{self._format_cpu_time()}"]
+        input_text = self._format_input()
+        if input_text:
+            lines += ["入力:", input_text]
+        exp_lines = r["expected"].strip().splitlines()
+        out_lines = stdout.strip().splitlines()
+        lines += ["期待する出力:"] + exp_lines + ["実際の出力:"] + (out_lines or ["（出力なし）"])
+        # 誤差を許す比較でACになったときは、文字列が違っても違う行としない
+        differing = [str(i) for i, (e, o) in enumerate(zip_longest(exp_lines, out_lines), 1) if e != o]
+        if differing and self.verdict() != "AC":
+            lines.append(f"違う行: {', '.join(differing)}行目")
+        if self._format_alternatives():
+            lines.append(self._format_alternatives())
+        if self.result.get("output_limit_exceeded"):
+            lines.append(self._format_output_limit())
+        if self.result.get("runtime_error"):
+            lines.append(self._format_runtime_error())
+        if stderr:
+            lines += ["標準エラー出力:", stderr.strip()]
+        return "\n".join(lines)
+
+    def verdict(self):
+        # 実行時に比較設定を反映した判定済みverdictがあればそれを使う
+        return self.result.get("verdict") or judge_verdict(self.result)
+
     def _format_header(self):
         r = self.result
         name = r["name"]
-        returncode, _, _ = r["result"]
         time_sec = r["time"]
-        expected = r["expected"]
-        stdout = r["result"][1]
-        if returncode != 0:
-            verdict_colored = self.color_text("RE", "yellow")
-        elif stdout.strip() == expected.strip():
-            verdict_colored = self.color_text("AC", "green")
-        else:
-            verdict_colored = self.color_text("WA", "red")
-        return f"{name}  {verdict_colored}  {time_sec:.3f}秒"
+        rest = f"  {self._format_verdict()}  {time_sec:.3f}秒{self._format_cpu_time()}"
+        name = truncate(name, max(self.width - display_width(rest), MIN_NAME_WIDTH), position="middle")
+        return f"{name}{rest}"
+
+    def _format_verdict(self):
+        verdict = self.verdict()
+        label = output_mode.verdict_label(verdict) if self.accessible else verdict
+        text = self.palette.paint(label, verdict)
+        warnings = self.result.get("warnings") or []
+        if verdict == "AC" and warnings:
+            # 失敗にはしないが、コンパイラの警告があったことを判定の横に出す
+            text += " " + self.palette.paint(f"(警告あり: {len(warnings)}件)", "warning")
+        return text
+
+    def _format_cpu_time(self):
+        cpu = self.result.get("cpu_time")
+        if cpu is None:
+            return ""
+        text = f" (CPU {cpu:.3f}秒)"
+        if is_idle_bound(self.result["time"], cpu):
+            # 壁時計の時間の大半が待ち（I/O・sleep）で、計算が重いわけではない
+            text += " " + self.palette.paint("待ち時間が大半", "warning")
+        return text
 
     def _format_input(self):
         r = self.result
         in_file = r.get("in_file") if "in_file" in r else None
+        if in_file and is_compressed(in_file):
+            # 圧縮して保存するほど大きなケースは表示しない
+            return f"（圧縮されたケースのため入力は省略: {os.path.basename(str(in_file))}）"
         if in_file and os.path.exists(in_file):
             with open(in_file, "r", encoding="utf-8") as f:
                 input_content = f.read().rstrip()
             return input_content
         return ""
 
+    def _format_alternatives(self):
+        # 表は1つ目の期待出力との比較なので、ほかにも受理する出力があることを添える
+        alternatives = self.result.get("alternatives") or []
+        if not alternatives or self.verdict() == "AC":
+            return ""
+        return f"（ほかに受理する期待出力が{len(alternatives)}件あり、どれとも一致しませんでした）"
+
     def _format_input_error_bar(self):
         r = self.result
         stderr = r["result"][2]
@@ -60,9 +150,19 @@ class ResultFormatter:
     def _format_error(self):
         r = self.result
         stderr = r["result"][2]
+        lines = [self._format_output_limit()] if r.get("output_limit_exceeded") else []
+        if r.get("runtime_error"):
+            lines.append(self._format_runtime_error())
         if stderr:
-            return f"{stderr.strip()}"
-        return ""
+            lines.append(stderr.strip())
+        return "\n".join(lines)
+
+    def _format_output_limit(self):
+        return f"出力が上限（{self.result['output_limit_exceeded']}バイト）を超えたため止めました（上限までを表示）"
+
+    def _format_runtime_error(self):
+        # 標準エラー出力から見当を付けたREの原因（runtime_errors）
+        return self.palette.paint(f"原因の見当: {self.result['runtime_error']['note']}", "warning")
 
     def _format_table(self):
         r = self.result
@@ -70,16 +170,28 @@ class ResultFormatter:
         stdout = r["result"][1]
         exp_lines = expected.strip().splitlines()
         out_lines = stdout.strip().splitlines()
-        max_exp = max([len(s) for s in exp_lines] + [8]) if exp_lines else 8  # 'Expected'の長さ
-        max_out = max([len(s) for s in out_lines] + [6]) if out_lines else 6  # 'Output'の長さ
         max_len = max(len(exp_lines), len(out_lines))
         if max_len == 0:
             return ""
+        exp_width, out_width = self._column_widths(exp_lines, out_lines)
         lines = []
         # カラム名を追加
-        lines.append(f"{'Expected':<{max_exp}} | {'Output':<{max_out}}")
-        for i in range(max_len):
-            exp = exp_lines[i] if i < len(exp_lines) else ""
-            out = out_lines[i] if i < len(out_lines) else ""
-            lines.append(f"{exp:<{max_exp}} | {out:<{max_out}}")
-        return "\n".join(lines) 
\ No newline at end of file
+        lines.append(f"{pad('Expected', exp_width)} | {pad('Output', out_width)}")
+        for exp, out in zip_longest(exp_lines, out_lines, fillvalue=""):
+            # 列に収まらない行は折り返し、もう片方の列は空けて揃える
+            differs = exp != out
+            for e, o in zip_longest(wrap(exp, exp_width), wrap(out, out_width), fillvalue=""):
+                o = pad(o, out_width)
+                lines.append(f"{pad(e, exp_width)} | {self.palette.paint(o, 'diff_actual') if differs else o}")
+        return "\n".join(lines)
+
+    def _column_widths(self, exp_lines, out_lines):
+        """表示幅で測った列の幅。端末に収まらなければ半分ずつに縮める"""
+        exp_width = max([display_width(s) for s in exp_lines] + [MIN_EXPECTED_WIDTH])
+        out_width = max([display_width(s) for s in out_lines] + [MIN_OUTPUT_WIDTH])
+        available = self.width - len(" | ")
+        if exp_width + out_width <= available:
+            return exp_width, out_width
+        exp_width = min(exp_width, max(available // 2, MIN_EXPECTED_WIDTH))
+        out_width = min(out_width, max(available - exp_width, MIN_OUTPUT_WIDTH))
+        return exp_width, out_width
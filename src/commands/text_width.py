@@ -0,0 +1,71 @@
+import re
+import shutil
+import unicodedata
+
+# 色付けなどのエスケープシーケンス（表示幅に数えない）
+ANSI_ESCAPE = re.compile(r"\033\[[0-9;]*[A-Za-z]")
+ELLIPSIS = "…"
+# 端末の幅が分からないとき（パイプ・CIなど）の既定
+DEFAULT_TERMINAL_WIDTH = 80
+# ZWJ・異体字セレクタなど、前の文字とまとめて表示される文字
+ZERO_WIDTH = {0x200B, 0x200C, 0x200D, 0x2060, 0xFEFF} | set(range(0xFE00, 0xFE10))
+
+def char_width(ch):
+    """1文字の端末上の幅。全角・絵文字は2、結合文字やゼロ幅の文字は0"""
+    code = ord(ch)
+    if code in ZERO_WIDTH or unicodedata.combining(ch) or unicodedata.category(ch) in ("Mn", "Me", "Cf"):
+        return 0
+    if unicodedata.east_asian_width(ch) in ("W", "F") or 0x1F000 <= code <= 0x1FAFF:
+        return 2
+    return 1
+
+def display_width(text):
+    return sum(char_width(ch) for ch in ANSI_ESCAPE.sub("", str(text)))
+
+def truncate(text, width, position="end"):
+    """
+    表示幅がwidthに収まるように切り詰め、省いた所に…を入れる。
+    position="middle"は前後を残す（パスやケース名は末尾で区別が付くことが多いため）
+    """
+    text = str(text)
+    if display_width(text) <= width:
+        return text
+    if width <= 0:
+        return ""
+    budget = width - display_width(ELLIPSIS)
+    if position == "middle":
+        head = _take(text, budget - budget // 2)
+        tail = _take(text[::-1], budget // 2)[::-1]
+        return head + ELLIPSIS + tail
+    return _take(text, budget) + ELLIPSIS
+
+def _take(text, width):
+    """先頭から表示幅widthまでの文字（全角を半分で切らない）"""
+    taken, used = [], 0
+    for ch in text:
+        w = char_width(ch)
+        if used + w > width:
+            break
+        taken.append(ch)
+        used += w
+    return "".join(taken)
+
+def pad(text, width):
+    """表示幅でwidthまで右を空白で埋める（str.ljustは全角を1文字と数えてずれる）"""
+    return f"{text}{' ' * max(width - display_width(text), 0)}"
+
+def wrap(text, width):
+    """表示幅widthごとに折り返す。空文字列は1行として扱う"""
+    text = str(text)
+    if width <= 0 or display_width(text) <= width:
+        return [text]
+    lines = []
+    while text:
+        line = _take(text, width) or text[0]
+        lines.append(line)
+        text = text[len(line):]
+    return lines
+
+def terminal_width(default=DEFAULT_TERMINAL_WIDTH):
+    """端末の幅（$COLUMNSがあればそれ）。分からなければdefault"""
+    return shutil.get_terminal_size((default, 24)).columns or default
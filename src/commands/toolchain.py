@@ -0,0 +1,92 @@
+import re
+import subprocess
+
+DEFAULT_SITE = "atcoder"
+# ジャッジ側の言語バージョン（AtCoder 2023年言語アップデート）
+JUDGE_TOOLCHAINS = {
+    "atcoder": {
+        "python": "3.11.4",
+        "pypy": "3.10-v7.3.12",
+        "rust": "1.70.0",
+    },
+}
+# バージョン確認コマンド
+VERSION_COMMANDS = {
+    "python": ["python3", "--version"],
+    "pypy": ["pypy3", "--version"],
+    "rust": ["rustc", "--version"],
+}
+# 提出前のコンパイル確認に使うジャッジ相当のコマンド
+# {file}: 提出ファイル、{dir}: 確認用のcargoプロジェクト、{target}: ビルドキャッシュ
+JUDGE_COMPILE_COMMANDS = {
+    "atcoder": {
+        "python": ["python3", "-m", "py_compile", "{file}"],
+        "pypy": ["pypy3", "-m", "py_compile", "{file}"],
+        "rust": ["cargo", "build", "--release", "--quiet", "--offline", "--manifest-path", "{dir}/Cargo.toml", "--target-dir", "{target}"],
+    },
+}
+# 固定したバージョンに対応するDockerイメージ
+IMAGE_TEMPLATES = {
+    "python": "python:{version}",
+    "pypy": "pypy:{python}-{pypy}",
+    "rust": "rust:{version}",
+}
+
+def get_pins(config, site=DEFAULT_SITE):
+    """config.jsonのtoolchain.{site}で固定されたバージョン。未設定の言語はジャッジの既定値で補う"""
+    return {**JUDGE_TOOLCHAINS.get(site, {}), **config.get_toolchain(site)}
+
+def split_pypy_version(version):
+    # "3.10-v7.3.12" → ("3.10", "7.3.12")
+    python, _, pypy = str(version).partition("-v")
+    return python, pypy
+
+def image_for(language_name, version):
+    python, pypy = split_pypy_version(version)
+    return IMAGE_TEMPLATES[language_name].format(version=version, python=python, pypy=pypy)
+
+def compile_command(language_name, site=DEFAULT_SITE, **paths):
+    """ジャッジ相当のコンパイル確認コマンド。定義が無ければNone"""
+    template = JUDGE_COMPILE_COMMANDS.get(site, {}).get(language_name)
+    if template is None:
+        return None
+    return [token.format(**paths) for token in template]
+
+def pinned_images(pins):
+    return {lang: image_for(lang, v) for lang, v in pins.items() if lang in IMAGE_TEMPLATES}
+
+def parse_version(language_name, output):
+    """--versionの出力からバージョン文字列を取り出す（PyPyは"3.10-v7.3.12"形式）"""
+    if not output:
+        return None
+    m = re.search(r"(\d+\.\d+(?:\.\d+)?)", output)
+    if not m:
+        return None
+    if language_name == "pypy":
+        p = re.search(r"PyPy (\d+\.\d+\.\d+)", output)
+        if p:
+            python = ".".join(m.group(1).split(".")[:2])
+            return f"{python}-v{p.group(1)}"
+    return m.group(1)
+
+def version_matches(expected, actual):
+    """期待バージョンの各要素が実際のバージョンの先頭と一致するか（"3.11"は"3.11.4"に一致）"""
+    if actual is None:
+        return False
+    expected_parts = re.split(r"[.\-]", str(expected))
+    actual_parts = re.split(r"[.\-]", str(actual))
+    return actual_parts[:len(expected_parts)] == expected_parts
+
+def installed_version(language_name, image=None, cwd=None):
+    """ローカル（imageがNone）またはイメージ内のツールチェインのバージョンを返す。確認できなければNone"""
+    cmd = VERSION_COMMANDS[language_name]
+    if image:
+        cmd = ["docker", "run", "--rm", image] + cmd
+    try:
+        result = subprocess.run(cmd, capture_output=True, text=True, cwd=cwd)
+    except (FileNotFoundError, OSError):
+        return None
+    if result.returncode != 0:
+        return None
+    # python3 --versionは古い版だとstderrに出す
+    return parse_version(language_name, result.stdout + result.stderr)
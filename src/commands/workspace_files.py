@@ -0,0 +1,118 @@
+import os
+from src.file_operator import atomic_write
+
+# cphが書き直す範囲の目印（この外に書いた行はそのまま残す）
+BLOCK_BEGIN = "# >>> cph: init が生成した範囲（書き直されます。追加はこの外に）"
+BLOCK_END = "# <<< cph"
+
+# 言語によらず無視するもの（作業用の一時ファイル・実行の記録やキャッシュ・ログインのクッキー）
+# .cph/archive.json（解いた問題の索引）は残しておきたい記録なので含めない
+COMMON_GITIGNORE = [
+    ".temp/",
+    ".cph/runs/",
+    ".cph/run_inputs/",
+    ".cph/results.json",
+    ".cph/cache_epochs.json",
+    ".cph/scrape/",
+    ".cph/verify/",
+    ".cph/backups/",
+    ".cph/diagnostics.json",
+    ".cph/cases/",
+    ".cph/watch.json",
+    "sites.yaml",
+    "cph-bug-report-*.tar.gz",
+]
+# 言語ごとのビルドの生成物
+LANGUAGE_GITIGNORE = {
+    "python": ["__pycache__/", "*.py[cod]"],
+    "pypy": ["__pycache__/", "*.py[cod]"],
+    "rust": ["target/", "**/*.rs.bk"],
+}
+
+# 言語によらない書式。テストケース（.in / .out）は改行や末尾の空白も中身なので、エディタに変えさせない
+COMMON_EDITORCONFIG = {
+    "*": {"charset": "utf-8", "end_of_line": "lf", "insert_final_newline": "true", "trim_trailing_whitespace": "true"},
+    "*.{in,out}": {"insert_final_newline": "false", "trim_trailing_whitespace": "false"},
+    "*.{json,yaml,yml}": {"indent_style": "space", "indent_size": "2"},
+}
+LANGUAGE_EDITORCONFIG = {
+    "python": {"*.py": {"indent_style": "space", "indent_size": "4"}},
+    "pypy": {"*.py": {"indent_style": "space", "indent_size": "4"}},
+    "rust": {"*.rs": {"indent_style": "space", "indent_size": "4"}},
+}
+
+def gitignore_lines(languages, config=None):
+    """
+    言語ごとの断片を重複なくつなげた.gitignoreの行。
+    config.jsonのworkspace.gitignoreで足せる。例: {"common": ["*.log"], "rust": ["*.exe"]}
+    """
+    extra = (config or {}).get("gitignore", {})
+    lines = []
+    for fragment in [COMMON_GITIGNORE, extra.get("common", [])] + [LANGUAGE_GITIGNORE.get(l, []) + extra.get(l, []) for l in languages]:
+        lines.extend(line for line in fragment if line not in lines)
+    return lines
+
+def editorconfig_sections(languages, config=None):
+    """
+    セクション（glob）→ 設定。config.jsonのworkspace.editorconfigで足す・上書きできる。
+    例: {"*.rs": {"indent_size": 2}}
+    """
+    sections = {glob: dict(values) for glob, values in COMMON_EDITORCONFIG.items()}
+    for language in languages:
+        for glob, values in LANGUAGE_EDITORCONFIG.get(language, {}).items():
+            sections.setdefault(glob, {}).update(values)
+    for glob, values in (config or {}).get("editorconfig", {}).items():
+        sections.setdefault(glob, {}).update({k: str(v).lower() if isinstance(v, bool) else str(v) for k, v in values.items()})
+    return sections
+
+def render_editorconfig(sections):
+    lines = []
+    for glob, values in sections.items():
+        if lines:
+            lines.append("")
+        lines.append(f"[{glob}]")
+        lines.extend(f"{k} = {v}" for k, v in values.items())
+    return lines
+
+def merge_block(existing, lines):
+    """既存の内容の目印の範囲だけを置き換える（無ければ末尾に足す）。何度実行しても同じ内容になる"""
+    block = "\n".join([BLOCK_BEGIN] + lines + [BLOCK_END]) + "\n"
+    if BLOCK_BEGIN in existing and BLOCK_END in existing:
+        head, _, rest = existing.partition(BLOCK_BEGIN)
+        _, _, tail = rest.partition(BLOCK_END)
+        return head + block.rstrip("\n") + tail
+    if not existing:
+        return block
+    return existing.rstrip("\n") + "\n\n" + block
+
+class WorkspaceFiles:
+    """ワークスペースの.gitignoreと.editorconfigを、使う言語の断片から作る（目印の範囲だけを書き直す）"""
+    def __init__(self, root, languages, config=None):
+        self.root = str(root)
+        self.languages = list(languages)
+        self.config = config or {}
+
+    def write(self, name, content_for):
+        """書き換えた場合はTrue。内容が変わらなければ書かない"""
+        path = os.path.join(self.root, name)
+        existing = ""
+        if os.path.exists(path):
+            with open(path, "r", encoding="utf-8") as f:
+                existing = f.read()
+        content = content_for(existing)
+        if content == existing:
+            return False
+        atomic_write(path, content)
+        return True
+
+    def generate(self):
+        """書き換えたファイル名の一覧"""
+        gitignore = gitignore_lines(self.languages, self.config)
+        editorconfig = render_editorconfig(editorconfig_sections(self.languages, self.config))
+        written = []
+        if self.write(".gitignore", lambda existing: merge_block(existing, gitignore)):
+            written.append(".gitignore")
+        # root = true は目印の外（ファイルの先頭）に置く。既にあるファイルには足さない
+        if self.write(".editorconfig", lambda existing: merge_block(existing or "root = true\n", editorconfig)):
+            written.append(".editorconfig")
+        return written
@@ -1,6 +1,8 @@
 import json
 import os
 from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.file_operator import atomic_write_json
+from src.file_lock import file_lock
 
 class ConfigJsonManager:
     def __init__(self, path=None):
@@ -17,9 +19,8 @@ class ConfigJsonManager:
             return json.load(f)
 
     def save(self):
-        os.makedirs(os.path.dirname(self.path), exist_ok=True)
-        with open(self.path, "w", encoding="utf-8") as f:
-            json.dump(self.data, f, ensure_ascii=False, indent=2)
+        with file_lock(self.path):
+            atomic_write_json(self.path, self.data)
 
     def get_moveignore(self):
         return self.data.get("moveignore", [])
@@ -40,6 +41,135 @@ class ConfigJsonManager:
         # 必要に応じてバリデーションを追加
         pass
 
+    def get_compare(self):
+        """出力比較方法の設定（"unordered-lines" や {"mode": ..., "per_block": ...}）。未設定ならNone"""
+        return self.data.get("compare")
+
+    def get_hooks(self):
+        """フック名→コマンド定義。例: {"visualize": {"command": "...", "artifact": "{case}.html"}}"""
+        return self.data.get("hooks", {})
+
+    def get_profile(self):
+        """プロファイラ設定。例: {"memory": {"rust": "heaptrack"}}"""
+        return self.data.get("profile", {})
+
+    def get_time_limit(self):
+        """問題の実行時間制限（秒）。未設定ならNone"""
+        return self.data.get("time_limit")
+
+    def get_toolchain(self, site=None):
+        """サイトごとに固定する言語バージョン。例: {"atcoder": {"rust": "1.70.0"}}"""
+        toolchain = self.data.get("toolchain", {})
+        if site is None:
+            return toolchain
+        return toolchain.get(site, {})
+
+    def set_toolchain(self, site, pins):
+        if "toolchain" not in self.data or not isinstance(self.data["toolchain"], dict):
+            self.data["toolchain"] = {}
+        self.data["toolchain"][site] = pins
+        self.save()
+
+    def get_team(self):
+        """チーム戦の設定。例: {"members": ["alice", "bob"], "lib": "team/lib", "notebook": "team/notebook/notebook.pdf"}"""
+        return self.data.get("team", {})
+
+    def set_team(self, team):
+        self.data["team"] = team
+        self.save()
+
+    def get_http(self):
+        """HTTPのタイムアウト（秒）。例: {"timeout_seconds": 10, "operation_timeout_seconds": 120}"""
+        return self.data.get("http", {})
+
+    def get_network(self):
+        """ネットワーク操作の設定。例: {"max_concurrent": 2}"""
+        return self.data.get("network", {})
+
+    def get_error_hints(self):
+        """エラーの種類ごとの追加ヒント。例: {"build_failed": {"ja": "...", "doc": "https://..."}}"""
+        return self.data.get("hints", {})
+
+    def get_test(self):
+        """テストケースの発見規則と保存方法。例: {"ignore": ["notes*"], "sets": {"stress": ["stress-*"]}, "compress_threshold": 8388608, "order": "failed-first", "tl_warn_ratio": 0.7, "lint": true, "check": true, "validator": "python3 validator.py", "env": {"allow": ["PATH"], "set": {"LANG": "C.UTF-8"}}, "pass_input_as": "file:input.txt", "cwd": "work", "problem_lock": "prompt", "anonymize": {"patterns": {"alice": null}, "reference": "python3 ref.py"}, "output_limit": "64MB", "total_budget_seconds": 600, "show_constraints": true, "runtime_errors": [{"pattern": "Killed", "kind": "MLE", "note": "OOM killerに止められました"}]}"""
+        return self.data.get("test", {})
+
+    def get_submit(self):
+        """提出用ファイルの加工設定。例: {"author": "alice", "header": "author: {author} / {time}", "footer": "...", "checklist": ["オーバーフローは確認した？", "制約を読み直した？"]}"""
+        return self.data.get("submit", {})
+
+    def get_stats(self):
+        """ローカルの利用統計の設定。例: {"enabled": true}"""
+        return self.data.get("stats", {})
+
+    def get_images(self):
+        """cphが管理するdockerイメージの片付け方。例: {"auto_clean_days": 30}（未使用のまま30日経ったら自動で削除）"""
+        return self.data.get("images", {})
+
+    def get_notify(self):
+        """イベントの通知先。例: {"channels": {"discord": {"url_env": "CPH_DISCORD_WEBHOOK"}}, "routes": {"submission_accepted": ["discord"]}}"""
+        return self.data.get("notify", {})
+
+    def get_workspace(self):
+        """initで生成する.gitignore / .editorconfigに足すもの。例: {"gitignore": {"rust": ["*.exe"]}, "editorconfig": {"*.rs": {"indent_size": 2}}}"""
+        return self.data.get("workspace", {})
+
+    def get_watch(self):
+        """test --watchで実行中に変更があったときの扱い。例: {"strategy": "cancel", "interval_seconds": 0.5}（strategyはcancel / queue / skip）"""
+        return self.data.get("watch", {})
+
+    def get_experimental(self):
+        """有効にする実験的な機能の名前。例: ["daemon"]（使えるものはsrc/experimental.pyのFEATURES）"""
+        return self.data.get("experimental", [])
+
+    def get_shutdown(self):
+        """test --watchなど常駐するモードがSIGTERMで終わるときの猶予。例: {"grace_seconds": 10}（過ぎたら後片付けだけして強制終了）"""
+        return self.data.get("shutdown", {})
+
+    def get_difficulty(self):
+        """openでAtCoder Problemsの推定難易度を取得するか。例: {"fetch": true, "max_age_hours": 24}"""
+        return self.data.get("difficulty", {})
+
+    def get_estimate(self):
+        """estimateの入力の生成方法。例: {"generator": "python3 gen.py {n} {seed}", "sizes": [1000, 10000, 100000], "variable": "N"}"""
+        return self.data.get("estimate", {})
+
+    def get_share(self):
+        """shareの貼り付け先。例: {"service": "gist", "token_env": "GITHUB_TOKEN"}（0x0はurlで貼り付け先を変えられる）"""
+        return self.data.get("share", {})
+
+    def get_editor(self):
+        """エラー位置へのジャンプ。例: {"command": "vim +{line} {file}", "auto_jump": true}（{file} {line} {column}が使える）"""
+        return self.data.get("editor", {})
+
+    def get_switch(self):
+        """switchの後に開くもの。例: {"editor": true, "browser": false}（hooks.switchがあればそれも実行する）"""
+        return self.data.get("switch", {})
+
+    def get_result(self):
+        """resultで成績を取得するユーザー。例: {"user": "alice"}（--userで上書きできる）"""
+        return self.data.get("result", {})
+
+    def get_repl(self):
+        """言語ごとの対話環境のコマンド。例: {"python": "ipython -i {startup}", "rust": "evcxr"}（{startup}は起動用スクリプト）"""
+        return self.data.get("repl", {})
+
+    def get_color(self):
+        """表示の色。例: {"theme": "high-contrast", "mode": "auto"}（themeはdefault / high-contrast / monochrome、modeは--colorの既定）"""
+        return self.data.get("color", {})
+
+    def get_output(self):
+        """表示の形式。例: {"accessible": true}（色・罫線・横並びの表を使わない。未設定ならTERM=dumbのときだけ）"""
+        return self.data.get("output", {})
+
+    def get_languages(self):
+        """
+        設定だけで追加する言語。例: {"nim": {"source": "main.nim", "compile": "nim c -o:{binary} {source}", "run": "{binary}"}}
+        （{source} {binary} {dir} {input}が使える。imageでdockerのイメージ、aliasesで別名、envで実行時の環境変数を指定できる）
+        組み込みの言語は実行時の隔離だけを変えられる。例: {"python": {"isolate": false}} / {"python": {"flags": ["-B"]}}
+        """
+        return self.data.get("languages", {})
+
     def get_entry_file(self, language_name=None):
         entry = self.data.get("entry_file", {})
         if language_name is None:
@@ -2,12 +2,14 @@ from src.path_manager.file_operator import FileOperator
 from src.info_json_manager import InfoJsonManager
 from src.config_json_manager import ConfigJsonManager
 from src.moveignore_manager import MoveIgnoreManager
+from src.backup_manager import BackupManager
 from src.path_manager.unified_path_manager import UnifiedPathManager
 
 class ContestFileManager:
     def __init__(self, file_operator: FileOperator, project_root=None, container_root="/workspace"):
         self.file_operator = file_operator
         self.upm = UnifiedPathManager(project_root, container_root)
+        self.changes = []
 
     def get_current_info_path(self):
         return self.file_operator.resolve_path(self.upm.info_json())
@@ -101,7 +103,7 @@ class ContestFileManager:
         if not any(x for x in src_dir.iterdir() if not self._is_ignored(x.name, ignore_patterns)):
             self.file_operator.rmtree(src_dir)
         self._remove_empty_parents(src_dir.parent, self.file_operator.resolve_path(self.upm.contest_stocks(old_contest_name)))
-        self._remove_empty_parents(src_dir.parent.parent, self.file_operator.resolve_path("contest_stocks"))
+        self._remove_empty_parents(src_dir.parent.parent, self.file_operator.resolve_path(self.upm.stocks_root()))
 
     def move_from_stocks_to_current(self, contest_name, problem_name, language_name):
         """
@@ -118,7 +120,7 @@ class ContestFileManager:
             self.file_operator.rmtree(src_dir)
         self._remove_empty_parents(src_dir.parent, self.file_operator.resolve_path(self.upm.contest_stocks(contest_name, problem_name)))
         self._remove_empty_parents(src_dir.parent.parent, self.file_operator.resolve_path(self.upm.contest_stocks(contest_name)))
-        self._remove_empty_parents(src_dir.parent.parent.parent, self.file_operator.resolve_path("contest_stocks"))
+        self._remove_empty_parents(src_dir.parent.parent.parent, self.file_operator.resolve_path(self.upm.stocks_root()))
 
     def move_from_stock_test_to_current(self, contest_name, problem_name, language_name):
         """
@@ -135,7 +137,7 @@ class ContestFileManager:
             self.file_operator.rmtree(src_dir)
         self._remove_empty_parents(src_dir.parent, self.file_operator.resolve_path(self.upm.contest_stocks(contest_name, problem_name)))
         self._remove_empty_parents(src_dir.parent.parent, self.file_operator.resolve_path(self.upm.contest_stocks(contest_name)))
-        self._remove_empty_parents(src_dir.parent.parent.parent, self.file_operator.resolve_path("contest_stocks"))
+        self._remove_empty_parents(src_dir.parent.parent.parent, self.file_operator.resolve_path(self.upm.stocks_root()))
 
     def copy_from_template_to_current(self, contest_name, problem_name, language_name):
         """
@@ -226,23 +228,116 @@ class ContestFileManager:
             elif item.is_dir():
                 self.file_operator.copytree(item, dst_dir / item.name)
 
+    def _sync_tree(self, src_dir, dst_dir, kind, ignore_patterns=(), backup=None):
+        """
+        src_dir配下のファイルをdst_dirにコピーする。同じ内容のものは飛ばし、置き換えるものは先にbackupへ残す。
+        コピーしたファイルはself.changesに(kind, パス)で記録する
+        """
+        src_dir = self.file_operator.resolve_path(src_dir)
+        dst_dir = self.file_operator.resolve_path(dst_dir)
+        if not src_dir.exists():
+            return
+        for src in sorted(src_dir.rglob("*")):
+            rel = src.relative_to(src_dir)
+            if not src.is_file() or self._is_ignored(rel.parts[0], ignore_patterns):
+                continue
+            dst = dst_dir / rel
+            if dst.is_file():
+                if dst.read_bytes() == src.read_bytes():
+                    continue
+                if backup:
+                    self.changes.append(("backup", backup.save(dst)))
+            self.file_operator.copy(src, dst)
+            self.changes.append((kind, dst))
+
+    def clear_current(self, language_name, backup=None):
+        """contest_current/{language}（moveignoreのものは残す）とcontest_current/testを空にする。backupがあれば先に残す"""
+        ignore_patterns = self.get_exclude_files(self.get_current_config_path())
+        lang_dir = self.file_operator.resolve_path(self.upm.contest_current(language_name))
+        test_dir = self.file_operator.resolve_path(self.upm.contest_current("test"))
+        targets = [item for item in lang_dir.iterdir() if not self._is_ignored(item.name, ignore_patterns)] if lang_dir.exists() else []
+        if test_dir.exists():
+            targets.append(test_dir)
+        for item in targets:
+            if backup:
+                files = [item] if item.is_file() else sorted(p for p in item.rglob("*") if p.is_file())
+                self.changes.extend(("backup", backup.save(f)) for f in files)
+            self.file_operator.rmtree(item)
+
+    def stash_current(self, contest_name, problem_name, language_name, backup=None):
+        """
+        contest_currentのコードとサンプルを、その問題のcontest_stocksに残して空にする（moveignoreのものは残す）。
+        contest_stocksで置き換わるファイルはbackupに残す
+        """
+        ignore_patterns = self.get_exclude_files(self.get_current_config_path())
+        self._sync_tree(self.upm.contest_current(language_name), self.upm.contest_stocks(contest_name, problem_name, language_name),
+                        "stash", ignore_patterns, backup)
+        self._sync_tree(self.upm.contest_current("test"), self.upm.contest_stocks(contest_name, problem_name, "test"), "stash", (), backup)
+        self.clear_current(language_name)
+
+    def load_problem(self, contest_name, problem_name, language_name):
+        """
+        問題のコードとサンプルをcontest_stocksからcontest_currentに取り出す。contest_currentにある解答は上書きしない。
+        解答が無ければテンプレートから作る。どちらにも無ければFileNotFoundError
+        """
+        current = self.file_operator.resolve_path(self.upm.contest_current(language_name))
+        has_solution = current.exists() and any(current.iterdir())
+        if not has_solution:
+            self._sync_tree(self.upm.contest_stocks(contest_name, problem_name, language_name), current, "restore")
+        test_dir = self.file_operator.resolve_path(self.upm.contest_current("test"))
+        if not (test_dir.exists() and any(test_dir.iterdir())):
+            self._sync_tree(self.upm.contest_stocks(contest_name, problem_name, "test"), test_dir, "restore")
+        if current.exists() and any(current.iterdir()):
+            return
+        template = self.file_operator.resolve_path(self.upm.contest_template(language_name))
+        if not template.exists():
+            raise FileNotFoundError(f"問題ファイルがcontest_stocksにもtemplateにも存在しません")
+        self.copy_from_template_to_current(contest_name, problem_name, language_name)
+        ignore_patterns = self.get_exclude_files(self.get_current_config_path())
+        self.changes.extend(("template", current / p.relative_to(template)) for p in sorted(template.rglob("*"))
+                            if p.is_file() and not self._is_ignored(p.relative_to(template).parts[0], ignore_patterns))
+
+    def make_current(self, contest_name, problem_name, language_name, label="open"):
+        """
+        contest_currentを指定の問題にし、system_info.jsonに保存する。編集中の解答は上書きしない:
+        - contest_currentが別の問題なら、そのコードとサンプルをその問題のcontest_stocksに残してから、指定の問題を取り出す
+        - 同じ問題なら、contest_currentにあるものはそのままにし、足りないものだけcontest_stocks・テンプレートから補う
+        - どの問題のものか分からない（system_info.jsonに無い）ものは、保管済みの問題を取り出すときに限り置き換える
+        置き換えたファイルは先に.cph/backups/{日時}-{label}/に残す。変更したファイルを[(種類, パス)]で返す
+        （種類: stash / restore / template / backup）
+        """
+        info_path = self.get_current_info_path()
+        info = InfoJsonManager(info_path).data if info_path.exists() else {}
+        self.changes = []
+        backup = BackupManager(label, upm=self.upm)
+        active = (info.get("contest_name"), info.get("problem_name"))
+        active_language = info.get("language_name") or language_name
+        if None not in active and active != (contest_name, problem_name):
+            self.stash_current(active[0], active[1], active_language, backup)
+            # 言語も変えるときは、その言語のディレクトリに残っているのがどの問題のものか分からない
+            if language_name != active_language:
+                self.clear_current(language_name, backup)
+        elif None in active and self.stocks_exists(contest_name, problem_name, language_name):
+            self.clear_current(language_name, backup)
+        self.load_problem(contest_name, problem_name, language_name)
+        # contest_currentがどの問題かを残す（testの取り違え検出・switchが使う）
+        manager = InfoJsonManager(info_path)
+        manager.data.update(contest_name=contest_name, problem_name=problem_name, language_name=language_name)
+        manager.save()
+        return self.changes
+
     def prepare_problem_files(self, contest_name=None, problem_name=None, language_name=None):
         """
-        system_info.jsonに contest_name, problem_name, language_name をすべて保存し、
-        次回実行時の初期値として利用できるようにする。
-        引数がNoneの場合はsystem_info.jsonの値を使う。
+        contest_currentを指定の問題にし（make_current）、system_info.jsonに contest_name, problem_name, language_name を
+        すべて保存して次回実行時の初期値として利用できるようにする。引数がNoneの場合はsystem_info.jsonの値を使う。
+        変更したファイルを[(種類, パス)]で返す
         """
         info_path = self.get_current_info_path()
         config_path = self.get_current_config_path()
-        if info_path.exists():
-            manager = InfoJsonManager(info_path)
-            info = manager.data
-            if contest_name is None:
-                contest_name = info.get("contest_name")
-            if problem_name is None:
-                problem_name = info.get("problem_name")
-            if language_name is None:
-                language_name = info.get("language_name")
+        info = InfoJsonManager(info_path).data if info_path.exists() else {}
+        contest_name = contest_name or info.get("contest_name")
+        problem_name = problem_name or info.get("problem_name")
+        language_name = language_name or info.get("language_name")
 
         manager = ConfigJsonManager(str(config_path))
         manager.ensure_language_id({
@@ -250,16 +345,7 @@ class ContestFileManager:
             "pypy": "5078",
             "rust": "5054"
         })
-        # stocksにコピー
-        self.copy_current_to_stocks(contest_name, problem_name, language_name)
-        self.copy_test_to_stocks(contest_name, problem_name)
-        if self.stocks_exists(contest_name, problem_name, language_name):
-            self.copy_from_stocks_to_current(contest_name, problem_name, language_name)
-        if not self.file_operator.resolve_path(self.upm.contest_current(language_name)).exists():
-            if self.file_operator.resolve_path(self.upm.contest_template(language_name)).exists():
-                self.copy_from_template_to_current(contest_name, problem_name, language_name)
-            else:
-                raise FileNotFoundError(f"問題ファイルがcontest_stocksにもtemplateにも存在しません")
+        return self.make_current(contest_name, problem_name, language_name)
 
     def move_tests_to_stocks(self, contest_name, problem_name, tests_root):
         """
@@ -0,0 +1,116 @@
+import os
+import re
+import subprocess
+import sys
+import threading
+from src.shell import is_stream
+
+# 解答のCPU時間（user + sys）と最大メモリ使用量は、実行したスレッドごとに直近の1回分を覚えておく。
+# テストはケースごとに別のスレッドで実行するので、並列でも他のケースの値と混ざらない
+_local = threading.local()
+# wall / CPU がこれ以上で、wallが長いケースは「待ち（I/O・sleep）が大半」とみなす
+IDLE_RATIO = 2.0
+IDLE_MIN_WALL = 0.1
+# コンテナ内の計測結果をstderrの最終行で受け取る目印
+CGROUP_MARKER = "__cph_cpu_usec__"
+CGROUP_MARKER_LINE = re.compile(rf"{CGROUP_MARKER} (-?\d+)\n?$")
+# コンテナのcgroupのCPU使用量（v2のcpu.stat、無ければv1のcpuacct）を実行の前後で読み、差をstderrの最後に書く
+CGROUP_WRAPPER = (
+    'cpu() { if [ -r /sys/fs/cgroup/cpu.stat ]; then sed -n "s/^usage_usec //p" /sys/fs/cgroup/cpu.stat; '
+    'elif [ -r /sys/fs/cgroup/cpuacct/cpuacct.usage ]; then echo $(( $(cat /sys/fs/cgroup/cpuacct/cpuacct.usage) / 1000 )); fi; }; '
+    'b=$(cpu); "$@"; r=$?; a=$(cpu); '
+    f'if [ -n "$b" ] && [ -n "$a" ]; then echo "{CGROUP_MARKER} $((a - b))" >&2; fi; exit $r'
+)
+
+def record(seconds, memory_kb=None):
+    _local.value = seconds
+    _local.memory = memory_kb
+
+def take():
+    """このスレッドで直近に計測したCPU時間（秒）を返して消す。計測できなかったときはNone"""
+    value = getattr(_local, "value", None)
+    _local.value = None
+    return value
+
+def take_memory():
+    """このスレッドで直近に計測した最大メモリ使用量（KB）を返して消す。計測できなかったとき（コンテナ内など）はNone"""
+    value = getattr(_local, "memory", None)
+    _local.memory = None
+    return value
+
+def max_rss_kb(usage):
+    """rusageのru_maxrssをKBにする（macOSはバイト単位）"""
+    return usage.ru_maxrss // 1024 if sys.platform == "darwin" else usage.ru_maxrss
+
+def is_idle_bound(wall, cpu):
+    """wallに比べてCPU時間が短い（I/O待ち・sleep・入力待ちが大半）か"""
+    return cpu is not None and wall is not None and wall >= IDLE_MIN_WALL and wall >= cpu * IDLE_RATIO
+
+def run_measured(command, input=None, cwd=None, preexec_fn=None):
+    """
+    subprocess.runと同じように実行し、(returncode, stdout, stderr, CPU時間) を返す。
+    子プロセスをos.wait4で回収して、そのプロセス（と子孫）だけのrusageを得る（並列実行中でも混ざらない）。
+    """
+    return run_measured_usage(command, input, cwd, preexec_fn)[:4]
+
+def run_measured_usage(command, input=None, cwd=None, preexec_fn=None):
+    """run_measuredに最大メモリ使用量（KB）を加えた (returncode, stdout, stderr, CPU時間, メモリ) を返す"""
+    if not hasattr(os, "wait4"):
+        stdin = {"stdin": input} if is_stream(input) else {"input": input}
+        result = subprocess.run(command, text=True, errors="replace", capture_output=True, cwd=cwd, preexec_fn=preexec_fn, **stdin)
+        return result.returncode, result.stdout, result.stderr, None, None
+    stream = is_stream(input)
+    stdin = input if stream else (subprocess.PIPE if input is not None else None)
+    # 出力の上限（OutputLimit）で文字の途中で切れることがあるので、読めないバイトは置き換える
+    proc = subprocess.Popen(command, stdin=stdin, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, errors="replace", cwd=cwd, preexec_fn=preexec_fn)
+    output = {}
+
+    def read(key, pipe):
+        output[key] = pipe.read()
+        pipe.close()
+    readers = [threading.Thread(target=read, args=(key, pipe), daemon=True) for key, pipe in (("stdout", proc.stdout), ("stderr", proc.stderr))]
+    for t in readers:
+        t.start()
+    if proc.stdin is not None:
+        try:
+            proc.stdin.write(input)
+        except BrokenPipeError:
+            # 入力を読み切らずに終了した解答
+            pass
+        finally:
+            try:
+                proc.stdin.close()
+            except BrokenPipeError:
+                pass
+    for t in readers:
+        t.join()
+    _, status, usage = os.wait4(proc.pid, 0)
+    proc.returncode = os.waitstatus_to_exitcode(status)
+    return proc.returncode, output.get("stdout", ""), output.get("stderr", ""), usage.ru_utime + usage.ru_stime, max_rss_kb(usage)
+
+def wrap_cgroup_command(cmd):
+    return ["sh", "-c", CGROUP_WRAPPER, "sh"] + list(cmd)
+
+def split_cgroup_marker(stderr):
+    """stderrの最後の計測結果の行を取り除き、(stderr, CPU時間) を返す。行が無ければCPU時間はNone"""
+    m = CGROUP_MARKER_LINE.search(stderr or "")
+    if not m:
+        return stderr, None
+    return stderr[:m.start()], max(int(m.group(1)), 0) / 1_000_000
+
+class CgroupCpuClient:
+    """
+    ContainerClientを包み、exec_in_containerで実行したコマンドのCPU時間をコンテナのcgroupから測る。
+    同じコンテナで同時に動いている他のプロセスの分も含むので、並列数がコンテナ数を超えると多めに出る。
+    """
+    def __init__(self, client):
+        self.client = client
+
+    def __getattr__(self, name):
+        return getattr(self.client, name)
+
+    def exec_in_container(self, name, cmd, **kwargs):
+        result = self.client.exec_in_container(name, wrap_cgroup_command(cmd), **kwargs)
+        stderr, seconds = split_cgroup_marker(result.stderr)
+        record(seconds)
+        return subprocess.CompletedProcess(getattr(result, "args", cmd), result.returncode, result.stdout, stderr)
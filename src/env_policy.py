@@ -0,0 +1,55 @@
+import re
+import shlex
+
+# 解答のプロセスに渡す環境変数の既定。PATHだけを引き継ぎ、ロケールはどのマシンでも同じにする
+DEFAULT_ALLOW = ("PATH",)
+DEFAULT_SET = {"LANG": "C.UTF-8"}
+# allowに書くと、denyに書いたもの以外をすべて引き継ぐ
+ALLOW_ALL = "*"
+ENV_NAME = re.compile(r"^[A-Za-z_][A-Za-z0-9_]*$")
+
+def _check_names(names):
+    for name in names:
+        if not ENV_NAME.match(str(name)):
+            raise ValueError(f"test.envの環境変数名が不正です: {name}")
+    return list(names)
+
+class EnvPolicy:
+    """
+    テストで解答を実行するときの環境変数。手元の環境変数（PYTHONPATH・トークン・ロケールなど）で結果が変わらないよう、
+    allowに書いたものだけを実行する側（ローカルなら手元、dockerならコンテナ）の環境から引き継ぎ、setの値を足す。
+    config.jsonのtest.envで変える。例: {"allow": ["PATH", "HOME"], "set": {"LANG": "ja_JP.UTF-8"}}
+    {"allow": ["*"], "deny": ["GITHUB_TOKEN"]}はdenyだけを消す。falseで何もしない（以前の動作）。
+    """
+    def __init__(self, allow=DEFAULT_ALLOW, deny=(), assign=None, enabled=True, extra=None):
+        self.deny = _check_names(deny)
+        self.allow = [n for n in allow if n == ALLOW_ALL or n not in self.deny]
+        _check_names(n for n in self.allow if n != ALLOW_ALL)
+        self.set = dict(DEFAULT_SET if assign is None else assign)
+        _check_names(self.set)
+        self.enabled = enabled
+        # cph自身が渡す値（CPH_SEEDなど）。無効にしていても渡す
+        self.extra = dict(extra or {})
+
+    @classmethod
+    def from_config(cls, test_config, extra=None):
+        config = (test_config or {}).get("env", {})
+        if config is False:
+            return cls(enabled=False, extra=extra)
+        if not isinstance(config, dict):
+            raise ValueError("test.envにはallow / deny / setを書くか、falseを指定してください")
+        return cls(config.get("allow", DEFAULT_ALLOW), config.get("deny", ()), config.get("set"), extra=extra)
+
+    def wrap(self, cmd):
+        """cmdを、環境変数を入れ替えてから実行するコマンドにする（コンテナ内でもローカルでも同じ形で動く）"""
+        extra = [f"{k}={v}" for k, v in self.extra.items()]
+        if not self.enabled:
+            return ["env"] + extra + list(cmd) if extra else list(cmd)
+        assignments = [f"{k}={v}" for k, v in self.set.items()] + extra
+        if ALLOW_ALL in self.allow:
+            unset = [arg for name in self.deny for arg in ("-u", name)]
+            return ["env"] + unset + assignments + list(cmd)
+        # 引き継ぐ値は実行する側のシェルで展開する（未設定の変数は渡さない）
+        inherited = " ".join(f'${{{name}+"{name}=${name}"}}' for name in self.allow)
+        script = " ".join(part for part in ("exec env -i", inherited, " ".join(shlex.quote(a) for a in assignments), '"$@"') if part)
+        return ["sh", "-c", script, "sh"] + list(cmd)
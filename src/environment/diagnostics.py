@@ -0,0 +1,87 @@
+import json
+import re
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# error[E0425]: cannot find value `x` in this scope
+#   --> src/main.rs:3:5
+RUSTC_HEADER = re.compile(r"^(error|warning)(\[\w+\])?: (.*)$")
+RUSTC_LOCATION = re.compile(r"^\s*--> (.+?):(\d+):(\d+)\s*$")
+# thread 'main' panicked at src/main.rs:10:5:（1.73以降） / panicked at 'msg', src/main.rs:10:5（それ以前）
+RUST_PANIC = re.compile(r"panicked at (?:'(.*)', )?(.+?):(\d+):(\d+):?\s*$")
+#   File "/workspace/.temp/main.py", line 3, in main
+PYTHON_FRAME = re.compile(r'^\s*File "(.+?)", line (\d+)')
+# 診断の置き場（直近のtestのもの）
+DIAGNOSTICS_FILE = "diagnostics.json"
+
+def diagnostic(level, message, file, line, column=1):
+    return {"level": level, "message": message, "file": file, "line": int(line), "column": int(column)}
+
+def parse_rustc_diagnostics(output):
+    """cargo / rustcの出力からエラー・警告と、その位置（--> の行）を取り出す。位置の無いものは除く"""
+    diagnostics = []
+    lines = (output or "").splitlines()
+    for i, line in enumerate(lines):
+        header = RUSTC_HEADER.match(line)
+        if not header:
+            continue
+        for follow in lines[i + 1:i + 3]:
+            location = RUSTC_LOCATION.match(follow)
+            if location:
+                diagnostics.append(diagnostic(header.group(1), header.group(3).strip(), *location.groups()))
+                break
+    return diagnostics
+
+def parse_rust_panic(output):
+    diagnostics = []
+    lines = (output or "").splitlines()
+    for i, line in enumerate(lines):
+        m = RUST_PANIC.search(line)
+        if not m:
+            continue
+        # 新しい形式ではメッセージが次の行にある
+        message = m.group(1) or (lines[i + 1].strip() if i + 1 < len(lines) else "")
+        diagnostics.append(diagnostic("panic", f"panicked: {message}".rstrip(": "), m.group(2), m.group(3), m.group(4)))
+    return diagnostics
+
+def parse_python_traceback(output):
+    """Pythonのトレースバック（SyntaxErrorを含む）から、最も内側のフレームの位置と例外のメッセージを取り出す"""
+    lines = (output or "").splitlines()
+    frames = [(i, PYTHON_FRAME.match(line)) for i, line in enumerate(lines)]
+    frames = [(i, m) for i, m in frames if m and not m.group(1).startswith("<")]
+    if not frames:
+        return []
+    _, m = frames[-1]
+    message = next((line.strip() for line in reversed(lines) if line.strip() and not line.startswith(" ")), "")
+    return [diagnostic("error", message, m.group(1), m.group(2))]
+
+def parse_diagnostics(output):
+    """コンパイラ・実行時エラーの出力から、ファイルと行が分かる診断をすべて取り出す"""
+    return parse_rustc_diagnostics(output) + parse_rust_panic(output) + parse_python_traceback(output)
+
+class DiagnosticStore:
+    """
+    直近のtestで得た診断（ビルドエラーか、最初に実行時エラーになったケースのもの）を .cph/diagnostics.json に残す。
+    fixはここからエディタで開く位置を読む。
+    """
+    def __init__(self, upm=None):
+        self.upm = upm or UnifiedPathManager()
+        self.path = self.upm.cph_dir(DIAGNOSTICS_FILE)
+
+    def save(self, language_name, stage, diagnostics, case=None):
+        if not diagnostics:
+            return self.clear()
+        data = {"language_name": language_name, "stage": stage, "case": case, "diagnostics": diagnostics}
+        atomic_write_json(self.path, data)
+        return data
+
+    def load(self):
+        try:
+            return json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            return None
+
+    def clear(self):
+        if self.path.exists():
+            self.path.unlink()
+        return None
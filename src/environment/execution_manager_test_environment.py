@@ -1,14 +1,21 @@
 from src.environment.test_environment import TestExecutionEnvironment, TestEnvFileOpsMixin
 from execution_client.execution_manager import ExecutionManager
 from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.network_limit import network_slot
+from src.error_hints import CphError
+from src.shell import Shell, CommandSpec
+from src.commands.case_storage import case_input
+from src.command_wrap import CommandWrapClient
 import os
 import shutil
-import subprocess
 
 class ExecutionManagerTestEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironment):
-    def __init__(self, file_manager, manager, handlers=None):
+    uses_containers = False
+
+    def __init__(self, file_manager, manager, handlers=None, shell=None):
         self.file_manager = file_manager
         self.manager = manager
+        self.shell = shell or Shell()
         self.file_operator = file_manager.file_operator if file_manager and hasattr(file_manager, 'file_operator') else None
         from src.environment.test_language_handler import HANDLERS as DEFAULT_HANDLERS
         self.handlers = handlers if handlers is not None else DEFAULT_HANDLERS
@@ -23,14 +30,34 @@ class ExecutionManagerTestEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironm
         # ローカル実行時は変換せずそのまま返す
         return str(container_path)
 
-    def run_test_case(self, language_name, name, in_file, source_path, retry=3):
-        handler = self.handlers[language_name]
+    def run_test_case(self, language_name, name, in_file, source_path, retry=3, wall_limit=None):
+        from src.environment.test_language_handler import get_handler
+        handler = get_handler(language_name, self.handlers)
+        manager = CommandWrapClient(self.manager, *self.solution_wrappers(in_file, wall_limit))
+        if wall_limit is not None:
+            # 持ち時間で止めた実行をやり直すと、予算（total_budget_seconds）を超えて使ってしまう
+            retry = 1
         for attempt in range(retry):
-            ok, stdout, stderr = handler.run(self.manager, name, in_file, source_path)
+            ok, stdout, stderr = handler.run(manager, name, in_file, source_path)
             if ok:
                 break
         return ok, stdout, stderr, attempt+1
 
+    def exec_command(self, name, cmd, in_file=None):
+        # 任意のコマンドをローカルで実行する（プロファイラでラップした実行など）
+        if in_file is None:
+            result = self.manager.run_and_measure(name, cmd, timeout=None, input="")
+        else:
+            # 入力を渡すもの（解答の実行）は、テストと同じ入力の渡し方・環境変数で実行する
+            cmd = CommandWrapClient(self.manager, *self.solution_wrappers(in_file)).wrap(cmd)
+            with case_input(in_file) as input_data:
+                result = self.manager.run_and_measure(name, cmd, timeout=None, input=input_data)
+        return result.returncode == 0, result.stdout, result.stderr
+
+    def attach_command(self, name, cmd):
+        # 端末の標準入出力をそのまま子プロセスに渡す（runコマンド用）
+        return self.shell.attach(CommandSpec.from_argv(cmd))
+
     def adjust_containers(self, requirements, contest_name=None, problem_name=None, language_name=None):
         # ローカル実行では特に何もしないが、インターフェース維持のためダミー返却
         return []
@@ -41,10 +68,16 @@ class ExecutionManagerTestEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironm
             shutil.rmtree(test_dir_host)
         os.makedirs(test_dir_host, exist_ok=True)
         # oj downloadをローカルで実行
-        result = subprocess.run(["oj", "download", url, "-d", test_dir_host], capture_output=True, text=True)
+        cookie_jar = self.oj_cookie_jar()
+        timeout = self.oj_download_timeout()
+        with network_slot():
+            result = self.shell.run(CommandSpec("oj", (["--cookie", cookie_jar] if cookie_jar else []) + ["download", url, "-d", test_dir_host], timeout=timeout))
+        self.check_oj_download(result.timed_out, timeout)
         if result.returncode != 0:
             print(f"[ERROR] oj download failed: {result.stderr}")
-            raise RuntimeError("oj download failed")
+            raise CphError("oj download failed", kind="oj_download_failed")
+        # 取得したファイルがリンク経由でワークスペースの外を指していないか確認する
+        self.upm.validator().check_tree(test_dir_host)
         print(result.stdout)
 
     def submit_via_ojtools(self, args, volumes, workdir):
@@ -52,14 +85,16 @@ class ExecutionManagerTestEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironm
         if workdir.startswith("/workspace"):
             # 例: /workspace/contest_current/python → ./contest_current/python
             workdir = "." + workdir[len("/workspace"):]
-        cookie_path = "/home/cphelper/.local/share/online-judge-tools/cookie.jar"
+        # sites.yamlのクッキーがあれば、ojのcookie.jarに足したものを使う
+        cookie_path = self.oj_cookie_jar() or "/home/cphelper/.local/share/online-judge-tools/cookie.jar"
         if not args:
             args = []
         if args and args[0] == "submit":
             cmd = ["oj", "--cookie", cookie_path, "submit"] + args[1:]
         else:
             cmd = ["oj", "--cookie", cookie_path] + args
-        result = subprocess.run(cmd, cwd=workdir, capture_output=True, text=True)
+        with network_slot():
+            result = self.shell.run(CommandSpec.from_argv(cmd, cwd=workdir))
         ok = result.returncode == 0
         if not ok:
             print(f"[ERROR] oj submit failed: {result.stderr}")
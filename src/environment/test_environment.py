@@ -1,15 +1,29 @@
 from abc import ABC, abstractmethod
 import os
 import shutil
-from src.execution_client.container.client import ContainerClient
+from src.execution_client.container.client import ContainerClient, shared_client
 from src.execution_client.container.pool import ContainerPool
 from src.execution_client.container.image_manager import ContainerImageManager
 from src.path_manager.unified_path_manager import UnifiedPathManager
 from src.path_manager.file_operator import FileOperator
+from src.file_operator import AsyncFileOpsMixin, LocalFileOperator, run_blocking
+from src.network_limit import network_slot
+from src.error_hints import CphError
+from src.commands.case_budget import WallTimeLimit
+from src.commands.case_storage import case_input
+from src.cpu_time import CgroupCpuClient
+from src.env_policy import EnvPolicy
+from src.input_passing import InputPassing
+from src.output_limit import OutputLimit
+from src.command_wrap import CommandWrapClient
+from src.config_json_manager import ConfigJsonManager
+from src.site_config import OJ_COOKIE_JAR
+from src import cancellation, http_client, temp_space
 from pathlib import Path
 
 HOST_PROJECT_ROOT = os.path.abspath(".")
 CONTAINER_WORKSPACE = "/workspace"
+OJ_DOWNLOAD_TIMEOUT = 120  # oj download 1回の制限時間（秒）。openの残り時間（http.operation_timeout_seconds）より長くはしない
 
 class TestEnvFileOpsMixin:
     def prepare_source_code(self, contest_name, problem_name, language_name):
@@ -47,9 +61,10 @@ class TestEnvFileOpsMixin:
                 shutil.copytree(src_dir, dst_dir, dirs_exist_ok=True)
             return str(dst_dir)
         elif language_name in ("python", "pypy"):
-            src = self.upm.contest_current(language_name, "main.py")
+            source_file = self.upm.source_file(language_name)
+            src = self.upm.contest_current(language_name, source_file)
             dst_dir = temp_dir / language_name
-            dst = dst_dir / "main.py"
+            dst = dst_dir / Path(source_file).name
             if self.file_operator:
                 if not self.file_operator.exists(dst_dir):
                     self.file_operator.makedirs(dst_dir)
@@ -59,14 +74,17 @@ class TestEnvFileOpsMixin:
                 shutil.copy(src, dst)
             return str(dst)
         else:
-            src = self.upm.contest_current(language_name, "main.py")
-            dst = temp_dir / "main.py"
+            # config.jsonのlanguagesで定義した言語も、ビルドの出力（{binary}）が混ざらないよう言語ごとのディレクトリに置く
+            source_file = self.upm.source_file(language_name)
+            src = self.upm.contest_current(language_name, source_file)
+            dst_dir = temp_dir / language_name
+            dst = dst_dir / Path(source_file).name
             if self.file_operator:
-                if not self.file_operator.exists(temp_dir):
-                    self.file_operator.makedirs(temp_dir)
+                if not self.file_operator.exists(dst_dir):
+                    self.file_operator.makedirs(dst_dir)
                 self.file_operator.copy(src, dst)
             else:
-                temp_dir.mkdir(parents=True, exist_ok=True)
+                dst_dir.mkdir(parents=True, exist_ok=True)
                 shutil.copy(src, dst)
             return str(dst)
 
@@ -82,7 +100,32 @@ class TestEnvFileOpsMixin:
                 shutil.copytree(test_dir, temp_test_dir, dirs_exist_ok=True)
         return str(temp_test_dir)
 
+    async def aprepare_test_cases(self, contest_name, problem_name):
+        """
+        prepare_test_casesの非同期版。ケースはcopy_dirで並行してコピーし、多くてもイベントループ（進捗表示など）を止めない。
+        非同期の操作を持たないfile_operatorでは、同期版を別スレッドで実行する
+        """
+        operator = self.file_operator or LocalFileOperator()
+        if not isinstance(operator, AsyncFileOpsMixin):
+            return await run_blocking(self.prepare_test_cases, contest_name, problem_name)
+        test_dir = self.upm.contest_current("test")
+        temp_test_dir = Path(".temp") / "test"
+        if self.file_operator:
+            if not await operator.aexists(temp_test_dir):
+                await operator.amakedirs(temp_test_dir)
+                await operator.copy_dir(test_dir, temp_test_dir)
+        elif test_dir.exists():
+            await operator.copy_dir(test_dir, temp_test_dir)
+        return str(temp_test_dir)
+
 class TestExecutionEnvironment(ABC):
+    # コンテナ上で解答を実行するか（Falseならローカルのプロセスで実行し、コンテナの起動確認をしない）
+    uses_containers = True
+    # 解答に渡す、実行ごとの環境変数（testがCPH_SEEDを入れる）
+    solution_env = {}
+    # 解答の標準出力の上限（testが問題ごとの設定で置き換える）
+    output_limit = OutputLimit()
+
     @abstractmethod
     def prepare_source_code(self, contest_name, problem_name, language_name):
         pass
@@ -100,17 +143,54 @@ class TestExecutionEnvironment(ABC):
         pass
 
     @abstractmethod
-    def run_test_case(self, language_name, container, in_file, source_path, retry=2):
+    def run_test_case(self, language_name, container, in_file, source_path, retry=2, wall_limit=None):
         pass
 
+    def oj_cookie_jar(self):
+        """
+        sites.yamlにクッキーがあれば、ojのcookie.jarに足したものをこのプロセスの一時ディレクトリに書き、
+        ojの--cookieに渡すパス（コンテナで実行するならコンテナから見たパス）を返す。無ければNone
+        """
+        sites = http_client.sites()
+        if not sites.has_cookies():
+            return None
+        path = sites.write_cookie_jar(temp_space.for_env(self).file("oj", "cookie.jar"), base=OJ_COOKIE_JAR)
+        return self.to_container_path(path) if self.uses_containers else path
+
+    def oj_download_timeout(self):
+        """oj downloadに掛ける制限時間。始める前に中断・時間切れを確かめる"""
+        token = cancellation.current()
+        token.check()
+        return token.request_timeout(OJ_DOWNLOAD_TIMEOUT)
+
+    def check_oj_download(self, timed_out, timeout):
+        """oj downloadの後に中断・時間切れを確かめる（ojが止まらず時間切れになったときもエラーにする）"""
+        cancellation.current().check()
+        if timed_out:
+            raise CphError(f"oj downloadが{timeout:g}秒以内に終わりませんでした", kind="oj_download_failed")
+
+    def solution_wrappers(self, in_file, wall_limit=None):
+        """
+        解答の実行コマンドを包むもの。予算から割り振った持ち時間（wall_limit秒、test.total_budget_seconds）、
+        出力の上限、入力の渡し方と作業ディレクトリ（test.pass_input_as / test.cwd）、環境変数（test.env）
+        """
+        test_config = ConfigJsonManager(str(self.upm.config_json())).get_test()
+        # 持ち時間は解答そのものを止めるよう一番内側に置く
+        limits = [WallTimeLimit(wall_limit)] if wall_limit else []
+        return limits + [
+            self.output_limit,
+            InputPassing.for_case(test_config, in_file, self.upm.project_path.root, self.to_container_path),
+            EnvPolicy.from_config(test_config, extra=self.solution_env),
+        ]
+
 class DockerTestExecutionEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironment):
-    def __init__(self, file_manager, handlers=None):
+    def __init__(self, file_manager, handlers=None, image_overrides=None):
         self.file_manager = file_manager
         self.file_operator = file_manager.file_operator if file_manager and hasattr(file_manager, 'file_operator') else None
-        self.ctl = ContainerClient()
+        self.ctl = shared_client()
         from src.environment.test_language_handler import HANDLERS as DEFAULT_HANDLERS
         self.handlers = handlers if handlers is not None else DEFAULT_HANDLERS
-        self.pool = ContainerPool({})
+        self.pool = ContainerPool({}, image_overrides=image_overrides)
         # .tempも含めたマウントリストで初期化
         temp_abs = Path(os.path.abspath(".temp")).resolve()
         mounts = [
@@ -126,8 +206,9 @@ class DockerTestExecutionEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironme
     def to_host_path(self, container_path: str) -> str:
         return str(self.unified_path_manager.to_host_path(container_path))
 
-    def run_test_case(self, language_name, container, in_file, source_path, retry=3):
-        handler = self.handlers[language_name]
+    def run_test_case(self, language_name, container, in_file, source_path, retry=3, wall_limit=None):
+        from src.environment.test_language_handler import get_handler
+        handler = get_handler(language_name, self.handlers)
         image = ContainerImageManager().ensure_image("ojtools") if container.startswith("cph_ojtools") else language_name
         ctl = self.ctl
         stdout = stderr = ""
@@ -139,14 +220,38 @@ class DockerTestExecutionEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironme
             host_in_file = str(host_in_file)
         else:
             host_in_file = cont_in_file
+        client = CgroupCpuClient(CommandWrapClient(ctl, *self.solution_wrappers(cont_in_file, wall_limit)))
+        if wall_limit is not None:
+            # 持ち時間で止めた実行をやり直すと、予算（total_budget_seconds）を超えて使ってしまう
+            retry = 1
         for attempt in range(retry):
-            ok, stdout, stderr = handler.run(ctl, container, cont_in_file, cont_source_path, host_in_file=host_in_file)
+            # コンテナのcgroupから解答のCPU時間も測る（cpu_time.take()で受け取る）
+            ok, stdout, stderr = handler.run(client, container, cont_in_file, cont_source_path, host_in_file=host_in_file)
             if ok:
                 break
             else:
                 print(f"[WARN] exec失敗: {container} (attempt {attempt+1})")
         return ok, stdout, stderr, attempt+1
 
+    def exec_command(self, container, cmd, in_file=None):
+        """
+        任意のコマンドをテスト用コンテナ内で実行する（プロファイラでラップした実行など）。
+        入力を渡すもの（解答の実行）は、テストと同じ入力の渡し方・環境変数で実行する
+        """
+        input_data = None
+        if in_file is not None:
+            cmd = CommandWrapClient(self.ctl, *self.solution_wrappers(in_file)).wrap(cmd)
+            host_in_file = self.unified_path_manager.to_host_path(in_file) or in_file
+            with case_input(host_in_file) as input_data:
+                result = self.ctl.exec_in_container(container, cmd, stdin=input_data)
+        else:
+            result = self.ctl.exec_in_container(container, cmd, stdin=input_data)
+        return result.returncode == 0, result.stdout, result.stderr
+
+    def attach_command(self, container, cmd):
+        """端末の標準入出力をつないだままコンテナ内で実行する（runコマンド用）"""
+        return self.ctl.exec_interactive(container, cmd)
+
     def adjust_containers(self, requirements, contest_name=None, problem_name=None, language_name=None):
         """必要なコンテナ数・ボリュームを調整し、system_info.jsonも更新する。"""
         containers = self.pool.adjust(requirements)
@@ -169,13 +274,24 @@ class DockerTestExecutionEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironme
         manager = InfoJsonManager(info_path)
         ojtools_list = manager.get_containers(type="ojtools")
         if not ojtools_list:
-            raise RuntimeError("ojtools用コンテナがsystem_info.jsonにありません")
+            raise CphError("ojtools用コンテナがsystem_info.jsonにありません", kind="ojtools_container_missing")
         ojtools_name = ojtools_list[0]["name"]
         ctl = self.ctl
         if not ctl.is_container_running(ojtools_name):
             ctl.run_container(ojtools_name, ContainerImageManager().ensure_image("ojtools"), {})
-        # test_dir_hostの親ディレクトリを取得
-        self.unified_path_manager.to_container_path(test_dir_host)
+        if os.path.exists(test_dir_host):
+            shutil.rmtree(test_dir_host)
+        os.makedirs(test_dir_host, exist_ok=True)
+        cookie_jar = self.oj_cookie_jar()
+        cmd = ["oj"] + (["--cookie", cookie_jar] if cookie_jar else []) + ["download", url, "-d", self.to_container_path(test_dir_host)]
+        timeout = self.oj_download_timeout()
+        with network_slot():
+            result = ctl.docker(["docker", "exec", "-i", ojtools_name] + cmd, timeout=timeout)
+        self.check_oj_download(result.timed_out, timeout)
+        if result.returncode != 0:
+            raise CphError("oj download failed", kind="oj_download_failed")
+        # 取得したファイルがリンク経由でワークスペースの外を指していないか確認する
+        self.upm.validator().check_tree(test_dir_host)
 
     def submit_via_ojtools(self, args, volumes, workdir):
         # ojtoolsコンテナでoj submitを実行
@@ -184,14 +300,16 @@ class DockerTestExecutionEnvironment(TestEnvFileOpsMixin, TestExecutionEnvironme
         manager = InfoJsonManager(info_path)
         ojtools_list = manager.get_containers(type="ojtools")
         if not ojtools_list:
-            raise RuntimeError("ojtools用コンテナがsystem_info.jsonにありません")
+            raise CphError("ojtools用コンテナがsystem_info.jsonにありません", kind="ojtools_container_missing")
         ojtools_name = ojtools_list[0]["name"]
         ctl = self.ctl
         if not ctl.is_container_running(ojtools_name):
             ctl.start_container(ojtools_name, ContainerImageManager().ensure_image("ojtools"), {})
-        cmd = ["oj"] + args
+        cookie_jar = self.oj_cookie_jar()
+        cmd = ["oj"] + (["--cookie", cookie_jar] if cookie_jar else []) + args
         print(f"[DEBUG] docker exec {ojtools_name} {' '.join(map(str, cmd))}")
-        result = ctl.exec_in_container(ojtools_name, cmd)
+        with network_slot():
+            result = ctl.exec_in_container(ojtools_name, cmd)
         print(f"[DEBUG] returncode: {result.returncode}")
         print(f"[DEBUG] stdout: {result.stdout}")
         print(f"[DEBUG] stderr: {result.stderr}")
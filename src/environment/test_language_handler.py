@@ -1,92 +1,171 @@
 from src.path_manager.unified_path_manager import UnifiedPathManager
 import os
+import re
+import shlex
+from src.config_json_manager import ConfigJsonManager
+from src.commands.case_storage import case_input
+from src.environment.diagnostics import parse_diagnostics, parse_rustc_diagnostics
 HOST_PROJECT_ROOT = __import__('os').path.abspath('.')
 CONTAINER_WORKSPACE = '/workspace'
 upm = UnifiedPathManager(HOST_PROJECT_ROOT, CONTAINER_WORKSPACE)
 
+def parse_rustc_warnings(output):
+    """
+    cargo / rustcの出力から警告を1件ずつ取り出す（見出しと、あれば --> の位置）。
+    「N warnings emitted」などの集計行は除く。
+    """
+    warnings = []
+    lines = (output or "").splitlines()
+    for i, line in enumerate(lines):
+        if not line.startswith("warning:"):
+            continue
+        message = line[len("warning:"):].strip()
+        if message.endswith(("warning emitted", "warnings emitted")) or "generated" in message and message.endswith(("warning", "warnings")):
+            continue
+        location = lines[i + 1].strip() if i + 1 < len(lines) and lines[i + 1].strip().startswith("-->") else None
+        warnings.append(f"{message} ({location[3:].strip()})" if location else message)
+    return warnings
+
 class TestLanguageHandler:
+    # 対応するビルドプリセット（releaseは通常のテスト用、debugはオーバーフロー検査などを有効にしたビルド）
+    BUILD_PRESETS = ("release",)
+
     def build(self, manager, name, temp_source_path):
         # Python, Pypyはビルド不要なので常に成功扱い
         return True, "", ""
     def run(self, manager, name, in_file, temp_source_path, host_in_file=None):
         raise NotImplementedError
+    def run_command(self, temp_source_path):
+        # 解答を実行するコマンド（プロファイラ等でラップする際にも使う）
+        raise NotImplementedError
+    def compile_warnings(self, stdout, stderr):
+        # ビルド出力に含まれるコンパイラの警告（ビルドの無い言語では常に空）
+        return []
+    def compile_diagnostics(self, stdout, stderr):
+        # ビルドエラー・警告の位置（ファイル・行・列）。エラーを先に並べる
+        return []
+    def runtime_diagnostics(self, stderr):
+        # 実行時エラー（トレースバック・panic）の位置
+        return parse_diagnostics(stderr)
+
+def language_profile(language_name):
+    """config.jsonのlanguagesにある言語ごとの設定（組み込みの言語もisolate / flags / envを上書きできる）"""
+    return ConfigJsonManager(str(UnifiedPathManager().config_json())).get_languages().get(language_name) or {}
+
+def env_prefix(env):
+    # envコマンドで環境変数を置き換えてから実行する（コンテナ内でもローカルでも同じコマンドで済む）
+    return ["env"] + [f"{k}={v}" for k, v in env.items()] if env else []
+
+class InterpreterTestHandler(TestLanguageHandler):
+    """
+    インタプリタで実行する言語。手元のモジュールが標準ライブラリを隠したり、.pycが残ったりしないよう、
+    既定で隔離用のフラグと環境変数を付けて実行する。
+    config.jsonのlanguages.{言語}で変えられる。例: {"python": {"isolate": false}} / {"python": {"flags": ["-B"], "env": {"PYTHONHASHSEED": "0"}}}
+    """
+    LANGUAGE = None
+    INTERPRETER = None
+    ISOLATION_FLAGS = []
+    ISOLATION_ENV = {}
+
+    def run_command(self, temp_source_path):
+        profile = language_profile(self.LANGUAGE)
+        isolate = profile.get("isolate", True)
+        flags = profile.get("flags", self.ISOLATION_FLAGS if isolate else [])
+        env = {**(self.ISOLATION_ENV if isolate else {}), **profile.get("env", {})}
+        return env_prefix(env) + [self.INTERPRETER] + list(flags) + [temp_source_path]
+
+class PythonTestHandler(InterpreterTestHandler):
+    LANGUAGE = "python"
+    INTERPRETER = "python3"
+    # -I: PYTHON*の環境変数・ユーザーのsite-packages・解答のディレクトリをsys.pathに入れない / -B: .pycを書かない
+    ISOLATION_FLAGS = ["-I", "-B"]
+    ISOLATION_ENV = {"PYTHONPATH": ""}
 
-class PythonTestHandler(TestLanguageHandler):
     def build(self, manager, name, temp_source_path):
         return True, "", ""
     def run(self, manager, name, in_file, temp_source_path, host_in_file=None):
         # managerがContainerClientならコンテナ内で実行
         if hasattr(manager, 'exec_in_container'):
-            # host_in_fileから内容を読む
+            # host_in_fileの内容を渡す（.zstは展開しながら流す）
             if host_in_file is None:
                 raise ValueError("host_in_file must be provided for container execution")
-            with open(host_in_file, "r", encoding="utf-8") as f:
-                input_data = f.read()
-            cmd = ["python3", temp_source_path]
-            result = manager.exec_in_container(name, cmd, stdin=input_data)
+            cmd = self.run_command(temp_source_path)
+            with case_input(host_in_file) as input_data:
+                result = manager.exec_in_container(name, cmd, stdin=input_data)
             ok = result.returncode == 0
             stdout = result.stdout
             stderr = result.stderr
             return ok, stdout, stderr
         else:
             # ローカル実行用: main.pyにinputを渡して実行
-            cmd = ["python3", temp_source_path]
-            with open(in_file, "r", encoding="utf-8") as f:
-                input_data = f.read()
-            result = manager.run_and_measure(name, cmd, timeout=None, input=input_data)
+            cmd = self.run_command(temp_source_path)
+            with case_input(in_file) as input_data:
+                result = manager.run_and_measure(name, cmd, timeout=None, input=input_data)
             ok = result.returncode == 0
             return ok, result.stdout, result.stderr
 
-class PypyTestHandler(TestLanguageHandler):
+class PypyTestHandler(InterpreterTestHandler):
+    LANGUAGE = "pypy"
+    INTERPRETER = "pypy3"
+    # PyPyの版によっては-Iが無いので、同じ効果の-E（PYTHON*の環境変数を無視）-s（ユーザーのsite-packagesを使わない）を使う
+    ISOLATION_FLAGS = ["-E", "-s", "-B"]
+    ISOLATION_ENV = {"PYTHONPATH": ""}
+
     def build(self, manager, name, temp_source_path):
         return True, "", ""
     def run(self, manager, name, in_file, temp_source_path, host_in_file=None):
         if hasattr(manager, 'exec_in_container'):
             if host_in_file is None:
                 raise ValueError("host_in_file must be provided for container execution")
-            with open(host_in_file, "r", encoding="utf-8") as f:
-                input_data = f.read()
-            cmd = ["pypy3", temp_source_path]
-            result = manager.exec_in_container(name, cmd, stdin=input_data)
+            cmd = self.run_command(temp_source_path)
+            with case_input(host_in_file) as input_data:
+                result = manager.exec_in_container(name, cmd, stdin=input_data)
             ok = result.returncode == 0
             stdout = result.stdout
             stderr = result.stderr
             return ok, stdout, stderr
         else:
-            cmd = ["pypy3", temp_source_path]
-            with open(in_file, "r", encoding="utf-8") as f:
-                input_data = f.read()
-            result = manager.run_and_measure(name, cmd, timeout=None, input=input_data)
+            cmd = self.run_command(temp_source_path)
+            with case_input(in_file) as input_data:
+                result = manager.run_and_measure(name, cmd, timeout=None, input=input_data)
             ok = result.returncode == 0
             return ok, result.stdout, result.stderr
 
 class RustTestHandler(TestLanguageHandler):
-    def build(self, manager, name, temp_source_path):
+    # debugビルドは整数オーバーフロー検査とdebug_assertionsが有効になる
+    BUILD_PRESETS = ("release", "debug")
+    CARGO_ARGS = {"release": ["--release"], "debug": []}
+
+    def build(self, manager, name, temp_source_path, preset="release"):
         # temp_source_pathは.temp/rustディレクトリ
         cargo_dir = os.path.abspath(temp_source_path)
-        cmd = ["cargo", "build", "--release"]
+        cmd = ["cargo", "build"] + self.CARGO_ARGS[preset]
         result = manager.run_and_measure(name, cmd, timeout=None, cwd=cargo_dir)
         ok = result.returncode == 0
         return ok, result.stdout, result.stderr
-    def run(self, manager, name, in_file, temp_source_path, host_in_file=None):
+    def compile_warnings(self, stdout, stderr):
+        return parse_rustc_warnings(stderr) + parse_rustc_warnings(stdout)
+    def compile_diagnostics(self, stdout, stderr):
+        diagnostics = parse_rustc_diagnostics(stderr) + parse_rustc_diagnostics(stdout)
+        return sorted(diagnostics, key=lambda d: d["level"] != "error")
+    def run_command(self, temp_source_path, preset="release"):
         cargo_dir = os.path.abspath(temp_source_path)
-        bin_path = os.path.join(cargo_dir, "target/release/rust")
+        return [os.path.join(cargo_dir, f"target/{preset}/rust")]
+    def run(self, manager, name, in_file, temp_source_path, host_in_file=None):
         if hasattr(manager, 'exec_in_container'):
             if host_in_file is None:
                 raise ValueError("host_in_file must be provided for container execution")
-            with open(host_in_file, "r", encoding="utf-8") as f:
-                input_data = f.read()
-            cmd = [bin_path]
-            result = manager.exec_in_container(name, cmd, stdin=input_data)
+            cmd = self.run_command(temp_source_path)
+            with case_input(host_in_file) as input_data:
+                result = manager.exec_in_container(name, cmd, stdin=input_data)
             ok = result.returncode == 0
             stdout = result.stdout
             stderr = result.stderr
             return ok, stdout, stderr
         else:
-            cmd = [bin_path]
-            with open(in_file, "r", encoding="utf-8") as f:
-                input_data = f.read()
-            result = manager.run_and_measure(name, cmd, timeout=None, input=input_data)
+            cmd = self.run_command(temp_source_path)
+            with case_input(in_file) as input_data:
+                result = manager.run_and_measure(name, cmd, timeout=None, input=input_data)
             ok = result.returncode == 0
             return ok, result.stdout, result.stderr
 
@@ -94,4 +173,74 @@ HANDLERS = {
     "python": PythonTestHandler(),
     "pypy": PypyTestHandler(),
     "rust": RustTestHandler(),
-} 
\ No newline at end of file
+} 
+
+class CustomCommandHandler(TestLanguageHandler):
+    """
+    config.jsonのlanguagesで定義した言語。compile / runはシェルのコマンドのテンプレートで、
+    {source}（解答ファイル）{binary}（ビルドの出力先）{dir}（解答ファイルのディレクトリ）{input}（入力ファイル）が使える。
+    例: {"languages": {"nim": {"source": "main.nim", "compile": "nim c -d:release -o:{binary} {source}", "run": "{binary}", "image": "nimlang/nim:2.0.0", "aliases": ["nm"]}}}
+    入力は常に標準入力にも渡すので、{input}は入力をファイルで受け取るコマンドのときだけ使えばよい。
+    envは実行時に置き換える環境変数で、インタプリタの探索パスを空にするのに使う（例: {"env": {"RUBYLIB": "", "NODE_PATH": ""}}）。
+    """
+    BINARY_NAME = "a.out"
+    PLACEHOLDER = re.compile(r"\{(source|binary|dir|input)\}")
+
+    def __init__(self, language_name, spec):
+        if not (spec or {}).get("run"):
+            raise ValueError(f"config.jsonのlanguages.{language_name}に実行するコマンド（run）がありません")
+        self.language_name = language_name
+        self.compile_template = spec.get("compile")
+        self.run_template = spec["run"]
+        self.env = spec.get("env") or {}
+
+    def placeholders(self, temp_source_path, in_file=None):
+        source = str(temp_source_path)
+        directory = os.path.dirname(source) or "."
+        return {"source": source, "dir": directory, "binary": os.path.join(directory, self.BINARY_NAME), "input": str(in_file) if in_file else "/dev/stdin"}
+
+    def render(self, template, temp_source_path, in_file=None):
+        # {}を使うシェルの構文（${VAR}など）を壊さないよう、決まった名前だけを置き換える
+        values = self.placeholders(temp_source_path, in_file)
+        return self.PLACEHOLDER.sub(lambda m: shlex.quote(values[m.group(1)]), template)
+
+    def shell_command(self, template, temp_source_path, in_file=None):
+        return ["sh", "-c", self.render(template, temp_source_path, in_file)]
+
+    def build(self, manager, name, temp_source_path):
+        if not self.compile_template:
+            return True, "", ""
+        cmd = self.shell_command(self.compile_template, temp_source_path)
+        if hasattr(manager, 'exec_in_container'):
+            result = manager.exec_in_container(name, cmd)
+        else:
+            result = manager.run_and_measure(name, cmd, timeout=None)
+        return result.returncode == 0, result.stdout, result.stderr
+    def run_command(self, temp_source_path, in_file=None):
+        return env_prefix(self.env) + self.shell_command(self.run_template, temp_source_path, in_file)
+    def run(self, manager, name, in_file, temp_source_path, host_in_file=None):
+        cmd = self.run_command(temp_source_path, in_file)
+        if hasattr(manager, 'exec_in_container'):
+            if host_in_file is None:
+                raise ValueError("host_in_file must be provided for container execution")
+            with case_input(host_in_file) as input_data:
+                result = manager.exec_in_container(name, cmd, stdin=input_data)
+            return result.returncode == 0, result.stdout, result.stderr
+        with case_input(in_file) as input_data:
+            result = manager.run_and_measure(name, cmd, timeout=None, input=input_data)
+        return result.returncode == 0, result.stdout, result.stderr
+
+def custom_handlers(config=None):
+    """config.jsonのlanguagesで定義された言語のハンドラ（組み込みの言語の設定の上書きは除く）"""
+    languages = (config or ConfigJsonManager(str(UnifiedPathManager().config_json()))).get_languages()
+    return {name: CustomCommandHandler(name, spec) for name, spec in languages.items() if name not in HANDLERS}
+
+def get_handler(language_name, handlers=None):
+    """組み込みの言語（HANDLERS）を優先し、無ければconfig.jsonのlanguagesから探す"""
+    handlers = HANDLERS if handlers is None else handlers
+    if language_name in handlers:
+        return handlers[language_name]
+    custom = custom_handlers()
+    if language_name not in custom:
+        raise ValueError(f"未対応の言語です: {language_name}（config.jsonのlanguagesでコマンドを指定すると使えます）")
+    return custom[language_name]
@@ -0,0 +1,217 @@
+import os
+
+class CphError(RuntimeError):
+    """種類（kind）付きのエラー。kindからヒントを引く"""
+    def __init__(self, message, kind="unknown"):
+        super().__init__(message)
+        self.kind = kind
+
+# kind → 言語ごとのヒントと参考リンク
+DEFAULT_HINTS = {
+    "docker_not_found": {
+        "ja": "dockerコマンドが見つかりません。Dockerをインストールするか、local を指定してローカル実行してください。",
+        "en": "docker command not found. Install Docker or pass `local` to run without containers.",
+        "doc": "https://docs.docker.com/get-docker/",
+    },
+    "docker_unavailable": {
+        "ja": "dockerデーモンに接続できません。Dockerが起動しているか（docker version）確認するか、local を指定してローカル実行してください。",
+        "en": "Cannot connect to the Docker daemon. Check that Docker is running (docker version) or pass `local` to run without containers.",
+        "doc": "https://docs.docker.com/config/daemon/start/",
+    },
+    "oj_not_found": {
+        "ja": "ojコマンドが見つかりません。pip install online-judge-tools でインストールしてください。",
+        "en": "oj command not found. Install it with `pip install online-judge-tools`.",
+        "doc": "https://github.com/online-judge-tools/oj",
+    },
+    "ojtools_container_missing": {
+        "ja": "ojtools用コンテナがありません。先に open を実行してコンテナを起動してください。",
+        "en": "No ojtools container. Run `open` first to start containers.",
+    },
+    "oj_download_failed": {
+        "ja": "テストケースの取得に失敗しました。ログイン状態（login）とURLを確認してください。",
+        "en": "Failed to download test cases. Check your login (`login`) and the problem URL.",
+    },
+    "build_failed": {
+        "ja": "ビルドに失敗しました。コンパイルエラーの内容を確認してください。",
+        "en": "Build failed. Check the compiler output above.",
+    },
+    "clipboard_unavailable": {
+        "ja": "クリップボードを読めません。--input - でパイプから入力を渡すこともできます。",
+        "en": "Cannot read the clipboard. You can pipe the input with `--input -` instead.",
+    },
+    "permission_denied": {
+        "ja": "権限がありません。ファイルの所有者やdockerグループへの所属を確認してください。",
+        "en": "Permission denied. Check file ownership or membership of the docker group.",
+        "doc": "https://docs.docker.com/engine/install/linux-postinstall/",
+    },
+    "path_escape": {
+        "ja": "ワークスペースの外に書き込もうとしたため中断しました。ファイル名やアーカイブの中身、シンボリックリンクを確認してください。",
+        "en": "Refused to write outside the workspace. Check the file name, the archive contents and any symlinks.",
+    },
+    "http_failed": {
+        "ja": "通信に失敗しました。ネットワーク接続と、ユーザー名・URLが正しいかを確認してください。",
+        "en": "HTTP request failed. Check your network connection and the user name or URL.",
+    },
+    "http_timeout": {
+        "ja": "応答がありませんでした。混雑しているときはconfig.jsonのhttp.timeout_secondsを長くしてください。",
+        "en": "The server did not respond in time. Increase http.timeout_seconds in config.json if the site is busy.",
+    },
+    "operation_timeout": {
+        "ja": "操作全体の制限時間を過ぎました。config.jsonのhttp.operation_timeout_secondsで変えられます。",
+        "en": "The operation exceeded its time limit. Change it with http.operation_timeout_seconds in config.json.",
+    },
+    "cassette_miss": {
+        "ja": "テスト用のカセットに無いリクエストです。CPH_RECORD_CASSETTES=1 を付けて実行し、カセットを録り直してください。",
+        "en": "The request is not in the test cassette. Re-record it by running with CPH_RECORD_CASSETTES=1.",
+    },
+    "share_token_missing": {
+        "ja": "GitHubでgist権限のトークンを作り、環境変数に設定してください。トークン不要の --service 0x0 も使えます。",
+        "en": "Create a GitHub token with the gist scope and set it in the environment, or use `--service 0x0` which needs no token.",
+        "doc": "https://github.com/settings/tokens",
+    },
+    "anonymize_reference_failed": {
+        "ja": "参照解（config.jsonのtest.anonymize.reference）が伏せた入力で失敗しました。伏せ方が入力の形式を崩していないか確認してください。",
+        "en": "The reference solution (test.anonymize.reference) failed on the scrubbed input. Check that the patterns keep the input format valid.",
+    },
+    "repl_missing": {
+        "ja": "対話環境が見つかりません。Rustはイメージにevcxrを入れる（cargo install evcxr_repl）か、config.jsonのrepl.{language}でコマンドを指定してください。",
+        "en": "REPL not found. For Rust, install evcxr in the image (`cargo install evcxr_repl`) or set repl.{language} in config.json.",
+        "doc": "https://github.com/evcxr/evcxr",
+    },
+    "yaml_missing": {
+        "ja": "pip install pyyaml でPyYAMLをインストールしてください。",
+        "en": "Install PyYAML with `pip install pyyaml`.",
+    },
+    "scrape_failed": {
+        "ja": "問題ページの構造が変わった可能性があります。open --debug-scrape で保存したHTMLを添えて報告してください。",
+        "en": "The problem page markup may have changed. Re-run `open --debug-scrape` and report it with the saved HTML.",
+    },
+    "case_check_failed": {
+        "ja": "入力と期待出力の組をそろえ、制約を満たさない入力は直すか.cphignoreで除外してください。",
+        "en": "Pair every input with an expected file, and fix inputs that violate the constraints or exclude them via .cphignore.",
+    },
+    "problem_mismatch": {
+        "ja": "switch で作業中の問題を切り替えてからtestしてください。意図どおりならconfig.jsonのtest.problem_lockをwarnかoffにします。",
+        "en": "Run `switch` to change the active problem before testing, or set test.problem_lock to warn/off in config.json if this is intended.",
+    },
+    "sample_count_mismatch": {
+        "ja": "取り出したサンプルの数が問題文と合いません。保存したHTMLを確認し、足りないケースはtestディレクトリに手で追加してください。",
+        "en": "The number of scraped samples does not match the statement. Check the saved HTML and add the missing cases to the test directory by hand.",
+    },
+    "site_env_missing": {
+        "ja": "sites.yamlの ${NAME} は環境変数から読みます。トークンなどを環境変数に設定してから実行してください。",
+        "en": "`${NAME}` in sites.yaml is read from the environment. Export the token before running.",
+    },
+    "zstd_missing": {
+        "ja": "zstdをインストールしてください（apt install zstd / brew install zstd）。圧縮されていないケースはそのまま使えます。",
+        "en": "Install zstd (apt install zstd / brew install zstd). Uncompressed cases work without it.",
+    },
+    "zstd_failed": {
+        "ja": "圧縮されたテストケースが壊れている可能性があります。ケースを生成し直すか、case export で中身を確認してください。",
+        "en": "The compressed test case may be corrupted. Regenerate it or inspect it with `case export`.",
+    },
+    "lock_timeout": {
+        "ja": "他のcph（watchや別端末のtestなど）がファイルを使用中です。終了を待つか、表示されたプロセスを確認してください。",
+        "en": "Another cph process (e.g. watch or a test in another terminal) holds the lock. Wait for it or check the listed process.",
+    },
+    "remote_run_login": {
+        "ja": "コードテストにはログインが必要です。login でログインするか、sites.yamlのatcoderにクッキーを書いてください。",
+        "en": "The custom test page requires a login. Run `login` or put the atcoder cookies in sites.yaml.",
+    },
+    "remote_run_timeout": {
+        "ja": "ジャッジが混んでいる可能性があります。少し待ってからもう一度 remote-run を実行してください。",
+        "en": "The judge may be busy. Wait a moment and run `remote-run` again.",
+    },
+    "remote_run_unsupported": {
+        "ja": "このサイトでは手元の run / test で確かめてください。--site atcoder ならコードテストを使えます。",
+        "en": "Use the local `run` / `test` for this site. `--site atcoder` supports the custom test page.",
+    },
+    "generator_failed": {
+        "ja": "config.jsonのestimate.generatorのコマンドを、contest_currentで単独に動かして確かめてください（{n}に大きさ、{seed}に乱数の種が入ります）。",
+        "en": "Run the estimate.generator command from config.json on its own in contest_current ({n} is the size and {seed} the random seed).",
+    },
+    "estimate_crashed": {
+        "ja": "同じ{n}・{seed}でestimate.generatorを実行し、その出力を run --input - に渡して確かめてください。",
+        "en": "Run estimate.generator with the same {n} and {seed} and pipe its output to `run --input -` to reproduce.",
+    },
+    "experimental_disabled": {
+        "ja": 'config.jsonに "experimental": ["機能名"] を足すと使えます。実験的な機能は今後の版で変わることがあります。',
+        "en": 'Add "experimental": ["<feature>"] to config.json to enable it. Experimental features may change in future versions.',
+    },
+    "workspace_manifest_invalid": {
+        "ja": ".cph/workspace.tomlを直すか、消してから実行し直してください（消すと今の構成で作り直します）。",
+        "en": "Fix .cph/workspace.toml, or delete it and rerun (it is recreated from the current layout).",
+    },
+    "toml_missing": {
+        "ja": "Python 3.11以降を使うか、pip install tomli でtomliを入れてください。",
+        "en": "Use Python 3.11 or later, or install tomli (pip install tomli).",
+    },
+    "workspace_too_new": {
+        "ja": "このワークスペースを更新したcphと同じか新しい版のcphを使ってください。",
+        "en": "Use the same or a newer cph than the one that last updated this workspace.",
+    },
+    "workspace_migration_failed": {
+        "ja": "表示されたバックアップから元に戻せます。原因を直してから実行し直すと、続きから更新します。",
+        "en": "You can restore from the listed backup. Fix the cause and rerun to resume the upgrade.",
+    },
+    "difficulty_unavailable": {
+        "ja": "ネットワークを確認してください。難易度はtag --difficultyで手で付けることもできます。",
+        "en": "Check your network. You can also set the difficulty by hand with tag --difficulty.",
+    },
+    "file_not_found": {
+        "ja": "ファイルが見つかりません。contest_current の状態を確認してください（open で展開し直せます）。",
+        "en": "File not found. Check contest_current (re-run `open` to restore it).",
+    },
+}
+
+def classify(exc):
+    """例外からkindを求める。CphError以外は例外の型と対象ファイルから判定する"""
+    if isinstance(exc, CphError):
+        return exc.kind
+    if isinstance(exc, FileNotFoundError):
+        target = os.path.basename(str(exc.filename or ""))
+        if target == "docker":
+            return "docker_not_found"
+        if target == "oj":
+            return "oj_not_found"
+        return "file_not_found"
+    if isinstance(exc, PermissionError):
+        return "permission_denied"
+    return "unknown"
+
+def current_locale():
+    lang = os.environ.get("CPH_LANG") or os.environ.get("LANG") or "ja"
+    # en_US.UTF-8 → en。接頭辞で比べると ca（カタルーニャ語）なども英語になってしまうので、言語の部分だけを比べる
+    return "en" if lang.split(".")[0].split("_")[0].lower() in ("en", "c", "posix") else "ja"
+
+class HintRegistry:
+    """
+    エラーの種類ごとのヒントを管理する。config.jsonのhintsで追加・上書きできる。
+    例: {"hints": {"build_failed": {"ja": "社内Wikiのビルド手順を参照", "doc": "https://..."}}}
+    """
+    def __init__(self, extra=None, locale=None):
+        self.hints = {kind: dict(entry) for kind, entry in DEFAULT_HINTS.items()}
+        self.locale = locale or current_locale()
+        for kind, entry in (extra or {}).items():
+            self.register(kind, **(entry if isinstance(entry, dict) else {self.locale: entry}))
+
+    def register(self, kind, doc=None, **messages):
+        entry = self.hints.setdefault(kind, {})
+        entry.update(messages)
+        if doc:
+            entry["doc"] = doc
+
+    def lookup(self, exc):
+        kind = classify(exc)
+        return kind, self.hints.get(kind)
+
+    def format(self, exc):
+        """表示用のヒント行を返す。該当するヒントが無ければ空リスト"""
+        kind, entry = self.lookup(exc)
+        if not entry:
+            return []
+        message = entry.get(self.locale) or entry.get("ja") or entry.get("en")
+        lines = [f"[ヒント] {message}"] if message else []
+        if entry.get("doc"):
+            lines.append(f"[参考] {entry['doc']}")
+        return lines
@@ -0,0 +1,52 @@
+from pathlib import Path
+from src import events
+from src.commands.hooks import HookRunner
+from src.config_json_manager import ConfigJsonManager
+from src.notifier import NotificationRouter
+from src.usage_stats import UsageStats
+
+# フックのプレースホルダに渡せる値（結果の一覧などは渡さない）
+HOOK_VALUE_TYPES = (str, int, float, bool, Path)
+
+def hook_values(event):
+    return {k: v for k, v in event.fields.items() if isinstance(v, HOOK_VALUE_TYPES)}
+
+def record_test_stats(stats=None):
+    """testの各ケースの実行時間を利用統計に加える（統計が無効なら何もしない）"""
+    def handler(event):
+        (stats or UsageStats()).record_tests(event.results)
+    return handler
+
+def run_event_hooks(get_hooks=None):
+    """
+    config.jsonのhooksにイベント名のフックがあれば実行する。
+    例: {"hooks": {"submission_accepted": "notify-send 'AC {contest} {problem}'"}}
+    """
+    def handler(event):
+        hooks = HookRunner(get_hooks() if get_hooks else ConfigJsonManager().get_hooks())
+        if hooks.has(event.name):
+            hooks.run(event.name, **hook_values(event))
+    return handler
+
+def send_notifications(get_notify=None):
+    """config.jsonのnotify.routesでイベントに割り当てたチャンネル（デスクトップ・Slack・Discord）に通知する"""
+    def handler(event):
+        config = get_notify() if get_notify else ConfigJsonManager().get_notify()
+        if config.get("routes"):
+            NotificationRouter(config).notify(event)
+    return handler
+
+# 既定の購読を登録済みのバス（同じプロセスでmainが何度呼ばれても二重に反応しないように）
+_installed = []
+
+def install(bus=None, stats=None, get_hooks=None, get_notify=None):
+    """cphの実行で使う購読をまとめて登録する（mainで呼ぶ）。解除する関数の一覧を返す（登録済みなら空）"""
+    bus = bus or events.current()
+    if any(b is bus for b in _installed):
+        return []
+    _installed.append(bus)
+    return [
+        bus.subscribe(events.TEST_RUN_COMPLETED, record_test_stats(stats)),
+        bus.subscribe(events.ALL, run_event_hooks(get_hooks)),
+        bus.subscribe(events.ALL, send_notifications(get_notify)),
+    ]
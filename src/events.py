@@ -0,0 +1,76 @@
+import threading
+
+# cph内で起きたこと。購読する側（統計・フックなど）はこの名前で受け取る
+PROBLEM_OPENED = "problem_opened"
+TEST_RUN_COMPLETED = "test_run_completed"
+SUBMISSION_SENT = "submission_sent"
+SUBMISSION_ACCEPTED = "submission_accepted"
+EVENTS = [PROBLEM_OPENED, TEST_RUN_COMPLETED, SUBMISSION_SENT, SUBMISSION_ACCEPTED]
+# すべてのイベントを受け取る購読
+ALL = "*"
+
+class Event:
+    """イベントの名前と値（contest / problem / language など）。値は属性としても読める"""
+    def __init__(self, name, **fields):
+        self.name = name
+        self.fields = fields
+
+    def __getattr__(self, key):
+        try:
+            return self.__dict__["fields"][key]
+        except KeyError:
+            raise AttributeError(key) from None
+
+    def __repr__(self):
+        return f"Event({self.name!r}, {self.fields!r})"
+
+class EventBus:
+    """
+    プロセス内のイベントの受け渡し。test / submit などの処理は起きたことをpublishするだけにし、
+    統計の記録やフックの実行などの反応はsubscribeした側に置く（反応を足すたびに処理側を直さないため）。
+    購読側の失敗で元の処理を失敗させないよう、例外は警告を出して無視する。
+    """
+    def __init__(self):
+        self.handlers = {}
+        self.lock = threading.Lock()
+
+    def subscribe(self, name, handler):
+        """nameのイベント（ALLなら全部）でhandler(event)を呼ぶ。戻り値を呼ぶと購読をやめる"""
+        if name != ALL and name not in EVENTS:
+            raise ValueError(f"未対応のイベントです: {name}（{', '.join(EVENTS)}）")
+        with self.lock:
+            self.handlers.setdefault(name, []).append(handler)
+        def unsubscribe():
+            with self.lock:
+                if handler in self.handlers.get(name, []):
+                    self.handlers[name].remove(handler)
+        return unsubscribe
+
+    def publish(self, name, **fields):
+        """購読している順に呼び、作ったEventを返す"""
+        event = Event(name, **fields)
+        with self.lock:
+            handlers = list(self.handlers.get(name, [])) + list(self.handlers.get(ALL, []))
+        for handler in handlers:
+            try:
+                handler(event)
+            except Exception as e:
+                print(f"[警告] イベント {name} の処理に失敗しました: {e}")
+        return event
+
+_bus = None
+_bus_lock = threading.Lock()
+
+def current():
+    """このプロセスのバス。購読はmainでevent_subscribers.installする（テストなどでは誰も購読しない）"""
+    global _bus
+    with _bus_lock:
+        if _bus is None:
+            _bus = EventBus()
+        return _bus
+
+def publish(name, **fields):
+    return current().publish(name, **fields)
+
+def subscribe(name, handler):
+    return current().subscribe(name, handler)
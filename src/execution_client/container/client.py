@@ -1,10 +1,22 @@
 from abc import ABC, abstractmethod
 import subprocess
+import sys
+import threading
+import time
 from typing import Optional, List, Dict, Any, Callable
 import json
 from execution_client.abstract_client import AbstractExecutionClient
 from execution_client.types import ExecutionResult
-import threading
+from src.shell import Shell, CommandSpec
+from src.error_hints import CphError
+
+DEFAULT_TIMEOUT = 30
+# dockerデーモンへの疎通確認。成功したらHEALTH_INTERVAL秒は確認を省く
+PING_TIMEOUT = 5
+HEALTH_INTERVAL = 30.0
+# 疎通できないときにやり直す回数と、1回目の待ち時間（秒、やり直すたびに倍にする）
+RECONNECT_ATTEMPTS = 3
+RECONNECT_BACKOFF = 0.5
 
 class AbstractContainerClient(ABC):
     @abstractmethod
@@ -32,8 +44,36 @@ class AbstractContainerClient(ABC):
         pass
 
 class ContainerClient(AbstractExecutionClient, AbstractContainerClient):
-    def __init__(self, timeout: int = 30):
+    def __init__(self, timeout: int = DEFAULT_TIMEOUT, shell: Optional[Shell] = None):
         self.timeout = timeout
+        self.shell = shell or Shell()
+        # 最後にデーモンへ疎通できた時刻（time.monotonic）
+        self.healthy_at: Optional[float] = None
+
+    def docker(self, cmd: List[str], stdin: Optional[str] = None, timeout: Optional[float] = None):
+        """dockerコマンドを実行する（cmdは先頭の"docker"を含む）。タイムアウトはresult.timed_outで返る"""
+        return self.shell.run(CommandSpec.from_argv(cmd, stdin=stdin, timeout=self.timeout if timeout is None else timeout))
+
+    def ping(self, timeout: float = PING_TIMEOUT) -> bool:
+        """dockerデーモンに接続できるか"""
+        result = self.docker(["docker", "version", "--format", "{{.Server.Version}}"], timeout=timeout)
+        return not result.timed_out and result.returncode == 0
+
+    def ensure_daemon(self, attempts: int = RECONNECT_ATTEMPTS, backoff: float = RECONNECT_BACKOFF, sleep: Callable[[float], None] = time.sleep) -> None:
+        """
+        dockerデーモンに接続できることを確かめる。直前に確かめていれば何もしない。
+        接続できなければ待ってからやり直し（デーモンの再起動中など）、attempts回とも失敗したらCphErrorにする
+        """
+        if self.healthy_at is not None and time.monotonic() - self.healthy_at < HEALTH_INTERVAL:
+            return
+        for attempt in range(attempts):
+            if attempt:
+                sleep(backoff * 2 ** (attempt - 1))
+            if self.ping():
+                self.healthy_at = time.monotonic()
+                return
+        self.healthy_at = None
+        raise CphError("dockerデーモンに接続できません", kind="docker_unavailable")
 
     def run_container(self, name: str, image: str, command: Optional[List[str]] = None, volumes: Optional[Dict[str, str]] = None, detach: bool = True, env: Optional[Dict[str, str]] = None, ports: Optional[Dict[int, int]] = None, cpus: Optional[float] = None, memory: Optional[str] = None) -> str:
         cmd = ["docker", "run"]
@@ -58,192 +98,175 @@ class ContainerClient(AbstractExecutionClient, AbstractContainerClient):
             cmd += command
         else:
             cmd += ["tail", "-f", "/dev/null"]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return result.stdout.strip()
-            else:
-                print(f"[ERROR] docker run failed: {result.stderr}")
-                return ""
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker run timed out")
             return ""
+        if result.returncode == 0:
+            return result.stdout.strip()
+        else:
+            print(f"[ERROR] docker run failed: {result.stderr}")
+            return ""
 
     def stop_container(self, name: str) -> bool:
         cmd = ["docker", "stop", name]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return True
-            else:
-                print(f"[ERROR] docker stop failed: {result.stderr}")
-                return False
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker stop timed out")
             return False
+        if result.returncode == 0:
+            return True
+        else:
+            print(f"[ERROR] docker stop failed: {result.stderr}")
+            return False
 
     def remove_container(self, name: str) -> bool:
         cmd = ["docker", "rm", "-f", name]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return True
-            else:
-                print(f"[ERROR] docker rm failed: {result.stderr}")
-                return False
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker rm timed out")
             return False
+        if result.returncode == 0:
+            return True
+        else:
+            print(f"[ERROR] docker rm failed: {result.stderr}")
+            return False
 
     def exec_in_container(self, name: str, cmd_list: List[str], realtime: bool = False, stdin: str = None) -> subprocess.CompletedProcess:
         cmd = ["docker", "exec", "-i", name] + cmd_list
         if not realtime:
-            try:
-                result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout, input=stdin)
-                if result.returncode != 0:
-                    print(f"[ERROR] docker exec failed: {result.stderr}")
-                return result
-            except subprocess.TimeoutExpired:
+            result = self.docker(cmd, stdin=stdin)
+            if result.timed_out:
                 print("[ERROR] docker exec timed out")
                 return subprocess.CompletedProcess(cmd, 1, '', 'timeout')
+            if result.returncode != 0:
+                print(f"[ERROR] docker exec failed: {result.stderr}")
+            return result.to_completed_process()
         else:
-            try:
-                proc = subprocess.Popen(cmd, stdout=subprocess.PIPE, stderr=subprocess.STDOUT, text=True, stdin=subprocess.PIPE)
-                output = ""
-                if stdin is not None:
-                    proc.stdin.write(stdin)
-                    proc.stdin.close()
-                try:
-                    for line in proc.stdout:
-                        output += line
-                except Exception:
-                    pass
-                proc.wait(timeout=self.timeout)
-                if proc.returncode != 0:
-                    print(f"[ERROR] docker exec (realtime) failed: {output}")
-                return subprocess.CompletedProcess(cmd, proc.returncode, output, output if proc.returncode != 0 else "")
-            except subprocess.TimeoutExpired:
+            # 出力を読みながら待つ（stdout・stderrは分けて返す）
+            result = self.shell.spawn(CommandSpec.from_argv(cmd, stdin=stdin if stdin is not None else "", timeout=self.timeout)).wait()
+            if result.timed_out:
                 print("[ERROR] docker exec (realtime) timed out")
                 return subprocess.CompletedProcess(cmd, 1, '', 'timeout')
+            if result.returncode != 0:
+                print(f"[ERROR] docker exec (realtime) failed: {result.stderr or result.stdout}")
+            return result.to_completed_process()
+
+    def exec_interactive(self, name: str, cmd_list: List[str]) -> int:
+        """標準入出力を端末につないだままコンテナ内でコマンドを実行し、終了コードを返す"""
+        tty = ["-t"] if sys.stdin.isatty() and sys.stdout.isatty() else []
+        cmd = ["docker", "exec", "-i"] + tty + [name] + cmd_list
+        return self.shell.attach(CommandSpec.from_argv(cmd))
 
     def copy_to_container(self, name: str, src_path: str, dst_path: str) -> bool:
         cmd = ["docker", "cp", src_path, f"{name}:{dst_path}"]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return True
-            else:
-                print(f"[ERROR] docker cp to container failed: {result.stderr}")
-                return False
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker cp to container timed out")
             return False
+        if result.returncode == 0:
+            return True
+        else:
+            print(f"[ERROR] docker cp to container failed: {result.stderr}")
+            return False
 
     def copy_from_container(self, name: str, src_path: str, dst_path: str) -> bool:
         cmd = ["docker", "cp", f"{name}:{src_path}", dst_path]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return True
-            else:
-                print(f"[ERROR] docker cp from container failed: {result.stderr}")
-                return False
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker cp from container timed out")
             return False
+        if result.returncode == 0:
+            return True
+        else:
+            print(f"[ERROR] docker cp from container failed: {result.stderr}")
+            return False
 
     def is_container_running(self, name: str) -> bool:
         cmd = ["docker", "inspect", "-f", "{{.State.Running}}", name]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode != 0:
-                return False
-            return result.stdout.strip() == "true"
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print(f"[ERROR] docker inspect timed out for {name}")
             return False
+        if result.returncode != 0:
+            return False
+        return result.stdout.strip() == "true"
 
     def list_containers(self, all: bool = True, prefix: Optional[str] = None) -> List[str]:
         cmd = ["docker", "ps", "-a" if all else "", "--format", "{{.Names}}"]
         cmd = [c for c in cmd if c]  # 空文字列を除去
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode != 0:
-                print(f"[ERROR] docker ps failed: {result.stderr}")
-                return []
-            names = result.stdout.splitlines()
-            if prefix:
-                names = [n for n in names if n.startswith(prefix)]
-            return names
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker ps timed out")
             return []
+        if result.returncode != 0:
+            print(f"[ERROR] docker ps failed: {result.stderr}")
+            return []
+        names = result.stdout.splitlines()
+        if prefix:
+            names = [n for n in names if n.startswith(prefix)]
+        return names
 
     def inspect_container(self, name: str) -> Optional[dict]:
         cmd = ["docker", "inspect", name]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return json.loads(result.stdout)[0]
-            else:
-                print(f"[ERROR] docker inspect failed: {result.stderr}")
-                return None
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print(f"[ERROR] docker inspect timed out for {name}")
             return None
+        if result.returncode == 0:
+            return json.loads(result.stdout)[0]
+        else:
+            print(f"[ERROR] docker inspect failed: {result.stderr}")
+            return None
 
     def inspect_image(self, image_name: str) -> Optional[dict]:
         cmd = ["docker", "inspect", image_name]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return json.loads(result.stdout)[0]
-            else:
-                print(f"[ERROR] docker inspect image failed: {result.stderr}")
-                return None
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print(f"[ERROR] docker inspect image timed out for {image_name}")
             return None
+        if result.returncode == 0:
+            return json.loads(result.stdout)[0]
+        else:
+            print(f"[ERROR] docker inspect image failed: {result.stderr}")
+            return None
 
     def get_container_logs(self, name: str, tail: Optional[int] = None) -> str:
         cmd = ["docker", "logs"]
         if tail is not None:
             cmd += ["--tail", str(tail)]
         cmd.append(name)
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode == 0:
-                return result.stdout
-            else:
-                print(f"[ERROR] docker logs failed: {result.stderr}")
-                return ""
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print(f"[ERROR] docker logs timed out for {name}")
             return ""
+        if result.returncode == 0:
+            return result.stdout
+        else:
+            print(f"[ERROR] docker logs failed: {result.stderr}")
+            return ""
 
     def container_exists(self, name: str) -> bool:
         cmd = ["docker", "ps", "-a", "--format", "{{.Names}}"]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode != 0:
-                return False
-            names = result.stdout.splitlines()
-            return name in names
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker ps timed out")
             return False
+        if result.returncode != 0:
+            return False
+        names = result.stdout.splitlines()
+        return name in names
 
     def image_exists(self, image_name: str) -> bool:
         cmd = ["docker", "images", "--format", "{{.Repository}}"]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            if result.returncode != 0:
-                return False
-            images = result.stdout.splitlines()
-            return image_name in images
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker images timed out")
             return False
+        if result.returncode != 0:
+            return False
+        images = result.stdout.splitlines()
+        return image_name in images
 
     def run(self, name: str, image: Optional[str] = None, command: Optional[List[str]] = None, volumes: Optional[Dict[str, str]] = None, detach: bool = True, realtime: bool = False, on_stdout: Optional[Callable[[str], None]] = None, on_stderr: Optional[Callable[[str], None]] = None, **kwargs) -> Any:
         if not realtime:
@@ -258,18 +281,8 @@ class ContainerClient(AbstractExecutionClient, AbstractContainerClient):
             cmd.append(image)
             if command:
                 cmd += command
-            proc = subprocess.Popen(cmd, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, bufsize=1)
-            def reader(stream, callback):
-                for line in iter(stream.readline, ''):
-                    if callback:
-                        callback(line)
-            t_out = threading.Thread(target=reader, args=(proc.stdout, on_stdout))
-            t_err = threading.Thread(target=reader, args=(proc.stderr, on_stderr))
-            t_out.daemon = True
-            t_err.daemon = True
-            t_out.start()
-            t_err.start()
-            return ExecutionResult(returncode=None, stdout=None, stderr=None, extra={"popen": proc})
+            handle = self.shell.spawn(CommandSpec.from_argv(cmd), on_stdout=on_stdout, on_stderr=on_stderr)
+            return ExecutionResult(returncode=None, stdout=None, stderr=None, extra={"popen": handle.proc, "handle": handle})
 
     def stop(self, name: str) -> bool:
         return self.stop_container(name)
@@ -283,18 +296,8 @@ class ContainerClient(AbstractExecutionClient, AbstractContainerClient):
         else:
             # docker execのリアルタイム出力取得
             full_cmd = ["docker", "exec", name] + cmd
-            proc = subprocess.Popen(full_cmd, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, bufsize=1)
-            def reader(stream, callback):
-                for line in iter(stream.readline, ''):
-                    if callback:
-                        callback(line)
-            t_out = threading.Thread(target=reader, args=(proc.stdout, on_stdout))
-            t_err = threading.Thread(target=reader, args=(proc.stderr, on_stderr))
-            t_out.daemon = True
-            t_err.daemon = True
-            t_out.start()
-            t_err.start()
-            return ExecutionResult(returncode=None, stdout=None, stderr=None, extra={"popen": proc})
+            handle = self.shell.spawn(CommandSpec.from_argv(full_cmd), on_stdout=on_stdout, on_stderr=on_stderr)
+            return ExecutionResult(returncode=None, stdout=None, stderr=None, extra={"popen": handle.proc, "handle": handle})
 
     def is_running(self, name: str) -> bool:
         return self.is_container_running(name)
@@ -304,9 +307,28 @@ class ContainerClient(AbstractExecutionClient, AbstractContainerClient):
 
     def start_container(self, name: str, image: str = None, opts: dict = None) -> bool:
         cmd = ["docker", "start", name]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, timeout=self.timeout)
-            return result.returncode == 0
-        except subprocess.TimeoutExpired:
+        result = self.docker(cmd)
+        if result.timed_out:
             print("[ERROR] docker start timed out")
-            return False 
\ No newline at end of file
+            return False
+        return result.returncode == 0
+
+# プロセス全体で使い回すクライアント（疎通確認の結果も共有する）
+_shared_lock = threading.Lock()
+_shared: Optional[ContainerClient] = None
+
+def shared_client() -> ContainerClient:
+    """共有のContainerClientを返す。初めて使うときに作る"""
+    global _shared
+    with _shared_lock:
+        if _shared is None:
+            _shared = ContainerClient()
+        return _shared
+
+def set_shared_client(client: Optional[ContainerClient]) -> Optional[ContainerClient]:
+    """共有のクライアントを差し替え、元のものを返す（Noneなら次に使うときに作り直す）"""
+    global _shared
+    with _shared_lock:
+        previous = _shared
+        _shared = client
+        return previous
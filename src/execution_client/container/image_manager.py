@@ -1,8 +1,20 @@
 from abc import ABC, abstractmethod
-import subprocess
 from typing import Optional, Dict
 import hashlib
 import os
+import time
+from datetime import datetime
+from src.network_limit import network_slot
+from src.error_hints import CphError
+from src.shell import Shell, CommandSpec
+from src.execution_client.container.image_usage import ImageUsage, MANAGED_LABEL, SECONDS_PER_DAY
+
+def parse_created(value):
+    """docker imagesのCreatedAt（2024-01-01 12:00:00 +0900 JST）をUNIX時刻にする。読めなければNone"""
+    try:
+        return datetime.strptime(value.strip()[:25], "%Y-%m-%d %H:%M:%S %z").timestamp()
+    except ValueError:
+        return None
 
 class AbstractContainerImageManager(ABC):
     @abstractmethod
@@ -22,41 +34,50 @@ class AbstractContainerImageManager(ABC):
         pass
 
 class ContainerImageManager(AbstractContainerImageManager):
-    def __init__(self, dockerfile_map: Optional[Dict[str, str]] = None):
+    def __init__(self, dockerfile_map: Optional[Dict[str, str]] = None, image_overrides: Optional[Dict[str, str]] = None, usage: Optional[ImageUsage] = None, shell: Optional[Shell] = None):
         self.dockerfile_map = dockerfile_map or {}
+        # 言語ごとに固定したイメージ（"rust:1.70.0"など）。指定があればDockerfileより優先する
+        self.image_overrides = image_overrides or {}
+        # cphが用意したイメージと最終使用時刻の記録（片付けの判断に使う）
+        self.usage = usage or ImageUsage()
+        self.shell = shell or Shell()
+
+    def docker(self, cmd):
+        """dockerコマンドを実行する（cmdは先頭の"docker"を含む）。Shell経由なのでcommand_logにも残る"""
+        return self.shell.run(CommandSpec.from_argv(cmd))
 
     def build_image(self, dockerfile_path: str, image_name: str, context_dir: str = ".") -> bool:
         """
-        Dockerfileからイメージをビルドする。
+        Dockerfileからイメージをビルドする。cphが管理するイメージとしてラベルを付ける。
         """
         cmd = [
-            "docker", "build", "-f", dockerfile_path, "-t", image_name, context_dir
+            "docker", "build", "-f", dockerfile_path, "-t", image_name, "--label", f"{MANAGED_LABEL}=true", context_dir
         ]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, check=True)
-            return result.returncode == 0
-        except subprocess.CalledProcessError as e:
-            print(f"[ERROR] docker build failed: {e.stderr}")
+        # ベースイメージの取得を伴うのでネットワーク操作として数える
+        with network_slot():
+            result = self.docker(cmd)
+        if not result.ok:
+            print(f"[ERROR] docker build failed: {result.stderr}")
             return False
+        self.usage.touch(image_name, source="build")
+        return True
 
     def remove_image(self, image_name: str) -> bool:
         """
         イメージを削除する。
         """
-        cmd = ["docker", "rmi", image_name]
-        try:
-            result = subprocess.run(cmd, capture_output=True, text=True, check=True)
-            return result.returncode == 0
-        except subprocess.CalledProcessError as e:
-            print(f"[ERROR] docker rmi failed: {e.stderr}")
+        result = self.docker(["docker", "rmi", image_name])
+        if not result.ok:
+            print(f"[ERROR] docker rmi failed: {result.stderr}")
             return False
+        self.usage.forget(image_name)
+        return True
 
     def image_exists(self, image_name: str) -> bool:
         """
         イメージが存在するか確認する。
         """
-        cmd = ["docker", "images", "--format", "{{.Repository}}", image_name]
-        result = subprocess.run(cmd, capture_output=True, text=True)
+        result = self.docker(["docker", "images", "--format", "{{.Repository}}", image_name])
         images = result.stdout.splitlines()
         return image_name in images
 
@@ -68,12 +89,87 @@ class ContainerImageManager(AbstractContainerImageManager):
         # ojtoolsだけはハッシュなしの固定名
         if key == "ojtools":
             return "cph_image_ojtools"
+        if key in self.image_overrides:
+            return self.image_overrides[key]
         dockerfile = self.dockerfile_map.get(key, None)
         if not dockerfile or not os.path.exists(dockerfile):
             return key  # fallback
         hashval = self.get_dockerfile_hash(dockerfile)
         return f"cph_image_{key}_{hashval}"
 
+    def pull_image(self, image_name: str) -> bool:
+        """
+        レジストリからイメージを取得する。
+        """
+        with network_slot():
+            result = self.docker(["docker", "pull", image_name])
+        if not result.ok:
+            print(f"[ERROR] docker pull failed: {result.stderr}")
+            return False
+        # 取得したイメージにはラベルを付けられないので、記録で管理対象にする
+        self.usage.touch(image_name, source="pull")
+        self.apply_cleanup_policy()
+        return True
+
+    def list_managed_images(self) -> Dict[str, Optional[float]]:
+        """
+        cphが管理するイメージ → 作成時刻。ラベル付きのもの（ビルドしたもの）と、
+        取得・ビルドの記録があり今も存在するものを合わせる。
+        """
+        fmt = "{{.Repository}}:{{.Tag}}\t{{.CreatedAt}}"
+        labeled = self.docker(["docker", "images", "--filter", f"label={MANAGED_LABEL}=true", "--format", fmt])
+        listed = self.docker(["docker", "images", "--format", fmt])
+        def parse(output):
+            images = {}
+            for line in (output or "").splitlines():
+                name, _, created = line.partition("\t")
+                if name and "<none>" not in name:
+                    images[name] = parse_created(created)
+            return images
+        managed = parse(labeled.stdout)
+        existing = parse(listed.stdout)
+        for name, entry in self.usage.images().items():
+            if entry.get("source") and name in existing:
+                managed[name] = existing[name]
+        return managed
+
+    def unused_images(self, days: float, now: Optional[float] = None):
+        """days日以上使っていない管理対象のイメージを [(名前, 最終使用時刻)] で返す（古い順）"""
+        now = now or time.time()
+        threshold = now - days * SECONDS_PER_DAY
+        unused = []
+        for name, created in self.list_managed_images().items():
+            last = self.usage.last_used(name) or created
+            if last is not None and last <= threshold:
+                unused.append((name, last))
+        return sorted(unused, key=lambda x: x[1])
+
+    def clean_unused_images(self, days: float, dry_run: bool = False, now: Optional[float] = None):
+        """days日以上使っていないイメージを削除し、削除できたものを返す（使用中のコンテナがあるものは残る）"""
+        removed = []
+        for name, _ in self.unused_images(days, now):
+            if dry_run or self.remove_image(name):
+                removed.append(name)
+        return removed
+
+    def apply_cleanup_policy(self):
+        """
+        config.jsonの {"images": {"auto_clean_days": 30}} があれば、1日1回まで未使用のイメージを自動で削除する。
+        片付けに失敗しても（dockerが無いなど）コマンドは続ける。
+        """
+        try:
+            from src.config_json_manager import ConfigJsonManager
+            days = ConfigJsonManager().get_images().get("auto_clean_days")
+            if not days or not self.usage.cleanup_due():
+                return []
+            self.usage.mark_cleanup()
+            removed = self.clean_unused_images(float(days))
+        except (CphError, OSError):
+            return []
+        for name in removed:
+            print(f"[clean] {days}日以上使っていないイメージを削除しました: {name}")
+        return removed
+
     def cleanup_old_images(self, key: str):
         """
         key: 言語名や用途名
@@ -81,7 +177,7 @@ class ContainerImageManager(AbstractContainerImageManager):
         """
         prefix = f"cph_image_{key}_"
         current = self.get_image_name(key)
-        images = subprocess.run(["docker", "images", "--format", "{{.Repository}}"], capture_output=True, text=True)
+        images = self.docker(["docker", "images", "--format", "{{.Repository}}"])
         image_names = images.stdout.splitlines()
         for img in image_names:
             if img.startswith(prefix) and img != current:
@@ -89,7 +185,7 @@ class ContainerImageManager(AbstractContainerImageManager):
 
     def ensure_image(self, key: str, context_dir: str = ".") -> str:
         image = self.get_image_name(key)
-        images = subprocess.run(["docker", "images", "--format", "{{.Repository}}"], capture_output=True, text=True)
+        images = self.docker(["docker", "images", "--format", "{{.Repository}}"])
         image_names = images.stdout.splitlines()
         if image not in image_names:
             dockerfile = self.dockerfile_map.get(key, None)
@@ -98,4 +194,6 @@ class ContainerImageManager(AbstractContainerImageManager):
                 self.build_image(dockerfile, image, context_dir)
                 if key != "ojtools":
                     self.cleanup_old_images(key)
+                self.apply_cleanup_policy()
+        self.usage.touch(image)
         return image 
\ No newline at end of file
@@ -0,0 +1,87 @@
+import json
+import time
+from pathlib import Path
+from src.file_lock import file_lock
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# cphがビルドしたイメージに付けるラベル（docker images --filter label=... で見つける）
+MANAGED_LABEL = "cph.managed"
+# 使用時刻の書き込みを間引く間隔（秒）。テストのたびに書き込まないようにする
+TOUCH_INTERVAL = 60
+SECONDS_PER_DAY = 24 * 60 * 60
+
+def normalize_image(name):
+    """タグの無い名前は:latestとして扱う（docker imagesの表示と照合するため）"""
+    last = name.rsplit("/", 1)[-1]
+    return name if ":" in last else f"{name}:latest"
+
+def merge_usage(old_path, new_path):
+    """以前プロジェクトの.cphにあった記録を、stateの置き場の記録にまとめる（同じイメージは最後に使った時刻の新しい方）"""
+    old = json.loads(Path(old_path).read_text(encoding="utf-8"))
+    merged = json.loads(Path(new_path).read_text(encoding="utf-8"))
+    images = merged.setdefault("images", {})
+    for key, entry in old.get("images", {}).items():
+        if entry.get("last_used", 0) > images.get(key, {}).get("last_used", 0):
+            images[key] = {**images.get(key, {}), **entry}
+    atomic_write_json(new_path, merged)
+
+class ImageUsage:
+    """
+    cphがビルド・取得したイメージと、最後に使った時刻を ~/.local/state/cph/images.json に記録する（イメージはマシンで共有なので、記録もプロジェクトをまたいで1つ）。
+    例: {"images": {"cph_image_python_0123abcd:latest": {"source": "build", "last_used": 1700000000.0}}, "last_cleanup": ...}
+    記録の失敗でコマンドを失敗させないよう、書き込みのエラーは無視する。
+    """
+    def __init__(self, path=None):
+        self.path = Path(str(path)) if path else UnifiedPathManager().shared_state("images.json", merge_usage)
+
+    def load(self):
+        try:
+            data = json.loads(self.path.read_text(encoding="utf-8"))
+        except (OSError, ValueError):
+            data = {}
+        data.setdefault("images", {})
+        return data
+
+    def update(self, func):
+        try:
+            with file_lock(self.path):
+                data = self.load()
+                func(data)
+                atomic_write_json(self.path, data)
+            return data
+        except Exception:
+            return None
+
+    def images(self):
+        return self.load()["images"]
+
+    def touch(self, image, source=None, now=None):
+        """imageを使ったことを記録する。sourceはbuild / pull（cphが用意したイメージであることの印）"""
+        now = now or time.time()
+        key = normalize_image(image)
+        entry = self.images().get(key)
+        if entry and source is None and now - entry.get("last_used", 0) < TOUCH_INTERVAL:
+            return None
+        def apply(data):
+            e = data["images"].setdefault(key, {})
+            if source:
+                e["source"] = source
+            e["last_used"] = now
+        return self.update(apply)
+
+    def last_used(self, image):
+        entry = self.images().get(normalize_image(image))
+        return entry.get("last_used") if entry else None
+
+    def forget(self, image):
+        return self.update(lambda data: data["images"].pop(normalize_image(image), None))
+
+    def cleanup_due(self, interval_days=1, now=None):
+        """自動の片付けを前回からinterval_days以上経っていれば行う"""
+        now = now or time.time()
+        return now - self.load().get("last_cleanup", 0) >= interval_days * SECONDS_PER_DAY
+
+    def mark_cleanup(self, now=None):
+        now = now or time.time()
+        return self.update(lambda data: data.__setitem__("last_cleanup", now))
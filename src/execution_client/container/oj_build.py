@@ -1,10 +1,10 @@
-import subprocess
 import os
+from src.shell import Shell, CommandSpec
 
 DOCKERFILE = "contest_env/oj.Dockerfile"
 IMAGE_NAME = "cph_image_ojtools"
 
-def build_ojtools_image():
+def build_ojtools_image(shell=None):
     if not os.path.exists(DOCKERFILE):
         print(f"[ERROR] Dockerfile not found: {DOCKERFILE}")
         exit(1)
@@ -14,11 +14,12 @@ def build_ojtools_image():
         "-t", IMAGE_NAME,
         "."
     ]
-    result = subprocess.run(cmd)
-    if result.returncode == 0:
+    # ビルドの出力はそのまま端末に流す（Shell経由なのでcommand_logにも残る）
+    returncode = (shell or Shell()).attach(CommandSpec.from_argv(cmd))
+    if returncode == 0:
         print(f"[OK] Built {IMAGE_NAME}")
     else:
         print(f"[ERROR] Build failed")
 
 if __name__ == "__main__":
-    build_ojtools_image() 
\ No newline at end of file
+    build_ojtools_image()
@@ -1,8 +1,9 @@
 from typing import List, Dict, Optional
 from concurrent.futures import ThreadPoolExecutor
-from execution_client.container.client import ContainerClient
+from src.execution_client.container.client import ContainerClient, DEFAULT_TIMEOUT, shared_client
 from .image_manager import ContainerImageManager
 from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.info_json_manager import InfoJsonManager
 from abc import ABC, abstractmethod
 from .naming_utils import generate_container_name
 import subprocess
@@ -13,23 +14,35 @@ class AbstractContainerPool(ABC):
         pass
 
 class ContainerPool(AbstractContainerPool):
-    def __init__(self, dockerfile_map: Dict[str, str], project_root: Optional[str] = None, container_root: str = "/workspace", max_workers: int = 8, timeout: int = 30):
-        self.client = ContainerClient(timeout=timeout)
-        self.image_manager = ContainerImageManager(dockerfile_map)
+    def __init__(self, dockerfile_map: Dict[str, str], project_root: Optional[str] = None, container_root: str = "/workspace", max_workers: int = 8, timeout: int = DEFAULT_TIMEOUT, image_overrides: Optional[Dict[str, str]] = None):
+        # 既定のタイムアウトなら共有のクライアントを使う
+        self.client = shared_client() if timeout == DEFAULT_TIMEOUT else ContainerClient(timeout=timeout)
+        self.image_manager = ContainerImageManager(dockerfile_map, image_overrides=image_overrides)
         self.max_workers = max_workers
         self.dockerfile_map = dockerfile_map
+        self.image_overrides = image_overrides or {}
         self.unified_path_manager = UnifiedPathManager(project_root=project_root, container_root=container_root)
+        # 直近のadjustで揃えたコンテナ名（stop_allで止める対象）
+        self.container_names: List[str] = []
 
     def generate_container_name(self, purpose: str, language: Optional[str] = None, index: Optional[int] = None) -> str:
         return generate_container_name(purpose, language, index)
 
+    def ensure_daemon(self):
+        # コンテナを揃える前にデーモンへの疎通を確かめる（確かめられないクライアントはそのまま使う）
+        ensure_daemon = getattr(self.client, "ensure_daemon", None)
+        if ensure_daemon is not None:
+            ensure_daemon()
+
     def adjust(self, requirements: List[Dict]) -> List[Dict]:
+        self.ensure_daemon()
         required_containers = self._generate_required_containers(requirements)
         existing = set(self._get_existing_container_names())
         required_names = set(c["name"] for c in required_containers)
         self._remove_unneeded_containers(existing, required_names)
         to_start = self._get_containers_to_start(existing, required_containers)
         self._start_containers(to_start)
+        self.container_names = [c["name"] for c in required_containers]
         return required_containers
 
     def _generate_required_containers(self, requirements: List[Dict]) -> List[Dict]:
@@ -53,6 +66,29 @@ class ContainerPool(AbstractContainerPool):
                 required_containers.append(c)
         return required_containers
 
+    def owned_containers(self) -> List[str]:
+        """
+        このプールのコンテナ名。まだadjustしていなければsystem_info.jsonに記録したもの
+        （別のプロジェクトや別のcphが使っているcph_のコンテナは含めない）
+        """
+        if self.container_names:
+            return list(self.container_names)
+        try:
+            info = InfoJsonManager(str(self.unified_path_manager.info_json()))
+        except (OSError, ValueError):
+            return []
+        return [c["name"] for c in info.get_containers() if c.get("name")]
+
+    def running_containers(self) -> List[str]:
+        return [name for name in self.owned_containers() if self.client.is_container_running(name)]
+
+    def stop_all(self) -> List[str]:
+        """このプールの動いているコンテナを止め、止めたものを返す（消さないので、次のadjustで起動し直して使う）"""
+        running = self.running_containers()
+        with ThreadPoolExecutor(max_workers=self.max_workers) as executor:
+            stopped = list(executor.map(self.client.stop_container, running))
+        return [name for name, ok in zip(running, stopped) if ok]
+
     def _get_existing_container_names(self) -> List[str]:
         return self.client.list_containers(prefix="cph_")
 
@@ -67,8 +103,12 @@ class ContainerPool(AbstractContainerPool):
             expected_vols = c.get("volumes")
             if c["name"] in existing:
                 needs_restart = False
+                pinned_image = self.image_overrides.get(c.get("language"))
                 if not self.client.is_container_running(c["name"]):
                     needs_restart = True
+                elif pinned_image and self._container_image(c["name"]) != pinned_image:
+                    # 固定したツールチェインのイメージと異なるコンテナは作り直す
+                    needs_restart = True
                 elif expected_vols:
                     inspect = self.client.inspect_container(c["name"])
                     if inspect:
@@ -85,6 +125,12 @@ class ContainerPool(AbstractContainerPool):
                 to_start.append(c)
         return to_start
 
+    def _container_image(self, name: str) -> Optional[str]:
+        inspect = self.client.inspect_container(name)
+        if not inspect:
+            return None
+        return inspect.get("Config", {}).get("Image")
+
     def _start_containers(self, to_start: List[Dict]):
         def start_c(c):
             language = "ojtools" if c["type"] == "ojtools" else c.get("language", "python")
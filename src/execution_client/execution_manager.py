@@ -24,7 +24,7 @@ class ExecutionManager(AbstractExecutionManager):
                 returncode=result.returncode,
                 stdout=result.stdout,
                 stderr=result.stderr,
-                extra={"elapsed": elapsed, "timeout": False}
+                extra={"elapsed": elapsed, "timeout": False, "cpu_time": (result.extra or {}).get("cpu_time")}
             )
         # プロセス起動（detach=TrueでPopenを取得）
         result = self.client.run(name, command=command, detach=True, **kwargs)
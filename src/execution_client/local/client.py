@@ -1,34 +1,46 @@
 from execution_client.abstract_client import AbstractExecutionClient
 from execution_client.types import ExecutionResult
+from execution_client.local.priority import make_priority_preexec
+from src.shell import is_stream
+from src import cpu_time
 from typing import Any, Optional, List, Dict, Callable
 import subprocess
 import threading
 import time
 
 class LocalAsyncClient(AbstractExecutionClient):
-    def __init__(self):
+    def __init__(self, cpu_affinity=None, nice=None):
         # name -> (Popen, stdout, stderr)
         self._processes = {}
         self._lock = threading.Lock()
+        # 計測を安定させるためのCPUアフィニティ（"0" / "0-3"など）とnice値。run時のkwargsで上書き可能
+        self.cpu_affinity = cpu_affinity
+        self.nice = nice
+
+    def _preexec_fn(self, kwargs):
+        return make_priority_preexec(kwargs.get("cpu_affinity", self.cpu_affinity), kwargs.get("nice", self.nice))
 
     def run(self, name: str, image: Optional[str] = None, command: Optional[List[str]] = None, volumes: Optional[Dict[str, str]] = None, detach: bool = True, realtime: bool = False, on_stdout: Optional[Callable[[str], None]] = None, on_stderr: Optional[Callable[[str], None]] = None, **kwargs) -> ExecutionResult:
         if not command:
             raise ValueError("command must be specified for local execution")
         input_data = kwargs.get("input", None)
         cwd = kwargs.get("cwd", None)
+        preexec_fn = self._preexec_fn(kwargs)
         with self._lock:
             if name in self._processes:
                 raise RuntimeError(f"Process with name {name} already running")
             if not realtime:
                 if not detach:
-                    # subprocess.runで即時実行
-                    result = subprocess.run(command, input=input_data, text=True, capture_output=True, cwd=cwd)
-                    return ExecutionResult(returncode=result.returncode, stdout=result.stdout, stderr=result.stderr)
+                    # 即時実行し、CPU時間と最大メモリ使用量も測る
+                    # 圧縮ケースの展開パイプなどファイルの入力はstdinにつないで流す
+                    returncode, stdout, stderr, seconds, memory_kb = cpu_time.run_measured_usage(command, input=input_data, cwd=cwd, preexec_fn=preexec_fn)
+                    cpu_time.record(seconds, memory_kb)
+                    return ExecutionResult(returncode=returncode, stdout=stdout, stderr=stderr, extra={"cpu_time": seconds, "memory_kb": memory_kb})
                 else:
-                    proc = subprocess.Popen(command, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, cwd=cwd)
+                    proc = subprocess.Popen(command, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, cwd=cwd, preexec_fn=preexec_fn)
                     self._processes[name] = proc
             else:
-                proc = subprocess.Popen(command, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, bufsize=1, cwd=cwd)
+                proc = subprocess.Popen(command, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, bufsize=1, cwd=cwd, preexec_fn=preexec_fn)
                 self._processes[name] = proc
                 def reader(stream, callback):
                     for line in iter(stream.readline, ''):
@@ -61,11 +73,12 @@ class LocalAsyncClient(AbstractExecutionClient):
         return self.stop(name)
 
     def exec_in(self, name: str, cmd: List[str], realtime: bool = False, on_stdout: Optional[Callable[[str], None]] = None, on_stderr: Optional[Callable[[str], None]] = None, **kwargs) -> ExecutionResult:
+        preexec_fn = self._preexec_fn(kwargs)
         if not realtime:
-            result = subprocess.run(cmd, capture_output=True, text=True)
+            result = subprocess.run(cmd, capture_output=True, text=True, preexec_fn=preexec_fn)
             return ExecutionResult(returncode=result.returncode, stdout=result.stdout, stderr=result.stderr)
         else:
-            proc = subprocess.Popen(cmd, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, bufsize=1)
+            proc = subprocess.Popen(cmd, stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True, bufsize=1, preexec_fn=preexec_fn)
             def reader(stream, callback):
                 for line in iter(stream.readline, ''):
                     if callback:
@@ -0,0 +1,57 @@
+import os
+
+def parse_cpu_list(spec):
+    """"0" / "0,2" / "0-3" 形式のCPU指定を集合に変換する"""
+    if spec is None:
+        return None
+    if isinstance(spec, int):
+        return {spec}
+    if isinstance(spec, (list, tuple, set)):
+        return {int(c) for c in spec}
+    cpus = set()
+    for part in str(spec).split(","):
+        part = part.strip()
+        if not part:
+            continue
+        if "-" in part:
+            lo, hi = part.split("-", 1)
+            cpus.update(range(int(lo), int(hi) + 1))
+        else:
+            cpus.add(int(part))
+    return cpus
+
+def supports_affinity():
+    return hasattr(os, "sched_setaffinity")
+
+def supports_nice():
+    return hasattr(os, "setpriority")
+
+def make_priority_preexec(cpu_affinity=None, nice=None):
+    """
+    子プロセスの起動直前にCPUアフィニティとnice値を設定するpreexec_fnを返す。
+    未対応のプラットフォームでは警告を出して設定を省略し、何も設定しない場合はNoneを返す。
+    権限不足（負のnice値など）のときは設定を諦めてそのまま実行する。
+    """
+    cpus = parse_cpu_list(cpu_affinity)
+    if cpus is not None and not supports_affinity():
+        print("[警告] このプラットフォームではCPUアフィニティを設定できません")
+        cpus = None
+    if nice is not None and not supports_nice():
+        print("[警告] このプラットフォームではnice値を設定できません")
+        nice = None
+    if not cpus and nice is None:
+        return None
+
+    def preexec():
+        # fork後・exec前に実行されるため、ここでは例外を外に出さない
+        if cpus:
+            try:
+                os.sched_setaffinity(0, cpus)
+            except OSError:
+                pass
+        if nice is not None:
+            try:
+                os.setpriority(os.PRIO_PROCESS, 0, int(nice))
+            except OSError:
+                pass
+    return preexec
@@ -0,0 +1,64 @@
+import threading
+from src.config_json_manager import ConfigJsonManager
+from src.error_hints import CphError
+
+# 実験的な機能（名前 → 説明）。config.jsonのexperimentalに名前を並べたときだけ使える。
+# 例: {"experimental": ["daemon"]}
+FEATURES = {
+    "daemon": "常駐するモード（test --watch と、SIGTERMでの終了手順）",
+}
+
+_lock = threading.Lock()
+# 有効にした機能（configureするまではNoneで、初めて使うときにconfig.jsonから読む）
+_enabled = None
+# このプロセスで一度警告した機能
+_warned = set()
+
+def parse(names):
+    """config.jsonのexperimentalを機能名の集合にする。未知の名前は警告して無視する"""
+    if names is None:
+        return set()
+    if isinstance(names, str) or not isinstance(names, list) or not all(isinstance(n, str) for n in names):
+        raise ValueError(f'experimentalは機能名のリストで指定してください（例: ["daemon"]）: {names!r}')
+    unknown = sorted(set(names) - set(FEATURES))
+    if unknown:
+        print(f"[警告] 未知の実験的機能を無視します: {', '.join(unknown)}（使えるもの: {', '.join(sorted(FEATURES))}）")
+    return set(names) & set(FEATURES)
+
+def configure(names):
+    """起動時にconfig.jsonのexperimentalを読み込む"""
+    global _enabled
+    enabled = parse(names)
+    with _lock:
+        _enabled = enabled
+        _warned.clear()
+    return enabled
+
+def enabled_features():
+    with _lock:
+        if _enabled is not None:
+            return set(_enabled)
+    try:
+        names = ConfigJsonManager().get_experimental()
+    except (OSError, ValueError):
+        names = None
+    return configure(names)
+
+def is_enabled(name):
+    return name in enabled_features()
+
+def require(name, usage):
+    """
+    実験的な機能を使う入口で呼ぶ。有効でなければ止め（有効にする方法を添える）、
+    有効なら最初の1回だけ、実験的な機能であることを知らせる
+    """
+    if name not in FEATURES:
+        raise KeyError(name)
+    if not is_enabled(name):
+        raise CphError(f"{usage} は実験的な機能（{name}: {FEATURES[name]}）です。config.jsonのexperimentalで有効にしてください",
+                       kind="experimental_disabled")
+    with _lock:
+        first = name not in _warned
+        _warned.add(name)
+    if first:
+        print(f"[実験的] {usage}（{name}）は実験的な機能です。挙動や設定は今後の版で変わることがあります")
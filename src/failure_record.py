@@ -0,0 +1,48 @@
+import json
+import traceback
+from datetime import datetime
+from src import command_log
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 直近に失敗したコマンドの記録（report-bugで同梱する）。どのディレクトリで失敗しても
+# そこに.cphを作らない（ワークスペースと見なされてしまう）よう、利用統計と同じstateの置き場に置く
+FAILURE_FILE = "last_failure.json"
+
+def keep_newer(old_path, new_path):
+    """以前プロジェクトの.cphに残した記録の方が新しければ、そちらを直近の失敗にする"""
+    old = json.loads(old_path.read_text(encoding="utf-8"))
+    if old.get("created_at", "") > json.loads(new_path.read_text(encoding="utf-8")).get("created_at", ""):
+        atomic_write_json(new_path, old)
+
+def record_failure(argv, exc, upm=None):
+    """
+    失敗したコマンドの引数（伏せ字にしたもの）・エラー・トレースバックと、その実行で使ったdocker / ojコマンドを
+    ~/.local/state/cph/last_failure.json に残す。記録に失敗しても元のエラーの表示を妨げないよう、書き込みのエラーは無視する
+    """
+    data = {
+        "created_at": datetime.now().isoformat(),
+        "argv": command_log.redact(argv),
+        "error": str(exc),
+        "error_type": type(exc).__name__,
+        "kind": getattr(exc, "kind", None),
+        "traceback": "".join(traceback.format_exception(type(exc), exc, exc.__traceback__)),
+        "commands": command_log.get_log().since(0),
+    }
+    try:
+        path = (upm or UnifiedPathManager()).shared_state(FAILURE_FILE, keep_newer)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        atomic_write_json(path, data)
+        return path
+    except Exception:
+        return None
+
+def load_last_failure(upm=None):
+    """直近の失敗の記録。無ければNone"""
+    path = (upm or UnifiedPathManager()).shared_state(FAILURE_FILE, keep_newer)
+    if not path.exists():
+        return None
+    try:
+        return json.loads(path.read_text(encoding="utf-8"))
+    except (OSError, ValueError):
+        return None
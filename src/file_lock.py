@@ -0,0 +1,107 @@
+import os
+import threading
+import time
+from contextlib import contextmanager
+from pathlib import Path
+from src.error_hints import CphError
+
+try:
+    import fcntl
+except ImportError:  # Windows
+    fcntl = None
+
+# ロック取得を諦めるまでの秒数と再試行の間隔
+LOCK_TIMEOUT = 10.0
+LOCK_POLL_INTERVAL = 0.05
+
+# このプロセスが保持しているロック（ロックファイル → fd）。終了時の後片付け（release_all）で使う
+_held = {}
+_held_lock = threading.Lock()
+
+def lock_path_for(path):
+    path = Path(str(path))
+    return path.parent / f".{path.name}.lock"
+
+def _try_lock(fd):
+    if fcntl is None:
+        import msvcrt
+        try:
+            msvcrt.locking(fd, msvcrt.LK_NBLCK, 1)
+            return True
+        except OSError:
+            return False
+    try:
+        fcntl.flock(fd, fcntl.LOCK_EX | fcntl.LOCK_NB)
+        return True
+    except (BlockingIOError, PermissionError):
+        return False
+
+def _unlock(fd):
+    if fcntl is None:
+        import msvcrt
+        os.lseek(fd, 0, os.SEEK_SET)
+        msvcrt.locking(fd, msvcrt.LK_UNLCK, 1)
+    else:
+        fcntl.flock(fd, fcntl.LOCK_UN)
+
+def _holder(lock_path):
+    try:
+        return Path(lock_path).read_text(encoding="utf-8").strip() or "不明"
+    except OSError:
+        return "不明"
+
+@contextmanager
+def file_lock(path, timeout=LOCK_TIMEOUT):
+    """
+    pathの隣の .{name}.lock に排他の advisory lock を掛ける。
+    並行して動くcph（watchと手動testなど）が同じキャッシュを書き換えるときに使う。
+    timeout秒待っても取れなければ、保持しているプロセスを添えてCphError(kind="lock_timeout")。
+    """
+    lock_path = lock_path_for(path)
+    lock_path.parent.mkdir(parents=True, exist_ok=True)
+    fd = os.open(lock_path, os.O_RDWR | os.O_CREAT, 0o666)
+    try:
+        deadline = time.monotonic() + timeout
+        warned = False
+        while not _try_lock(fd):
+            if time.monotonic() >= deadline:
+                raise CphError(f"ロックを取得できません: {path}（保持しているプロセス: {_holder(lock_path)}）", kind="lock_timeout")
+            if not warned:
+                print(f"[待機] 他のcphが使用中です: {path}（保持しているプロセス: {_holder(lock_path)}）")
+                warned = True
+            time.sleep(LOCK_POLL_INTERVAL)
+        # 診断用に保持しているプロセスを書いておく
+        os.ftruncate(fd, 0)
+        os.lseek(fd, 0, os.SEEK_SET)
+        os.write(fd, f"pid={os.getpid()}\n".encode("utf-8"))
+        with _held_lock:
+            _held[str(lock_path)] = fd
+        try:
+            yield lock_path
+        finally:
+            with _held_lock:
+                held = _held.pop(str(lock_path), None) is not None
+            if held:
+                _unlock(fd)
+    finally:
+        os.close(fd)
+
+def held_locks():
+    """このプロセスが保持しているロックファイルの一覧"""
+    with _held_lock:
+        return sorted(_held)
+
+def release_all():
+    """
+    保持しているロックをすべて放し、放したロックファイルを返す（終了の猶予を過ぎたときなど、withを抜けずに終わる場合）。
+    fdは持ち主のwithが閉じる
+    """
+    with _held_lock:
+        held = list(_held.items())
+        _held.clear()
+    for lock_path, fd in held:
+        try:
+            _unlock(fd)
+        except OSError:
+            continue
+    return sorted(path for path, _ in held)
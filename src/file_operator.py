@@ -1,9 +1,138 @@
 from pathlib import Path
 from abc import ABC, abstractmethod
 from shutil import copy2
+import asyncio
+import fnmatch
+import itertools
+import json
+import os
 import shutil
+import uuid
+from src import cancellation
+
+# iter_dirで1回のスレッド呼び出しで読むエントリ数、copy_dirで同時にコピーするファイル数
+ITER_DIR_BATCH = 256
+COPY_DIR_JOBS = 8
+
+def atomic_write(path, content, encoding="utf-8"):
+    """
+    同じディレクトリの一時ファイルに書いてfsyncしてからrenameで置き換える。
+    書き込み途中で落ちても、元のファイルか新しいファイルのどちらかが必ず残る。
+    """
+    path = Path(str(path))
+    path.parent.mkdir(parents=True, exist_ok=True)
+    tmp = path.parent / f".{path.name}.{uuid.uuid4().hex[:8]}.tmp"
+    data = content.encode(encoding) if isinstance(content, str) else content
+    # 0o666で作りumaskを効かせる（既存ファイルがあればその権限を引き継ぐ）
+    mode = path.stat().st_mode & 0o777 if path.exists() else 0o666
+    fd = os.open(tmp, os.O_WRONLY | os.O_CREAT | os.O_EXCL, mode)
+    try:
+        with os.fdopen(fd, "wb") as f:
+            f.write(data)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(tmp, path)
+    except BaseException:
+        if tmp.exists():
+            tmp.unlink()
+        raise
+    fsync_dir(path.parent)
+    return path
+
+def atomic_write_json(path, data):
+    return atomic_write(path, json.dumps(data, ensure_ascii=False, indent=2))
+
+def fsync_dir(path):
+    # renameをディスクに反映させる（ディレクトリをopenできない環境では何もしない）
+    try:
+        fd = os.open(str(path), os.O_RDONLY)
+    except OSError:
+        return
+    try:
+        os.fsync(fd)
+    except OSError:
+        pass
+    finally:
+        os.close(fd)
+
+async def run_blocking(func, *args, **kwargs):
+    """
+    ブロッキングする処理を別スレッドで実行し、イベントループを止めない。
+    Ctrl+Cなどで待つのをやめたときは、スレッド側の通信もcancellationで止める
+    """
+    try:
+        return await asyncio.to_thread(func, *args, **kwargs)
+    except asyncio.CancelledError:
+        cancellation.current().cancel()
+        raise
+
+class AsyncFileOpsMixin:
+    """
+    FileOperatorの操作の非同期版。実体は同期の操作をスレッドで実行するだけなので、
+    Mockなどの派生クラスでもそのまま使える（記録される操作も同期版と同じ）。
+    """
+    async def aexists(self, path):
+        return await run_blocking(self.exists, path)
+
+    async def amakedirs(self, path, exist_ok=True):
+        return await run_blocking(self.makedirs, path, exist_ok)
+
+    async def acreate(self, path, content=""):
+        return await run_blocking(self.create, path, content)
+
+    async def acopy(self, src, dst):
+        return await run_blocking(self.copy, src, dst)
+
+    async def amove(self, src, dst):
+        return await run_blocking(self.move, src, dst)
+
+    async def armtree(self, path):
+        return await run_blocking(self.rmtree, path)
+
+    async def aread_text(self, path, encoding="utf-8"):
+        def read():
+            with self.open(path, "r", encoding=encoding) as f:
+                return f.read()
+        return await run_blocking(read)
+
+    async def iter_dir(self, path, pattern="*", batch_size=ITER_DIR_BATCH):
+        """
+        ディレクトリ直下のエントリをbatch_sizeずつ読みながら返す（順序は不定）。
+        大量のテストケースがあっても全件を読み終えるまで待たない。
+        """
+        it = await run_blocking(os.scandir, self.resolve_path(path))
+        try:
+            while True:
+                batch = await run_blocking(lambda: list(itertools.islice(it, batch_size)))
+                if not batch:
+                    break
+                for entry in batch:
+                    if fnmatch.fnmatch(entry.name, pattern):
+                        yield Path(entry.path)
+        finally:
+            it.close()
+
+    async def copy_dir(self, src, dst, ignore=(), jobs=COPY_DIR_JOBS):
+        """srcの中身をdstにコピーする。ファイルはjobs個まで並行してコピーし、コピーした数を返す"""
+        src_path = self.resolve_path(src)
+        dst_path = self.resolve_path(dst)
 
-class FileOperator(ABC):
+        def collect():
+            files = []
+            for root, dirs, names in os.walk(src_path):
+                dirs[:] = [d for d in dirs if d not in ignore]
+                files.extend(Path(root) / n for n in names if n not in ignore)
+            return files
+        files = await run_blocking(collect)
+        semaphore = asyncio.Semaphore(max(1, jobs))
+
+        async def copy_one(f):
+            async with semaphore:
+                await run_blocking(self.copy, f, dst_path / f.relative_to(src_path))
+        await asyncio.gather(*(copy_one(f) for f in files))
+        return len(files)
+
+class FileOperator(AsyncFileOpsMixin, ABC):
     def __init__(self, base_dir=Path(".")):
         self.base_dir = Path(base_dir)
 
@@ -120,10 +249,7 @@ class LocalFileOperator(FileOperator):
         return path.exists()
 
     def create(self, path, content: str = ""):
-        path = self.resolve_path(path)
-        path.parent.mkdir(parents=True, exist_ok=True)
-        with open(path, "w", encoding="utf-8") as f:
-            f.write(content)
+        atomic_write(self.resolve_path(path), content)
 
     def copytree(self, src, dst):
         src_path = self.resolve_path(src)
@@ -0,0 +1,148 @@
+import json
+import socket
+import urllib.error
+import urllib.parse
+import urllib.request
+from contextlib import contextmanager
+from src import cancellation
+from src.error_hints import CphError
+from src.network_limit import network_slot
+from src.site_config import SiteConfig
+
+USER_AGENT = "cph (competitive programming helper)"
+# 1回のリクエストのタイムアウト（秒）。config.jsonのhttp.timeout_secondsで変えられる
+DEFAULT_TIMEOUT = 30
+REACHABLE_TIMEOUT = 3
+
+def build_url(url, params=None):
+    if not params:
+        return url
+    return f"{url}?{urllib.parse.urlencode(params)}"
+
+class HttpResponse:
+    def __init__(self, status, body=b"", headers=None):
+        self.status = status
+        self.body = body
+        self.headers = headers or {}
+
+    def text(self):
+        return self.body.decode("utf-8")
+
+class UrllibTransport:
+    """実際に通信するトランスポート。4xx/5xxも例外にせずHttpResponseで返す"""
+    def request(self, method, url, headers=None, body=None, timeout=DEFAULT_TIMEOUT):
+        request = urllib.request.Request(url, data=body, method=method, headers=headers or {})
+        try:
+            with urllib.request.urlopen(request, timeout=timeout) as response:
+                return HttpResponse(response.status, response.read(), dict(response.headers))
+        except urllib.error.HTTPError as e:
+            return HttpResponse(e.code, e.read(), dict(e.headers or {}))
+
+    def reachable(self, url, timeout=REACHABLE_TIMEOUT):
+        """urlのホストにTCPで接続できるか（提出前にオフラインかどうかを判断する）"""
+        parts = urllib.parse.urlsplit(url)
+        port = parts.port or (443 if parts.scheme == "https" else 80)
+        try:
+            with socket.create_connection((parts.hostname, port), timeout=timeout):
+                return True
+        except OSError:
+            return False
+
+# サイトごとのヘッダー・クッキー（sites.yaml）。読み込む関数なら、初めて通信するときに読む
+_sites = SiteConfig()
+
+def configure_sites(sites):
+    """
+    sites.yamlの設定をプロセス全体で使う。SiteConfigか、それを返す関数（通信しないコマンドでは読まないので、
+    sites.yamlの誤りやPyYAMLが無いことで止まるのは通信するときだけ）。元の設定を返す
+    """
+    global _sites
+    previous = _sites
+    _sites = sites or SiteConfig()
+    return previous
+
+def sites():
+    """今の設定（SiteConfig）。読み込む関数が設定されていれば、ここで一度だけ読む"""
+    global _sites
+    if callable(_sites):
+        _sites = _sites() or SiteConfig()
+    return _sites
+
+_timeout = DEFAULT_TIMEOUT
+
+def configure_timeout(seconds=None):
+    """config.jsonのhttp.timeout_secondsをプロセス全体で使う"""
+    global _timeout
+    value = float(seconds) if seconds else DEFAULT_TIMEOUT
+    if value <= 0:
+        raise ValueError(f"http.timeout_secondsは正の数を指定してください: {seconds}")
+    _timeout = value
+
+def is_timeout(error):
+    return isinstance(error, TimeoutError) or isinstance(getattr(error, "reason", None), TimeoutError)
+
+# 通信を行うトランスポート。テストではsrc.test_supportのカセットなどに差し替える
+_transport = UrllibTransport()
+
+def get_transport():
+    return _transport
+
+def set_transport(transport):
+    """トランスポートを差し替え、元のものを返す"""
+    global _transport
+    previous = _transport
+    _transport = transport
+    return previous
+
+@contextmanager
+def use_transport(transport):
+    previous = set_transport(transport)
+    try:
+        yield transport
+    finally:
+        set_transport(previous)
+
+def is_reachable(url, timeout=REACHABLE_TIMEOUT):
+    """接続できるか。reachableを持たないトランスポート（カセットなど）は常に接続できるものとする"""
+    reachable = getattr(_transport, "reachable", None)
+    return True if reachable is None else reachable(url, timeout=timeout)
+
+def send(method, url, headers=None, body=None, timeout=None):
+    """
+    リクエストしてHttpResponseを返す。同時接続数はnetwork.max_concurrentに従い、4xx/5xxはCphErrorにする。
+    ヘッダーは 既定 < sites.yamlのサイトごとの設定 < 引数 の順で上書きする。
+    タイムアウトは引数 > http.timeout_seconds の順で、操作全体の残り時間（cancellation.operation）より長くしない。
+    リクエストの前後で中断・操作の時間切れを確かめる
+    """
+    token = cancellation.current()
+    token.check()
+    timeout = token.request_timeout(timeout or _timeout)
+    headers = {"User-Agent": USER_AGENT, "Accept-Encoding": "identity", **sites().headers_for(url), **(headers or {})}
+    try:
+        with network_slot():
+            response = _transport.request(method, url, headers=headers, body=body, timeout=timeout)
+    except OSError as e:
+        token.check()
+        if is_timeout(e):
+            raise CphError(f"HTTPリクエストがタイムアウトしました: {url}（{timeout:g}秒）", kind="http_timeout")
+        raise CphError(f"HTTPリクエストに失敗しました: {url}（{e}）", kind="http_failed")
+    token.check()
+    if response.status >= 400:
+        raise CphError(f"HTTPリクエストに失敗しました: {url}（HTTP {response.status}）", kind="http_failed")
+    return response
+
+def get(url, params=None, timeout=None):
+    """GETしてHttpResponseを返す"""
+    return send("GET", build_url(url, params), timeout=timeout)
+
+def post(url, body, headers=None, timeout=None):
+    """bodyをPOSTしてHttpResponseを返す"""
+    return send("POST", url, headers=headers, body=body, timeout=timeout)
+
+def get_json(url, params=None, timeout=None):
+    """GETしてJSONを返す"""
+    response = get(url, params, timeout)
+    try:
+        return json.loads(response.text())
+    except ValueError as e:
+        raise CphError(f"HTTPリクエストに失敗しました: {url}（{e}）", kind="http_failed")
@@ -1,6 +1,8 @@
 import json
 import os
 from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.file_operator import atomic_write_json
+from src.file_lock import file_lock
 
 class InfoJsonManager:
     def __init__(self, path=None):
@@ -19,8 +21,8 @@ class InfoJsonManager:
     def save(self):
         # __commentを必ず付与
         self.data["__comment"] = "通常、このファイルを編集する必要はありません"
-        with open(self.path, "w", encoding="utf-8") as f:
-            json.dump(self.data, f, ensure_ascii=False, indent=2)
+        with file_lock(self.path):
+            atomic_write_json(self.path, self.data)
 
     def get_containers(self, type=None, language=None):
         containers = self.data.get("containers", [])
@@ -0,0 +1,73 @@
+import os
+from src.commands.case_storage import case_name, case_stem
+
+# 入力の渡し方（config.jsonのtest.pass_input_as）
+STDIN = "stdin"
+ARG = "arg"
+FILE_PREFIX = "file:"
+# ファイルで渡すときに、ケースごとに分けて作る作業ディレクトリ（.temp配下なのでコンテナからも見える）
+WORK_DIR = os.path.join(".temp", "work")
+
+def parse_mode(value):
+    """"stdin" / "arg" / "file:input.txt" → (方法, ファイル名)"""
+    value = STDIN if value is None else str(value)
+    if value in (STDIN, ARG):
+        return value, None
+    if value.startswith(FILE_PREFIX):
+        name = value[len(FILE_PREFIX):]
+        if name and "/" not in name and name not in (".", ".."):
+            return FILE_PREFIX, name
+    raise ValueError(f"test.pass_input_asが不正です: {value}（stdin / arg / file:NAME）")
+
+def shares_input_file(test_config):
+    """test.cwdにfile:NAMEで書き出すと、どのケースも同じファイルに入力を書くので並列には走らせられない"""
+    test_config = test_config or {}
+    mode, _ = parse_mode(test_config.get("pass_input_as"))
+    return bool(test_config.get("cwd")) and mode == FILE_PREFIX
+
+class InputPassing:
+    """
+    解答への入力の渡し方と、実行するディレクトリ。標準入力ではなくファイルから読む問題のため。
+    - stdin: 標準入力に渡す（既定）
+    - arg: 作業ディレクトリに入力を書き出し、そのパスを最後の引数に足す
+    - file:NAME: 作業ディレクトリにNAMEという名前で入力を書き出す
+    どちらも標準入力を書き出すので、圧縮したケースもコンテナ内でもそのまま使え、解答からは標準入力が空に見える（ジャッジと同じ）。
+    作業ディレクトリはtest.cwd（プロジェクトからの相対パス）、無ければケースごとの.temp/work/{ケース}。
+    test.cwdとfile:NAMEを合わせると入力ファイルが共有されるので、testは1ケースずつ実行する（shares_input_file）。
+    config.jsonのlanguagesで定義した言語は、引数ではなくrunの{input}で受け取る。
+    """
+    def __init__(self, mode=STDIN, work_dir=None, file_name=None):
+        self.mode = mode
+        self.work_dir = work_dir
+        self.file_name = file_name
+
+    @classmethod
+    def for_case(cls, test_config, in_file, root, to_runner_path=str):
+        """
+        test_configに従って、in_fileのケースの渡し方を作る。
+        to_runner_pathはホストのパスを実行する側（コンテナ）のパスにする関数
+        """
+        test_config = test_config or {}
+        mode, name = parse_mode(test_config.get("pass_input_as"))
+        cwd = test_config.get("cwd")
+        if cwd:
+            host_dir = os.path.join(str(root), cwd)
+        elif mode != STDIN:
+            host_dir = os.path.join(str(root), WORK_DIR, os.path.basename(case_stem(in_file)))
+        else:
+            return cls()
+        return cls(mode, to_runner_path(os.path.abspath(host_dir)), name or case_name(in_file))
+
+    def wrap(self, cmd):
+        """作業ディレクトリに移り、必要なら入力を書き出してからcmdを実行するコマンドにする"""
+        if self.work_dir is None:
+            return list(cmd)
+        script = 'mkdir -p "$1" && cd "$1" || exit 1; '
+        if self.mode == STDIN:
+            script += 'shift 2; exec "$@"'
+        else:
+            script += 'cat > "$2" || exit 1; input="$PWD/$2"; shift 2; exec "$@"'
+            if self.mode == ARG:
+                script += ' "$input"'
+        return ["sh", "-c", script, "sh", self.work_dir, self.file_name or ""] + list(cmd)
+
@@ -1,8 +1,15 @@
 import sys
+import time
 from .command_executor import CommandExecutor
 from .contest_file_manager import ContestFileManager
 from .file_operator import LocalFileOperator
 from .command_parser import CommandParser
+from .config_json_manager import ConfigJsonManager
+from .error_hints import CphError, HintRegistry
+from .commands import color_theme, output_mode
+from .usage_stats import UsageStats
+from .failure_record import record_failure
+from . import cancellation, event_subscribers, experimental, temp_space, workspace_manifest
 
 # コマンドライン引数: main.py {contest_name} {command} {problem_name} {language_name}
 
@@ -11,39 +18,195 @@ def print_help():
 使い方: python3 src/main.py [contest_name] [command] [problem_name] [language_name]
 
 コマンド一覧:
-  open (o)     : 問題テンプレート展開＋テストケース取得
+  open (o)     : 問題テンプレート展開＋テストケース取得（言語を省くと保管済みの解答の言語で開く。編集中の解答は上書きしない）
   test (t)     : テストケースで実行
-  submit (s)   : 提出
-  login        : ログイン
+  submit (s)   : 提出（オフラインなら保留し、submit --flush で後から送信）。config.jsonのsubmit.checklistの項目を提出前にy/nで確かめる
+  login        : ログイン（login --check でサイトごとのログイン状態・有効期限・アカウント名を表示。未ログインなら終了コード3、期限切れなら2）
+  profile      : 1ケースをプロファイラ配下で実行（--memory / --cpu と --case N）
+  run          : ビルドして端末の入出力につないで実行（--tl 秒 / --ml MB で制限可）
+  repl         : 言語の対話環境を開く（pythonはテンプレートとteam.libを読み込み済み、rustはevcxr）
+  doctor       : ツールチェインのバージョンがジャッジ（config.jsonのtoolchain）と一致するか確認
+  prepare      : 固定したバージョンのツールチェインを導入（docker: イメージ取得 / local: rustup）
+  init         : ワークスペースを初期化（使う言語に合わせた.gitignore・.editorconfigを作る。--team でチーム戦・ICPC向けレイアウトも）
+  print        : 共有ライブラリを印刷用ノートブック（PS / PDF）にまとめる
+  verify       : ライブラリの検証用ファイル（*.test.py）を実行し、verify-helper互換の結果を出力
+  verify-env   : 言語ごとに hello world をビルド・実行し、入出力・TL・終了コードが使えるかを表で表示（使えない言語があれば終了コード1）
+  tag          : 問題にタグ・難易度を付ける（--add dp,graph / --remove / --difficulty 1200）。--compare yesno でこの問題だけ比較方法を変え、--output-limit 256MB で出力の上限を変える（defaultで戻す）。--checklist で提出前に確かめる項目を足す
+  search       : タグ・難易度・未AC（--unsolved）で練習する問題を探す
+  import       : 提出履歴を取り込んで索引を作る（import history --site atcoder|codeforces --user NAME）
+  gc           : 長く触っていない未ACの問題を圧縮して退避・削除（--days 30 / --yes / --delete）
+  stats        : cph自体の利用統計を表示（stats --tool、config.jsonのstats.enabledで記録。送信はしない）、result で残したコンテスト成績の一覧（stats --results）
+  archive      : 索引の問題の提出結果をジャッジから取り直して更新する（archive sync-verdicts --site atcoder|codeforces --user NAME）
+  history      : テスト実行の履歴をCSVに書き出す（history export --csv runs.csv [contest] [problem]）
+  result       : 終了したコンテストの自分の順位・パフォーマンス・レート増減を取得して残す（result abc350 --user NAME）
+  case         : 圧縮（.zst）したテストケースの展開・大きなケースの圧縮（case export [NAME] / case compress / case lint / case check）。case rebind でサンプルを取り直し、違いをケースごとに取り込む
+  remote-run   : 解答と入力をAtCoderのコードテストで実行し、ジャッジの環境での出力・時間・メモリを表示する（remote-run --input FILE）
+  estimate     : config.jsonのestimate.generatorで大きさの違う入力を作って実行時間を測り、伸びから制約の上限（問題文から読む / --max-n）でのTLEを見積もる
+  replay       : 過去のtestの1ケースを、記録した入力・シード・環境変数で今の解答に流し直し、記録した出力・期待出力と比べる（replay RUN_ID [--case N]、RUN_IDはlatestも可）
+  statement    : 作業中の問題の問題文をブラウザで開く（--constraints で制約の一覧を表示）
+  refresh      : コンテスト中に問題ページを取り直し、増えたサンプルを新しいケースとして取り込む。問題文の変更も知らせる（--poll 60s で確かめ続ける）
+  status       : 作業中の問題と保留中の提出を表示
+  switch       : 作業中の問題を切り替える（switch c / switch next / switch prev / switch abc300_c。今の問題はcontest_stocksに残す。初めての問題はopenで取得）
+  badge        : 直近のAC数と最後のテスト結果をREADME用のバッジ（badges/*.svg・cph.json）に書き出す（--days 7 / --output DIR）
+  share        : 解答を貼り付けサービス（gist / 0x0.st）に上げてURLを表示（share --failing で失敗ケースも）
+  explain      : 直近のtestで失敗したケースの違い（最初に違う行・トークン、長さ、空白だけの違いか、TLとの比較）をまとめる（explain [CASE]）
+  fix          : 直近のtestのビルドエラー・実行時エラーの位置をエディタで開く（fix [N]、config.jsonのeditor.command）
+  report-bug   : 不具合の報告用に、伏せ字にした設定・バージョン・直近の失敗の記録をtar.gzにまとめる（--workspace で作業中の問題も）
+  view         : 他の人のアーカイブを読み取り専用で開く（view ARCHIVE [contest problem [language]] [--run]）
+  clean        : cphがビルド・取得したdockerイメージのうち使っていないものを削除（clean --images --days 30）
 
 引数例:
   python3 src/main.py abc300 open a python
   python3 src/main.py abc300 t b pypy
   python3 src/main.py abc300 s c rust
 
+オプション:
+  --progress ndjson : 進捗イベントを1行1JSONでstderrに出力（stdoutは通常表示のまま）
+  --memory          : profileでメモリ使用量を計測（valgrind massif / heaptrack）
+  --cpu             : profileでフレームグラフ(SVG)を生成（perf / flamegraph / py-spy）
+  --case N          : profile・replay対象のケース（番号またはケース名）
+  --jobs N          : testの並列数（既定はmin(CPU数, ケース数)）
+  --budget 90s      : testの時間予算。サンプル→直近に失敗したケースの順に実行し、使い切ったら残りを打ち切る
+  --dir PATH        : testをワークスペースの外のディレクトリ（解答とテストケース。zipから展開した問題など）で実行する。PATHは解答ファイルでもよい
+  --watch           : testで解答とテストケースを監視し、変わるたびに実行し直す（実行中の変更はconfig.jsonのwatch.strategy: cancel/queue/skip。実験的な機能で、config.jsonの"experimental": ["daemon"]が必要）
+  --order MODE      : testのケースの実行順（failed-first（既定）/ natural / slowest-first、config.jsonのtest.order）
+  --set NAME        : 実行するテストセット（samples / stress / all、config.jsonのtest.setsで追加。小課題ごとのサンプルは group_1 など）
+  --compare MODE    : 今回のtestだけ比較方法を変える（exact / tokens / float:1e-6 / unordered / checker:PATH。+yesnoでYes/YES/yesを同じとみなす: tokens+yesno）
+  --accurate-timing : TLに近いケースを直列・コア固定で計測し直す
+  --pin-cpu 0-3     : ローカル実行時に解答プロセスを指定CPUに固定（対応環境のみ）
+  --nice N          : ローカル実行時の解答プロセスのnice値（負値は権限がある場合のみ有効）
+  --tl SEC          : runの実行時間制限（秒）
+  --ml MB           : runのメモリ制限（MB）
+  --input -         : run・remote-runの入力を標準入力（パイプ/ヒアドキュメント）から読む（ファイルパスも可）
+  --input-clipboard : runの入力をクリップボードから読む
+  --save-as NAME    : runに渡した入力をテストケースNAME.inとして保存
+  --pin             : prepareでジャッジの既定バージョンをconfig.jsonのtoolchainに書き込む（testのDockerイメージもそのバージョンになる。省略時は案内のみ）
+  --site NAME       : doctor / prepareで照合するサイト、import / archive sync-verdictsの取得元（既定: atcoder）、login --checkで見るサイト（既定: すべて）、remote-runで実行するジャッジ（既定: atcoder）
+  --user NAME       : importで履歴を取り込む・resultで成績を取得する・archive sync-verdictsで結果を取り直すユーザー名（result / archiveはconfig.jsonのresult.userでも指定できる）
+  --ub-check        : 失敗ケースをdebugビルドでも実行し、結果が変わるもの（UB・オーバーフロー疑い）を報告
+  --team            : initでチーム用ディレクトリ（members/ lib/ notebook/）を作成
+  --members a,b,c   : initで作成するメンバー名
+  --files a.py,b.rs : printでまとめるファイル（既定: lib配下すべて）
+  --output PATH     : printの出力先（既定: team/notebook/notebook.pdf）、case exportの書き出し先（既定: .cph/cases）、badgeの書き出し先（既定: badges）、report-bugのアーカイブ
+  --lib DIR         : verifyの対象ディレクトリ（既定: team/lib）
+  --force           : verifyで変更のないファイルも再検証する
+  --dry-run         : submitで提出用ファイルへの加工を差分表示し、提出はしない（cleanでは削除対象の表示のみ、case lintでは直さず指摘のみ、case rebindでは違いの表示のみ）
+  --flush           : submitでネットワーク不通のため保留した提出を古い順に送信
+  --images          : cleanでcph管理のdockerイメージを片付ける（config.jsonのimages.auto_clean_daysで自動化）
+  --service NAME    : shareの貼り付け先（gist / 0x0。既定: config.jsonのshare.service、0x0）
+  --failing         : shareで直近のtestで失敗したケースの入力・期待出力・実際の出力も貼る
+  --workspace       : report-bugで作業中の問題（contest_current、ビルド成果物を除く）も同梱する
+  --anonymize       : share・case exportでテストケースをconfig.jsonのtest.anonymizeの設定で伏せる（期待出力は参照解で作り直す）
+  --run             : viewでアーカイブの解答を一時的なワークスペースでテストする（自分の作業状態は変えない）
+  --add / --remove  : tagで付ける・外すタグ（カンマ区切り）
+  --difficulty N    : tagで記録する難易度
+  --output-limit SIZE : tagでこの問題のtestの1ケースの出力の上限（64MB など、0で上限なし。既定はconfig.jsonのtest.output_limit、64MB）
+  --checklist "A;B"  : tagでこの問題の提出前のチェックリストに足す項目（;区切り、defaultで消す）
+  --tag dp,graph    : searchで絞り込むタグ（すべてを含む問題）
+  --unsolved        : searchでAC済みの問題を除く
+  --min-difficulty N / --max-difficulty N : searchの難易度の範囲
+  --days N          : gc・clean --imagesの対象にする未更新・未使用の日数（既定: 30）、badgeでACを数える日数（既定: 7）
+  --poll SEC        : refreshで問題ページを確かめ続ける間隔（60s / 2m、Ctrl+Cで終了）。test --watchでは増えたサンプルを取り込んで実行し直す
+  --refresh         : openで取得済みのサンプルを取り直す（元のサンプルは.cph/backups/に残す）。statement --constraintsでは制約を読み直す
+  --constraints     : statementで問題文から読んだ制約の一覧を表示（testの冒頭にも表示。config.jsonのtest.show_constraints: falseで表示しない）
+  --yes, -y         : 確認せずに実行する（gcは既定で圧縮して退避。testの問題の取り違え・openの上書き・case rebind・submitのチェックリストの確認も省く）
+  --delete          : gc --yesで退避せずに削除する
+  --tool            : statsでcphのコマンド利用回数・テスト時間・キャッシュヒット率を表示
+  --csv FILE        : history exportの書き出し先（- で標準出力）
+  --results         : statsでresultで残したコンテストの成績（順位・パフォーマンス・レート増減）を表示
+  --threshold BYTES : case compressで圧縮する大きさ（既定: config.jsonのtest.compress_threshold、8MB）
+  --color MODE      : 色を付けるか（auto（既定、NO_COLOR / CLICOLORに従う）/ always / never）。テーマはconfig.jsonのcolor.theme
+  --debug-scrape    : openでojを使わず問題ページからサンプルを取り出し、HTML（.cph/scrape/）とどの取り出し方が使えたかを残す
+  --check           : loginで対話せずにログイン状態を確認する
+  --seed N          : testで解答に渡す乱数のシード（環境変数CPH_SEED。省略時は実行ごとに選び、.cph/runsに残す）。estimateでは入力の生成に使う
+  --max-n N         : estimateで見積もる制約の上限（200000 / 2e5。problem.jsonに残す）
+  --debug           : エラー時にヒントではなくトレースバックを表示し、実行したdocker/ojコマンドを標準エラーに表示
+
 引数は順不同・エイリアス可
   contest_name: abc300, arc100, agc001, ahc100...
   problem_name: a, b, c, d, e, f, g, ex
-  language_name: python, pypy, rust
+  language_name: python, pypy, rust（config.jsonのlanguagesでコマンドを書けば他の言語も使える）
+
+読み上げ向けの表示: config.jsonに "output": {"accessible": true}（TERM=dumbなら自動）で、
+  色・罫線・横並びの表を使わず、判定をPASS / FAILの言葉で1行ずつ表示する
 """)
 
+def report_error(e):
+    """エラー内容と、エラーの種類に応じたヒントを表示する"""
+    print(f"[エラー] {e}")
+    try:
+        extra = ConfigJsonManager().get_error_hints()
+    except Exception:
+        extra = {}
+    for line in HintRegistry(extra).format(e):
+        print(line)
+
+def configure_colors(options):
+    """
+    色の有無とテーマ、アクセシブル表示かどうかをここで一度だけ決める（各コマンドはcolor_theme.current()を使う）。
+    アクセシブル表示では、--colorで明示しない限り色を付けない
+    """
+    try:
+        manager = ConfigJsonManager()
+        config, output = manager.get_color(), manager.get_output()
+    except Exception:
+        config, output = {}, {}
+    accessible = output_mode.configure(output.get("accessible"))
+    mode = options.get("color") or ("never" if accessible else config.get("mode"))
+    color_theme.configure(mode, config.get("theme"))
+
+def configure_experimental():
+    """config.jsonのexperimentalで有効にした実験的な機能をここで一度だけ読み込む（未知の名前は警告する）"""
+    try:
+        names = ConfigJsonManager().get_experimental()
+    except Exception:
+        names = None
+    experimental.configure(names)
+
 def main():
     if any(arg in ("--help", "-h") for arg in sys.argv[1:]):
         print_help()
         return
 
     parser = CommandParser()
-    parser.parse(sys.argv[1:])
+    try:
+        parser.parse(sys.argv[1:])
+    except ValueError as e:
+        print(f"エラー: {e}")
+        print_help()
+        return
     args = parser.get_effective_args()
+    options = parser.get_options()
     command = args["command"]
+    try:
+        configure_colors(options)
+        configure_experimental()
+    except ValueError as e:
+        # 設定の誤りで何もしなかったので、スクリプトなどから失敗と分かるよう終了コードを1にする
+        print(f"エラー: {e}")
+        sys.exit(1)
+    # 前回異常終了したときに残った一時ディレクトリを掃除する（この実行の分は終了時に消える）
+    temp_space.current()
+    # 統計の記録・フックなど、処理の結果への反応をイベントの購読として登録する
+    event_subscribers.install()
+    # 古い構成のワークスペースを最新の版へ移行する（書き換える前のファイルは.cph/backupsに残す）
+    try:
+        workspace_manifest.ensure_current()
+    except CphError as e:
+        report_error(e)
+        sys.exit(1)
     contest_name = args["contest_name"]
     problem_name = args["problem_name"]
     language_name = args["language_name"]
     exec_mode = args["exec_mode"]
 
     # 不足要素があればエラー内容をprintして終了
-    if command == "login":
+    if command in ("login", "doctor", "prepare", "init", "print", "verify", "verify-env", "search", "import", "gc", "stats", "case", "clean", "status", "view", "fix", "badge", "history", "archive", "report-bug", "refresh", "replay", "statement") or (command == "submit" and options.get("flush")) or (command == "test" and options.get("dir")):
         missing = [k for k in ["command"] if args[k] is None]
+    elif command == "tag":
+        missing = [k for k in ["contest_name", "command", "problem_name"] if args[k] is None]
+    elif command == "result":
+        missing = [k for k in ["contest_name", "command"] if args[k] is None]
     else:
         missing = [k for k in ["contest_name", "command", "problem_name", "language_name"] if args[k] is None]
     if missing:
@@ -51,22 +214,116 @@ def main():
         print_help()
         return
 
-    executor = CommandExecutor(
-        file_manager=ContestFileManager(LocalFileOperator()),
-        exec_mode=exec_mode
-    )
-    import asyncio
-    if command == "open":
-        asyncio.run(executor.open(contest_name, problem_name, language_name))
-    elif command == "login":
-        asyncio.run(executor.execute(command, contest_name, problem_name, language_name))
-    elif command == "submit":
-        asyncio.run(executor.submit(contest_name, problem_name, language_name))
-    elif command == "test":
-        asyncio.run(executor.run_test(contest_name, problem_name, language_name))
-    else:
-        print("未対応のコマンドです\n")
-        print_help()
+    start = time.perf_counter()
+    ok = False
+    try:
+        executor = CommandExecutor(
+            file_manager=ContestFileManager(LocalFileOperator()),
+            exec_mode=exec_mode,
+            options=options
+        )
+        import asyncio
+        if command == "open":
+            # 言語が指定されていなければ、保管済みの解答から言語を決めさせる
+            asyncio.run(executor.open(contest_name, problem_name, language_name if parser.parsed["language_name"] else None))
+        elif command == "login":
+            code = asyncio.run(executor.execute(command, contest_name, problem_name, language_name))
+            if options.get("check") and code:
+                # シェルのプロンプトなどから使えるよう、ログイン状態を終了コードで返す
+                sys.exit(code)
+        elif command == "submit":
+            asyncio.run(executor.submit(contest_name, problem_name, language_name))
+        elif command == "test":
+            asyncio.run(executor.run_test(contest_name, problem_name, language_name))
+        elif command == "profile":
+            asyncio.run(executor.profile(contest_name, problem_name, language_name))
+        elif command == "run":
+            asyncio.run(executor.run(contest_name, problem_name, language_name))
+        elif command == "repl":
+            asyncio.run(executor.repl(contest_name, problem_name, language_name))
+        elif command == "doctor":
+            asyncio.run(executor.doctor(parser.parsed["language_name"]))
+        elif command == "prepare":
+            asyncio.run(executor.prepare(parser.parsed["language_name"]))
+        elif command == "init":
+            asyncio.run(executor.init())
+        elif command == "print":
+            asyncio.run(executor.print_notebook())
+        elif command == "verify":
+            asyncio.run(executor.verify())
+        elif command == "verify-env":
+            # 言語を指定したときだけその言語に絞る（system_info.jsonの言語では絞らない）
+            code = asyncio.run(executor.verify_env(parser.parsed["language_name"]))
+            if code:
+                sys.exit(code)
+        elif command == "tag":
+            asyncio.run(executor.tag(contest_name, problem_name))
+        elif command == "search":
+            asyncio.run(executor.search())
+        elif command == "import":
+            asyncio.run(executor.import_history())
+        elif command == "gc":
+            asyncio.run(executor.gc())
+        elif command == "stats":
+            asyncio.run(executor.stats())
+        elif command == "clean":
+            asyncio.run(executor.clean())
+        elif command == "status":
+            asyncio.run(executor.status())
+        elif command == "badge":
+            asyncio.run(executor.badge())
+        elif command == "history":
+            # system_info.jsonの値で補わず、指定された問題だけに絞る
+            asyncio.run(executor.history(parser.extra_args, parser.parsed["contest_name"], parser.parsed["problem_name"]))
+        elif command == "result":
+            asyncio.run(executor.result(contest_name))
+        elif command == "archive":
+            asyncio.run(executor.archive(parser.extra_args))
+        elif command == "switch":
+            asyncio.run(executor.switch(contest_name, problem_name, language_name, parser.extra_args))
+        elif command == "share":
+            asyncio.run(executor.share(contest_name, problem_name, language_name))
+        elif command == "explain":
+            asyncio.run(executor.explain(contest_name, problem_name, language_name, parser.extra_args))
+        elif command == "fix":
+            asyncio.run(executor.fix(parser.extra_args))
+        elif command == "report-bug":
+            asyncio.run(executor.report_bug())
+        elif command == "refresh":
+            asyncio.run(executor.refresh())
+        elif command == "remote-run":
+            asyncio.run(executor.remote_run(contest_name, problem_name, language_name))
+        elif command == "estimate":
+            asyncio.run(executor.estimate(contest_name, problem_name, language_name))
+        elif command == "replay":
+            asyncio.run(executor.replay(parser.extra_args))
+        elif command == "statement":
+            asyncio.run(executor.statement())
+        elif command == "view":
+            # system_info.jsonの値で補わず、指定された問題だけを対象にする
+            asyncio.run(executor.view(parser.extra_args, parser.parsed["contest_name"], parser.parsed["problem_name"], parser.parsed["language_name"]))
+        elif command == "case":
+            asyncio.run(executor.case(parser.extra_args))
+        else:
+            print("未対応のコマンドです\n")
+            print_help()
+        ok = True
+    except KeyboardInterrupt:
+        # 通信中のスレッドにも中断を伝え、トレースバックは出さない
+        cancellation.current().cancel()
+        print("\n中断しました")
+        sys.exit(130)
+    except Exception as e:
+        # report-bugで添付できるよう、失敗したコマンドの記録を残す
+        record_failure(sys.argv, e)
+        if options.get("debug"):
+            raise
+        report_error(e)
+        sys.exit(1)
+    finally:
+        # 有効な場合のみ、ローカルの~/.local/state/cph/stats.jsonに記録する
+        UsageStats().record_command(command, time.perf_counter() - start, ok=ok)
+
 
 if __name__ == "__main__":
     main() 
\ No newline at end of file
@@ -0,0 +1,33 @@
+import threading
+from contextlib import contextmanager
+
+# 同時に行うネットワーク操作（HTTPリクエスト・oj download/submit・docker pull）の上限
+DEFAULT_MAX_CONCURRENT = 2
+
+_lock = threading.Lock()
+_max_concurrent = DEFAULT_MAX_CONCURRENT
+_semaphore = threading.BoundedSemaphore(DEFAULT_MAX_CONCURRENT)
+
+def configure(max_concurrent=None):
+    """config.jsonのnetwork.max_concurrentで上限を設定する（プロセス全体で共有）"""
+    global _semaphore, _max_concurrent
+    value = int(max_concurrent) if max_concurrent else DEFAULT_MAX_CONCURRENT
+    if value < 1:
+        raise ValueError(f"network.max_concurrentは1以上を指定してください: {max_concurrent}")
+    with _lock:
+        if value != _max_concurrent:
+            _max_concurrent = value
+            _semaphore = threading.BoundedSemaphore(value)
+
+def get_max_concurrent():
+    return _max_concurrent
+
+@contextmanager
+def network_slot():
+    """ネットワーク操作の前後で囲み、同時実行数を上限以内に抑える"""
+    semaphore = _semaphore
+    semaphore.acquire()
+    try:
+        yield
+    finally:
+        semaphore.release()
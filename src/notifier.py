@@ -0,0 +1,142 @@
+import json
+import os
+import sys
+from abc import ABC, abstractmethod
+from src import events, http_client
+from src.error_hints import CphError
+from src.shell import CommandSpec, Shell
+
+# 通知コマンドの実行時間の上限（秒）
+DESKTOP_TIMEOUT = 10
+# イベントごとの既定の文面（config.jsonのnotify.messagesで変えられる。{contest}などはイベントの値）
+DEFAULT_MESSAGES = {
+    events.PROBLEM_OPENED: "{contest} {problem} を開きました",
+    events.TEST_RUN_COMPLETED: "test {contest} {problem}: {passed}/{total} AC",
+    events.SUBMISSION_SENT: "{contest} {problem} を提出しました",
+    events.SUBMISSION_ACCEPTED: "AC: {contest} {problem}",
+}
+NOTIFY_TITLE = "cph"
+
+class SafeValues(dict):
+    """文面に無い値の{name}はそのまま残す（イベントごとに値が違うため）"""
+    def __missing__(self, key):
+        return "{" + key + "}"
+
+def render_message(event, messages=None):
+    template = (messages or {}).get(event.name) or DEFAULT_MESSAGES.get(event.name, event.name)
+    return template.format_map(SafeValues({k: v for k, v in event.fields.items() if not isinstance(v, (list, dict))}))
+
+class Notifier(ABC):
+    """通知の送り先（デスクトップ・チャットのWebhookなど）"""
+    name = None
+
+    @abstractmethod
+    def send(self, title, message):
+        """通知を1件送る。送れなければ例外"""
+
+class DesktopNotifier(Notifier):
+    """デスクトップ通知（Linuxはnotify-send、macOSはosascript）"""
+    name = "desktop"
+
+    def __init__(self, shell=None, platform=None):
+        self.shell = shell or Shell()
+        self.platform = platform or sys.platform
+
+    def command(self, title, message):
+        if self.platform == "darwin":
+            script = f"display notification {json.dumps(message)} with title {json.dumps(title)}"
+            return ["osascript", "-e", script]
+        return ["notify-send", title, message]
+
+    def send(self, title, message):
+        result = self.shell.run(CommandSpec.from_argv(self.command(title, message), timeout=DESKTOP_TIMEOUT))
+        if not result.ok:
+            raise CphError(f"デスクトップ通知に失敗しました: {(result.stderr or '').strip() or result.returncode}", kind="notify_failed")
+
+class WebhookNotifier(Notifier):
+    """チャットのIncoming WebhookにJSONをPOSTする"""
+    def __init__(self, url):
+        self.url = url
+
+    @abstractmethod
+    def payload(self, title, message):
+        """POSTするJSONの中身"""
+
+    def send(self, title, message):
+        body = json.dumps(self.payload(title, message)).encode("utf-8")
+        http_client.post(self.url, body, headers={"Content-Type": "application/json"})
+
+class SlackNotifier(WebhookNotifier):
+    name = "slack"
+
+    def payload(self, title, message):
+        return {"text": f"*{title}* {message}"}
+
+class DiscordNotifier(WebhookNotifier):
+    name = "discord"
+
+    def payload(self, title, message):
+        return {"content": f"**{title}** {message}"}
+
+NOTIFIERS = {
+    DesktopNotifier.name: DesktopNotifier,
+    SlackNotifier.name: SlackNotifier,
+    DiscordNotifier.name: DiscordNotifier,
+}
+
+def create_notifier(name, spec=None):
+    """
+    チャンネルの定義から送り先を作る。typeを省略すればチャンネル名を種類とみなす。
+    WebhookのURLはconfig.jsonに書かず、url_envの環境変数から読むのがおすすめ（urlに直接書いてもよい）
+    例: {"type": "discord", "url_env": "CPH_DISCORD_WEBHOOK"}
+    """
+    spec = spec or {}
+    kind = spec.get("type", name)
+    notifier = NOTIFIERS.get(kind)
+    if notifier is None:
+        raise ValueError(f"未対応の通知の種類です: {kind}（{', '.join(NOTIFIERS)}）")
+    if not issubclass(notifier, WebhookNotifier):
+        return notifier()
+    url = spec.get("url") or (os.environ.get(spec["url_env"]) if spec.get("url_env") else None)
+    if not url:
+        where = f"環境変数 {spec['url_env']}" if spec.get("url_env") else "url / url_env"
+        raise CphError(f"通知チャンネル {name} のWebhookのURLがありません（{where}）", kind="notify_webhook_missing")
+    return notifier(url)
+
+def routed_channels(event_name, routes):
+    """イベントを送るチャンネル名（イベント名の指定のあとに"*"の指定を重複なく続ける）"""
+    names = []
+    for key in (event_name, events.ALL):
+        value = routes.get(key) or []
+        for name in [value] if isinstance(value, str) else value:
+            if name not in names:
+                names.append(name)
+    return names
+
+class NotificationRouter:
+    """
+    config.jsonのnotifyに従い、イベントをチャンネルに振り分けて通知する。
+    例: {"notify": {"channels": {"discord": {"url_env": "CPH_DISCORD_WEBHOOK"}, "desktop": {}},
+                    "routes": {"submission_accepted": ["discord", "desktop"], "test_run_completed": "desktop"},
+                    "messages": {"submission_accepted": "AC! {contest} {problem}"}}}
+    1つのチャンネルに送れなくても、ほかのチャンネルには送る。
+    """
+    def __init__(self, notify_config=None, factory=None):
+        self.config = notify_config or {}
+        self.factory = factory or create_notifier
+
+    def notify(self, event):
+        """送れたチャンネル名の一覧"""
+        channels = self.config.get("channels") or {}
+        sent = []
+        for name in routed_channels(event.name, self.config.get("routes") or {}):
+            if name not in channels:
+                print(f"[警告] 通知チャンネル {name} がnotify.channelsにありません")
+                continue
+            try:
+                self.factory(name, channels[name]).send(NOTIFY_TITLE, render_message(event, self.config.get("messages")))
+            except Exception as e:
+                print(f"[警告] 通知チャンネル {name} に送れませんでした: {e}")
+                continue
+            sent.append(name)
+        return sent
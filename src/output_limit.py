@@ -0,0 +1,50 @@
+import re
+
+# 1ケースの標準出力の上限（バイト）の既定。出力し続ける解答でディスクやメモリを埋めないように
+DEFAULT_OUTPUT_LIMIT = 64 * 1024 * 1024
+# 上限を超えたケースの判定
+OLE = "OLE"
+SIZE_UNITS = {"": 1, "B": 1, "KB": 1024, "MB": 1024 ** 2, "GB": 1024 ** 3}
+SIZE_PATTERN = re.compile(r"^(\d+)\s*([KMG]?B?)$", re.IGNORECASE)
+
+def parse_output_limit(value):
+    """"64MB" / "1048576" / 1048576 → バイト数。0（またはfalse）は上限なし"""
+    if value is False:
+        return 0
+    m = SIZE_PATTERN.match(str(value).strip())
+    if not m:
+        raise ValueError(f"出力の上限はバイト数か 64MB のような大きさで指定してください: {value}")
+    return int(m.group(1)) * SIZE_UNITS[m.group(2).upper()]
+
+class OutputLimit:
+    """
+    解答の標準出力をlimitバイトまでにする。上限の1バイト先まで読んだところでパイプを閉じるので、
+    書き続ける解答はSIGPIPE（またはBrokenPipeError）で止まる。終了コードは解答のものを返す。
+    headで切るだけなので、ローカルでもコンテナ内でも同じコマンドで済む。
+    config.jsonのtest.output_limit（既定64MB、0で上限なし）と、問題ごとの tag --output-limit で変える。
+    """
+    SCRIPT = ('n=$1; shift; exec 4>&1; '
+              'status=$( { { "$@" 3>&- 4>&-; echo $? >&3; } | head -c "$n" >&4; } 3>&1 ); '
+              'exit "${status:-1}"')
+
+    def __init__(self, limit=DEFAULT_OUTPUT_LIMIT):
+        self.limit = parse_output_limit(limit) if limit is not None else DEFAULT_OUTPUT_LIMIT
+
+    @classmethod
+    def from_config(cls, test_config, problem_limit=None):
+        """問題ごとの設定（problem.jsonのoutput_limit）があればそれを、無ければconfig.jsonのtest.output_limitを使う"""
+        if problem_limit is not None:
+            return cls(problem_limit)
+        return cls((test_config or {}).get("output_limit", DEFAULT_OUTPUT_LIMIT))
+
+    def wrap(self, cmd):
+        if not self.limit:
+            return list(cmd)
+        return ["sh", "-c", self.SCRIPT, "sh", str(self.limit + 1)] + list(cmd)
+
+    def exceeded(self, stdout):
+        return bool(self.limit) and len((stdout or "").encode("utf-8", errors="replace")) > self.limit
+
+    def truncate(self, stdout):
+        """表示・保存用に上限までに切る（途中で切れた文字は置き換える）"""
+        return (stdout or "").encode("utf-8", errors="replace")[:self.limit].decode("utf-8", errors="replace")
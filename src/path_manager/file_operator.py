@@ -2,8 +2,9 @@ from pathlib import Path
 from abc import ABC, abstractmethod
 from shutil import copy2
 import shutil
+from src.file_operator import atomic_write, AsyncFileOpsMixin
 
-class FileOperator(ABC):
+class FileOperator(AsyncFileOpsMixin, ABC):
     def __init__(self, base_dir=Path(".")):
         self.base_dir = Path(base_dir)
 
@@ -80,10 +81,7 @@ class LocalFileOperator(FileOperator):
         return path.exists()
 
     def create(self, path, content: str = ""):
-        path = self.resolve_path(path)
-        path.parent.mkdir(parents=True, exist_ok=True)
-        with open(path, "w", encoding="utf-8") as f:
-            f.write(content)
+        atomic_write(self.resolve_path(path), content)
 
     def copytree(self, src, dst):
         src_path = self.resolve_path(src)
@@ -0,0 +1,94 @@
+import json
+import re
+from pathlib import Path
+
+# 問題を保管するディレクトリと、言語ごとの解答ファイルの既定レイアウト
+DEFAULT_STOCKS_TEMPLATE = "contest_stocks/{contest}/{problem}/{language}"
+DEFAULT_SOURCE_FILES = {
+    "python": "main.py",
+    "pypy": "main.py",
+    "rust": "src/main.rs",
+}
+DEFAULT_SOURCE_FILE = "main.py"
+PLACEHOLDER = re.compile(r"\{(\w+)\}")
+
+def segment_regex(segment):
+    """"{contest}-{problem}" → 変数を名前付きグループにした正規表現"""
+    parts = PLACEHOLDER.split(segment)
+    return "".join(re.escape(p) if i % 2 == 0 else f"(?P<{p}>[^/]+)" for i, p in enumerate(parts))
+
+class PathResolver:
+    """
+    ディレクトリ構成をテンプレートで決める。contest_current/config.jsonのlayoutで上書きできる。
+    例: {"layout": {"stocks": "archive/{contest}/{problem}/{language}", "source": {"python": "solution.py"}}}
+    使える変数: {contest} {problem} {language}
+    """
+    def __init__(self, root, layout=None):
+        self.root = Path(str(root))
+        layout = layout or {}
+        self.stocks_template = layout.get("stocks", DEFAULT_STOCKS_TEMPLATE)
+        self.source_files = dict(DEFAULT_SOURCE_FILES)
+        self.source_files.update(layout.get("source", {}))
+        unknown = set(PLACEHOLDER.findall(self.stocks_template)) - {"contest", "problem", "language"}
+        if unknown:
+            raise ValueError(f"layout.stocksに未知の変数があります: {', '.join(sorted(unknown))}")
+
+    @classmethod
+    def from_config(cls, root):
+        """
+        config.jsonのlayoutを読み込む（ConfigJsonManagerはパス管理に依存するので直接読む）。
+        languagesで定義した言語の解答ファイル（source）も既定として加える
+        """
+        path = Path(str(root)) / "contest_current" / "config.json"
+        try:
+            data = json.loads(path.read_text(encoding="utf-8"))
+            layout, languages = data.get("layout"), data.get("languages")
+        except (OSError, ValueError, AttributeError):
+            layout, languages = None, None
+        layout = dict(layout) if isinstance(layout, dict) else {}
+        if isinstance(languages, dict):
+            sources = {name: spec["source"] for name, spec in languages.items() if isinstance(spec, dict) and spec.get("source")}
+            layout["source"] = {**sources, **layout.get("source", {})}
+        return cls(root, layout or None)
+
+    def render(self, template, **values):
+        """
+        テンプレートを展開する。値の無い変数を含むセグメント以降は切り捨てるので、
+        render(t, contest="abc") はそのコンテストのディレクトリを指す。
+        """
+        parts = []
+        for segment in template.split("/"):
+            names = PLACEHOLDER.findall(segment)
+            if any(not values.get(n) for n in names):
+                break
+            parts.append(PLACEHOLDER.sub(lambda m: str(values[m.group(1)]), segment))
+        return self.root.joinpath(*parts) if parts else self.root
+
+    def stocks_root(self):
+        return self.render(self.stocks_template)
+
+    def stock_dir(self, contest_name=None, problem_name=None, language_name=None, *paths):
+        p = self.render(self.stocks_template, contest=contest_name, problem=problem_name, language=language_name)
+        return p / Path(*paths) if paths else p
+
+    def problem_dirs(self):
+        """保管済みの問題ディレクトリを [(contest, problem, path)] で返す（テンプレートの{language}より前の部分で探す）"""
+        segments = []
+        for segment in self.stocks_template.split("/"):
+            if "{language}" in segment:
+                break
+            segments.append(segment)
+        if not any("{problem}" in s for s in segments) or not any("{contest}" in s for s in segments):
+            return []
+        glob_pattern = "/".join(PLACEHOLDER.sub("*", s) for s in segments)
+        regex = re.compile("/".join(segment_regex(s) for s in segments) + "$")
+        found = []
+        for path in sorted(self.root.glob(glob_pattern)):
+            m = regex.match(path.relative_to(self.root).as_posix())
+            if m and path.is_dir():
+                found.append((m.group("contest"), m.group("problem"), path))
+        return found
+
+    def source_file(self, language_name):
+        """言語ディレクトリからの解答ファイルの相対パス"""
+        return self.source_files.get(language_name, DEFAULT_SOURCE_FILE)
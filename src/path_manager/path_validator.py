@@ -0,0 +1,80 @@
+import os
+import tarfile
+import zipfile
+from pathlib import Path, PurePosixPath
+from src.error_hints import CphError
+
+class PathEscapeError(CphError):
+    """ワークスペースの外を指すパスへの書き込みを検出した"""
+    def __init__(self, message):
+        super().__init__(message, kind="path_escape")
+
+class PathValidator:
+    """
+    書き込み先がワークスペース（root）の内側にあるかを確認する。
+    シンボリックリンクを解決した実体パスで比べるので、リンク経由の脱出も検出できる。
+    """
+    def __init__(self, root):
+        self.root = Path(str(root)).resolve()
+
+    def is_within(self, path):
+        real = Path(str(path)).resolve(strict=False)
+        return real == self.root or self.root in real.parents
+
+    def ensure_within(self, path):
+        """rootの内側ならresolve済みのパスを返し、外側ならPathEscapeError"""
+        if not self.is_within(path):
+            raise PathEscapeError(f"ワークスペースの外への書き込みは許可されていません: {path}")
+        return Path(str(path)).resolve(strict=False)
+
+    def safe_join(self, base, name):
+        """
+        アーカイブのメンバー名やスクレイピングしたファイル名をbaseに結合する。
+        絶対パス・..・ドライブ指定を拒否し、結合結果もrootの内側にあることを確かめる。
+        """
+        parts = PurePosixPath(str(name).replace("\\", "/")).parts
+        if not parts or parts[0] == "/" or ".." in parts or ":" in parts[0]:
+            raise PathEscapeError(f"不正なファイル名です: {name}")
+        return self.ensure_within(Path(str(base)).joinpath(*parts))
+
+    def check_tree(self, directory):
+        """directory配下にroot外を指すシンボリックリンクがあればPathEscapeError"""
+        for current, dirs, names in os.walk(str(directory)):
+            for name in dirs + names:
+                path = os.path.join(current, name)
+                if os.path.islink(path) and not self.is_within(path):
+                    raise PathEscapeError(f"ワークスペースの外を指すシンボリックリンクがあります: {path}")
+
+    def extract_archive(self, archive_path, dest_dir):
+        """
+        zip/tarアーカイブをdest_dirに展開する。全メンバーを先に検査し、
+        1つでも外に出るものがあれば何も書かずに中断する。リンクやデバイスは展開しない。
+        """
+        dest_dir = self.ensure_within(dest_dir)
+        if zipfile.is_zipfile(str(archive_path)):
+            with zipfile.ZipFile(str(archive_path)) as zf:
+                members = [(m, self.safe_join(dest_dir, m.filename)) for m in zf.infolist()]
+                for member, target in members:
+                    if member.is_dir():
+                        target.mkdir(parents=True, exist_ok=True)
+                        continue
+                    target.parent.mkdir(parents=True, exist_ok=True)
+                    self.ensure_within(target)
+                    with zf.open(member) as src, open(target, "wb") as dst:
+                        dst.write(src.read())
+            return [target for _, target in members]
+        with tarfile.open(str(archive_path)) as tf:
+            members = []
+            for member in tf.getmembers():
+                if not (member.isfile() or member.isdir()):
+                    raise PathEscapeError(f"リンクや特殊ファイルを含むアーカイブは展開できません: {member.name}")
+                members.append((member, self.safe_join(dest_dir, member.name)))
+            for member, target in members:
+                if member.isdir():
+                    target.mkdir(parents=True, exist_ok=True)
+                    continue
+                target.parent.mkdir(parents=True, exist_ok=True)
+                self.ensure_within(target)
+                with tf.extractfile(member) as src, open(target, "wb") as dst:
+                    dst.write(src.read())
+            return [target for _, target in members]
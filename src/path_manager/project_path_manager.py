@@ -1,6 +1,7 @@
 import os
 from pathlib import Path
 from typing import Optional
+from src.path_manager.path_resolver import PathResolver
 
 class ProjectPathManager:
     """
@@ -14,21 +15,18 @@ class ProjectPathManager:
     def contest_current(self, *paths) -> Path:
         return self.root / "contest_current" / Path(*paths) if paths else self.root / "contest_current"
 
-    # contest_stocks
+    # contest_stocks（構成はconfig.jsonのlayout.stocksで変更できる）
     def contest_stocks(self, contest_name: Optional[str] = None, problem_name: Optional[str] = None, language_name: Optional[str] = None, *paths) -> Path:
-        parts = ["contest_stocks"]
-        if contest_name:
-            parts.append(contest_name)
-        if problem_name:
-            parts.append(problem_name)
-        if language_name:
-            parts.append(language_name)
-        p = self.root
-        for part in parts:
-            p = p / part
-        if paths:
-            p = p / Path(*paths)
-        return p
+        return self.resolver().stock_dir(contest_name, problem_name, language_name, *paths)
+
+    def stock_problem_dirs(self):
+        return self.resolver().problem_dirs()
+
+    def stocks_root(self) -> Path:
+        return self.resolver().stocks_root()
+
+    def resolver(self) -> PathResolver:
+        return PathResolver.from_config(self.root)
 
     # contest_env
     def contest_env(self, filename: str) -> Path:
@@ -50,8 +48,25 @@ class ProjectPathManager:
     def config_json(self) -> Path:
         return self.contest_current("config.json")
 
+    def sites_yaml(self) -> Path:
+        return self.root / "sites.yaml"
+
+    def badges_dir(self, *paths) -> Path:
+        return self.root / "badges" / Path(*paths) if paths else self.root / "badges"
+
     def test_dir(self) -> Path:
         return self.contest_current("test")
 
     def readme_md(self) -> Path:
-        return self.contest_current("README.md") 
\ No newline at end of file
+        return self.contest_current("README.md")
+
+    # .cph: cphが管理する状態・実行成果物の置き場
+    def cph_dir(self, *paths) -> Path:
+        return self.root / ".cph" / Path(*paths) if paths else self.root / ".cph"
+
+    def runs_dir(self, *paths) -> Path:
+        return self.cph_dir("runs", *paths)
+
+    # team: チーム戦（ICPC）用のメンバー別ディレクトリ・共有ライブラリ・ノートブック
+    def team_dir(self, *paths) -> Path:
+        return self.root / "team" / Path(*paths) if paths else self.root / "team" 
\ No newline at end of file
@@ -2,6 +2,8 @@ from pathlib import Path
 from typing import List, Tuple, Optional
 from src.path_manager.project_path_manager import ProjectPathManager
 from src.path_manager.volume_path_mapper import VolumePathMapper
+from src.path_manager.path_validator import PathValidator
+from src.path_manager.user_paths import UserPaths, migrate
 
 class UnifiedPathManager:
     """
@@ -15,12 +17,19 @@ class UnifiedPathManager:
             host_root = Path(project_root).resolve() if project_root else Path.cwd().resolve()
             mounts = [(host_root, Path(container_root))]
         self.volume_mapper = VolumePathMapper(mounts)
+        self.user_paths = UserPaths()
 
     # ProjectPathManagerのラップ
     def contest_current(self, *paths) -> Path:
         return self.project_path.contest_current(*paths)
     def contest_stocks(self, *args, **kwargs) -> Path:
         return self.project_path.contest_stocks(*args, **kwargs)
+    def stocks_root(self) -> Path:
+        return self.project_path.stocks_root()
+    def stock_problem_dirs(self):
+        return self.project_path.stock_problem_dirs()
+    def source_file(self, language_name) -> str:
+        return self.project_path.resolver().source_file(language_name)
     def contest_env(self, filename) -> Path:
         return self.project_path.contest_env(filename)
     def contest_template(self, *args, **kwargs) -> Path:
@@ -29,10 +38,35 @@ class UnifiedPathManager:
         return self.project_path.info_json()
     def config_json(self) -> Path:
         return self.project_path.config_json()
+    def sites_yaml(self) -> Path:
+        return self.project_path.sites_yaml()
+    def badges_dir(self, *paths) -> Path:
+        return self.project_path.badges_dir(*paths)
     def test_dir(self) -> Path:
         return self.project_path.test_dir()
     def readme_md(self) -> Path:
         return self.project_path.readme_md()
+    def cph_dir(self, *paths) -> Path:
+        return self.project_path.cph_dir(*paths)
+    def runs_dir(self, *paths) -> Path:
+        return self.project_path.runs_dir(*paths)
+    def team_dir(self, *paths) -> Path:
+        return self.project_path.team_dir(*paths)
+    # UserPathsのラップ（プロジェクトをまたいで共有するもの）
+    def cache_dir(self, *paths) -> Path:
+        return self.user_paths.cache_dir(*paths)
+    def state_dir(self, *paths) -> Path:
+        return self.user_paths.state_dir(*paths)
+    def user_config_dir(self, *paths) -> Path:
+        return self.user_paths.config_dir(*paths)
+    def shared_state(self, name, merge=None) -> Path:
+        """以前は.cph/{name}にあったマシン単位の記録。残っていればstateの置き場へ移す（既にあればmergeでまとめる）"""
+        return migrate(self.cph_dir(name), self.state_dir(name), merge)
+    def sites_yamls(self) -> List[Path]:
+        """ユーザー共通のsites.yamlとプロジェクトのsites.yaml（後のものがサイトごとに優先）"""
+        return [self.user_config_dir(self.sites_yaml().name), self.sites_yaml()]
+    def validator(self) -> PathValidator:
+        return PathValidator(self.project_path.root)
 
     # VolumePathMapperのラップ
     def to_container_path(self, host_path: Path) -> Optional[Path]:
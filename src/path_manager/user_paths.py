@@ -0,0 +1,72 @@
+import os
+import shutil
+import sys
+from pathlib import Path
+from typing import Optional
+from src.error_hints import CphError
+from src.file_lock import file_lock
+
+APP_NAME = "cph"
+# XDG Base Directoryの環境変数と、未設定のときのホームからの場所
+XDG_DIRS = {
+    "cache": ("XDG_CACHE_HOME", ".cache"),
+    "state": ("XDG_STATE_HOME", os.path.join(".local", "state")),
+    "config": ("XDG_CONFIG_HOME", ".config"),
+}
+
+class UserPaths:
+    """
+    プロジェクトをまたいで共有する、ユーザーごとの置き場（XDG Base Directory準拠）。
+    - cache（~/.cache/cph）: 消しても取り直せるもの（verifyで取得したテストケースなど）
+    - state（~/.local/state/cph）: 利用統計・dockerイメージの使用記録など、マシン単位で残したいもの
+    - config（~/.config/cph）: すべてのプロジェクトで使う設定（sites.yamlのクッキーなど）
+    プロジェクトの.cphには、その問題・解答に結びついたメタデータだけを置く。
+    """
+    def __init__(self, environ=None, home: Optional[str] = None):
+        self.environ = os.environ if environ is None else environ
+        self.home = Path(home) if home else Path.home()
+
+    def base(self, kind) -> Path:
+        env, default = XDG_DIRS[kind]
+        value = self.environ.get(env)
+        # XDGの仕様どおり、相対パスの値は無視する
+        root = Path(value) if value and os.path.isabs(value) else self.home / default
+        return root / APP_NAME
+
+    def cache_dir(self, *paths) -> Path:
+        return self.base("cache").joinpath(*paths)
+
+    def state_dir(self, *paths) -> Path:
+        return self.base("state").joinpath(*paths)
+
+    def config_dir(self, *paths) -> Path:
+        return self.base("config").joinpath(*paths)
+
+def migrate(old_path, new_path, merge=None):
+    """
+    以前の置き場（プロジェクトの.cphなど）にあるものを新しい置き場へ移し、新しいパスを返す。
+    複数のcphが同時に移そうとしても1つだけが移すようロックする。新しい置き場に既にあれば、
+    merge(old_path, new_path)で新しい方へまとめて古いものを消す。mergeが無ければ古いものには触らず、使われていないことを知らせる。
+    お知らせは標準エラーに出す（標準出力はコマンドの結果に使う）。
+    """
+    old_path, new_path = Path(str(old_path)), Path(str(new_path))
+    if not old_path.exists():
+        return new_path
+    try:
+        with file_lock(new_path):
+            if not old_path.exists():
+                return new_path
+            if not new_path.exists():
+                new_path.parent.mkdir(parents=True, exist_ok=True)
+                shutil.move(str(old_path), str(new_path))
+                print(f"[cph] {old_path} を {new_path} へ移しました", file=sys.stderr)
+            elif merge is not None:
+                merge(old_path, new_path)
+                old_path.unlink()
+                print(f"[cph] {old_path} を {new_path} にまとめました", file=sys.stderr)
+            else:
+                print(f"[cph] 警告: {new_path} が既にあるため、{old_path} は使われていません（不要なら削除してください）", file=sys.stderr)
+    except (OSError, ValueError, CphError):
+        # 移せなくても新しい置き場で作り直せばよい
+        pass
+    return new_path
@@ -0,0 +1,110 @@
+import threading
+from src import cancellation
+from src.error_hints import CphError
+
+# 実行中に次の実行を求められたときの扱い（config.jsonのwatch.strategy）
+# cancel: 実行中のものを中断して新しいものを実行する / queue: 終わるのを待ってから実行する / skip: 無視する
+STRATEGIES = ("cancel", "queue", "skip")
+DEFAULT_STRATEGY = "cancel"
+
+class RunCoordinator:
+    """
+    watchなどで繰り返し起こる実行（test）が重ならないようにする。
+    実行は1つのワーカースレッドで順に行うので、同時に動くのは常に1つだけ。
+    待っている実行は1つにまとめる（何度変更があっても、次に行うのは最新の1回）。
+    cancelでの中断は各ケースの前にcheckするトークンで行うため、実行中のケース（コンテナでの実行）は最後まで待ち、
+    一時ディレクトリの後片付けやコンテナの状態が次の実行と混ざらない。
+    中断した実行は結果の記録（.cph/runs・キャッシュ）の前に止まるので、古い結果は残らない。
+    """
+    def __init__(self, run, strategy=None):
+        strategy = strategy or DEFAULT_STRATEGY
+        if strategy not in STRATEGIES:
+            raise ValueError(f"watch.strategyは {', '.join(STRATEGIES)} のどれかを指定してください: {strategy}")
+        self.run = run
+        self.strategy = strategy
+        self.condition = threading.Condition()
+        # 待っている実行があるか（とその理由）
+        self.requested = False
+        self.reason = None
+        self.current = None
+        self.worker = None
+        self.closed = False
+        # 実行ごとの(理由, 結果)。結果はdone / cancelled / failed
+        self.history = []
+
+    @property
+    def busy(self):
+        return self.worker is not None
+
+    def trigger(self, reason=None):
+        """
+        実行を求める。started（すぐ始めた）/ queued（待たせた）/ cancelled（実行中のものを中断して待たせた）/
+        skipped（無視した）/ closed のどれかを返す
+        """
+        with self.condition:
+            if self.closed:
+                return "closed"
+            if not self.busy:
+                self.requested, self.reason = True, reason
+                self.worker = threading.Thread(target=self.work, name="cph-run-coordinator", daemon=True)
+                self.worker.start()
+                return "started"
+            if self.strategy == "skip":
+                return "skipped"
+            self.requested, self.reason = True, reason
+            if self.strategy == "cancel" and self.current is not None and not self.current.cancelled:
+                self.current.cancel()
+                return "cancelled"
+            return "queued"
+
+    def work(self):
+        while True:
+            with self.condition:
+                if self.closed or not self.requested:
+                    self.worker = None
+                    self.condition.notify_all()
+                    return
+                reason, self.requested = self.reason, False
+                token = cancellation.CancellationToken()
+                self.current = token
+            outcome = self.run_one(reason, token)
+            with self.condition:
+                self.current = None
+                self.history.append((reason, outcome))
+
+    def run_one(self, reason, token):
+        try:
+            with cancellation.operation(token=token):
+                self.run(reason)
+        except CphError as e:
+            if e.kind == cancellation.CANCELLED:
+                print("[watch] 実行中のtestを中断しました")
+                return "cancelled"
+            print(f"[watch] エラー: {e}")
+            return "failed"
+        except Exception as e:
+            # watchは続ける（次の変更で直っているかもしれない）
+            print(f"[watch] エラー: {e}")
+            return "failed"
+        return "done"
+
+    def wait_idle(self, timeout=None):
+        """実行中・待っている実行が無くなるまで待つ。timeout秒で終わらなければFalse"""
+        with self.condition:
+            return self.condition.wait_for(lambda: not self.busy, timeout)
+
+    def drain(self, timeout=None):
+        """新しい実行を受け付けず、待っている実行を捨て、実行中のものは最後まで待つ（終了の手順で使う）"""
+        with self.condition:
+            self.closed = True
+            self.requested = False
+        return self.wait_idle(timeout)
+
+    def close(self, timeout=None):
+        """待っている実行を捨て、実行中のものを中断して終わるのを待つ"""
+        with self.condition:
+            self.closed = True
+            self.requested = False
+            if self.current is not None:
+                self.current.cancel()
+        return self.wait_idle(timeout)
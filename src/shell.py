@@ -0,0 +1,182 @@
+import os
+import subprocess
+import threading
+import time
+from datetime import datetime
+from typing import IO, Callable, Dict, List, Optional, Union
+from src.execution_client.types import ExecutionResult
+from src import command_log
+
+# タイムアウトで強制終了したときのreturncode（シェルの慣習に合わせる）
+TIMEOUT_RETURNCODE = 124
+INTERRUPTED_RETURNCODE = 130
+
+class CommandSpec:
+    """
+    外部コマンドの実行内容。envは現在の環境変数に追加・上書きする分だけを書く（値がNoneなら削除）。
+    timeoutは秒（Noneなら無制限）、stdinは標準入力に渡す文字列か、読み出し可能なファイル（展開中のパイプなど）。
+    """
+    def __init__(self, program: str, args: Optional[List[str]] = None, cwd: Optional[str] = None, env: Optional[Dict[str, Optional[str]]] = None, timeout: Optional[float] = None, stdin: Optional[Union[str, IO]] = None):
+        self.program = program
+        self.args = list(args or [])
+        self.cwd = cwd
+        self.env = env
+        self.timeout = timeout
+        self.stdin = stdin
+
+    @classmethod
+    def from_argv(cls, argv: List[str], **kwargs):
+        return cls(argv[0], list(argv[1:]), **kwargs)
+
+    def argv(self) -> List[str]:
+        return [self.program] + self.args
+
+    def stdin_stream(self) -> bool:
+        return is_stream(self.stdin)
+
+    def build_env(self) -> Optional[Dict[str, str]]:
+        if self.env is None:
+            return None
+        env = os.environ.copy()
+        for k, v in self.env.items():
+            if v is None:
+                env.pop(k, None)
+            else:
+                env[k] = str(v)
+        return env
+
+    def __repr__(self):
+        return f"CommandSpec({' '.join(map(str, self.argv()))!r}, cwd={self.cwd!r}, timeout={self.timeout!r})"
+
+class ShellResult(ExecutionResult):
+    def __init__(self, argv: List[str], returncode: int, stdout: str = "", stderr: str = "", timed_out: bool = False):
+        super().__init__(returncode, stdout, stderr)
+        self.argv = argv
+        self.timed_out = timed_out
+
+    @property
+    def ok(self) -> bool:
+        return self.returncode == 0 and not self.timed_out
+
+    def to_completed_process(self) -> subprocess.CompletedProcess:
+        return subprocess.CompletedProcess(self.argv, self.returncode, self.stdout, self.stderr)
+
+class Started:
+    """コマンドを始めた時刻。終わったらdocker / ojのコマンドだけをcommand_logに記録する"""
+    def __init__(self):
+        self.at = datetime.now()
+        self.perf = time.perf_counter()
+
+    def record(self, spec: CommandSpec, returncode: int, timed_out: bool = False):
+        if command_log.should_log(spec.argv()):
+            command_log.get_log().record(spec.argv(), self.at, time.perf_counter() - self.perf, returncode, timed_out=timed_out, cwd=spec.cwd)
+
+class ShellHandle:
+    """
+    実行中のコマンド。stdout・stderrを別々のスレッドで1行ずつ読み、コールバックに渡しながら溜める。
+    wait()でtimeoutまで待ち、超えたら強制終了してShellResultを返す。
+    """
+    def __init__(self, spec: CommandSpec, on_stdout: Optional[Callable[[str], None]] = None, on_stderr: Optional[Callable[[str], None]] = None):
+        self.spec = spec
+        self.started = Started()
+        self.proc = subprocess.Popen(
+            spec.argv(),
+            stdin=spec.stdin if spec.stdin_stream() else subprocess.PIPE if spec.stdin is not None else None,
+            stdout=subprocess.PIPE,
+            stderr=subprocess.PIPE,
+            text=True,
+            errors="replace",
+            bufsize=1,
+            cwd=spec.cwd,
+            env=spec.build_env(),
+        )
+        self.stdout_lines = []
+        self.stderr_lines = []
+        self.threads = [
+            self._start(self._read, self.proc.stdout, self.stdout_lines, on_stdout),
+            self._start(self._read, self.proc.stderr, self.stderr_lines, on_stderr),
+        ]
+        if spec.stdin is not None and not spec.stdin_stream():
+            # 大きな入力でパイプが詰まらないよう、書き込みも別スレッドで行う
+            self.threads.append(self._start(self._write, self.proc.stdin, spec.stdin))
+
+    @staticmethod
+    def _start(target, *args):
+        t = threading.Thread(target=target, args=args, daemon=True)
+        t.start()
+        return t
+
+    @staticmethod
+    def _read(stream, lines, callback):
+        for line in iter(stream.readline, ""):
+            lines.append(line)
+            if callback:
+                callback(line)
+        stream.close()
+
+    @staticmethod
+    def _write(stream, data):
+        try:
+            stream.write(data)
+            stream.close()
+        except (BrokenPipeError, OSError):
+            pass
+
+    def kill(self):
+        if self.proc.poll() is None:
+            self.proc.kill()
+
+    def wait(self, timeout: Optional[float] = None) -> ShellResult:
+        timeout = self.spec.timeout if timeout is None else timeout
+        timed_out = False
+        try:
+            self.proc.wait(timeout=timeout)
+        except subprocess.TimeoutExpired:
+            timed_out = True
+            self.kill()
+            self.proc.wait()
+        for t in self.threads:
+            t.join()
+        returncode = TIMEOUT_RETURNCODE if timed_out else self.proc.returncode
+        self.started.record(self.spec, returncode, timed_out)
+        return ShellResult(self.spec.argv(), returncode, "".join(self.stdout_lines), "".join(self.stderr_lines), timed_out=timed_out)
+
+class Shell:
+    """外部コマンドの実行をまとめる。テストではこのクラスを差し替えて実行内容を記録できる"""
+    def run(self, spec: CommandSpec) -> ShellResult:
+        """終了まで待ち、stdout・stderrを取り込んで返す。タイムアウトは例外ではなくtimed_outで知らせる"""
+        # ファイルのstdinはそのまま子プロセスにつなぎ、メモリに読み込まない
+        stdin = {"stdin": spec.stdin} if spec.stdin_stream() else {"input": spec.stdin}
+        started = Started()
+        try:
+            result = subprocess.run(spec.argv(), capture_output=True, text=True, errors="replace", cwd=spec.cwd, env=spec.build_env(), timeout=spec.timeout, **stdin)
+        except subprocess.TimeoutExpired as e:
+            started.record(spec, TIMEOUT_RETURNCODE, True)
+            return ShellResult(spec.argv(), TIMEOUT_RETURNCODE, decode(e.stdout), decode(e.stderr), timed_out=True)
+        started.record(spec, result.returncode)
+        return ShellResult(spec.argv(), result.returncode, result.stdout, result.stderr)
+
+    def spawn(self, spec: CommandSpec, on_stdout: Optional[Callable[[str], None]] = None, on_stderr: Optional[Callable[[str], None]] = None) -> ShellHandle:
+        """終了を待たずに実行を始め、出力を行ごとにコールバックへ流す"""
+        return ShellHandle(spec, on_stdout=on_stdout, on_stderr=on_stderr)
+
+    def attach(self, spec: CommandSpec) -> int:
+        """標準入出力を端末につないだまま実行し、終了コードを返す（Ctrl+Cは130）"""
+        started = Started()
+        try:
+            returncode = subprocess.run(spec.argv(), cwd=spec.cwd, env=spec.build_env(), timeout=spec.timeout).returncode
+        except subprocess.TimeoutExpired:
+            started.record(spec, TIMEOUT_RETURNCODE, True)
+            return TIMEOUT_RETURNCODE
+        except KeyboardInterrupt:
+            returncode = INTERRUPTED_RETURNCODE
+        started.record(spec, returncode)
+        return returncode
+
+def is_stream(value) -> bool:
+    return value is not None and not isinstance(value, (str, bytes)) and hasattr(value, "read")
+
+def decode(output) -> str:
+    if output is None:
+        return ""
+    return output.decode("utf-8", errors="replace") if isinstance(output, bytes) else output
@@ -0,0 +1,105 @@
+import os
+import signal
+import threading
+
+# config.jsonのshutdown.grace_seconds。SIGTERMを受けてから後片付けが終わるのを待つ秒数
+DEFAULT_GRACE_SECONDS = 10.0
+# 猶予を過ぎて強制終了するときの終了コード（128 + SIGTERM）
+FORCED_EXIT_CODE = 143
+
+class GracefulShutdown:
+    """
+    watchのように常駐するモードの終了手順。SIGTERM（Ctrl+Cと同じく終了の合図）を受けたら、
+    登録した手順（実行中のtestを終わらせる → 状態・履歴を保存する → コンテナを止める → ロックを放す）を順に行う。
+    手順の途中で止まってもgrace秒で見張り（watchdog）が残りの後片付け（force=Trueの手順）だけを行って強制終了する。
+    各手順のcheckは後片付けの確認（残っているものの一覧）で、残っていれば警告する。
+    """
+    def __init__(self, grace_seconds=None, exit=os._exit):
+        grace = DEFAULT_GRACE_SECONDS if grace_seconds is None else float(grace_seconds)
+        if grace <= 0:
+            raise ValueError(f"shutdown.grace_secondsは正の数を指定してください: {grace_seconds}")
+        self.grace = grace
+        self.exit = exit
+        self.steps = []
+        self.requested = threading.Event()
+        self.lock = threading.Lock()
+        # 終わった手順の名前（watchdogは終わっていない手順だけを行う）
+        self.completed = []
+        self.started = False
+        self.forced = False
+        self.previous = None
+
+    @classmethod
+    def from_config(cls, config, exit=os._exit):
+        return cls((config or {}).get("grace_seconds"), exit=exit)
+
+    def add(self, name, func, force=False, check=None):
+        """手順を足す（足した順に行う）。forceなら猶予を過ぎたときにも行う。checkは残っているものの一覧を返す"""
+        self.steps.append((name, func, force, check))
+        return self
+
+    def request(self, signum=None, frame=None):
+        """終了を求める（SIGTERMのハンドラ）。ループの側がrequestedを見て抜け、run()を呼ぶ"""
+        if not self.requested.is_set():
+            print("\n[shutdown] 終了の要求を受けました。実行中のtestが終わるのを待ってから終了します")
+        self.requested.set()
+
+    def install(self):
+        """SIGTERMのハンドラを入れる（メインスレッド以外では何もしない）"""
+        if threading.current_thread() is threading.main_thread() and hasattr(signal, "SIGTERM"):
+            self.previous = signal.signal(signal.SIGTERM, self.request)
+        return self
+
+    def uninstall(self):
+        if self.previous is not None:
+            signal.signal(signal.SIGTERM, self.previous)
+            self.previous = None
+
+    def __enter__(self):
+        return self.install()
+
+    def __exit__(self, exc_type, exc, tb):
+        self.uninstall()
+        return False
+
+    def run_step(self, name, func):
+        try:
+            func()
+        except Exception as e:
+            # 1つの手順が失敗しても残りの後片付けは続ける
+            print(f"[shutdown] {name}に失敗しました: {e}")
+        with self.lock:
+            self.completed.append(name)
+
+    def run(self):
+        """手順を順に行い、後片付けの確認で残っていたもの（手順名 → 一覧）を返す。grace秒を過ぎるとforce()"""
+        with self.lock:
+            if self.started:
+                return {}
+            self.started = True
+        watchdog = threading.Timer(self.grace, self.force)
+        watchdog.daemon = True
+        watchdog.start()
+        try:
+            for name, func, _, _ in self.steps:
+                self.run_step(name, func)
+        finally:
+            watchdog.cancel()
+        leftovers = {}
+        for name, _, _, check in self.steps:
+            remaining = list(check()) if check else []
+            if remaining:
+                leftovers[name] = remaining
+                print(f"[shutdown] {name}の後に残っています: {', '.join(map(str, remaining))}")
+        return leftovers
+
+    def force(self):
+        """猶予を過ぎたとき（watchdog）: 終わっていないforceの手順だけを行って強制終了する"""
+        self.forced = True
+        print(f"[shutdown] {self.grace:g}秒で終わらなかったため、後片付けだけを行って強制終了します")
+        for name, func, force, _ in self.steps:
+            with self.lock:
+                done = name in self.completed
+            if force and not done:
+                self.run_step(name, func)
+        self.exit(FORCED_EXIT_CODE)
@@ -0,0 +1,126 @@
+import http.cookiejar
+import os
+import re
+import urllib.parse
+from src.error_hints import CphError
+
+# プロジェクト直下（すべてのプロジェクトで使うものは~/.config/cph/）に置く、サイトごとの設定
+SITES_FILE = "sites.yaml"
+# hostsを書かなかったときに、サイト名から対応付けるホスト
+SITE_HOSTS = {
+    "atcoder": ["atcoder.jp", "kenkoooo.com"],
+    "codeforces": ["codeforces.com"],
+    "yukicoder": ["yukicoder.me"],
+}
+# ojがloginで書くcookie.jar（ホスト側）
+OJ_COOKIE_JAR = os.path.expanduser("~/.local/share/online-judge-tools/cookie.jar")
+# ${NAME} は環境変数に置き換える（トークンなどをファイルに書かないため）
+ENV_REF = re.compile(r"\$\{(\w+)\}")
+
+def expand_env(value, environ=None):
+    environ = os.environ if environ is None else environ
+
+    def replace(m):
+        if m.group(1) not in environ:
+            raise CphError(f"{SITES_FILE}で参照している環境変数 {m.group(1)} が設定されていません", kind="site_env_missing")
+        return environ[m.group(1)]
+    return ENV_REF.sub(replace, str(value))
+
+def load_sites_file(path):
+    try:
+        import yaml
+    except ImportError:
+        raise CphError(f"{SITES_FILE}を読むにはPyYAMLが必要です", kind="yaml_missing")
+    with open(path, "r", encoding="utf-8") as f:
+        data = yaml.safe_load(f) or {}
+    if not isinstance(data, dict) or not all(isinstance(v, dict) for v in data.values()):
+        raise ValueError(f"{path}: サイト名ごとにheaders / cookiesを書いてください")
+    return data
+
+class SiteConfig:
+    """
+    sites.yaml に書いたサイトごとのHTTPヘッダーとクッキー。http_clientが送るリクエストに、URLのホストで選んで足す。
+    oj（download / submit）にはクッキーだけをcookie.jarに足して渡す（ojはヘッダーを足せない）。
+    例:
+        codeforces:
+          headers:
+            User-Agent: my-team-bot/1.0
+            Authorization: "Bearer ${CF_TOKEN}"   # ${NAME}は環境変数
+          cookies:
+            JSESSIONID: ${CF_SESSION}
+        myjudge:
+          hosts: [judge.example.com]            # 既知のサイト以外はhostsで対応付ける
+          headers: {X-Api-Key: "${MYJUDGE_KEY}"}
+    環境変数はリクエストのときに展開するので、使わないサイトの変数は未設定でもよい。
+    """
+    def __init__(self, sites=None, environ=None):
+        self.sites = sites or {}
+        self.environ = environ
+
+    @classmethod
+    def load(cls, path, environ=None):
+        if not os.path.isfile(str(path)):
+            return cls(environ=environ)
+        return cls(load_sites_file(str(path)), environ)
+
+    @classmethod
+    def load_layered(cls, paths, environ=None):
+        """~/.config/cph/sites.yamlとプロジェクトのsites.yamlのように重ねて読む（後のファイルがサイト単位で優先）"""
+        sites = {}
+        for path in paths:
+            if os.path.isfile(str(path)):
+                sites.update(load_sites_file(str(path)))
+        return cls(sites, environ)
+
+    def hosts(self, name):
+        return self.sites[name].get("hosts") or SITE_HOSTS.get(name, [])
+
+    def site_for(self, url):
+        host = (urllib.parse.urlsplit(url).hostname or "").lower()
+        for name in self.sites:
+            if any(host == h or host.endswith("." + h) for h in self.hosts(name)):
+                return name
+        return None
+
+    def headers_for(self, url):
+        """urlのサイトに足すヘッダー。クッキーはCookieヘッダーにまとめる"""
+        name = self.site_for(url)
+        if name is None:
+            return {}
+        site = self.sites[name]
+        headers = {k: expand_env(v, self.environ) for k, v in (site.get("headers") or {}).items()}
+        cookies = site.get("cookies") or {}
+        if cookies:
+            headers["Cookie"] = "; ".join(f"{k}={expand_env(v, self.environ)}" for k, v in cookies.items())
+        return headers
+
+    def has_cookies(self):
+        return any(site.get("cookies") for site in self.sites.values())
+
+    def write_cookie_jar(self, path, base=None):
+        """
+        ojの--cookieに渡すcookie.jar（LWP形式）をpathに書く。base（ojのloginで書いたもの）を写してから、
+        sites.yamlのクッキーを各サイトのホストに足す（同じ名前はsites.yamlを優先）。baseは書き換えない
+        """
+        jar = http.cookiejar.LWPCookieJar(str(path))
+        if base and os.path.isfile(str(base)):
+            try:
+                jar.load(str(base), ignore_discard=True, ignore_expires=True)
+            except (OSError, http.cookiejar.LoadError):
+                pass
+        for name, site in self.sites.items():
+            for key, value in (site.get("cookies") or {}).items():
+                value = expand_env(value, self.environ)
+                for host in self.hosts(name):
+                    jar.set_cookie(site_cookie(host, key, value))
+        jar.save(ignore_discard=True, ignore_expires=True)
+        # セッションのクッキーなので本人だけが読めるようにする
+        os.chmod(str(path), 0o600)
+        return str(path)
+
+def site_cookie(host, name, value):
+    """hostとそのサブドメインに送るクッキー"""
+    return http.cookiejar.Cookie(
+        version=0, name=name, value=value, port=None, port_specified=False,
+        domain=f".{host}", domain_specified=True, domain_initial_dot=True, path="/", path_specified=True,
+        secure=False, expires=None, discard=False, comment=None, comment_url=None, rest={})
@@ -0,0 +1,116 @@
+import atexit
+import getpass
+import os
+import shutil
+import tempfile
+import threading
+import time
+from contextlib import contextmanager
+from pathlib import Path
+
+# セッションのディレクトリ名（cph-{pid}-{開始時刻}）
+SESSION_PREFIX = "cph-"
+# dockerのコンテナから見える必要があるもの（提出用ファイル・runの入力）の置き場。.tempはコンテナの/workspace/.tempにマウントされている
+CONTAINER_BASE = os.path.join(".temp", "sessions")
+
+def default_base():
+    """システムの一時ディレクトリの下の、ユーザーごとのディレクトリ（他のユーザーのcphと混ざらないように）"""
+    try:
+        user = getpass.getuser()
+    except Exception:
+        user = str(os.getuid()) if hasattr(os, "getuid") else "user"
+    return Path(tempfile.gettempdir()) / f"cph-{user}"
+
+def session_pid(name):
+    """"cph-1234-1700000000" → 1234。セッションのディレクトリでなければNone"""
+    if not name.startswith(SESSION_PREFIX):
+        return None
+    pid = name[len(SESSION_PREFIX):].split("-", 1)[0]
+    return int(pid) if pid.isdigit() else None
+
+def pid_alive(pid):
+    try:
+        os.kill(pid, 0)
+    except ProcessLookupError:
+        return False
+    except (PermissionError, OSError):
+        # 他のユーザーのプロセスなど、存在はしている
+        return True
+    return True
+
+class TempSpace:
+    """
+    1回のcphの実行（プロセス）だけで使う一時ディレクトリ。{base}/cph-{pid}-{開始時刻}に作り、終了時に消す。
+    異常終了で残ったもの（作ったプロセスがもう無いもの）は、次にcphを起動したときにsweepで消す。
+    ワークスペースに一時ファイルを散らかさないため、既定の置き場はシステムの一時ディレクトリ。
+    """
+    def __init__(self, base=None, pid=None, now=None):
+        self.base = Path(str(base)) if base else default_base()
+        self.pid = pid or os.getpid()
+        self.path = self.base / f"{SESSION_PREFIX}{self.pid}-{int(now if now is not None else time.time())}"
+
+    def dir(self, *parts):
+        """セッションの下のディレクトリ（無ければ作る）"""
+        path = self.path.joinpath(*parts)
+        path.mkdir(parents=True, exist_ok=True)
+        return path
+
+    def file(self, *parts):
+        """セッションの下のファイルのパス（親ディレクトリは作る）"""
+        path = self.path.joinpath(*parts)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        return path
+
+    @contextmanager
+    def scoped(self, prefix="tmp-"):
+        """withの間だけ使うディレクトリ（tempfile.TemporaryDirectoryの代わり）"""
+        path = Path(tempfile.mkdtemp(prefix=prefix, dir=str(self.dir())))
+        try:
+            yield path
+        finally:
+            shutil.rmtree(path, ignore_errors=True)
+
+    def cleanup(self):
+        shutil.rmtree(self.path, ignore_errors=True)
+
+    def sweep(self):
+        """終了したプロセスのセッション（異常終了で残ったもの）を消し、消したパスを返す"""
+        if not self.base.is_dir():
+            return []
+        removed = []
+        for entry in sorted(self.base.iterdir()):
+            pid = session_pid(entry.name)
+            if pid is None or pid == self.pid or pid_alive(pid):
+                continue
+            shutil.rmtree(entry, ignore_errors=True)
+            removed.append(entry)
+        return removed
+
+# 置き場ごとのセッション（プロセスの中で1つずつ）
+_spaces = {}
+_lock = threading.Lock()
+
+def current(base=None):
+    """
+    このプロセスのセッション。初めて使うときに前回までの残りを掃除し、終了時に消すよう登録する。
+    baseを省くとシステムの一時ディレクトリ
+    """
+    key = os.path.abspath(str(base)) if base else None
+    with _lock:
+        space = _spaces.get(key)
+        if space is None:
+            space = _spaces[key] = TempSpace(key)
+            space.sweep()
+            atexit.register(space.cleanup)
+    return space
+
+def cleanup_all():
+    """このプロセスのセッションをすべて消す（atexitが動かない強制終了の前に呼ぶ）"""
+    with _lock:
+        spaces = list(_spaces.values())
+    for space in spaces:
+        space.cleanup()
+
+def for_env(test_env):
+    """実行環境に合わせたセッション。dockerのコンテナで読むファイルは、コンテナから見える.temp/sessionsに置く"""
+    return current(CONTAINER_BASE if getattr(test_env, "uses_containers", True) else None)
@@ -0,0 +1,119 @@
+import base64
+import json
+import os
+import urllib.parse
+from contextlib import contextmanager
+from pathlib import Path
+from src import http_client
+from src.error_hints import CphError
+from src.file_operator import atomic_write_json
+
+# 1にするとカセットを録り直す（実際に通信して上書きする）
+RECORD_ENV = "CPH_RECORD_CASSETTES"
+# 録音するレスポンスヘッダー（Cookieなどの秘密を残さないよう最小限にする）
+KEPT_RESPONSE_HEADERS = {"content-type"}
+
+def normalize_url(url):
+    """クエリパラメータの順序に依存せず照合できるよう、キー順に並べ替える"""
+    parts = urllib.parse.urlsplit(url)
+    query = urllib.parse.urlencode(sorted(urllib.parse.parse_qsl(parts.query, keep_blank_values=True)))
+    return urllib.parse.urlunsplit((parts.scheme, parts.netloc, parts.path, query, ""))
+
+def encode_body(body):
+    try:
+        return {"body": body.decode("utf-8")}
+    except UnicodeDecodeError:
+        return {"body_base64": base64.b64encode(body).decode("ascii")}
+
+def decode_body(response):
+    if "body_base64" in response:
+        return base64.b64decode(response["body_base64"])
+    return response.get("body", "").encode("utf-8")
+
+class StaticTransport:
+    """
+    URLごとに決まったレスポンスを返す模擬サイト。routesは {url: 本文} か {url: (status, 本文)}。
+    本文がdict/listならJSONにする。登録の無いURLは404。onlineをFalseにするとオフラインを模擬する。
+    """
+    def __init__(self, routes=None, online=True):
+        self.routes = {normalize_url(url): value for url, value in (routes or {}).items()}
+        self.requests = []
+        self.online = online
+
+    def reachable(self, url, timeout=None):
+        return self.online
+
+    def request(self, method, url, headers=None, body=None, timeout=None):
+        self.requests.append((method, url))
+        if not self.online:
+            raise OSError("network is unreachable")
+        value = self.routes.get(normalize_url(url))
+        if value is None:
+            return http_client.HttpResponse(404, b"not found")
+        status, content = value if isinstance(value, tuple) else (200, value)
+        if isinstance(content, (dict, list)):
+            return http_client.HttpResponse(status, json.dumps(content).encode("utf-8"), {"Content-Type": "application/json"})
+        return http_client.HttpResponse(status, content.encode("utf-8") if isinstance(content, str) else content)
+
+class Cassette:
+    """
+    HTTPのやり取りをJSONファイル（カセット）に録音・再生するトランスポート。
+    mode="replay" は録音済みのレスポンスだけを返し、無いリクエストはエラーにする。
+    mode="record" はinner（既定は実際の通信）に流して録音し、close時に書き出す。
+    modeを省略すると、CPH_RECORD_CASSETTES=1 かカセットが無いときに録音、それ以外は再生する。
+    """
+    def __init__(self, path, mode=None, inner=None):
+        self.path = Path(str(path))
+        if mode is None:
+            mode = "record" if os.environ.get(RECORD_ENV) == "1" or not self.path.exists() else "replay"
+        if mode not in ("record", "replay"):
+            raise ValueError(f"カセットのmodeはrecordかreplayです: {mode}")
+        self.mode = mode
+        self.inner = inner or http_client.UrllibTransport()
+        self.interactions = [] if mode == "record" else self.load()
+        self.used = set()
+
+    def load(self):
+        return json.loads(self.path.read_text(encoding="utf-8")).get("interactions", [])
+
+    def save(self):
+        atomic_write_json(self.path, {"interactions": self.interactions})
+
+    def find(self, method, url):
+        """同じリクエストが複数回あれば録音順に返す。使い切ったら最後のものを返し続ける"""
+        key = (method, normalize_url(url))
+        matches = [i for i, x in enumerate(self.interactions) if (x["request"]["method"], normalize_url(x["request"]["url"])) == key]
+        if not matches:
+            return None
+        index = next((i for i in matches if i not in self.used), matches[-1])
+        self.used.add(index)
+        return self.interactions[index]
+
+    def request(self, method, url, headers=None, body=None, timeout=None):
+        if self.mode == "replay":
+            found = self.find(method, url)
+            if found is None:
+                raise CphError(f"カセット {self.path.name} に録音されていないリクエストです: {method} {url}", kind="cassette_miss")
+            response = found["response"]
+            return http_client.HttpResponse(response["status"], decode_body(response), response.get("headers", {}))
+        response = self.inner.request(method, url, headers=headers, body=body, timeout=timeout)
+        headers = {k: v for k, v in response.headers.items() if k.lower() in KEPT_RESPONSE_HEADERS}
+        self.interactions.append({
+            "request": {"method": method, "url": url},
+            "response": {"status": response.status, "headers": headers, **encode_body(response.body)},
+        })
+        return response
+
+    def close(self):
+        if self.mode == "record":
+            self.save()
+
+@contextmanager
+def use_cassette(path, mode=None, inner=None):
+    """with内のhttp_clientの通信をカセットで録音・再生する"""
+    cassette = Cassette(path, mode=mode, inner=inner)
+    try:
+        with http_client.use_transport(cassette):
+            yield cassette
+    finally:
+        cassette.close()
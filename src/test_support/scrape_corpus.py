@@ -0,0 +1,83 @@
+import json
+import os
+from pathlib import Path
+from src.commands.sample_scraper import count_sample_headings, parse_blocks, scrape_samples
+from src.file_operator import atomic_write_json
+
+# 1にすると、ゴールデンファイルを今の取り出し結果で書き直す
+UPDATE_ENV = "CPH_UPDATE_GOLDEN"
+# cphに同梱しているコーパス（tests/scrape_corpus/{サイト}/{名前}.html と .json）
+DEFAULT_CORPUS = Path(__file__).resolve().parents[2] / "tests" / "scrape_corpus"
+
+def scrape_result(html):
+    """ゴールデンファイルと比べる内容。使った方法・入力例の見出しの数・サンプル"""
+    samples, matched, _ = scrape_samples(html)
+    return {
+        "strategy": matched,
+        "headings": count_sample_headings(parse_blocks(html)),
+        "samples": [{"input": i, "output": o} for i, o in samples],
+    }
+
+class CorpusCase:
+    """
+    保存した問題ページ（.html）と、そこから取り出せるはずの結果（同じ名前の.json）の組。
+    .jsonにはurl（取得元、比べない）とscrape_resultの内容を書く
+    """
+    def __init__(self, html_path, root=None):
+        self.html_path = Path(html_path)
+        self.golden_path = self.html_path.with_suffix(".json")
+        self.name = self.html_path.relative_to(root).with_suffix("").as_posix() if root else self.html_path.stem
+
+    def actual(self):
+        return scrape_result(self.html_path.read_text(encoding="utf-8"))
+
+    def expected(self):
+        if not self.golden_path.exists():
+            return None
+        return json.loads(self.golden_path.read_text(encoding="utf-8"))
+
+    def differences(self, actual=None):
+        """ゴールデンファイルとの違い（無ければ空）"""
+        actual = actual or self.actual()
+        expected = self.expected()
+        if expected is None:
+            return [f"ゴールデンファイルがありません（{UPDATE_ENV}=1 で作成）: {self.golden_path.name}"]
+        diffs = []
+        for key in ("strategy", "headings"):
+            if expected.get(key) != actual[key]:
+                diffs.append(f"{key}: 期待 {expected.get(key)!r} / 実際 {actual[key]!r}")
+        want, got = expected.get("samples", []), actual["samples"]
+        if len(want) != len(got):
+            diffs.append(f"サンプルの数: 期待 {len(want)} / 実際 {len(got)}")
+        for i, (w, g) in enumerate(zip(want, got), 1):
+            for part in ("input", "output"):
+                if w.get(part) != g[part]:
+                    diffs.append(f"サンプル{i}の{part}: 期待 {w.get(part)!r} / 実際 {g[part]!r}")
+        return diffs
+
+    def update(self, actual=None):
+        """今の結果でゴールデンファイルを書き直す（urlは残す）"""
+        golden = {"url": (self.expected() or {}).get("url"), **(actual or self.actual())}
+        atomic_write_json(self.golden_path, golden)
+
+def discover(corpus_dir=DEFAULT_CORPUS):
+    corpus_dir = Path(corpus_dir)
+    return [CorpusCase(p, corpus_dir) for p in sorted(corpus_dir.rglob("*.html"))]
+
+def run_corpus(corpus_dir=DEFAULT_CORPUS, update=None):
+    """
+    コーパスのすべてのページを取り出し直し、ゴールデンファイルと比べる。{名前: 違いの一覧} を返す（すべて一致なら違いは空）。
+    updateなら（省略時はCPH_UPDATE_GOLDEN=1のとき）違うものを書き直し、一致したものとして扱う。
+    サイトの取り出し方を変えたときや、新しいサイトのページを足したときにオフラインで確かめる
+    """
+    if update is None:
+        update = os.environ.get(UPDATE_ENV) == "1"
+    results = {}
+    for case in discover(corpus_dir):
+        actual = case.actual()
+        diffs = case.differences(actual)
+        if diffs and update:
+            case.update(actual)
+            diffs = []
+        results[case.name] = diffs
+    return results
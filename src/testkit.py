@@ -0,0 +1,324 @@
+"""
+cphのワークフロー（open → test → submit）を実際のサイトやdockerなしで通しで動かすためのテスト用部品。
+サイトやプラグインの実装を足すときに、同じ流れのテストを書けるよう公開している。
+
+    workspace = setup_test_environment(tmp_path)
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "print(sum(map(int, input().split())))\n")
+        results = sim.execute("test", "abc300", "a", "python")
+
+dockerを使う部分（ContainerClient / ContainerImageManager / ContainerPool）は、TestDockerRunnerを
+shellに渡すと、dockerデーモンなしで同じコマンドの流れを確かめられる。
+"""
+import asyncio
+import json
+import os
+import shutil
+from pathlib import Path
+from src.shell import CommandSpec, Shell, ShellResult
+from src.test_support.http_cassette import StaticTransport
+from src import http_client
+
+DEFAULT_LANGUAGE_IDS = {"python": "5082", "pypy": "5078", "rust": "5054"}
+# 言語ごとのテンプレート（openで展開される解答ファイル）
+DEFAULT_TEMPLATES = {
+    "python": {"main.py": "import sys\n\ndef main():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n"},
+    "pypy": {"main.py": "import sys\n\ndef main():\n    pass\n\nif __name__ == \"__main__\":\n    main()\n"},
+}
+
+def atcoder_task_url(contest_name, problem_name):
+    return f"https://atcoder.jp/contests/{contest_name}/tasks/{contest_name}_{problem_name}"
+
+class TestWorkspace:
+    """setup_test_environmentで作ったワークスペース。パスの取得とファイルの書き換えを簡単にする"""
+    __test__ = False  # pytestにテストクラスとして集めさせない
+
+    def __init__(self, root):
+        self.root = Path(str(root))
+
+    def path(self, *parts):
+        return self.root.joinpath(*parts)
+
+    def read_json(self, *parts):
+        return json.loads(self.path(*parts).read_text(encoding="utf-8"))
+
+    def write_source(self, language_name, code, file_name="main.py"):
+        path = self.path("contest_current", language_name, file_name)
+        path.parent.mkdir(parents=True, exist_ok=True)
+        path.write_text(code, encoding="utf-8")
+        return path
+
+    def write_case(self, name, input_data, expected):
+        test_dir = self.path("contest_current", "test")
+        test_dir.mkdir(parents=True, exist_ok=True)
+        (test_dir / f"{name}.in").write_text(input_data, encoding="utf-8")
+        (test_dir / f"{name}.out").write_text(expected, encoding="utf-8")
+
+    def update_config(self, **values):
+        path = self.path("contest_current", "config.json")
+        data = json.loads(path.read_text(encoding="utf-8")) if path.exists() else {}
+        data.update(values)
+        path.write_text(json.dumps(data, ensure_ascii=False, indent=2), encoding="utf-8")
+
+def setup_test_environment(root, templates=None, language_ids=None, config=None):
+    """
+    rootにcphのワークスペース（contest_current / contest_template / config.json）を作る。
+    作業中の問題は無い状態から始まり、openで展開される。
+    """
+    workspace = TestWorkspace(root)
+    current = workspace.path("contest_current")
+    current.mkdir(parents=True, exist_ok=True)
+    for language_name, files in (templates or DEFAULT_TEMPLATES).items():
+        for name, content in files.items():
+            path = workspace.path("contest_template", language_name, name)
+            path.parent.mkdir(parents=True, exist_ok=True)
+            path.write_text(content, encoding="utf-8")
+    workspace.update_config(language_id=dict(language_ids or DEFAULT_LANGUAGE_IDS), moveignore=[], **(config or {}))
+    (current / "system_info.json").write_text("{}", encoding="utf-8")
+    return workspace
+
+class MockSiteShell(Shell):
+    """oj download / oj submit を模擬サイトで処理し、それ以外のコマンドは実際に実行するShell"""
+    def __init__(self, site):
+        self.site = site
+
+    def run(self, spec):
+        argv = [str(a) for a in spec.argv()]
+        if Path(argv[0]).name == "oj":
+            return self.site.handle_oj(argv, cwd=spec.cwd)
+        return super().run(spec)
+
+class AtCoderMock:
+    """
+    AtCoderの代わりになる模擬サイト。問題ごとのサンプルを持ち、oj download でそれを書き出し、
+    oj submit は提出内容を記録して成功を返す。http_client向けのレスポンスもtransportに登録できる。
+    onlineをFalseにするとネットワークに接続できない状態を模擬する。
+    """
+    def __init__(self, routes=None):
+        self.problems = {}
+        self.submissions = []
+        self.transport = StaticTransport(routes)
+        self.shell = MockSiteShell(self)
+
+    @property
+    def online(self):
+        return self.transport.online
+
+    @online.setter
+    def online(self, value):
+        self.transport.online = value
+
+    def add_problem(self, contest_name, problem_name, samples):
+        """samplesは [(入力, 期待出力)]"""
+        self.problems[atcoder_task_url(contest_name, problem_name)] = list(samples)
+
+    def handle_oj(self, argv, cwd=None):
+        if not self.online:
+            return ShellResult(argv, 1, "", "[ERROR] Failed to establish a new connection: [Errno 101] Network is unreachable\n")
+        if "download" in argv:
+            url = argv[argv.index("download") + 1]
+            dest = Path(argv[argv.index("-d") + 1])
+            samples = self.problems.get(url)
+            if samples is None:
+                return ShellResult(argv, 1, "", f"[ERROR] unknown problem: {url}\n")
+            dest.mkdir(parents=True, exist_ok=True)
+            for i, (input_data, expected) in enumerate(samples, 1):
+                (dest / f"sample-{i}.in").write_text(input_data, encoding="utf-8")
+                (dest / f"sample-{i}.out").write_text(expected, encoding="utf-8")
+            return ShellResult(argv, 0, f"[SUCCESS] {len(samples)} samples\n", "")
+        if "submit" in argv:
+            rest = argv[argv.index("submit") + 1:]
+            url, file_path = rest[0], rest[1]
+            source = Path(cwd or ".", file_path).read_text(encoding="utf-8")
+            language = rest[rest.index("--language") + 1] if "--language" in rest else None
+            self.submissions.append({"url": url, "file": file_path, "language": language, "source": source})
+            return ShellResult(argv, 0, f"[SUCCESS] submitted: {url}\n", "")
+        return ShellResult(argv, 0, "", "")
+
+class TestDockerRunner(Shell):
+    """
+    dockerデーモンの代わりになるShell。dockerコマンドを受け取ってコンテナ・イメージの状態を手元で持ち、
+    docker exec はマウント（-v）したパスをホストのパスに直してホストで実行する（docker cpも同様）。
+    受け取ったdockerコマンドはcommandsに残る。docker以外のコマンドはそのまま実行する。
+    """
+    __test__ = False  # pytestにテストクラスとして集めさせない
+
+    def __init__(self, images=None):
+        self.images = set(images or [])
+        self.containers = {}
+        self.commands = []
+
+    def run(self, spec):
+        argv = [str(a) for a in spec.argv()]
+        if Path(argv[0]).name != "docker" or len(argv) < 2:
+            return super().run(spec)
+        self.commands.append(argv)
+        if argv[1] == "exec":
+            return self._exec(argv, spec, super().run)
+        handler = getattr(self, "_" + argv[1], None)
+        return handler(argv) if handler else ShellResult(argv, 0, "", "")
+
+    def spawn(self, spec, on_stdout=None, on_stderr=None):
+        argv = [str(a) for a in spec.argv()]
+        if Path(argv[0]).name == "docker" and argv[1:2] == ["exec"]:
+            self.commands.append(argv)
+            name, cmd = self._split_exec(argv)
+            return super().spawn(self._host_spec(self.containers.get(name), cmd, spec), on_stdout, on_stderr)
+        return super().spawn(spec, on_stdout, on_stderr)
+
+    def attach(self, spec):
+        argv = [str(a) for a in spec.argv()]
+        if Path(argv[0]).name == "docker" and argv[1:2] == ["exec"]:
+            return self._exec(argv, spec, lambda host: ShellResult(argv, super(TestDockerRunner, self).attach(host))).returncode
+        return super().attach(spec)
+
+    def to_host_path(self, container, path):
+        """コンテナ内のパスを、マウントしたホストのパスに直す（マウント外ならそのまま）"""
+        for host, mounted in (container or {}).get("volumes", []):
+            if path == mounted or path.startswith(mounted.rstrip("/") + "/"):
+                return host + path[len(mounted.rstrip("/")):]
+        return path
+
+    @staticmethod
+    def _split_exec(argv):
+        i = 2
+        while argv[i].startswith("-"):
+            i += 2 if argv[i] in ("-w", "-e", "-u") else 1
+        return argv[i], argv[i + 1:]
+
+    def _host_spec(self, container, cmd, spec):
+        return CommandSpec.from_argv([self.to_host_path(container, a) for a in cmd], stdin=spec.stdin, timeout=spec.timeout)
+
+    def _exec(self, argv, spec, run):
+        name, cmd = self._split_exec(argv)
+        container = self.containers.get(name)
+        if container is None or not container["running"]:
+            return ShellResult(argv, 1, "", f"Error response from daemon: container {name} is not running\n")
+        return run(self._host_spec(container, cmd, spec))
+
+    def _version(self, argv):
+        return ShellResult(argv, 0, "test\n", "")
+
+    def _run(self, argv):
+        options, i = {"volumes": [], "name": None}, 2
+        while argv[i].startswith("-"):
+            if argv[i] == "-v":
+                host, _, mounted = argv[i + 1].partition(":")
+                options["volumes"].append((host, mounted))
+            elif argv[i] == "--name":
+                options["name"] = argv[i + 1]
+            i += 1 if argv[i] in ("-d", "--rm", "-i", "-t") else 2
+        image = argv[i]
+        if image not in self.images:
+            return ShellResult(argv, 125, "", f"Unable to find image '{image}:latest' locally\n")
+        name = options["name"] or f"container{len(self.containers) + 1}"
+        self.containers[name] = {"image": image, "volumes": options["volumes"], "running": True}
+        return ShellResult(argv, 0, f"{name}-id\n", "")
+
+    def _start(self, argv):
+        return self._set_running(argv, argv[-1], True)
+
+    def _stop(self, argv):
+        return self._set_running(argv, argv[-1], False)
+
+    def _set_running(self, argv, name, running):
+        if name not in self.containers:
+            return ShellResult(argv, 1, "", f"Error: No such container: {name}\n")
+        self.containers[name]["running"] = running
+        return ShellResult(argv, 0, f"{name}\n", "")
+
+    def _rm(self, argv):
+        name = argv[-1]
+        if self.containers.pop(name, None) is None:
+            return ShellResult(argv, 1, "", f"Error: No such container: {name}\n")
+        return ShellResult(argv, 0, f"{name}\n", "")
+
+    def _ps(self, argv):
+        names = [n for n, c in self.containers.items() if c["running"] or "-a" in argv]
+        return ShellResult(argv, 0, "".join(f"{n}\n" for n in names), "")
+
+    def _inspect(self, argv):
+        name = argv[-1]
+        if name in self.containers:
+            container = self.containers[name]
+            if "-f" in argv:
+                return ShellResult(argv, 0, "true\n" if container["running"] else "false\n", "")
+            return ShellResult(argv, 0, json.dumps([{"Name": f"/{name}", "State": {"Running": container["running"]}}]), "")
+        if name in self.images:
+            return ShellResult(argv, 0, json.dumps([{"RepoTags": [f"{name}:latest"]}]), "")
+        return ShellResult(argv, 1, "", f"Error: No such object: {name}\n")
+
+    def _images(self, argv):
+        # docker images [--filter ...] --format FMT [イメージ名]
+        positional = [a for a, prev in zip(argv[2:], argv[1:]) if not a.startswith("-") and prev not in ("--format", "--filter")]
+        names = [n for n in sorted(self.images) if not positional or n in positional]
+        return ShellResult(argv, 0, "".join(f"{n}\n" for n in names), "")
+
+    def _build(self, argv):
+        self.images.add(argv[argv.index("-t") + 1])
+        return ShellResult(argv, 0, "", "")
+
+    def _pull(self, argv):
+        self.images.add(argv[-1])
+        return ShellResult(argv, 0, "", "")
+
+    def _rmi(self, argv):
+        if argv[-1] not in self.images:
+            return ShellResult(argv, 1, "", f"Error: No such image: {argv[-1]}\n")
+        self.images.discard(argv[-1])
+        return ShellResult(argv, 0, "", "")
+
+    def _cp(self, argv):
+        src, dst = argv[2], argv[3]
+        paths = []
+        for path in (src, dst):
+            name, sep, inner = path.partition(":")
+            paths.append(self.to_host_path(self.containers.get(name), inner) if sep and name in self.containers else path)
+        if os.path.isdir(paths[0]):
+            shutil.copytree(paths[0], paths[1], dirs_exist_ok=True)
+        else:
+            shutil.copy(paths[0], paths[1])
+        return ShellResult(argv, 0, "", "")
+
+class ContestSimulation:
+    """
+    ワークスペースをカレントディレクトリにし、模擬サイトとローカル実行でCommandExecutorを動かす。
+    withを抜けるとカレントディレクトリとHTTPのトランスポートを元に戻す。
+    """
+    def __init__(self, workspace, site=None, options=None):
+        self.workspace = workspace if isinstance(workspace, TestWorkspace) else TestWorkspace(workspace)
+        self.site = site or AtCoderMock()
+        self.options = options or {}
+        self.previous_cwd = None
+        self.previous_transport = None
+
+    def __enter__(self):
+        self.previous_cwd = os.getcwd()
+        os.chdir(self.workspace.root)
+        self.previous_transport = http_client.set_transport(self.site.transport)
+        return self
+
+    def __exit__(self, *exc):
+        http_client.set_transport(self.previous_transport)
+        os.chdir(self.previous_cwd)
+        return False
+
+    def executor(self, options=None):
+        from src.command_executor import CommandExecutor, MockOpener
+        from src.contest_file_manager import ContestFileManager
+        from src.file_operator import LocalFileOperator
+        executor = CommandExecutor(
+            file_manager=ContestFileManager(LocalFileOperator()),
+            opener=MockOpener(),
+            exec_mode="local",
+            options={**self.options, **(options or {})},
+        )
+        # すべてのコマンドが同じ実行環境を共有しているので、そこのShellを模擬サイトにつなぐ
+        executor.test_handler.env.shell = self.site.shell
+        return executor
+
+    def execute(self, command, contest_name=None, problem_name=None, language_name=None, options=None):
+        return asyncio.run(self.executor(options).execute(command, contest_name, problem_name, language_name))
@@ -0,0 +1,116 @@
+import json
+import os
+from datetime import datetime
+from pathlib import Path
+from src.file_lock import file_lock
+from src.file_operator import atomic_write_json
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+# 環境変数で有効・無効を上書きできる（"1"で有効、"0"で無効）。未設定ならconfig.jsonのstats.enabled
+STATS_ENV = "CPH_STATS"
+
+def empty_stats():
+    return {"since": datetime.now().isoformat(), "commands": {}, "tests": {"runs": 0, "cases": 0, "seconds": 0.0}, "caches": {}}
+
+def average(total, count):
+    return total / count if count else None
+
+def merge_stats(old_path, new_path):
+    """以前プロジェクトの.cphに集計していたものを、stateの置き場の集計に足し合わせる"""
+    old = json.loads(Path(old_path).read_text(encoding="utf-8"))
+    merged = {**empty_stats(), **json.loads(Path(new_path).read_text(encoding="utf-8"))}
+    merged["since"] = min(s for s in (old.get("since"), merged["since"]) if s)
+    for name, e in old.get("commands", {}).items():
+        target = merged["commands"].setdefault(name, {"count": 0, "failed": 0, "seconds": 0.0})
+        for key in ("count", "failed", "seconds"):
+            target[key] += e.get(key, 0)
+    for key in ("runs", "cases", "seconds"):
+        merged["tests"][key] += old.get("tests", {}).get(key, 0)
+    for name, e in old.get("caches", {}).items():
+        target = merged["caches"].setdefault(name, {"hit": 0, "miss": 0})
+        for key in ("hit", "miss"):
+            target[key] += e.get(key, 0)
+    atomic_write_json(new_path, merged)
+
+class UsageStats:
+    """
+    コマンドの利用回数・所要時間、テストの平均実行時間、キャッシュのヒット率を ~/.local/state/cph/stats.json に集計する（プロジェクトをまたいで1つ）。
+    外部には送信しない。既定では無効で、config.jsonの {"stats": {"enabled": true}} か CPH_STATS=1 で有効になる。
+    集計の失敗でコマンドを失敗させないよう、書き込みのエラーは無視する。
+    """
+    def __init__(self, path=None, enabled=None):
+        self.path = Path(str(path)) if path else UnifiedPathManager().shared_state("stats.json", merge_stats)
+        self.enabled = self.read_enabled() if enabled is None else enabled
+
+    @staticmethod
+    def read_enabled():
+        env = os.environ.get(STATS_ENV)
+        if env is not None:
+            return env not in ("", "0")
+        try:
+            from src.config_json_manager import ConfigJsonManager
+            return bool(ConfigJsonManager().get_stats().get("enabled"))
+        except Exception:
+            return False
+
+    def load(self):
+        if not self.path.exists():
+            return empty_stats()
+        try:
+            return {**empty_stats(), **json.loads(self.path.read_text(encoding="utf-8"))}
+        except (OSError, ValueError):
+            return empty_stats()
+
+    def update(self, func):
+        if not self.enabled:
+            return None
+        try:
+            with file_lock(self.path):
+                data = self.load()
+                func(data)
+                atomic_write_json(self.path, data)
+            return data
+        except Exception:
+            return None
+
+    def record_command(self, command, seconds, ok=True):
+        def apply(data):
+            entry = data["commands"].setdefault(command, {"count": 0, "failed": 0, "seconds": 0.0})
+            entry["count"] += 1
+            entry["seconds"] += seconds
+            if not ok:
+                entry["failed"] += 1
+        return self.update(apply)
+
+    def record_tests(self, results):
+        """1回のtestの全ケースの実行時間を加算する"""
+        def apply(data):
+            tests = data["tests"]
+            tests["runs"] += 1
+            for r in results:
+                if r.get("time") is not None:
+                    tests["cases"] += 1
+                    tests["seconds"] += r["time"]
+        return self.update(apply)
+
+    def record_cache(self, name, hit):
+        def apply(data):
+            entry = data["caches"].setdefault(name, {"hit": 0, "miss": 0})
+            entry["hit" if hit else "miss"] += 1
+        return self.update(apply)
+
+    def summary(self):
+        """表示用に平均値とヒット率を計算したもの"""
+        data = self.load()
+        commands = {
+            name: {**e, "average": average(e["seconds"], e["count"])}
+            for name, e in sorted(data["commands"].items(), key=lambda kv: -kv[1]["count"])
+        }
+        tests = data["tests"]
+        caches = {name: {**e, "rate": average(e["hit"], e["hit"] + e["miss"])} for name, e in sorted(data["caches"].items())}
+        return {
+            "since": data.get("since"),
+            "commands": commands,
+            "tests": {**tests, "average_case": average(tests["seconds"], tests["cases"])},
+            "caches": caches,
+        }
@@ -0,0 +1,2 @@
+# cphの版。ここだけで定義する（workspace.tomlのcreated_by / updated_by、report-bugのversions.jsonで使う）
+CPH_VERSION = "0.1.0"
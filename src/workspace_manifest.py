@@ -0,0 +1,165 @@
+import json
+from dataclasses import dataclass, field
+from src.backup_manager import BackupManager
+from src.error_hints import CphError
+from src.file_operator import atomic_write
+from src.path_manager.path_resolver import PathResolver
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.version import CPH_VERSION
+
+try:
+    import tomllib
+except ModuleNotFoundError:
+    # tomllibはPython 3.11から。それより前はtomli（tomllibの元になったもの）で読み、どちらも無ければ読むときに止める
+    try:
+        import tomli as tomllib
+    except ModuleNotFoundError:
+        tomllib = None
+
+MANIFEST_FILE = "workspace.toml"
+DEFAULT_SITE = "atcoder"
+# manifestの無いワークスペース（この仕組みより前に作ったもの）の版
+LEGACY_LAYOUT_VERSION = 0
+# 項目 → 型。これ以外の項目や型の違う値は読み込みで止める
+FIELDS = {"layout_version": int, "created_by": str, "updated_by": str, "site": str, "options": dict}
+OPTION_TYPES = (str, int, bool)
+
+@dataclass
+class WorkspaceManifest:
+    """.cph/workspace.toml の中身。layout_versionはディレクトリ構成の版で、変わるたびにMigrationで上げる"""
+    layout_version: int
+    created_by: str = CPH_VERSION
+    updated_by: str = CPH_VERSION
+    site: str = DEFAULT_SITE
+    # 構成の設定（layout.stocksのテンプレートなど）。値は文字列・整数・真偽値
+    options: dict = field(default_factory=dict)
+
+    @classmethod
+    def from_dict(cls, data, source=MANIFEST_FILE):
+        unknown = sorted(set(data) - set(FIELDS))
+        if unknown:
+            raise CphError(f"{source} に未知の項目があります: {', '.join(unknown)}", kind="workspace_manifest_invalid")
+        if "layout_version" not in data:
+            raise CphError(f"{source} にlayout_versionがありません", kind="workspace_manifest_invalid")
+        for name, value in data.items():
+            # boolはintの派生なので、型はそのものと比べる
+            if type(value) is not FIELDS[name]:
+                raise CphError(f"{source} の{name}は{FIELDS[name].__name__}で指定してください: {value!r}", kind="workspace_manifest_invalid")
+        for name, value in data.get("options", {}).items():
+            if type(value) not in OPTION_TYPES:
+                raise CphError(f"{source} のoptions.{name}は文字列・整数・真偽値で指定してください: {value!r}", kind="workspace_manifest_invalid")
+        return cls(**data)
+
+    def to_toml(self):
+        lines = ["# cphのワークスペースの構成（cphが書き換えます）"]
+        lines += [f"{name} = {toml_value(getattr(self, name))}" for name in FIELDS if name != "options"]
+        if self.options:
+            lines += ["", "[options]"] + [f"{name} = {toml_value(value)}" for name, value in sorted(self.options.items())]
+        return "\n".join(lines) + "\n"
+
+def toml_value(value):
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, int):
+        return str(value)
+    # TOMLの基本文字列はJSONの文字列と同じエスケープで書ける
+    return json.dumps(str(value), ensure_ascii=False)
+
+class Migration:
+    """layout_versionを version - 1 から version へ上げる手順。applyはワークスペースを書き換え、manifestの値を更新する"""
+    def __init__(self, version, description, apply, paths=()):
+        self.version = version
+        self.description = description
+        self.apply = apply
+        # 書き換える前にバックアップするファイル（ワークスペースからの相対パス）
+        self.paths = tuple(paths)
+
+def adopt_legacy(root, manifest):
+    """manifestの無いワークスペースに、今の構成（layout.stocksのテンプレート）を記録する"""
+    manifest.options["stocks"] = PathResolver.from_config(root).stocks_template
+
+MIGRATIONS = [
+    Migration(1, "ワークスペースの構成をworkspace.tomlに記録する", adopt_legacy, paths=("contest_current/config.json",)),
+]
+LAYOUT_VERSION = MIGRATIONS[-1].version
+
+class WorkspaceManifestStore:
+    """
+    .cph/workspace.toml の読み書きと、古い構成のワークスペースの移行。
+    移行は版を1つずつ上げ、書き換える前のファイルとmanifestを .cph/backups/{日時}-migrate-v{版}/ に残す。
+    新しいcphで作られた（layout_versionがこのcphより新しい）ワークスペースは、壊さないよう触らずに止める。
+    """
+    def __init__(self, upm=None, migrations=None):
+        self.upm = upm or UnifiedPathManager()
+        self.root = self.upm.project_path.root
+        self.migrations = MIGRATIONS if migrations is None else migrations
+        self.path = self.upm.cph_dir(MANIFEST_FILE)
+
+    @property
+    def latest(self):
+        return self.migrations[-1].version if self.migrations else LEGACY_LAYOUT_VERSION
+
+    def is_workspace(self):
+        """cphのワークスペースか（manifest・.cph・contest_currentのどれかがある）"""
+        return self.path.is_file() or self.upm.cph_dir().is_dir() or self.upm.contest_current().is_dir()
+
+    def load(self):
+        """manifest。無ければNone"""
+        try:
+            text = self.path.read_text(encoding="utf-8")
+        except FileNotFoundError:
+            return None
+        if tomllib is None:
+            raise CphError(f"{self.path} を読むにはPython 3.11以降か、tomliが必要です", kind="toml_missing")
+        try:
+            data = tomllib.loads(text)
+        except tomllib.TOMLDecodeError as e:
+            raise CphError(f"{self.path} を読めません: {e}", kind="workspace_manifest_invalid")
+        return WorkspaceManifest.from_dict(data, source=str(self.path))
+
+    def save(self, manifest):
+        atomic_write(self.path, manifest.to_toml())
+
+    def create(self, site=None):
+        """新しいワークスペースのmanifest（最新の版）。既にあればそれを返す"""
+        manifest = self.load()
+        if manifest is not None:
+            return manifest
+        manifest = WorkspaceManifest(LEGACY_LAYOUT_VERSION, site=site or DEFAULT_SITE)
+        return self.migrate(manifest=manifest, quiet=True, backup=False)
+
+    def pending(self, manifest):
+        if manifest.layout_version > self.latest:
+            raise CphError(f"このワークスペースは新しいcph（{manifest.updated_by}、構成の版 {manifest.layout_version}）で更新されています"
+                           f"（このcph {CPH_VERSION} は版 {self.latest} まで）", kind="workspace_too_new")
+        return [m for m in self.migrations if m.version > manifest.layout_version]
+
+    def migrate(self, manifest=None, quiet=False, backup=True):
+        """必要な移行をすべて行い、最新のmanifestを返す。移行が無ければ何も書かない。backupを外すのは作ったばかりのとき"""
+        manifest = manifest or self.load() or WorkspaceManifest(LEGACY_LAYOUT_VERSION)
+        pending = self.pending(manifest)
+        for migration in pending:
+            backups = BackupManager(f"migrate-v{migration.version}", upm=self.upm)
+            if backup:
+                for rel in migration.paths:
+                    backups.save(self.root / rel)
+                backups.save(self.path)
+            try:
+                migration.apply(self.root, manifest)
+            except (OSError, ValueError) as e:
+                raise CphError(f"ワークスペースの構成を版 {migration.version} に更新できませんでした: {e}"
+                               + (f"（更新前のファイル: {backups.path}）" if backups.saved else ""), kind="workspace_migration_failed")
+            manifest.layout_version = migration.version
+            manifest.updated_by = CPH_VERSION
+            self.save(manifest)
+            if not quiet:
+                print(f"[cph] ワークスペースの構成を版 {migration.version} に更新しました: {migration.description}"
+                      + (f"（バックアップ: {backups.path}）" if backups.saved else ""))
+        return manifest
+
+def ensure_current(upm=None):
+    """コマンドの前に、古い構成のワークスペースを最新の版へ移行する（ワークスペースの外では何もしない）"""
+    store = WorkspaceManifestStore(upm)
+    if not store.is_workspace():
+        return None
+    return store.migrate()
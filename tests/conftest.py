@@ -1,5 +1,7 @@
 import pytest
 from src.command_executor import MockOpener
+from src import http_client
+from src.test_support.http_cassette import StaticTransport
 import shutil
 import os
 import pathlib
@@ -9,6 +11,12 @@ import json
 def patch_opener(monkeypatch):
     monkeypatch.setattr("src.command_executor.Opener", MockOpener)
 
+@pytest.fixture(autouse=True)
+def offline_http():
+    """テストから実際に通信しないよう、HTTPのトランスポートを模擬サイト（接続可・全URL 404）にする"""
+    with http_client.use_transport(StaticTransport()):
+        yield
+
 @pytest.fixture(autouse=True)
 def prepare_contest_current(tmp_path, monkeypatch):
     """
@@ -63,5 +71,8 @@ def prepare_contest_current(tmp_path, monkeypatch):
                 "rust": "5054"
             }
         }, ensure_ascii=False, indent=2), encoding="utf-8")
+    # ユーザーごとの置き場（~/.cache/cph など）も一時ディレクトリの下にする
+    for env in ("XDG_CACHE_HOME", "XDG_STATE_HOME", "XDG_CONFIG_HOME"):
+        monkeypatch.setenv(env, str(tmp_path / "xdg" / env.lower()))
     # カレントディレクトリをtmp_pathに切り替え
     monkeypatch.chdir(tmp_path) 
\ No newline at end of file
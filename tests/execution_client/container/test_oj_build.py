@@ -1,9 +1,16 @@
 import pytest
-from unittest.mock import patch, MagicMock
-import sys
+from unittest.mock import patch
 
 import src.execution_client.container.oj_build as oj_build
 
+class FakeShell:
+    def __init__(self, returncode):
+        self.returncode = returncode
+        self.calls = []
+    def attach(self, spec):
+        self.calls.append(spec.argv())
+        return self.returncode
+
 def test_build_ojtools_image_dockerfile_not_found(capsys):
     with patch("os.path.exists", return_value=False):
         with pytest.raises(SystemExit):
@@ -12,19 +19,24 @@ def test_build_ojtools_image_dockerfile_not_found(capsys):
         assert "Dockerfile not found" in captured.out
 
 def test_build_ojtools_image_success(capsys):
+    shell = FakeShell(0)
     with patch("os.path.exists", return_value=True):
-        mock_result = MagicMock()
-        mock_result.returncode = 0
-        with patch("subprocess.run", return_value=mock_result):
-            oj_build.build_ojtools_image()
-            captured = capsys.readouterr()
-            assert "[OK] Built" in captured.out
+        oj_build.build_ojtools_image(shell)
+        captured = capsys.readouterr()
+        assert "[OK] Built" in captured.out
+    assert shell.calls == [["docker", "build", "-f", oj_build.DOCKERFILE, "-t", oj_build.IMAGE_NAME, "."]]
 
 def test_build_ojtools_image_failure(capsys):
     with patch("os.path.exists", return_value=True):
-        mock_result = MagicMock()
-        mock_result.returncode = 1
-        with patch("subprocess.run", return_value=mock_result):
-            oj_build.build_ojtools_image()
-            captured = capsys.readouterr()
-            assert "[ERROR] Build failed" in captured.out 
\ No newline at end of file
+        oj_build.build_ojtools_image(FakeShell(1))
+        captured = capsys.readouterr()
+        assert "[ERROR] Build failed" in captured.out
+
+def test_build_ojtools_image_is_logged():
+    from src import command_log
+    log = command_log.get_log()
+    mark = log.mark()
+    with patch("os.path.exists", return_value=True), patch("subprocess.run") as run:
+        run.return_value.returncode = 0
+        oj_build.build_ojtools_image()
+    assert [entry["argv"][:2] for entry in log.since(mark)] == [["docker", "build"]]
@@ -0,0 +1,30 @@
+import os
+import pytest
+from execution_client.local.client import LocalAsyncClient
+from execution_client.local import priority
+from execution_client.local.priority import parse_cpu_list, make_priority_preexec
+
+def test_parse_cpu_list():
+    assert parse_cpu_list(None) is None
+    assert parse_cpu_list("0") == {0}
+    assert parse_cpu_list("0,2-3") == {0, 2, 3}
+    assert parse_cpu_list([1, 2]) == {1, 2}
+
+def test_make_priority_preexec_none():
+    assert make_priority_preexec() is None
+
+def test_make_priority_preexec_unsupported(monkeypatch, capsys):
+    monkeypatch.setattr(priority, "supports_affinity", lambda: False)
+    monkeypatch.setattr(priority, "supports_nice", lambda: False)
+    assert make_priority_preexec("0", 5) is None
+    assert "警告" in capsys.readouterr().out
+
+@pytest.mark.skipif(not hasattr(os, "sched_getaffinity"), reason="sched_getaffinity未対応")
+def test_run_with_cpu_affinity(tmp_path):
+    cpu = sorted(os.sched_getaffinity(0))[0]
+    client = LocalAsyncClient(cpu_affinity=str(cpu), nice=5)
+    script = tmp_path / "affinity.py"
+    script.write_text("import os; print(sorted(os.sched_getaffinity(0)), os.nice(0))")
+    result = client.run("affinity", command=["python3", str(script)], detach=False, input="")
+    assert result.returncode == 0
+    assert result.stdout.strip() == f"[{cpu}] 5"
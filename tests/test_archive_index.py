@@ -0,0 +1,38 @@
+import asyncio
+import json
+from src.commands.archive_index import ArchiveIndex, parse_tags
+from src.commands.command_tag import CommandTag
+from src.commands.command_search import CommandSearch
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def make_index(tmp_path):
+    return ArchiveIndex(upm=UnifiedPathManager(project_root=tmp_path))
+
+def test_tag_writes_index_and_problem_meta(tmp_path):
+    index = make_index(tmp_path)
+    asyncio.run(CommandTag({"add": "dp, graph", "difficulty": "1200"}, index=index).tag("abc300", "d"))
+    entry = asyncio.run(CommandTag({"add": "dp", "remove": "graph"}, index=index).tag("abc300", "d"))
+    assert entry["tags"] == ["dp"] and entry["difficulty"] == 1200
+    meta = json.loads((tmp_path / "contest_stocks" / "abc300" / "d" / "problem.json").read_text(encoding="utf-8"))
+    assert meta["tags"] == ["dp"]
+    assert json.loads((tmp_path / ".cph" / "archive.json").read_text(encoding="utf-8"))["abc300/d"]["difficulty"] == 1200
+
+def test_search_by_tag_difficulty_and_status(tmp_path, capsys):
+    index = make_index(tmp_path)
+    index.set_tags("abc300", "d", add=["dp"], difficulty=1200)
+    index.set_tags("abc301", "e", add=["dp", "graph"], difficulty=1600)
+    index.set_tags("abc302", "c", add=["graph"])
+    index.record_submission("abc300", "d", "python", status="AC")
+    index.record_submission("abc300", "d", "rust")
+    assert index.get("abc300", "d")["status"] == "AC"
+    found = asyncio.run(CommandSearch({"tag": "dp"}, index=index).search())
+    assert [(e["contest"], e["problem"]) for e in found] == [("abc300", "d"), ("abc301", "e")]
+    found = asyncio.run(CommandSearch({"tag": "dp", "unsolved": True}, index=index).search())
+    assert [e["contest"] for e in found] == ["abc301"]
+    assert [e["contest"] for e in index.search(max_difficulty=1500)] == ["abc300"]
+    assert [e["contest"] for e in index.search(tags=["graph"])] == ["abc301", "abc302"]
+    assert "abc301" in capsys.readouterr().out
+
+def test_parse_tags():
+    assert parse_tags("dp, graph,,") == ["dp", "graph"]
+    assert parse_tags(None) == [] and parse_tags(True) == []
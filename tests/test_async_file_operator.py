@@ -0,0 +1,53 @@
+import asyncio
+from pathlib import Path
+from src.file_operator import LocalFileOperator, MockFileOperator, run_blocking
+
+def make_cases(root, count):
+    root.mkdir(parents=True, exist_ok=True)
+    for i in range(count):
+        (root / f"case{i}.in").write_text(f"{i}\n", encoding="utf-8")
+        (root / f"case{i}.out").write_text(f"{i}\n", encoding="utf-8")
+
+def test_iter_dir_streams_matching_entries(tmp_path):
+    make_cases(tmp_path / "test", 300)
+    op = LocalFileOperator(tmp_path)
+
+    async def collect():
+        return [p async for p in op.iter_dir("test", "*.in", batch_size=64)]
+    found = asyncio.run(collect())
+    assert len(found) == 300
+    assert all(p.suffix == ".in" for p in found)
+
+def test_copy_dir_copies_tree_and_skips_ignored(tmp_path):
+    make_cases(tmp_path / "src" / "test", 20)
+    (tmp_path / "src" / "target").mkdir()
+    (tmp_path / "src" / "target" / "big").write_bytes(b"\0" * 16)
+    op = LocalFileOperator(tmp_path)
+    copied = asyncio.run(op.copy_dir("src", "dst", ignore=("target",), jobs=4))
+    assert copied == 40
+    assert (tmp_path / "dst" / "test" / "case19.out").read_text(encoding="utf-8") == "19\n"
+    assert not (tmp_path / "dst" / "target").exists()
+
+def test_async_operations_delegate_to_sync_ones():
+    op = MockFileOperator(Path("/base"))
+
+    async def run():
+        await op.acreate("a.txt", "x")
+        await op.acopy("a.txt", "b.txt")
+        return await op.aexists("b.txt")
+    assert asyncio.run(run())
+    assert [o[0] for o in op.operations] == ["create", "copy"]
+
+def test_run_blocking_does_not_block_event_loop():
+    import time
+    ticks = []
+
+    async def ticker():
+        for _ in range(5):
+            ticks.append(time.perf_counter())
+            await asyncio.sleep(0.01)
+
+    async def run():
+        await asyncio.gather(run_blocking(time.sleep, 0.1), ticker())
+    asyncio.run(run())
+    assert len(ticks) == 5 and ticks[-1] - ticks[0] < 0.1
@@ -0,0 +1,35 @@
+import json
+import os
+import pytest
+from src.file_operator import atomic_write, atomic_write_json, LocalFileOperator
+
+def test_atomic_write_replaces_content_without_leftovers(tmp_path):
+    path = tmp_path / "sub" / "a.out"
+    atomic_write(path, "first\n")
+    atomic_write(path, "second\n")
+    assert path.read_text() == "second\n"
+    assert os.listdir(path.parent) == ["a.out"]
+
+def test_atomic_write_keeps_original_on_failure(tmp_path, monkeypatch):
+    path = tmp_path / "state.json"
+    atomic_write_json(path, {"a": 1})
+    def broken_fsync(fd):
+        raise OSError("disk full")
+    monkeypatch.setattr(os, "fsync", broken_fsync)
+    with pytest.raises(OSError):
+        atomic_write_json(path, {"a": 2})
+    assert json.loads(path.read_text()) == {"a": 1}
+    assert not [n for n in os.listdir(tmp_path) if n.endswith(".tmp")]
+
+def test_atomic_write_preserves_mode(tmp_path):
+    path = tmp_path / "run.sh"
+    path.write_text("echo 1\n")
+    os.chmod(path, 0o755)
+    atomic_write(path, "echo 2\n")
+    assert os.stat(path).st_mode & 0o777 == 0o755
+
+def test_local_file_operator_create_is_atomic(tmp_path):
+    op = LocalFileOperator(tmp_path)
+    op.create("test/1.in", "1 2\n")
+    assert (tmp_path / "test" / "1.in").read_text() == "1 2\n"
+    assert sorted(os.listdir(tmp_path / "test")) == ["1.in"]
@@ -0,0 +1,19 @@
+from datetime import datetime
+from src.backup_manager import BackupManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def test_save_keeps_relative_path(tmp_path):
+    upm = UnifiedPathManager(str(tmp_path))
+    source = tmp_path / "contest_current" / "python" / "main.py"
+    source.parent.mkdir(parents=True, exist_ok=True)
+    source.write_text("print(1)\n", encoding="utf-8")
+    backup = BackupManager("open", upm=upm, now=datetime(2024, 5, 1, 12, 0, 0))
+    saved = backup.save(source)
+    assert saved == tmp_path / ".cph" / "backups" / "20240501-120000-000000-open" / "contest_current" / "python" / "main.py"
+    assert saved.read_text(encoding="utf-8") == "print(1)\n"
+    assert backup.saved == [(source.resolve(), saved)]
+
+def test_save_missing_file_creates_nothing(tmp_path):
+    backup = BackupManager("open", upm=UnifiedPathManager(str(tmp_path)))
+    assert backup.save(tmp_path / "missing.py") is None
+    assert not backup.path.exists()
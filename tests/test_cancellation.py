@@ -0,0 +1,114 @@
+import asyncio
+import threading
+import pytest
+from src import cancellation, http_client
+from src.cancellation import CANCELLED, OPERATION_TIMEOUT, CancellationToken
+from src.commands.command_open import CommandOpen
+from src.error_hints import CphError
+from src.file_operator import run_blocking
+from src.http_client import HttpResponse
+
+class FakeClock:
+    def __init__(self):
+        self.now = 100.0
+    def __call__(self):
+        return self.now
+
+class RecordingTransport:
+    def __init__(self, error=None):
+        self.error = error
+        self.timeouts = []
+    def request(self, method, url, headers=None, body=None, timeout=None):
+        self.timeouts.append(timeout)
+        if self.error:
+            raise self.error
+        return HttpResponse(200, b"ok")
+
+def test_token_cancel_and_deadline():
+    clock = FakeClock()
+    token = CancellationToken(timeout=10, clock=clock)
+    token.check()
+    assert token.request_timeout(30) == 10
+    clock.now += 8
+    assert token.request_timeout(30) == 2
+    clock.now += 2
+    with pytest.raises(CphError) as e:
+        token.check()
+    assert e.value.kind == OPERATION_TIMEOUT
+    token = CancellationToken()
+    assert token.remaining() is None and token.request_timeout(30) == 30
+    token.cancel()
+    with pytest.raises(CphError) as e:
+        token.check()
+    assert e.value.kind == CANCELLED
+
+def test_operation_scopes_the_current_token():
+    outer = cancellation.current()
+    with cancellation.operation(timeout=5) as token:
+        assert cancellation.current() is token and token.timeout == 5
+    assert cancellation.current() is outer
+
+def test_http_uses_configured_timeout_and_checks_cancellation(monkeypatch):
+    monkeypatch.setattr(http_client, "_timeout", http_client.DEFAULT_TIMEOUT)
+    transport = RecordingTransport()
+    with http_client.use_transport(transport):
+        http_client.configure_timeout(7)
+        http_client.get("https://example.com/a")
+        http_client.get("https://example.com/b", timeout=3)
+        with cancellation.operation(timeout=2):
+            http_client.get("https://example.com/c")
+        with cancellation.operation() as token:
+            token.cancel()
+            with pytest.raises(CphError) as e:
+                http_client.get("https://example.com/d")
+            assert e.value.kind == CANCELLED
+    assert transport.timeouts[:2] == [7, 3]
+    assert 0 < transport.timeouts[2] <= 2
+    assert len(transport.timeouts) == 3
+    with pytest.raises(ValueError):
+        http_client.configure_timeout(-1)
+
+def test_http_timeout_has_its_own_kind():
+    with http_client.use_transport(RecordingTransport(error=TimeoutError("timed out"))):
+        with pytest.raises(CphError) as e:
+            http_client.get("https://example.com/slow", timeout=1)
+    assert e.value.kind == "http_timeout"
+
+def test_run_blocking_cancels_the_thread_side():
+    started, release = threading.Event(), threading.Event()
+    def work():
+        started.set()
+        release.wait(5)
+        return cancellation.current().cancelled
+    async def scenario():
+        with cancellation.operation() as token:
+            task = asyncio.ensure_future(run_blocking(work))
+            while not started.is_set():
+                await asyncio.sleep(0.01)
+            task.cancel()
+            with pytest.raises(asyncio.CancelledError):
+                await task
+            release.set()
+            return token
+    assert asyncio.run(scenario()).cancelled
+
+def test_open_reports_partial_progress_on_timeout(tmp_path, capsys):
+    class Files:
+        def prepare_problem_files(self, *args):
+            return []
+        def get_problem_files(self, *args):
+            return tmp_path, tmp_path / "test"
+    class Env:
+        def adjust_containers(self, *args):
+            return []
+    cmd = CommandOpen(Files(), None, Env())
+    cmd.confirm_overwrite = lambda *args: True
+    cmd.should_download = lambda test_dir: True
+    def slow(url, test_dir):
+        raise CphError("120秒以内に終わらなかったため中止しました", kind=OPERATION_TIMEOUT)
+    cmd.download_keeping_own_cases = slow
+    with pytest.raises(CphError):
+        asyncio.run(cmd.open("abc300", "a", "python"))
+    out = capsys.readouterr().out
+    assert "[open] サンプルの取得の途中で中断しました（完了: 問題ファイルの準備、コンテナの準備）" in out
+    assert "open abc300 a --refresh" in out
@@ -0,0 +1,70 @@
+import asyncio
+import json
+import pytest
+from src.commands.case_anonymizer import CaseAnonymizer
+from src.commands.command_case import CommandCase
+from src.commands.command_share import CommandShare
+from src.error_hints import CphError
+
+# 名前の長さの合計を数える参照解（伏せた入力から期待出力を作り直す）
+REFERENCE = "import sys\nprint(sum(len(w) for w in sys.stdin.read().split()[1:]))\n"
+
+def write_case(tmp_path, config):
+    current = tmp_path / "contest_current"
+    (current / "test").mkdir(parents=True, exist_ok=True)
+    (current / "test" / "sample-1.in").write_text("2\nalice bob\n", encoding="utf-8")
+    (current / "test" / "sample-1.out").write_text("8\n", encoding="utf-8")
+    (current / "test" / "sample-2.in").write_text("1\ncarol\n", encoding="utf-8")
+    (current / "test" / "sample-2.out").write_text("5\n", encoding="utf-8")
+    (current / "config.json").write_text(json.dumps({"test": {"anonymize": config}}), encoding="utf-8")
+    (tmp_path / "ref.py").write_text(REFERENCE, encoding="utf-8")
+
+def test_scrub_masks_and_replaces():
+    anonymizer = CaseAnonymizer({"alice": None, "tok_[0-9a-f]+": "TOKEN"})
+    assert anonymizer.scrub("alice tok_beef bob\n") == ("xxxxx TOKEN bob\n", 2)
+    assert anonymizer.transform("bob\n", "3\n") == ("bob\n", "3\n", 0)
+    assert anonymizer.transform("alice\n", "5\n") == ("xxxxx\n", None, 1)
+    with pytest.raises(ValueError):
+        CaseAnonymizer({"(": None})
+    with pytest.raises(ValueError):
+        CaseAnonymizer.from_config({}).require()
+
+def test_reference_regenerates_expected(tmp_path):
+    (tmp_path / "ref.py").write_text(REFERENCE, encoding="utf-8")
+    anonymizer = CaseAnonymizer({"alice|bob": "u"}, reference="python3 ref.py", cwd=str(tmp_path))
+    assert anonymizer.transform("2\nalice bob\n", "8\n") == ("2\nu u\n", "2\n", 2)
+    failing = CaseAnonymizer({"alice": "u"}, reference="python3 -c 'import sys; sys.exit(1)'")
+    with pytest.raises(CphError) as e:
+        failing.transform("alice\n")
+    assert e.value.kind == "anonymize_reference_failed"
+
+def test_case_export_anonymized_bundle(tmp_path):
+    write_case(tmp_path, {"patterns": {"alice|bob": "u"}, "reference": "python3 ref.py"})
+    out = tmp_path / "bundle"
+    exported = asyncio.run(CommandCase(options={"output": str(out), "anonymize": True}, args=["export", "sample-1", "sample-2"]).run())
+    assert sorted(p.rsplit("/", 1)[-1] for p in exported) == ["sample-1.in", "sample-1.out", "sample-2.in", "sample-2.out"]
+    assert (out / "sample-1.in").read_text(encoding="utf-8") == "2\nu u\n"
+    assert (out / "sample-1.out").read_text(encoding="utf-8") == "2\n"
+    # 伏せる箇所が無いケースは期待出力をそのまま使う
+    assert (out / "sample-2.out").read_text(encoding="utf-8") == "5\n"
+
+def test_case_export_without_reference_drops_expected(tmp_path, capsys):
+    write_case(tmp_path, {"patterns": {"alice": None}})
+    out = tmp_path / "bundle"
+    asyncio.run(CommandCase(options={"output": str(out), "anonymize": True}, args=["export", "sample-1"]).run())
+    assert (out / "sample-1.in").read_text(encoding="utf-8") == "2\nxxxxx bob\n"
+    assert not (out / "sample-1.out").exists()
+    assert "期待出力は書き出しません" in capsys.readouterr().out
+
+def test_share_anonymizes_case(tmp_path):
+    write_case(tmp_path, {"patterns": {"carol": "u"}, "reference": "python3 ref.py"})
+    (tmp_path / "contest_current" / "python").mkdir(exist_ok=True)
+    (tmp_path / "contest_current" / "python" / "main.py").write_text("print(1)\n", encoding="utf-8")
+    class Service:
+        name = "fake"
+        def upload(self, files, description=""):
+            self.files = files
+            return "https://paste.example/1"
+    service = Service()
+    asyncio.run(CommandShare(options={"case": "sample-2", "anonymize": True}, service=service).share("abc300", "a", "python"))
+    assert service.files == {"main.py": "print(1)\n", "sample-2.in": "1\nu\n", "sample-2.out": "1\n"}
@@ -0,0 +1,98 @@
+import asyncio
+import json
+import time
+import pytest
+from src.commands import command_test
+from src.commands.case_budget import BUDGET_EXHAUSTED, CaseBudget, WallTimeLimit
+from src.commands.command_test import SKIPPED_VERDICT, CommandTest
+from src.environment.execution_manager_test_environment import ExecutionManagerTestEnvironment
+from src.execution_client.execution_manager import ExecutionManager
+from src.execution_client.local.client import LocalAsyncClient
+from src.output_limit import OutputLimit
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+class LocalEnv:
+    uses_containers = False
+
+    def to_container_path(self, path):
+        return path
+
+class Config:
+    def __init__(self, test, time_limit=1.0):
+        self.test = test
+        self.time_limit = time_limit
+
+    def get_test(self):
+        return self.test
+
+    def get_time_limit(self):
+        return self.time_limit
+
+def test_budget_prorates_remaining_time_across_cases():
+    clock = [0.0]
+    budget = CaseBudget(60, 4, clock=lambda: clock[0])
+    assert budget.start_case() == 15.0
+    # 早く終わった分は残りのケースに回る
+    clock[0] = 3.0
+    assert budget.start_case() == 19.0
+    clock[0] = 50.0
+    assert budget.start_case() == 5.0
+    clock[0] = 60.0
+    assert budget.start_case() is None
+
+def test_budget_share_respects_jobs_and_time_limit_floor():
+    budget = CaseBudget(60, 8, jobs=4, clock=lambda: 0.0)
+    assert budget.start_case() == 30.0
+    # TLの2倍を下限にするが、残りの予算は超えない
+    assert CaseBudget(10, 100, time_limit=2.0, clock=lambda: 0.0).start_case() == 4.0
+    assert CaseBudget(3, 100, time_limit=2.0, clock=lambda: 0.0).start_case() == 3.0
+
+def test_budget_from_config():
+    assert CaseBudget.from_config({}, 3) is None
+    assert CaseBudget.from_config({"total_budget_seconds": 30}, 3).total == 30.0
+    with pytest.raises(ValueError):
+        CaseBudget(-1, 3)
+
+def test_wall_time_limit_wraps_solution():
+    assert WallTimeLimit(1.5).wrap(["python3", "main.py"]) == ["timeout", "--signal=KILL", "1.500", "python3", "main.py"]
+
+def test_total_budget_marks_unstarted_cases_skipped(monkeypatch, capsys):
+    clock = [0.0]
+    monkeypatch.setattr(command_test.time, "perf_counter", lambda: clock[0])
+    cmd = CommandTest(None, LocalEnv(), options={"jobs": "1"})
+    monkeypatch.setattr(cmd, "get_config", lambda: Config({"total_budget_seconds": 10}))
+    monkeypatch.setattr(cmd, "build_in_container", lambda *a: (True, "", ""))
+    limits = []
+    def run_single_case(ctl, container, in_file, language_name, source_path, i, total, wall_limit=None):
+        limits.append(wall_limit)
+        clock[0] += 4.0
+        return {"name": in_file, "result": (0, "", ""), "expected": "", "time": 4.0, "verdict": "AC"}
+    monkeypatch.setattr(cmd, "run_single_case", run_single_case)
+    files = ["sample-1.in", "sample-2.in", "sample-3.in", "large-1.in"]
+    results = asyncio.run(cmd.run_test_cases("main.py", files, "python"))
+    assert limits == [2.5, 2.0, 2.0]
+    assert [r["verdict"] for r in results] == ["AC", "AC", "AC", SKIPPED_VERDICT]
+    assert results[-1]["skip_reason"] == BUDGET_EXHAUSTED
+    cmd.print_test_results(results)
+    out = capsys.readouterr().out
+    assert "test.total_budget_seconds（10秒）を使い切ったため 1/4 ケースは Skipped(BudgetExhausted) です: large-1.in" in out
+
+def test_budget_killed_case_is_not_retried(tmp_path):
+    # 持ち時間で止めたケースをやり直すと、予算の数倍の時間がかかる
+    source = tmp_path / "main.py"
+    source.write_text("import time\ntime.sleep(30)\n")
+    files = []
+    for name in ("a", "b"):
+        (tmp_path / f"{name}.in").write_text("1\n")
+        (tmp_path / f"{name}.out").write_text("1\n")
+        files.append(str(tmp_path / f"{name}.in"))
+    env = ExecutionManagerTestEnvironment(None, ExecutionManager(LocalAsyncClient()))
+    env.solution_env = {}
+    env.output_limit = OutputLimit.from_config({})
+    cmd = CommandTest(None, env, options={"jobs": "1"})
+    config = UnifiedPathManager().config_json()
+    config.write_text(json.dumps({**json.loads(config.read_text()), "test": {"total_budget_seconds": 1}}))
+    started = time.perf_counter()
+    results = asyncio.run(cmd.run_test_cases(str(source), files, "python"))
+    assert time.perf_counter() - started < 1.0 + 0.8
+    assert all(r["verdict"] == SKIPPED_VERDICT for r in results)
@@ -0,0 +1,85 @@
+import asyncio
+import sys
+import pytest
+from src.commands.case_check import ERROR, WARNING, CaseChecker
+from src.commands.command_case import CommandCase
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+# 1行目のNが1以上10以下でなければ失敗する検証コマンド
+VALIDATOR = "import sys\nn = int(sys.stdin.readline())\nif not 1 <= n <= 10:\n    sys.exit(f'N={n} is out of range')\n"
+
+def write_cases(test_dir, cases):
+    test_dir.mkdir(parents=True, exist_ok=True)
+    for name, content in cases.items():
+        (test_dir / name).write_text(content, encoding="utf-8")
+
+def summarize(issues):
+    return sorted((i.level, i.case, i.message) for i in issues)
+
+def test_check_reports_missing_orphaned_empty_and_duplicates(tmp_path):
+    write_cases(tmp_path, {
+        "sample-1.in": "1 2\n", "sample-1.out": "3\n",
+        "sample-2.in": "1 2\n", "sample-2.out": "3\n",
+        "sample-3.in": "5\n",
+        "sample-4.in": "\n", "sample-4.out": "",
+        "echo-1.in": "7\n", "echo-1.out": "7\n",
+        "old-1.out": "9\n",
+    })
+    issues = summarize(CaseChecker(tmp_path).check())
+    assert (ERROR, "sample-3.in", "期待出力（.out）がありません") in issues
+    assert (ERROR, "old-1.out", "対応する入力（.in）がありません") in issues
+    assert (WARNING, "sample-2.in", "sample-1.in と同じ入力です") in issues
+    assert (WARNING, "sample-4.in", "入力が空です") in issues
+    assert (WARNING, "sample-4.out", "期待出力が空です") in issues
+    assert (WARNING, "echo-1.in", "期待出力が入力と同じ内容です（コピーの取り違え？）") in issues
+    assert not any(case.startswith("sample-1") for _, case, _ in issues)
+
+def test_check_counts_alternative_expected_outputs(tmp_path):
+    write_cases(tmp_path, {
+        "sample-1.in": "4\n", "sample-1.out": "1 3\n", "sample-1.a.out": "3 1\n",
+        "sample-2.in": "6\n", "sample-2.a.out": "1 5\n", "sample-2.b.out": "5 1\n",
+        "sample-9.a.out": "0\n",
+    })
+    issues = summarize(CaseChecker(tmp_path).check())
+    assert issues == [(ERROR, "sample-9.a.out", "対応する入力（.in）がありません")]
+
+def test_check_accepts_case_names_with_a_letter_suffix(tmp_path):
+    # x.b.in の期待出力 x.b.out は、xのもう一つの期待出力ではない
+    write_cases(tmp_path, {"x.b.in": "2\n", "x.b.out": "2 \n"})
+    assert CaseChecker(tmp_path).check() == []
+
+def test_check_respects_ignore_patterns(tmp_path):
+    write_cases(tmp_path, {"notes.out": "memo\n", "sample-1.in": "1\n", "sample-1.out": "1 \n"})
+    assert CaseChecker(tmp_path, {"ignore": ["notes*"]}).check() == []
+
+def test_check_runs_validator_on_inputs(tmp_path):
+    (tmp_path / "validator.py").write_text(VALIDATOR, encoding="utf-8")
+    write_cases(tmp_path / "test", {"sample-1.in": "3\n", "sample-1.out": "x\n", "large-1.in": "11\n", "large-1.out": "y\n"})
+    checker = CaseChecker(tmp_path / "test", {"validator": f"{sys.executable} validator.py"}, cwd=str(tmp_path))
+    issues = summarize(checker.check())
+    assert issues == [(ERROR, "large-1.in", "制約を満たしていません（N=11 is out of range）")]
+
+def test_case_check_command_fails_on_errors(tmp_path, capsys):
+    upm = UnifiedPathManager(str(tmp_path))
+    test_dir = tmp_path / "contest_current" / "test"
+    write_cases(test_dir, {"mine-1.in": "1\n"})
+    with pytest.raises(CphError) as e:
+        asyncio.run(CommandCase(args=["check"], upm=upm).run())
+    assert e.value.kind == "case_check_failed"
+    assert "[case] エラー: mine-1.in: 期待出力（.out）がありません" in capsys.readouterr().out
+    write_cases(test_dir, {"mine-1.out": "2\n"})
+    assert asyncio.run(CommandCase(args=["check"], upm=upm).run()) == []
+
+def test_test_runs_check_when_enabled(tmp_path, capsys):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    workspace = setup_test_environment(tmp_path / "ws", config={"test": {"check": True}})
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.path("contest_current", "test", "stray-1.out").write_text("3\n", encoding="utf-8")
+        workspace.write_source("python", "a, b = map(int, input().split())\nprint(a + b)\n")
+        results = sim.execute("test", "abc300", "a", "python")
+    assert [r["verdict"] for r in results] == ["AC"]
+    assert "[test] エラー: stray-1.out: 対応する入力（.in）がありません" in capsys.readouterr().out
@@ -0,0 +1,71 @@
+import os
+import asyncio
+from src.commands.case_lint import lint_cases, lint_expected_file, normalize, suspicious
+from src.commands.command_case import CommandCase
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+def test_normalize_line_endings_and_final_newline():
+    assert normalize("1\r\n2\r\n") == "1\n2\n"
+    assert normalize("1\r2") == "1\n2\n"
+    assert normalize("3") == "3\n"
+    assert normalize("") == ""
+    # 末尾の空行は直さない（指摘する）
+    assert normalize("3\n\n") == "3\n\n"
+
+def test_suspicious_reports_whitespace():
+    assert suspicious("1 2\n3\n") == []
+    issues = suspicious("a\tb\nc \nd　e\n\n")
+    assert "1行目にタブがあります" in issues
+    assert "2行目の末尾に空白があります" in issues
+    assert "3行目に全角スペースがあります" in issues
+    assert "末尾に空行があります" in issues
+    assert suspicious("\nx\n") == ["先頭が空行です"]
+    assert suspicious("".join(f"{i} \n" for i in range(5))) == ["1, 2, 3行目ほか2行の末尾に空白があります"]
+
+def test_lint_expected_file_fixes_and_dry_run(tmp_path):
+    path = tmp_path / "a.out"
+    path.write_bytes(b"1\r\n2 ")
+    assert lint_expected_file(str(path), fix=False) == (False, ["2行目の末尾に空白があります"])
+    assert path.read_bytes() == b"1\r\n2 "
+    assert lint_expected_file(str(path)) == (True, ["2行目の末尾に空白があります"])
+    assert path.read_bytes() == b"1\n2 \n"
+
+def test_lint_cases_only_touches_expected(tmp_path, capsys):
+    (tmp_path / "s-1.in").write_bytes(b"1\t2\r\n")
+    (tmp_path / "s-1.out").write_bytes(b"3\r\n")
+    (tmp_path / "s-2.out").write_text("x\t\n", encoding="utf-8")
+    found = lint_cases(str(tmp_path))
+    assert list(found) == ["s-2.out"]
+    assert (tmp_path / "s-1.in").read_bytes() == b"1\t2\r\n"
+    assert (tmp_path / "s-1.out").read_bytes() == b"3\n"
+    out = capsys.readouterr().out
+    assert "s-1.out: 改行をLFにそろえ" in out and "s-2.out: 1行目にタブがあります" in out
+    assert lint_cases(str(tmp_path / "missing")) == {}
+
+def test_lint_cases_checks_subtask_groups(tmp_path, capsys):
+    (tmp_path / "group_1").mkdir()
+    (tmp_path / "group_1" / "sample-1-1.out").write_bytes(b"3\r\n")
+    (tmp_path / "group_1" / "sample-1-2.out").write_text("x \n", encoding="utf-8")
+    found = lint_cases(str(tmp_path))
+    assert list(found) == [os.path.join("group_1", "sample-1-2.out")]
+    assert (tmp_path / "group_1" / "sample-1-1.out").read_bytes() == b"3\n"
+    assert "group_1/sample-1-1.out: 改行をLFにそろえ" in capsys.readouterr().out
+
+def test_case_lint_command(tmp_path):
+    test_dir = tmp_path / "contest_current" / "test"
+    test_dir.mkdir(parents=True, exist_ok=True)
+    (test_dir / "mine-1.out").write_bytes(b"ok ")
+    command = CommandCase(options={"dry-run": True}, args=["lint"], upm=UnifiedPathManager(str(tmp_path)))
+    found = asyncio.run(command.run())
+    assert found["mine-1.out"] == ["1行目の末尾に空白があります"]
+    assert (test_dir / "mine-1.out").read_bytes() == b"ok "
+
+def test_open_lints_downloaded_samples(tmp_path, capsys):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\r\n"), ("1\n", "x \n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+    assert workspace.path("contest_current", "test", "sample-1.out").read_bytes() == b"3\n"
+    assert "sample-2.out: 1行目の末尾に空白があります" in capsys.readouterr().out
@@ -0,0 +1,82 @@
+import asyncio
+import shutil
+import pytest
+from src.commands.case_storage import (
+    case_input, case_name, compress_case, compress_if_large, expected_file, read_case,
+)
+from src.commands.command_case import CommandCase
+from src.commands.command_test import CommandTest
+from src.command_parser import CommandParser
+from src.shell import Shell, CommandSpec
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+pytestmark = pytest.mark.skipif(shutil.which("zstd") is None, reason="zstdコマンドが無い")
+
+LARGE_INPUT = "".join(f"{i}\n" for i in range(20000))
+
+def test_compress_if_large(tmp_path):
+    small = tmp_path / "small.in"
+    small.write_text("1\n", encoding="utf-8")
+    large = tmp_path / "large.in"
+    large.write_text(LARGE_INPUT, encoding="utf-8")
+    assert compress_if_large(small, threshold=1024) == str(small)
+    dest = compress_if_large(large, threshold=1024)
+    assert dest == str(large) + ".zst" and not large.exists()
+    assert read_case(dest) == LARGE_INPUT
+    assert case_name(dest) == "large.in"
+
+def test_case_input_streams_to_child(tmp_path):
+    path = tmp_path / "large.in"
+    path.write_text(LARGE_INPUT, encoding="utf-8")
+    dest = compress_case(path)
+    with case_input(dest) as stdin:
+        assert not isinstance(stdin, str)
+        result = Shell().run(CommandSpec("python3", ["-c", "import sys; print(sum(map(int, sys.stdin.read().split())))"], stdin=stdin))
+    assert result.ok and result.stdout.strip() == str(sum(range(20000)))
+
+def test_collect_and_read_compressed_expected(tmp_path):
+    (tmp_path / "sample-1.in").write_text("1\n", encoding="utf-8")
+    (tmp_path / "sample-1.out").write_text("1\n", encoding="utf-8")
+    (tmp_path / "large-1.in").write_text(LARGE_INPUT, encoding="utf-8")
+    (tmp_path / "large-1.out").write_text("ok\n", encoding="utf-8")
+    compress_case(tmp_path / "large-1.in")
+    compress_case(tmp_path / "large-1.out")
+    cmd = CommandTest(None, None)
+    in_files, out_files = cmd.collect_test_cases(str(tmp_path))
+    assert [case_name(f) for f in in_files] == ["large-1.in", "sample-1.in"]
+    assert out_files[0] == expected_file(in_files[0]) and out_files[0].endswith("large-1.out.zst")
+    assert cmd.read_expected(in_files[0]) == "ok\n"
+    assert cmd.read_expected(in_files[1]) == "1\n"
+
+def test_test_command_runs_compressed_case(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "import sys\nprint(sum(map(int, sys.stdin.read().split())))\n")
+        workspace.write_case("large-1", LARGE_INPUT, f"{sum(range(20000))}\n")
+        compress_case(workspace.path("contest_current", "test", "large-1.in"))
+        results = sim.execute("test", "abc300", "a", "python")
+    assert {r["name"]: r["verdict"] for r in results} == {"large-1.in": "AC", "sample-1.in": "AC"}
+
+def test_case_export_and_compress(tmp_path):
+    test_dir = tmp_path / "contest_current" / "test"
+    test_dir.mkdir(parents=True, exist_ok=True)
+    (test_dir / "large-1.in").write_text(LARGE_INPUT, encoding="utf-8")
+    (test_dir / "sample-1.in").write_text("1\n", encoding="utf-8")
+    compressed = asyncio.run(CommandCase(options={"threshold": "1024"}, args=["compress"]).run())
+    assert [case_name(p) for p in compressed] == ["large-1.in"]
+    assert (test_dir / "large-1.in.zst").exists() and (test_dir / "sample-1.in").exists()
+    out = tmp_path / "exported"
+    exported = asyncio.run(CommandCase(options={"output": str(out)}, args=["export", "large-1"]).run())
+    assert exported == [str(out / "large-1.in")]
+    assert (out / "large-1.in").read_text(encoding="utf-8") == LARGE_INPUT
+    with pytest.raises(FileNotFoundError):
+        asyncio.run(CommandCase(args=["export", "missing"]).run())
+
+def test_parser_keeps_case_action():
+    parser = CommandParser()
+    parser.parse(["case", "export", "large-1", "--output", "out"])
+    assert parser.parsed["command"] == "case"
+    assert parser.extra_args == ["export", "large-1"]
@@ -0,0 +1,53 @@
+import io
+import pytest
+from src.commands import color_theme
+from src.commands.color_theme import Palette, color_enabled, configure
+from src.commands.test_result_formatter import ResultFormatter
+
+class Tty(io.StringIO):
+    def isatty(self):
+        return True
+
+def test_color_enabled_modes_and_env():
+    assert color_enabled("always", io.StringIO(), {"NO_COLOR": "1"}) is True
+    assert color_enabled("never", Tty(), {}) is False
+    assert color_enabled("auto", Tty(), {}) is True
+    assert color_enabled("auto", io.StringIO(), {}) is False
+    assert color_enabled("auto", Tty(), {"NO_COLOR": "1"}) is False
+    assert color_enabled("auto", Tty(), {"NO_COLOR": ""}) is True
+    assert color_enabled("auto", Tty(), {"CLICOLOR": "0"}) is False
+    assert color_enabled("auto", io.StringIO(), {"CLICOLOR_FORCE": "1"}) is True
+    with pytest.raises(ValueError):
+        color_enabled("sometimes", Tty(), {})
+
+def test_palette_themes():
+    assert Palette("default").paint("AC", "AC") == "\033[32mAC\033[0m"
+    assert Palette("high-contrast").paint("WA", "WA").startswith("\033[1;91m")
+    assert Palette("monochrome").paint("WA", "WA").startswith("\033[7m")
+    assert Palette("default", enabled=False).paint("AC", "AC") == "AC"
+    with pytest.raises(ValueError):
+        Palette("neon")
+
+def test_diff_line_roles():
+    p = Palette("default")
+    assert p.diff_line("+added").startswith("\033[31m")
+    assert p.diff_line("-removed").startswith("\033[32m")
+    assert p.diff_line("@@ -1 +1 @@").startswith("\033[36m")
+    assert p.diff_line(" same") == " same"
+
+def make_result(stdout, expected):
+    return {"name": "c", "result": (0, stdout, ""), "expected": expected, "time": 0.1}
+
+def test_formatter_uses_palette():
+    plain = ResultFormatter(make_result("2\n", "1\n"), width=80, palette=Palette(enabled=False)).format()
+    assert "\033[" not in plain
+    colored = ResultFormatter(make_result("2\n", "1\n"), width=80, palette=Palette("monochrome")).format()
+    assert "\033[7mWA\033[0m" in colored
+    assert "\033[1m2     \033[0m" in colored  # 期待と違う出力の行
+
+def test_configure_sets_global_palette(monkeypatch):
+    monkeypatch.setattr(color_theme, "_palette", None)
+    palette = configure("always", "monochrome")
+    assert color_theme.current() is palette
+    assert palette.enabled and palette.theme == "monochrome"
+    assert ResultFormatter(make_result("1\n", "1\n")).palette is palette
@@ -0,0 +1,87 @@
+import asyncio
+import pytest
+from src import events, http_client
+from src.commands import command_import
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_archive import CommandArchive, belongs_to
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def fake_get_json(submissions):
+    def get_json(url, params=None, timeout=None):
+        if url == command_import.ATCODER_PROBLEMS_SUBMISSIONS:
+            return submissions
+        return {}
+    return get_json
+
+def test_belongs_to_site():
+    assert belongs_to("atcoder")({"contest": "abc300"})
+    assert not belongs_to("atcoder")({"contest": "cf1850"})
+    assert belongs_to("codeforces")({"contest": "cf1850"})
+    # AOJなど、形で分からないコンテストは取得した結果に載っているときだけ
+    assert not belongs_to("atcoder")({"contest": "aoj_itp1"})
+    assert belongs_to("atcoder", {"typical90"})({"contest": "typical90"})
+    assert belongs_to("atcoder")({"contest": "practice2", "source": "atcoder"})
+
+def test_sync_verdicts_updates_archived_problems(tmp_path, monkeypatch, capsys):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    index = ArchiveIndex(upm=upm)
+    # cphから提出して結果未確認のもの・サイトから直接提出したもの・提出していないもの
+    index.record_submission("abc300", "a", "python")
+    index.set_tags("abc300", "b", add=["dp"])
+    index.update("abc300", "c", lambda e: e.update(status="WA"))
+    index.update("cf1850", "a", lambda e: e.update(status="AC"))
+    index.update("aoj_itp1", "1_a", lambda e: e.update(status="AC"))
+    index.update("typical90", "b", lambda e: e.update(status="WA"))
+    monkeypatch.setattr(http_client, "get_json", fake_get_json([
+        {"contest_id": "abc300", "problem_id": "abc300_a", "result": "AC", "language": "Python", "epoch_second": 100},
+        {"contest_id": "abc300", "problem_id": "abc300_b", "result": "WA", "language": "Python", "epoch_second": 200},
+        {"contest_id": "abc300", "problem_id": "abc300_b", "result": "WJ", "language": "Python", "epoch_second": 300},
+        {"contest_id": "typical90", "problem_id": "typical90_b", "result": "AC", "language": "Python", "epoch_second": 350},
+        # 索引に無い問題は増やさない
+        {"contest_id": "abc301", "problem_id": "abc301_a", "result": "AC", "language": "Python", "epoch_second": 400},
+    ]))
+    monkeypatch.setattr(events, "_bus", events.EventBus())
+    accepted = []
+    events.subscribe(events.SUBMISSION_ACCEPTED, accepted.append)
+    handler = CommandArchive({"user": "me"}, upm=upm, index=index)
+    handler.args = ["sync-verdicts"]
+    changed = asyncio.run(handler.run())
+    assert {(e["contest"], e["problem"]): previous for e, previous in changed} == {
+        ("abc300", "a"): "submitted", ("abc300", "b"): None, ("typical90", "b"): "WA"}
+    assert index.get("abc300", "a")["status"] == "AC"
+    assert index.get("abc300", "b")["status"] == "submitted"
+    # 取得した結果に載っていない問題（まだ集計されていない提出など）は変えない
+    assert index.get("abc300", "c")["status"] == "WA"
+    assert index.get("cf1850", "a")["status"] == "AC"
+    assert index.get("aoj_itp1", "1_a")["status"] == "AC"
+    assert index.get("abc301", "a") is None
+    # 新しくACになった問題だけをイベントで知らせる
+    assert [(e.contest, e.problem, e.languages) for e in accepted] == [("abc300", "a", "python"), ("typical90", "b", "")]
+    out = capsys.readouterr().out
+    assert "abc300 a: submitted → AC" in out
+    assert "abc300 c" not in out
+    assert "4問の結果を確認しました（変更 3問、AC 2問）" in out
+
+def test_sync_verdicts_keeps_submission_not_yet_crawled(tmp_path, monkeypatch):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    index = ArchiveIndex(upm=upm)
+    index.record_submission("abc300", "a", "python")
+    index.update("abc300", "b", lambda e: e.update(status="AC"))
+    monkeypatch.setattr(http_client, "get_json", fake_get_json([
+        {"contest_id": "abc300", "problem_id": "abc300_b", "result": "AC", "language": "Python", "epoch_second": 100},
+    ]))
+    handler = CommandArchive({"user": "me"}, upm=upm, index=index)
+    handler.args = ["sync-verdicts"]
+    assert asyncio.run(handler.run()) == []
+    assert index.get("abc300", "a")["status"] == "submitted"
+
+def test_sync_verdicts_requires_subcommand_and_user(tmp_path):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    index = ArchiveIndex(upm=upm)
+    index.record_submission("abc300", "a", "python")
+    handler = CommandArchive({}, upm=upm, index=index)
+    with pytest.raises(ValueError):
+        asyncio.run(handler.run())
+    handler.args = ["sync-verdicts"]
+    with pytest.raises(ValueError):
+        asyncio.run(handler.run())
@@ -0,0 +1,61 @@
+import asyncio
+import json
+from datetime import datetime, timedelta
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_badge import CommandBadge, render_svg, run_verdict, solved_within
+from src.commands.run_artifacts import RunArtifacts
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def make_result(name, verdict):
+    return {"name": name, "result": (0, "", ""), "time": 0.1, "verdict": verdict}
+
+def svg_width(svg):
+    return int(svg.split('width="', 1)[1].split('"')[0])
+
+def make_badge(tmp_path, options=None):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    return CommandBadge(options or {}, upm=upm, index=ArchiveIndex(upm=upm))
+
+def test_run_verdict_ignores_skipped_cases():
+    assert run_verdict({"cases": [{"verdict": "AC"}, {"verdict": "SKIP"}]}) == "AC"
+    assert run_verdict({"cases": [{"verdict": "AC"}, {"verdict": "TLE"}, {"verdict": "WA"}]}) == "TLE"
+    assert run_verdict({"cases": [{"verdict": "SKIP"}]}) is None
+
+def test_solved_within_counts_recent_ac_only():
+    now = datetime(2024, 5, 10)
+    entries = [
+        {"contest": "abc300", "problem": "a", "status": "AC", "submitted_at": "2024-05-09T10:00:00"},
+        {"contest": "abc300", "problem": "b", "status": "AC", "submitted_at": "2024-04-01T10:00:00"},
+        {"contest": "abc300", "problem": "c", "status": "WA", "submitted_at": "2024-05-09T11:00:00"},
+        {"contest": "abc300", "problem": "d", "status": "AC"},
+    ]
+    solved = solved_within(entries, now - timedelta(days=7))
+    assert [e["problem"] for e in solved] == ["a"]
+
+def test_render_svg_escapes_and_sizes_by_display_width():
+    svg = render_svg("last verdict", "WA (a<b)", "#e05d44")
+    assert "a&lt;b" in svg and 'fill="#e05d44"' in svg
+    assert svg_width(render_svg("x", "ああ", "#000")) == svg_width(render_svg("x", "aaaa", "#000"))
+
+def test_badge_writes_json_and_svg(tmp_path, capsys):
+    badge = make_badge(tmp_path)
+    badge.index.record_submission("abc300", "a", "python", status="AC")
+    badge.index.record_submission("abc300", "b", "python")
+    RunArtifacts(run_id="run1", project_root=tmp_path).save_results([make_result("sample-1.in", "AC")], contest_name="abc300", problem_name="a", language_name="python")
+    RunArtifacts(run_id="run2", project_root=tmp_path).save_results(
+        [make_result("sample-1.in", "AC"), make_result("sample-2.in", "WA")], contest_name="abc300", problem_name="b", language_name="python")
+    summary = asyncio.run(badge.badge())
+    assert summary["solved"] == 1 and summary["solved_problems"] == ["abc300/a"]
+    assert summary["last_verdict"]["verdict"] == "WA" and summary["last_verdict"]["problem"] == "b"
+    out = tmp_path / "badges"
+    assert json.loads((out / "cph.json").read_text(encoding="utf-8"))["solved"] == 1
+    assert "WA (abc300 b)" in (out / "verdict.svg").read_text(encoding="utf-8")
+    assert ">1</text>" in (out / "solved.svg").read_text(encoding="utf-8")
+    assert "直近7日のAC: 1問" in capsys.readouterr().out
+
+def test_badge_without_history_and_custom_output(tmp_path):
+    badge = make_badge(tmp_path, {"output": "docs/img", "days": "30"})
+    summary = asyncio.run(badge.badge())
+    assert summary["solved"] == 0 and summary["last_verdict"] is None and summary["days"] == 30
+    svg = (tmp_path / "docs" / "img" / "verdict.svg").read_text(encoding="utf-8")
+    assert ">-</text>" in svg and "solved (30d)" in (tmp_path / "docs" / "img" / "solved.svg").read_text(encoding="utf-8")
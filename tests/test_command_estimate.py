@@ -0,0 +1,64 @@
+import pytest
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_estimate import evaluate_bound, fit_growth, growth_label, parse_max_n, predict_time
+from src.error_hints import CphError
+from src.testkit import AtCoderMock, ContestSimulation, atcoder_task_url, setup_test_environment
+
+GENERATOR = "import sys\nprint(sys.argv[1])\n"
+# 大きさnに対してn^2 / nに比例する時間だけ眠る解答
+QUADRATIC = "import time\nn = int(input())\ntime.sleep(n * n * 1e-7)\nprint(n)\n"
+LINEAR = "import time\nn = int(input())\ntime.sleep(n * 1e-4)\nprint(n)\n"
+
+def statement(constraints):
+    return f'<html><body><div id="task-statement"><h3>制約</h3><ul><li><var>{constraints}</var></li></ul></div></body></html>'
+
+def test_parse_max_n_from_constraints():
+    assert parse_max_n(r"1 \leq N \leq 2 \times 10^5") == 200000
+    assert parse_max_n("1≦N,M≦10^{5}") == 100000
+    assert parse_max_n("1 ≤ Q ≤ 100 1 ≤ N ≤ 200,000") == 200000
+    assert parse_max_n(r"1 \le M \le 10^9", "M") == 10 ** 9
+    assert parse_max_n(r"1 \le NN \le 10") is None
+    assert evaluate_bound("3 * 10 ^ 4") == 30000
+
+def test_fit_growth_recovers_exponent():
+    fit = fit_growth([(1000, 0.1), (2000, 0.4), (4000, 1.6)])
+    assert fit["exponent"] == pytest.approx(2)
+    assert predict_time(fit, 8000) == pytest.approx(6.4)
+    assert growth_label(fit["exponent"]) == "O(N^2)"
+    # 起動の時間に埋もれる短い実行しか無ければ見積もらない
+    assert fit_growth([(10, 0.01), (100, 0.01), (1000, 0.2)]) is None
+
+def setup(tmp_path, solution, constraints=r"1 \le N \le 2 \times 10^5"):
+    workspace = setup_test_environment(tmp_path / "ws", config={"estimate": {"generator": "python3 gen.py {n}", "sizes": [500, 1000, 2000]}})
+    site = AtCoderMock(routes={atcoder_task_url("abc300", "a"): statement(constraints)})
+    site.add_problem("abc300", "a", [("1\n", "1\n")])
+    return workspace, site, solution
+
+def run_estimate(tmp_path, solution, options=None):
+    workspace, site, solution = setup(tmp_path, solution)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", solution)
+        workspace.path("contest_current", "gen.py").write_text(GENERATOR, encoding="utf-8")
+        result = sim.execute("estimate", "abc300", "a", "python", options=options)
+        entry = ArchiveIndex().get("abc300", "a")
+    return result, entry
+
+def test_estimate_warns_quadratic_growth_at_max_n(tmp_path, capsys):
+    result, entry = run_estimate(tmp_path, QUADRATIC)
+    assert entry["max_n"] == 200000
+    assert result["tle"] and result["fit"]["exponent"] > 1.5
+    out = capsys.readouterr().out
+    assert "N ≦ 200000 を読みました" in out
+    assert "警告: N=200000では" in out
+
+def test_estimate_passes_linear_growth_with_max_n_option(tmp_path, capsys):
+    result, entry = run_estimate(tmp_path, LINEAR, options={"max-n": "4e3"})
+    assert entry["max_n"] == 4000
+    assert not result["tle"] and result["predicted"] < 2
+    assert "N=4000では約" in capsys.readouterr().out
+
+def test_estimate_reports_crash(tmp_path):
+    with pytest.raises(CphError) as e:
+        run_estimate(tmp_path, "raise SystemExit(1)\n", options={"seed": "7"})
+    assert e.value.kind == "estimate_crashed" and "--seed 7" in str(e.value)
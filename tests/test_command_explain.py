@@ -0,0 +1,71 @@
+import asyncio
+import pytest
+from src.commands.command_explain import CommandExplain, explain_difference, formatting_difference
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+def test_explain_difference_finds_first_line_and_token():
+    analysis = explain_difference("1\n2\n3\n4\n", "1\n2\n5\n4\n")
+    assert analysis["line"] == 3
+    assert analysis["token"] == 3
+    assert (analysis["expected_token"], analysis["actual_token"]) == ("3", "5")
+    assert analysis["context"] == [(1, "1", "1"), (2, "2", "2"), (3, "3", "5"), (4, "4", "4")]
+    assert analysis["formatting"] is None
+
+def test_explain_difference_reports_length():
+    analysis = explain_difference("1 2 3\n", "1 2\n")
+    assert (analysis["expected_tokens"], analysis["actual_tokens"]) == (3, 2)
+    assert analysis["token"] == 3
+    assert analysis["actual_token"] is None
+
+def test_formatting_only_differences():
+    assert formatting_difference("1 2\n", "1\n2\n") == "whitespace"
+    assert formatting_difference("Yes\n", "YES\n") == "yes_no"
+    assert formatting_difference("Alice\n", "alice\n") == "case"
+    assert formatting_difference("1\n", "2\n") is None
+
+def test_explain_latest_failure(tmp_path, capsys):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("2\n", "1\n2\n"), ("3\n", "1\n2\n3\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "n = int(input())\nprint(*range(1, n + 1))\n")
+        sim.execute("test", "abc300", "a", "python")
+        capsys.readouterr()
+        explain = CommandExplain()
+        analysis = asyncio.run(explain.explain("abc300", "a", "python"))
+        out = capsys.readouterr().out
+        assert analysis["formatting"] == "whitespace"
+        assert "[explain] sample-1.in: WA" in out
+        assert "空白・改行の違いだけです" in out
+        assert "TL 2秒" in out
+        explain.args = ["sample-2"]
+        assert asyncio.run(explain.explain("abc300", "a", "python"))["line"] == 1
+        explain.args = ["own-9"]
+        with pytest.raises(ValueError):
+            asyncio.run(explain.explain("abc300", "a", "python"))
+
+def test_explain_reads_grouped_case_and_alternatives(tmp_path, capsys):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("2\n", "2\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        group = workspace.path("contest_current", "test", "group_1")
+        group.mkdir()
+        (group / "g-1.in").write_text("3\n", encoding="utf-8")
+        (group / "g-1.out").write_text("3\n", encoding="utf-8")
+        (group / "g-1.a.out").write_text("three\n", encoding="utf-8")
+        (group / "g-2.in").write_text("4\n", encoding="utf-8")
+        (group / "g-2.out").write_text("4\n", encoding="utf-8")
+        workspace.write_source("python", "n = input()\nprint('three' if n == '3' else 'x')\n")
+        sim.execute("test", "abc300", "a", "python")
+        capsys.readouterr()
+        explain = CommandExplain()
+        # もう一つの期待出力に一致していれば、違いは無い
+        explain.args = ["g-1"]
+        analysis = asyncio.run(explain.explain("abc300", "a", "python"))
+        assert analysis["line"] is None and analysis["token"] is None
+        explain.args = ["g-2"]
+        analysis = asyncio.run(explain.explain("abc300", "a", "python"))
+        assert (analysis["expected_token"], analysis["actual_token"]) == ("4", "x")
@@ -0,0 +1,86 @@
+import asyncio
+from src.commands.command_fix import CommandFix
+from src.commands.opener import MockOpener, build_editor_argv, editor_command
+from src.environment.diagnostics import (
+    DiagnosticStore, parse_python_traceback, parse_rust_panic, parse_rustc_diagnostics,
+)
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+RUSTC_OUTPUT = """warning: unused variable: `y`
+ --> src/main.rs:2:9
+  |
+error[E0425]: cannot find value `x` in this scope
+ --> src/main.rs:3:20
+  |
+error: aborting due to 1 previous error
+"""
+
+def test_parse_rustc_diagnostics():
+    diagnostics = parse_rustc_diagnostics(RUSTC_OUTPUT)
+    assert [(d["level"], d["file"], d["line"], d["column"]) for d in diagnostics] == [
+        ("warning", "src/main.rs", 2, 9), ("error", "src/main.rs", 3, 20),
+    ]
+    assert diagnostics[1]["message"] == "cannot find value `x` in this scope"
+
+def test_parse_rust_panic_formats():
+    new = "thread 'main' panicked at src/main.rs:10:5:\nindex out of bounds\n"
+    old = "thread 'main' panicked at 'attempt to add with overflow', src/main.rs:7:13\n"
+    assert [(d["file"], d["line"], d["message"]) for d in parse_rust_panic(new)] == [("src/main.rs", 10, "panicked: index out of bounds")]
+    assert [(d["file"], d["line"], d["column"]) for d in parse_rust_panic(old)] == [("src/main.rs", 7, 13)]
+
+def test_parse_python_traceback_uses_innermost_frame():
+    stderr = (
+        "Traceback (most recent call last):\n"
+        '  File "/workspace/.temp/main.py", line 8, in <module>\n'
+        "    main()\n"
+        '  File "/workspace/.temp/main.py", line 5, in main\n'
+        "    print(1 // 0)\n"
+        "ZeroDivisionError: integer division or modulo by zero\n"
+    )
+    assert parse_python_traceback(stderr) == [{
+        "level": "error", "message": "ZeroDivisionError: integer division or modulo by zero",
+        "file": "/workspace/.temp/main.py", "line": 5, "column": 1,
+    }]
+    assert parse_python_traceback("") == []
+
+def test_editor_command_resolution():
+    assert editor_command("vim +{line} {file}") == "vim +{line} {file}"
+    assert editor_command(environ={"EDITOR": "nano"}) == "nano +{line} {file}"
+    assert editor_command(environ={}) == "code -g {file}:{line}:{column}"
+    assert build_editor_argv("code -g {file}:{line}:{column}", "a b/main.py", 3, 4) == ["code", "-g", "a b/main.py:3:4"]
+
+def test_fix_maps_container_path_to_current(tmp_path, capsys):
+    source = tmp_path / "contest_current" / "rust" / "src" / "main.rs"
+    source.parent.mkdir(parents=True)
+    source.write_text("fn main() {}\n", encoding="utf-8")
+    DiagnosticStore().save("rust", "build", parse_rustc_diagnostics(RUSTC_OUTPUT))
+    opener = MockOpener()
+    target = asyncio.run(CommandFix(opener=opener, args=["2"]).fix())
+    assert target["line"] == 3
+    assert opener.opened_locations == [(str(source.resolve()), 3, 20)]
+    assert "→ 2." in capsys.readouterr().out
+
+def test_fix_without_diagnostics(tmp_path, capsys):
+    opener = MockOpener()
+    assert asyncio.run(CommandFix(opener=opener).fix()) is None
+    assert opener.opened_locations == []
+    assert "エラーはありません" in capsys.readouterr().out
+
+def test_failed_test_records_runtime_error_and_auto_jumps(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws", config={"editor": {"auto_jump": True}})
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "a, b = map(int, input().split())\nprint(a // (b - 2))\n")
+        executor = sim.executor()
+        asyncio.run(executor.execute("test", "abc300", "a", "python"))
+        stored = DiagnosticStore().load()
+        opened = executor.opener.opened_locations
+        workspace.write_source("python", "print(sum(map(int, input().split())))\n")
+        asyncio.run(sim.executor().execute("test", "abc300", "a", "python"))
+        cleared = DiagnosticStore().load()
+    assert stored["stage"] == "runtime" and stored["case"] == "sample-1.in"
+    assert stored["diagnostics"][0]["line"] == 2
+    assert opened == [(str(workspace.path("contest_current", "python", "main.py").resolve()), 2, 1)]
+    assert cleared is None
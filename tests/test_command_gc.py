@@ -0,0 +1,55 @@
+import asyncio
+import json
+import os
+import tarfile
+import time
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_gc import CommandGc
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+OLD = time.time() - 60 * 24 * 60 * 60
+
+def make_problem(root, contest, problem, mtime=OLD):
+    d = root / "contest_stocks" / contest / problem / "rust"
+    (d / "target" / "release").mkdir(parents=True)
+    (d / "src").mkdir()
+    (d / "src" / "main.rs").write_text("fn main() {}\n", encoding="utf-8")
+    (d / "target" / "release" / "rust").write_bytes(b"\0" * 4096)
+    problem_dir = root / "contest_stocks" / contest / problem
+    for path in [problem_dir, *problem_dir.rglob("*")]:
+        os.utime(path, (mtime, mtime))
+    return d.parent
+
+def setup(tmp_path, options):
+    info = {"contest_name": "abc300", "problem_name": "a", "language_name": "rust"}
+    (tmp_path / "contest_current" / "system_info.json").write_text(json.dumps(info), encoding="utf-8")
+    upm = UnifiedPathManager(project_root=tmp_path)
+    index = ArchiveIndex(upm=upm)
+    return CommandGc(options, upm=upm, index=index), index
+
+def test_gc_archives_stale_unsolved_problems(tmp_path):
+    stale = make_problem(tmp_path, "abc100", "c")
+    solved = make_problem(tmp_path, "abc100", "d")
+    fresh = make_problem(tmp_path, "abc101", "a", mtime=time.time())
+    current = make_problem(tmp_path, "abc300", "a")  # 作業中の問題
+    cmd, index = setup(tmp_path, {"yes": True})
+    index.record_submission("abc100", "d", "rust", status="AC")
+    handled = asyncio.run(cmd.gc())
+    assert [(h["contest"], h["problem"], h["action"]) for h in handled] == [("abc100", "c", "archive")]
+    assert not stale.exists() and solved.exists() and fresh.exists()
+    archive = tmp_path / ".cph" / "gc" / "abc100_c.tar.gz"
+    with tarfile.open(archive) as tf:
+        names = tf.getnames()
+    assert "abc100/c/rust/src/main.rs" in names
+    assert not any("target" in n for n in names)
+
+def test_gc_interactive_delete_and_skip(tmp_path, monkeypatch):
+    make_problem(tmp_path, "abc100", "c")
+    make_problem(tmp_path, "abc100", "e")
+    answers = iter(["d", ""])
+    monkeypatch.setattr("builtins.input", lambda prompt: next(answers))
+    cmd, _ = setup(tmp_path, {"days": "10"})
+    handled = asyncio.run(cmd.gc())
+    assert [(h["problem"], h["action"]) for h in handled] == [("c", "delete")]
+    assert not (tmp_path / "contest_stocks" / "abc100" / "c").exists()
+    assert (tmp_path / "contest_stocks" / "abc100" / "e").exists()
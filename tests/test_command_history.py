@@ -0,0 +1,33 @@
+import asyncio
+import csv
+from src.commands.command_history import CSV_COLUMNS, CommandHistory, history_rows
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+def test_history_rows_flatten_and_filter():
+    runs = [
+        {"run_id": "2", "contest_name": "abc300", "problem_name": "b", "language_name": "python", "cases": [{"name": "sample-1.in", "verdict": "AC", "time": 0.1}]},
+        {"run_id": "1", "contest_name": "abc300", "problem_name": "a", "language_name": "python",
+         "cases": [{"name": "sample-1.in", "verdict": "AC"}, {"name": "sample-2.in", "verdict": "WA"}]},
+    ]
+    assert [(r["run_id"], r["case"]) for r in history_rows(runs)] == [("1", "sample-1.in"), ("1", "sample-2.in"), ("2", "sample-1.in")]
+    assert [r["verdict"] for r in history_rows(runs, "abc300", "a")] == ["AC", "WA"]
+
+def test_export_csv_after_test_runs(tmp_path):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n"), ("2 2\n", "5\n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "a, b = map(int, input().split())\nprint(a + b)\n")
+        sim.execute("test", "abc300", "a", "python")
+        out = workspace.path("runs.csv")
+        handler = CommandHistory({"csv": str(out)}, upm=UnifiedPathManager(str(workspace.root)))
+        handler.args = ["export"]
+        rows = asyncio.run(handler.run())
+    assert len(rows) == 2
+    with open(out, encoding="utf-8-sig", newline="") as f:
+        table = list(csv.DictReader(f))
+    assert list(table[0]) == CSV_COLUMNS
+    assert sorted(r["verdict"] for r in table) == ["AC", "WA"]
+    assert all(r["contest"] == "abc300" and float(r["time"]) > 0 and int(r["memory_kb"]) > 0 for r in table)
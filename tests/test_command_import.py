@@ -0,0 +1,63 @@
+import asyncio
+import pytest
+from src import http_client
+from src.commands import command_import
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_import import CommandImport, split_atcoder_problem
+from src.commands.problem_difficulty import FETCHED_SOURCE
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def fake_get_json(responses, calls):
+    def get_json(url, params=None, timeout=None):
+        calls.append((url, params))
+        return responses[url]
+    return get_json
+
+def test_split_atcoder_problem():
+    assert split_atcoder_problem("abc300", "abc300_a") == "a"
+    assert split_atcoder_problem("abc300", "abc300_ex") == "ex"
+    assert split_atcoder_problem("jsc2021", "abc200_c") == "c"
+
+def test_import_atcoder_history(tmp_path, monkeypatch):
+    submissions = [
+        {"contest_id": "abc300", "problem_id": "abc300_a", "result": "WA", "language": "Python (3.11.4)", "epoch_second": 100},
+        {"contest_id": "abc300", "problem_id": "abc300_a", "result": "AC", "language": "Rust (1.70.0)", "epoch_second": 200},
+        {"contest_id": "abc300", "problem_id": "abc300_a", "result": "WA", "language": "Rust (1.70.0)", "epoch_second": 300},
+        {"contest_id": "abc301", "problem_id": "abc301_d", "result": "TLE", "language": "Python (3.11.4)", "epoch_second": 400},
+    ]
+    calls = []
+    monkeypatch.setattr(http_client, "get_json", fake_get_json({
+        command_import.ATCODER_PROBLEMS_SUBMISSIONS: submissions,
+        command_import.ATCODER_PROBLEMS_MODELS: {"abc300_a": {"difficulty": -512.3}, "abc301_d": {"difficulty": 1234.6}},
+    }, calls))
+    index = ArchiveIndex(upm=UnifiedPathManager(project_root=tmp_path))
+    index.set_tags("abc301", "d", add=["dp"], difficulty=1500)
+    problems = asyncio.run(CommandImport({"site": "atcoder", "user": "me"}, index=index).import_history())
+    assert len(problems) == 2
+    assert calls[0] == (command_import.ATCODER_PROBLEMS_SUBMISSIONS, {"user": "me", "from_second": 0})
+    a = index.get("abc300", "a")
+    assert a["status"] == "AC" and a["languages"] == ["Python (3.11.4)", "Rust (1.70.0)"]
+    # openで取得したときと同じく丸め込み、取得した値として残す（次のopenで取り直せる）
+    assert a["difficulty"] == 41 and a["difficulty_source"] == FETCHED_SOURCE
+    d = index.get("abc301", "d")
+    assert d["status"] == "TLE" and d["difficulty"] == 1500 and d["tags"] == ["dp"]
+    assert d["difficulty_source"] != FETCHED_SOURCE
+    assert [e["contest"] for e in index.search(unsolved=True)] == ["abc301"]
+
+def test_import_codeforces_history(tmp_path, monkeypatch):
+    result = {"status": "OK", "result": [
+        {"creationTimeSeconds": 10, "verdict": "OK", "programmingLanguage": "Rust 2021",
+         "problem": {"contestId": 1850, "index": "A", "rating": 800, "tags": ["math"]}},
+    ]}
+    monkeypatch.setattr(http_client, "get_json", fake_get_json({command_import.CODEFORCES_USER_STATUS: result}, []))
+    index = ArchiveIndex(upm=UnifiedPathManager(project_root=tmp_path))
+    asyncio.run(CommandImport({"site": "codeforces", "user": "me"}, index=index).import_history())
+    entry = index.get("cf1850", "a")
+    assert entry["status"] == "AC" and entry["difficulty"] == 800 and entry["tags"] == ["math"]
+
+def test_import_requires_user_and_known_site(tmp_path):
+    index = ArchiveIndex(upm=UnifiedPathManager(project_root=tmp_path))
+    with pytest.raises(ValueError):
+        asyncio.run(CommandImport({}, index=index).import_history())
+    with pytest.raises(ValueError):
+        asyncio.run(CommandImport({"site": "unknown", "user": "me"}, index=index).import_history())
@@ -0,0 +1,26 @@
+import asyncio
+from src.commands.command_init import CommandInit, DEFAULT_TEAM_MEMBERS
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.config_json_manager import ConfigJsonManager
+
+def test_init_team_creates_layout(tmp_path):
+    cmd = CommandInit(options={"team": True, "members": "alice,bob"})
+    cmd.upm = UnifiedPathManager(str(tmp_path))
+    team = asyncio.run(cmd.init())
+    assert (tmp_path / "team" / "members" / "alice").is_dir()
+    assert (tmp_path / "team" / "members" / "bob").is_dir()
+    assert (tmp_path / "team" / "lib").is_dir()
+    assert (tmp_path / "team" / "notebook").is_dir()
+    assert team["notebook"] == "team/notebook/notebook.pdf"
+    saved = ConfigJsonManager(str(tmp_path / "contest_current" / "config.json")).get_team()
+    assert saved["members"] == ["alice", "bob"]
+
+def test_init_team_default_members():
+    assert CommandInit(options={"team": True}).members() == DEFAULT_TEAM_MEMBERS
+
+def test_init_without_team_generates_workspace_files(tmp_path):
+    # --team が無ければ、.gitignore と .editorconfig だけを作る
+    cmd = CommandInit()
+    cmd.upm = UnifiedPathManager(str(tmp_path))
+    assert asyncio.run(cmd.init()) == [".gitignore", ".editorconfig"]
+    assert not (tmp_path / "team").exists()
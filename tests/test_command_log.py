@@ -0,0 +1,54 @@
+import io
+import json
+import os
+from datetime import datetime
+from src import command_log
+from src.command_log import CommandLog, MAX_ENTRIES, redact
+from src.commands.command_test import CommandTest
+from src.shell import CommandSpec, Shell
+
+def test_redact_hides_passwords_and_tokens():
+    assert redact(["oj", "login", "-u", "me", "-p", "hunter2", "https://atcoder.jp/"]) == ["oj", "login", "-u", "me", "-p", "***", "https://atcoder.jp/"]
+    assert redact(["oj", "login", "--password=hunter2"]) == ["oj", "login", "--password=***"]
+    # docker runの-pはポートなので残す
+    assert redact(["docker", "run", "-p", "8080:80", "-e", "GITHUB_TOKEN=abc", "-e", "LANG=C.UTF-8", "img"]) == ["docker", "run", "-p", "8080:80", "-e", "GITHUB_TOKEN=***", "-e", "LANG=C.UTF-8", "img"]
+
+def test_log_records_since_mark_and_drops_old_entries():
+    log = CommandLog()
+    log.record(["docker", "ps"], datetime.now(), 0.1, 0)
+    mark = log.mark()
+    for i in range(MAX_ENTRIES + 5):
+        log.record(["docker", "inspect", f"c{i}"], datetime.now(), 0.1, 0)
+    entries = log.since(mark)
+    assert len(entries) == MAX_ENTRIES and entries[-1]["command"] == f"docker inspect c{MAX_ENTRIES + 4}"
+    assert log.since(log.mark()) == []
+
+def test_debug_prints_redacted_command():
+    stream = io.StringIO()
+    log = CommandLog(debug=True, stream=stream)
+    log.record(["oj", "login", "-p", "secret"], datetime.now(), 1.234, None, timed_out=True)
+    assert stream.getvalue() == "[debug] $ oj login -p '***'（タイムアウト、1.23秒）\n"
+
+def test_shell_logs_only_docker_and_oj(tmp_path):
+    docker = tmp_path / "docker"
+    docker.write_text("#!/bin/sh\nexit 3\n", encoding="utf-8")
+    os.chmod(docker, 0o755)
+    log = command_log.get_log()
+    mark = log.mark()
+    Shell().run(CommandSpec(str(docker), ["exec", "-e", "API_TOKEN=abc", "cph_test"], cwd=str(tmp_path)))
+    Shell().run(CommandSpec("true"))
+    entries = log.since(mark)
+    assert len(entries) == 1
+    entry = entries[0]
+    assert entry["argv"][1:] == ["exec", "-e", "API_TOKEN=***", "cph_test"]
+    assert entry["returncode"] == 3 and not entry["timed_out"] and entry["cwd"] == str(tmp_path)
+    assert entry["duration"] >= 0
+
+def test_test_run_saves_its_commands():
+    log = command_log.get_log()
+    log.record(["docker", "ps"], datetime.now(), 0.1, 0)
+    command = CommandTest(None, None)
+    log.record(["docker", "exec", "cph_test", "python3", "main.py"], datetime.now(), 0.2, 1)
+    artifacts = command.record_run([], "abc300", "a", "python")
+    saved = json.loads((artifacts.path / "commands.json").read_text(encoding="utf-8"))
+    assert [e["command"] for e in saved] == ["docker exec cph_test python3 main.py"]
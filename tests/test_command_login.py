@@ -0,0 +1,59 @@
+import asyncio
+import http.cookiejar
+from src.commands.command_login import CommandLogin, EXIT_EXPIRED, EXIT_MISSING, EXIT_VALID, OJ_COOKIE_JAR_PATH, oj_cookie_jars
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+NOW = 1_800_000_000
+
+def cookie(name, value, domain, expires):
+    return http.cookiejar.Cookie(0, name, value, None, False, domain, True, domain.startswith("."), "/", True,
+                                 True, expires, expires is None, None, None, {})
+
+def write_jar(path, cookies):
+    jar = http.cookiejar.LWPCookieJar(str(path))
+    for c in cookies:
+        jar.set_cookie(c)
+    jar.save(ignore_discard=True, ignore_expires=True)
+    return path
+
+def check(tmp_path, jar_path, options=None):
+    handler = CommandLogin({"check": True, **(options or {})}, upm=UnifiedPathManager(project_root=tmp_path), jar_paths=[str(jar_path)])
+    return handler.check(now=NOW)
+
+def test_check_reports_sessions_expiry_and_account(tmp_path, capsys):
+    jar = write_jar(tmp_path / "cookie.jar", [
+        cookie("REVEL_SESSION", "abc-%00UserScreenName%3Aalice%00csrf%3Ax%00", "atcoder.jp", NOW + 86400),
+        cookie("X-User-Sha1", "deadbeef", ".codeforces.com", NOW - 60),
+        # ログインと関係ないクッキーは見ない
+        cookie("language", "ja", "atcoder.jp", NOW + 86400),
+    ])
+    # 期限切れのサイトが1つでもあれば0にしない
+    assert check(tmp_path, jar) == EXIT_EXPIRED
+    lines = capsys.readouterr().out.splitlines()
+    assert lines[0].startswith("[login] atcoder: ログイン済み（alice、有効期限 ")
+    assert lines[1].startswith("[login] codeforces: 期限切れ（")
+    assert lines[2] == "[login] yukicoder: 未ログイン"
+    # サイトを絞ると、そのサイトの状態が終了コードになる
+    assert check(tmp_path, jar, {"site": "atcoder"}) == EXIT_VALID
+    assert check(tmp_path, jar, {"site": "codeforces"}) == EXIT_EXPIRED
+    assert check(tmp_path, jar, {"site": "yukicoder"}) == EXIT_MISSING
+
+def test_check_without_cookie_jar(tmp_path, capsys):
+    assert check(tmp_path, tmp_path / "missing.jar") == EXIT_MISSING
+    assert capsys.readouterr().out.count("未ログイン") == 3
+
+def test_check_with_broken_cookie_jar(tmp_path, capsys):
+    jar = tmp_path / "cookie.jar"
+    jar.write_text("not a cookie jar\n", encoding="utf-8")
+    assert check(tmp_path, jar, {"site": "atcoder"}) == EXIT_EXPIRED
+    assert f"atcoder: cookie.jarを読めません（{jar}）" in capsys.readouterr().out
+
+def test_cookie_jar_follows_xdg_data_home(tmp_path):
+    paths = oj_cookie_jars({"XDG_DATA_HOME": str(tmp_path)})
+    assert paths[0] == str(tmp_path / OJ_COOKIE_JAR_PATH)
+    assert all("cphelper" not in p for p in paths)
+    assert oj_cookie_jars({"XDG_DATA_HOME": "relative"})[0].endswith("/.local/share/online-judge-tools/cookie.jar")
+
+def test_login_dispatches_to_check(tmp_path):
+    handler = CommandLogin({"check": True, "site": "atcoder"}, upm=UnifiedPathManager(project_root=tmp_path), jar_paths=[str(tmp_path / "none")])
+    assert asyncio.run(handler.login()) == EXIT_MISSING
@@ -100,4 +100,30 @@ async def test_open_no_file_operator(mock_upm, mock_info, mock_config):
         await cmd.open('abc', 'pqr', 'python')
         assert opener.editor_opened
         assert test_env.adjusted
-        assert test_env.downloaded 
\ No newline at end of file
+        assert test_env.downloaded 
+def test_reopen_keeps_modified_solution_and_samples(tmp_path, capsys):
+    from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1\n", "1\n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        assert "テンプレートから作りました: contest_current/python/main.py" in capsys.readouterr().out
+        workspace.write_source("python", "print(1)\n")
+        sample = workspace.path("contest_current", "test", "sample-1.out")
+        sample.write_text("edited\n", encoding="utf-8")
+        workspace.write_case("mine", "5\n", "5\n")
+        sim.execute("open", "abc300", "a", "python")
+        out = capsys.readouterr().out
+        assert "contest_currentはそのままです" in out
+        assert "サンプルは取得済みなので取り直しません" in out
+        assert workspace.path("contest_current", "python", "main.py").read_text(encoding="utf-8") == "print(1)\n"
+        assert sample.read_text(encoding="utf-8") == "edited\n"
+        # --refreshでサンプルだけ取り直し、元のサンプルはバックアップに残る
+        sim.execute("open", "abc300", "a", "python", options={"refresh": True})
+        assert "取得済みのサンプル2ファイルを.cph/backups/" in capsys.readouterr().out
+        assert sample.read_text(encoding="utf-8") == "1\n"
+        assert workspace.path("contest_current", "test", "mine.in").exists()
+        backups = list(workspace.path(".cph", "backups").glob("*-refresh/contest_current/test/sample-1.out"))
+        assert [p.read_text(encoding="utf-8") for p in backups] == ["edited\n"]
+        assert workspace.path("contest_current", "python", "main.py").read_text(encoding="utf-8") == "print(1)\n"
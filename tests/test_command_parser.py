@@ -87,4 +87,41 @@ def test_parse_invalid(monkeypatch, tmp_path):
     assert args["contest_name"] is None
     assert args["problem_name"] is None
     assert args["language_name"] is None
-    assert args["command"] is None 
\ No newline at end of file
+    assert args["command"] is None 
+def test_parse_options_with_value():
+    parser = CommandParser()
+    parser.parse(["abc300", "t", "a", "python", "--progress", "ndjson"])
+    assert parser.get_options() == {"progress": "ndjson"}
+    assert parser.parsed["command"] == "test"
+    assert parser.parsed["language_name"] == "python"
+
+def test_parse_options_equals_form():
+    parser = CommandParser()
+    parser.parse(["--progress=ndjson", "abc300", "t", "a", "python"])
+    assert parser.get_options()["progress"] == "ndjson"
+    assert parser.parsed["contest_name"] == "abc300"
+
+def test_parse_options_invalid_choice():
+    parser = CommandParser()
+    with pytest.raises(ValueError):
+        parser.parse(["abc300", "t", "--progress", "xml"])
+
+def test_parse_leftmost_command_wins():
+    # import history の history はコマンドではなくサブコマンド
+    parser = CommandParser()
+    parser.parse(["import", "history", "--user", "me"])
+    assert parser.parsed["command"] == "import"
+    assert parser.extra_args == ["history"]
+    parser.parse(["history", "export", "--csv", "-"])
+    assert parser.parsed["command"] == "history"
+    assert parser.extra_args == ["export"]
+
+def test_parse_command_not_taken_as_contest():
+    # archiveはarcで始まるが、コンテスト名ではなくコマンド
+    parser = CommandParser()
+    parser.parse(["archive", "sync-verdicts"])
+    assert parser.parsed["command"] == "archive"
+    assert parser.parsed["contest_name"] is None
+    parser.parse(["arc150", "archive", "sync-verdicts"])
+    assert parser.parsed["contest_name"] == "arc150"
+    assert parser.parsed["command"] == "archive"
@@ -0,0 +1,36 @@
+import asyncio
+from src.commands.command_print import CommandPrint, render_postscript, wrap_lines, paginate, ps_escape, LINES_PER_PAGE, LINE_WIDTH
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def test_ps_escape():
+    assert ps_escape("f(a\\b)") == "f\\(a\\\\b\\)"
+    assert ps_escape("あ") == "?"
+
+def test_wrap_lines():
+    lines = wrap_lines("x" * (LINE_WIDTH + 5))
+    assert len(lines) == 2
+    assert lines[1] == "  " + "x" * 5
+
+def test_paginate_breaks_per_file():
+    pages = paginate([("a.py", "\n".join(["1"] * (LINES_PER_PAGE + 1))), ("b.py", "2")])
+    assert [title for title, _ in pages] == ["a.py", "a.py", "b.py"]
+
+def test_render_postscript():
+    ps = render_postscript([("a.py", "print(1)")])
+    assert ps.startswith("%!PS-Adobe-3.0")
+    assert "%%Pages: 1" in ps
+    assert "(print\\(1\\)) show" in ps
+
+def test_print_notebook_writes_postscript(tmp_path, monkeypatch):
+    lib = tmp_path / "team" / "lib"
+    lib.mkdir(parents=True)
+    (lib / "uf.py").write_text("class UnionFind:\n    pass\n")
+    (lib / "notes.bin").write_text("skip")
+    cmd = CommandPrint()
+    cmd.upm = UnifiedPathManager(str(tmp_path))
+    monkeypatch.setattr(cmd, "to_pdf", lambda ps, pdf: False)
+    result = asyncio.run(cmd.print_notebook())
+    assert result.endswith("notebook.ps")
+    text = (tmp_path / "team" / "notebook" / "notebook.ps").read_text()
+    assert "(uf.py) show" in text
+    assert "notes.bin" not in text
@@ -0,0 +1,96 @@
+import asyncio
+import pytest
+from src.commands.command_profile import CommandProfile, parse_massif_peak, parse_heaptrack_peak, format_bytes
+
+MASSIF_OUT = """desc: --massif-out-file=x
+snapshot=0
+mem_heap_B=100
+mem_heap_extra_B=8
+snapshot=1
+mem_heap_B=2048
+mem_heap_extra_B=16
+snapshot=2
+mem_heap_B=512
+mem_heap_extra_B=8
+"""
+
+def test_parse_massif_peak():
+    assert parse_massif_peak(MASSIF_OUT) == 2064
+    assert parse_massif_peak("") is None
+
+def test_parse_heaptrack_peak():
+    assert parse_heaptrack_peak("peak heap memory consumption: 1.50M\n") == 1500000
+    assert parse_heaptrack_peak("nothing") is None
+
+def test_format_bytes():
+    assert format_bytes(512) == "512 B"
+    assert format_bytes(2 * 1024 * 1024) == "2.0 MiB"
+
+def test_select_case():
+    files = ["t/sample-1.in", "t/sample-2.in"]
+    assert CommandProfile.select_case(files, "2") == "t/sample-2.in"
+    assert CommandProfile.select_case(files, "sample-1") == "t/sample-1.in"
+    with pytest.raises(ValueError):
+        CommandProfile.select_case(files, "3")
+    with pytest.raises(ValueError):
+        CommandProfile.select_case(files, None)
+
+class FakeEnv:
+    def __init__(self):
+        self.commands = []
+        self.manager = object()
+    def to_container_path(self, path):
+        return str(path)
+    def exec_command(self, container, cmd, in_file=None):
+        self.commands.append(cmd)
+        # --massif-out-file=... からパスを取り出してmassif出力を書き込む
+        out = cmd[2].split("=", 1)[1]
+        with open(out, "w", encoding="utf-8") as f:
+            f.write(MASSIF_OUT)
+        return True, "", ""
+
+def test_profile_memory_writes_artifact(tmp_path, monkeypatch, capsys):
+    env = FakeEnv()
+    cmd = CommandProfile(None, env, options={"memory": True, "case": "1"})
+    in_file = tmp_path / "contest_current" / "test" / "sample-1.in"
+    monkeypatch.setattr(cmd, "prepare", lambda c, p, l: (type("H", (), {"run_command": lambda self, s: ["python3", s]})(), "cont", "main.py", str(in_file)))
+    result = asyncio.run(cmd.profile("abc300", "a", "python"))
+    assert result["peak_bytes"] == 2064
+    assert result["profile_path"].name == "sample-1.massif.out"
+    assert env.commands[0][:2] == ["valgrind", "--tool=massif"]
+    assert env.commands[0][-2:] == ["python3", "main.py"]
+    assert "ピークヒープ使用量" in capsys.readouterr().out
+
+def test_profile_requires_mode():
+    cmd = CommandProfile(None, FakeEnv(), options={})
+    with pytest.raises(ValueError):
+        asyncio.run(cmd.profile("abc300", "a", "python"))
+
+class FakeFlamegraphEnv(FakeEnv):
+    def exec_command(self, container, cmd, in_file=None):
+        self.commands.append(cmd)
+        if "-o" in cmd:
+            with open(cmd[cmd.index("-o") + 1], "w", encoding="utf-8") as f:
+                f.write("<svg></svg>")
+        return True, "", ""
+
+def test_profile_cpu_writes_svg(tmp_path, monkeypatch):
+    env = FakeFlamegraphEnv()
+    cmd = CommandProfile(None, env, options={"cpu": True, "case": "1"})
+    in_file = tmp_path / "contest_current" / "test" / "sample-1.in"
+    monkeypatch.setattr(cmd, "prepare", lambda c, p, l: (type("H", (), {"run_command": lambda self, s: ["bin"]})(), "cont", "rust", str(in_file)))
+    result = asyncio.run(cmd.profile("abc300", "a", "rust"))
+    assert result["svg_path"].name == "sample-1.flamegraph.svg"
+    assert env.commands[0][0] == "flamegraph"
+    assert env.commands[0][-2:] == ["--", "bin"]
+
+def test_profile_cpu_perf_runs_report(tmp_path, monkeypatch):
+    env = FakeFlamegraphEnv()
+    cmd = CommandProfile(None, env, options={"cpu": True, "case": "1"})
+    monkeypatch.setattr(cmd.command_test, "get_config", lambda: type("C", (), {"get_profile": lambda self: {"cpu": {"python": "perf"}}})())
+    in_file = tmp_path / "sample-1.in"
+    monkeypatch.setattr(cmd, "prepare", lambda c, p, l: (type("H", (), {"run_command": lambda self, s: ["python3", s]})(), "cont", "main.py", str(in_file)))
+    asyncio.run(cmd.profile("abc300", "a", "python"))
+    assert env.commands[0][:2] == ["perf", "record"]
+    assert env.commands[1][:2] == ["sh", "-c"]
+    assert "flamegraph.pl" in env.commands[1][2]
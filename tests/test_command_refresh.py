@@ -0,0 +1,99 @@
+import asyncio
+from src import http_client
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_refresh import CommandRefresh, statement_digest
+from src.commands.command_watch import CommandWatch
+from src.commands.result_cache import ResultCache
+from src.commands.sample_rebind import sample_checksums
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.test_support.http_cassette import StaticTransport, normalize_url
+
+URL = "https://atcoder.jp/contests/abc300/tasks/abc300_a"
+
+def page(*samples, constraints="1 <= N <= 100"):
+    parts = [f'<div class="part"><section><h3>制約</h3><ul><li>{constraints}</li></ul></section></div>']
+    for i, (given, expected) in enumerate(samples, 1):
+        parts.append(f'<div class="part"><section><h3>入力例 {i}</h3><pre>{given}</pre></section></div>')
+        parts.append(f'<div class="part"><section><h3>出力例 {i}</h3><pre>{expected}</pre></section></div>')
+    return f'<html><head><script>var csrfToken = "x";</script></head><body><div id="task-statement"><span class="lang-ja">{"".join(parts)}</span></div></body></html>'
+
+def setup_problem(tmp_path):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    upm.info_json().write_text('{"contest_name": "abc300", "problem_name": "a"}', encoding="utf-8")
+    test_dir = upm.contest_current("test")
+    for f in test_dir.iterdir():
+        f.unlink()
+    (test_dir / "sample-1.in").write_text("1\n", encoding="utf-8")
+    (test_dir / "sample-1.out").write_text("a\n", encoding="utf-8")
+    ArchiveIndex(upm=upm).update("abc300", "a", lambda e: e.update(sample_checksums=sample_checksums(test_dir)))
+    return upm, test_dir
+
+def test_statement_digest_ignores_scripts_and_outside_text():
+    base = page(("1\n", "a\n"))
+    assert statement_digest(base) == statement_digest(base.replace('"x"', '"y"').replace("<body>", "<body>12:00:01"))
+    assert statement_digest(base) != statement_digest(page(("1\n", "a\n"), constraints="1 <= N <= 10^5"))
+
+def test_refresh_adds_new_samples_without_touching_existing(tmp_path, capsys):
+    upm, test_dir = setup_problem(tmp_path)
+    ResultCache(upm.cph_dir("results.json")).put("abc300/a/python", "hash", [{"name": "sample-1.in", "verdict": "AC"}])
+    # 自分で直したサンプルと、自分で足したケース
+    (test_dir / "sample-1.out").write_text("a (fixed)\n", encoding="utf-8")
+    (test_dir / "my-1.in").write_text("9\n", encoding="utf-8")
+    with http_client.use_transport(StaticTransport({URL: page(("1\n", "a\n"), ("2\n", "b\n"))})):
+        added = asyncio.run(CommandRefresh(upm=upm).refresh())
+    assert added == ["sample-2"]
+    assert (test_dir / "sample-2.out").read_text(encoding="utf-8") == "b\n"
+    assert (test_dir / "sample-1.out").read_text(encoding="utf-8") == "a (fixed)\n"
+    assert (test_dir / "my-1.in").exists()
+    out = capsys.readouterr().out
+    assert "sample-1: 内容が変わりました（手元のケースは変えていません）" in out
+    assert "case rebind" in out
+    # ケースが増えたので、提出前チェックに前回のフル実行を使わない
+    assert ResultCache(upm.cph_dir("results.json")).get("abc300/a/python") is None
+    assert ArchiveIndex(upm=upm).get("abc300", "a")["sample_checksums"]["sample-2"] == sample_checksums(test_dir)["sample-2"]
+
+def test_refresh_reports_statement_change_and_polls(tmp_path, capsys):
+    upm, test_dir = setup_problem(tmp_path)
+    transport = StaticTransport({URL: page(("1\n", "a\n"))})
+    pages = iter([page(("1\n", "a\n"), constraints="1 <= N <= 10^5"), page(("1\n", "a\n"), ("3\n", "c\n"), constraints="1 <= N <= 10^5")])
+
+    def sleep(seconds):
+        assert seconds == 60
+        transport.routes[normalize_url(URL)] = next(pages)
+    with http_client.use_transport(transport):
+        asyncio.run(CommandRefresh(upm=upm).refresh())
+        assert "新しいサンプル・変更はありません" in capsys.readouterr().out
+        added = asyncio.run(CommandRefresh({"poll": "1m"}, upm=upm, sleep=sleep).refresh(rounds=3))
+    assert added == ["sample-2"]
+    out = capsys.readouterr().out
+    assert out.count("問題文が前回から変わっています") == 2
+    assert "60秒ごとに" in out
+
+def test_poll_keeps_going_after_network_error(tmp_path, capsys):
+    upm, test_dir = setup_problem(tmp_path)
+    transport = StaticTransport({URL: page(("1\n", "a\n"), ("2\n", "b\n"))}, online=False)
+
+    def sleep(seconds):
+        transport.online = True
+    with http_client.use_transport(transport):
+        added = asyncio.run(CommandRefresh({"poll": "1m"}, upm=upm, sleep=sleep).refresh(rounds=2))
+    assert added == ["sample-2"]
+    assert "確かめられませんでした（次の回にもう一度確かめます）" in capsys.readouterr().out
+
+def test_watch_with_poll_reruns_test_on_new_samples(tmp_path, monkeypatch, capsys):
+    upm, test_dir = setup_problem(tmp_path)
+    ran = []
+
+    class FakeTest:
+        async def run_test(self, contest_name, problem_name, language_name):
+            ran.append(sorted(p.name for p in test_dir.iterdir()))
+
+    async def sleep(seconds):
+        await asyncio.sleep(0.05)
+    watch = CommandWatch(FakeTest(), options={"poll": "1s"}, upm=upm, sleep=sleep)
+    monkeypatch.setattr(watch, "get_config", lambda: {"strategy": "queue", "interval_seconds": 0.5})
+    with http_client.use_transport(StaticTransport({URL: page(("1\n", "a\n"), ("2\n", "b\n"))})):
+        history = asyncio.run(watch.watch("abc300", "a", "python", max_polls=4))
+    assert [outcome for _, outcome in history] == ["done", "done"]
+    assert "sample-2.in" not in ran[0] and "sample-2.in" in ran[1]
+    assert "1秒ごとに問題ページを確かめ" in capsys.readouterr().out
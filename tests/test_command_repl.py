@@ -0,0 +1,62 @@
+import asyncio
+import subprocess
+import pytest
+from src.commands.command_repl import CommandRepl, startup_script
+from src.error_hints import CphError
+
+class LocalEnv:
+    """attach_commandの呼び出しを記録する手元の実行環境"""
+    def __init__(self, returncode=0):
+        self.returncode = returncode
+        self.attached = []
+
+    def to_container_path(self, path):
+        return path
+
+    def adjust_containers(self, requirements, contest_name=None, problem_name=None, language_name=None):
+        return []
+
+    def attach_command(self, container, cmd):
+        self.attached.append((container, cmd))
+        return self.returncode
+
+def write_workspace(tmp_path):
+    template = tmp_path / "contest_template" / "python" / "main.py"
+    template.parent.mkdir(parents=True, exist_ok=True)
+    template.write_text("def solve(n):\n    return n * (n + 1) // 2\n\nif __name__ == '__main__':\n    raise SystemExit('main')\n", encoding="utf-8")
+    lib = tmp_path / "team" / "lib"
+    lib.mkdir(parents=True, exist_ok=True)
+    (lib / "mylib.py").write_text("ANSWER = 42\n", encoding="utf-8")
+
+def test_startup_script_loads_template_and_lib(tmp_path):
+    write_workspace(tmp_path)
+    script = tmp_path / "startup.py"
+    script.write_text(startup_script(tmp_path / "team" / "lib", tmp_path / "contest_template" / "python" / "main.py"), encoding="utf-8")
+    code = f"exec(open({str(script)!r}).read()); import mylib; print(solve(10), mylib.ANSWER, gcd(12, 18), Counter('aab')['a'])"
+    result = subprocess.run(["python3", "-c", code], capture_output=True, text=True)
+    assert result.returncode == 0, result.stderr
+    assert result.stdout.strip().splitlines()[-1] == "55 42 6 2"
+
+def test_repl_attaches_python_with_startup(tmp_path):
+    write_workspace(tmp_path)
+    env = LocalEnv()
+    asyncio.run(CommandRepl(None, env).repl("abc300", "a", "python"))
+    container, cmd = env.attached[0]
+    assert container == "cph_repl_python"
+    assert cmd[:2] == ["python3", "-i"] and cmd[2].endswith(".temp/repl/startup.py")
+    startup = (tmp_path / ".temp" / "repl" / "startup.py").read_text(encoding="utf-8")
+    assert str(tmp_path / "team" / "lib") in startup and "run_path" in startup
+
+def test_repl_rust_and_configured_command(tmp_path):
+    env = LocalEnv()
+    asyncio.run(CommandRepl(None, env).repl("abc300", "a", "rust"))
+    assert env.attached[-1][1] == ["evcxr"]
+    (tmp_path / "contest_current").mkdir(exist_ok=True)
+    (tmp_path / "contest_current" / "config.json").write_text('{"repl": {"python": "ipython -i {startup}"}}', encoding="utf-8")
+    asyncio.run(CommandRepl(None, env).repl("abc300", "a", "python"))
+    assert env.attached[-1][1][:2] == ["ipython", "-i"]
+
+def test_repl_missing_command(tmp_path):
+    with pytest.raises(CphError) as e:
+        asyncio.run(CommandRepl(None, LocalEnv(returncode=127)).repl("abc300", "a", "rust"))
+    assert e.value.kind == "repl_missing"
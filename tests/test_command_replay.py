@@ -0,0 +1,61 @@
+import asyncio
+import pytest
+from src.commands.command_replay import output_diff
+from src.commands.run_artifacts import RunArtifacts
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+# 1つ目のケースで間違える解答と、それを直した解答。シードを出力に混ぜる解答
+BUGGY = "a, b = map(int, input().split())\nprint(a - b if a == 1 else a + b)\n"
+FIXED = "a, b = map(int, input().split())\nprint(a + b)\n"
+SEEDED = "import os\ninput()\nprint(os.environ['CPH_SEED'])\n"
+
+def setup(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n"), ("10 20\n", "30\n")])
+    return workspace, site
+
+def test_output_diff_is_empty_for_same_output():
+    assert output_diff("1\n", "1\n", "a", "b") == []
+    assert output_diff("1\n", "2\n", "a", "b")[-2:] == ["-1", "+2"]
+
+def test_replay_reruns_recorded_input_against_current_code(tmp_path, capsys):
+    workspace, site = setup(tmp_path)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", BUGGY)
+        sim.execute("test", "abc300", "a", "python")
+        run = RunArtifacts.all_runs()[0]
+        assert run["cases"][0]["stored_input"] == run["cases"][0]["input_digest"]
+        # 記録のあとにケースを書き換えても、記録した入力で実行し直す
+        workspace.write_case("sample-1", "5 5\n", "10\n")
+        workspace.write_source("python", FIXED)
+        capsys.readouterr()
+        result = asyncio.run(sim.executor().replay([run["run_id"][:15]]))
+    assert result["case"] == "sample-1.in"
+    assert result["stdout"] == "3\n" and not result["same_as_recorded"]
+    out = capsys.readouterr().out
+    assert "記録 WA" in out and "記録した出力との差分:" in out
+    assert "+3" in out and "期待出力との差分:" in out
+
+def test_replay_passes_recorded_seed(tmp_path):
+    workspace, site = setup(tmp_path)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", SEEDED)
+        sim.execute("test", "abc300", "a", "python", options={"seed": "42"})
+        result = asyncio.run(sim.executor({"case": "2"}).replay(["latest"]))
+    assert result["case"] == "sample-2.in"
+    assert result["stdout"] == "42\n" and result["same_as_recorded"]
+    assert result["verdict"] == "WA"
+
+def test_replay_rejects_unknown_run_and_case(tmp_path):
+    workspace, site = setup(tmp_path)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", FIXED)
+        sim.execute("test", "abc300", "a", "python")
+        with pytest.raises(ValueError):
+            asyncio.run(sim.executor().replay(["1999"]))
+        with pytest.raises(ValueError):
+            asyncio.run(sim.executor({"case": "9"}).replay(["latest"]))
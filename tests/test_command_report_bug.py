@@ -0,0 +1,77 @@
+import asyncio
+import json
+import tarfile
+from src.commands.command_report_bug import CommandReportBug, sanitize
+from src.error_hints import CphError
+from src.failure_record import load_last_failure, record_failure
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.version import CPH_VERSION
+
+def read_bundle(path):
+    with tarfile.open(str(path)) as tar:
+        return {m.name.split("/", 1)[1]: tar.extractfile(m).read().decode("utf-8") for m in tar.getmembers()}
+
+def test_sanitize_masks_secrets_and_home():
+    config = {"share": {"gist_token": "abc", "service": "gist"}, "hooks": {"cookie": "x"}, "editor": {"command": "/home/alice/bin/ed"},
+              "notify": [{"webhook_url": "https://example.com/x"}], "login": {"password": ""}}
+    sanitized = sanitize(config, home="/home/alice")
+    assert sanitized["share"] == {"gist_token": "***", "service": "gist"}
+    assert sanitized["hooks"]["cookie"] == "***"
+    assert sanitized["notify"][0]["webhook_url"] == "***"
+    assert sanitized["editor"]["command"] == "~/bin/ed"
+    # 空の値は伏せる必要がない
+    assert sanitized["login"]["password"] == ""
+
+def test_record_failure_redacts_argv(tmp_path):
+    try:
+        raise CphError("取得に失敗しました", kind="http_timeout")
+    except CphError as e:
+        record_failure(["main.py", "login", "--password", "hunter2"], e)
+    failure = load_last_failure()
+    assert failure["argv"] == ["main.py", "login", "--password", "***"]
+    assert failure["kind"] == "http_timeout"
+    assert "CphError" in failure["traceback"]
+
+def test_record_failure_outside_workspace_creates_no_cph_dir(tmp_path, monkeypatch):
+    elsewhere = tmp_path / "elsewhere"
+    elsewhere.mkdir()
+    monkeypatch.chdir(elsewhere)
+    upm = UnifiedPathManager(str(elsewhere))
+    path = record_failure(["main.py", "abc300", "test"], ValueError("x"), upm=upm)
+    assert path == upm.state_dir("last_failure.json")
+    assert not upm.cph_dir().exists()
+    assert load_last_failure(upm)["error"] == "x"
+
+def test_report_bug_bundles_sanitized_state(tmp_path, capsys):
+    upm = UnifiedPathManager()
+    upm.config_json().write_text(json.dumps({"share": {"token": "secret-value"}, "time_limit": 2}), encoding="utf-8")
+    try:
+        raise ValueError("壊れた入力")
+    except ValueError as e:
+        record_failure(["main.py", "abc300", "test", "a", "python"], e)
+    source = upm.contest_current("python", "main.py")
+    source.parent.mkdir(parents=True, exist_ok=True)
+    source.write_text("print(1)\n", encoding="utf-8")
+    (upm.contest_current(".temp")).mkdir(exist_ok=True)
+    (upm.contest_current(".temp", "big.bin")).write_text("x")
+    output = tmp_path / "report.tar.gz"
+    command = CommandReportBug({"output": str(output), "workspace": True}, version_of=lambda cmd: f"{cmd[0]} 1.0")
+    assert asyncio.run(command.report_bug()) == output
+    files = read_bundle(output)
+    assert "secret-value" not in files["config.json"]
+    assert json.loads(files["config.json"])["time_limit"] == 2
+    assert json.loads(files["versions.json"])["tools"]["docker"] == "docker 1.0"
+    assert json.loads(files["versions.json"])["cph"] == CPH_VERSION
+    assert json.loads(files["last_failure.json"])["error"] == "壊れた入力"
+    assert files["workspace/python/main.py"] == "print(1)\n"
+    assert not any(name.startswith("workspace/.temp") for name in files)
+    assert "壊れた入力" in files["summary.md"]
+    assert "- workspace/python/main.py" in files["summary.md"]
+    assert "sites.yaml" in capsys.readouterr().out
+
+def test_report_bug_without_workspace(tmp_path):
+    output = tmp_path / "report.tar.gz"
+    asyncio.run(CommandReportBug({"output": str(output)}, version_of=lambda cmd: None).report_bug())
+    files = read_bundle(output)
+    assert set(files) == {"summary.md", "config.json", "versions.json"}
+    assert "記録はありません" in files["summary.md"]
@@ -0,0 +1,62 @@
+import asyncio
+import json
+import pytest
+from src import http_client
+from src.commands.command_result import ATCODER_HISTORY, CommandResult, ResultStore, to_result
+from src.commands.command_stats import CommandStats
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.test_support.http_cassette import StaticTransport
+
+HISTORY = [
+    {"IsRated": True, "Place": 1234, "OldRating": 1180, "NewRating": 1215, "Performance": 1420,
+     "ContestName": "AtCoder Beginner Contest 349", "ContestScreenName": "abc349.contest.atcoder.jp", "EndTime": "2024-04-13T22:40:00+09:00"},
+    {"IsRated": False, "Place": 88, "OldRating": 1215, "NewRating": 1215, "Performance": 2100,
+     "ContestName": "AtCoder Regular Contest 175", "ContestScreenName": "arc175.contest.atcoder.jp", "EndTime": "2024-03-24T23:00:00+09:00"},
+]
+
+def run_result(tmp_path, contest_name, options=None):
+    upm = UnifiedPathManager(str(tmp_path))
+    with http_client.use_transport(StaticTransport({ATCODER_HISTORY.format(user="alice"): HISTORY})):
+        return asyncio.run(CommandResult(options or {"user": "alice"}, upm=upm).result(contest_name)), upm
+
+def test_result_is_stored_with_the_contest(tmp_path, capsys):
+    result, upm = run_result(tmp_path, "abc349")
+    assert (result["place"], result["performance"], result["delta"]) == (1234, 1420, 35)
+    assert "[result] abc349（AtCoder Beginner Contest 349）: 1234位 パフォーマンス 1420 レート 1180 → 1215（+35）" in capsys.readouterr().out
+    stored = json.loads(upm.contest_stocks("abc349", None, None, "result.json").read_text(encoding="utf-8"))
+    assert stored["new_rating"] == 1215
+    assert ResultStore(upm=upm).load()["abc349"]["delta"] == 35
+
+def test_unrated_and_unpublished(tmp_path, capsys):
+    result, _ = run_result(tmp_path, "arc175")
+    assert result["delta"] is None
+    assert "2100（Unrated）" in capsys.readouterr().out.replace("パフォーマンス ", "")
+    result, upm = run_result(tmp_path, "abc350")
+    assert result is None
+    assert "まだ公開されていません" in capsys.readouterr().out
+    assert "abc350" not in ResultStore(upm=upm).load()
+
+def test_user_from_config(tmp_path):
+    (tmp_path / "contest_current").mkdir(exist_ok=True)
+    (tmp_path / "contest_current" / "config.json").write_text(json.dumps({"result": {"user": "alice"}}), encoding="utf-8")
+    result, _ = run_result(tmp_path, "abc349", options={})
+    assert result["user"] == "alice"
+    with pytest.raises(ValueError):
+        asyncio.run(CommandResult({}, upm=UnifiedPathManager(str(tmp_path / "other"))).result("abc349"))
+
+def test_stats_lists_results(tmp_path, capsys):
+    _, upm = run_result(tmp_path, "abc349")
+    run_result(tmp_path, "arc175")
+    capsys.readouterr()
+    results = asyncio.run(CommandStats({"results": True}, results=ResultStore(upm=upm)).show())
+    assert [r["contest"] for r in results] == ["arc175", "abc349"]
+    out = capsys.readouterr().out
+    assert "1215（+35）" in out and "Unrated" in out
+
+def test_stats_shows_missing_place_and_performance(tmp_path, capsys):
+    upm = UnifiedPathManager(str(tmp_path))
+    entry = {**HISTORY[0], "Place": None, "Performance": None, "NewRating": None}
+    ResultStore(upm=upm).save(to_result(entry, "alice"))
+    asyncio.run(CommandStats({"results": True}, results=ResultStore(upm=upm)).show())
+    line = capsys.readouterr().out.splitlines()[-1]
+    assert line.split() == ["abc349", "-位", "パフォーマンス", "-", "レート", "-（-）"]
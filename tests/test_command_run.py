@@ -0,0 +1,75 @@
+import asyncio
+from src.commands.command_run import CommandRun, limit_command
+
+def test_limit_command_no_limits():
+    assert limit_command(["python3", "main.py"]) == ["python3", "main.py"]
+
+def test_limit_command_with_limits():
+    cmd = limit_command(["python3", "main.py"], time_limit="2", memory_limit_mb="256")
+    assert cmd[:2] == ["sh", "-c"]
+    assert "ulimit -v 262144" in cmd[2]
+    assert cmd[4:] == ["timeout", "--signal=KILL", "2", "python3", "main.py"]
+
+class FakeHandler:
+    def run_command(self, source_path):
+        return ["python3", source_path]
+
+class FakeEnv:
+    def __init__(self, returncode=0):
+        self.returncode = returncode
+        self.attached = []
+    def attach_command(self, container, cmd):
+        self.attached.append((container, cmd))
+        return self.returncode
+
+def test_run_attaches_to_terminal(monkeypatch, capsys):
+    env = FakeEnv()
+    cmd = CommandRun(None, env)
+    monkeypatch.setattr(cmd, "prepare", lambda c, p, l: (FakeHandler(), "cont", "main.py"))
+    assert asyncio.run(cmd.run("abc300", "a", "python")) == 0
+    assert env.attached == [("cont", ["python3", "main.py"])]
+
+def test_run_reports_tle(monkeypatch, capsys):
+    env = FakeEnv(returncode=137)
+    cmd = CommandRun(None, env, options={"tl": "1"})
+    monkeypatch.setattr(cmd, "prepare", lambda c, p, l: (FakeHandler(), "cont", "main.py"))
+    asyncio.run(cmd.run("abc300", "a", "python"))
+    assert env.attached[0][1][:3] == ["timeout", "--signal=KILL", "1"]
+    assert "TLE" in capsys.readouterr().out
+
+class CapturingEnv(FakeEnv):
+    def __init__(self):
+        super().__init__()
+        self.executed = []
+    def to_container_path(self, path):
+        return str(path)
+    def exec_command(self, container, cmd, in_file=None):
+        with open(in_file, "r", encoding="utf-8") as f:
+            self.executed.append(f.read())
+        return True, "42\n", ""
+
+def test_run_with_piped_input_and_save_as(monkeypatch, tmp_path, capsys):
+    import io
+    import sys
+    from src.commands import command_run
+    env = CapturingEnv()
+    cmd = CommandRun(None, env, options={"input": "-", "save-as": "bug1"})
+    monkeypatch.setattr(sys, "stdin", io.StringIO("21\n"))
+    monkeypatch.setattr(command_run.temp_space, "CONTAINER_BASE", str(tmp_path / ".temp" / "sessions"))
+    monkeypatch.setattr(cmd.command_test.upm, "contest_current", lambda *p: tmp_path.joinpath("contest_current", *p))
+    monkeypatch.setattr(cmd, "prepare", lambda c, p, l: (FakeHandler(), "cont", "main.py"))
+    assert asyncio.run(cmd.run("abc300", "a", "python")) == 0
+    assert env.executed == ["21\n"]
+    assert env.attached == []
+    assert (tmp_path / "contest_current" / "test" / "bug1.in").read_text() == "21\n"
+    assert "42" in capsys.readouterr().out
+
+def test_run_with_clipboard_input(monkeypatch, tmp_path):
+    from src.commands import command_run
+    env = CapturingEnv()
+    cmd = CommandRun(None, env, options={"input-clipboard": True})
+    monkeypatch.setattr(command_run, "read_clipboard", lambda: "1 2\n")
+    monkeypatch.setattr(command_run.temp_space, "CONTAINER_BASE", str(tmp_path / ".temp" / "sessions"))
+    monkeypatch.setattr(cmd, "prepare", lambda c, p, l: (FakeHandler(), "cont", "main.py"))
+    asyncio.run(cmd.run("abc300", "a", "python"))
+    assert env.executed == ["1 2\n"]
@@ -0,0 +1,67 @@
+import asyncio
+import pytest
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_switch import split_problem_id, step_problem, task_url
+from src.commands.sample_scraper import page_title
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.testkit import AtCoderMock, ContestSimulation, atcoder_task_url, setup_test_environment
+
+def task_page(title):
+    return f"<html><head><title>{title}</title></head><body></body></html>"
+
+def test_problem_id_and_steps():
+    assert split_problem_id("abc300_c") == ("abc300", "c")
+    assert split_problem_id("abc300") is None
+    assert split_problem_id("abc300_z") is None
+    assert step_problem("a", 1) == "b"
+    assert step_problem("b", -1) == "a"
+    with pytest.raises(ValueError):
+        step_problem("a", -1)
+    with pytest.raises(ValueError):
+        step_problem("ex", 1)
+
+def test_task_url_by_site():
+    assert task_url("abc300", "c") == atcoder_task_url("abc300", "c")
+    assert task_url("cf1900", "b1") == "https://codeforces.com/contest/1900/problem/B1"
+
+def test_page_title():
+    assert page_title("<TITLE>\n  A - N&amp;M </title>") == "A - N&M"
+    assert page_title("<p>no title</p>") is None
+
+def test_switch_next_prev_and_problem_id(tmp_path, capsys):
+    site = AtCoderMock({atcoder_task_url("abc300", "b"): task_page("B - Same Letters")})
+    site.add_problem("abc300", "a", [("1\n", "1\n"), ("2\n", "2\n")])
+    workspace = setup_test_environment(tmp_path / "ws", config={"switch": {"browser": True, "editor": True}})
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        executor = sim.executor()
+        capsys.readouterr()
+        assert asyncio.run(executor.switch("abc300", "a", "python", ["next"])) == ("abc300", "b")
+        out = capsys.readouterr().out
+        assert "[switch] abc300 b: B - Same Letters" in out
+        assert "サンプルがありません（取得するには: open abc300 b）" in out
+        assert executor.opener.opened_urls == [atcoder_task_url("abc300", "b")]
+        assert executor.opener.opened_paths
+        # 取得した問題名はarchive.jsonに残り、次からは通信しない
+        assert ArchiveIndex(upm=UnifiedPathManager(str(workspace.root))).get("abc300", "b")["title"] == "B - Same Letters"
+        site.online = False
+        assert asyncio.run(executor.switch("abc300", "b", "python", ["prev"])) == ("abc300", "a")
+        assert "サンプル 2件" in capsys.readouterr().out
+        asyncio.run(executor.switch("abc300", "a", "python", ["abc300_b"]))
+        assert "[switch] abc300 b: B - Same Letters" in capsys.readouterr().out
+        assert workspace.read_json("contest_current", "system_info.json")["problem_name"] == "b"
+
+def test_switch_reads_codeforces_title(tmp_path, capsys):
+    site = AtCoderMock({"https://codeforces.com/contest/1900/problem/A": task_page("Problem - A - Codeforces")})
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        asyncio.run(sim.executor().switch("cf1900", "a", "python", []))
+    assert "[switch] cf1900 a: Problem - A - Codeforces" in capsys.readouterr().out
+
+def test_switch_rejects_unknown_target(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace) as sim:
+        with pytest.raises(ValueError):
+            asyncio.run(sim.executor().switch("abc300", "a", "python", ["next"]))
+        with pytest.raises(ValueError):
+            asyncio.run(sim.executor().switch("abc300", "a", "python", ["later"]))
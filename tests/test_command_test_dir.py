@@ -0,0 +1,79 @@
+import asyncio
+import pytest
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_test_dir import CommandTestDir, detect_cases, detect_source, source_languages
+from src.info_json_manager import InfoJsonManager
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+SOLUTION = "a, b = map(int, input().split())\nprint(a + b)\n"
+
+def write(path, text):
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(text, encoding="utf-8")
+
+def test_detect_cases_recognizes_common_layouts(tmp_path):
+    # conftestが作るワークスペースの外（展開した問題）に見立てる
+    root = tmp_path / "unzipped"
+    write(root / "sample-1.in", "1 2\n")
+    write(root / "sample-1.out", "3\n")
+    write(root / "in" / "01_random.txt", "2 3\n")
+    write(root / "out" / "01_random.txt", "5\n")
+    write(root / "tests" / "input_2.txt", "4 4\n")
+    write(root / "tests" / "output_2.txt", "8\n")
+    write(root / "extra.in", "0 0\n")
+    write(root / "input.py", SOLUTION)
+    cases = detect_cases(root, exclude=[root / "input.py"])
+    assert [(name, out_file is not None) for name, _, out_file in cases] == [
+        ("01_random.txt", True), ("extra", False), ("sample-1", True), ("tests-2.txt", True)]
+
+def test_detect_source_prefers_main(tmp_path):
+    # conftestが作るワークスペースの外（展開した問題）に見立てる
+    root = tmp_path / "unzipped"
+    languages = source_languages({"nim": {"source": "main.nim"}, "python": {"isolate": False}})
+    assert languages == {".py": "python", ".nim": "nim"}
+    write(root / "gen.py", "")
+    write(root / "main.py", SOLUTION)
+    assert detect_source(root, languages) == (root / "main.py", "python")
+    (root / "main.py").unlink()
+    write(root / "brute.py", "")
+    with pytest.raises(ValueError):
+        detect_source(root, languages)
+
+def test_run_outside_workspace(tmp_path, capsys):
+    problem = tmp_path / "unzipped"
+    write(problem / "main.py", SOLUTION)
+    write(problem / "in" / "a.txt", "1 2\n")
+    write(problem / "out" / "a.txt", "3\n")
+    write(problem / "in" / "b.txt", "2 2\n")
+    write(problem / "out" / "b.txt", "5\n")
+    results = asyncio.run(CommandTestDir(options={"dir": str(problem), "jobs": "1"}).run())
+    assert [r["verdict"] for r in results] == ["AC", "WA"]
+    assert "[test] 1/2 AC" in capsys.readouterr().out
+    # 元のディレクトリには何も増やさない
+    assert sorted(p.name for p in problem.iterdir()) == ["in", "main.py", "out"]
+
+def test_run_accepts_source_file_and_rejects_missing_cases(tmp_path):
+    # conftestが作るワークスペースの外（展開した問題）に見立てる
+    root = tmp_path / "unzipped"
+    write(root / "solve.py", SOLUTION)
+    with pytest.raises(ValueError):
+        asyncio.run(CommandTestDir().run(str(root / "solve.py")))
+    write(root / "1.in", "5 6\n")
+    write(root / "1.out", "11\n")
+    results = asyncio.run(CommandTestDir().run(str(root / "solve.py")))
+    assert [r["verdict"] for r in results] == ["AC"]
+
+def test_run_ignores_active_problem_settings(tmp_path):
+    # 作業中の問題のtag --compare（yes/noの大文字小文字を区別しない）は、別のディレクトリの判定に使わない
+    upm = UnifiedPathManager()
+    info = InfoJsonManager(str(upm.info_json()))
+    info.data.update(contest_name="abc300", problem_name="a")
+    info.save()
+    ArchiveIndex().update("abc300", "a", lambda e: e.update(compare={"yes_no": True}, output_limit="1B"))
+    root = tmp_path / "unzipped"
+    write(root / "main.py", "print('YES')\n")
+    write(root / "1.in", "\n")
+    write(root / "1.out", "Yes\n")
+    results = asyncio.run(CommandTestDir(options={"dir": str(root)}).run())
+    assert [r["verdict"] for r in results] == ["WA"]
+    assert results[0]["result"][1].strip() == "YES"
@@ -0,0 +1,51 @@
+import asyncio
+import json
+from src.commands.command_verify import CommandVerify, find_problem_url
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+URL = "https://judge.yosupo.jp/problem/aplusb"
+
+def test_find_problem_url():
+    assert find_problem_url(f"# verification-helper: PROBLEM {URL}\n") == URL
+    assert find_problem_url("print(1)") is None
+
+class DummyEnv:
+    def __init__(self):
+        self.downloads = []
+    def download_testcases(self, url, test_dir_host):
+        import os
+        self.downloads.append(url)
+        os.makedirs(test_dir_host, exist_ok=True)
+        with open(os.path.join(test_dir_host, "sample-1.in"), "w") as f:
+            f.write("1 2\n")
+        with open(os.path.join(test_dir_host, "sample-1.out"), "w") as f:
+            f.write("3\n")
+
+def make_verify(tmp_path, source):
+    lib = tmp_path / "team" / "lib"
+    lib.mkdir(parents=True)
+    (lib / "aplusb.test.py").write_text(f"# verification-helper: PROBLEM {URL}\n{source}")
+    env = DummyEnv()
+    cmd = CommandVerify(None, env)
+    cmd.upm = UnifiedPathManager(str(tmp_path))
+    return cmd, env
+
+def test_verify_writes_timestamps_and_summary(tmp_path, capsys):
+    cmd, env = make_verify(tmp_path, "a, b = map(int, input().split())\nprint(a + b)\n")
+    statuses = asyncio.run(cmd.verify())
+    assert statuses == {"team/lib/aplusb.test.py": "AC"}
+    timestamps = json.loads((tmp_path / ".verify-helper" / "timestamps.remote.json").read_text())
+    assert "team/lib/aplusb.test.py" in timestamps
+    assert "verify-AC" in (tmp_path / ".verify-helper" / "markdown" / "summary.md").read_text()
+    # 変更がなければ再検証しない
+    capsys.readouterr()
+    asyncio.run(cmd.verify())
+    assert "[verify] team/lib/aplusb.test.py" not in capsys.readouterr().out
+    assert env.downloads == [URL]
+
+def test_verify_wrong_answer(tmp_path):
+    cmd, env = make_verify(tmp_path, "print(0)\n")
+    statuses = asyncio.run(cmd.verify())
+    assert statuses == {"team/lib/aplusb.test.py": "WA"}
+    timestamps = json.loads((tmp_path / ".verify-helper" / "timestamps.remote.json").read_text())
+    assert timestamps == {}
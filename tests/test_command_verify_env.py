@@ -0,0 +1,93 @@
+import asyncio
+import json
+from src.commands import command_verify_env
+from src.commands.command_verify_env import BUILD, CHECKS, EXIT_CODE, IO, TL, CommandVerifyEnv
+from src.environment.execution_manager_test_environment import ExecutionManagerTestEnvironment
+from execution_client.execution_manager import ExecutionManager
+from execution_client.local.client import LocalAsyncClient
+
+def local_env():
+    return ExecutionManagerTestEnvironment(None, ExecutionManager(LocalAsyncClient()))
+
+class FakeEnv:
+    """docker環境の代わり。実行したコマンドを記録し、決めた結果を返す"""
+    uses_containers = True
+
+    def __init__(self, enforce_tl=True, build_ok=True):
+        self.enforce_tl = enforce_tl
+        self.build_ok = build_ok
+        self.ctl = self
+        self.requirements = None
+        self.commands = []
+
+    def adjust_containers(self, requirements, *args):
+        self.requirements = requirements
+        return [{"type": r["type"], "language": r.get("language"), "name": f"cph_{r['type']}_{r.get('language')}_1"} for r in requirements]
+
+    def to_container_path(self, path):
+        return str(path)
+
+    def run_and_measure(self, name, cmd, timeout=None, **kwargs):
+        class Result:
+            returncode = 0 if self.build_ok else 1
+            stdout = ""
+            stderr = "" if self.build_ok else "error: could not compile\n"
+        return Result()
+
+    def exec_command(self, container, cmd, in_file=None):
+        self.commands.append((container, cmd))
+        line = open(in_file, encoding="utf-8").read().split()
+        if line[0] == "echo":
+            return True, f"hello {line[1]}\n", ""
+        if line[0] == "loop":
+            return (False, "loop\n", "") if self.enforce_tl else (True, "loop\nescaped\n", "")
+        return False, "exit\n", ""
+
+def test_python_passes_every_check_locally(capsys):
+    code = asyncio.run(CommandVerifyEnv(None, local_env()).verify_env("python"))
+    out = capsys.readouterr().out
+    assert code == 0, out
+    assert "[verify-env] local で" in out
+    assert "すべての言語が使えます" in out
+
+def test_check_language_reports_each_check(tmp_path):
+    results = CommandVerifyEnv(None, local_env()).check_language("python", "verify-env-python", tmp_path)
+    assert [results[c][0] for c in CHECKS] == [True, True, True, True]
+    assert "秒で停止" in results[TL][1]
+
+def test_unenforced_tl_is_red_and_exit_code_one(capsys):
+    env = FakeEnv(enforce_tl=False)
+    code = asyncio.run(CommandVerifyEnv(None, env).verify_env("python"))
+    out = capsys.readouterr().out
+    assert code == 1
+    assert "python TL: 1秒で止まりませんでした" in out
+    # テスト用コンテナで実行し、ojtoolsのコンテナは残す
+    assert [r["type"] for r in env.requirements] == ["test", "ojtools"]
+    assert {c for c, _ in env.commands} == {"cph_test_python_1"}
+    assert env.commands[1][1][:3] == ["timeout", "--signal=KILL", "1"]
+
+def test_build_failure_skips_remaining_checks(tmp_path):
+    results = CommandVerifyEnv(None, FakeEnv(build_ok=False)).check_language("rust", "cph_test_rust_1", tmp_path)
+    assert results[BUILD] == (False, "error: could not compile")
+    assert results[IO] is None and results[TL] is None and results[EXIT_CODE] is None
+    assert (tmp_path / "src" / "main.rs").exists() and 'name = "rust"' in (tmp_path / "Cargo.toml").read_text()
+
+def test_custom_language_needs_smoke_source(tmp_path):
+    config = json.load(open("contest_current/config.json"))
+    config["languages"] = {"ruby": {"source": "main.rb", "run": "ruby {source}"}}
+    json.dump(config, open("contest_current/config.json", "w"))
+    cmd = CommandVerifyEnv(None, FakeEnv())
+    assert cmd.languages() == ["python", "pypy", "rust", "ruby"]
+    results = cmd.check_language("ruby", "w", tmp_path / "ruby")
+    assert results[BUILD][0] is False and "smoke_source" in results[BUILD][1]
+
+def test_matrix_marks_and_accessible_lines(monkeypatch):
+    rows = {"python": {BUILD: (True, ""), IO: (True, ""), TL: (False, "x"), EXIT_CODE: (True, "")},
+            "rust": {BUILD: (False, "e"), IO: None, TL: None, EXIT_CODE: None}}
+    cmd = CommandVerifyEnv(None, FakeEnv())
+    monkeypatch.setattr(command_verify_env, "is_accessible", lambda: False)
+    lines = cmd.format_matrix(rows)
+    assert lines[0].split() == ["言語", "ビルド", "入出力", "TL", "終了コード"]
+    assert "NG" in lines[1] and lines[2].split()[-3:] == ["-", "-", "-"]
+    monkeypatch.setattr(command_verify_env, "is_accessible", lambda: True)
+    assert cmd.format_matrix(rows)[1] == "rust: ビルド FAIL、入出力 未確認、TL 未確認、終了コード 未確認"
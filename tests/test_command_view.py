@@ -0,0 +1,94 @@
+import asyncio
+import tarfile
+import zipfile
+import pytest
+from src.command_parser import CommandParser
+from src.commands.command_view import CommandView, find_problems
+from src.path_manager.path_validator import PathEscapeError
+from src.testkit import ContestSimulation, setup_test_environment
+
+SOLUTION = "a, b = map(int, input().split())\nprint(a + b)\n"
+
+def write_stock(root):
+    """他の人のcontest_stocksと同じ並び（{contest}/{problem}/{language}, {contest}/{problem}/test）を作る"""
+    problem = root / "contest_stocks" / "abc300" / "a"
+    (problem / "python").mkdir(parents=True)
+    (problem / "python" / "main.py").write_text(SOLUTION, encoding="utf-8")
+    (problem / "test").mkdir()
+    (problem / "test" / "sample-1.in").write_text("1 2\n", encoding="utf-8")
+    (problem / "test" / "sample-1.out").write_text("3\n", encoding="utf-8")
+    (problem / "test" / "sample-2.in").write_text("5 5\n", encoding="utf-8")
+    (problem / "test" / "sample-2.out").write_text("11\n", encoding="utf-8")
+    other = root / "contest_stocks" / "abc301" / "b"
+    (other / "pypy").mkdir(parents=True)
+    (other / "pypy" / "main.py").write_text("print(0)\n", encoding="utf-8")
+    return root
+
+def make_tar(tmp_path):
+    src = write_stock(tmp_path / "alice")
+    archive = tmp_path / "alice.tar.gz"
+    with tarfile.open(archive, "w:gz") as tf:
+        tf.add(src / "contest_stocks", arcname="contest_stocks")
+    return archive
+
+def view(archive, contest_name=None, problem_name=None, language_name=None, exec_mode="local", **options):
+    cmd = CommandView(exec_mode, options=options, args=[str(archive)])
+    return asyncio.run(cmd.view(contest_name, problem_name, language_name))
+
+def test_find_problems(tmp_path):
+    root = write_stock(tmp_path / "alice")
+    problems = find_problems(root)
+    assert sorted(problems) == [("abc300", "a"), ("abc301", "b")]
+    assert problems[("abc300", "a")]["languages"] == ["python"]
+    assert problems[("abc300", "a")]["cases"] == ["sample-1.in", "sample-2.in"]
+    assert problems[("abc301", "b")]["cases"] == []
+
+def test_view_lists_and_shows_solution(tmp_path, capsys):
+    archive = make_tar(tmp_path)
+    shown = view(archive)
+    assert sorted(shown) == [("abc300", "a"), ("abc301", "b")]
+    out = capsys.readouterr().out
+    assert "[view] abc300 a: python（テストケース 2件）" in out
+    view(archive, "abc300", "a")
+    out = capsys.readouterr().out
+    assert "===== python/main.py =====" in out and "print(a + b)" in out
+    with pytest.raises(FileNotFoundError):
+        view(archive, "abc300", "c")
+    with pytest.raises(FileNotFoundError):
+        view(archive, "abc300", "a", "rust")
+
+def test_view_zip_rejects_escaping_members(tmp_path):
+    archive = tmp_path / "evil.zip"
+    with zipfile.ZipFile(archive, "w") as zf:
+        zf.writestr("../outside/abc300/a/python/main.py", "print(1)\n")
+    with pytest.raises(PathEscapeError):
+        view(archive)
+    assert not (tmp_path / "outside").exists()
+
+def test_view_run_does_not_touch_own_workspace(tmp_path, capsys):
+    archive = make_tar(tmp_path)
+    workspace = setup_test_environment(tmp_path / "ws")
+    workspace.write_source("python", "print('mine')\n")
+    info_before = workspace.path("contest_current", "system_info.json").read_text(encoding="utf-8")
+    with ContestSimulation(workspace):
+        results = view(archive, "abc300", "a", "python", run=True)
+    assert {r["name"]: r["verdict"] for r in results} == {"sample-1.in": "AC", "sample-2.in": "WA"}
+    assert "sample-2.in" in capsys.readouterr().out
+    assert workspace.path("contest_current", "python", "main.py").read_text(encoding="utf-8") == "print('mine')\n"
+    assert workspace.path("contest_current", "system_info.json").read_text(encoding="utf-8") == info_before
+    assert not workspace.path("contest_current", "test").exists()
+    assert not workspace.path(".cph", "runs").exists()
+
+def test_parser_keeps_archive_path():
+    parser = CommandParser()
+    parser.parse(["view", "reviews/alice.tar.gz", "abc300", "a", "--run"])
+    assert parser.parsed["command"] == "view"
+    assert (parser.parsed["contest_name"], parser.parsed["problem_name"]) == ("abc300", "a")
+    assert parser.extra_args == ["reviews/alice.tar.gz"]
+    assert parser.options == {"run": True}
+
+def test_archive_named_like_contest(tmp_path):
+    archive = make_tar(tmp_path).rename(tmp_path / "abc300.tar.gz")
+    cmd = CommandView("local", args=[])
+    shown = asyncio.run(cmd.view(str(archive)))
+    assert ("abc300", "a") in shown
@@ -863,4 +863,27 @@ class DummyTestEnv:
             raise RuntimeError("ojtools用コンテナがsystem_info.jsonにありません")
         if any("no_ojtools" in str(a) for a in args):
             raise RuntimeError("ojtools用コンテナがsystem_info.jsonにありません")
-        return True, "ok", "" 
\ No newline at end of file
+        return True, "ok", "" 
+
+
+def test_every_command_is_documented_and_dispatched(monkeypatch, capsys):
+    import sys
+    from src import main as mainmod
+    from src.command_parser import COMMANDS
+    # どのコマンドもヘルプに載っていて、mainからexecutorまで届くこと（1つの入口ですべてのコマンドが使える）
+    mainmod.print_help()
+    help_text = capsys.readouterr().out
+    called = []
+    class DummyExecutor:
+        def __getattr__(self, name):
+            async def method(*a, **k):
+                called.append(name)
+            return method
+    monkeypatch.setattr(mainmod, "CommandExecutor", lambda *a, **k: DummyExecutor())
+    for command, spec in COMMANDS.items():
+        assert f"  {command}" in help_text, command
+        for word in [command] + spec["aliases"]:
+            called.clear()
+            monkeypatch.setattr(sys, "argv", ["main.py", "abc300", word, "a", "python"])
+            mainmod.main()
+            assert called, word
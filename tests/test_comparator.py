@@ -0,0 +1,163 @@
+import pytest
+from src.error_hints import CphError
+from src.commands.comparator import ExactComparator, UnorderedLinesComparator, YesNoComparator, create_comparator, merge_compare_specs, parse_compare_option
+from src.commands.case_storage import alternative_expected_files, expected_stem
+from src.commands.test_result_formatter import ResultFormatter, judge_verdict
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+def test_exact_comparator():
+    c = ExactComparator()
+    assert c.match("1\n2\n", "1\n2")
+    assert not c.match("1\n2\n", "2\n1\n")
+
+def test_unordered_lines_comparator():
+    c = UnorderedLinesComparator()
+    assert c.match("1 2\n3 4\n", "3 4\n1 2\n")
+    assert not c.match("1 2\n3 4\n", "3 4\n1 3\n")
+
+def test_unordered_lines_per_block():
+    c = UnorderedLinesComparator(per_block=True)
+    assert c.match("a\nb\n\nc\nd\n", "b\na\n\nd\nc\n")
+    # ブロックをまたいだ入れ替えは不一致
+    assert not c.match("a\nb\n\nc\nd\n", "c\nd\n\na\nb\n")
+
+def test_create_comparator_from_config():
+    assert isinstance(create_comparator(None), ExactComparator)
+    assert isinstance(create_comparator("unordered-lines"), UnorderedLinesComparator)
+    c = create_comparator({"mode": "unordered-lines", "per_block": True})
+    assert c.per_block is True
+    with pytest.raises(ValueError):
+        create_comparator("unknown")
+
+def test_judge_verdict_with_comparator():
+    result = {"result": (0, "2\n1\n", ""), "expected": "1\n2\n"}
+    assert judge_verdict(result) == "WA"
+    assert judge_verdict(result, UnorderedLinesComparator()) == "AC"
+
+def test_command_test_uses_config_compare(tmp_path, monkeypatch):
+    import json
+    from src.commands.command_test import CommandTest
+    config = tmp_path / "contest_current" / "config.json"
+    config.write_text(json.dumps({"compare": "unordered-lines"}), encoding="utf-8")
+    cmd = CommandTest(None, None)
+    assert cmd.is_all_ac([{"result": (0, "b\na\n", ""), "expected": "a\nb\n"}]) is True
+
+def test_tokens_and_float_comparators():
+    assert create_comparator("tokens").match("1 2\n3\n", "1\n2 3")
+    assert not create_comparator("tokens").match("1 2", "1 2 3")
+    c = create_comparator(parse_compare_option("float:1e-6"))
+    assert c.match("0.3333333 abc\n", "0.33333331 abc")
+    assert c.match("1000000.0", "1000000.5")  # 相対誤差で一致
+    assert not c.match("0.5", "0.6")
+    assert not c.match("1 2", "1")
+
+def test_parse_compare_option():
+    assert parse_compare_option("exact") == {"mode": "exact"}
+    assert parse_compare_option("unordered") == {"mode": "unordered"}
+    assert parse_compare_option("float:1e-9") == {"mode": "float", "eps": 1e-9}
+    assert parse_compare_option("checker:./chk") == {"mode": "checker", "path": "./chk"}
+    for bad in ["unknown", "checker", "tokens:1"]:
+        with pytest.raises(ValueError):
+            parse_compare_option(bad)
+    with pytest.raises(ValueError, match="float:1e-6"):
+        parse_compare_option("float:abc")
+
+def test_checker_comparator(tmp_path):
+    checker = tmp_path / "checker.sh"
+    # 出力（$2）と答え（$3）の1行目が等しく、入力（$1）が空でなければAC
+    checker.write_text("#!/bin/sh\n[ \"$(head -n1 \"$2\")\" = \"$(head -n1 \"$3\")\" ] && grep -q . \"$1\"\n", encoding="utf-8")
+    checker.chmod(0o755)
+    in_file = tmp_path / "1.in"
+    in_file.write_text("3\n", encoding="utf-8")
+    c = create_comparator(parse_compare_option(f"checker:{checker}"))
+    assert c.match("6\n", "6\n", input_file=str(in_file))
+    assert not c.match("6\n", "7\n", input_file=str(in_file))
+    assert not c.match("6\n", "6\n")  # 入力が無いと空ファイルが渡される
+
+def test_checker_comparator_missing_binary(tmp_path):
+    c = create_comparator(parse_compare_option(f"checker:{tmp_path / 'missing'}"))
+    with pytest.raises(CphError) as e:
+        c.match("6\n", "6\n")
+    assert e.value.kind == "checker_failed" and "missing" in str(e.value)
+
+def test_yes_no_comparator_wraps_other_modes():
+    c = create_comparator({"mode": "tokens", "yes_no": True})
+    assert isinstance(c, YesNoComparator)
+    assert c.match("Yes\n1 2\n", "YES 1\n2")
+    assert c.match("No", "no")
+    assert not c.match("Yes", "No")
+    # 語の一部は置き換えない
+    assert not c.match("Yes", "Yess")
+    assert not create_comparator("tokens").match("Yes", "YES")
+    assert create_comparator(parse_compare_option("yesno")).match("Yes\nNo\n", "yes\nNO")
+    assert create_comparator(parse_compare_option("unordered+yesno")).match("Yes\nNo\n", "no\nyes")
+
+def test_parse_compare_option_with_modifier():
+    assert parse_compare_option("yesno") == {"yes_no": True}
+    assert parse_compare_option("tokens+yesno") == {"mode": "tokens", "yes_no": True}
+    assert parse_compare_option("float:1e-6+yesno") == {"mode": "float", "eps": 1e-6, "yes_no": True}
+
+def test_merge_compare_specs():
+    assert merge_compare_specs("tokens", None) == "tokens"
+    assert merge_compare_specs("tokens", {"yes_no": True}) == {"mode": "tokens", "yes_no": True}
+    assert merge_compare_specs(None, {"yes_no": True}) == {"yes_no": True}
+
+def test_mode_override_replaces_base_arguments():
+    # config.jsonがfloat（eps付き）で、問題ごとにtokensにしたとき
+    spec = merge_compare_specs({"mode": "float", "eps": 1e-3, "yes_no": True}, {"mode": "tokens"})
+    assert spec == {"mode": "tokens", "yes_no": True}
+    assert create_comparator(spec).match("Yes 1\n", "YES\n1")
+
+def test_command_test_uses_problem_compare(tmp_path):
+    import asyncio
+    from src.commands.command_tag import CommandTag
+    from src.commands.command_test import CommandTest
+    from src.commands.archive_index import ArchiveIndex
+    from src.path_manager.unified_path_manager import UnifiedPathManager
+    UnifiedPathManager().info_json().write_text('{"contest_name": "abc300", "problem_name": "a"}', encoding="utf-8")
+    result = {"result": (0, "YES\n", ""), "expected": "Yes\n"}
+    assert CommandTest(None, None).is_all_ac([result]) is False
+    # contest_currentの問題（abc300 a）だけ、Yes/YESを同じとみなす
+    asyncio.run(CommandTag({"compare": "yesno"}).tag("abc300", "a"))
+    assert ArchiveIndex().get("abc300", "a")["compare"] == {"yes_no": True}
+    assert CommandTest(None, None).is_all_ac([result]) is True
+    asyncio.run(CommandTag({"compare": "default"}).tag("abc300", "a"))
+    assert CommandTest(None, None).is_all_ac([result]) is False
+
+def test_alternative_expected_outputs_are_discovered_in_tag_order(tmp_path):
+    for name in ("sample-3.in", "sample-3.out", "sample-3.b.out", "sample-3.a.out", "sample-30.a.out", "sample-3.ab.out"):
+        (tmp_path / name).write_text("x\n")
+    found = alternative_expected_files(str(tmp_path / "sample-3.in"))
+    assert [p.rsplit("/", 1)[1] for p in found] == ["sample-3.a.out", "sample-3.b.out"]
+    assert expected_stem(str(tmp_path / "sample-3.b.out")) == str(tmp_path / "sample-3")
+    assert expected_stem(str(tmp_path / "sample-3.out")) == str(tmp_path / "sample-3")
+
+def test_output_of_own_case_is_not_an_alternative(tmp_path):
+    for name in ("x.in", "x.out", "x.a.out", "x.b.in", "x.b.out"):
+        (tmp_path / name).write_text("x\n")
+    found = alternative_expected_files(str(tmp_path / "x.in"))
+    assert [p.rsplit("/", 1)[1] for p in found] == ["x.a.out"]
+
+def test_judge_verdict_accepts_any_alternative():
+    result = {"result": (0, "3 1\n", ""), "expected": "1 3\n", "alternatives": ["2 2\n", "3 1\n"]}
+    assert judge_verdict(result) == "AC"
+    result["result"] = (0, "4 0\n", "")
+    assert judge_verdict(result) == "WA"
+    # 比較方法は候補ごとに使う
+    result["result"] = (0, "3\n1\n", "")
+    assert judge_verdict(result, create_comparator("tokens")) == "AC"
+    lines = ResultFormatter({**result, "name": "sample-1.in", "time": 0.1, "verdict": "WA"}, width=80, accessible=True).format()
+    assert "ほかに受理する期待出力が2件あり" in lines
+
+def test_test_accepts_any_listed_expected_output(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("4\n", "1 3\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "n = int(input())\nprint(n - 1, 1)\n")
+        test_dir = workspace.path("contest_current", "test")
+        (test_dir / "sample-1.a.out").write_text("3 1\n", encoding="utf-8")
+        workspace.write_case("own-1", "6\n", "1 5\n")
+        results = sim.execute("test", "abc300", "a", "python")
+    assert {r["name"]: r["verdict"] for r in results} == {"sample-1.in": "AC", "own-1.in": "WA"}
@@ -0,0 +1,59 @@
+import asyncio
+import json
+from src.environment.test_language_handler import parse_rustc_warnings, RustTestHandler
+from src.commands.command_test import CommandTest
+from src.commands.run_artifacts import RunArtifacts
+from src.commands.test_result_formatter import ResultFormatter
+
+CARGO_STDERR = """   Compiling rust v0.1.0 (/workspace/.temp/rust)
+warning: unused variable: `x`
+ --> src/main.rs:2:9
+  |
+2 |     let x = 1;
+  |         ^ help: if this is intentional, prefix it with an underscore: `_x`
+  |
+  = note: `#[warn(unused_variables)]` on by default
+
+warning: function `helper` is never used
+ --> src/main.rs:5:4
+
+warning: `rust` (bin "rust") generated 2 warnings
+    Finished release [optimized] target(s) in 0.52s
+"""
+
+class LocalEnv:
+    uses_containers = False
+
+    def to_container_path(self, path):
+        return path
+
+class WarningHandler:
+    def build(self, manager, name, source_path):
+        return True, "", CARGO_STDERR
+
+    def compile_warnings(self, stdout, stderr):
+        return RustTestHandler().compile_warnings(stdout, stderr)
+
+def test_parse_rustc_warnings():
+    assert parse_rustc_warnings(CARGO_STDERR) == [
+        "unused variable: `x` (src/main.rs:2:9)",
+        "function `helper` is never used (src/main.rs:5:4)",
+    ]
+    assert parse_rustc_warnings("warning: 1 warning emitted\n") == []
+    assert parse_rustc_warnings("") == []
+
+def test_warnings_keep_ac_and_are_reported(monkeypatch, tmp_path):
+    from src.commands import command_test
+    monkeypatch.setitem(command_test.HANDLERS, "rust", WarningHandler())
+    cmd = CommandTest(None, LocalEnv(), options={"jobs": "1"})
+    def run_single_case(ctl, container, in_file, language_name, source_path, i, total):
+        return {"name": in_file, "result": (0, "1\n", ""), "expected": "1\n", "time": 0.1, "verdict": "AC"}
+    monkeypatch.setattr(cmd, "run_single_case", run_single_case)
+    results = asyncio.run(cmd.run_test_cases(".temp/rust", ["sample-1.in"], "rust"))
+    assert cmd.is_all_ac(results)
+    assert len(results[0]["warnings"]) == 2
+    assert "(警告あり: 2件)" in ResultFormatter(results[0]).format()
+    path = RunArtifacts(project_root=tmp_path).save_results(results)
+    data = json.loads((path / "run.json").read_text(encoding="utf-8"))
+    assert data["compile_warnings"][0].startswith("unused variable")
+    assert data["cases"][0]["warnings"] == 2
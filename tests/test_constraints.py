@@ -0,0 +1,82 @@
+from src.commands.archive_index import ArchiveIndex
+from src.commands.constraints import compact_constraint, format_constraints, parse_constraints, parse_max_n
+from src.testkit import AtCoderMock, ContestSimulation, atcoder_task_url, setup_test_environment
+
+SOLUTION = "a, b = map(int, input().split())\nprint(a + b)\n"
+# AtCoderの問題ページと同じく、日本語と英語の両方に制約の節がある
+PAGE = """<html><body><div id="task-statement"><span class="lang">
+<span class="lang-ja"><div class="part"><section><h3>問題文</h3><p><var>A+B</var> を出力してください。</p></section></div>
+<div class="part"><section><h3>制約</h3><ul>
+<li><var>1 \\leq A, B \\leq 2 \\times 10^{5}</var></li>
+<li>入力はすべて整数</li>
+</ul></section></div>
+<div class="part"><section><h3>入力</h3><p>...</p></section></div></span>
+<span class="lang-en"><div class="part"><section><h3>Constraints</h3><ul><li><var>1 \\leq A, B \\leq 2 \\times 10^{5}</var></li></ul></section></div></span>
+</span></div></body></html>"""
+
+def test_compact_constraint_replaces_latex():
+    assert compact_constraint(r"$1 \le N \le 2 \times 10^{5}$") == "1 ≤ N ≤ 2 × 10^5"
+    assert compact_constraint(r"A_1 \neq A_2, \ldots, K \lt N") == "A_1 ≠ A_2, …, K < N"
+    assert compact_constraint(r"\mathrm{S} \geq 0") == "S ≥ 0"
+
+def test_parse_constraints_reads_first_section_only():
+    assert parse_constraints(PAGE) == ["1 ≤ A, B ≤ 2 × 10^5", "入力はすべて整数"]
+    assert parse_constraints("<html><body><h3>問題文</h3><ul><li>x</li></ul></body></html>") == []
+
+def test_max_n_is_read_from_recorded_constraints():
+    # problem.jsonのconstraints（parse_constraintsで整えたもの）からも、問題文の表記からも読める
+    constraints = parse_constraints(PAGE)
+    assert parse_max_n("\n".join(constraints), "A") == 200000
+    assert parse_max_n(r"1 \leq A \leq 2\,000", "A") == 2000
+
+def test_format_constraints_wraps_long_lists():
+    assert format_constraints([]) == []
+    assert format_constraints(["1 ≤ N ≤ 10", "入力はすべて整数"]) == ["[制約] 1 ≤ N ≤ 10 / 入力はすべて整数"]
+    long = [f"1 ≤ A_{i} ≤ 10^9" for i in range(10)]
+    assert format_constraints(long) == ["[制約]"] + [f"  {c}" for c in long]
+
+def simulation(tmp_path, config=None):
+    workspace = setup_test_environment(tmp_path / "ws", config=config)
+    site = AtCoderMock(routes={atcoder_task_url("abc300", "a"): PAGE})
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    return workspace, ContestSimulation(workspace, site)
+
+def test_open_records_constraints_and_test_shows_them(tmp_path, capsys):
+    workspace, sim = simulation(tmp_path)
+    with sim:
+        sim.execute("open", "abc300", "a", "python")
+        assert ArchiveIndex().get("abc300", "a")["constraints"] == ["1 ≤ A, B ≤ 2 × 10^5", "入力はすべて整数"]
+        workspace.write_source("python", SOLUTION)
+        capsys.readouterr()
+        sim.execute("test", "abc300", "a", "python")
+    out = capsys.readouterr().out
+    assert out.startswith("[制約] 1 ≤ A, B ≤ 2 × 10^5 / 入力はすべて整数\n")
+
+def test_show_constraints_can_be_disabled(tmp_path, capsys):
+    workspace, sim = simulation(tmp_path, config={"test": {"show_constraints": False}})
+    with sim:
+        sim.execute("open", "abc300", "a", "python")
+        assert "constraints" not in ArchiveIndex().get("abc300", "a")
+        workspace.write_source("python", SOLUTION)
+        sim.execute("test", "abc300", "a", "python")
+    assert "[制約]" not in capsys.readouterr().out
+
+def test_statement_constraints_fetches_when_missing(tmp_path, capsys):
+    workspace, sim = simulation(tmp_path, config={"test": {"show_constraints": False}})
+    with sim:
+        sim.execute("open", "abc300", "a", "python")
+        capsys.readouterr()
+        assert sim.execute("statement", options={"constraints": True}) == ["1 ≤ A, B ≤ 2 × 10^5", "入力はすべて整数"]
+        assert ArchiveIndex().get("abc300", "a")["constraints"][1] == "入力はすべて整数"
+        assert sim.execute("statement") == atcoder_task_url("abc300", "a")
+    assert "  1 ≤ A, B ≤ 2 × 10^5" in capsys.readouterr().out
+
+def test_open_reads_constraints_from_the_scraped_page(tmp_path):
+    # oj downloadに失敗して問題ページからサンプルを取り出したときは、そのページから制約も読む
+    samples = '<h3>入力例 1</h3><pre>1 2\n</pre><h3>出力例 1</h3><pre>3\n</pre>'
+    url = atcoder_task_url("abc300", "a")
+    site = AtCoderMock(routes={url: PAGE.replace("</span>\n<span class=\"lang-en\">", samples + "</span>\n<span class=\"lang-en\">")})
+    with ContestSimulation(setup_test_environment(tmp_path / "ws"), site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        assert ArchiveIndex().get("abc300", "a")["constraints"][0] == "1 ≤ A, B ≤ 2 × 10^5"
+    assert [u for _, u in site.transport.requests if u == url] == [url]
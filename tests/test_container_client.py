@@ -128,4 +128,41 @@ def test_is_container_running(mock_run):
     mock_run.return_value.stdout = "false"
     assert not client.is_container_running("test")
     mock_run.return_value.returncode = 1
-    assert not client.is_container_running("test") 
\ No newline at end of file
+    assert not client.is_container_running("test") 
+
+@patch("subprocess.run")
+def test_ensure_daemon_retries_then_caches(mock_run):
+    failed = type("Result", (), {"returncode": 1, "stdout": "", "stderr": "Cannot connect"})()
+    ok = type("Result", (), {"returncode": 0, "stdout": "27.0.3\n", "stderr": ""})()
+    mock_run.side_effect = [failed, ok]
+    client = ContainerClient()
+    waits = []
+    client.ensure_daemon(sleep=waits.append)
+    assert waits == [0.5]
+    assert mock_run.call_args[0][0] == ["docker", "version", "--format", "{{.Server.Version}}"]
+    # 直前に確かめていればdockerを呼ばない
+    client.ensure_daemon()
+    assert mock_run.call_count == 2
+
+@patch("subprocess.run")
+def test_ensure_daemon_raises_when_unreachable(mock_run):
+    from src.error_hints import CphError
+    mock_run.return_value.returncode = 1
+    client = ContainerClient()
+    waits = []
+    with pytest.raises(CphError) as e:
+        client.ensure_daemon(sleep=waits.append)
+    assert e.value.kind == "docker_unavailable"
+    assert waits == [0.5, 1.0] and mock_run.call_count == 3
+
+def test_shared_client_is_reused():
+    from src.execution_client.container import client as module
+    from src.execution_client.container.pool import ContainerPool
+    previous = module.set_shared_client(None)
+    try:
+        shared = module.shared_client()
+        assert module.shared_client() is shared
+        assert ContainerPool({}).client is shared
+        assert ContainerPool({}, timeout=5).client is not shared
+    finally:
+        module.set_shared_client(previous)
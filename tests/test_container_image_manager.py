@@ -5,6 +5,18 @@ import hashlib
 import pytest
 from unittest.mock import patch
 from src.execution_client.container.image_manager import ContainerImageManager
+from src.shell import ShellResult
+
+class FakeShell:
+    """dockerコマンドの出力を決めておき、実行したコマンドを記録する"""
+    def __init__(self, stdout="", returncode=0):
+        self.stdout = stdout
+        self.returncode = returncode
+        self.calls = []
+
+    def run(self, spec):
+        self.calls.append(spec.argv())
+        return ShellResult(spec.argv(), self.returncode, self.stdout, "")
 
 def create_temp_dockerfile(content="FROM scratch\n"):
     temp_dir = tempfile.mkdtemp()
@@ -27,43 +39,44 @@ def test_get_image_name():
     assert manager.get_image_name("python") == f"cph_image_python_{hashval}"
     shutil.rmtree(temp_dir)
 
-@patch("subprocess.run")
-def test_image_exists(mock_run):
-    mock_run.return_value.stdout = "cph_image_python_123456789abc\n"
-    manager = ContainerImageManager()
+def test_image_exists():
+    manager = ContainerImageManager(shell=FakeShell("cph_image_python_123456789abc\n"))
     assert manager.image_exists("cph_image_python_123456789abc")
     assert not manager.image_exists("not_exist_image")
 
-@patch("subprocess.run")
-def test_build_image(mock_run):
-    mock_run.return_value.returncode = 0
+def test_build_image():
     temp_dir, dockerfile_path = create_temp_dockerfile()
-    manager = ContainerImageManager()
+    shell = FakeShell()
+    manager = ContainerImageManager(shell=shell)
     assert manager.build_image(dockerfile_path, "test_image", temp_dir)
+    assert shell.calls[0][:2] == ["docker", "build"]
+    shutil.rmtree(temp_dir)
+
+def test_build_image_failure(capsys):
+    temp_dir, dockerfile_path = create_temp_dockerfile()
+    manager = ContainerImageManager(shell=FakeShell(returncode=1))
+    assert not manager.build_image(dockerfile_path, "test_image", temp_dir)
+    assert "docker build failed" in capsys.readouterr().out
     shutil.rmtree(temp_dir)
 
-@patch("subprocess.run")
-def test_remove_image(mock_run):
-    mock_run.return_value.returncode = 0
-    manager = ContainerImageManager()
+def test_remove_image():
+    shell = FakeShell()
+    manager = ContainerImageManager(shell=shell)
     assert manager.remove_image("test_image")
+    assert shell.calls == [["docker", "rmi", "test_image"]]
 
-@patch("subprocess.run")
-def test_cleanup_old_images(mock_run):
+def test_cleanup_old_images():
     # 2つのイメージがあり、1つだけ残す
-    mock_run.return_value.stdout = "cph_image_python_aaa\ncph_image_python_bbb\n"
-    manager = ContainerImageManager()
+    manager = ContainerImageManager(shell=FakeShell("cph_image_python_aaa\ncph_image_python_bbb\n"))
     with patch.object(manager, "remove_image") as mock_remove:
         with patch.object(manager, "get_image_name", return_value="cph_image_python_aaa"):
             manager.cleanup_old_images("python")
             mock_remove.assert_called_once_with("cph_image_python_bbb")
 
-@patch("subprocess.run")
-def test_ensure_image_builds_if_not_exists(mock_run):
+def test_ensure_image_builds_if_not_exists():
     # イメージが存在しない場合はbuild_imageとcleanup_old_imagesが呼ばれる
-    mock_run.return_value.stdout = ""
     temp_dir, dockerfile_path = create_temp_dockerfile()
-    manager = ContainerImageManager({"python": dockerfile_path})
+    manager = ContainerImageManager({"python": dockerfile_path}, shell=FakeShell(""))
     with patch.object(manager, "build_image") as mock_build, \
          patch.object(manager, "cleanup_old_images") as mock_cleanup:
         mock_build.return_value = True
@@ -72,15 +85,24 @@ def test_ensure_image_builds_if_not_exists(mock_run):
         assert mock_cleanup.called
     shutil.rmtree(temp_dir)
 
-@patch("subprocess.run")
-def test_ensure_image_skips_if_exists(mock_run):
+def test_ensure_image_skips_if_exists():
     temp_dir, dockerfile_path = create_temp_dockerfile()
-    manager = ContainerImageManager({"python": dockerfile_path})
-    image_name = manager.get_image_name("python")
-    mock_run.return_value.stdout = f"{image_name}\n"
+    shell = FakeShell()
+    manager = ContainerImageManager({"python": dockerfile_path}, shell=shell)
+    shell.stdout = f"{manager.get_image_name('python')}\n"
     with patch.object(manager, "build_image") as mock_build, \
          patch.object(manager, "cleanup_old_images") as mock_cleanup:
         image = manager.ensure_image("python", temp_dir)
         assert not mock_build.called
         assert not mock_cleanup.called
-    shutil.rmtree(temp_dir) 
\ No newline at end of file
+    shutil.rmtree(temp_dir)
+
+def test_docker_commands_are_logged():
+    from src import command_log
+    mark = command_log.get_log().mark()
+    with patch("subprocess.run") as run:
+        run.return_value.returncode = 0
+        run.return_value.stdout = ""
+        run.return_value.stderr = ""
+        ContainerImageManager().remove_image("test_image")
+    assert [e["argv"] for e in command_log.get_log().since(mark)] == [["docker", "rmi", "test_image"]]
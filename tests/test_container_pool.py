@@ -95,4 +95,34 @@ def test_adjust_removes_extra(pool_with_dummy):
     assert set(names) == {"cph_test_python_1", "cph_test_python_2"}
     assert set(names) == set(pool.client.containers)
     # removedには「余計なもの」が含まれていること（cph_test_python_3が含まれていること）
-    assert "cph_test_python_3" in pool.client.removed 
\ No newline at end of file
+    assert "cph_test_python_3" in pool.client.removed 
+def test_adjust_checks_daemon_first(pool_with_dummy):
+    pool = pool_with_dummy
+    calls = []
+    pool.client.ensure_daemon = lambda: calls.append("ensure_daemon")
+    original = pool.client.list_containers
+    pool.client.list_containers = lambda **kwargs: calls.append("list") or original(**kwargs)
+    pool.adjust([{"type": "test", "language": "python", "count": 1}])
+    assert calls[:2] == ["ensure_daemon", "list"]
+
+def test_stop_all_stops_only_own_containers(pool_with_dummy):
+    pool = pool_with_dummy
+    # 別のプロジェクトのcph_コンテナは止めない
+    pool.client.containers = {"cph_other_rust_1"}
+    pool.client.running = {"cph_other_rust_1"}
+    pool.client.list_containers = lambda **kwargs: []
+    pool.adjust([{"type": "test", "language": "python", "count": 2}])
+    pool.client.containers.add("cph_other_rust_1")
+    assert sorted(pool.running_containers()) == ["cph_test_python_1", "cph_test_python_2"]
+    assert sorted(pool.stop_all()) == ["cph_test_python_1", "cph_test_python_2"]
+    assert pool.client.running == {"cph_other_rust_1"}
+
+def test_stop_all_uses_recorded_containers(pool_with_dummy):
+    import json
+    pool = pool_with_dummy
+    path = pool.unified_path_manager.info_json()
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(json.dumps({"containers": [{"name": "cph_test_python_1", "type": "test"}]}), encoding="utf-8")
+    pool.client.running = {"cph_test_python_1", "cph_other_rust_1"}
+    assert pool.stop_all() == ["cph_test_python_1"]
+    assert pool.client.running == {"cph_other_rust_1"}
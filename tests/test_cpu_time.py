@@ -0,0 +1,89 @@
+import subprocess
+import sys
+import threading
+from src import cpu_time
+from src.cpu_time import CgroupCpuClient, is_idle_bound, run_measured, split_cgroup_marker, wrap_cgroup_command
+from src.commands.command_test import CommandTest
+from src.commands.test_result_formatter import ResultFormatter
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+def test_run_measured_separates_cpu_from_sleep():
+    busy = run_measured([sys.executable, "-c", "import sys\nx=0\nfor i in range(3000000): x+=i\nprint(sys.stdin.read().strip())"], input="hi\n")
+    assert busy[:3] == (0, "hi\n", "")
+    assert busy[3] > 0.05
+    idle = run_measured([sys.executable, "-c", "import time; time.sleep(0.3)"], input="")
+    assert idle[0] == 0 and idle[3] < 0.2
+
+def test_run_measured_keeps_returncode_and_ignores_unread_input():
+    code, out, err, _ = run_measured([sys.executable, "-c", "import sys; sys.stderr.write('e'); sys.exit(3)"], input="x" * 1_000_000)
+    assert (code, out, err) == (3, "", "e")
+
+def test_thread_local_record_and_take():
+    cpu_time.record(1.5)
+    seen = []
+    t = threading.Thread(target=lambda: seen.append(cpu_time.take()))
+    t.start()
+    t.join()
+    assert seen == [None]
+    assert cpu_time.take() == 1.5
+    assert cpu_time.take() is None
+
+def test_split_cgroup_marker():
+    assert split_cgroup_marker("warn\n__cph_cpu_usec__ 250000\n") == ("warn\n", 0.25)
+    assert split_cgroup_marker("__cph_cpu_usec__ 1000") == ("", 0.001)
+    assert split_cgroup_marker("plain") == ("plain", None)
+
+def test_cgroup_wrapper_reports_with_sh():
+    # cgroupが読めない環境では計測行を出さず、コマンドの終了コードと出力はそのまま
+    p = subprocess.run(wrap_cgroup_command(["sh", "-c", "cat; exit 4"]), input="in", capture_output=True, text=True)
+    assert p.returncode == 4 and p.stdout == "in"
+    assert split_cgroup_marker(p.stderr)[0] == ""
+
+class FakeContainerClient:
+    def __init__(self):
+        self.commands = []
+
+    def exec_in_container(self, name, cmd, stdin=None):
+        self.commands.append(cmd)
+        return subprocess.CompletedProcess(cmd, 0, "out", "oops\n__cph_cpu_usec__ 120000\n")
+
+    def is_container_running(self, name):
+        return True
+
+def test_cgroup_client_wraps_and_records():
+    inner = FakeContainerClient()
+    client = CgroupCpuClient(inner)
+    result = client.exec_in_container("c", ["./a.out"], stdin="1\n")
+    assert inner.commands[0][:2] == ["sh", "-c"] and inner.commands[0][-1] == "./a.out"
+    assert (result.stdout, result.stderr) == ("out", "oops\n")
+    assert cpu_time.take() == 0.12
+    assert client.is_container_running("c")
+
+def test_idle_bound_and_formatting():
+    assert is_idle_bound(1.0, 0.1)
+    assert not is_idle_bound(1.0, 0.9)
+    assert not is_idle_bound(0.05, 0.0)
+    assert not is_idle_bound(1.0, None)
+    result = {"name": "c", "result": (0, "1", ""), "expected": "1", "time": 1.2, "cpu_time": 0.05}
+    header = ResultFormatter(result, width=80).format().splitlines()[0]
+    assert "1.200秒 (CPU 0.050秒)" in header and "待ち時間が大半" in header
+    assert "CPU" not in ResultFormatter({**result, "cpu_time": None}, width=80).format()
+    assert "待ち" in CommandTest.cpu_note({"time": 1.2, "cpu_time": 0.05})
+    assert "計算が重い" in CommandTest.cpu_note({"time": 1.2, "cpu_time": 1.1})
+
+def test_local_test_records_cpu_time(tmp_path):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "a, b = map(int, input().split())\nprint(a + b)\n")
+        results = sim.execute("test", "abc300", "a", "python")
+    assert results[0]["verdict"] == "AC"
+    assert results[0]["cpu_time"] is not None and results[0]["cpu_time"] <= results[0]["time"] + 0.05
+
+def test_run_measured_usage_reports_memory():
+    small = cpu_time.run_measured_usage([sys.executable, "-c", "pass"], input="")
+    large = cpu_time.run_measured_usage([sys.executable, "-c", "x = bytearray(64 * 1024 * 1024); print(len(x))"], input="")
+    assert small[4] > 0
+    assert large[4] - small[4] > 32 * 1024
@@ -0,0 +1,77 @@
+import subprocess
+import pytest
+from src.command_parser import CommandParser
+from src.environment.test_language_handler import CustomCommandHandler, custom_handlers, get_handler, PythonTestHandler
+from src.path_manager.path_resolver import PathResolver
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+SHELL_LANGUAGE = {"source": "main.sh", "compile": "cp {source} {binary}", "run": "sh {binary}", "aliases": ["sh"]}
+
+class Result:
+    def __init__(self, returncode, stdout="", stderr=""):
+        self.returncode, self.stdout, self.stderr = returncode, stdout, stderr
+
+class LocalManager:
+    """run_and_measureを実際のプロセスで行う（ExecutionManagerの代わり）"""
+    def __init__(self):
+        self.commands = []
+
+    def run_and_measure(self, name, cmd, timeout=None, input=None, cwd=None):
+        self.commands.append(cmd)
+        p = subprocess.run(cmd, input=input, capture_output=True, text=True, cwd=cwd)
+        return Result(p.returncode, p.stdout, p.stderr)
+
+def test_render_quotes_placeholders_and_keeps_shell_braces():
+    handler = CustomCommandHandler("nim", {"compile": "nim c -o:{binary} {source}", "run": "${RUNNER:-} {binary} < {input}"})
+    assert handler.render(handler.compile_template, "/w/.temp/nim dir/main.nim") == "nim c -o:'/w/.temp/nim dir/a.out' '/w/.temp/nim dir/main.nim'"
+    assert handler.run_command("/w/main.nim", "/w/t/1.in") == ["sh", "-c", "${RUNNER:-} /w/a.out < /w/t/1.in"]
+    assert handler.run_command("/w/main.nim")[2].endswith("< /dev/stdin")
+    with pytest.raises(ValueError):
+        CustomCommandHandler("nim", {"compile": "x"})
+
+def test_build_and_run_through_manager(tmp_path):
+    source = tmp_path / "main.sh"
+    source.write_text("read a b\necho $((a + b))\n", encoding="utf-8")
+    in_file = tmp_path / "1.in"
+    in_file.write_text("2 3\n", encoding="utf-8")
+    handler = CustomCommandHandler("shell", SHELL_LANGUAGE)
+    manager = LocalManager()
+    assert handler.build(manager, "w", str(source))[0]
+    assert (tmp_path / "a.out").exists()
+    assert handler.run(manager, "w", str(in_file), str(source)) == (True, "5\n", "")
+    # compileが無ければビルドは何もしない
+    assert CustomCommandHandler("x", {"run": "true"}).build(manager, "w", str(source)) == (True, "", "")
+    assert len(manager.commands) == 2
+
+def write_config(tmp_path, languages):
+    import json
+    (tmp_path / "contest_current" / "config.json").write_text(json.dumps({"languages": languages}), encoding="utf-8")
+
+def test_get_handler_prefers_builtin_and_reads_config(tmp_path):
+    write_config(tmp_path, {"shell": SHELL_LANGUAGE, "python": {"run": "ignored"}})
+    assert isinstance(get_handler("python"), PythonTestHandler)
+    assert get_handler("shell").run_template == "sh {binary}"
+    with pytest.raises(ValueError):
+        get_handler("cobol")
+
+def test_parser_and_layout_know_custom_languages(tmp_path):
+    write_config(tmp_path, {"shell": SHELL_LANGUAGE})
+    parser = CommandParser()
+    parser.parse(["abc300", "test", "a", "sh"])
+    assert parser.parsed["language_name"] == "shell"
+    assert PathResolver.from_config(tmp_path).source_file("shell") == "main.sh"
+
+def test_custom_language_contest_workflow(tmp_path):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n"), ("10 20\n", "30\n")])
+    workspace = setup_test_environment(tmp_path / "ws", templates={"shell": {"main.sh": "read a b\necho $((a + b))\n"}}, config={"languages": {"shell": SHELL_LANGUAGE}})
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "shell")
+        results = sim.execute("test", "abc300", "a", "shell")
+    assert [r["verdict"] for r in results] == ["AC", "AC"]
+
+def test_custom_language_env_and_builtin_profiles_are_not_languages():
+    handler = CustomCommandHandler("ruby", {"run": "ruby {source}", "env": {"RUBYLIB": ""}})
+    assert handler.run_command("/w/main.rb") == ["env", "RUBYLIB=", "sh", "-c", "ruby /w/main.rb"]
+    config = type("C", (), {"get_languages": lambda self: {"python": {"isolate": False}, "ruby": {"run": "ruby {source}"}}})()
+    assert list(custom_handlers(config)) == ["ruby"]
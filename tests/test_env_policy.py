@@ -0,0 +1,60 @@
+import subprocess
+import pytest
+from src.command_wrap import CommandWrapClient
+from src.env_policy import EnvPolicy
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+SHOW_ENV = ["sh", "-c", 'echo "${SECRET-unset}|${LANG-unset}|${PATH:+path}"']
+
+def run(cmd):
+    return subprocess.run(cmd, capture_output=True, text=True).stdout
+
+def test_default_policy_keeps_path_and_fixes_locale(monkeypatch):
+    monkeypatch.setenv("SECRET", "token")
+    monkeypatch.setenv("LANG", "ja_JP.UTF-8")
+    assert run(EnvPolicy().wrap(SHOW_ENV)) == "unset|C.UTF-8|path\n"
+    assert run(EnvPolicy(enabled=False).wrap(SHOW_ENV)) == "token|ja_JP.UTF-8|path\n"
+
+def test_allow_all_with_deny_and_unset_allowed_names(monkeypatch):
+    monkeypatch.setenv("SECRET", "token")
+    monkeypatch.setenv("KEEP", "1")
+    monkeypatch.delenv("MISSING", raising=False)
+    policy = EnvPolicy.from_config({"env": {"allow": ["*"], "deny": ["SECRET"], "set": {}}})
+    assert policy.wrap(["true"]) == ["env", "-u", "SECRET", "true"]
+    assert run(policy.wrap(["sh", "-c", 'echo "${SECRET-unset} $KEEP"'])) == "unset 1\n"
+    # 許可しても未設定の変数は空文字列として渡さない
+    policy = EnvPolicy.from_config({"env": {"allow": ["PATH", "MISSING"], "set": {"X": "a b"}}})
+    assert run(policy.wrap(["sh", "-c", 'echo "${MISSING-unset}|$X"'])) == "unset|a b\n"
+
+def test_invalid_config_is_rejected():
+    with pytest.raises(ValueError):
+        EnvPolicy.from_config({"env": {"allow": ["PATH; rm -rf /"]}})
+    with pytest.raises(ValueError):
+        EnvPolicy.from_config({"env": ["PATH"]})
+    assert not EnvPolicy.from_config({"env": False}).enabled
+
+class LocalManager:
+    def __init__(self):
+        self.commands = []
+
+    def run_and_measure(self, name, cmd, timeout=None, input=None):
+        self.commands.append(cmd)
+
+def test_client_wraps_only_existing_run_methods():
+    manager = LocalManager()
+    client = CommandWrapClient(manager, EnvPolicy())
+    # ローカルのマネージャーがコンテナ扱いにならない
+    assert not hasattr(client, "exec_in_container")
+    client.run_and_measure("name", ["python3", "main.py"], input="")
+    assert manager.commands[0][:2] == ["sh", "-c"] and manager.commands[0][-2:] == ["python3", "main.py"]
+
+def test_local_test_run_does_not_see_host_environment(tmp_path, monkeypatch):
+    monkeypatch.setenv("CPH_TEST_SECRET", "leaked")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("\n", "None\n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "import os\nprint(os.environ.get('CPH_TEST_SECRET'))\n")
+        results = sim.execute("test", "abc300", "a", "python")
+    assert [r["verdict"] for r in results] == ["AC"]
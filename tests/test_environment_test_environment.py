@@ -1,7 +1,9 @@
 import os
 import pytest
 from unittest.mock import MagicMock, patch
+from src import cancellation
 from src.environment.test_environment import TestEnvFileOpsMixin, DockerTestExecutionEnvironment
+from src.shell import ShellResult
 
 class DummyFileOperator:
     def __init__(self):
@@ -34,6 +36,8 @@ class DummyUPM:
         return 'src_dir'
     def contest_stocks(self, *a):
         return 'stocks_dir'
+    def source_file(self, language_name):
+        return 'main.py'
 
 class DummyFileManager:
     def __init__(self):
@@ -113,6 +117,21 @@ def test_download_testcases_raises():
         with pytest.raises(RuntimeError):
             env.download_testcases('url', 'dir')
 
+def test_download_testcases_runs_oj_with_timeout(tmp_path):
+    env = DockerTestExecutionEnvironment(DummyFileManager())
+    env.upm = MagicMock()
+    env.ctl = MagicMock()
+    env.ctl.is_container_running.return_value = True
+    env.ctl.docker.return_value = ShellResult([], 0, "", "")
+    env.to_container_path = lambda path: "/workspace/test"
+    with patch('src.info_json_manager.InfoJsonManager') as mock_info:
+        mock_info.return_value.get_containers.return_value = [{"name": "cph_ojtools_1"}]
+        with cancellation.operation(timeout=5):
+            env.download_testcases('url', str(tmp_path / "test"))
+    argv, kwargs = env.ctl.docker.call_args
+    assert argv[0] == ["docker", "exec", "-i", "cph_ojtools_1", "oj", "download", "url", "-d", "/workspace/test"]
+    assert 0 < kwargs["timeout"] <= 5
+
 def test_submit_via_ojtools_raises():
     env = DockerTestExecutionEnvironment(DummyFileManager())
     env.upm = MagicMock()
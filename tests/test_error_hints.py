@@ -0,0 +1,34 @@
+from src.error_hints import CphError, HintRegistry, classify, current_locale
+
+def test_classify_by_kind_and_exception_type():
+    assert classify(CphError("x", kind="build_failed")) == "build_failed"
+    assert classify(FileNotFoundError(2, "No such file", "docker")) == "docker_not_found"
+    assert classify(FileNotFoundError(2, "No such file", "/usr/bin/oj")) == "oj_not_found"
+    assert classify(FileNotFoundError(2, "No such file", "main.py")) == "file_not_found"
+    assert classify(PermissionError("denied")) == "permission_denied"
+    assert classify(ValueError("x")) == "unknown"
+
+def test_format_uses_locale_and_doc():
+    lines = HintRegistry(locale="en").format(FileNotFoundError(2, "No such file", "docker"))
+    assert lines[0].startswith("[ヒント] docker command not found")
+    assert lines[1] == "[参考] https://docs.docker.com/get-docker/"
+    assert HintRegistry(locale="ja").format(ValueError("x")) == []
+
+def test_config_hints_override_and_add_kinds():
+    registry = HintRegistry({
+        "build_failed": {"ja": "社内手順を参照", "doc": "https://example.com/build"},
+        "custom": "独自のヒント",
+    }, locale="ja")
+    assert registry.format(CphError("x", kind="build_failed")) == ["[ヒント] 社内手順を参照", "[参考] https://example.com/build"]
+    assert registry.format(CphError("x", kind="custom")) == ["[ヒント] 独自のヒント"]
+
+def test_current_locale(monkeypatch):
+    monkeypatch.setenv("CPH_LANG", "en_US.UTF-8")
+    assert current_locale() == "en"
+    monkeypatch.setenv("CPH_LANG", "ja_JP.UTF-8")
+    assert current_locale() == "ja"
+    monkeypatch.setenv("CPH_LANG", "C.UTF-8")
+    assert current_locale() == "en"
+    # 先頭がcでも、C以外の言語は英語にしない
+    monkeypatch.setenv("CPH_LANG", "ca_ES.UTF-8")
+    assert current_locale() == "ja"
@@ -0,0 +1,56 @@
+import pytest
+from pathlib import Path
+from src import event_subscribers, events
+from src.events import ALL, PROBLEM_OPENED, TEST_RUN_COMPLETED, EventBus
+from src.usage_stats import UsageStats
+
+def test_publish_calls_subscribers_in_order():
+    bus = EventBus()
+    received = []
+    bus.subscribe(PROBLEM_OPENED, lambda e: received.append(("opened", e.contest, e.problem)))
+    bus.subscribe(ALL, lambda e: received.append(("all", e.name)))
+    event = bus.publish(PROBLEM_OPENED, contest="abc300", problem="a")
+    bus.publish(TEST_RUN_COMPLETED, contest="abc300", problem="a", results=[])
+    assert received == [("opened", "abc300", "a"), ("all", PROBLEM_OPENED), ("all", TEST_RUN_COMPLETED)]
+    assert event.fields == {"contest": "abc300", "problem": "a"}
+    with pytest.raises(AttributeError):
+        event.language
+
+def test_unsubscribe_and_unknown_event():
+    bus = EventBus()
+    received = []
+    unsubscribe = bus.subscribe(PROBLEM_OPENED, received.append)
+    unsubscribe()
+    bus.publish(PROBLEM_OPENED, contest="abc300", problem="a")
+    assert received == []
+    with pytest.raises(ValueError):
+        bus.subscribe("problem_closed", received.append)
+
+def test_failing_subscriber_does_not_stop_others(capsys):
+    bus = EventBus()
+    received = []
+    bus.subscribe(PROBLEM_OPENED, lambda e: 1 / 0)
+    bus.subscribe(PROBLEM_OPENED, received.append)
+    bus.publish(PROBLEM_OPENED, contest="abc300", problem="a")
+    assert len(received) == 1
+    assert "イベント problem_opened の処理に失敗しました" in capsys.readouterr().out
+
+def test_installed_subscribers_record_stats_and_run_hooks(tmp_path, monkeypatch):
+    bus = EventBus()
+    stats = UsageStats(path=tmp_path / "stats.json", enabled=True)
+    out = tmp_path / "hook.txt"
+    hooks = {TEST_RUN_COMPLETED: f"sh -c 'echo {{contest}} {{problem}} {{passed}}/{{total}} > {out}'"}
+    monkeypatch.setattr(event_subscribers, "_installed", [])
+    assert len(event_subscribers.install(bus, stats=stats, get_hooks=lambda: hooks, get_notify=lambda: {})) == 3
+    # 同じバスには二重に登録しない
+    assert event_subscribers.install(bus, stats=stats, get_hooks=lambda: hooks) == []
+    bus.publish(TEST_RUN_COMPLETED, contest="abc300", problem="a", passed=1, total=2,
+                results=[{"time": 0.5}, {"time": 0.25}])
+    assert stats.load()["tests"] == {"runs": 1, "cases": 2, "seconds": 0.75}
+    assert out.read_text(encoding="utf-8").strip() == "abc300 a 1/2"
+    # フックが無いイベントは何もしない
+    bus.publish(PROBLEM_OPENED, contest="abc300", problem="b")
+
+def test_hook_values_skip_structured_fields():
+    event = events.Event(TEST_RUN_COMPLETED, contest="abc300", results=[{"time": 0.1}], all_ac=True, dir=Path("/w"))
+    assert event_subscribers.hook_values(event) == {"contest": "abc300", "all_ac": True, "dir": Path("/w")}
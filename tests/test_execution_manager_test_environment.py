@@ -2,7 +2,10 @@ import os
 import tempfile
 import shutil
 import pytest
+from src import cancellation
 from src.environment.execution_manager_test_environment import ExecutionManagerTestEnvironment
+from src.error_hints import CphError
+from src.shell import ShellResult
 from unittest.mock import patch, MagicMock
 
 class DummyManager:
@@ -91,6 +94,25 @@ def test_download_testcases_fail(mock_run, temp_dir):
             env.download_testcases('http://example.com', temp_dir)
     mock_run.assert_called_once()
 
+# download_testcases: 操作の残り時間を超えて待たない
+def test_download_testcases_timeout(temp_dir):
+    ran = []
+    class SlowShell:
+        def run(self, spec):
+            ran.append(spec)
+            return ShellResult(spec.argv(), -9, "", "", timed_out=True)
+    env = ExecutionManagerTestEnvironment(file_manager=None, manager=None, shell=SlowShell())
+    with cancellation.operation(timeout=5):
+        with pytest.raises(CphError, match="秒以内に終わりませんでした") as e:
+            env.download_testcases('http://example.com', temp_dir)
+    assert e.value.kind == "oj_download_failed"
+    assert 0 < ran[0].timeout <= 5
+    with cancellation.operation(timeout=5) as token:
+        token.cancel()
+        with pytest.raises(CphError, match="中断"):
+            env.download_testcases('http://example.com', temp_dir)
+    assert len(ran) == 1
+
 # submit_via_ojtools: workdirが/workspaceで始まる場合・正常系
 @patch('subprocess.run')
 def test_submit_via_ojtools_workspace_success(mock_run, temp_dir):
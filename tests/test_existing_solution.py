@@ -0,0 +1,73 @@
+import asyncio
+from src.commands.existing_solution import detect_language, replaced_files, solution_languages
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+RUST = "fn main() {\n    println!(\"1\");\n}\n"
+OLD = "print('old solution')\n"
+NEW = "print('new code')\n"
+
+def write(path, text):
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(text, encoding="utf-8")
+
+def test_detect_language_from_extension(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    upm = UnifiedPathManager(str(workspace.root))
+    write(workspace.path("contest_stocks", "abc300", "a", "rust", "src", "main.rs"), RUST)
+    # 解答ファイルの拡張子が合わないもの・言語でないディレクトリは数えない
+    write(workspace.path("contest_stocks", "abc300", "a", "python", "notes.txt"), "memo\n")
+    write(workspace.path("contest_stocks", "abc300", "a", "notes", "draft.py"), OLD)
+    assert solution_languages(upm, "abc300", "a") == ["rust"]
+    assert detect_language(upm, "abc300", "a", "python") == ("rust", ["rust"])
+    # 既定の言語にも解答があれば既定のまま
+    write(workspace.path("contest_stocks", "abc300", "a", "python", "main.py"), OLD)
+    assert detect_language(upm, "abc300", "a", "python")[0] == "python"
+    assert detect_language(upm, "abc300", "b", "python") == ("python", [])
+
+def test_open_without_language_uses_stocked_solution(tmp_path, capsys):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1\n", "1\n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    write(workspace.path("contest_stocks", "abc300", "a", "rust", "src", "main.rs"), RUST)
+    with ContestSimulation(workspace, site) as sim:
+        asyncio.run(sim.executor().open("abc300", "a", None))
+    assert "abc300 a には rust の解答があるので、言語を rust にします" in capsys.readouterr().out
+    assert workspace.read_json("contest_current", "system_info.json")["language_name"] == "rust"
+    assert workspace.path("contest_current", "rust", "src", "main.rs").read_text(encoding="utf-8") == RUST
+    assert not workspace.path("contest_current", "python").exists()
+
+def test_open_asks_before_replacing_code_of_unknown_problem(tmp_path):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1\n", "1\n")])
+    site.add_problem("abc300", "b", [("2\n", "2\n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    stocked = workspace.path("contest_stocks", "abc300", "b", "python", "main.py")
+    current = workspace.path("contest_current", "python", "main.py")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", NEW)
+        write(stocked, OLD)
+        # 作業中の問題が分かっていれば、そのcontest_stocksに残すので確認しない
+        executor = sim.executor()
+        executor.open_handler.ask = lambda _: (_ for _ in ()).throw(AssertionError("確認しないはず"))
+        asyncio.run(executor.open("abc300", "b", "python"))
+        assert current.read_text(encoding="utf-8") == OLD
+        assert workspace.path("contest_stocks", "abc300", "a", "python", "main.py").read_text(encoding="utf-8") == NEW
+        # どの問題のコードか分からなければ、置き換える前に確認する
+        workspace.path("contest_current", "system_info.json").write_text("{}", encoding="utf-8")
+        current.write_text(NEW, encoding="utf-8")
+        upm = UnifiedPathManager(str(workspace.root))
+        assert replaced_files(upm, "abc300", "b", "python") == [current]
+        executor.open_handler.ask = lambda _: "n"
+        assert asyncio.run(executor.open("abc300", "b", "python")) is None
+        assert current.read_text(encoding="utf-8") == NEW
+        def closed_stdin(_):
+            raise EOFError
+        executor.open_handler.ask = closed_stdin
+        assert asyncio.run(executor.open("abc300", "b", "python")) is None
+        assert current.read_text(encoding="utf-8") == NEW
+        assert stocked.read_text(encoding="utf-8") == OLD
+        sim.execute("open", "abc300", "b", "python", options={"yes": True})
+        assert current.read_text(encoding="utf-8") == OLD
+        assert workspace.read_json("contest_current", "system_info.json")["problem_name"] == "b"
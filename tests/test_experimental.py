@@ -0,0 +1,66 @@
+import asyncio
+import json
+import sys
+import pytest
+from src import experimental, main
+from src.command_executor import CommandExecutor
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+@pytest.fixture(autouse=True)
+def reset_features(monkeypatch):
+    # 有効にした機能はプロセスで1つなので、テストごとに読み直させる
+    monkeypatch.setattr(experimental, "_enabled", None)
+    monkeypatch.setattr(experimental, "_warned", set())
+
+def test_unknown_features_are_ignored_with_warning(capsys):
+    assert experimental.configure(["daemon", "wasm-runner"]) == {"daemon"}
+    assert "未知の実験的機能を無視します: wasm-runner" in capsys.readouterr().out
+    assert experimental.configure(None) == set()
+
+@pytest.mark.parametrize("value", ["daemon", {"daemon": True}, ["daemon", 1]])
+def test_experimental_must_be_a_list_of_names(value):
+    with pytest.raises(ValueError):
+        experimental.configure(value)
+
+def test_require_stops_unless_enabled_and_warns_once(capsys):
+    experimental.configure([])
+    with pytest.raises(CphError) as e:
+        experimental.require("daemon", "test --watch")
+    assert e.value.kind == "experimental_disabled" and "experimental" in str(e.value)
+    experimental.configure(["daemon"])
+    experimental.require("daemon", "test --watch")
+    experimental.require("daemon", "test --watch")
+    assert capsys.readouterr().out.count("[実験的] test --watch（daemon）は実験的な機能です") == 1
+
+def test_features_are_read_from_config_when_not_configured():
+    config = UnifiedPathManager().config_json()
+    data = json.loads(config.read_text()) if config.exists() else {}
+    config.write_text(json.dumps({**data, "experimental": ["daemon"]}))
+    assert experimental.is_enabled("daemon")
+
+def test_watch_is_gated_behind_daemon_feature():
+    watched = []
+
+    class FakeWatch:
+        async def watch(self, contest_name, problem_name, language_name):
+            watched.append(contest_name)
+
+    executor = CommandExecutor(options={"watch": True})
+    executor.watch_handler = FakeWatch()
+    experimental.configure([])
+    with pytest.raises(CphError):
+        asyncio.run(executor.run_test("abc300", "a", "python"))
+    experimental.configure(["daemon"])
+    asyncio.run(executor.run_test("abc300", "a", "python"))
+    assert watched == ["abc300"]
+
+def test_invalid_experimental_config_exits_with_error(monkeypatch, capsys):
+    config = UnifiedPathManager().config_json()
+    data = json.loads(config.read_text()) if config.exists() else {}
+    config.write_text(json.dumps({**data, "experimental": "daemon"}))
+    monkeypatch.setattr(sys, "argv", ["main.py", "abc300", "test", "a", "python"])
+    with pytest.raises(SystemExit) as e:
+        main.main()
+    assert e.value.code == 1
+    assert "エラー: experimentalは機能名のリストで指定してください" in capsys.readouterr().out
@@ -0,0 +1,52 @@
+import os
+import threading
+import time
+import pytest
+from src.error_hints import CphError
+from src import file_lock as file_lock_module
+from src.file_lock import file_lock, lock_path_for
+from src.commands.result_cache import ResultCache
+
+def test_lock_times_out_with_holder_diagnostics(tmp_path, monkeypatch):
+    monkeypatch.setattr(file_lock_module, "LOCK_POLL_INTERVAL", 0.01)
+    target = tmp_path / "results.json"
+    with file_lock(target):
+        assert f"pid={os.getpid()}" in lock_path_for(target).read_text()
+        errors = []
+        def contend():
+            try:
+                with file_lock(target, timeout=0.05):
+                    pass
+            except CphError as e:
+                errors.append(e)
+        t = threading.Thread(target=contend)
+        t.start()
+        t.join()
+    assert errors and errors[0].kind == "lock_timeout"
+    assert "pid=" in str(errors[0])
+
+def test_lock_waits_for_release(tmp_path):
+    target = tmp_path / "results.json"
+    order = []
+    def holder(ready):
+        with file_lock(target):
+            ready.set()
+            time.sleep(0.1)
+            order.append("holder")
+    ready = threading.Event()
+    t = threading.Thread(target=holder, args=(ready,))
+    t.start()
+    ready.wait()
+    with file_lock(target, timeout=5):
+        order.append("waiter")
+    t.join()
+    assert order == ["holder", "waiter"]
+
+def test_result_cache_concurrent_puts_keep_all_keys(tmp_path):
+    cache = ResultCache(tmp_path / "results.json")
+    threads = [threading.Thread(target=cache.put, args=(f"abc/{i}/python", "h", [])) for i in range(8)]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+    assert sorted(cache.load()) == sorted(f"abc/{i}/python" for i in range(8))
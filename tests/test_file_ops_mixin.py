@@ -27,6 +27,8 @@ class DummyEnv(TestEnvFileOpsMixin):
     def contest_current(self, *args):
         # e.g. ('python', 'main.py') -> 'src/python/main.py'
         return os.path.join(*args)
+    def source_file(self, language_name):
+        return "main.py"
 
 @pytest.fixture
 def temp_dir():
@@ -70,4 +72,18 @@ def test_prepare_test_cases(temp_dir):
         f.write('1 2 3')
     dst = env.prepare_test_cases('contest', 'problem')
     assert os.path.exists(os.path.join(temp_dir, dst))
-    assert os.path.exists(os.path.join(temp_dir, '.temp/test/sample.in')) 
\ No newline at end of file
+    assert os.path.exists(os.path.join(temp_dir, '.temp/test/sample.in')) 
+def test_aprepare_test_cases_copies_groups(temp_dir, monkeypatch):
+    import asyncio
+    from pathlib import Path
+    env = DummyEnv(temp_dir)
+    env.file_operator = None
+    env.contest_current = lambda *args: Path(temp_dir, *args)
+    os.makedirs(os.path.join(temp_dir, 'test', 'group_1'))
+    for name in ('sample-1.in', 'group_1/sample-1-1.in'):
+        with open(os.path.join(temp_dir, 'test', name), 'w') as f:
+            f.write('1\n')
+    monkeypatch.chdir(temp_dir)
+    dst = asyncio.run(env.aprepare_test_cases('contest', 'problem'))
+    assert os.path.exists(os.path.join(temp_dir, dst, 'sample-1.in'))
+    assert os.path.exists(os.path.join(temp_dir, '.temp/test/group_1/sample-1-1.in'))
@@ -0,0 +1,53 @@
+from fractions import Fraction
+import pytest
+from src.commands.formula_cases import evaluate, expand_formula_cases, format_value
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+CASES_YAML = """variables: [n, m]
+cases:
+  - name: tri-10
+    input: "10 3"
+    expected: "{n}*({n}+1)/2"
+  - name: multi
+    input: "4 6"
+    expected: ["gcd({n}, {m})", "{n}/{m}"]
+  - name: sample-1
+    expected: "{n}+{m}"
+"""
+
+def test_evaluate_uses_exact_fractions():
+    n = {"n": Fraction(10)}
+    assert evaluate("{n}*({n}+1)/2", n) == 55
+    assert evaluate("comb({n}, 2) + {n} % 3 - 2**3", n) == 45 + 1 - 8
+    assert format_value(evaluate("1/3", {})) == "0.3333333333"
+    assert format_value(evaluate("0.1+0.2", {})) == "0.3"
+    assert format_value(evaluate("{n}/2", n)) == "5"
+
+@pytest.mark.parametrize("expression", ["__import__('os')", "{x}+1", "open('f')", "1/0", "2**0.5", "{n}.real"])
+def test_evaluate_rejects(expression):
+    with pytest.raises(ValueError):
+        evaluate(expression, {"n": Fraction(1)})
+
+def test_expand_writes_cases(tmp_path):
+    (tmp_path / "sample-1.in").write_text("1 2\n", encoding="utf-8")
+    (tmp_path / "cases.yaml").write_text(CASES_YAML, encoding="utf-8")
+    written = expand_formula_cases(tmp_path)
+    assert [p.rsplit("/", 1)[-1] for p in written] == ["tri-10.in", "multi.in", "sample-1.in"]
+    assert (tmp_path / "tri-10.in").read_text(encoding="utf-8") == "10 3\n"
+    assert (tmp_path / "tri-10.out").read_text(encoding="utf-8") == "55\n"
+    assert (tmp_path / "multi.out").read_text(encoding="utf-8") == "2\n0.6666666667\n"
+    assert (tmp_path / "sample-1.out").read_text(encoding="utf-8") == "3\n"
+    assert expand_formula_cases(tmp_path / "missing") == []
+
+def test_test_command_runs_formula_cases(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "n, m = map(int, input().split())\nprint(n * (n + 1) // 2)\n")
+        workspace.path("contest_current", "test", "cases.yaml").write_text(CASES_YAML.split("  - name: multi")[0], encoding="utf-8")
+        results = sim.execute("test", "abc300", "a", "python")
+    assert {r["name"]: r["verdict"] for r in results} == {"sample-1.in": "WA", "tri-10.in": "AC"}
+    # 作業中のテストディレクトリには.in/.outを作らない
+    assert not workspace.path("contest_current", "test", "tri-10.in").exists()
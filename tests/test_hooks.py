@@ -0,0 +1,35 @@
+import sys
+from src.commands.hooks import HookRunner
+from src.commands.command_test import CommandTest
+from src.commands.opener import MockOpener
+from src.commands.run_artifacts import RunArtifacts
+
+def test_build_command_keeps_paths_with_spaces():
+    hooks = HookRunner({"visualize": "python3 vis.py {input} {output}"})
+    cmd = hooks.build_command("visualize", input="/tmp/a b.in", output="/tmp/out")
+    assert cmd == ["python3", "vis.py", "/tmp/a b.in", "/tmp/out"]
+
+def test_missing_hook_returns_none():
+    hooks = HookRunner({})
+    assert hooks.has("visualize") is False
+    assert hooks.run("visualize") is None
+
+def test_visualize_hook_runs_only_for_failed_cases(tmp_path):
+    script = tmp_path / "vis.py"
+    script.write_text("import sys\nopen(sys.argv[2], 'w').write(open(sys.argv[1]).read())\n", encoding="utf-8")
+    in_file = tmp_path / "sample-1.in"
+    in_file.write_text("1 2\n", encoding="utf-8")
+    cmd = CommandTest(None, None, opener=MockOpener())
+    cmd.get_config = lambda: type("C", (), {"get_hooks": lambda self: {
+        "visualize": {"command": f"{sys.executable} {script} {{input}} {{artifact}}", "artifact": "{case}.html"}
+    }})()
+    artifacts = RunArtifacts(run_id="run1", project_root=tmp_path)
+    results = [
+        {"name": "sample-1.in", "result": (0, "3\n", ""), "expected": "4\n", "time": 0.0, "in_file": str(in_file), "verdict": "WA"},
+        {"name": "sample-2.in", "result": (0, "4\n", ""), "expected": "4\n", "time": 0.0, "in_file": str(in_file), "verdict": "AC"},
+    ]
+    artifacts.save_results(results)
+    produced = cmd.run_visualize_hooks(results, artifacts)
+    assert [p.name for p in produced] == ["sample-1.html"]
+    assert produced[0].read_text(encoding="utf-8") == "1 2\n"
+    assert cmd.opener.opened_urls == [produced[0].resolve().as_uri()]
@@ -0,0 +1,46 @@
+import asyncio
+import json
+import os
+import pytest
+from src import http_client
+from src.error_hints import CphError
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_import import CommandImport
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.test_support.http_cassette import Cassette, StaticTransport, use_cassette
+
+CASSETTES = os.path.join(os.path.dirname(__file__), "cassettes")
+
+def test_record_then_replay_without_network(tmp_path):
+    path = tmp_path / "cassette.json"
+    site = StaticTransport({"https://example.com/api?b=2&a=1": {"ok": True}, "https://example.com/missing": (404, "no")})
+    with use_cassette(path, mode="record", inner=site):
+        assert http_client.get_json("https://example.com/api", {"a": 1, "b": 2}) == {"ok": True}
+        with pytest.raises(CphError):
+            http_client.get("https://example.com/missing")
+    assert len(json.loads(path.read_text(encoding="utf-8"))["interactions"]) == 2
+    with use_cassette(path) as cassette:
+        assert cassette.mode == "replay"
+        assert http_client.get_json("https://example.com/api", {"b": 2, "a": 1}) == {"ok": True}
+    assert len(site.requests) == 2
+
+def test_replay_miss_raises_with_hint_kind(tmp_path):
+    path = tmp_path / "cassette.json"
+    path.write_text(json.dumps({"interactions": []}), encoding="utf-8")
+    with use_cassette(path):
+        with pytest.raises(CphError) as e:
+            http_client.get("https://example.com/other")
+    assert e.value.kind == "cassette_miss"
+
+def test_record_env_forces_rerecording(tmp_path, monkeypatch):
+    path = tmp_path / "cassette.json"
+    path.write_text(json.dumps({"interactions": []}), encoding="utf-8")
+    monkeypatch.setenv("CPH_RECORD_CASSETTES", "1")
+    assert Cassette(path, inner=StaticTransport()).mode == "record"
+
+def test_import_history_against_recorded_atcoder_problems(tmp_path):
+    index = ArchiveIndex(upm=UnifiedPathManager(project_root=tmp_path))
+    with use_cassette(os.path.join(CASSETTES, "atcoder_import_history.json"), mode="replay"):
+        asyncio.run(CommandImport({"site": "atcoder", "user": "cph_sample"}, index=index).import_history())
+    assert index.get("abc300", "b")["status"] == "AC"
+    assert index.get("abc300", "c")["status"] == "TLE" and index.get("abc300", "c")["difficulty"] == 895
@@ -0,0 +1,96 @@
+import asyncio
+import time
+import pytest
+from src.commands.command_clean import CommandClean
+from src.execution_client.container.image_manager import ContainerImageManager, parse_created
+from src.execution_client.container.image_usage import ImageUsage, MANAGED_LABEL
+from src.shell import ShellResult
+
+DAY = 24 * 60 * 60
+NOW = time.time()
+
+class FakeDocker:
+    """docker images / rmi / pull を模擬するShell"""
+    def __init__(self, images):
+        self.images = dict(images)  # name -> (created, labeled)
+        self.calls = []
+
+    def run(self, spec):
+        cmd = spec.argv()
+        self.calls.append(cmd)
+        if cmd[:2] == ["docker", "images"]:
+            labeled_only = any(a.startswith("label=") for a in cmd)
+            lines = [f"{n}\t{time.strftime('%Y-%m-%d %H:%M:%S +0000 UTC', time.gmtime(c))}"
+                     for n, (c, labeled) in self.images.items() if labeled or not labeled_only]
+            return ShellResult(cmd, 0, "\n".join(lines) + "\n", "")
+        if cmd[:2] == ["docker", "rmi"]:
+            self.images.pop(cmd[2], None)
+            return ShellResult(cmd, 0, "", "")
+        if cmd[:2] == ["docker", "pull"]:
+            self.images[cmd[2]] = (NOW, False)
+            return ShellResult(cmd, 0, "", "")
+        return ShellResult(cmd, 0, "", "")
+
+@pytest.fixture
+def docker():
+    fake = FakeDocker({
+        "cph_image_python_old:latest": (NOW - 40 * DAY, True),
+        "cph_image_rust_new:latest": (NOW - 40 * DAY, True),
+        "rust:1.70.0": (NOW - 90 * DAY, False),
+        "ubuntu:22.04": (NOW - 90 * DAY, False),
+    })
+    return fake
+
+def test_parse_created():
+    assert parse_created("2024-01-01 00:00:00 +0000 UTC") == 1704067200
+    assert parse_created("unknown") is None
+
+def test_unused_images_uses_last_use_and_labels(docker, tmp_path):
+    usage = ImageUsage(tmp_path / "images.json")
+    usage.touch("cph_image_rust_new", now=NOW - DAY)
+    usage.touch("rust:1.70.0", source="pull", now=NOW - 45 * DAY)
+    manager = ContainerImageManager(usage=usage, shell=docker)
+    assert [name for name, _ in manager.unused_images(30, now=NOW)] == ["rust:1.70.0", "cph_image_python_old:latest"]
+    # 記録の無いイメージ（ubuntu）はcphの管理外なので消さない
+    assert manager.clean_unused_images(30, now=NOW) == ["rust:1.70.0", "cph_image_python_old:latest"]
+    assert "ubuntu:22.04" in docker.images and "cph_image_rust_new:latest" in docker.images
+    assert usage.last_used("rust:1.70.0") is None
+
+def test_build_adds_label_and_records_use(docker, tmp_path):
+    usage = ImageUsage(tmp_path / "images.json")
+    manager = ContainerImageManager(usage=usage, shell=docker)
+    dockerfile = tmp_path / "Dockerfile"
+    dockerfile.write_text("FROM scratch\n")
+    assert manager.build_image(str(dockerfile), "cph_image_x_abc", str(tmp_path))
+    assert f"{MANAGED_LABEL}=true" in docker.calls[-1]
+    assert usage.images()["cph_image_x_abc:latest"]["source"] == "build"
+
+def test_auto_clean_policy(docker, tmp_path):
+    (tmp_path / "contest_current").mkdir(exist_ok=True)
+    (tmp_path / "contest_current" / "config.json").write_text('{"images": {"auto_clean_days": 30}}', encoding="utf-8")
+    usage = ImageUsage(tmp_path / "images.json")
+    manager = ContainerImageManager(usage=usage, shell=docker)
+    assert manager.pull_image("rust:1.75.0")
+    assert "cph_image_python_old:latest" not in docker.images
+    # 1日に1回まで
+    docker.images["cph_image_python_old:latest"] = (NOW - 40 * DAY, True)
+    assert manager.apply_cleanup_policy() == []
+
+def test_clean_command(docker, tmp_path, capsys):
+    manager = ContainerImageManager(usage=ImageUsage(tmp_path / "images.json"), shell=docker)
+    with pytest.raises(ValueError):
+        asyncio.run(CommandClean(image_manager=manager).clean())
+    assert asyncio.run(CommandClean({"images": True, "dry-run": True}, image_manager=manager).clean()) == []
+    assert "cph_image_python_old:latest" in docker.images
+    removed = asyncio.run(CommandClean({"images": True, "days": "30"}, image_manager=manager).clean())
+    assert removed == ["cph_image_python_old:latest", "cph_image_rust_new:latest"]
+    assert "[clean] 削除: cph_image_python_old:latest" in capsys.readouterr().out
+
+def test_auto_clean_keeps_going_without_docker(tmp_path):
+    class NoDocker:
+        def run(self, spec):
+            raise FileNotFoundError("docker")
+    (tmp_path / "contest_current").mkdir(exist_ok=True)
+    (tmp_path / "contest_current" / "config.json").write_text('{"images": {"auto_clean_days": 30}}', encoding="utf-8")
+    manager = ContainerImageManager(usage=ImageUsage(tmp_path / "images.json"), shell=NoDocker())
+    assert manager.apply_cleanup_policy() == []
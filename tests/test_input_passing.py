@@ -0,0 +1,63 @@
+import json
+import subprocess
+import pytest
+from src.commands.command_test import CommandTest
+from src.input_passing import InputPassing, parse_mode, shares_input_file
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+READ_FILE = "print(sum(map(int, open('input.txt').read().split())))\n"
+READ_ARG = "import sys\nprint(sum(map(int, open(sys.argv[1]).read().split())))\n"
+
+def test_parse_mode():
+    assert parse_mode(None) == ("stdin", None)
+    assert parse_mode("arg") == ("arg", None)
+    assert parse_mode("file:in.txt") == ("file:", "in.txt")
+    for value in ("file:", "file:../x", "pipe"):
+        with pytest.raises(ValueError):
+            parse_mode(value)
+
+def test_for_case_work_dirs(tmp_path):
+    assert InputPassing.for_case({}, "/w/test/sample-1.in", tmp_path).wrap(["a"]) == ["a"]
+    passing = InputPassing.for_case({"pass_input_as": "arg"}, "/w/test/large-1.in.zst", tmp_path, lambda p: "/cont" + p)
+    assert passing.work_dir == f"/cont{tmp_path}/.temp/work/large-1" and passing.file_name == "large-1.in"
+    passing = InputPassing.for_case({"cwd": "data"}, "/w/test/sample-1.in", tmp_path)
+    assert passing.mode == "stdin" and passing.work_dir == str(tmp_path / "data")
+
+def test_wrap_writes_stdin_to_named_file(tmp_path):
+    passing = InputPassing("file:", str(tmp_path / "work"), "input.txt")
+    result = subprocess.run(passing.wrap(["sh", "-c", "cat input.txt; cat; pwd"]), input="1 2\n", capture_output=True, text=True)
+    assert result.stdout == f"1 2\n{tmp_path / 'work'}\n"
+    passing = InputPassing("arg", str(tmp_path / "work"), "sample-1.in")
+    result = subprocess.run(passing.wrap(["sh", "-c", 'cat "$1"', "sh"]), input="3\n", capture_output=True, text=True)
+    assert result.stdout == "3\n"
+
+@pytest.mark.parametrize("mode, source", [("file:input.txt", READ_FILE), ("arg", READ_ARG)])
+def test_local_test_passes_input_as_file(tmp_path, mode, source):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n"), ("10 20\n", "30\n")])
+    workspace = setup_test_environment(tmp_path / "ws", config={"test": {"pass_input_as": mode}})
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", source)
+        results = sim.execute("test", "abc300", "a", "python")
+    assert [r["verdict"] for r in results] == ["AC", "AC"]
+
+def test_shared_input_file_runs_cases_one_by_one(tmp_path):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [(f"{i} {i}\n", f"{2 * i}\n") for i in range(1, 9)])
+    config = {"test": {"pass_input_as": "file:input.txt", "cwd": "data"}}
+    workspace = setup_test_environment(tmp_path / "ws", config=config)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", READ_FILE)
+        results = sim.execute("test", "abc300", "a", "python", options={"jobs": "4"})
+    assert [r["verdict"] for r in results] == ["AC"] * 8
+
+def test_shared_input_file_forces_one_job():
+    assert shares_input_file({"pass_input_as": "file:input.txt", "cwd": "data"})
+    assert not shares_input_file({"pass_input_as": "file:input.txt"})
+    assert not shares_input_file({"pass_input_as": "arg", "cwd": "data"})
+    config = UnifiedPathManager().config_json()
+    config.write_text(json.dumps({**json.loads(config.read_text()), "test": {"pass_input_as": "file:input.txt", "cwd": "data"}}))
+    assert CommandTest(None, None, options={"jobs": "4"}).decide_jobs(8) == 1
@@ -0,0 +1,33 @@
+import threading
+import time
+import pytest
+from src.network_limit import configure, network_slot, get_max_concurrent, DEFAULT_MAX_CONCURRENT
+
+def test_configure_default():
+    configure(None)
+    assert get_max_concurrent() == DEFAULT_MAX_CONCURRENT
+
+def test_configure_invalid():
+    with pytest.raises(ValueError):
+        configure(-1)
+
+def test_network_slot_limits_concurrency():
+    configure(2)
+    active = []
+    peak = []
+    lock = threading.Lock()
+    def work():
+        with network_slot():
+            with lock:
+                active.append(1)
+                peak.append(len(active))
+            time.sleep(0.05)
+            with lock:
+                active.pop()
+    threads = [threading.Thread(target=work) for _ in range(6)]
+    for t in threads:
+        t.start()
+    for t in threads:
+        t.join()
+    assert max(peak) == 2
+    configure(None)
@@ -0,0 +1,99 @@
+import json
+import pytest
+from src import event_subscribers, http_client
+from src.error_hints import CphError
+from src.events import ALL, SUBMISSION_ACCEPTED, TEST_RUN_COMPLETED, Event, EventBus
+from src.notifier import DesktopNotifier, DiscordNotifier, NotificationRouter, SlackNotifier, create_notifier, render_message, routed_channels
+from src.shell import ShellResult
+
+WEBHOOK = "https://discord.example/api/webhooks/1/x"
+
+class RecordingTransport:
+    def __init__(self):
+        self.requests = []
+
+    def request(self, method, url, headers=None, body=None, timeout=None):
+        self.requests.append({"method": method, "url": url, "headers": headers, "body": body})
+        return http_client.HttpResponse(204, b"")
+
+class RecordingShell:
+    def __init__(self, returncode=0):
+        self.returncode = returncode
+        self.specs = []
+
+    def run(self, spec):
+        self.specs.append(spec)
+        return ShellResult(spec.argv(), self.returncode, "", "no display" if self.returncode else "")
+
+@pytest.fixture
+def transport():
+    recording = RecordingTransport()
+    previous = http_client.set_transport(recording)
+    yield recording
+    http_client.set_transport(previous)
+
+def test_render_message_uses_event_fields():
+    event = Event(TEST_RUN_COMPLETED, contest="abc300", problem="a", passed=2, total=3, results=[{}])
+    assert render_message(event) == "test abc300 a: 2/3 AC"
+    assert render_message(event, {TEST_RUN_COMPLETED: "{problem} {unknown}"}) == "a {unknown}"
+
+def test_routed_channels_appends_wildcard_without_duplicates():
+    routes = {SUBMISSION_ACCEPTED: ["discord", "desktop"], ALL: "desktop"}
+    assert routed_channels(SUBMISSION_ACCEPTED, routes) == ["discord", "desktop"]
+    assert routed_channels(TEST_RUN_COMPLETED, routes) == ["desktop"]
+
+def test_webhooks_post_json(transport):
+    DiscordNotifier(WEBHOOK).send("cph", "AC: abc300 a")
+    SlackNotifier("https://hooks.slack.example/x").send("cph", "AC")
+    assert json.loads(transport.requests[0]["body"]) == {"content": "**cph** AC: abc300 a"}
+    assert transport.requests[0]["url"] == WEBHOOK
+    assert json.loads(transport.requests[1]["body"]) == {"text": "*cph* AC"}
+
+def test_create_notifier_reads_webhook_from_env(monkeypatch):
+    monkeypatch.setenv("CPH_DISCORD_WEBHOOK", WEBHOOK)
+    notifier = create_notifier("team", {"type": "discord", "url_env": "CPH_DISCORD_WEBHOOK"})
+    assert isinstance(notifier, DiscordNotifier) and notifier.url == WEBHOOK
+    monkeypatch.delenv("CPH_DISCORD_WEBHOOK")
+    with pytest.raises(CphError) as e:
+        create_notifier("team", {"type": "discord", "url_env": "CPH_DISCORD_WEBHOOK"})
+    assert e.value.kind == "notify_webhook_missing"
+    assert isinstance(create_notifier("desktop"), DesktopNotifier)
+    with pytest.raises(ValueError):
+        create_notifier("pager")
+
+def test_desktop_notifier_commands():
+    shell = RecordingShell()
+    DesktopNotifier(shell=shell, platform="linux").send("cph", "AC")
+    assert shell.specs[0].argv() == ["notify-send", "cph", "AC"]
+    assert DesktopNotifier(shell=shell, platform="darwin").command("cph", 'say "hi"')[:2] == ["osascript", "-e"]
+    with pytest.raises(CphError):
+        DesktopNotifier(shell=RecordingShell(returncode=1), platform="linux").send("cph", "AC")
+
+def test_router_keeps_sending_when_a_channel_fails(capsys):
+    sent = []
+
+    class Fake:
+        def __init__(self, name):
+            self.name = name
+
+        def send(self, title, message):
+            if self.name == "broken":
+                raise RuntimeError("offline")
+            sent.append((self.name, message))
+
+    config = {"channels": {"broken": {}, "discord": {}}, "routes": {SUBMISSION_ACCEPTED: ["broken", "missing", "discord"]}}
+    router = NotificationRouter(config, factory=lambda name, spec: Fake(name))
+    assert router.notify(Event(SUBMISSION_ACCEPTED, contest="abc300", problem="a")) == ["discord"]
+    assert sent == [("discord", "AC: abc300 a")]
+    out = capsys.readouterr().out
+    assert "broken に送れませんでした: offline" in out and "missing がnotify.channelsにありません" in out
+
+def test_installed_subscriber_routes_events(transport, monkeypatch):
+    bus = EventBus()
+    monkeypatch.setattr(event_subscribers, "_installed", [])
+    notify = {"channels": {"discord": {"url": WEBHOOK}}, "routes": {SUBMISSION_ACCEPTED: ["discord"]}}
+    event_subscribers.install(bus, get_hooks=lambda: {}, get_notify=lambda: notify)
+    bus.publish(TEST_RUN_COMPLETED, contest="abc300", problem="a", passed=1, total=1, results=[])
+    assert transport.requests == []
+    bus.publish(SUBMISSION_ACCEPTED, contest="abc300", problem="a")
+    assert json.loads(transport.requests[0]["body"])["content"] == "**cph** AC: abc300 a"
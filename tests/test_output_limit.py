@@ -0,0 +1,69 @@
+import asyncio
+import subprocess
+import sys
+import pytest
+from src.output_limit import DEFAULT_OUTPUT_LIMIT, OLE, OutputLimit, parse_output_limit
+from src.commands.test_result_formatter import ResultFormatter, judge_verdict
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+def test_parse_output_limit():
+    assert parse_output_limit("64MB") == 64 * 1024 * 1024
+    assert parse_output_limit("512kb") == 512 * 1024
+    assert parse_output_limit(1000) == 1000
+    assert parse_output_limit("0") == 0
+    assert parse_output_limit(False) == 0
+    with pytest.raises(ValueError):
+        parse_output_limit("lots")
+
+def test_from_config_prefers_problem_limit():
+    assert OutputLimit.from_config({}).limit == DEFAULT_OUTPUT_LIMIT
+    assert OutputLimit.from_config({"output_limit": "1KB"}).limit == 1024
+    assert OutputLimit.from_config({"output_limit": "1KB"}, 0).limit == 0
+    assert OutputLimit(0).wrap(["a.out"]) == ["a.out"]
+
+def test_wrap_stops_infinite_output():
+    limit = OutputLimit(1000)
+    cmd = limit.wrap([sys.executable, "-c", "while True: print('x' * 100)"])
+    proc = subprocess.run(cmd, capture_output=True, timeout=30)
+    assert len(proc.stdout) == 1001
+    assert limit.exceeded(proc.stdout.decode())
+    assert len(limit.truncate(proc.stdout.decode())) == 1000
+
+def test_wrap_keeps_exit_status_and_short_output():
+    limit = OutputLimit(1000)
+    proc = subprocess.run(limit.wrap([sys.executable, "-c", "print('ok'); raise SystemExit(3)"]), capture_output=True, timeout=30)
+    assert proc.returncode == 3
+    assert proc.stdout == b"ok\n"
+    assert not limit.exceeded(proc.stdout.decode())
+
+def test_judge_verdict_is_ole_before_re():
+    result = {"result": (1, "x" * 10, ""), "expected": "x\n", "output_limit_exceeded": 10}
+    assert judge_verdict(result) == OLE
+    lines = ResultFormatter({**result, "name": "sample-1.in", "time": 0.1, "verdict": OLE}, width=80, accessible=True).format()
+    assert "出力が上限（10バイト）を超えたため止めました" in lines
+
+def test_tag_stores_problem_output_limit(tmp_path, capsys):
+    from src.commands.command_tag import CommandTag
+    from src.commands.archive_index import ArchiveIndex
+    asyncio.run(CommandTag({"output-limit": "256MB"}).tag("abc300", "a"))
+    assert ArchiveIndex().get("abc300", "a")["output_limit"] == 256 * 1024 * 1024
+    assert "出力の上限 268435456バイト" in capsys.readouterr().out
+    asyncio.run(CommandTag({"output-limit": "default"}).tag("abc300", "a"))
+    assert ArchiveIndex().get("abc300", "a")["output_limit"] is None
+    with pytest.raises(ValueError):
+        asyncio.run(CommandTag({"output-limit": "huge"}).tag("abc300", "a"))
+
+def test_test_reports_ole_for_endless_output(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    workspace.update_config(test={"output_limit": "4KB"})
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1\n", "1\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "n = input()\nprint(n)\nif n == '2':\n    while True:\n        print('y' * 100)\n")
+        workspace.write_case("own-1", "2\n", "2\n")
+        results = sim.execute("test", "abc300", "a", "python")
+    verdicts = {r["name"]: r["verdict"] for r in results}
+    assert verdicts == {"sample-1.in": "AC", "own-1.in": OLE}
+    ole = next(r for r in results if r["name"] == "own-1.in")
+    assert len(ole["result"][1].encode()) == 4096
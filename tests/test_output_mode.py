@@ -0,0 +1,46 @@
+from src.commands import output_mode
+from src.commands.color_theme import Palette
+from src.commands.output_mode import accessible_enabled, verdict_label
+from src.commands.test_result_formatter import ResultFormatter
+
+def make_result(stdout, expected, stderr="", returncode=0):
+    return {"name": "sample-1", "result": (returncode, stdout, stderr), "expected": expected, "time": 0.1}
+
+def test_accessible_enabled_by_config_or_dumb_terminal():
+    assert accessible_enabled(None, {"TERM": "dumb"}) is True
+    assert accessible_enabled(None, {"TERM": "xterm-256color"}) is False
+    assert accessible_enabled(True, {}) is True
+    # 明示的にfalseならTERM=dumbでも通常の表示
+    assert accessible_enabled(False, {"TERM": "dumb"}) is False
+
+def test_verdict_label():
+    assert verdict_label("AC") == "PASS (AC)"
+    assert verdict_label("WA") == "FAIL (WA)"
+    assert verdict_label("RE") == "FAIL (RE)"
+
+def test_accessible_format_is_line_oriented():
+    text = ResultFormatter(make_result("1\n3\n", "1\n2\n", stderr="warn"), width=80,
+                           palette=Palette(enabled=False), accessible=True).format()
+    assert text.splitlines() == [
+        "sample-1: FAIL (WA) 0.100秒",
+        "期待する出力:", "1", "2",
+        "実際の出力:", "1", "3",
+        "違う行: 2行目",
+        "標準エラー出力:", "warn",
+    ]
+    assert "|" not in text and "---" not in text
+
+def test_accessible_format_pass_and_no_output():
+    text = ResultFormatter(make_result("1\n", "1\n"), width=80, palette=Palette(enabled=False), accessible=True).format()
+    assert text.splitlines()[0] == "sample-1: PASS (AC) 0.100秒"
+    assert "違う行" not in text
+    text = ResultFormatter(make_result("", "1\n", returncode=1), width=80, palette=Palette(enabled=False), accessible=True).format()
+    assert "FAIL (RE)" in text and "（出力なし）" in text
+
+def test_formatter_follows_configured_mode(monkeypatch):
+    monkeypatch.setattr(output_mode, "_accessible", None)
+    output_mode.configure(None, {"TERM": "dumb"})
+    assert output_mode.is_accessible() is True
+    assert ResultFormatter(make_result("1\n", "1\n"), palette=Palette(enabled=False)).accessible is True
+    output_mode.configure(False, {"TERM": "dumb"})
+    assert ResultFormatter(make_result("1\n", "1\n"), palette=Palette(enabled=False)).accessible is False
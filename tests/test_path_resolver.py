@@ -0,0 +1,28 @@
+import json
+import pytest
+from src.path_manager.path_resolver import PathResolver
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def test_default_layout_matches_contest_stocks(tmp_path):
+    r = PathResolver(tmp_path)
+    assert r.stock_dir("abc300", "a", "python") == tmp_path / "contest_stocks" / "abc300" / "a" / "python"
+    assert r.stock_dir("abc300", "a") == tmp_path / "contest_stocks" / "abc300" / "a"
+    assert r.stocks_root() == tmp_path / "contest_stocks"
+    assert r.source_file("rust") == "src/main.rs"
+    assert r.source_file("unknown") == "main.py"
+
+def test_custom_layout_from_config(tmp_path):
+    (tmp_path / "contest_current").mkdir(exist_ok=True)
+    (tmp_path / "contest_current" / "config.json").write_text(json.dumps({
+        "layout": {"stocks": "archive/{contest}-{problem}/{language}", "source": {"python": "solution.py"}}
+    }), encoding="utf-8")
+    upm = UnifiedPathManager(project_root=tmp_path)
+    assert upm.contest_stocks("abc300", "a", "python") == tmp_path.resolve() / "archive" / "abc300-a" / "python"
+    assert upm.contest_stocks("abc300") == tmp_path.resolve() / "archive"
+    assert upm.stocks_root() == tmp_path.resolve() / "archive"
+    assert upm.source_file("python") == "solution.py"
+    assert upm.source_file("rust") == "src/main.rs"
+
+def test_unknown_placeholder_is_rejected(tmp_path):
+    with pytest.raises(ValueError):
+        PathResolver(tmp_path, {"stocks": "{contest}/{task}"})
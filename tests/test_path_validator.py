@@ -0,0 +1,59 @@
+import io
+import os
+import tarfile
+import zipfile
+import pytest
+from src.path_manager.path_validator import PathValidator, PathEscapeError
+from src.error_hints import classify
+
+def test_safe_join_rejects_traversal_and_absolute(tmp_path):
+    v = PathValidator(tmp_path)
+    assert v.safe_join(tmp_path / "test", "sample-1.in") == (tmp_path / "test" / "sample-1.in").resolve()
+    for name in ["../x.in", "a/../../x.in", "/etc/passwd", "C:/x.in", "..\\x.in", ""]:
+        with pytest.raises(PathEscapeError):
+            v.safe_join(tmp_path / "test", name)
+
+def test_symlink_escape_is_detected(tmp_path):
+    outside = tmp_path / "outside"
+    root = tmp_path / "ws"
+    outside.mkdir()
+    (root / "test").mkdir(parents=True)
+    os.symlink(outside, root / "test" / "link")
+    v = PathValidator(root)
+    with pytest.raises(PathEscapeError) as e:
+        v.safe_join(root / "test", "link/x.in")
+    assert classify(e.value) == "path_escape"
+    with pytest.raises(PathEscapeError):
+        v.check_tree(root / "test")
+    os.remove(root / "test" / "link")
+    v.check_tree(root / "test")
+
+def test_extract_zip_aborts_before_writing(tmp_path):
+    archive = tmp_path / "a.zip"
+    with zipfile.ZipFile(archive, "w") as zf:
+        zf.writestr("ok/main.py", "print(1)\n")
+        zf.writestr("../evil.py", "x")
+    v = PathValidator(tmp_path / "ws")
+    with pytest.raises(PathEscapeError):
+        v.extract_archive(archive, tmp_path / "ws" / "import")
+    assert not (tmp_path / "ws" / "import" / "ok").exists()
+    assert not (tmp_path / "evil.py").exists()
+
+def test_extract_tar_rejects_links_and_extracts_files(tmp_path):
+    good = tmp_path / "good.tar"
+    with tarfile.open(good, "w") as tf:
+        data = b"1 2\n"
+        info = tarfile.TarInfo("test/1.in")
+        info.size = len(data)
+        tf.addfile(info, io.BytesIO(data))
+    bad = tmp_path / "bad.tar"
+    with tarfile.open(bad, "w") as tf:
+        info = tarfile.TarInfo("link")
+        info.type = tarfile.SYMTYPE
+        info.linkname = "/etc"
+        tf.addfile(info)
+    v = PathValidator(tmp_path)
+    v.extract_archive(good, tmp_path / "import")
+    assert (tmp_path / "import" / "test" / "1.in").read_text() == "1 2\n"
+    with pytest.raises(PathEscapeError):
+        v.extract_archive(bad, tmp_path / "import2")
@@ -0,0 +1,67 @@
+import asyncio
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_import import ATCODER_PROBLEMS_MODELS
+from src.commands.command_status import CommandStatus
+from src.commands.problem_difficulty import DifficultyModels, describe_difficulty, fetch_difficulty
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+MODELS = {"abc300_a": {"difficulty": -512.3}, "abc300_f": {"difficulty": 2031.6}, "abc300_x": {"slope": 1.0}}
+
+def test_describe_difficulty_uses_atcoder_colors():
+    assert describe_difficulty(None) == "未設定"
+    assert describe_difficulty(-512) == "-512（灰）"
+    assert describe_difficulty(1200) == "1200（水）"
+    assert describe_difficulty(3000) == "3000（赤）"
+
+def test_models_are_cached_until_max_age(tmp_path):
+    site = AtCoderMock(routes={ATCODER_PROBLEMS_MODELS: MODELS})
+    now = [1000.0]
+    with ContestSimulation(setup_test_environment(tmp_path / "ws"), site):
+        models = DifficultyModels(tmp_path / "cache.json", max_age_hours=1, clock=lambda: now[0])
+        assert models.difficulty("abc300", "f") == 2032
+        assert models.difficulty("abc300", "x") is None
+        assert len(site.transport.requests) == 1
+        # オフラインでも期限切れのキャッシュを使う
+        site.online = False
+        now[0] += 7200
+        # 400未満はAtCoder Problemsと同じく丸め込む（400 / exp(1 + 512.3 / 400)）
+        assert models.difficulty("abc300", "a") == 41
+        assert len(site.transport.requests) == 2
+
+def test_open_records_difficulty_and_status_shows_it(tmp_path, capsys):
+    workspace = setup_test_environment(tmp_path / "ws", config={"difficulty": {"fetch": True}})
+    site = AtCoderMock(routes={ATCODER_PROBLEMS_MODELS: MODELS})
+    site.add_problem("abc300", "f", [("1\n", "1\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "f", "python")
+        entry = ArchiveIndex().get("abc300", "f")
+        assert entry["difficulty"] == 2032 and entry["difficulty_source"] == "atcoder_problems"
+        asyncio.run(CommandStatus().show())
+    out = capsys.readouterr().out
+    assert "[open] 難易度: 2032（黄）" in out and "[status] 難易度: 2032（黄）" in out
+
+def test_manual_difficulty_wins_and_fetch_is_optional(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock(routes={ATCODER_PROBLEMS_MODELS: MODELS})
+    site.add_problem("abc300", "f", [("1\n", "1\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "f", "python")
+        assert ArchiveIndex().get("abc300", "f")["difficulty"] is None
+        ArchiveIndex().set_tags("abc300", "f", difficulty=1500)
+        assert fetch_difficulty("abc300", "f", {"fetch": True}) == 1500
+        assert fetch_difficulty("cf1800", "a", {"fetch": True}) is None
+    assert not any(ATCODER_PROBLEMS_MODELS in url for _, url in site.transport.requests)
+
+def test_fetched_difficulty_is_refreshed_but_manual_is_kept(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    models = {"abc300_f": {"difficulty": 2031.6}}
+    site = AtCoderMock(routes={ATCODER_PROBLEMS_MODELS: models})
+    with ContestSimulation(workspace, site):
+        cache = tmp_path / "cache.json"
+        assert fetch_difficulty("abc300", "f", {"fetch": True}, models=DifficultyModels(cache, max_age_hours=0)) == 2032
+        # AtCoder Problemsの推定が変わった
+        models["abc300_f"]["difficulty"] = 2150.2
+        assert fetch_difficulty("abc300", "f", {"fetch": True}, models=DifficultyModels(cache, max_age_hours=0)) == 2150
+        assert ArchiveIndex().get("abc300", "f")["difficulty"] == 2150
+        ArchiveIndex().set_tags("abc300", "f", difficulty=1800)
+        assert fetch_difficulty("abc300", "f", {"fetch": True}, models=DifficultyModels(cache, max_age_hours=0)) == 1800
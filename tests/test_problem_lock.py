@@ -0,0 +1,62 @@
+import json
+import pytest
+from src.commands.problem_lock import ProblemLock
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+SUM = "a, b = map(int, input().split())\nprint(a + b)\n"
+PRODUCT = "a, b = map(int, input().split())\nprint(a * b)\n"
+
+def write_active(tmp_path, contest_name, problem_name):
+    info = tmp_path / "contest_current" / "system_info.json"
+    info.parent.mkdir(parents=True, exist_ok=True)
+    info.write_text(json.dumps({"contest_name": contest_name, "problem_name": problem_name}), encoding="utf-8")
+    return UnifiedPathManager(str(tmp_path))
+
+def test_lock_modes(tmp_path, capsys):
+    upm = write_active(tmp_path, "abc300", "a")
+    assert ProblemLock("warn", upm=upm).check("abc300", "b")
+    assert "contest_currentにあるのは abc300 a のコードとサンプルですが、abc300 b としてtestしようとしています" in capsys.readouterr().out
+    assert ProblemLock("error", upm=upm).check("abc300", "a")
+    with pytest.raises(CphError) as e:
+        ProblemLock("error", upm=upm).check("abc300", "b")
+    assert e.value.kind == "problem_mismatch"
+    assert not ProblemLock("prompt", upm=upm, ask=lambda _: "n").check("abc300", "b")
+    assert ProblemLock("prompt", upm=upm, ask=lambda _: "y").check("abc300", "b")
+    assert ProblemLock("prompt", {"yes": True}, upm=upm, ask=lambda _: "n").check("abc300", "b")
+    assert ProblemLock("off", upm=upm).check("abc300", "b")
+    with pytest.raises(ValueError):
+        ProblemLock.from_config({"problem_lock": "ask"}, upm=upm)
+
+def test_lock_prompt_without_tty_declines(tmp_path, capsys):
+    upm = write_active(tmp_path, "abc300", "a")
+    def closed_stdin(_):
+        raise EOFError
+    assert not ProblemLock("prompt", upm=upm, ask=closed_stdin).check("abc300", "b")
+    assert "確認せずに続けるには --yes" in capsys.readouterr().out
+
+def test_lock_without_active_problem_allows_any(tmp_path):
+    assert ProblemLock("error", upm=UnifiedPathManager(str(tmp_path))).check("abc300", "b")
+
+def test_test_stops_on_mismatch_and_switch_changes_problem(tmp_path):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    site.add_problem("abc300", "b", [("2 3\n", "6\n")])
+    workspace = setup_test_environment(tmp_path / "ws", config={"test": {"problem_lock": "error"}})
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", SUM)
+        with pytest.raises(CphError):
+            sim.execute("test", "abc300", "b", "python")
+        # bへ切り替え、bのコードを書いてから戻ってもaのコードとサンプルが残っている
+        sim.execute("switch", "abc300", "b", "python")
+        assert workspace.read_json("contest_current", "system_info.json")["problem_name"] == "b"
+        assert not workspace.path("contest_current", "test").exists()
+        workspace.write_source("python", PRODUCT)
+        sim.execute("switch", "abc300", "a", "python")
+        assert workspace.path("contest_current", "python", "main.py").read_text(encoding="utf-8") == SUM
+        results = sim.execute("test", "abc300", "a", "python")
+        assert [r["verdict"] for r in results] == ["AC"]
+        sim.execute("switch", "abc300", "b", "python")
+        assert workspace.path("contest_current", "python", "main.py").read_text(encoding="utf-8") == PRODUCT
@@ -0,0 +1,36 @@
+import io
+import json
+import pytest
+from src.commands.progress_reporter import ProgressReporter, NdjsonProgressReporter, create_progress_reporter
+
+def test_default_reporter_is_silent(capsys):
+    reporter = create_progress_reporter()
+    reporter.emit("case_finished", name="sample-1.in")
+    out, err = capsys.readouterr()
+    assert out == ""
+    assert err == ""
+
+def test_ndjson_reporter_writes_one_line_per_event():
+    stream = io.StringIO()
+    reporter = NdjsonProgressReporter(stream)
+    reporter.emit("download_started", url="https://example.com")
+    reporter.emit("case_finished", name="sample-1.in", verdict="AC")
+    lines = stream.getvalue().splitlines()
+    assert len(lines) == 2
+    first = json.loads(lines[0])
+    second = json.loads(lines[1])
+    assert first["event"] == "download_started"
+    assert first["url"] == "https://example.com"
+    assert "timestamp" in first
+    assert second["verdict"] == "AC"
+
+def test_ndjson_reporter_defaults_to_stderr(capsys):
+    reporter = create_progress_reporter("ndjson")
+    reporter.emit("test_started", total=3)
+    out, err = capsys.readouterr()
+    assert out == ""
+    assert json.loads(err)["total"] == 3
+
+def test_create_progress_reporter_invalid():
+    with pytest.raises(ValueError):
+        create_progress_reporter("xml")
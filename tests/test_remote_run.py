@@ -0,0 +1,88 @@
+import asyncio
+import http.cookiejar
+import json
+import urllib.parse
+import pytest
+from src import http_client
+from src.commands.command_remote_run import CommandRemoteRun
+from src.commands.remote_runner import AtCoderCustomTest, create_remote_runner
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.test_support.http_cassette import StaticTransport
+
+BASE = "https://atcoder.jp/contests/abc300/custom_test"
+FORM = '<script>var csrfToken = "tok123"</script><form><textarea name="sourceCode"></textarea></form>'
+
+class CustomTestSite(StaticTransport):
+    """コードテストの模擬。1回目の結果の取得は実行中（Status 2）を返す"""
+    def __init__(self, page=FORM):
+        super().__init__({BASE: page})
+        self.posted = None
+        self.polls = 0
+
+    def request(self, method, url, headers=None, body=None, timeout=None):
+        if url == f"{BASE}/submit/json":
+            self.requests.append((method, url))
+            self.posted = (urllib.parse.parse_qs(body.decode("utf-8")), headers)
+            return http_client.HttpResponse(200, b"{}")
+        if url == f"{BASE}/json?reload=true":
+            self.polls += 1
+            status = 3 if self.polls > 1 else 2
+            data = {"Result": {"Status": status, "ExitCode": 0, "TimeConsumption": 12, "MemoryConsumption": 3712},
+                    "Stdout": "3\n", "Stderr": "", "Interval": 500}
+            return http_client.HttpResponse(200, json.dumps(data).encode("utf-8"))
+        return super().request(method, url, headers, body, timeout)
+
+def session_jar():
+    jar = http.cookiejar.CookieJar()
+    jar.set_cookie(http.cookiejar.Cookie(0, "REVEL_SESSION", "sess", None, False, "atcoder.jp", True, False, "/", True,
+                                         True, None, False, None, None, {}))
+    return jar
+
+def test_custom_test_submits_and_waits_for_result():
+    site = CustomTestSite()
+    waits = []
+    runner = AtCoderCustomTest(sleep=waits.append, cookie_jar=session_jar())
+    with http_client.use_transport(site):
+        result = runner.run("abc300", "5055", "print(3)\n", "1 2\n")
+    form, headers = site.posted
+    assert form["data.LanguageId"] == ["5055"]
+    assert form["sourceCode"] == ["print(3)\n"]
+    assert form["input"] == ["1 2\n"]
+    assert form["csrf_token"] == ["tok123"]
+    assert headers["Cookie"] == "REVEL_SESSION=sess"
+    assert waits == [0.5]
+    assert result == {"exit_code": 0, "stdout": "3\n", "stderr": "", "time_ms": 12, "memory_kb": 3712}
+
+def test_custom_test_requires_login():
+    with http_client.use_transport(CustomTestSite(page='<script>var csrfToken = "x"</script><form action="/login"></form>')):
+        with pytest.raises(CphError) as e:
+            AtCoderCustomTest(cookie_jar=[]).run("abc300", "5055", "", "")
+    assert e.value.kind == "remote_run_login"
+
+def test_unsupported_site():
+    with pytest.raises(CphError) as e:
+        create_remote_runner("yukicoder")
+    assert e.value.kind == "remote_run_unsupported"
+
+def test_remote_run_judges_test_case_input(tmp_path, capsys):
+    upm = UnifiedPathManager()
+    upm.config_json().write_text(json.dumps({"language_id": {"python": "5055"}}), encoding="utf-8")
+    source = upm.contest_current("python", upm.source_file("python"))
+    source.parent.mkdir(parents=True, exist_ok=True)
+    source.write_text("print(3)\n", encoding="utf-8")
+    test_dir = upm.contest_current("test")
+    (test_dir / "own-1.in").write_text("1 2\n", encoding="utf-8")
+    (test_dir / "own-1.out").write_text("3\n", encoding="utf-8")
+    site = CustomTestSite()
+    command = CommandRemoteRun({"input": str(test_dir / "own-1.in")}, runner=AtCoderCustomTest(sleep=lambda s: None, cookie_jar=[]))
+    with http_client.use_transport(site):
+        result = asyncio.run(command.remote_run("abc300", "a", "python"))
+    assert result["verdict"] == "AC"
+    out = capsys.readouterr().out
+    assert "終了コード 0 / 12 ms / 3712 KB" in out
+    assert "期待出力と比べた結果: AC" in out
+
+def test_remote_run_requires_input(tmp_path):
+    with pytest.raises(ValueError):
+        CommandRemoteRun({}).read_input()
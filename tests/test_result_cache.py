@@ -0,0 +1,107 @@
+import json
+import asyncio
+from src.commands.cache_epoch import CacheEpochs
+from src.commands.result_cache import ResultCache, source_hash
+from src.commands.command_submit import CommandSubmit
+from src.commands import command_submit
+
+def test_source_hash_tracks_content(tmp_path):
+    src = tmp_path / "python"
+    src.mkdir()
+    (src / "main.py").write_text("print(1)")
+    first = source_hash(src)
+    assert first == source_hash(src)
+    (src / "main.py").write_text("print(2)")
+    assert source_hash(src) != first
+    assert source_hash(tmp_path / "missing") is None
+
+def test_source_hash_ignores_build_dir(tmp_path):
+    (tmp_path / "main.rs").write_text("fn main() {}")
+    before = source_hash(tmp_path)
+    (tmp_path / "target").mkdir()
+    (tmp_path / "target" / "rust").write_text("binary")
+    assert source_hash(tmp_path) == before
+
+def test_result_cache_put_and_get(tmp_path):
+    cache = ResultCache(tmp_path / "results.json")
+    key = cache.key("abc300", "a", "python")
+    cache.put(key, "h1", [{"name": "sample-1.in", "verdict": "AC", "time": 0.1}])
+    entry = cache.get(key)
+    assert entry["source_hash"] == "h1"
+    assert entry["all_ac"] is True
+    assert entry["cases"][0]["name"] == "sample-1.in"
+
+class DummyCommandTest:
+    def __init__(self):
+        self.calls = []
+    async def run_test_return_results(self, *a, **k):
+        self.calls.append(k)
+        return [{"name": "sample-1.in", "verdict": "WA"}]
+    def print_test_results(self, results):
+        pass
+    def is_all_ac(self, results):
+        return all(r["verdict"] == "AC" for r in results)
+
+def make_submit(tmp_path, monkeypatch):
+    cmd = CommandSubmit(None, None)
+    cmd.command_test = DummyCommandTest()
+    (tmp_path / "python").mkdir()
+    (tmp_path / "python" / "main.py").write_text("print(1)")
+    monkeypatch.setattr(cmd.upm, "contest_current", lambda *p: tmp_path.joinpath(*p))
+    monkeypatch.setattr(command_submit, "ResultCache", lambda: ResultCache(tmp_path / "results.json"))
+    return cmd
+
+def test_preflight_uses_cache_when_source_unchanged(tmp_path, monkeypatch):
+    cmd = make_submit(tmp_path, monkeypatch)
+    cache = ResultCache(tmp_path / "results.json")
+    cache.put(cache.key("abc300", "a", "python"), source_hash(tmp_path / "python"), [{"name": "sample-1.in", "verdict": "AC"}], epoch=cmd.cache_epoch("python"))
+    all_ac, summary = asyncio.run(cmd.preflight_samples("abc300", "a", "python"))
+    assert all_ac is True
+    assert "1/1 AC" in summary
+    assert cmd.command_test.calls == []
+
+def test_preflight_reruns_samples_when_source_changed(tmp_path, monkeypatch):
+    cmd = make_submit(tmp_path, monkeypatch)
+    cache = ResultCache(tmp_path / "results.json")
+    cache.put(cache.key("abc300", "a", "python"), "stale", [{"name": "sample-1.in", "verdict": "AC"}])
+    all_ac, summary = asyncio.run(cmd.preflight_samples("abc300", "a", "python"))
+    assert all_ac is False
+    assert "0/1 AC" in summary
+    assert cmd.command_test.calls[0]["case_filter"]("test/sample-1.in")
+    assert not cmd.command_test.calls[0]["case_filter"]("test/custom-1.in")
+
+def set_toolchain(version):
+    config = json.load(open("contest_current/config.json"))
+    config["toolchain"] = {"atcoder": {"rust": version}}
+    json.dump(config, open("contest_current/config.json", "w"))
+
+def test_toolchain_change_drops_rust_caches(tmp_path, capsys):
+    results = ResultCache(tmp_path / "results.json")
+    results.put(results.key("abc300", "a", "rust"), "h", [{"name": "sample-1.in", "verdict": "AC"}], epoch="atcoder:1.70.0")
+    results.put(results.key("abc300", "a", "python"), "h", [{"name": "sample-1.in", "verdict": "AC"}])
+    target = tmp_path / ".temp" / "rust" / "target"
+    target.mkdir(parents=True)
+    epochs = CacheEpochs(path=tmp_path / "cache_epochs.json")
+    # 初めて記録するときは消さない
+    assert epochs.refresh("rust", "atcoder:1.70.0", results) is False
+    assert epochs.refresh("rust", "atcoder:1.70.0", results) is False
+    assert target.exists()
+    assert epochs.refresh("rust", "atcoder:1.79.0", results) is True
+    assert not target.exists()
+    assert list(results.load()) == ["abc300/a/python"]
+    assert "rust のジャッジのバージョンが変わりました（atcoder:1.70.0 → atcoder:1.79.0）" in capsys.readouterr().out
+    assert epochs.load() == {"rust": "atcoder:1.79.0"}
+
+def test_preflight_ignores_results_from_another_toolchain(tmp_path, monkeypatch):
+    set_toolchain("1.70.0")
+    cmd = make_submit(tmp_path, monkeypatch)
+    (tmp_path / "rust").mkdir()
+    (tmp_path / "rust" / "main.rs").write_text("fn main() {}")
+    assert cmd.cache_epoch("rust") == "atcoder:1.70.0"
+    cache = ResultCache(tmp_path / "results.json")
+    cache.put(cache.key("abc300", "a", "rust"), source_hash(tmp_path / "rust"), [{"name": "sample-1.in", "verdict": "AC"}], epoch="atcoder:1.70.0")
+    set_toolchain("1.79.0")
+    all_ac, summary = asyncio.run(cmd.preflight_samples("abc300", "a", "rust"))
+    assert all_ac is False
+    assert "ツールチェイン" in summary
+    assert len(cmd.command_test.calls) == 1
@@ -0,0 +1,43 @@
+import json
+from src.commands.run_artifacts import RunArtifacts
+
+def make_result(name, stdout, verdict):
+    return {"name": name, "result": (0, stdout, ""), "expected": "", "time": 0.1, "in_file": f"/tmp/{name}", "verdict": verdict}
+
+def test_save_results(tmp_path):
+    artifacts = RunArtifacts(run_id="run1", project_root=tmp_path)
+    artifacts.save_results([make_result("sample-1.in", "2\n", "AC")], problem_name="a")
+    run_dir = tmp_path / ".cph" / "runs" / "run1"
+    assert (run_dir / "sample-1.stdout").read_text(encoding="utf-8") == "2\n"
+    data = json.loads((run_dir / "run.json").read_text(encoding="utf-8"))
+    assert data["problem_name"] == "a"
+    assert data["cases"][0]["verdict"] == "AC"
+
+def test_prune_keeps_latest(tmp_path):
+    for i in range(3):
+        RunArtifacts(run_id=f"run{i}", project_root=tmp_path).ensure_dir()
+    artifacts = RunArtifacts(run_id="run3", project_root=tmp_path)
+    artifacts.ensure_dir()
+    artifacts.prune(keep=2)
+    remaining = sorted(p.name for p in (tmp_path / ".cph" / "runs").iterdir())
+    assert remaining == ["run2", "run3"]
+
+def test_inputs_are_shared_across_runs(tmp_path):
+    in_file = tmp_path / "sample-1.in"
+    in_file.write_text("1 2\n", encoding="utf-8")
+    result = {**make_result("sample-1.in", "3\n", "AC"), "in_file": str(in_file)}
+    for i in range(3):
+        RunArtifacts(run_id=f"run{i}", project_root=tmp_path).save_results([result])
+    inputs = tmp_path / ".cph" / "run_inputs"
+    stored = [p.name for p in inputs.iterdir()]
+    assert len(stored) == 1
+    artifacts = RunArtifacts(run_id="run0", project_root=tmp_path)
+    case = RunArtifacts.all_runs(tmp_path)[-1]["cases"][0]
+    assert case["stored_input"] == stored[0] == case["input_digest"]
+    assert artifacts.stored_input(case).read_text(encoding="utf-8") == "1 2\n"
+    # どの実行からも使われなくなった写しは消す
+    in_file.write_text("5 5\n", encoding="utf-8")
+    latest = RunArtifacts(run_id="run3", project_root=tmp_path)
+    latest.save_results([result])
+    latest.prune(keep=1)
+    assert sorted(p.name for p in inputs.iterdir()) == [latest.stored_input(RunArtifacts.all_runs(tmp_path)[0]["cases"][0]).name]
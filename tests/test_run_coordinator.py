@@ -0,0 +1,106 @@
+import asyncio
+import threading
+import time
+import pytest
+from src import cancellation
+from src.commands.command_watch import CommandWatch, SourceWatcher
+from src.run_coordinator import RunCoordinator
+
+class BlockingRun:
+    """release されるまで（中断されればそこで）止まる実行。理由を順に記録する"""
+    def __init__(self):
+        self.started = []
+        self.entered = threading.Event()
+        self.release = threading.Event()
+
+    def __call__(self, reason):
+        self.started.append(reason)
+        self.entered.set()
+        while not self.release.wait(0.01):
+            cancellation.current().check()
+
+def start(strategy):
+    run = BlockingRun()
+    coordinator = RunCoordinator(run, strategy)
+    assert coordinator.trigger("first") == "started"
+    assert run.entered.wait(5)
+    return run, coordinator
+
+def test_cancel_stops_stale_run_and_runs_latest_change():
+    run, coordinator = start("cancel")
+    run.entered.clear()
+    assert coordinator.trigger("second") == "cancelled"
+    assert run.entered.wait(5)
+    run.release.set()
+    assert coordinator.wait_idle(5)
+    assert coordinator.history == [("first", "cancelled"), ("second", "done")]
+
+def test_queue_waits_and_coalesces_pending_runs():
+    run, coordinator = start("queue")
+    assert coordinator.trigger("second") == "queued"
+    assert coordinator.trigger("third") == "queued"
+    run.release.set()
+    assert coordinator.wait_idle(5)
+    assert coordinator.history == [("first", "done"), ("third", "done")]
+
+def test_skip_ignores_changes_while_running():
+    run, coordinator = start("skip")
+    assert coordinator.trigger("second") == "skipped"
+    run.release.set()
+    assert coordinator.wait_idle(5)
+    assert run.started == ["first"]
+
+def test_close_cancels_running_and_drops_pending():
+    run, coordinator = start("queue")
+    coordinator.trigger("second")
+    assert coordinator.close(5)
+    assert coordinator.history == [("first", "cancelled")]
+    assert coordinator.trigger("third") == "closed"
+
+def test_failed_run_does_not_stop_coordinator(capsys):
+    def broken(reason):
+        raise RuntimeError("build failed")
+    coordinator = RunCoordinator(broken)
+    coordinator.trigger()
+    assert coordinator.wait_idle(5)
+    assert coordinator.history == [(None, "failed")]
+    assert "build failed" in capsys.readouterr().out
+
+def test_unknown_strategy_is_rejected():
+    with pytest.raises(ValueError):
+        RunCoordinator(lambda reason: None, "restart")
+
+def test_source_watcher_reports_changed_files(tmp_path):
+    (tmp_path / "main.py").write_text("print(1)\n")
+    watcher = SourceWatcher([tmp_path])
+    assert watcher.changes() == []
+    (tmp_path / "sample-1.in").write_text("1\n")
+    (tmp_path / "main.py").unlink()
+    assert watcher.changes() == [str(tmp_path / "main.py"), str(tmp_path / "sample-1.in")]
+
+def test_watch_reruns_test_on_change(tmp_path, monkeypatch):
+    source = tmp_path / "python"
+    source.mkdir()
+    (source / "main.py").write_text("print(1)\n")
+    ran = []
+
+    class FakeTest:
+        async def run_test(self, contest_name, problem_name, language_name):
+            ran.append((contest_name, problem_name, language_name))
+
+    async def edit_once(interval):
+        if len(ran) == 1 and not edited:
+            edited.append(True)
+            (source / "main.py").write_text("print(2)\n")
+            # 更新時刻の分解能が粗いファイルシステムでも変更とみなされるようにする
+            time.sleep(0.01)
+        else:
+            await asyncio.sleep(0.05)
+
+    edited = []
+    watch = CommandWatch(FakeTest(), sleep=edit_once)
+    monkeypatch.setattr(watch, "get_config", lambda: {"strategy": "queue"})
+    monkeypatch.setattr(watch, "watched_paths", lambda language_name: [source])
+    watch_runs = asyncio.run(watch.watch("abc300", "a", "python", max_polls=20))
+    assert [outcome for _, outcome in watch_runs] == ["done", "done"]
+    assert ran == [("abc300", "a", "python")] * 2
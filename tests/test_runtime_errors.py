@@ -0,0 +1,41 @@
+import pytest
+from src.commands.runtime_errors import RuntimeErrorClassifier
+from src.commands.test_result_formatter import ResultFormatter
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+PYTHON_MEMORY_ERROR = 'Traceback (most recent call last):\n  File "main.py", line 1, in <module>\nMemoryError\n'
+RUST_OVERFLOW = "thread 'main' panicked at src/main.rs:4:13:\nattempt to subtract with overflow\n"
+
+def test_builtin_patterns_by_language():
+    classifier = RuntimeErrorClassifier()
+    assert classifier.classify(PYTHON_MEMORY_ERROR, "python")["kind"] == "MLE"
+    assert classifier.classify(RUST_OVERFLOW, "rust")["kind"] == "overflow"
+    assert classifier.classify("Exception in thread \"main\" java.lang.StackOverflowError\n", "java")["kind"] == "recursion"
+    # 言語を限った文言は、ほかの言語では見ない
+    assert classifier.classify(RUST_OVERFLOW, "python") is None
+    assert classifier.classify("ValueError: bad\n", "python") is None
+
+def test_config_entries_come_first():
+    classifier = RuntimeErrorClassifier.from_config({"runtime_errors": [{"pattern": "MemoryError", "kind": "custom", "note": "配列を小さく"}]})
+    assert classifier.classify(PYTHON_MEMORY_ERROR, "python") == {"kind": "custom", "note": "配列を小さく"}
+    assert classifier.classify(RUST_OVERFLOW, "rust")["kind"] == "overflow"
+    with pytest.raises(ValueError):
+        RuntimeErrorClassifier.from_config({"runtime_errors": [{"pattern": "("}]})
+
+def test_formatter_shows_note():
+    result = {"result": (1, "", RUST_OVERFLOW), "expected": "1\n", "name": "sample-1.in", "time": 0.1, "verdict": "RE",
+              "runtime_error": {"kind": "overflow", "note": "算術オーバーフロー"}}
+    assert "原因の見当: 算術オーバーフロー" in ResultFormatter(result, width=80, accessible=True).format()
+
+def test_test_attaches_runtime_error_to_re_cases(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1\n", "1\n"), ("0\n", "0\n")])
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", "n = int(input())\nprint(1 // n)\n")
+        results = sim.execute("test", "abc300", "a", "python")
+    by_name = {r["name"]: r for r in results}
+    assert by_name["sample-2.in"]["verdict"] == "RE"
+    assert by_name["sample-2.in"]["runtime_error"]["kind"] == "zero_division"
+    assert "runtime_error" not in by_name["sample-1.in"]
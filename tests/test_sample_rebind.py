@@ -0,0 +1,117 @@
+import asyncio
+from src import http_client
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_case import CommandCase
+from src.commands.sample_rebind import (
+    ACCEPT, ADDED, CHANGED, KEEP, REMOVED, RENAMED, SKIP, apply_rebind, lint_fresh_samples, plan_rebind,
+    sample_checksums,
+)
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.test_support.http_cassette import StaticTransport
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+URL = "https://atcoder.jp/contests/abc300/tasks/abc300_a"
+
+def page(*samples):
+    parts = []
+    for i, (given, expected) in enumerate(samples, 1):
+        parts.append(f'<div class="part"><section><h3>入力例 {i}</h3><pre>{given}</pre></section></div>')
+        parts.append(f'<div class="part"><section><h3>出力例 {i}</h3><pre>{expected}</pre></section></div>')
+    return f'<html><body><span class="lang-ja">{"".join(parts)}</span></body></html>'
+
+def write_case(test_dir, name, given, expected):
+    (test_dir / f"{name}.in").write_text(given, encoding="utf-8")
+    (test_dir / f"{name}.out").write_text(expected, encoding="utf-8")
+
+def kinds(changes):
+    return sorted((c.kind, c.name, c.to, c.edited) for c in changes)
+
+def test_plan_rebind_detects_renumbering_and_edits():
+    stored = {"sample-1": "x", "sample-2": "y", "sample-3": "edited", "sample-4": "w"}
+    fresh = {"sample-1": "y", "sample-2": "x", "sample-3": "z", "sample-5": "v"}
+    recorded = {"sample-3": "z0", "sample-4": "w"}
+    assert kinds(plan_rebind(stored, fresh, recorded)) == [
+        (ADDED, "sample-5", "sample-5", False),
+        (CHANGED, "sample-3", "sample-3", True),
+        (REMOVED, "sample-4", "sample-4", False),
+        (RENAMED, "sample-1", "sample-2", False),
+        (RENAMED, "sample-2", "sample-1", False),
+    ]
+    assert plan_rebind(fresh, fresh) == []
+
+def test_apply_rebind_swaps_without_overwriting(tmp_path):
+    test_dir, fresh_dir = tmp_path / "test", tmp_path / "fresh"
+    test_dir.mkdir()
+    fresh_dir.mkdir()
+    write_case(test_dir, "sample-1", "1\n", "a\n")
+    write_case(test_dir, "sample-2", "2\n", "b\n")
+    write_case(fresh_dir, "sample-1", "2\n", "b\n")
+    write_case(fresh_dir, "sample-2", "1\n", "a\n")
+    changes = plan_rebind(sample_checksums(test_dir), sample_checksums(fresh_dir))
+    apply_rebind(test_dir, fresh_dir, [(c, ACCEPT) for c in changes])
+    assert (test_dir / "sample-1.in").read_text(encoding="utf-8") == "2\n"
+    assert (test_dir / "sample-2.out").read_text(encoding="utf-8") == "a\n"
+
+def test_case_rebind_keeps_edited_samples_and_own_cases(tmp_path, capsys):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    upm.info_json().write_text('{"contest_name": "abc300", "problem_name": "a"}', encoding="utf-8")
+    test_dir = upm.contest_current("test")
+    for f in test_dir.iterdir():
+        f.unlink()
+    write_case(test_dir, "sample-1", "1\n", "a\n")
+    write_case(test_dir, "sample-2", "2\n", "b\n")
+    recorded = sample_checksums(test_dir)
+    ArchiveIndex(upm=upm).update("abc300", "a", lambda e: e.update(sample_checksums=recorded))
+    # 手で直したサンプルと、自分で足したケース
+    (test_dir / "sample-2.out").write_text("b (fixed)\n", encoding="utf-8")
+    write_case(test_dir, "my-1", "9\n", "z\n")
+    # 訂正で入力例1と2が入れ替わり、3が増えた
+    with http_client.use_transport(StaticTransport({URL: page(("3\n", "c\n"), ("1\n", "a\n"), ("4\n", "d\n"))})):
+        decisions = CommandCase({"yes": True}, args=["rebind"], upm=upm).rebind()
+    assert sorted((c.kind, c.name, c.to, action) for c, action in decisions) == [
+        (ADDED, "sample-1", "sample-1", ACCEPT),
+        (ADDED, "sample-3", "sample-3", ACCEPT),
+        (REMOVED, "sample-2", "sample-2", KEEP),
+        (RENAMED, "sample-1", "sample-2", ACCEPT),
+    ]
+    names = sorted(p.name for p in test_dir.iterdir())
+    assert names == ["custom-sample-2.in", "custom-sample-2.out", "my-1.in", "my-1.out",
+                     "sample-1.in", "sample-1.out", "sample-2.in", "sample-2.out", "sample-3.in", "sample-3.out"]
+    assert (test_dir / "sample-2.in").read_text(encoding="utf-8") == "1\n"
+    assert (test_dir / "custom-sample-2.out").read_text(encoding="utf-8") == "b (fixed)\n"
+    assert ArchiveIndex(upm=upm).get("abc300", "a")["sample_checksums"] == sample_checksums(test_dir)
+    assert "（手元で変更されています）" in capsys.readouterr().out
+
+def test_case_rebind_asks_and_dry_run(tmp_path, capsys):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    upm.info_json().write_text('{"contest_name": "abc300", "problem_name": "a"}', encoding="utf-8")
+    test_dir = upm.contest_current("test")
+    for f in test_dir.iterdir():
+        f.unlink()
+    write_case(test_dir, "sample-1", "1\n", "a\n")
+    transport = StaticTransport({URL: page(("1\n", "A\n"))})
+    with http_client.use_transport(transport):
+        changes = CommandCase({"dry-run": True}, args=["rebind"], upm=upm).rebind()
+        assert [c.kind for c in changes] == [CHANGED]
+        assert "+A" in capsys.readouterr().out
+        decisions = CommandCase({}, args=["rebind"], upm=upm, ask=lambda prompt: "s").rebind()
+    assert [action for _, action in decisions] == [SKIP]
+    assert (test_dir / "sample-1.out").read_text(encoding="utf-8") == "a\n"
+
+def test_open_records_checksums_after_lint(tmp_path):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1\r\n", "a\r\nb")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        test_dir = workspace.path("contest_current", "test")
+        assert (test_dir / "sample-1.out").read_bytes() == b"a\nb\n"
+        recorded = ArchiveIndex(upm=UnifiedPathManager()).get("abc300", "a")["sample_checksums"]
+    assert recorded == sample_checksums(test_dir)
+    # 取り直したものも同じように直してから比べるので、改行の違いだけでは変更にならない
+    fresh_dir = tmp_path / "fresh"
+    fresh_dir.mkdir()
+    (fresh_dir / "sample-1.in").write_bytes(b"1\r\n")
+    (fresh_dir / "sample-1.out").write_bytes(b"a\r\nb")
+    lint_fresh_samples(fresh_dir, {})
+    assert plan_rebind(sample_checksums(test_dir), sample_checksums(fresh_dir), recorded) == []
@@ -0,0 +1,186 @@
+import pytest
+from src import http_client
+from src.commands.archive_index import ArchiveIndex
+from src.commands.command_open import CommandOpen
+from src.commands.sample_scraper import SampleScraper, count_sample_headings, parse_blocks, scrape_samples
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.test_support.http_cassette import StaticTransport
+
+ATCODER = """<html><body><div id="task-statement">
+<span class="lang"><span class="lang-ja">
+<div class="part"><section><h3>入力</h3><pre><var>N</var></pre></section></div>
+<div class="part"><section><h3>入力例 1<span class="btn-copy">Copy</span></h3><pre id="pre-sample0">3
+1 2 3
+</pre></section></div>
+<div class="part"><section><h3>出力例 1</h3><pre id="pre-sample1">6
+</pre></section></div>
+<div class="part"><section><h3>入力例 2</h3><pre>1
+&lt;x&gt;
+</pre></section></div>
+<div class="part"><section><h3>出力例 2</h3><pre>ok</pre></section></div>
+</span>
+<span class="lang-en">
+<div class="part"><section><h3>Sample Input 1</h3><pre>3
+1 2 3
+</pre></section></div>
+<div class="part"><section><h3>Sample Output 1</h3><pre>6
+</pre></section></div>
+</span></span></div></body></html>"""
+
+CODEFORCES = """<div class="sample-tests"><div class="sample-test">
+<div class="input"><div class="title">Input</div><pre><div class="test-example-line">2</div><div class="test-example-line">1 2</div></pre></div>
+<div class="output"><div class="title">Output</div><pre>3
+</pre></div>
+<div class="input"><div class="title">Input</div><pre>1<br>5<br></pre></div>
+<div class="output"><div class="title">Output</div><pre>5</pre></div>
+</div></div>"""
+
+def test_atcoder_prefers_japanese_statement():
+    samples, matched, report = scrape_samples(ATCODER)
+    assert matched == "atcoder-lang-ja"
+    assert samples == [("3\n1 2 3\n", "6\n"), ("1\n<x>\n", "ok\n")]
+    assert dict(report)["heading"] == 2
+
+def test_codeforces_sample_test_lines():
+    samples, matched, _ = scrape_samples(CODEFORCES)
+    assert matched == "codeforces-sample-test"
+    assert samples == [("2\n1 2\n", "3\n"), ("1\n5\n", "5\n")]
+
+def test_generic_heading_and_pre_pairs_fallbacks():
+    html = "<h4>Example Input</h4><p>x</p><pre>7</pre><h4>Example Output</h4><pre>49</pre>"
+    assert scrape_samples(html)[:2] == ([("7\n", "49\n")], "heading")
+    # 見出しが無い（構造が変わった）ときは<pre>を2つずつ組にする
+    html = "<div><pre>1</pre><pre>2</pre></div>"
+    assert scrape_samples(html)[:2] == ([("1\n", "2\n")], "pre-pairs")
+
+def test_unmatched_markup_reports_no_strategy():
+    samples, matched, report = scrape_samples("<div><pre>only one</pre><p>unclosed")
+    assert samples == [] and matched is None
+    assert all(count == 0 for _, count in report)
+
+def test_download_writes_cases_and_debug_dump(tmp_path, capsys):
+    url = "https://atcoder.jp/contests/abc1/tasks/abc1_a"
+    test_dir = tmp_path / "samples"
+    with http_client.use_transport(StaticTransport({url: ATCODER})):
+        SampleScraper(UnifiedPathManager(str(tmp_path)), debug=True).download(url, test_dir)
+    assert (test_dir / "sample-2.in").read_text(encoding="utf-8") == "1\n<x>\n"
+    assert (test_dir / "sample-1.out").read_text(encoding="utf-8") == "6\n"
+    out = capsys.readouterr().out
+    assert "→ atcoder-lang-ja: 2組" in out
+    dumps = list((tmp_path / ".cph" / "scrape").iterdir())
+    assert len(dumps) == 1 and "入力例 1" in dumps[0].read_text(encoding="utf-8")
+
+def test_download_without_samples_raises(tmp_path):
+    url = "https://example.com/p"
+    with http_client.use_transport(StaticTransport({url: "<p>maintenance</p>"})):
+        with pytest.raises(CphError) as e:
+            SampleScraper(UnifiedPathManager(str(tmp_path))).download(url, tmp_path / "t")
+    assert e.value.kind == "scrape_failed"
+
+class FailingEnv:
+    def download_testcases(self, url, test_dir):
+        raise CphError("oj download failed", kind="oj_download_failed")
+
+def test_open_falls_back_to_scraper_when_oj_fails(tmp_path):
+    url = "https://codeforces.com/contest/1/problem/A"
+    test_dir = tmp_path / "fallback"
+    command = CommandOpen(None, None, FailingEnv())
+    command.upm = UnifiedPathManager(str(tmp_path))
+    with http_client.use_transport(StaticTransport({url: CODEFORCES})):
+        command.download_samples(url, test_dir)
+    assert (test_dir / "sample-1.in").read_text(encoding="utf-8") == "2\n1 2\n"
+
+def test_open_reraises_oj_error_when_scraping_also_fails(tmp_path):
+    command = CommandOpen(None, None, FailingEnv())
+    command.upm = UnifiedPathManager(str(tmp_path))
+    with pytest.raises(CphError) as e:
+        command.download_samples("https://atcoder.jp/contests/x/tasks/x_a", tmp_path / "t")
+    assert e.value.kind == "oj_download_failed"
+
+def test_count_sample_headings_prefers_japanese():
+    assert count_sample_headings(parse_blocks(ATCODER)) == 2
+    assert count_sample_headings(parse_blocks(CODEFORCES)) == 0
+
+def test_download_fails_on_partial_parse_and_keeps_html(tmp_path):
+    url = "https://example.com/p"
+    # 入力例2の<pre>が見出しの直後に無い（マークアップが崩れている）
+    html = "<h3>入力例 1</h3><pre>1</pre><h3>出力例 1</h3><pre>2</pre><h3>入力例 2</h3><div>3</div><h3>出力例 2</h3><pre>4</pre>"
+    command = CommandOpen(None, None, FailingEnv())
+    command.upm = UnifiedPathManager(str(tmp_path))
+    with http_client.use_transport(StaticTransport({url: html})):
+        with pytest.raises(CphError) as e:
+            command.download_samples(url, tmp_path / "t")
+    assert e.value.kind == "sample_count_mismatch"
+    assert "入力例は2個ですが、1組しか" in str(e.value)
+    assert len(list((tmp_path / ".cph" / "scrape").iterdir())) == 1
+    assert not (tmp_path / "t").exists()
+
+SUBTASKS = """<h3>制約</h3><h3>小課題 1 (30 点)</h3><p>N ≤ 10</p>
+<h3>入出力例</h3>
+<h3>小課題 1 のサンプル</h3>
+<h3>入力例 1</h3><pre>1</pre><h3>出力例 1</h3><pre>2</pre>
+<h3>入力例 2</h3><pre>3</pre><h3>出力例 2</h3><pre>4</pre>
+<h3>Subtask 2</h3>
+<h3>入力例 1</h3><pre>5</pre><h3>出力例 1</h3><pre>6</pre>"""
+
+def test_samples_carry_subtask_groups(tmp_path, capsys):
+    samples, matched, _ = scrape_samples(SUBTASKS)
+    assert matched == "heading"
+    # 小課題ごとに番号が振り直されていても別のサンプル
+    assert samples == [("1\n", "2\n"), ("3\n", "4\n"), ("5\n", "6\n")]
+    assert [s.group for s in samples] == ["1", "1", "2"]
+    assert count_sample_headings(parse_blocks(SUBTASKS)) == 3
+    # 制約の小課題の見出しは、間に別の見出しがあれば後のサンプルに付かない
+    assert [s.group for s in scrape_samples("<h3>小課題 1</h3><h3>入出力例</h3><h3>入力例 1</h3><pre>1</pre><h3>出力例 1</h3><pre>2</pre>")[0]] == [None]
+    url = "https://example.com/joi"
+    with http_client.use_transport(StaticTransport({url: SUBTASKS})):
+        SampleScraper(UnifiedPathManager(str(tmp_path))).download(url, tmp_path / "t")
+    assert (tmp_path / "t" / "group_1" / "sample-1-2.in").read_text(encoding="utf-8") == "3\n"
+    assert (tmp_path / "t" / "group_2" / "sample-2-1.out").read_text(encoding="utf-8") == "6\n"
+    assert "小課題ごとにgroup_1, group_2へ" in capsys.readouterr().out
+
+def test_open_records_sample_groups_in_problem_meta(tmp_path):
+    command = CommandOpen(None, None, FailingEnv())
+    command.upm = UnifiedPathManager(str(tmp_path))
+    samples, _, _ = scrape_samples(SUBTASKS)
+    assert command.record_sample_groups("joi2024", "a", samples) == ["group_1", "group_2"]
+    assert ArchiveIndex(upm=command.upm).get("joi2024", "a")["sample_groups"] == ["group_1", "group_2"]
+    assert command.record_sample_groups("abc1", "a", scrape_samples(ATCODER)[0]) == []
+
+class OjEnv:
+    """oj downloadの代わりに、小課題を区別しない通し番号でサンプルを書き出す"""
+    def __init__(self, samples):
+        self.samples = samples
+
+    def download_testcases(self, url, test_dir):
+        test_dir.mkdir(parents=True, exist_ok=True)
+        for i, (given, expected) in enumerate(self.samples, 1):
+            (test_dir / f"sample-{i}.in").write_text(given, encoding="utf-8")
+            (test_dir / f"sample-{i}.out").write_text(expected, encoding="utf-8")
+
+def test_open_groups_oj_samples_by_subtask(tmp_path):
+    url = "https://example.com/joi"
+    test_dir = tmp_path / "t"
+    command = CommandOpen(None, None, OjEnv([("1\n", "2\n"), ("3\n", "4\n"), ("5\n", "6\n")]))
+    command.upm = UnifiedPathManager(str(tmp_path))
+    with http_client.use_transport(StaticTransport({url: SUBTASKS})):
+        samples = command.download_samples(url, test_dir)
+    assert command.record_sample_groups("joi2024", "a", samples) == ["group_1", "group_2"]
+    assert sorted(str(p.relative_to(test_dir)) for p in test_dir.rglob("*.in")) == [
+        "group_1/sample-1-1.in", "group_1/sample-1-2.in", "group_2/sample-2-1.in"]
+    assert (test_dir / "group_2" / "sample-2-1.out").read_text(encoding="utf-8") == "6\n"
+
+def test_open_keeps_oj_samples_when_page_does_not_match(tmp_path):
+    url = "https://example.com/joi"
+    test_dir = tmp_path / "t"
+    command = CommandOpen(None, None, OjEnv([("1\n", "2\n"), ("9\n", "4\n"), ("5\n", "6\n")]))
+    command.upm = UnifiedPathManager(str(tmp_path))
+    with http_client.use_transport(StaticTransport({url: SUBTASKS})):
+        assert command.download_samples(url, test_dir) == []
+    # ページが取れなくても、ojのサンプルのまま進める
+    command = CommandOpen(None, None, OjEnv([("1\n", "2\n")]))
+    command.upm = UnifiedPathManager(str(tmp_path))
+    with http_client.use_transport(StaticTransport({})):
+        assert command.download_samples(url, tmp_path / "u") == []
+    assert sorted(p.name for p in test_dir.iterdir()) == [f"sample-{i}.{ext}" for i in (1, 2, 3) for ext in ("in", "out")]
@@ -0,0 +1,36 @@
+import json
+import shutil
+import pytest
+from src.test_support.scrape_corpus import DEFAULT_CORPUS, CorpusCase, discover, run_corpus
+
+@pytest.mark.parametrize("case", discover(), ids=lambda case: case.name)
+def test_corpus_matches_golden(case):
+    # 取り出し方を変えて違いが出たら、意図したものか確かめてから CPH_UPDATE_GOLDEN=1 で書き直す
+    assert case.differences() == []
+
+def test_corpus_covers_each_strategy():
+    strategies = {case.expected()["strategy"] for case in discover()}
+    assert {"atcoder-lang-ja", "codeforces-sample-test", "heading"} <= strategies
+
+def test_runner_reports_changes_and_missing_golden(tmp_path):
+    shutil.copytree(DEFAULT_CORPUS / "aoj", tmp_path / "aoj")
+    golden = tmp_path / "aoj" / "ITP1_1_B.json"
+    data = json.loads(golden.read_text(encoding="utf-8"))
+    data["samples"][1]["output"] = "9\n"
+    golden.write_text(json.dumps(data), encoding="utf-8")
+    (tmp_path / "aoj" / "new.html").write_text("<h3>入力例 1</h3><pre>1</pre><h3>出力例 1</h3><pre>2</pre>", encoding="utf-8")
+    results = run_corpus(tmp_path, update=False)
+    assert results["aoj/ITP1_1_B"] == ["サンプル2のoutput: 期待 '9\\n' / 実際 '27\\n'"]
+    assert results["aoj/new"][0].startswith("ゴールデンファイルがありません")
+
+def test_runner_updates_golden_and_keeps_url(tmp_path, monkeypatch):
+    shutil.copytree(DEFAULT_CORPUS / "aoj", tmp_path / "aoj")
+    golden = tmp_path / "aoj" / "ITP1_1_B.json"
+    data = json.loads(golden.read_text(encoding="utf-8"))
+    data["headings"] = 5
+    golden.write_text(json.dumps(data), encoding="utf-8")
+    monkeypatch.setenv("CPH_UPDATE_GOLDEN", "1")
+    assert run_corpus(tmp_path) == {"aoj/ITP1_1_B": []}
+    updated = json.loads(golden.read_text(encoding="utf-8"))
+    assert updated["headings"] == 2 and updated["url"] == data["url"]
+    assert CorpusCase(tmp_path / "aoj" / "ITP1_1_B.html").differences() == []
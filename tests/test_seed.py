@@ -0,0 +1,33 @@
+import json
+import pytest
+from src.commands.command_test import SEED_ENV, decide_seed
+from src.env_policy import EnvPolicy
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+PRINT_SEED = "import os\ninput()\nprint(os.environ['CPH_SEED'])\n"
+
+def test_decide_seed_uses_option_or_random():
+    assert decide_seed({"seed": "42"}) == 42
+    assert 1 <= decide_seed({}) < 2 ** 31
+    with pytest.raises(ValueError):
+        decide_seed({"seed": "abc"})
+
+def test_seed_is_passed_even_without_env_policy():
+    assert EnvPolicy(enabled=False, extra={SEED_ENV: "7"}).wrap(["a"]) == ["env", "CPH_SEED=7", "a"]
+    assert EnvPolicy(enabled=False).wrap(["a"]) == ["a"]
+    assert EnvPolicy(extra={SEED_ENV: "7"}).wrap(["a"])[2].endswith('LANG=C.UTF-8 CPH_SEED=7 "$@"')
+
+def test_seed_reaches_solution_and_run_artifacts(tmp_path, capsys):
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("x\n", "12345\n")])
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", PRINT_SEED)
+        results = sim.execute("test", "abc300", "a", "python", options={"seed": "12345"})
+        assert [r["verdict"] for r in results] == ["AC"]
+        results = sim.execute("test", "abc300", "a", "python", options={"seed": "999"})
+        assert [r["verdict"] for r in results] == ["WA"]
+    assert "CPH_SEED=999（同じシードで再実行: test --seed 999）" in capsys.readouterr().out
+    runs = sorted(workspace.path(".cph", "runs").iterdir())
+    assert [json.loads((r / "run.json").read_text(encoding="utf-8"))["seed"] for r in runs] == [12345, 999]
@@ -0,0 +1,103 @@
+import asyncio
+import json
+import shutil
+import pytest
+from src import http_client
+from src.commands.case_storage import compress_case
+from src.commands.command_share import MAX_CASE_BYTES, CommandShare
+from src.commands.paste_service import GistService, ZeroXZeroService, create_paste_service, join_files
+from src.commands.run_artifacts import RunArtifacts
+from src.error_hints import CphError
+
+class RecordingTransport:
+    """POSTされた本文を記録し、決まったレスポンスを返す"""
+    def __init__(self, body):
+        self.body = body
+        self.requests = []
+
+    def request(self, method, url, headers=None, body=None, timeout=None):
+        self.requests.append({"method": method, "url": url, "headers": headers, "body": body})
+        return http_client.HttpResponse(201, self.body.encode("utf-8"))
+
+class FakeService:
+    name = "fake"
+
+    def __init__(self):
+        self.uploaded = None
+
+    def upload(self, files, description=""):
+        self.uploaded = (files, description)
+        return "https://paste.example/1"
+
+def write_problem(tmp_path):
+    current = tmp_path / "contest_current"
+    (current / "python").mkdir(parents=True, exist_ok=True)
+    (current / "python" / "main.py").write_text("print(1)\n", encoding="utf-8")
+    (current / "test").mkdir(exist_ok=True)
+    for name, data in (("sample-1.in", "1\n"), ("sample-1.out", "1\n"), ("sample-2.in", "2\n"), ("sample-2.out", "2\n")):
+        (current / "test" / name).write_text(data, encoding="utf-8")
+
+def test_gist_posts_secret_gist():
+    transport = RecordingTransport(json.dumps({"html_url": "https://gist.github.com/x/1"}))
+    with http_client.use_transport(transport):
+        url = GistService(token="t0ken").upload({"main.py": "print(1)\n"}, description="abc300 a (python)")
+    assert url == "https://gist.github.com/x/1"
+    request = transport.requests[0]
+    assert request["method"] == "POST" and request["headers"]["Authorization"] == "token t0ken"
+    body = json.loads(request["body"])
+    assert body["public"] is False and body["files"] == {"main.py": {"content": "print(1)\n"}}
+
+def test_gist_requires_token(monkeypatch):
+    monkeypatch.delenv("MY_TOKEN", raising=False)
+    service = create_paste_service("gist", {"token_env": "MY_TOKEN"})
+    with pytest.raises(CphError) as e:
+        service.upload({"main.py": ""})
+    assert e.value.kind == "share_token_missing" and "MY_TOKEN" in str(e.value)
+
+def test_0x0_uploads_joined_file():
+    transport = RecordingTransport("https://0x0.st/abc.txt\n")
+    with http_client.use_transport(transport):
+        url = ZeroXZeroService().upload({"main.py": "print(1)\n", "sample-2.in": "2\n"})
+    assert url == "https://0x0.st/abc.txt"
+    body = transport.requests[0]["body"].decode("utf-8")
+    assert 'name="file"' in body and "===== main.py =====\nprint(1)\n" in body
+    assert join_files({"main.py": "x"}) == "x"
+    with pytest.raises(ValueError):
+        create_paste_service("pastebin")
+
+def test_share_with_failing_case(tmp_path, capsys):
+    write_problem(tmp_path)
+    results = [
+        {"name": "sample-1.in", "result": (0, "1\n", ""), "verdict": "AC"},
+        {"name": "sample-2.in", "result": (0, "3\n", ""), "verdict": "WA"},
+    ]
+    RunArtifacts().save_results(results, contest_name="abc300", problem_name="a", language_name="python")
+    service = FakeService()
+    url = asyncio.run(CommandShare(options={"failing": True}, service=service).share("abc300", "a", "python"))
+    assert url == "https://paste.example/1"
+    files, description = service.uploaded
+    assert files == {"main.py": "print(1)\n", "sample-2.in": "2\n", "sample-2.out": "2\n", "sample-2.actual": "3\n"}
+    assert description == "abc300 a (python)"
+    assert "[share] fake に 4ファイルを貼りました: https://paste.example/1" in capsys.readouterr().out
+
+def test_share_source_only_without_failures(tmp_path, capsys):
+    write_problem(tmp_path)
+    service = FakeService()
+    asyncio.run(CommandShare(options={"failing": True}, service=service).share("abc300", "a", "python"))
+    assert service.uploaded[0] == {"main.py": "print(1)\n"}
+    assert "失敗したケースはありません" in capsys.readouterr().out
+    asyncio.run(CommandShare(options={"case": "sample-1"}, service=service).share("abc300", "a", "python"))
+    assert service.uploaded[0] == {"main.py": "print(1)\n", "sample-1.in": "1\n", "sample-1.out": "1\n"}
+
+@pytest.mark.skipif(shutil.which("zstd") is None, reason="zstdコマンドが無い")
+def test_share_omits_large_compressed_case(tmp_path):
+    write_problem(tmp_path)
+    test_dir = tmp_path / "contest_current" / "test"
+    (test_dir / "sample-2.in").write_text("0" * (MAX_CASE_BYTES * 4), encoding="utf-8")
+    compress_case(test_dir / "sample-2.in")
+    compress_case(test_dir / "sample-1.in")
+    service = FakeService()
+    asyncio.run(CommandShare(options={"case": "sample-2"}, service=service).share("abc300", "a", "python"))
+    assert service.uploaded[0]["sample-2.in"] == "（64KBを超えるため省略）\n"
+    asyncio.run(CommandShare(options={"case": "sample-1"}, service=service).share("abc300", "a", "python"))
+    assert service.uploaded[0]["sample-1.in"] == "1\n"
@@ -0,0 +1,42 @@
+import sys
+from src.shell import Shell, CommandSpec, TIMEOUT_RETURNCODE
+
+def python(code, **kwargs):
+    return CommandSpec(sys.executable, ["-c", code], **kwargs)
+
+def test_run_captures_streams_with_cwd_env_and_stdin(tmp_path):
+    code = "import os, sys; print(os.getcwd()); print(os.environ['CPH_SHELL_TEST']); print(sys.stdin.read().upper()); print('warn', file=sys.stderr)"
+    result = Shell().run(python(code, cwd=str(tmp_path), env={"CPH_SHELL_TEST": "yes"}, stdin="abc"))
+    assert result.ok
+    assert result.stdout.split() == [str(tmp_path.resolve()), "yes", "ABC"]
+    assert result.stderr == "warn\n"
+
+def test_run_reports_timeout_without_raising():
+    result = Shell().run(python("import time; time.sleep(5)", timeout=0.2))
+    assert result.timed_out and not result.ok
+    assert result.returncode == TIMEOUT_RETURNCODE
+
+def test_env_none_removes_variable(monkeypatch):
+    monkeypatch.setenv("CPH_SHELL_TEST", "set")
+    result = Shell().run(python("import os; print(os.environ.get('CPH_SHELL_TEST', 'unset'))", env={"CPH_SHELL_TEST": None}))
+    assert result.stdout.strip() == "unset"
+
+def test_spawn_streams_lines_to_callbacks():
+    out, err = [], []
+    code = "import sys\nfor i in range(3):\n    print(i, flush=True)\nprint('e', file=sys.stderr)"
+    handle = Shell().spawn(python(code), on_stdout=out.append, on_stderr=err.append)
+    result = handle.wait()
+    assert out == ["0\n", "1\n", "2\n"] and err == ["e\n"]
+    assert result.stdout == "0\n1\n2\n" and result.returncode == 0
+
+def test_spawn_kills_on_timeout():
+    handle = Shell().spawn(python("import time; print('start', flush=True); time.sleep(5)", timeout=0.3))
+    result = handle.wait()
+    assert result.timed_out and result.stdout == "start\n"
+
+def test_spawn_replaces_undecodable_output():
+    # 解答が壊れたバイト列を出しても、読み取りのスレッドが止まらずに残りの出力を読める
+    handle = Shell().spawn(python("import sys; sys.stdout.buffer.write(b'\\xff\\xfe ok\\n'); sys.stdout.flush(); print('next')"))
+    result = handle.wait()
+    assert result.returncode == 0
+    assert result.stdout.endswith(" ok\nnext\n") and "�" in result.stdout
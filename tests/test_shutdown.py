@@ -0,0 +1,99 @@
+import asyncio
+import json
+import os
+import signal
+import threading
+import pytest
+from src import file_lock
+from src.commands.command_watch import STATE_FILE, CommandWatch
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.shutdown import FORCED_EXIT_CODE, GracefulShutdown
+
+def test_steps_run_in_order_and_report_leftovers(capsys):
+    done = []
+    def broken():
+        raise RuntimeError("docker unavailable")
+    shutdown = GracefulShutdown(5)
+    shutdown.add("drain", lambda: done.append("drain"))
+    shutdown.add("containers", broken, check=lambda: ["cph_python_1"])
+    shutdown.add("locks", lambda: done.append("locks"), check=lambda: [])
+    assert shutdown.run() == {"containers": ["cph_python_1"]}
+    assert done == ["drain", "locks"]
+    assert shutdown.completed == ["drain", "containers", "locks"]
+    out = capsys.readouterr().out
+    assert "containersに失敗しました: docker unavailable" in out and "cph_python_1" in out
+    # 2回目は何もしない
+    assert shutdown.run() == {}
+
+def test_watchdog_forces_cleanup_after_grace():
+    exited = threading.Event()
+    codes, done = [], []
+    stuck = threading.Event()
+    def exit(code):
+        codes.append(code)
+        exited.set()
+    shutdown = GracefulShutdown(0.05, exit=exit)
+    shutdown.add("drain", lambda: stuck.wait(5))
+    shutdown.add("persist", lambda: done.append("persist"), force=True)
+    shutdown.add("report", lambda: done.append("report"))
+    runner = threading.Thread(target=shutdown.run)
+    runner.start()
+    assert exited.wait(5)
+    assert codes == [FORCED_EXIT_CODE] and done == ["persist"] and shutdown.forced
+    stuck.set()
+    runner.join(5)
+
+def test_grace_must_be_positive():
+    with pytest.raises(ValueError):
+        GracefulShutdown.from_config({"grace_seconds": 0})
+
+def test_sigterm_requests_shutdown():
+    with GracefulShutdown(5) as shutdown:
+        os.kill(os.getpid(), signal.SIGTERM)
+        assert shutdown.requested.wait(5)
+    assert signal.getsignal(signal.SIGTERM) is not shutdown.request
+
+def test_release_all_unlocks_held_locks(tmp_path):
+    target = tmp_path / "results.json"
+    with file_lock.file_lock(target) as lock_path:
+        assert file_lock.held_locks() == [str(lock_path)]
+        assert file_lock.release_all() == [str(lock_path)]
+        assert file_lock.held_locks() == []
+        # 放したので、別のfdからでも取れる
+        with file_lock.file_lock(target, timeout=0.5):
+            pass
+
+def test_watch_drains_and_persists_on_sigterm(tmp_path, monkeypatch):
+    release = threading.Event()
+    finished = []
+
+    class FakePool:
+        def __init__(self):
+            self.running = ["cph_python_1"]
+        def stop_all(self):
+            stopped, self.running = self.running, []
+            return stopped
+        def running_containers(self):
+            return self.running
+
+    class FakeTest:
+        env = type("Env", (), {"pool": FakePool()})()
+        async def run_test(self, contest_name, problem_name, language_name):
+            await asyncio.get_running_loop().run_in_executor(None, release.wait, 5)
+            finished.append(contest_name)
+
+    async def sigterm_during_run(interval):
+        # 実行中にSIGTERMを受け、実行は終わりまで待ってもらう
+        os.kill(os.getpid(), signal.SIGTERM)
+        threading.Timer(0.1, release.set).start()
+        await asyncio.sleep(0)
+
+    test = FakeTest()
+    watch = CommandWatch(test, sleep=sigterm_during_run)
+    monkeypatch.setattr(watch, "get_config", lambda: {})
+    monkeypatch.setattr(watch, "watched_paths", lambda language_name: [tmp_path])
+    history = asyncio.run(watch.watch("abc300", "a", "python"))
+    assert history == [("start", "done")] and finished == ["abc300"]
+    assert test.env.pool.running == []
+    state = json.loads(UnifiedPathManager().cph_dir(STATE_FILE).read_text())
+    assert state["problem_name"] == "a" and state["history"] == [{"reason": "start", "outcome": "done"}]
@@ -0,0 +1,124 @@
+import http.cookiejar
+import os
+import pytest
+from src import http_client
+from src.environment.execution_manager_test_environment import ExecutionManagerTestEnvironment
+from src.error_hints import CphError
+from src.site_config import SiteConfig, expand_env, site_cookie
+
+class RecordingTransport:
+    def __init__(self):
+        self.requests = []
+
+    def request(self, method, url, headers=None, body=None, timeout=None):
+        self.requests.append({"url": url, "headers": headers})
+        return http_client.HttpResponse(200, b"ok")
+
+SITES = {
+    "codeforces": {"headers": {"Authorization": "Bearer ${CF_TOKEN}", "User-Agent": "team-bot"}, "cookies": {"JSESSIONID": "${CF_SESSION}", "lang": "en"}},
+    "myjudge": {"hosts": ["judge.example.com"], "headers": {"X-Api-Key": "${MISSING_KEY}"}},
+}
+ENV = {"CF_TOKEN": "t0k", "CF_SESSION": "s3s"}
+
+def test_expand_env():
+    assert expand_env("a-${X}-b", {"X": "1"}) == "a-1-b"
+    with pytest.raises(CphError) as e:
+        expand_env("${NOPE}", {})
+    assert e.value.kind == "site_env_missing"
+
+def test_headers_for_matches_host():
+    sites = SiteConfig(SITES, ENV)
+    assert sites.site_for("https://codeforces.com/api/user.status") == "codeforces"
+    assert sites.site_for("https://m1.codeforces.com/x") == "codeforces"
+    assert sites.site_for("https://notcodeforces.com/x") is None
+    assert sites.site_for("https://judge.example.com/p/1") == "myjudge"
+    headers = sites.headers_for("https://codeforces.com/api")
+    assert headers == {"Authorization": "Bearer t0k", "User-Agent": "team-bot", "Cookie": "JSESSIONID=s3s; lang=en"}
+    assert sites.headers_for("https://atcoder.jp/") == {}
+
+def test_unused_site_env_is_not_required():
+    sites = SiteConfig(SITES, ENV)
+    sites.headers_for("https://codeforces.com/")
+    with pytest.raises(CphError):
+        sites.headers_for("https://judge.example.com/")
+
+def test_send_merges_site_headers():
+    transport = RecordingTransport()
+    previous = http_client.configure_sites(SiteConfig(SITES, ENV))
+    try:
+        with http_client.use_transport(transport):
+            http_client.get("https://codeforces.com/api")
+            http_client.post("https://codeforces.com/api", b"", headers={"User-Agent": "explicit"})
+            http_client.get("https://atcoder.jp/")
+    finally:
+        http_client.configure_sites(previous)
+    first, second, other = (r["headers"] for r in transport.requests)
+    assert first["Authorization"] == "Bearer t0k" and first["User-Agent"] == "team-bot"
+    assert second["User-Agent"] == "explicit"
+    assert "Authorization" not in other and other["User-Agent"] == http_client.USER_AGENT
+
+def test_load_sites_yaml(tmp_path):
+    pytest.importorskip("yaml")
+    path = tmp_path / "sites.yaml"
+    path.write_text("codeforces:\n  headers:\n    X-Token: ${T}\n", encoding="utf-8")
+    assert SiteConfig.load(path, {"T": "v"}).headers_for("https://codeforces.com/") == {"X-Token": "v"}
+    assert SiteConfig.load(tmp_path / "none.yaml").sites == {}
+    path.write_text("- a\n", encoding="utf-8")
+    with pytest.raises(ValueError):
+        SiteConfig.load(path)
+
+def test_sites_are_loaded_on_first_request():
+    loads = []
+    def loader():
+        loads.append(True)
+        return SiteConfig(SITES, ENV)
+    previous = http_client.configure_sites(loader)
+    try:
+        assert loads == []
+        with http_client.use_transport(RecordingTransport()):
+            http_client.get("https://codeforces.com/api")
+            http_client.get("https://codeforces.com/api")
+    finally:
+        http_client.configure_sites(previous)
+    assert loads == [True]
+
+def test_broken_sites_yaml_only_fails_when_requesting():
+    def loader():
+        raise CphError("sites.yamlを読むにはPyYAMLが必要です", kind="yaml_missing")
+    previous = http_client.configure_sites(loader)
+    try:
+        with pytest.raises(CphError):
+            http_client.get("https://codeforces.com/api")
+    finally:
+        http_client.configure_sites(previous)
+
+def test_cookie_jar_for_oj_keeps_login_cookies(tmp_path):
+    base = http.cookiejar.LWPCookieJar(str(tmp_path / "oj.jar"))
+    base.set_cookie(site_cookie("atcoder.jp", "REVEL_SESSION", "login"))
+    base.set_cookie(site_cookie("codeforces.com", "JSESSIONID", "old"))
+    base.save(ignore_discard=True, ignore_expires=True)
+    sites = SiteConfig(SITES, ENV)
+    assert sites.has_cookies() and not SiteConfig({"x": {"headers": {"A": "b"}}}).has_cookies()
+    path = sites.write_cookie_jar(tmp_path / "merged.jar", base=tmp_path / "oj.jar")
+    jar = http.cookiejar.LWPCookieJar(path)
+    jar.load(ignore_discard=True, ignore_expires=True)
+    cookies = {(c.domain, c.name): c.value for c in jar}
+    assert cookies == {(".atcoder.jp", "REVEL_SESSION"): "login", (".codeforces.com", "JSESSIONID"): "s3s",
+                       (".codeforces.com", "lang"): "en"}
+    assert os.stat(path).st_mode & 0o777 == 0o600
+
+def test_oj_download_gets_site_cookies(tmp_path):
+    ran = []
+    class RecordingShell:
+        def run(self, spec):
+            ran.append(spec)
+            return type("Result", (), {"returncode": 0, "stdout": "", "stderr": "", "timed_out": False})()
+    env = ExecutionManagerTestEnvironment(None, None, shell=RecordingShell())
+    previous = http_client.configure_sites(SiteConfig(SITES, ENV))
+    try:
+        env.download_testcases("https://codeforces.com/contest/1/problem/A", str(tmp_path / "test"))
+    finally:
+        http_client.configure_sites(previous)
+    args = list(ran[0].args)
+    assert args[0] == "--cookie" and args[2] == "download"
+    assert "s3s" in open(args[1]).read()
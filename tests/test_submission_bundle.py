@@ -0,0 +1,85 @@
+import os
+import pytest
+from src.commands.submission_bundle import SubmissionBundler, BUNDLE_DIR
+
+def test_header_and_footer_use_language_comments():
+    bundler = SubmissionBundler({
+        "author": "alice",
+        "header": "author: {author}\n{contest} {problem}",
+        "footer": {"rust": "submitted by cph"},
+    })
+    assert bundler.bundle("fn main() {}", "rust", contest="abc300", problem="a") == (
+        "// author: alice\n// abc300 a\nfn main() {}\n// submitted by cph\n"
+    )
+    assert bundler.bundle("print(1)\n", "python", contest="abc300", problem="a") == (
+        "# author: alice\n# abc300 a\nprint(1)\n"
+    )
+
+def test_raw_header_is_inserted_verbatim():
+    bundler = SubmissionBundler({"header": {"rust": "#![allow(unused)]"}, "comment": False})
+    assert bundler.bundle("fn main() {}\n", "rust").startswith("#![allow(unused)]\nfn main")
+    assert not bundler.is_active("python")
+
+def test_write_keeps_working_file(tmp_path):
+    src = tmp_path / "main.py"
+    src.write_text("print(1)\n", encoding="utf-8")
+    assert SubmissionBundler({}).write(src, "python") is None
+    path = SubmissionBundler({"author": "bob", "header": "by {author}"}).write(src, "python")
+    assert path == os.path.join(BUNDLE_DIR, "main.py")
+    with open(path, encoding="utf-8") as f:
+        assert f.read() == "# by bob\nprint(1)\n"
+    assert src.read_text(encoding="utf-8") == "print(1)\n"
+
+def test_header_keeps_unknown_names_and_reports_bad_format():
+    bundler = SubmissionBundler({"author": "bob", "header": "{author} {rating}", "comment": False})
+    assert bundler.bundle("x\n", "python", contest="abc300", problem="a") == "bob {rating}\nx\n"
+    for header in ("by {author", "{0}", "{author.name}"):
+        with pytest.raises(ValueError, match="submit.headerの書式が不正です"):
+            SubmissionBundler({"author": "bob", "header": header}).bundle("x\n", "python")
+
+def test_strip_debug_output_and_blocks():
+    bundler = SubmissionBundler({"strip": True})
+    source = (
+        "fn main() {\n"
+        "    eprintln!(\"{}\", x);\n"
+        "    dbg!(y);\n"
+        "    // DEBUG\n"
+        "    check();\n"
+        "    // END DEBUG\n"
+        "    println!(\"ok\");\n"
+        "}\n"
+    )
+    assert bundler.bundle(source, "rust") == "fn main() {\n    println!(\"ok\");\n}\n"
+    assert bundler.bundle("#ifdef LOCAL\nx\n#endif\nprint(1, file=sys.stderr)\nprint(2)\n", "python") == "print(2)\n"
+
+def test_strip_keeps_else_branch_of_local_block():
+    bundler = SubmissionBundler({"strip": True})
+    source = "#ifdef LOCAL\n#include \"debug.h\"\n#else\n#define debug(...)\n#endif\nint main() {}\n"
+    assert bundler.bundle(source, "cpp") == "#define debug(...)\nint main() {}\n"
+
+def test_strip_leaves_unclosed_block_unchanged(capsys):
+    bundler = SubmissionBundler({"strip": True})
+    source = "print(1, file=sys.stderr)\n#ifdef LOCAL\nx\nprint(2)\n"
+    assert bundler.bundle(source, "python") == source
+    assert "2行目から始まる区間が閉じていません" in capsys.readouterr().out
+
+def test_strip_custom_rules():
+    bundler = SubmissionBundler({"strip": {"python": {"patterns": [r"^\s*debug\("], "blocks": [["^# BEGIN LOCAL", "^# END LOCAL"]]}}})
+    source = "debug(a)\n# BEGIN LOCAL\nx = 1\n# END LOCAL\nprint(a)\n"
+    assert bundler.bundle(source, "python") == "print(a)\n"
+
+def test_submit_dry_run_previews_without_submitting(tmp_path, capsys):
+    import asyncio
+    import json
+    from src.commands.command_submit import CommandSubmit
+    config = tmp_path / "contest_current" / "config.json"
+    config.write_text(json.dumps({"submit": {"strip": True}}), encoding="utf-8")
+    main_py = tmp_path / "contest_current" / "python" / "main.py"
+    main_py.write_text("import sys\nprint(1, file=sys.stderr)\nprint(2)\n", encoding="utf-8")
+    cmd = CommandSubmit(None, None, options={"dry-run": True})
+    bundled = asyncio.run(cmd.submit("abc300", "a", "python"))
+    assert bundled == "import sys\nprint(2)\n"
+    out = capsys.readouterr().out
+    assert "-print(1, file=sys.stderr)" in out
+    assert "[dry-run] 提出は行いませんでした" in out
+    assert main_py.read_text(encoding="utf-8") == "import sys\nprint(1, file=sys.stderr)\nprint(2)\n"
@@ -0,0 +1,59 @@
+import asyncio
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment, atcoder_task_url
+from src.commands.submission_queue import SubmissionQueue, is_network_error
+from src.commands.command_status import CommandStatus
+from src.commands.archive_index import ArchiveIndex
+from src.shell import ShellResult
+
+SOLUTION = "a, b = map(int, input().split())\nprint(a + b)\n"
+
+def open_problem(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    return workspace, site
+
+def test_offline_submit_is_queued_and_flushed(tmp_path, capsys):
+    workspace, site = open_problem(tmp_path)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", SOLUTION)
+        site.online = False
+        item = sim.execute("submit", "abc300", "a", "python")
+        assert site.submissions == []
+        assert [i["id"] for i in SubmissionQueue().items()] == [item["id"]]
+        # 保留した時点の内容を送る
+        workspace.write_source("python", "print('changed')\n")
+        status = asyncio.run(CommandStatus().show())
+        assert len(status["queue"]) == 1
+        assert "保留中の提出: 1件" in capsys.readouterr().out
+        # 接続できないうちは送らない
+        assert sim.execute("submit", options={"flush": True}) == []
+        site.online = True
+        sent = sim.execute("submit", options={"flush": True})
+        assert [i["id"] for i in sent] == [item["id"]]
+        assert SubmissionQueue().items() == []
+        assert ArchiveIndex().get("abc300", "a")["status"] == "submitted"
+    assert len(site.submissions) == 1
+    assert site.submissions[0]["url"] == atcoder_task_url("abc300", "a")
+    assert site.submissions[0]["source"] == SOLUTION
+
+def test_network_error_during_submit_is_queued(tmp_path):
+    workspace, site = open_problem(tmp_path)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", SOLUTION)
+        original = site.handle_oj
+        def flaky(argv, cwd=None):
+            # 接続確認は通るが、送信中に接続が切れる
+            if "submit" in argv:
+                return ShellResult(argv, 1, "", "Max retries exceeded with url\n")
+            return original(argv, cwd)
+        site.handle_oj = flaky
+        item = sim.execute("submit", "abc300", "a", "python")
+        assert item["last_error"] == "提出中に接続が切れました"
+        assert len(SubmissionQueue().items()) == 1
+
+def test_is_network_error():
+    assert is_network_error("urllib3: Failed to establish a new connection")
+    assert not is_network_error("[ERROR] wrong language")
@@ -0,0 +1,77 @@
+import asyncio
+from src.command_parser import CommandParser
+from src.commands.archive_index import ArchiveIndex
+from src.commands.submit_checklist import SubmitChecklist, checklist_items, parse_checklist
+from src.testkit import AtCoderMock, ContestSimulation, setup_test_environment
+
+SOLUTION = "a, b = map(int, input().split())\nprint(a + b)\n"
+CHECKLIST = ["オーバーフローは確認した？", "制約を読み直した？"]
+
+def answer(*replies):
+    replies = list(replies)
+    return lambda prompt: replies.pop(0)
+
+def test_checklist_items_merges_config_and_problem():
+    assert parse_checklist("a, b;c;;") == ["a, b", "c"]
+    assert parse_checklist(True) == []
+    assert checklist_items({"checklist": CHECKLIST}, ["境界は？", CHECKLIST[0]]) == CHECKLIST + ["境界は？"]
+    assert checklist_items({"checklist": "1行だけ"}) == ["1行だけ"]
+    assert checklist_items({}) == []
+
+def test_checklist_stops_at_first_no():
+    answers = SubmitChecklist(CHECKLIST + ["境界は？"], ask=answer("y", "n")).run()
+    assert answers == [{"item": CHECKLIST[0], "answer": True}, {"item": CHECKLIST[1], "answer": False}]
+
+def test_checklist_without_tty_answers_no(capsys):
+    def closed_stdin(prompt):
+        raise EOFError
+    assert SubmitChecklist(CHECKLIST, ask=closed_stdin).run() == [{"item": CHECKLIST[0], "answer": False}]
+    assert "チェックリストを省くには --yes" in capsys.readouterr().out
+
+def test_parser_accepts_short_yes():
+    parser = CommandParser()
+    parser.parse(["s", "abc300", "a", "python", "-y"])
+    assert parser.get_options()["yes"] is True
+    assert parser.parsed["command"] == "submit"
+
+def setup(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws", config={"submit": {"checklist": CHECKLIST}})
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n")])
+    return workspace, site
+
+def test_submit_asks_checklist_and_logs_answers(tmp_path, monkeypatch):
+    workspace, site = setup(tmp_path)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", SOLUTION)
+        monkeypatch.setattr("builtins.input", answer("y", "n"))
+        assert sim.execute("submit", "abc300", "a", "python") is None
+        monkeypatch.setattr("builtins.input", answer("y", "y"))
+        sim.execute("submit", "abc300", "a", "python")
+        history = ArchiveIndex().get("abc300", "a")["checklist_history"]
+    assert len(site.submissions) == 1
+    assert [h["passed"] for h in history] == [False, True]
+    assert history[0]["answers"][-1] == {"item": CHECKLIST[1], "answer": False}
+    assert history[1]["language"] == "python"
+
+def test_submit_yes_skips_checklist_with_problem_items(tmp_path, monkeypatch, capsys):
+    from src.commands.command_tag import CommandTag
+    workspace, site = setup(tmp_path)
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        workspace.write_source("python", SOLUTION)
+        asyncio.run(CommandTag({"checklist": "N=1のとき？"}).tag("abc300", "a"))
+        assert "チェックリスト N=1のとき？" in capsys.readouterr().out
+
+        def fail(prompt):
+            raise AssertionError("聞かれないはず")
+        monkeypatch.setattr("builtins.input", fail)
+        sim.execute("submit", "abc300", "a", "python", options={"yes": True})
+        entry = ArchiveIndex().get("abc300", "a")
+    assert len(site.submissions) == 1
+    record = entry["checklist_history"][-1]
+    assert record["skipped"] and [a["item"] for a in record["answers"]] == CHECKLIST + ["N=1のとき？"]
+    assert "3項目を省きました" in capsys.readouterr().out
+    asyncio.run(CommandTag({"checklist": "default"}).tag("abc300", "a"))
+    assert ArchiveIndex().get("abc300", "a")["checklist"] is None
@@ -0,0 +1,40 @@
+import subprocess
+import pytest
+from src.commands.command_submit import CommandSubmit
+from src.commands.toolchain import compile_command
+from src.error_hints import CphError
+
+class LocalEnv:
+    """exec_commandをその場で実行するテスト用の環境"""
+    def __init__(self):
+        self.commands = []
+    def to_container_path(self, host_path):
+        return host_path
+    def exec_command(self, container, cmd, in_file=None):
+        self.commands.append((container, cmd))
+        result = subprocess.run(cmd, capture_output=True, text=True)
+        return result.returncode == 0, result.stdout, result.stderr
+
+def write_bundle(tmp_path, text):
+    path = tmp_path / ".temp" / "submit" / "main.py"
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(text, encoding="utf-8")
+    return path
+
+def test_compile_command_uses_judge_flags():
+    assert compile_command("python", file="a.py") == ["python3", "-m", "py_compile", "a.py"]
+    cmd = compile_command("rust", dir="/w/check", target="/w/target")
+    assert cmd[:3] == ["cargo", "build", "--release"] and "/w/check/Cargo.toml" in cmd
+    assert compile_command("python", site="unknown") is None
+
+def test_compile_check_passes_valid_bundle(tmp_path):
+    env = LocalEnv()
+    cmd = CommandSubmit(None, env)
+    assert cmd.compile_check(write_bundle(tmp_path, "# header\nprint(1)\n"), "python") is True
+    assert env.commands[0][1][:3] == ["python3", "-m", "py_compile"]
+
+def test_compile_check_rejects_broken_bundle(tmp_path):
+    cmd = CommandSubmit(None, LocalEnv())
+    with pytest.raises(CphError) as e:
+        cmd.compile_check(write_bundle(tmp_path, "if True:\n# END DEBUG\n"), "python")
+    assert e.value.kind == "build_failed"
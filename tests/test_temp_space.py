@@ -0,0 +1,61 @@
+import os
+from src import temp_space
+from src.temp_space import TempSpace, session_pid
+
+def dead_pid():
+    """存在しないプロセスのpid"""
+    pid = 999999
+    while temp_space.pid_alive(pid):
+        pid -= 1
+    return pid
+
+def test_session_pid():
+    assert session_pid("cph-1234-1700000000") == 1234
+    assert session_pid("notes") is None
+    assert session_pid("cph-x-1") is None
+
+def test_dir_file_and_cleanup(tmp_path):
+    space = TempSpace(tmp_path, now=100)
+    assert space.path == tmp_path / f"cph-{os.getpid()}-100"
+    assert space.dir("submit").is_dir()
+    space.file("run", "input.in").write_text("1\n", encoding="utf-8")
+    with space.scoped("open-") as d:
+        (d / "kept.in").write_text("x", encoding="utf-8")
+        assert d.parent == space.path
+    assert not d.exists()
+    space.cleanup()
+    assert not space.path.exists()
+
+def test_sweep_removes_sessions_of_finished_processes(tmp_path):
+    leaked = tmp_path / f"cph-{dead_pid()}-1"
+    leaked.mkdir()
+    alive = tmp_path / f"cph-{os.getppid()}-1"
+    alive.mkdir()
+    other = tmp_path / "keep-me"
+    other.mkdir()
+    space = TempSpace(tmp_path)
+    assert space.sweep() == [leaked]
+    assert alive.exists() and other.exists()
+
+def test_current_is_one_session_per_base(tmp_path, monkeypatch):
+    monkeypatch.setattr(temp_space, "_spaces", {})
+    leaked = tmp_path / f"cph-{dead_pid()}-1"
+    leaked.mkdir()
+    space = temp_space.current(tmp_path)
+    assert temp_space.current(tmp_path) is space
+    # 初めて使うときに前回の残りを掃除する
+    assert not leaked.exists()
+
+def test_for_env_uses_container_visible_base(tmp_path, monkeypatch):
+    monkeypatch.setattr(temp_space, "_spaces", {})
+    monkeypatch.setattr(temp_space, "CONTAINER_BASE", str(tmp_path / ".temp" / "sessions"))
+    monkeypatch.setattr(temp_space, "default_base", lambda: tmp_path / "system")
+
+    class Local:
+        uses_containers = False
+
+    class Docker:
+        uses_containers = True
+
+    assert temp_space.for_env(Docker()).base == tmp_path / ".temp" / "sessions"
+    assert temp_space.for_env(Local()).base == tmp_path / "system"
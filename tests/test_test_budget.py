@@ -0,0 +1,94 @@
+import asyncio
+import pytest
+from src.commands import command_test
+from src.commands.command_test import CommandTest, parse_duration, SKIPPED_VERDICT
+from src.commands.run_artifacts import RunArtifacts
+
+class LocalEnv:
+    uses_containers = False
+
+    def to_container_path(self, path):
+        return path
+
+def test_parse_duration():
+    assert parse_duration("90s") == 90
+    assert parse_duration("1.5m") == 90
+    assert parse_duration("45") == 45
+    with pytest.raises(ValueError):
+        parse_duration("soon")
+    with pytest.raises(ValueError):
+        parse_duration("0s")
+
+def test_prioritize_samples_then_recent_failures():
+    cmd = CommandTest(None, LocalEnv())
+    files = ["t/large-1.in", "t/random-1.in", "t/random-2.in", "t/sample-1.in", "t/sample-2.in"]
+    failures = {"random-2.in": 0, "random-1.in": 3, "sample-2.in": 1}
+    assert cmd.prioritize_cases(files, failures) == ["t/sample-2.in", "t/sample-1.in", "t/random-2.in", "t/random-1.in", "t/large-1.in"]
+
+def test_recent_failures_ranks_by_run(tmp_path):
+    meta = {"contest_name": "abc300", "problem_name": "a", "language_name": "python"}
+    def result(name, verdict):
+        return {"name": name, "result": (0, "", ""), "verdict": verdict}
+    RunArtifacts(run_id="20240101-000000-000001", project_root=tmp_path).save_results([result("random-1.in", "WA"), result("random-2.in", "WA")], **meta)
+    RunArtifacts(run_id="20240101-000000-000002", project_root=tmp_path).save_results([result("random-1.in", "AC"), result("random-3.in", "RE"), result("sample-1.in", SKIPPED_VERDICT)], **meta)
+    RunArtifacts(run_id="20240101-000000-000003", project_root=tmp_path).save_results([result("random-9.in", "WA")], contest_name="abc300", problem_name="b", language_name="python")
+    assert RunArtifacts.recent_failures("abc300", "a", "python", project_root=tmp_path) == {"random-3.in": 0, "random-1.in": 1, "random-2.in": 1}
+
+def test_budget_skips_remaining_cases(monkeypatch):
+    clock = [0.0]
+    monkeypatch.setattr(command_test.time, "perf_counter", lambda: clock[0])
+    cmd = CommandTest(None, LocalEnv(), options={"jobs": "1", "budget": "10s"})
+    monkeypatch.setattr(cmd, "build_in_container", lambda *a: (True, "", ""))
+    ran = []
+    def run_single_case(ctl, container, in_file, language_name, source_path, i, total):
+        ran.append(in_file)
+        clock[0] += 4.0
+        return {"name": in_file, "result": (0, "", ""), "expected": "", "time": 4.0, "verdict": "AC"}
+    monkeypatch.setattr(cmd, "run_single_case", run_single_case)
+    files = ["sample-1.in", "sample-2.in", "sample-3.in", "large-1.in"]
+    results = asyncio.run(cmd.run_test_cases("main.py", files, "python", deadline=10.0))
+    assert ran == ["sample-1.in", "sample-2.in", "sample-3.in"]
+    assert [r["verdict"] for r in results] == ["AC", "AC", "AC", SKIPPED_VERDICT]
+    assert not cmd.is_all_ac(results)
+
+def save_run(tmp_path, run_id, cases):
+    results = [{"name": name, "result": (0, "", ""), "verdict": verdict, "time": t} for name, verdict, t in cases]
+    RunArtifacts(run_id=run_id, project_root=tmp_path).save_results(results, contest_name="abc300", problem_name="a", language_name="python")
+
+def test_order_cases_by_history(tmp_path):
+    save_run(tmp_path, "20240101-000000-000001", [("sample-1.in", "AC", 0.1), ("sample-2.in", "WA", 0.9), ("random-1.in", "AC", 1.5)])
+    files = ["sample-1.in", "sample-2.in", "random-1.in", "random-2.in"]
+    def order(**options):
+        return CommandTest(None, LocalEnv(), options=options).order_cases(files, "abc300", "a", "python")
+    assert order() == ["sample-2.in", "sample-1.in", "random-1.in", "random-2.in"]
+    assert order(order="natural") == files
+    assert order(order="slowest-first") == ["random-1.in", "sample-2.in", "sample-1.in", "random-2.in"]
+    assert order(order="natural", budget="10s") == files
+    assert order(budget="10s") == ["sample-2.in", "sample-1.in", "random-1.in", "random-2.in"]
+    with pytest.raises(ValueError):
+        order(order="random")
+
+def test_order_cases_without_history_keeps_order(tmp_path):
+    files = ["b.in", "a.in"]
+    assert CommandTest(None, LocalEnv()).order_cases(files, "abc300", "z", "python") == files
+
+def test_report_time_headroom(tmp_path, capsys):
+    save_run(tmp_path, "20240101-000000-000001", [("sample-1.in", "AC", 0.2), ("random-1.in", "AC", 1.0)])
+    save_run(tmp_path, "20240101-000000-000002", [("sample-1.in", "AC", 0.2), ("random-1.in", "AC", 1.1)])
+    results = [
+        {"name": "sample-1.in", "time": 0.3, "verdict": "AC"},
+        {"name": "random-1.in", "time": 1.5, "verdict": "AC"},
+        {"name": "random-2.in", "time": 0.0, "verdict": SKIPPED_VERDICT},
+    ]
+    warned = CommandTest(None, LocalEnv()).report_time_headroom(results, "abc300", "a", "python")
+    assert [r["name"] for r in warned] == ["random-1.in"]
+    out = capsys.readouterr().out
+    assert "[TL警告] random-1.in: 1.500s（TL 2s の75%）" in out
+    assert "前回 1.100s（random-1.in） / 過去最遅 1.100s（random-1.in） ※前回より遅くなっています" in out
+
+def test_report_time_headroom_can_be_disabled(tmp_path, capsys):
+    (tmp_path / "contest_current").mkdir(exist_ok=True)
+    (tmp_path / "contest_current" / "config.json").write_text('{"test": {"tl_warn_ratio": 0}}', encoding="utf-8")
+    results = [{"name": "random-1.in", "time": 1.9, "verdict": "AC"}]
+    assert CommandTest(None, LocalEnv()).report_time_headroom(results, "abc300", "a", "python") == []
+    assert "[TL警告]" not in capsys.readouterr().out
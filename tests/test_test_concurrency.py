@@ -0,0 +1,53 @@
+from src.commands.command_test import CommandTest, auto_jobs, TIMING_PIN_CORE
+
+def test_auto_jobs():
+    assert auto_jobs(10, cpu_count=4) == 4
+    assert auto_jobs(2, cpu_count=8) == 2
+    assert auto_jobs(0, cpu_count=8) == 1
+
+def test_decide_jobs_respects_option():
+    cmd = CommandTest(None, None, options={"jobs": "3"})
+    assert cmd.decide_jobs(10) == 3
+    assert cmd.decide_jobs(2) == 2
+
+def test_timing_candidates():
+    cmd = CommandTest(None, None)
+    results = [
+        {"name": "fast.in", "verdict": "AC", "time": 0.1},
+        {"name": "slow.in", "verdict": "AC", "time": 1.5},
+        {"name": "crash.in", "verdict": "RE", "time": 1.9},
+    ]
+    assert [r["name"] for r in cmd.timing_candidates(results, 2.0)] == ["slow.in"]
+
+class FakeHandler:
+    def run_command(self, source_path):
+        return ["python3", source_path]
+
+class FakeEnv:
+    def __init__(self, taskset=True):
+        self.taskset = taskset
+        self.commands = []
+    def to_container_path(self, path):
+        return str(path)
+    def exec_command(self, container, cmd, in_file=None):
+        self.commands.append(cmd)
+        if cmd[0] == "taskset" and not self.taskset:
+            return False, "", "taskset: not found"
+        return True, "ok", ""
+
+def test_remeasure_timing_candidates_pins_core(monkeypatch):
+    env = FakeEnv()
+    cmd = CommandTest(None, env, options={"accurate-timing": True})
+    monkeypatch.setattr(cmd, "get_time_limit", lambda: 2.0)
+    results = [{"name": "slow.in", "in_file": "slow.in", "verdict": "AC", "time": 1.5}]
+    cmd.remeasure_timing_candidates(results, FakeHandler(), "cont", "main.py")
+    assert env.commands == [["taskset", "-c", str(TIMING_PIN_CORE), "python3", "main.py"]]
+    assert results[0]["timing"] == "serial"
+
+def test_remeasure_timing_candidates_without_taskset(monkeypatch):
+    env = FakeEnv(taskset=False)
+    cmd = CommandTest(None, env, options={"accurate-timing": True})
+    monkeypatch.setattr(cmd, "get_time_limit", lambda: 2.0)
+    results = [{"name": "slow.in", "in_file": "slow.in", "verdict": "AC", "time": 1.5}]
+    cmd.remeasure_timing_candidates(results, FakeHandler(), "cont", "main.py")
+    assert env.commands[-1] == ["python3", "main.py"]
@@ -115,4 +115,38 @@ def test_rust_handler_run_fail(tmp_path):
     make_dummy_file(in_file, "input")
     result = handler.run(manager, "name", str(in_file), str(src_dir), host_in_file=str(in_file))
     assert not result[0]
-    assert result[2] == "err"
\ No newline at end of file
+    assert result[2] == "err"
+def test_rust_handler_debug_preset(tmp_path):
+    handler = RustTestHandler()
+    manager = MagicMock()
+    manager.run_and_measure.return_value = MagicMock(returncode=0, stdout="", stderr="")
+    handler.build(manager, "name", str(tmp_path), preset="debug")
+    assert manager.run_and_measure.call_args[0][1] == ["cargo", "build"]
+    assert handler.run_command(str(tmp_path), preset="debug")[0].endswith("target/debug/rust")
+    assert "debug" not in PythonTestHandler.BUILD_PRESETS
+
+def write_config(data):
+    import json
+    path = Path("contest_current") / "config.json"
+    path.parent.mkdir(parents=True, exist_ok=True)
+    path.write_text(json.dumps(data), encoding="utf-8")
+
+def test_python_runs_isolated_by_default(tmp_path, monkeypatch):
+    assert PythonTestHandler().run_command("main.py") == ["env", "PYTHONPATH=", "python3", "-I", "-B", "main.py"]
+    assert PypyTestHandler().run_command("main.py") == ["env", "PYTHONPATH=", "pypy3", "-E", "-s", "-B", "main.py"]
+    # 解答の隣のrandom.pyやPYTHONPATHのモジュールが標準ライブラリを隠さない
+    work = tmp_path / "work"
+    work.mkdir()
+    (work / "random.py").write_text("raise SystemExit('shadowed')\n", encoding="utf-8")
+    (work / "main.py").write_text("import random\nprint(random.randint(1, 1))\n", encoding="utf-8")
+    monkeypatch.setenv("PYTHONPATH", str(work))
+    cmd = PythonTestHandler().run_command(str(work / "main.py"))
+    result = subprocess.run(cmd, capture_output=True, text=True, cwd=str(work))
+    assert result.returncode == 0 and result.stdout == "1\n"
+    assert not (work / "__pycache__").exists()
+
+def test_python_isolation_follows_language_profile():
+    write_config({"languages": {"python": {"isolate": False}}})
+    assert PythonTestHandler().run_command("main.py") == ["python3", "main.py"]
+    write_config({"languages": {"python": {"flags": ["-B"], "env": {"PYTHONHASHSEED": "0"}}}})
+    assert PythonTestHandler().run_command("main.py") == ["env", "PYTHONPATH=", "PYTHONHASHSEED=0", "python3", "-B", "main.py"]
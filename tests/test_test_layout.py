@@ -0,0 +1,33 @@
+import pytest
+from src.commands import test_layout
+
+FILES = ["t/sample-1.in", "t/sample-2.in", "t/stress-1.in", "t/random-3.in", "t/edge-1.in", "t/notes.in"]
+
+def test_cphignore_and_config_ignore(tmp_path):
+    (tmp_path / ".cphignore").write_text("# メモ\nnotes*\n\n", encoding="utf-8")
+    layout = test_layout.TestLayout(tmp_path, {"ignore": ["edge-*"]})
+    assert layout.discover(FILES) == ["t/sample-1.in", "t/sample-2.in", "t/stress-1.in", "t/random-3.in"]
+
+def test_named_sets(tmp_path):
+    layout = test_layout.TestLayout(tmp_path, {"sets": {"edge": ["edge-*", "sample-1"]}})
+    assert layout.select(FILES, "samples") == ["t/sample-1.in", "t/sample-2.in"]
+    assert layout.select(FILES, "stress") == ["t/stress-1.in", "t/random-3.in"]
+    assert layout.select(FILES, "edge") == ["t/sample-1.in", "t/edge-1.in"]
+    assert layout.select(FILES, "all") == FILES
+    assert layout.select(FILES) == FILES
+    with pytest.raises(ValueError):
+        layout.select(FILES, "unknown")
+
+def test_group_sets_from_directories(tmp_path):
+    (tmp_path / "group_1").mkdir()
+    (tmp_path / "group_2").mkdir()
+    files = ["t/sample-1.in", "t/group_1/sample-1-1.in", "t/group_2/sample-2-1.in"]
+    layout = test_layout.TestLayout(tmp_path, {})
+    assert layout.groups == ["group_1", "group_2"]
+    assert layout.select(files, "group_1") == ["t/group_1/sample-1-1.in"]
+    assert layout.select(files, "samples") == files
+    assert test_layout.group_of("t/group_2/sample-2-1.in") == "group_2"
+    assert test_layout.group_of("t/sample-1.in") is None
+    with pytest.raises(ValueError) as e:
+        layout.select(files, "group_3")
+    assert "group_1" in str(e.value)
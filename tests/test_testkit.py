@@ -0,0 +1,55 @@
+import pytest
+from src.testkit import AtCoderMock, ContestSimulation, TestDockerRunner, setup_test_environment, atcoder_task_url
+from src.commands.archive_index import ArchiveIndex
+from src.execution_client.container.client import ContainerClient
+from src.execution_client.container.image_manager import ContainerImageManager
+
+SOLUTION = "a, b = map(int, input().split())\nprint(a + b)\n"
+
+def make_site():
+    site = AtCoderMock()
+    site.add_problem("abc300", "a", [("1 2\n", "3\n"), ("10 20\n", "30\n")])
+    return site
+
+def test_contest_workflow_open_test_submit(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    site = make_site()
+    with ContestSimulation(workspace, site) as sim:
+        sim.execute("open", "abc300", "a", "python")
+        assert workspace.path("contest_current", "test", "sample-2.out").read_text(encoding="utf-8") == "30\n"
+        assert workspace.read_json("contest_current", "system_info.json")["problem_name"] == "a"
+        workspace.write_source("python", SOLUTION)
+        results = sim.execute("test", "abc300", "a", "python")
+        assert [r["verdict"] for r in results] == ["AC", "AC"]
+        sim.execute("submit", "abc300", "a", "python")
+        assert ArchiveIndex().get("abc300", "a")["status"] == "submitted"
+    assert len(site.submissions) == 1
+    submission = site.submissions[0]
+    assert submission["url"] == atcoder_task_url("abc300", "a")
+    assert submission["language"] == "5082" and submission["source"] == SOLUTION
+
+def test_open_unknown_problem_fails(tmp_path):
+    workspace = setup_test_environment(tmp_path / "ws")
+    with ContestSimulation(workspace, make_site()) as sim:
+        with pytest.raises(RuntimeError, match="oj download"):
+            sim.execute("open", "abc300", "b", "python")
+
+def test_docker_runner_runs_containers_on_host(tmp_path):
+    (tmp_path / "main.py").write_text("print(int(input()) * 2)\n", encoding="utf-8")
+    docker = TestDockerRunner()
+    images = ContainerImageManager(shell=docker)
+    assert not images.image_exists("python")
+    assert images.pull_image("python") and images.image_exists("python")
+    client = ContainerClient(shell=docker)
+    assert client.ping()
+    assert client.run_container("cph_python_1", "python", volumes={str(tmp_path): "/workspace"}) == "cph_python_1-id"
+    assert client.is_container_running("cph_python_1")
+    # マウントしたパスはホストのパスに直して実行する
+    result = client.exec_in_container("cph_python_1", ["python3", "/workspace/main.py"], stdin="21\n")
+    assert result.returncode == 0 and result.stdout == "42\n"
+    assert client.copy_from_container("cph_python_1", "/workspace/main.py", str(tmp_path / "copy.py"))
+    assert (tmp_path / "copy.py").read_text(encoding="utf-8").startswith("print")
+    assert client.stop_container("cph_python_1") and not client.is_container_running("cph_python_1")
+    assert client.exec_in_container("cph_python_1", ["true"]).returncode != 0
+    assert client.remove_container("cph_python_1") and docker.containers == {}
+    assert ["docker", "pull", "python"] in docker.commands
@@ -0,0 +1,50 @@
+from src.commands.text_width import char_width, display_width, truncate, pad, wrap, terminal_width
+from src.commands.test_result_formatter import ResultFormatter
+
+def test_display_width_counts_wide_and_zero_width():
+    assert display_width("abc") == 3
+    assert display_width("あいう") == 6
+    assert display_width("😀") == 2
+    assert display_width("é") == 1  # 結合文字
+    assert display_width("\033[32mAC\033[0m") == 2
+    assert char_width("‍") == 0
+
+def test_truncate_end_and_middle():
+    assert truncate("short", 10) == "short"
+    assert truncate("abcdefghij", 6) == "abcde…"
+    assert truncate("abcdefghij", 6, position="middle") == "abc…ij"
+    # 全角を半分で切らない
+    t = truncate("あいうえお", 6)
+    assert t == "あい…"
+    assert display_width(t) <= 6
+
+def test_pad_and_wrap_by_display_width():
+    assert pad("あ", 4) == "あ  "
+    assert wrap("あいうえ", 4) == ["あい", "うえ"]
+    assert wrap("abc", 0) == ["abc"]
+    assert wrap("", 5) == [""]
+
+def test_terminal_width_honors_columns(monkeypatch):
+    monkeypatch.setenv("COLUMNS", "42")
+    assert terminal_width() == 42
+
+def make_result(name, stdout, expected):
+    return {"name": name, "result": (0, stdout, ""), "expected": expected, "time": 0.5}
+
+def test_formatter_aligns_cjk_columns():
+    out = ResultFormatter(make_result("c", "はい\n", "はい\n"), width=80).format()
+    assert "はい     | はい" in out
+
+def test_formatter_truncates_long_name_in_narrow_terminal():
+    name = "very-long-case-name-" * 5 + "end.in"
+    out = ResultFormatter(make_result(name, "1\n", "1\n"), width=40).format()
+    header = out.splitlines()[0]
+    assert "…" in header and header.split()[0].endswith("end.in")
+    assert display_width(header) <= 40
+
+def test_formatter_wraps_table_within_width():
+    long = "x" * 50
+    out = ResultFormatter(make_result("c", long + "\n", long + "\n"), width=30).format()
+    table = out.splitlines()[-4:]
+    assert all(display_width(line) <= 30 for line in table)
+    assert "".join(line.split(" | ")[0].strip() for line in out.splitlines() if line.startswith("x")) == long
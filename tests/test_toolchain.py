@@ -0,0 +1,70 @@
+import asyncio
+from src.commands.toolchain import parse_version, version_matches, image_for, pinned_images, get_pins
+from src.commands.command_doctor import CommandDoctor
+from src.commands.command_prepare import CommandPrepare
+from src.config_json_manager import ConfigJsonManager
+from src.execution_client.container.image_manager import ContainerImageManager
+
+def test_parse_version():
+    assert parse_version("rust", "rustc 1.70.0 (90c541806 2023-05-31)") == "1.70.0"
+    assert parse_version("python", "Python 3.11.4\n") == "3.11.4"
+    assert parse_version("pypy", "Python 3.10.12 (af44d0b8114c, Jun 15 2023)\n[PyPy 7.3.12 with GCC 10.2.1]") == "3.10-v7.3.12"
+    assert parse_version("rust", "") is None
+
+def test_version_matches():
+    assert version_matches("1.70.0", "1.70.0")
+    assert version_matches("3.11", "3.11.4")
+    assert not version_matches("1.70.0", "1.75.0")
+    assert not version_matches("1.70.0", None)
+
+def test_image_for():
+    assert image_for("rust", "1.70.0") == "rust:1.70.0"
+    assert image_for("pypy", "3.10-v7.3.12") == "pypy:3.10-7.3.12"
+    assert pinned_images({"python": "3.11.4"}) == {"python": "python:3.11.4"}
+
+def test_get_pins_prefers_config(tmp_path):
+    config = ConfigJsonManager(str(tmp_path / "config.json"))
+    config.set_toolchain("atcoder", {"rust": "1.75.0"})
+    pins = get_pins(config)
+    assert pins["rust"] == "1.75.0"
+    assert pins["python"] == "3.11.4"
+
+def test_image_manager_uses_override():
+    manager = ContainerImageManager(image_overrides={"rust": "rust:1.70.0"})
+    assert manager.get_image_name("rust") == "rust:1.70.0"
+
+def test_doctor_reports_mismatch(tmp_path, monkeypatch, capsys):
+    from src.commands import command_doctor
+    doctor = CommandDoctor("local")
+    monkeypatch.setattr(doctor, "get_config", lambda: ConfigJsonManager(str(tmp_path / "config.json")))
+    monkeypatch.setattr(command_doctor, "installed_version", lambda lang, image=None, cwd=None: "1.75.0")
+    results = asyncio.run(doctor.doctor("rust"))
+    assert results[0]["ok"] is False
+    assert "NG" in capsys.readouterr().out
+
+class DummyImageManager:
+    def __init__(self):
+        self.pulled = []
+    def pull_image(self, image):
+        self.pulled.append(image)
+        return True
+
+def test_prepare_pins_and_pulls_images(tmp_path, monkeypatch):
+    config = ConfigJsonManager(str(tmp_path / "config.json"))
+    images = DummyImageManager()
+    prepare = CommandPrepare("docker", options={"pin": True}, image_manager=images)
+    monkeypatch.setattr(prepare, "get_config", lambda: config)
+    asyncio.run(prepare.prepare("rust"))
+    assert images.pulled == ["rust:1.70.0"]
+    assert ConfigJsonManager(str(tmp_path / "config.json")).get_toolchain("atcoder")["rust"] == "1.70.0"
+
+def test_prepare_without_pin_only_suggests_versions(tmp_path, monkeypatch, capsys):
+    config = ConfigJsonManager(str(tmp_path / "config.json"))
+    images = DummyImageManager()
+    prepare = CommandPrepare("docker", image_manager=images)
+    monkeypatch.setattr(prepare, "get_config", lambda: config)
+    asyncio.run(prepare.prepare("rust"))
+    assert images.pulled == ["rust:1.70.0"]
+    # testのイメージ（image_overrides）を変えないよう、config.jsonには書かない
+    assert ConfigJsonManager(str(tmp_path / "config.json")).get_toolchain("atcoder") == {}
+    assert "prepare --pin" in capsys.readouterr().out
@@ -0,0 +1,78 @@
+import asyncio
+import shutil
+import pytest
+from src.commands.command_test import CommandTest
+from src.environment.execution_manager_test_environment import ExecutionManagerTestEnvironment
+from src.execution_client.execution_manager import ExecutionManager
+from src.execution_client.local.client import LocalAsyncClient
+from src.output_limit import OutputLimit
+
+class FakeHandler:
+    BUILD_PRESETS = ("release", "debug")
+    def __init__(self):
+        self.built = []
+    def build(self, ctl, container, source_path, preset="release"):
+        self.built.append(preset)
+        return True, "", ""
+    def run_command(self, source_path, preset="release"):
+        return [f"target/{preset}/rust"]
+
+class FakeEnv:
+    def __init__(self, outputs):
+        self.outputs = outputs
+    def to_container_path(self, path):
+        return str(path)
+    def exec_command(self, container, cmd, in_file=None):
+        return self.outputs[in_file.rsplit("/", 1)[-1]]
+
+def make_result(name, verdict, stdout, code=0):
+    return {"name": name, "in_file": name, "verdict": verdict, "result": (code, stdout, "")}
+
+def test_detect_ub_flags_differing_outputs(capsys):
+    env = FakeEnv({
+        "overflow.in": (False, "", "thread 'main' panicked at 'attempt to add with overflow'"),
+        "wrong.in": (True, "1\n", ""),
+    })
+    cmd = CommandTest(None, env)
+    handler = FakeHandler()
+    results = [
+        make_result("ok.in", "AC", "3\n"),
+        make_result("overflow.in", "WA", "-2147483648\n"),
+        make_result("wrong.in", "WA", "1\n"),
+    ]
+    suspects = cmd.detect_ub(results, "rust", None, handler, "cont", "src")
+    assert [r["name"] for r in suspects] == ["overflow.in"]
+    assert handler.built == ["debug"]
+    assert "overflow" in capsys.readouterr().out
+
+def test_detect_ub_skips_unsupported_language(capsys):
+    class PythonHandler(FakeHandler):
+        BUILD_PRESETS = ("release",)
+    cmd = CommandTest(None, FakeEnv({}))
+    results = [make_result("a.in", "WA", "1\n")]
+    assert cmd.detect_ub(results, "python", None, PythonHandler(), "cont", "src") == []
+    assert "スキップ" in capsys.readouterr().out
+
+CARGO_TOML = '[package]\nname = "rust"\nversion = "0.1.0"\nedition = "2021"\n\n[dependencies]\n'
+# releaseでは桁あふれして負の値を出し、debugではpanicする
+OVERFLOW = "fn main() {\n    let mut s = String::new();\n    std::io::stdin().read_line(&mut s).unwrap();\n    let n: i32 = s.trim().parse().unwrap();\n    println!(\"{}\", n + 1);\n}\n"
+
+@pytest.mark.skipif(shutil.which("cargo") is None, reason="cargoが必要")
+def test_detect_ub_builds_rust_debug_locally(tmp_path, capsys):
+    cargo_dir = tmp_path / "rust"
+    (cargo_dir / "src").mkdir(parents=True)
+    (cargo_dir / "Cargo.toml").write_text(CARGO_TOML)
+    (cargo_dir / "src" / "main.rs").write_text(OVERFLOW)
+    in_file = tmp_path / "max.in"
+    in_file.write_text("2147483647\n")
+    (tmp_path / "max.out").write_text("2147483648\n")
+    env = ExecutionManagerTestEnvironment(None, ExecutionManager(LocalAsyncClient()))
+    env.solution_env = {}
+    env.output_limit = OutputLimit.from_config({})
+    cmd = CommandTest(None, env, options={"ub-check": True, "jobs": "1"})
+    # 通常のビルドと同じく、debugビルドもローカル実行ではExecutionManagerで行う
+    results = asyncio.run(cmd.run_test_cases(str(cargo_dir), [str(in_file)], "rust"))
+    assert results[0]["result"][1].strip() == "-2147483648"
+    suspect = results[0]["ub_suspect"]
+    assert not suspect["debug_ok"] and "overflow" in suspect["debug_stderr"]
+    assert "debugとreleaseで結果が異なります" in capsys.readouterr().out
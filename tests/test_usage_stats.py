@@ -0,0 +1,45 @@
+import asyncio
+import json
+from src.usage_stats import UsageStats
+from src.commands.command_stats import CommandStats
+
+def test_disabled_stats_write_nothing(tmp_path):
+    stats = UsageStats(path=tmp_path / "stats.json", enabled=False)
+    stats.record_command("test", 1.0)
+    stats.record_cache("results", True)
+    assert not (tmp_path / "stats.json").exists()
+
+def test_enabled_by_config_or_env(tmp_path, monkeypatch):
+    monkeypatch.delenv("CPH_STATS", raising=False)
+    assert UsageStats(path=tmp_path / "stats.json").enabled is False
+    config = tmp_path / "contest_current" / "config.json"
+    config.write_text(json.dumps({"stats": {"enabled": True}}), encoding="utf-8")
+    assert UsageStats(path=tmp_path / "stats.json").enabled is True
+    monkeypatch.setenv("CPH_STATS", "0")
+    assert UsageStats(path=tmp_path / "stats.json").enabled is False
+
+def test_summary_averages_and_hit_rates(tmp_path, capsys):
+    stats = UsageStats(path=tmp_path / "stats.json", enabled=True)
+    stats.record_command("test", 2.0)
+    stats.record_command("test", 4.0, ok=False)
+    stats.record_command("submit", 1.0)
+    stats.record_tests([{"time": 0.1}, {"time": 0.3}, {"time": None}])
+    for hit in (True, True, True, False):
+        stats.record_cache("results", hit)
+    summary = stats.summary()
+    assert list(summary["commands"]) == ["test", "submit"]
+    assert summary["commands"]["test"]["average"] == 3.0 and summary["commands"]["test"]["failed"] == 1
+    assert summary["tests"]["cases"] == 2 and abs(summary["tests"]["average_case"] - 0.2) < 1e-9
+    assert summary["caches"]["results"]["rate"] == 0.75
+    asyncio.run(CommandStats({"tool": True}, stats=stats).show())
+    out = capsys.readouterr().out
+    assert "test" in out and "75.0%" in out
+
+def test_stats_requires_tool_flag(tmp_path):
+    stats = UsageStats(path=tmp_path / "stats.json", enabled=True)
+    try:
+        asyncio.run(CommandStats({}, stats=stats).show())
+    except ValueError as e:
+        assert "--tool" in str(e)
+    else:
+        assert False
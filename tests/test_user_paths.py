@@ -0,0 +1,64 @@
+import json
+from pathlib import Path
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.path_manager.user_paths import UserPaths, migrate
+from src.site_config import SiteConfig
+from src.usage_stats import merge_stats
+
+def test_xdg_directories_and_defaults(tmp_path):
+    paths = UserPaths(environ={"XDG_CACHE_HOME": str(tmp_path / "c"), "XDG_STATE_HOME": "relative/ignored"}, home=str(tmp_path / "home"))
+    assert paths.cache_dir("verify") == tmp_path / "c" / "cph" / "verify"
+    # 相対パスの値は使わず、ホームの既定の場所にする
+    assert paths.state_dir("stats.json") == tmp_path / "home" / ".local" / "state" / "cph" / "stats.json"
+    assert paths.config_dir() == tmp_path / "home" / ".config" / "cph"
+
+def test_migrate_moves_once_and_keeps_existing(tmp_path, capsys):
+    old, new = tmp_path / "project" / ".cph" / "stats.json", tmp_path / "state" / "stats.json"
+    old.parent.mkdir(parents=True)
+    old.write_text("old", encoding="utf-8")
+    assert migrate(old, new) == new
+    assert new.read_text(encoding="utf-8") == "old" and not old.exists()
+    # お知らせはコマンドの出力に混ぜない
+    captured = capsys.readouterr()
+    assert "へ移しました" in captured.err and captured.out == ""
+    # 別のプロジェクトに残っていたものは、新しい置き場を上書きせず、使われていないことを知らせる
+    old.write_text("other", encoding="utf-8")
+    migrate(old, new)
+    assert new.read_text(encoding="utf-8") == "old" and old.exists()
+    assert "は使われていません" in capsys.readouterr().err
+
+def test_shared_stats_are_merged_with_existing(tmp_path, capsys):
+    a, b = UnifiedPathManager(project_root=tmp_path / "a"), UnifiedPathManager(project_root=tmp_path / "b")
+    for upm, count in ((a, 2), (b, 3)):
+        upm.cph_dir().mkdir(parents=True)
+        stats = {"since": f"2024-0{count}-01T00:00:00", "commands": {"test": {"count": count, "failed": 1, "seconds": 1.5}},
+                 "tests": {"runs": count, "cases": 10, "seconds": 2.0}, "caches": {"results": {"hit": 1, "miss": count}}}
+        upm.cph_dir("stats.json").write_text(json.dumps(stats), encoding="utf-8")
+    a.shared_state("stats.json", merge_stats)
+    path = b.shared_state("stats.json", merge_stats)
+    merged = json.loads(path.read_text(encoding="utf-8"))
+    assert merged["since"] == "2024-02-01T00:00:00"
+    assert merged["commands"]["test"] == {"count": 5, "failed": 2, "seconds": 3.0}
+    assert merged["tests"] == {"runs": 5, "cases": 20, "seconds": 4.0}
+    assert merged["caches"]["results"] == {"hit": 2, "miss": 5}
+    assert not b.cph_dir("stats.json").exists()
+    assert "にまとめました" in capsys.readouterr().err
+
+def test_shared_state_is_shared_between_projects(tmp_path):
+    a, b = UnifiedPathManager(project_root=tmp_path / "a"), UnifiedPathManager(project_root=tmp_path / "b")
+    a.cph_dir().mkdir(parents=True)
+    a.cph_dir("images.json").write_text("{}", encoding="utf-8")
+    assert a.shared_state("images.json") == b.shared_state("images.json")
+    assert a.shared_state("images.json").exists()
+    assert not a.cph_dir("images.json").exists()
+
+def test_sites_yaml_layers_user_and_project(tmp_path):
+    upm = UnifiedPathManager(project_root=tmp_path)
+    user, project = upm.sites_yamls()
+    user.parent.mkdir(parents=True)
+    user.write_text("atcoder:\n  cookies: {REVEL_SESSION: user}\ncodeforces:\n  cookies: {X-User: user}\n", encoding="utf-8")
+    project.write_text("atcoder:\n  cookies: {REVEL_SESSION: project}\n", encoding="utf-8")
+    config = SiteConfig.load_layered([user, project])
+    assert config.headers_for("https://atcoder.jp/")["Cookie"] == "REVEL_SESSION=project"
+    assert config.headers_for("https://codeforces.com/")["Cookie"] == "X-User=user"
+    assert Path(str(user)).parts[-2:] == ("cph", "sites.yaml")
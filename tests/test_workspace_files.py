@@ -0,0 +1,53 @@
+import asyncio
+import json
+from src.commands.command_init import CommandInit
+from src.commands.workspace_files import BLOCK_BEGIN, BLOCK_END, WorkspaceFiles, editorconfig_sections, gitignore_lines, merge_block
+from src.path_manager.unified_path_manager import UnifiedPathManager
+
+def test_gitignore_lines_merge_language_fragments():
+    lines = gitignore_lines(["python", "pypy", "rust"], {"gitignore": {"rust": ["*.exe"], "common": ["*.log"]}})
+    assert lines.count("__pycache__/") == 1
+    assert "target/" in lines and "*.exe" in lines and "*.log" in lines
+    assert ".cph/runs/" in lines and "sites.yaml" in lines
+    assert {".cph/diagnostics.json", ".cph/cases/", ".cph/watch.json"} <= set(lines)
+    assert ".cph/archive.json" not in lines
+    assert "target/" not in gitignore_lines(["python"])
+
+def test_editorconfig_keeps_test_cases_untouched_and_allows_overrides():
+    sections = editorconfig_sections(["rust"], {"editorconfig": {"*.rs": {"indent_size": 2}, "Makefile": {"indent_style": "tab"}}})
+    assert sections["*.{in,out}"]["trim_trailing_whitespace"] == "false"
+    assert sections["*.rs"] == {"indent_style": "space", "indent_size": "2"}
+    assert sections["Makefile"] == {"indent_style": "tab"}
+    assert "*.py" not in sections
+
+def test_merge_block_is_idempotent_and_keeps_user_lines():
+    once = merge_block("my-notes/\n", ["target/"])
+    assert once.startswith("my-notes/\n\n" + BLOCK_BEGIN)
+    assert merge_block(once, ["target/"]) == once
+    edited = once + "secret.txt\n"
+    again = merge_block(edited, ["__pycache__/"])
+    assert "target/" not in again and "__pycache__/" in again
+    assert again.endswith(BLOCK_END + "\nsecret.txt\n")
+
+def test_generate_writes_only_when_changed(tmp_path):
+    files = WorkspaceFiles(tmp_path, ["python"])
+    assert files.generate() == [".gitignore", ".editorconfig"]
+    assert (tmp_path / ".editorconfig").read_text(encoding="utf-8").startswith("root = true\n")
+    assert files.generate() == []
+    assert WorkspaceFiles(tmp_path, ["python", "rust"]).generate() == [".gitignore", ".editorconfig"]
+    assert "target/" in (tmp_path / ".gitignore").read_text(encoding="utf-8")
+    assert (tmp_path / ".editorconfig").read_text(encoding="utf-8").count("root = true") == 1
+
+def test_init_uses_template_and_config_languages(tmp_path, capsys):
+    (tmp_path / "contest_template" / "rust").mkdir(parents=True)
+    config = tmp_path / "contest_current" / "config.json"
+    config.parent.mkdir(parents=True, exist_ok=True)
+    config.write_text(json.dumps({"languages": {"nim": {}}, "workspace": {"gitignore": {"nim": ["nimcache/"]}}}), encoding="utf-8")
+    cmd = CommandInit()
+    cmd.upm = UnifiedPathManager(str(tmp_path))
+    assert cmd.languages() == ["rust", "nim"]
+    asyncio.run(cmd.init())
+    gitignore = (tmp_path / ".gitignore").read_text(encoding="utf-8")
+    assert "target/" in gitignore and "nimcache/" in gitignore
+    asyncio.run(cmd.init())
+    assert "最新です" in capsys.readouterr().out
@@ -0,0 +1,99 @@
+import asyncio
+import json
+import pytest
+from src import workspace_manifest
+from src.commands.command_init import CommandInit
+from src.error_hints import CphError
+from src.path_manager.unified_path_manager import UnifiedPathManager
+from src.workspace_manifest import (CPH_VERSION, LAYOUT_VERSION, MIGRATIONS, Migration, WorkspaceManifest,
+                                    WorkspaceManifestStore, ensure_current)
+
+def test_manifest_round_trips_through_toml():
+    manifest = WorkspaceManifest(3, created_by="0.0.9", site="codeforces",
+                                 options={"stocks": 'archive/{contest}/"{problem}"', "jobs": 4, "strict": True})
+    store = WorkspaceManifestStore()
+    store.save(manifest)
+    assert store.load() == manifest
+    assert 'stocks = "archive/{contest}/\\"{problem}\\""' in store.path.read_text()
+
+@pytest.mark.parametrize("text", [
+    'layout_version = "1"\n',
+    'layout_version = true\n',
+    'site = "atcoder"\n',
+    'layout_version = 1\ncolor = "red"\n',
+    'layout_version = 1\n[options]\npaths = ["a"]\n',
+    'layout_version = \n',
+])
+def test_invalid_manifest_is_rejected(text):
+    store = WorkspaceManifestStore()
+    store.path.parent.mkdir(parents=True, exist_ok=True)
+    store.path.write_text(text)
+    with pytest.raises(CphError) as e:
+        store.load()
+    assert e.value.kind == "workspace_manifest_invalid"
+
+def test_missing_toml_reader_is_reported(monkeypatch):
+    store = WorkspaceManifestStore()
+    store.save(WorkspaceManifest(1))
+    assert [p.name for p in store.path.parent.iterdir()] == ["workspace.toml"]
+    # Python 3.10以前でtomliも無い環境
+    monkeypatch.setattr(workspace_manifest, "tomllib", None)
+    with pytest.raises(CphError) as e:
+        store.load()
+    assert e.value.kind == "toml_missing"
+
+def test_legacy_workspace_is_migrated_with_backup(capsys):
+    upm = UnifiedPathManager()
+    config = upm.config_json()
+    config.write_text(json.dumps({"layout": {"stocks": "archive/{contest}/{problem}/{language}"}}))
+    manifest = ensure_current()
+    assert manifest.layout_version == LAYOUT_VERSION and manifest.options["stocks"] == "archive/{contest}/{problem}/{language}"
+    backups = list(upm.cph_dir("backups").glob("*-migrate-v1/contest_current/config.json"))
+    assert len(backups) == 1 and "archive" in backups[0].read_text()
+    assert "版 1 に更新しました" in capsys.readouterr().out
+    # 最新なら何もしない
+    assert ensure_current() == manifest
+    assert capsys.readouterr().out == ""
+
+def test_migrations_run_in_order_from_recorded_version():
+    applied = []
+    def rename_stocks(root, manifest):
+        applied.append(manifest.layout_version)
+        manifest.options["stocks"] = manifest.options["stocks"].replace("contest_stocks", "stocks")
+    store = WorkspaceManifestStore(migrations=MIGRATIONS + [Migration(2, "stocksへ移す", rename_stocks)])
+    store.save(WorkspaceManifest(1, created_by="0.0.1", options={"stocks": "contest_stocks/{contest}"}))
+    manifest = store.migrate(quiet=True)
+    assert applied == [1]
+    assert (manifest.layout_version, manifest.created_by, manifest.updated_by) == (2, "0.0.1", CPH_VERSION)
+    assert store.load().options["stocks"] == "stocks/{contest}"
+    assert list(store.upm.cph_dir("backups").glob("*-migrate-v2/.cph/workspace.toml"))
+
+def test_failed_migration_keeps_previous_version():
+    def broken(root, manifest):
+        raise OSError("disk full")
+    store = WorkspaceManifestStore(migrations=MIGRATIONS + [Migration(2, "壊れた移行", broken)])
+    store.save(WorkspaceManifest(1))
+    with pytest.raises(CphError) as e:
+        store.migrate(quiet=True)
+    assert e.value.kind == "workspace_migration_failed" and "disk full" in str(e.value)
+    assert store.load().layout_version == 1
+
+def test_newer_workspace_is_left_untouched():
+    store = WorkspaceManifestStore()
+    store.save(WorkspaceManifest(LAYOUT_VERSION + 1, updated_by="9.0.0"))
+    before = store.path.read_text()
+    with pytest.raises(CphError) as e:
+        ensure_current()
+    assert e.value.kind == "workspace_too_new" and "9.0.0" in str(e.value)
+    assert store.path.read_text() == before
+
+def test_outside_workspace_does_nothing(tmp_path):
+    upm = UnifiedPathManager(project_root=str(tmp_path / "elsewhere"))
+    assert ensure_current(upm) is None
+    assert not upm.cph_dir().exists()
+
+def test_init_records_manifest():
+    asyncio.run(CommandInit().init())
+    manifest = WorkspaceManifestStore().load()
+    assert manifest.layout_version == LAYOUT_VERSION and manifest.site == "atcoder"
+    assert not UnifiedPathManager().cph_dir("backups").exists()